@@ -0,0 +1,10 @@
+#!/bin/bash
+
+# Build `sig` without the standard library, for a target that does not have one, to check
+# that `bc`, `bls` and `hash` only need `alloc`.
+set -e
+
+TARGET=${1:-thumbv7em-none-eabihf}
+
+rustup target add "$TARGET"
+cargo build -p sig --lib --no-default-features --target "$TARGET"
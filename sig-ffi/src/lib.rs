@@ -0,0 +1,547 @@
+//! A C interface to the BLS signatures of `sig`, over BLS12-381 with `Parameters::setup`, so
+//! that consensus clients in other languages can check their signatures against it.
+//!
+//! Keys and signatures are opaque handles, created by the functions below and released
+//! with their `_free` function. Functions that create a handle return `NULL` when their
+//! input is invalid. Byte encodings are the ones of `sig-wasm`: secret keys are their
+//! scalar in little endian, and public keys and signatures are compressed points.
+//!
+//! Signatures on the same message are aggregated under the proof-of-possession scheme, so
+//! `sig_aggregate_verify` takes a proof of possession (from `sig_pop_prove`) for each public
+//! key, and rejects the aggregate unless all of them are valid. Without them, a rogue key
+//! chosen from the other keys could forge an aggregate signature.
+//!
+//! `cbindgen.toml` generates the header.
+use std::{ptr, slice};
+
+use ark_bls12_381::G2Affine;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use sig::{
+    bls::{AggregateSignature, Parameters, ProofOfPossession, PublicKey, SecretKey, Signature},
+    params::BlsSigConfig,
+};
+
+pub const SIG_SECRET_KEY_SIZE: usize = 32;
+pub const SIG_PUBLIC_KEY_SIZE: usize = 48;
+pub const SIG_SIGNATURE_SIZE: usize = 96;
+pub const SIG_PROOF_OF_POSSESSION_SIZE: usize = 96;
+
+pub struct SigSecretKey(SecretKey<BlsSigConfig>);
+
+pub struct SigPublicKey(PublicKey<BlsSigConfig>);
+
+pub struct SigSignature(Signature<BlsSigConfig>);
+
+pub struct SigProofOfPossession(ProofOfPossession<BlsSigConfig>);
+
+/// The `len` bytes at `ptr`, which may be `NULL` if `len` is 0.
+unsafe fn read_bytes<'a>(ptr: *const u8, len: usize) -> Option<&'a [u8]> {
+    match (ptr.is_null(), len) {
+        (true, 0) => Some(&[]),
+        (true, _) => None,
+        (false, _) => Some(slice::from_raw_parts(ptr, len)),
+    }
+}
+
+/// The handles of the `len` pointers at `ptr`, none of which may be `NULL`.
+unsafe fn handles<'a, T>(ptr: *const *const T, len: usize) -> Option<Vec<&'a T>> {
+    if ptr.is_null() {
+        return None;
+    }
+    slice::from_raw_parts(ptr, len)
+        .iter()
+        .map(|handle| handle.as_ref())
+        .collect()
+}
+
+fn into_handle<T>(value: Option<T>) -> *mut T {
+    value.map_or(ptr::null_mut(), |value| Box::into_raw(Box::new(value)))
+}
+
+/// Write the `size` bytes of `value` to `out`.
+unsafe fn write_bytes(value: &impl CanonicalSerialize, out: *mut u8, size: usize) -> bool {
+    if out.is_null() {
+        return false;
+    }
+    let out = slice::from_raw_parts_mut(out, size);
+    value.serialize_compressed(out).is_ok()
+}
+
+/// The secret key derived from the `ikm_len >= 32` bytes of `ikm` with the IETF `KeyGen`.
+///
+/// # Safety
+///
+/// `ikm` must point to `ikm_len` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn sig_secret_key_generate(
+    ikm: *const u8,
+    ikm_len: usize,
+) -> *mut SigSecretKey {
+    into_handle(
+        read_bytes(ikm, ikm_len).and_then(|ikm| SecretKey::keygen(ikm, &[]).map(SigSecretKey)),
+    )
+}
+
+/// # Safety
+///
+/// `bytes` must point to `SIG_SECRET_KEY_SIZE` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn sig_secret_key_from_bytes(bytes: *const u8) -> *mut SigSecretKey {
+    into_handle(
+        read_bytes(bytes, SIG_SECRET_KEY_SIZE)
+            .and_then(|bytes| SecretKey::deserialize_compressed(bytes).ok())
+            .map(SigSecretKey),
+    )
+}
+
+/// Write the `SIG_SECRET_KEY_SIZE` bytes of `sk` to `out`, and return whether it succeeded.
+///
+/// # Safety
+///
+/// `sk` must be a live handle, and `out` must point to `SIG_SECRET_KEY_SIZE` writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn sig_secret_key_to_bytes(sk: *const SigSecretKey, out: *mut u8) -> bool {
+    sk.as_ref()
+        .is_some_and(|sk| write_bytes(&sk.0, out, SIG_SECRET_KEY_SIZE))
+}
+
+/// Release `sk`, whose scalar is wiped from memory. `NULL` is ignored.
+///
+/// # Safety
+///
+/// `sk` must be `NULL` or a live handle, which must not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn sig_secret_key_free(sk: *mut SigSecretKey) {
+    if !sk.is_null() {
+        drop(Box::from_raw(sk));
+    }
+}
+
+/// # Safety
+///
+/// `sk` must be a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn sig_public_key_from_secret_key(
+    sk: *const SigSecretKey,
+) -> *mut SigPublicKey {
+    into_handle(
+        sk.as_ref()
+            .map(|sk| SigPublicKey(PublicKey::new(&sk.0, &Parameters::setup()))),
+    )
+}
+
+/// The public key compressed in `bytes`, which must be a point of the prime-order subgroup
+/// other than the identity.
+///
+/// # Safety
+///
+/// `bytes` must point to `SIG_PUBLIC_KEY_SIZE` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn sig_public_key_from_bytes(bytes: *const u8) -> *mut SigPublicKey {
+    into_handle(
+        read_bytes(bytes, SIG_PUBLIC_KEY_SIZE)
+            .and_then(|bytes| PublicKey::from_compressed_bytes(bytes).ok())
+            .map(SigPublicKey),
+    )
+}
+
+/// Write the `SIG_PUBLIC_KEY_SIZE` bytes of `pk` to `out`, and return whether it succeeded.
+///
+/// # Safety
+///
+/// `pk` must be a live handle, and `out` must point to `SIG_PUBLIC_KEY_SIZE` writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn sig_public_key_to_bytes(pk: *const SigPublicKey, out: *mut u8) -> bool {
+    pk.as_ref()
+        .is_some_and(|pk| write_bytes(&pk.0, out, SIG_PUBLIC_KEY_SIZE))
+}
+
+/// Release `pk`. `NULL` is ignored.
+///
+/// # Safety
+///
+/// `pk` must be `NULL` or a live handle, which must not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn sig_public_key_free(pk: *mut SigPublicKey) {
+    if !pk.is_null() {
+        drop(Box::from_raw(pk));
+    }
+}
+
+/// The signature of the `message_len` bytes of `message` by `sk`.
+///
+/// # Safety
+///
+/// `sk` must be a live handle, and `message` must point to `message_len` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn sig_sign(
+    sk: *const SigSecretKey,
+    message: *const u8,
+    message_len: usize,
+) -> *mut SigSignature {
+    into_handle(
+        sk.as_ref()
+            .zip(read_bytes(message, message_len))
+            .map(|(sk, message)| {
+                SigSignature(Signature::sign(message, &sk.0, &Parameters::setup()))
+            }),
+    )
+}
+
+/// Whether `signature` is a signature of the `message_len` bytes of `message` by `pk`.
+///
+/// # Safety
+///
+/// `pk` and `signature` must be live handles, and `message` must point to `message_len`
+/// readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn sig_verify(
+    pk: *const SigPublicKey,
+    message: *const u8,
+    message_len: usize,
+    signature: *const SigSignature,
+) -> bool {
+    match (
+        pk.as_ref(),
+        read_bytes(message, message_len),
+        signature.as_ref(),
+    ) {
+        (Some(pk), Some(message), Some(signature)) => {
+            Signature::verify(message, &signature.0, &pk.0, &Parameters::setup())
+        }
+        _ => false,
+    }
+}
+
+/// The aggregate of the `len` signatures at `signatures`, or `NULL` if `len` is 0.
+///
+/// # Safety
+///
+/// `signatures` must point to `len` live handles.
+#[no_mangle]
+pub unsafe extern "C" fn sig_aggregate(
+    signatures: *const *const SigSignature,
+    len: usize,
+) -> *mut SigSignature {
+    into_handle(handles(signatures, len).and_then(|signatures| {
+        signatures
+            .iter()
+            .map(|signature| signature.0)
+            .collect::<AggregateSignature<BlsSigConfig>>()
+            .finalize()
+            .map(SigSignature)
+    }))
+}
+
+/// Whether `signature` aggregates the signatures of the `message_len` bytes of `message` by
+/// each of the `len` public keys at `pks`, whose proofs of possession are the `len` handles
+/// at `pops`, in the same order. It is `false` if `len` is 0 or any proof of possession is
+/// invalid.
+///
+/// # Safety
+///
+/// `pks` and `pops` must each point to `len` live handles, `message` to `message_len`
+/// readable bytes, and `signature` must be a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn sig_aggregate_verify(
+    pks: *const *const SigPublicKey,
+    pops: *const *const SigProofOfPossession,
+    len: usize,
+    message: *const u8,
+    message_len: usize,
+    signature: *const SigSignature,
+) -> bool {
+    let (Some(pks), Some(pops), Some(message), Some(signature)) = (
+        handles(pks, len),
+        handles(pops, len),
+        read_bytes(message, message_len),
+        signature.as_ref(),
+    ) else {
+        return false;
+    };
+    let params = Parameters::setup();
+    if !pks
+        .iter()
+        .zip(&pops)
+        .all(|(pk, pop)| ProofOfPossession::verify(&pk.0, &pop.0, &params))
+    {
+        return false;
+    }
+    let pks: Vec<_> = pks.iter().map(|pk| pk.0).collect();
+    Signature::aggregate_verify_with_pop(message, &signature.0, &pks, &params).unwrap_or(false)
+}
+
+/// The signature compressed in `bytes`, which must be a point of the prime-order subgroup
+/// other than the identity.
+///
+/// # Safety
+///
+/// `bytes` must point to `SIG_SIGNATURE_SIZE` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn sig_signature_from_bytes(bytes: *const u8) -> *mut SigSignature {
+    into_handle(
+        read_bytes(bytes, SIG_SIGNATURE_SIZE)
+            .and_then(|bytes| G2Affine::deserialize_compressed_unchecked(bytes).ok())
+            .and_then(|point| Signature::from_affine_checked(point).ok())
+            .map(SigSignature),
+    )
+}
+
+/// Write the `SIG_SIGNATURE_SIZE` bytes of `signature` to `out`, and return whether it
+/// succeeded.
+///
+/// # Safety
+///
+/// `signature` must be a live handle, and `out` must point to `SIG_SIGNATURE_SIZE` writable
+/// bytes.
+#[no_mangle]
+pub unsafe extern "C" fn sig_signature_to_bytes(
+    signature: *const SigSignature,
+    out: *mut u8,
+) -> bool {
+    signature
+        .as_ref()
+        .is_some_and(|signature| write_bytes(&signature.0, out, SIG_SIGNATURE_SIZE))
+}
+
+/// Release `signature`. `NULL` is ignored.
+///
+/// # Safety
+///
+/// `signature` must be `NULL` or a live handle, which must not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn sig_signature_free(signature: *mut SigSignature) {
+    if !signature.is_null() {
+        drop(Box::from_raw(signature));
+    }
+}
+
+/// The proof of possession of the public key of `sk`, which `sig_aggregate_verify`
+/// requires for each key.
+///
+/// # Safety
+///
+/// `sk` must be a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn sig_pop_prove(sk: *const SigSecretKey) -> *mut SigProofOfPossession {
+    into_handle(
+        sk.as_ref()
+            .map(|sk| SigProofOfPossession(ProofOfPossession::prove(&sk.0, &Parameters::setup()))),
+    )
+}
+
+/// Whether `pop` is a valid proof of possession of `pk`.
+///
+/// # Safety
+///
+/// `pk` and `pop` must be live handles.
+#[no_mangle]
+pub unsafe extern "C" fn sig_pop_verify(
+    pk: *const SigPublicKey,
+    pop: *const SigProofOfPossession,
+) -> bool {
+    match (pk.as_ref(), pop.as_ref()) {
+        (Some(pk), Some(pop)) => ProofOfPossession::verify(&pk.0, &pop.0, &Parameters::setup()),
+        _ => false,
+    }
+}
+
+/// The proof of possession compressed in `bytes`, which must be a point of the prime-order
+/// subgroup.
+///
+/// # Safety
+///
+/// `bytes` must point to `SIG_PROOF_OF_POSSESSION_SIZE` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn sig_pop_from_bytes(bytes: *const u8) -> *mut SigProofOfPossession {
+    into_handle(
+        read_bytes(bytes, SIG_PROOF_OF_POSSESSION_SIZE)
+            .and_then(|bytes| ProofOfPossession::deserialize_compressed(bytes).ok())
+            .map(SigProofOfPossession),
+    )
+}
+
+/// Write the `SIG_PROOF_OF_POSSESSION_SIZE` bytes of `pop` to `out`, and return whether it
+/// succeeded.
+///
+/// # Safety
+///
+/// `pop` must be a live handle, and `out` must point to `SIG_PROOF_OF_POSSESSION_SIZE`
+/// writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn sig_pop_to_bytes(pop: *const SigProofOfPossession, out: *mut u8) -> bool {
+    pop.as_ref()
+        .is_some_and(|pop| write_bytes(&pop.0, out, SIG_PROOF_OF_POSSESSION_SIZE))
+}
+
+/// Release `pop`. `NULL` is ignored.
+///
+/// # Safety
+///
+/// `pop` must be `NULL` or a live handle, which must not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn sig_pop_free(pop: *mut SigProofOfPossession) {
+    if !pop.is_null() {
+        drop(Box::from_raw(pop));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::ptr;
+
+    use ark_bls12_381::{G1Affine, G2Affine};
+    use ark_ec::AffineRepr;
+    use ark_serialize::CanonicalSerialize;
+
+    use super::*;
+
+    #[test]
+    fn test_sizes() {
+        assert_eq!(G1Affine::zero().compressed_size(), SIG_PUBLIC_KEY_SIZE);
+        assert_eq!(G2Affine::zero().compressed_size(), SIG_SIGNATURE_SIZE);
+        assert_eq!(
+            ProofOfPossession::<BlsSigConfig>::default().compressed_size(),
+            SIG_PROOF_OF_POSSESSION_SIZE
+        );
+        assert_eq!(
+            SecretKey::<BlsSigConfig>::default().compressed_size(),
+            SIG_SECRET_KEY_SIZE
+        );
+    }
+
+    #[test]
+    fn test_sign_and_verify() {
+        let message = b"block";
+        unsafe {
+            let sks: Vec<_> = (0..3u8)
+                .map(|i| sig_secret_key_generate([i; 32].as_ptr(), 32))
+                .collect();
+            let pks: Vec<_> = sks
+                .iter()
+                .map(|&sk| sig_public_key_from_secret_key(sk).cast_const())
+                .collect();
+            let pops: Vec<_> = sks
+                .iter()
+                .map(|&sk| sig_pop_prove(sk).cast_const())
+                .collect();
+            let sigs: Vec<_> = sks
+                .iter()
+                .map(|&sk| sig_sign(sk, message.as_ptr(), message.len()).cast_const())
+                .collect();
+
+            assert!(sig_verify(pks[0], message.as_ptr(), message.len(), sigs[0]));
+            assert!(!sig_verify(
+                pks[1],
+                message.as_ptr(),
+                message.len(),
+                sigs[0]
+            ));
+            assert!(!sig_verify(pks[0], ptr::null(), 0, sigs[0]));
+
+            assert!(sig_pop_verify(pks[0], pops[0]));
+            assert!(!sig_pop_verify(pks[1], pops[0]));
+
+            let aggregate = sig_aggregate(sigs.as_ptr(), sigs.len());
+            assert!(sig_aggregate_verify(
+                pks.as_ptr(),
+                pops.as_ptr(),
+                pks.len(),
+                message.as_ptr(),
+                message.len(),
+                aggregate
+            ));
+            assert!(!sig_aggregate_verify(
+                pks.as_ptr(),
+                pops.as_ptr(),
+                2,
+                message.as_ptr(),
+                message.len(),
+                aggregate
+            ));
+            assert!(sig_aggregate(sigs.as_ptr(), 0).is_null());
+
+            // every key needs its own proof of possession
+            let swapped = [pops[1], pops[0], pops[2]];
+            assert!(!sig_aggregate_verify(
+                pks.as_ptr(),
+                swapped.as_ptr(),
+                pks.len(),
+                message.as_ptr(),
+                message.len(),
+                aggregate
+            ));
+            assert!(!sig_aggregate_verify(
+                pks.as_ptr(),
+                ptr::null(),
+                pks.len(),
+                message.as_ptr(),
+                message.len(),
+                aggregate
+            ));
+
+            let mut pop_bytes = [0; SIG_PROOF_OF_POSSESSION_SIZE];
+            assert!(sig_pop_to_bytes(pops[0], pop_bytes.as_mut_ptr()));
+            let decoded_pop = sig_pop_from_bytes(pop_bytes.as_ptr());
+            assert!(sig_pop_verify(pks[0], decoded_pop));
+            sig_pop_free(decoded_pop);
+
+            sig_signature_free(aggregate);
+            sks.into_iter().for_each(|sk| sig_secret_key_free(sk));
+            pks.into_iter()
+                .for_each(|pk| sig_public_key_free(pk.cast_mut()));
+            sigs.into_iter()
+                .for_each(|sig| sig_signature_free(sig.cast_mut()));
+            pops.into_iter()
+                .for_each(|pop| sig_pop_free(pop.cast_mut()));
+        }
+    }
+
+    #[test]
+    fn test_bytes() {
+        let message = b"block";
+        unsafe {
+            assert!(sig_secret_key_generate([0; 31].as_ptr(), 31).is_null());
+            let sk = sig_secret_key_generate([0; 32].as_ptr(), 32);
+            let pk = sig_public_key_from_secret_key(sk);
+            let sig = sig_sign(sk, message.as_ptr(), message.len());
+
+            let mut sk_bytes = [0; SIG_SECRET_KEY_SIZE];
+            let mut pk_bytes = [0; SIG_PUBLIC_KEY_SIZE];
+            let mut sig_bytes = [0; SIG_SIGNATURE_SIZE];
+            assert!(sig_secret_key_to_bytes(sk, sk_bytes.as_mut_ptr()));
+            assert!(sig_public_key_to_bytes(pk, pk_bytes.as_mut_ptr()));
+            assert!(sig_signature_to_bytes(sig, sig_bytes.as_mut_ptr()));
+            assert!(!sig_signature_to_bytes(sig, ptr::null_mut()));
+
+            let decoded_sk = sig_secret_key_from_bytes(sk_bytes.as_ptr());
+            let decoded_pk = sig_public_key_from_bytes(pk_bytes.as_ptr());
+            let decoded_sig = sig_signature_from_bytes(sig_bytes.as_ptr());
+            assert_eq!((*decoded_sk).0.secret_key, (*sk).0.secret_key);
+            assert!(sig_verify(
+                decoded_pk,
+                message.as_ptr(),
+                message.len(),
+                decoded_sig
+            ));
+
+            // the identity is not a valid public key
+            let mut identity = [0; SIG_PUBLIC_KEY_SIZE];
+            G1Affine::zero()
+                .serialize_compressed(&mut identity[..])
+                .unwrap();
+            assert!(sig_public_key_from_bytes(identity.as_ptr()).is_null());
+            assert!(sig_signature_from_bytes(ptr::null()).is_null());
+
+            for sk in [sk, decoded_sk] {
+                sig_secret_key_free(sk);
+            }
+            for pk in [pk, decoded_pk] {
+                sig_public_key_free(pk);
+            }
+            for sig in [sig, decoded_sig] {
+                sig_signature_free(sig);
+            }
+        }
+    }
+}
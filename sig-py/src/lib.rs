@@ -0,0 +1,321 @@
+//! Python bindings of `sig`, to script simulated chains and their folding in experiments.
+//!
+//! Signatures are BLS over BLS12-381, as in `bc`, with public keys and signatures passed
+//! as compressed points in `bytes`. `fold` folds a chain with Nova over MNT4-753/MNT6-753,
+//! as the `folding` bench does, and reports how long each phase took.
+use std::time::Instant;
+
+use ark_mnt4_753::{Fr, G1Projective as G1, MNT4_753 as MNT4};
+use ark_mnt6_753::{G1Projective as G2, MNT6_753 as MNT6};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use folding_schemes::{
+    commitment::kzg::KZG,
+    folding::nova::{Nova, PreprocessorParam},
+    frontend::FCircuit,
+    transcript::poseidon::poseidon_canonical_config,
+    FoldingScheme,
+};
+use pyo3::{
+    exceptions::{PyRuntimeError, PyValueError},
+    prelude::*,
+    types::PyBytes,
+};
+use rand::thread_rng;
+use sig::{
+    bc::{block, checkpoint::Checkpoint, params::BcConfig, testgen},
+    bls::{self, AggregateSignature},
+    folding::circuit::{BCCircuitNoMerkle, BCCircuitParams},
+    params::BlsSigConfig,
+};
+
+type FC = BCCircuitNoMerkle<(G1, G2)>;
+type N = Nova<G1, G2, FC, KZG<'static, MNT4>, KZG<'static, MNT6>, false>;
+
+fn folding_error(e: folding_schemes::Error) -> PyErr {
+    PyRuntimeError::new_err(e.to_string())
+}
+
+#[pyclass(frozen)]
+#[derive(Clone)]
+struct Parameters(bls::Parameters<BlsSigConfig>);
+
+#[pymethods]
+impl Parameters {
+    /// The standard generators, in strict mode.
+    #[new]
+    fn new() -> Self {
+        Self(bls::Parameters::setup())
+    }
+
+    #[getter]
+    fn strict(&self) -> bool {
+        self.0.strict
+    }
+}
+
+#[pyclass(frozen)]
+struct KeyPair(bls::KeyPair<BlsSigConfig>);
+
+#[pymethods]
+impl KeyPair {
+    /// A key pair with a secret key drawn from the operating system's randomness.
+    #[new]
+    fn new(params: &Parameters) -> Self {
+        Self(bls::KeyPair::generate(&mut thread_rng(), &params.0))
+    }
+
+    /// The key pair derived from the `ikm` (at least 32 bytes) with the IETF `KeyGen`.
+    #[staticmethod]
+    fn from_ikm(ikm: &[u8], params: &Parameters) -> PyResult<Self> {
+        let sk = bls::SecretKey::keygen(ikm, &[])
+            .ok_or_else(|| PyValueError::new_err("ikm must have at least 32 bytes"))?;
+        Ok(Self(bls::KeyPair::new(sk, &params.0)))
+    }
+
+    #[getter]
+    fn public_key<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new(py, &self.0.pk.to_compressed_bytes())
+    }
+
+    fn sign(&self, message: &[u8], params: &Parameters) -> Signature {
+        Signature(bls::Signature::sign(message, &self.0.sk, &params.0))
+    }
+}
+
+#[pyclass(frozen)]
+#[derive(Clone)]
+struct Signature(bls::Signature<BlsSigConfig>);
+
+#[pymethods]
+impl Signature {
+    /// The signature compressed in `bytes`, which must be a point of the prime-order
+    /// subgroup other than the identity.
+    #[staticmethod]
+    fn from_bytes(bytes: &[u8]) -> PyResult<Self> {
+        bls::Signature::deserialize_compressed(bytes)
+            .ok()
+            .filter(bls::Signature::<BlsSigConfig>::is_well_formed)
+            .map(Self)
+            .ok_or_else(|| PyValueError::new_err("invalid signature"))
+    }
+
+    fn to_bytes<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyBytes>> {
+        let mut bytes = vec![];
+        self.0
+            .serialize_compressed(&mut bytes)
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+        Ok(PyBytes::new(py, &bytes))
+    }
+
+    fn verify(&self, message: &[u8], public_key: &[u8], params: &Parameters) -> PyResult<bool> {
+        let pk = bls::PublicKey::from_compressed_bytes(public_key)
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+        Ok(bls::Signature::verify(message, &self.0, &pk, &params.0))
+    }
+
+    #[staticmethod]
+    fn aggregate(signatures: Vec<Signature>) -> PyResult<Self> {
+        signatures
+            .into_iter()
+            .map(|signature| signature.0)
+            .collect::<AggregateSignature<BlsSigConfig>>()
+            .finalize()
+            .map(Self)
+            .ok_or_else(|| PyValueError::new_err("no signature to aggregate"))
+    }
+}
+
+#[pyclass(frozen)]
+#[derive(Clone)]
+struct Block(block::Block);
+
+#[pymethods]
+impl Block {
+    #[getter]
+    fn epoch(&self) -> u64 {
+        self.0.epoch
+    }
+
+    #[getter]
+    fn prev_digest<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new(py, &self.0.prev_digest)
+    }
+
+    fn digest<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new(py, &self.0.digest())
+    }
+
+    /// The public keys and weights of the committee, padded to the maximum committee size.
+    #[getter]
+    fn committee<'py>(&self, py: Python<'py>) -> Vec<(Bound<'py, PyBytes>, u64)> {
+        self.0
+            .committee
+            .signers
+            .iter()
+            .map(|(pk, weight)| (PyBytes::new(py, &pk.to_compressed_bytes()), *weight))
+            .collect()
+    }
+
+    /// Which members of the previous committee signed the block.
+    #[getter]
+    fn signers(&self) -> Vec<bool> {
+        self.0.sig.signers.clone()
+    }
+
+    #[getter]
+    fn signature(&self) -> Signature {
+        Signature(self.0.sig.sig)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Block(epoch={}, committee_size={})",
+            self.0.epoch,
+            self.0.committee.signers.len()
+        )
+    }
+}
+
+#[pyclass(frozen)]
+struct ChainGenerator(testgen::ChainGenerator);
+
+#[pymethods]
+impl ChainGenerator {
+    /// See `sig::bc::testgen::ChainGenerator`. `churn` defaults to the whole committee, and
+    /// `max_committee_size` to `committee_size`.
+    #[new]
+    #[pyo3(signature = (num_epochs, committee_size, *, max_committee_size=None, participation=0.0, churn=None))]
+    fn new(
+        num_epochs: usize,
+        committee_size: usize,
+        max_committee_size: Option<usize>,
+        participation: f64,
+        churn: Option<usize>,
+    ) -> PyResult<Self> {
+        let max_committee_size = max_committee_size.unwrap_or(committee_size);
+        let churn = churn.unwrap_or(committee_size);
+        if num_epochs == 0 || committee_size == 0 {
+            return Err(PyValueError::new_err(
+                "num_epochs and committee_size should be positive",
+            ));
+        }
+        if committee_size > max_committee_size || churn > committee_size {
+            return Err(PyValueError::new_err(
+                "should have churn <= committee_size <= max_committee_size",
+            ));
+        }
+        if !(0.0..=1.0).contains(&participation) {
+            return Err(PyValueError::new_err("participation should be in [0, 1]"));
+        }
+
+        let config = BcConfig {
+            max_committee_size,
+            ..Default::default()
+        };
+        Ok(Self(
+            testgen::ChainGenerator::new(num_epochs, committee_size)
+                .config(config)
+                .participation(participation)
+                .churn(churn),
+        ))
+    }
+
+    /// `num_epochs` blocks, starting from a genesis block.
+    fn generate(&self, py: Python<'_>) -> Vec<Block> {
+        py.allow_threads(|| self.0.generate(&mut thread_rng()).0)
+            .into_iter()
+            .map(Block)
+            .collect()
+    }
+}
+
+/// How long each phase of `fold` took, in seconds.
+#[pyclass(frozen, get_all)]
+struct FoldMetrics {
+    preprocess_s: f64,
+    prove_step_s: Vec<f64>,
+    verify_s: f64,
+}
+
+#[pymethods]
+impl FoldMetrics {
+    fn __repr__(&self) -> String {
+        format!(
+            "FoldMetrics(preprocess_s={}, prove_step_s={:?}, verify_s={})",
+            self.preprocess_s, self.prove_step_s, self.verify_s
+        )
+    }
+}
+
+/// Fold every block after the first of `blocks` into a Nova IVC proof, starting from the
+/// committee of the first block, and verify it. The maximum committee size is that of the
+/// blocks, which must all be padded to it.
+#[pyfunction]
+fn fold(py: Python<'_>, blocks: Vec<Block>) -> PyResult<FoldMetrics> {
+    let Some((genesis, blocks)) = blocks.split_first() else {
+        return Err(PyValueError::new_err("at least one block should be given"));
+    };
+    let params = BCCircuitParams {
+        config: BcConfig {
+            max_committee_size: genesis.0.committee.signers.len(),
+            ..Default::default()
+        },
+        ..BCCircuitParams::setup()
+    };
+    if blocks
+        .iter()
+        .any(|block| block.0.check_padding(&params.config).is_err())
+    {
+        return Err(PyValueError::new_err(
+            "the blocks should be padded to the committee size of the first block",
+        ));
+    }
+
+    py.allow_threads(|| {
+        let rng = &mut thread_rng();
+        let f_circuit = FC::new(params).map_err(folding_error)?;
+
+        let start = Instant::now();
+        let nova_params = N::preprocess(
+            &mut *rng,
+            &PreprocessorParam::new(poseidon_canonical_config::<Fr>(), f_circuit),
+        )
+        .map_err(folding_error)?;
+        let preprocess_s = start.elapsed().as_secs_f64();
+
+        let z_0 = f_circuit
+            .initial_state(&Checkpoint::from_block(&genesis.0), &genesis.0.committee)
+            .map_err(folding_error)?;
+        let mut nova = N::init(&nova_params, f_circuit, z_0).map_err(folding_error)?;
+
+        let mut prove_step_s = Vec::with_capacity(blocks.len());
+        for block in blocks {
+            let start = Instant::now();
+            nova.prove_step(&mut *rng, Some(block.0.clone()), None)
+                .map_err(folding_error)?;
+            prove_step_s.push(start.elapsed().as_secs_f64());
+        }
+
+        let start = Instant::now();
+        N::verify(nova_params.1, nova.ivc_proof()).map_err(folding_error)?;
+        let verify_s = start.elapsed().as_secs_f64();
+
+        Ok(FoldMetrics {
+            preprocess_s,
+            prove_step_s,
+            verify_s,
+        })
+    })
+}
+
+#[pymodule]
+fn sig_py(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<Parameters>()?;
+    m.add_class::<KeyPair>()?;
+    m.add_class::<Signature>()?;
+    m.add_class::<Block>()?;
+    m.add_class::<ChainGenerator>()?;
+    m.add_class::<FoldMetrics>()?;
+    m.add_function(wrap_pyfunction!(fold, m)?)?;
+    Ok(())
+}
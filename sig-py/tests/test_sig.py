@@ -0,0 +1,50 @@
+# maturin develop --release && pytest tests
+import pytest
+
+from sig_py import ChainGenerator, KeyPair, Parameters, Signature, fold
+
+
+def test_sign_and_verify():
+    params = Parameters()
+    key_pair = KeyPair.from_ikm(bytes(32), params)
+    signature = key_pair.sign(b"block", params)
+
+    assert signature.verify(b"block", key_pair.public_key, params)
+    assert not signature.verify(b"other block", key_pair.public_key, params)
+    assert Signature.from_bytes(signature.to_bytes()).verify(b"block", key_pair.public_key, params)
+    with pytest.raises(ValueError):
+        KeyPair.from_ikm(bytes(31), params)
+
+
+def test_aggregate():
+    params = Parameters()
+    key_pairs = [KeyPair(params) for _ in range(3)]
+    aggregate = Signature.aggregate([key_pair.sign(b"block", params) for key_pair in key_pairs])
+
+    assert len(aggregate.to_bytes()) == 96
+    assert not aggregate.verify(b"block", key_pairs[0].public_key, params)
+    with pytest.raises(ValueError):
+        Signature.aggregate([])
+
+
+def test_generate_chain():
+    blocks = ChainGenerator(3, 4, max_committee_size=8, churn=2).generate()
+
+    assert [block.epoch for block in blocks] == [0, 1, 2]
+    assert all(len(block.committee) == 8 for block in blocks)
+    assert blocks[1].prev_digest == blocks[0].digest()
+    with pytest.raises(ValueError):
+        ChainGenerator(3, 4, max_committee_size=2)
+
+
+def test_fold():
+    # committees of 2 padded to 3, which takes a few minutes in release
+    blocks = ChainGenerator(3, 2, max_committee_size=3).generate()
+    metrics = fold(blocks)
+
+    assert len(metrics.prove_step_s) == 2
+    assert metrics.preprocess_s > 0 and metrics.verify_s > 0
+    with pytest.raises(ValueError):
+        fold([])
+    with pytest.raises(ValueError):
+        fold(blocks[:1] + ChainGenerator(2, 2).generate()[1:])
@@ -0,0 +1,186 @@
+//! `wasm-bindgen` exports of `sig` for light clients running in a browser: BLS signatures
+//! over BLS12-381 with `Parameters::setup`, and the verification of Groth16 proofs over
+//! BLS12-377, such as those of `BLSCircuit`.
+//!
+//! Everything goes in and out as bytes. Secret keys are their scalar in 32 little-endian
+//! bytes, and public keys, signatures and proofs of possession are compressed points;
+//! several of them are concatenated. Groth16 proofs, verifying keys and public inputs are in
+//! the `Calldata` encoding of `snark::encoding`. Malformed bytes are thrown as JS errors.
+//!
+//! Signatures on the same message are aggregated under the proof-of-possession scheme, so
+//! `aggregateVerify` takes a proof of possession (from `popProve`) for each public key and
+//! rejects the aggregate unless all of them are valid. Without them, a rogue key chosen
+//! from the other keys could forge an aggregate signature.
+use ark_bls12_377::{Bls12_377, Fr};
+use ark_bls12_381::{G1Affine, G2Affine};
+use ark_ec::AffineRepr;
+use ark_groth16::{Groth16, Proof, VerifyingKey};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use sig::{
+    bls::{AggregateSignature, Parameters, ProofOfPossession, PublicKey, SecretKey, Signature},
+    params::BlsSigConfig,
+    snark::encoding::Calldata,
+};
+use wasm_bindgen::prelude::*;
+
+fn secret_key_from_bytes(bytes: &[u8]) -> Result<SecretKey<BlsSigConfig>, JsError> {
+    if bytes.len() != SecretKey::<BlsSigConfig>::default().compressed_size() {
+        return Err(JsError::new("invalid secret key length"));
+    }
+    Ok(SecretKey::deserialize_compressed(bytes)?)
+}
+
+fn signature_from_bytes(bytes: &[u8]) -> Result<Signature<BlsSigConfig>, JsError> {
+    if bytes.len() != G2Affine::zero().compressed_size() {
+        return Err(JsError::new("invalid signature length"));
+    }
+    let point = G2Affine::deserialize_compressed_unchecked(bytes)?;
+    Ok(Signature::from_affine_checked(point)?)
+}
+
+fn signature_to_bytes(signature: &Signature<BlsSigConfig>) -> Result<Vec<u8>, JsError> {
+    let mut bytes = vec![];
+    signature.serialize_compressed(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// The concatenated public keys of `bytes`.
+fn public_keys_from_bytes(bytes: &[u8]) -> Result<Vec<PublicKey<BlsSigConfig>>, JsError> {
+    let size = G1Affine::zero().compressed_size();
+    if bytes.is_empty() || bytes.len() % size != 0 {
+        return Err(JsError::new("invalid public keys length"));
+    }
+    bytes
+        .chunks(size)
+        .map(|pk| Ok(PublicKey::from_compressed_bytes(pk)?))
+        .collect()
+}
+
+/// The concatenated proofs of possession of `bytes`.
+fn pops_from_bytes(bytes: &[u8]) -> Result<Vec<ProofOfPossession<BlsSigConfig>>, JsError> {
+    let size = G2Affine::zero().compressed_size();
+    if bytes.len() % size != 0 {
+        return Err(JsError::new("invalid proofs of possession length"));
+    }
+    bytes
+        .chunks(size)
+        .map(|pop| Ok(ProofOfPossession::deserialize_compressed(pop)?))
+        .collect()
+}
+
+/// The secret key derived from `ikm`, which must have at least 32 bytes of entropy (e.g.
+/// from `crypto.getRandomValues`), with the IETF `KeyGen`.
+#[wasm_bindgen]
+pub fn keygen(ikm: &[u8]) -> Result<Vec<u8>, JsError> {
+    let sk = SecretKey::<BlsSigConfig>::keygen(ikm, &[])
+        .ok_or_else(|| JsError::new("ikm must have at least 32 bytes"))?;
+    let mut bytes = vec![];
+    sk.serialize_compressed(&mut bytes)?;
+    Ok(bytes)
+}
+
+#[wasm_bindgen(js_name = publicKey)]
+pub fn public_key(secret_key: &[u8]) -> Result<Vec<u8>, JsError> {
+    let sk = secret_key_from_bytes(secret_key)?;
+    Ok(PublicKey::<BlsSigConfig>::new(&sk, &Parameters::setup()).to_compressed_bytes())
+}
+
+#[wasm_bindgen]
+pub fn sign(secret_key: &[u8], message: &[u8]) -> Result<Vec<u8>, JsError> {
+    let sk = secret_key_from_bytes(secret_key)?;
+    signature_to_bytes(&Signature::sign(message, &sk, &Parameters::setup()))
+}
+
+#[wasm_bindgen]
+pub fn verify(public_key: &[u8], message: &[u8], signature: &[u8]) -> Result<bool, JsError> {
+    let pk = PublicKey::from_compressed_bytes(public_key)?;
+    let signature = signature_from_bytes(signature)?;
+    Ok(Signature::verify(
+        message,
+        &signature,
+        &pk,
+        &Parameters::setup(),
+    ))
+}
+
+/// The aggregate of the concatenated `signatures`.
+#[wasm_bindgen]
+pub fn aggregate(signatures: &[u8]) -> Result<Vec<u8>, JsError> {
+    let size = G2Affine::zero().compressed_size();
+    if signatures.len() % size != 0 {
+        return Err(JsError::new("invalid signatures length"));
+    }
+    let aggregate = signatures
+        .chunks(size)
+        .map(signature_from_bytes)
+        .collect::<Result<AggregateSignature<BlsSigConfig>, _>>()?
+        .finalize()
+        .ok_or_else(|| JsError::new("no signature to aggregate"))?;
+    signature_to_bytes(&aggregate)
+}
+
+/// The proof of possession of the public key of `secret_key`, which `aggregateVerify`
+/// requires for each key.
+#[wasm_bindgen(js_name = popProve)]
+pub fn pop_prove(secret_key: &[u8]) -> Result<Vec<u8>, JsError> {
+    let sk = secret_key_from_bytes(secret_key)?;
+    let mut bytes = vec![];
+    ProofOfPossession::prove(&sk, &Parameters::setup()).serialize_compressed(&mut bytes)?;
+    Ok(bytes)
+}
+
+#[wasm_bindgen(js_name = popVerify)]
+pub fn pop_verify(public_key: &[u8], pop: &[u8]) -> Result<bool, JsError> {
+    let pk = PublicKey::from_compressed_bytes(public_key)?;
+    let [pop] = pops_from_bytes(pop)?[..] else {
+        return Err(JsError::new("invalid proof of possession length"));
+    };
+    Ok(ProofOfPossession::verify(&pk, &pop, &Parameters::setup()))
+}
+
+/// Whether `signature` aggregates signatures on `message` by each of the concatenated
+/// `public_keys`, whose proofs of possession are concatenated in `pops` in the same order.
+///
+/// Returns `false` if any proof of possession is invalid.
+#[wasm_bindgen(js_name = aggregateVerify)]
+pub fn aggregate_verify(
+    public_keys: &[u8],
+    pops: &[u8],
+    message: &[u8],
+    signature: &[u8],
+) -> Result<bool, JsError> {
+    let pks = public_keys_from_bytes(public_keys)?;
+    let pops = pops_from_bytes(pops)?;
+    if pops.len() != pks.len() {
+        return Err(JsError::new(
+            "expected one proof of possession per public key",
+        ));
+    }
+    let signature = signature_from_bytes(signature)?;
+
+    let params = Parameters::setup();
+    if !pks
+        .iter()
+        .zip(&pops)
+        .all(|(pk, pop)| ProofOfPossession::verify(pk, pop, &params))
+    {
+        return Ok(false);
+    }
+    Signature::aggregate_verify_with_pop(message, &signature, &pks, &params)
+        .ok_or_else(|| JsError::new("no public key to verify against"))
+}
+
+/// Whether `proof` is a Groth16 proof over BLS12-377 of `public_inputs` for
+/// `verifying_key`, all in their calldata.
+#[wasm_bindgen(js_name = verifyGroth16)]
+pub fn verify_groth16(
+    verifying_key: &[u8],
+    proof: &[u8],
+    public_inputs: &[u8],
+) -> Result<bool, JsError> {
+    let vk = VerifyingKey::<Bls12_377>::from_calldata(verifying_key)?;
+    let proof = Proof::<Bls12_377>::from_calldata(proof)?;
+    let inputs = Vec::<Fr>::from_calldata(public_inputs)?;
+    let pvk = Groth16::<Bls12_377>::process_vk(&vk)?;
+    Ok(Groth16::<Bls12_377>::verify_proof(&pvk, &proof, &inputs)?)
+}
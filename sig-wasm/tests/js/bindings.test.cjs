@@ -0,0 +1,47 @@
+// The module built by `wasm-pack build sig-wasm --target nodejs`, called from JavaScript:
+//
+// node --test sig-wasm/tests/js/bindings.test.cjs
+const assert = require("node:assert/strict");
+const { randomBytes } = require("node:crypto");
+const { test } = require("node:test");
+
+const sig = require("../../pkg/sig_wasm.js");
+
+const message = new TextEncoder().encode("block");
+
+function keys(n) {
+  return Array.from({ length: n }, () => {
+    const sk = sig.keygen(randomBytes(32));
+    return { sk, pk: sig.publicKey(sk), pop: sig.popProve(sk) };
+  });
+}
+
+test("sign and verify", () => {
+  const [{ sk, pk }] = keys(1);
+  const signature = sig.sign(sk, message);
+
+  assert.equal(signature.length, 96);
+  assert.ok(sig.verify(pk, message, signature));
+  assert.ok(!sig.verify(pk, new TextEncoder().encode("other block"), signature));
+  assert.throws(() => sig.verify(pk, message, signature.subarray(1)));
+  assert.throws(() => sig.keygen(randomBytes(31)));
+});
+
+test("aggregate verify requires a proof of possession per key", () => {
+  const signers = keys(3);
+  const pks = Buffer.concat(signers.map(({ pk }) => pk));
+  const pops = Buffer.concat(signers.map(({ pop }) => pop));
+  const signature = sig.aggregate(
+    Buffer.concat(signers.map(({ sk }) => sig.sign(sk, message))),
+  );
+
+  assert.ok(sig.popVerify(signers[0].pk, signers[0].pop));
+  assert.ok(!sig.popVerify(signers[1].pk, signers[0].pop));
+  assert.ok(sig.aggregateVerify(pks, pops, message, signature));
+  assert.ok(!sig.aggregateVerify(pks.subarray(48), pops.subarray(96), message, signature));
+
+  const swapped = Buffer.concat([signers[1].pop, signers[0].pop, signers[2].pop]);
+  assert.ok(!sig.aggregateVerify(pks, swapped, message, signature));
+  assert.throws(() => sig.aggregateVerify(pks, pops.subarray(96), message, signature));
+  assert.throws(() => sig.aggregate(new Uint8Array()));
+});
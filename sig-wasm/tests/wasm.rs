@@ -0,0 +1,88 @@
+//! The exports, called through `wasm-bindgen` in Node.js:
+//!
+//! wasm-pack test --node sig-wasm
+#![cfg(target_arch = "wasm32")]
+
+use ark_bls12_377::{Bls12_377, Fr};
+use ark_ff::Field;
+use ark_groth16::Groth16;
+use ark_r1cs_std::{alloc::AllocVar, eq::EqGadget, fields::fp::FpVar};
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
+use rand::{thread_rng, Rng};
+use sig::snark::encoding::Calldata;
+use sig_wasm::{
+    aggregate, aggregate_verify, keygen, pop_prove, pop_verify, public_key, sign, verify,
+    verify_groth16,
+};
+use wasm_bindgen_test::wasm_bindgen_test;
+
+/// Knowledge of a square root `x` of the public `y`.
+#[derive(Clone, Copy)]
+struct Square(Option<Fr>);
+
+impl ConstraintSynthesizer<Fr> for Square {
+    fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+        let x = FpVar::new_witness(cs.clone(), || {
+            self.0.ok_or(SynthesisError::AssignmentMissing)
+        })?;
+        let y = FpVar::new_input(cs, || {
+            self.0
+                .map(|x| x.square())
+                .ok_or(SynthesisError::AssignmentMissing)
+        })?;
+        x.square()?.enforce_equal(&y)
+    }
+}
+
+#[wasm_bindgen_test]
+fn test_sign_and_verify() {
+    let sk = keygen(&[7; 32]).unwrap();
+    let pk = public_key(&sk).unwrap();
+    let sig = sign(&sk, b"block").unwrap();
+
+    assert!(verify(&pk, b"block", &sig).unwrap());
+    assert!(!verify(&pk, b"other block", &sig).unwrap());
+    assert!(keygen(&[7; 31]).is_err());
+    assert!(verify(&pk, b"block", &sig[1..]).is_err());
+}
+
+#[wasm_bindgen_test]
+fn test_aggregate_verify() {
+    let sks: Vec<_> = (0..3u8).map(|i| keygen(&[i; 32]).unwrap()).collect();
+    let pks: Vec<u8> = sks.iter().flat_map(|sk| public_key(sk).unwrap()).collect();
+    let pops: Vec<u8> = sks.iter().flat_map(|sk| pop_prove(sk).unwrap()).collect();
+    let sigs: Vec<u8> = sks
+        .iter()
+        .flat_map(|sk| sign(sk, b"block").unwrap())
+        .collect();
+    let sig = aggregate(&sigs).unwrap();
+
+    assert!(pop_verify(&pks[..48], &pops[..96]).unwrap());
+    assert!(!pop_verify(&pks[48..96], &pops[..96]).unwrap());
+    assert!(aggregate_verify(&pks, &pops, b"block", &sig).unwrap());
+    assert!(!aggregate_verify(&pks[48..], &pops[96..], b"block", &sig).unwrap());
+    assert!(aggregate(&[]).is_err());
+    assert!(aggregate_verify(&[], &[], b"block", &sig).is_err());
+
+    // every key needs its own proof of possession
+    assert!(aggregate_verify(&pks, &pops[96..], b"block", &sig).is_err());
+    let swapped: Vec<u8> = [&pops[96..192], &pops[..96], &pops[192..]].concat();
+    assert!(!aggregate_verify(&pks, &swapped, b"block", &sig).unwrap());
+}
+
+#[wasm_bindgen_test]
+fn test_verify_groth16() {
+    let mut rng = thread_rng();
+    let pk =
+        Groth16::<Bls12_377>::generate_random_parameters_with_reduction(Square(None), &mut rng)
+            .unwrap();
+    let x: Fr = rng.gen();
+    let proof =
+        Groth16::<Bls12_377>::create_random_proof_with_reduction(Square(Some(x)), &pk, &mut rng)
+            .unwrap();
+
+    let (vk, proof) = (pk.vk.to_calldata(), proof.to_calldata());
+    assert!(verify_groth16(&vk, &proof, &vec![x.square()].to_calldata()).unwrap());
+    assert!(!verify_groth16(&vk, &proof, &vec![x].to_calldata()).unwrap());
+    assert!(verify_groth16(&vk, &proof[1..], &vec![x].to_calldata()).is_err());
+}
@@ -1,5 +1,5 @@
 use criterion::{criterion_group, criterion_main, Criterion};
-use sig::bls::{get_bls_instance, Signature};
+use sig::bls::{get_bls_instance, HashedMessage, MinSig, PreparedParameters, PublicKey, Signature};
 
 fn bls_verify_bench(c: &mut Criterion) {
     let (msg, params, _, pk, sig) = get_bls_instance::<ark_bls12_381::Config>();
@@ -14,5 +14,31 @@ fn bls_verify_bench(c: &mut Criterion) {
     group.finish();
 }
 
-criterion_group!(benches, bls_verify_bench);
+fn bls_verify_prepared_bench(c: &mut Criterion) {
+    let (msg, params, sk, pk, sig) = get_bls_instance::<ark_bls12_381::Config>();
+    let prepared = PreparedParameters::from(&params);
+    let mut group = c.benchmark_group("BLS Signature (pre-hashed message)");
+
+    // hash-to-curve is excluded so that only the pairing work is compared
+    let hashed_message = HashedMessage::new(msg.as_bytes(), &params);
+    group.bench_function("min_pk verify", |b| {
+        b.iter(|| Signature::verify_prehashed(&hashed_message, &sig, &pk, &params));
+    });
+    group.bench_function("min_pk verify (prepared parameters)", |b| {
+        b.iter(|| Signature::verify_prepared(&hashed_message, &sig, &pk, &prepared));
+    });
+
+    let pk = PublicKey::<_, MinSig>::new(&sk, &params);
+    let hashed_message = HashedMessage::<_, MinSig>::new(msg.as_bytes(), &params);
+    let sig = Signature::sign_prehashed(&hashed_message, &sk, &params);
+    group.bench_function("min_sig verify", |b| {
+        b.iter(|| Signature::verify_prehashed(&hashed_message, &sig, &pk, &params));
+    });
+    group.bench_function("min_sig verify (prepared parameters)", |b| {
+        b.iter(|| Signature::verify_prepared(&hashed_message, &sig, &pk, &prepared));
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bls_verify_bench, bls_verify_prepared_bench);
 criterion_main!(benches);
@@ -1,18 +1,28 @@
 mod utils;
 
-use ark_ec::{bls12::Bls12Config, pairing::Pairing};
+use ark_ec::{bls12::Bls12Config, pairing::Pairing, short_weierstrass::SWCurveConfig};
+use ark_ff::{BigInteger, PrimeField, UniformRand};
 use ark_r1cs_std::{
     alloc::AllocVar,
-    fields::{emulated_fp::EmulatedFpVar, fp::FpVar},
+    fields::{emulated_fp::EmulatedFpVar, fp::FpVar, fp2::Fp2Var, FieldOpsBounds, FieldVar},
+    groups::{curves::short_weierstrass::ProjectiveVar, CurveVar},
+    prelude::Boolean,
+    select::TwoBitLookupGadget,
     uint8::UInt8,
 };
 use ark_relations::r1cs::ConstraintSystem;
-use sig::bls::{
-    get_bls_instance, BLSAggregateSignatureVerifyGadget, ParametersVar, PublicKeyVar, SignatureVar,
+use rand::thread_rng;
+use sig::{
+    bc::params::MAX_COMMITTEE_SIZE,
+    bls::{
+        get_bls_instance, BLSAggregateSignatureVerifyGadget, FixedBaseMulGadget, Parameters,
+        ParametersVar, PublicKey, PublicKeyVar, SecretKey, Signature, SignatureVar,
+    },
+    eddsa,
 };
-use utils::register_tracing;
+use utils::{print_metrics, register_tracing, Metrics};
 
-fn tracing_num_constraints_native() {
+fn tracing_num_constraints_native(metrics: &mut Metrics) {
     type BlsSigConfig = ark_bls12_377::Config;
     type BaseSigCurveField = <BlsSigConfig as Bls12Config>::Fp;
     type BaseSNARKField = BaseSigCurveField;
@@ -39,9 +49,10 @@ fn tracing_num_constraints_native() {
     assert!(cs.is_satisfied().unwrap());
 
     tracing::info!("R1CS is satisfied!");
+    metrics.constraint_system("native verification", &cs);
 }
 
-fn tracing_num_constraints_emulated() {
+fn tracing_num_constraints_emulated(metrics: &mut Metrics) {
     type BlsSigConfig = ark_bls12_381::Config;
     type BaseSigCurveField = <BlsSigConfig as Bls12Config>::Fp;
     type SNARKCurve = ark_bls12_377::Bls12_377;
@@ -72,11 +83,197 @@ fn tracing_num_constraints_emulated() {
     assert!(cs.is_satisfied().unwrap());
 
     tracing::info!("R1CS is satisfied!");
+    metrics.constraint_system("emulated verification", &cs);
+}
+
+/// Constraints to verify a committee of `committee_size` signers on one message, with an
+/// aggregate BLS signature (pairing-based) and with one EdDSA signature per signer.
+///
+/// Both run natively: BLS in the base field of BLS12-377 and EdDSA in the base field of
+/// `ed_on_bls12_377` (the scalar field of BLS12-377).
+fn compare_committee_constraints(committee_size: usize, metrics: &mut Metrics) {
+    type BlsSigConfig = ark_bls12_377::Config;
+    type BaseSNARKField = <BlsSigConfig as Bls12Config>::Fp;
+
+    let mut rng = thread_rng();
+    let msg = b"Hello World";
+
+    let bls_constraints = {
+        let cs = ConstraintSystem::new_ref();
+        let params = Parameters::<BlsSigConfig>::setup();
+        let secret_keys: Vec<_> = (0..committee_size)
+            .map(|_| SecretKey::new(&mut rng))
+            .collect();
+        let sig = Signature::<BlsSigConfig>::aggregate_sign(msg, &secret_keys, &params).unwrap();
+
+        let msg_var: Vec<UInt8<BaseSNARKField>> = UInt8::new_input_vec(cs.clone(), msg).unwrap();
+        let params_var: ParametersVar<BlsSigConfig, FpVar<BaseSNARKField>, BaseSNARKField> =
+            ParametersVar::new_input(cs.clone(), || Ok(params)).unwrap();
+        let pk_vars: Vec<_> = secret_keys
+            .iter()
+            .map(|sk| {
+                PublicKeyVar::new_input(cs.clone(), || Ok(PublicKey::new(sk, &params))).unwrap()
+            })
+            .collect();
+        let sig_var = SignatureVar::new_input(cs.clone(), || Ok(sig)).unwrap();
+
+        BLSAggregateSignatureVerifyGadget::aggregate_verify(
+            &params_var,
+            &pk_vars,
+            &msg_var,
+            &sig_var,
+        )
+        .unwrap();
+        assert!(cs.is_satisfied().unwrap());
+        metrics.constraint_system(format!("BLS, committee of {committee_size}"), &cs);
+        cs.num_constraints()
+    };
+
+    let eddsa_constraints = {
+        let cs = ConstraintSystem::new_ref();
+        let params = eddsa::Parameters::setup();
+        let secret_keys: Vec<_> = (0..committee_size)
+            .map(|_| eddsa::SecretKey::new(&mut rng))
+            .collect();
+
+        let params_var = eddsa::ParametersVar::new_constant(cs.clone(), params).unwrap();
+        let pk_vars: Vec<_> = secret_keys
+            .iter()
+            .map(|sk| {
+                eddsa::PublicKeyVar::new_input(cs.clone(), || {
+                    Ok(eddsa::PublicKey::new(sk, &params))
+                })
+                .unwrap()
+            })
+            .collect();
+        let msg_vars: Vec<_> = (0..committee_size)
+            .map(|_| UInt8::new_input_vec(cs.clone(), msg).unwrap())
+            .collect();
+        let sig_vars: Vec<_> = secret_keys
+            .iter()
+            .map(|sk| {
+                eddsa::SignatureVar::new_witness(cs.clone(), || {
+                    Ok(eddsa::Signature::sign(msg, sk, &params))
+                })
+                .unwrap()
+            })
+            .collect();
+
+        eddsa::EdDSAVerifyGadget::verify_committee(&params_var, &pk_vars, &msg_vars, &sig_vars)
+            .unwrap();
+        assert!(cs.is_satisfied().unwrap());
+        metrics.constraint_system(format!("EdDSA, committee of {committee_size}"), &cs);
+        cs.num_constraints()
+    };
+
+    tracing::info!(
+        "Committee of {}: BLS {} constraints, EdDSA {} constraints",
+        committee_size,
+        bls_constraints,
+        eddsa_constraints
+    );
+}
+
+/// Constraints to multiply the generator of `P` by a random scalar with
+/// `FixedBaseMulGadget`, and with `scalar_mul_le` on the constant generator.
+fn fixed_base_constraints<P, FP, CF>(name: &str)
+where
+    P: SWCurveConfig,
+    FP: FieldVar<P::BaseField, CF> + TwoBitLookupGadget<CF, TableConstant = P::BaseField>,
+    CF: PrimeField,
+    for<'a> &'a FP: FieldOpsBounds<'a, P::BaseField, FP>,
+{
+    let generator = P::GENERATOR.into();
+    let gadget = FixedBaseMulGadget::new(generator);
+    let k = P::ScalarField::rand(&mut thread_rng());
+
+    let cs = ConstraintSystem::<CF>::new_ref();
+    let bits =
+        Vec::<Boolean<CF>>::new_witness(cs.clone(), || Ok(k.into_bigint().to_bits_le())).unwrap();
+
+    let num_constraints = cs.num_constraints();
+    let _: ProjectiveVar<P, FP, CF> = gadget.mul(&bits).unwrap();
+    let fixed_constraints = cs.num_constraints() - num_constraints;
+
+    let num_constraints = cs.num_constraints();
+    let _ = ProjectiveVar::<P, FP, CF>::constant(generator)
+        .scalar_mul_le(bits.iter())
+        .unwrap();
+    let generic_constraints = cs.num_constraints() - num_constraints;
+    assert!(cs.is_satisfied().unwrap());
+
+    tracing::info!(
+        "Multiplying the {} generator: {} constraints with FixedBaseMulGadget, {} with scalar_mul_le ({:.1}% saved)",
+        name,
+        fixed_constraints,
+        generic_constraints,
+        100.0 * (1.0 - fixed_constraints as f64 / generic_constraints as f64)
+    );
+}
+
+/// Constraints to verify a signature on BLS12-381 emulated over the scalar field of
+/// BLS12-377, with `ParametersVar` allocated as a witness and as a constant.
+///
+/// With constant parameters, `-g1_generator` is a constant G1 point in the pairing, and
+/// the Miller loop evaluates its lines with coefficients scaled natively by `1 / y`.
+fn constant_generator_constraints() {
+    type BlsSigConfig = ark_bls12_381::Config;
+    type BaseSigCurveField = <BlsSigConfig as Bls12Config>::Fp;
+    type BaseSNARKField = <ark_bls12_377::Bls12_377 as Pairing>::ScalarField;
+
+    let (msg, params, _, pk, sig) = get_bls_instance::<BlsSigConfig>();
+    let [witness_constraints, constant_constraints] = [false, true].map(|constant| {
+        let cs = ConstraintSystem::new_ref();
+        let msg_var: Vec<UInt8<BaseSNARKField>> =
+            UInt8::new_input_vec(cs.clone(), msg.as_bytes()).unwrap();
+        let params_var: ParametersVar<
+            BlsSigConfig,
+            EmulatedFpVar<BaseSigCurveField, BaseSNARKField>,
+            BaseSNARKField,
+        > = if constant {
+            ParametersVar::new_constant(cs.clone(), params).unwrap()
+        } else {
+            ParametersVar::new_witness(cs.clone(), || Ok(params)).unwrap()
+        };
+        let pk_var = PublicKeyVar::new_input(cs.clone(), || Ok(pk)).unwrap();
+        let sig_var = SignatureVar::new_input(cs.clone(), || Ok(sig)).unwrap();
+
+        BLSAggregateSignatureVerifyGadget::verify(&params_var, &pk_var, &msg_var, &sig_var)
+            .unwrap();
+        assert!(cs.is_satisfied().unwrap());
+        cs.num_constraints()
+    });
+
+    tracing::info!(
+        "Emulated verification: {} constraints with witness parameters, {} with constant parameters ({} saved)",
+        witness_constraints,
+        constant_constraints,
+        witness_constraints - constant_constraints
+    );
 }
 
 fn main() {
     register_tracing();
+    let mut metrics = Metrics::new();
+
+    tracing_num_constraints_native(&mut metrics);
+    tracing_num_constraints_emulated(&mut metrics);
+
+    for committee_size in [1, 4, 16, MAX_COMMITTEE_SIZE] {
+        compare_committee_constraints(committee_size, &mut metrics);
+    }
+
+    type BaseSNARKField = <ark_bls12_377::Config as Bls12Config>::Fp;
+    fixed_base_constraints::<ark_bls12_377::g1::Config, FpVar<BaseSNARKField>, BaseSNARKField>(
+        "G1",
+    );
+    fixed_base_constraints::<
+        ark_bls12_377::g2::Config,
+        Fp2Var<ark_bls12_377::Fq2Config>,
+        BaseSNARKField,
+    >("G2");
+
+    constant_generator_constraints();
 
-    tracing_num_constraints_native();
-    tracing_num_constraints_emulated();
+    print_metrics(&metrics);
 }
@@ -0,0 +1,111 @@
+mod utils;
+
+use ark_mnt4_753::Fr as CF;
+use ark_r1cs_std::{alloc::AllocVar, fields::fp::FpVar, R1CSVar};
+use ark_relations::r1cs::{ConstraintSystem, SynthesisError};
+use rand::thread_rng;
+use sig::{
+    bc::{
+        block::Committee,
+        params::{BcConfig, MAX_COMMITTEE_SIZE},
+        testgen::ChainGenerator,
+    },
+    folding::{
+        bc::CommitteeVar,
+        compressed::CompressedCommitteeVar,
+        profiler::{ConstraintProfiler, RegionCost},
+        state::StateSerde,
+    },
+};
+use utils::register_tracing;
+
+/// The constraints to handle the committee in one step of a circuit that keeps it in the
+/// state as `S`: rebuilding the committee from `z_i`, serializing the committee of the new
+/// block for the signed message, and packing that committee into `z_{i+1}`.
+///
+/// Returns the length of the state and the profiled regions.
+fn profile<S: StateSerde<CF, Shape = BcConfig>>(
+    config: BcConfig,
+    committee: &Committee,
+    to_state: impl Fn(&CommitteeVar<CF>) -> Result<S, SynthesisError>,
+    rebuild: impl Fn(S) -> Result<CommitteeVar<CF>, SynthesisError>,
+) -> (usize, Vec<(&'static str, RegionCost)>) {
+    let cs = ConstraintSystem::<CF>::new_ref();
+    let z_i = to_state(&CommitteeVar::new_constant(cs.clone(), committee).unwrap())
+        .unwrap()
+        .to_state()
+        .unwrap()
+        .iter()
+        .map(|x| FpVar::new_witness(cs.clone(), || x.value()))
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    let next = CommitteeVar::new_witness(cs.clone(), || Ok(committee)).unwrap();
+
+    let mut profiler = ConstraintProfiler::new(cs.clone());
+    profiler
+        .region("reconstruction", |_| {
+            rebuild(S::from_state(z_i.into_iter(), config)?)
+        })
+        .unwrap();
+    let next = profiler
+        .region("serialization", |_| to_state(&next))
+        .unwrap();
+    profiler.region("packing", |_| next.to_state()).unwrap();
+    assert!(cs.is_satisfied().unwrap());
+
+    (S::state_len(config), profiler.regions().collect())
+}
+
+fn compare_committee_state(committee_size: usize) {
+    let config = BcConfig {
+        max_committee_size: committee_size,
+        ..Default::default()
+    };
+    let (blocks, _) = ChainGenerator::new(1, committee_size)
+        .config(config)
+        .generate(&mut thread_rng());
+    let committee = &blocks[0].committee;
+
+    // `CommitteeVar` serializes the new committee for the message only, so the bytes are
+    // thrown away here
+    let emulated = profile(
+        config,
+        committee,
+        |committee| {
+            CompressedCommitteeVar::from_committee(committee)?;
+            Ok(committee.clone())
+        },
+        Ok,
+    );
+    let compressed = profile(
+        config,
+        committee,
+        CompressedCommitteeVar::from_committee,
+        |compressed| compressed.committee(),
+    );
+
+    for (name, (state_len, regions)) in [
+        ("CommitteeVar", emulated),
+        ("CompressedCommitteeVar", compressed),
+    ] {
+        tracing::info!(
+            "Committee of {}: {} has {} state elements, {}",
+            committee_size,
+            name,
+            state_len,
+            regions
+                .iter()
+                .map(|(label, cost)| format!("{label} {} constraints", cost.constraints))
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+    }
+}
+
+fn main() {
+    register_tracing();
+
+    for committee_size in [1, 4, 16, MAX_COMMITTEE_SIZE] {
+        compare_committee_state(committee_size);
+    }
+}
@@ -0,0 +1,159 @@
+/// This experiment folds `BCCircuitNoMerkle` with Nova for several committee sizes and
+/// prints one JSON object per committee size with
+/// - the time to preprocess the folding parameters
+/// - the time of each `prove_step`
+/// - the time to verify the final IVC proof
+/// - the peak resident set size of the process so far
+/// - the time to allocate the committee as a witness signer by signer, and as `CommitteeVar`
+///   does, from the limbs of all the keys computed beforehand (in parallel with the `parallel`
+///   feature), and the speedup of the latter
+///
+/// The committee sizes and the number of steps can be set with the `COMMITTEE_SIZES`
+/// (comma-separated) and `N_STEPS` environment variables.
+///
+/// With the `metrics` feature, it also prints a table of the time and the peak RSS of each
+/// phase, and of the size of the matrices of the step circuit, for every committee size.
+mod utils;
+
+use std::{env, fs, time::Instant};
+
+use ark_mnt4_753::{Fr, G1Projective as G1, MNT4_753 as MNT4};
+use ark_mnt6_753::{G1Projective as G2, MNT6_753 as MNT6};
+use ark_r1cs_std::{alloc::AllocVar, fields::fp::FpVar};
+use ark_relations::r1cs::ConstraintSystem;
+
+use folding_schemes::{
+    commitment::kzg::KZG,
+    folding::nova::{Nova, PreprocessorParam},
+    frontend::FCircuit,
+    transcript::poseidon::poseidon_canonical_config,
+    Error, FoldingScheme,
+};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use sig::{
+    bc::{checkpoint::Checkpoint, params::BcConfig, testgen::ChainGenerator},
+    folding::{
+        bc::{CommitteeVar, SignerVar},
+        circuit::{BCCircuitNoMerkle, BCCircuitParams},
+        input::StepInputVar,
+    },
+    snark::witness_only_cs,
+};
+use utils::{print_metrics, Metrics};
+
+type FC = BCCircuitNoMerkle<(G1, G2)>;
+type N = Nova<G1, G2, FC, KZG<'static, MNT4>, KZG<'static, MNT6>, false>;
+
+/// The peak resident set size in KiB, as reported by Linux.
+fn peak_rss_kib() -> Option<u64> {
+    fs::read_to_string("/proc/self/status")
+        .ok()?
+        .lines()
+        .find_map(|line| line.strip_prefix("VmHWM:"))?
+        .trim()
+        .trim_end_matches("kB")
+        .trim()
+        .parse()
+        .ok()
+}
+
+fn env_or<T: std::str::FromStr>(name: &str, default: T) -> T {
+    env::var(name)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(default)
+}
+
+fn run(
+    committee_size: usize,
+    n_steps: usize,
+    rng: &mut StdRng,
+    metrics: &mut Metrics,
+) -> Result<String, Error> {
+    let params = BCCircuitParams {
+        config: BcConfig {
+            max_committee_size: committee_size,
+            ..Default::default()
+        },
+        ..BCCircuitParams::setup()
+    };
+    let f_circuit = FC::new(params)?;
+
+    let start = Instant::now();
+    let nova_params = metrics.phase(format!("preprocess, {committee_size} signers"), || {
+        N::preprocess(
+            &mut *rng,
+            &PreprocessorParam::new(poseidon_canonical_config::<Fr>(), f_circuit),
+        )
+    })?;
+    let preprocess = start.elapsed();
+
+    let (blocks, _) = ChainGenerator::new(n_steps + 1, committee_size)
+        .config(params.config)
+        .generate(&mut *rng);
+    let z_0 = f_circuit.initial_state(&Checkpoint::from_block(&blocks[0]), &blocks[0].committee)?;
+
+    // the allocation of the committee that each step rebuilds, without its constraints
+    let committee = &blocks[0].committee;
+    let start = Instant::now();
+    Vec::<SignerVar<Fr>>::new_witness(witness_only_cs(), || Ok(&committee.signers[..]))?;
+    let by_signer = start.elapsed();
+    let start = Instant::now();
+    CommitteeVar::<Fr>::new_witness(witness_only_cs(), || Ok(committee))?;
+    let from_hints = start.elapsed();
+
+    // the step circuit alone, as Nova augments it with the verification of the folding
+    if cfg!(feature = "metrics") && blocks.len() > 1 {
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let z_i = Vec::<FpVar<Fr>>::new_witness(cs.clone(), || Ok(&z_0[..]))?;
+        let block = StepInputVar::new_witness(cs.clone(), || Ok(Some(blocks[1].clone())))?;
+        f_circuit.generate_step_constraints(cs.clone(), 0, z_i, block)?;
+        metrics.constraint_system(format!("step circuit, {committee_size} signers"), &cs);
+    }
+
+    let mut nova = N::init(&nova_params, f_circuit, z_0)?;
+
+    let mut steps = Vec::with_capacity(n_steps);
+    for (i, block) in blocks.into_iter().skip(1).enumerate() {
+        let start = Instant::now();
+        metrics.phase(format!("prove step {i}, {committee_size} signers"), || {
+            nova.prove_step(&mut *rng, Some(block), None)
+        })?;
+        steps.push(start.elapsed().as_secs_f64());
+    }
+
+    let start = Instant::now();
+    N::verify(nova_params.1, nova.ivc_proof())?;
+    let verify = start.elapsed();
+
+    Ok(format!(
+        r#"{{"committee_size":{committee_size},"preprocess_s":{},"prove_step_s":{steps:?},"verify_s":{},"peak_rss_kib":{},"committee_witness_by_signer_s":{},"committee_witness_from_hints_s":{},"committee_witness_speedup":{}}}"#,
+        preprocess.as_secs_f64(),
+        verify.as_secs_f64(),
+        peak_rss_kib().map_or("null".to_string(), |rss| rss.to_string()),
+        by_signer.as_secs_f64(),
+        from_hints.as_secs_f64(),
+        by_signer.as_secs_f64() / from_hints.as_secs_f64(),
+    ))
+}
+
+fn main() -> Result<(), Error> {
+    let mut committee_sizes: Vec<usize> = env::var("COMMITTEE_SIZES")
+        .unwrap_or_else(|_| "4,8,16,25".to_string())
+        .split(',')
+        .map(|size| size.trim().parse().expect("invalid committee size"))
+        .collect();
+    let n_steps = env_or("N_STEPS", 3);
+    let mut rng = StdRng::from_seed([42; 32]); // deterministic seeding
+
+    // the peak RSS never decreases, so the smaller committees go first
+    committee_sizes.sort_unstable();
+    let mut metrics = Metrics::new();
+    for committee_size in committee_sizes {
+        println!("{}", run(committee_size, n_steps, &mut rng, &mut metrics)?);
+    }
+    print_metrics(&metrics);
+
+    Ok(())
+}
@@ -0,0 +1,60 @@
+use ark_ec::bls12::Bls12Config;
+use ark_groth16::Groth16;
+use ark_r1cs_std::fields::fp::FpVar;
+use ark_snark::{CircuitSpecificSetupSNARK, SNARK};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use rand::thread_rng;
+use sig::{
+    bls::{get_bls_instance, BLSCircuit},
+    snark::batch_verify,
+};
+
+const BATCH_SIZES: [usize; 3] = [4, 16, 64];
+
+fn bench_batch_verify(c: &mut Criterion) {
+    type BlsSigConfig = ark_bls12_377::Config;
+    type BaseSNARKField = <BlsSigConfig as Bls12Config>::Fp;
+    type SNARKCurve = ark_bw6_761::BW6_761;
+    type Circuit<'a> = BLSCircuit<'a, BlsSigConfig, FpVar<BaseSNARKField>, BaseSNARKField>;
+
+    let (msg, params, _, pk_bls, sig) = get_bls_instance::<BlsSigConfig>();
+    let mut rng = thread_rng();
+
+    let unassigned = vec![None; msg.len()];
+    let (pk, vk) =
+        Groth16::<SNARKCurve>::setup(Circuit::new(None, None, &unassigned, None), &mut rng)
+            .unwrap();
+    let pvk = Groth16::<SNARKCurve>::process_vk(&vk).unwrap();
+
+    // Groth16 proofs are randomized, so the proofs of the same signature are all distinct
+    let msg: Vec<_> = msg.as_bytes().iter().copied().map(Some).collect();
+    let circuit = Circuit::new(Some(params), Some(pk_bls), &msg, Some(sig));
+    let public_inputs = circuit.get_public_inputs().unwrap();
+    let proofs: Vec<_> = (0..BATCH_SIZES[BATCH_SIZES.len() - 1])
+        .map(|_| {
+            let proof = Groth16::<SNARKCurve>::prove(&pk, circuit.clone(), &mut rng).unwrap();
+            (proof, public_inputs.clone())
+        })
+        .collect();
+
+    let mut group = c.benchmark_group("Groth16 verification of many proofs");
+    for size in BATCH_SIZES {
+        let proofs = &proofs[..size];
+        group.bench_with_input(BenchmarkId::new("sequential", size), proofs, |b, proofs| {
+            b.iter(|| {
+                proofs.iter().all(|(proof, inputs)| {
+                    Groth16::<SNARKCurve>::verify_with_processed_vk(&pvk, inputs, proof).unwrap()
+                })
+            });
+        });
+        group.bench_with_input(BenchmarkId::new("batched", size), proofs, |b, proofs| {
+            b.iter(|| batch_verify(&pvk, proofs, &mut thread_rng()).unwrap());
+        });
+        assert!(batch_verify(&pvk, proofs, &mut rng).unwrap());
+    }
+    group.finish();
+}
+
+// set the minimum possible sample size because running each of them takes time
+criterion_group! {name = benches; config = Criterion::default().sample_size(10); targets = bench_batch_verify}
+criterion_main!(benches);
@@ -0,0 +1,65 @@
+use ark_groth16::Groth16;
+use ark_r1cs_std::fields::fp::FpVar;
+use ark_snark::{CircuitSpecificSetupSNARK, SNARK};
+use criterion::{criterion_group, criterion_main, Criterion};
+use rand::thread_rng;
+use sig::{
+    bls::{get_bls_instance, BLSCircuit},
+    snark::{
+        gpu::IcicleMsm,
+        msm::{prove_no_zk, CpuMsm},
+    },
+};
+
+fn bench_groth16_gpu_msm(c: &mut Criterion) {
+    type BlsSigConfig = ark_bls12_377::Config;
+    type BaseSNARKField = ark_bls12_377::Fq;
+    type SNARKCurve = ark_bw6_761::BW6_761;
+
+    let (msg, params, _, pk_bls, sig) = get_bls_instance::<BlsSigConfig>();
+    let mut rng = thread_rng();
+
+    let (pk, vk) = {
+        let msg = vec![None; msg.len()];
+        let circuit = BLSCircuit::<BlsSigConfig, FpVar<BaseSNARKField>, BaseSNARKField>::new(
+            None, None, &msg, None,
+        );
+        Groth16::<SNARKCurve>::setup(circuit, &mut rng).unwrap()
+    };
+
+    let msg: Vec<_> = msg.as_bytes().iter().copied().map(Some).collect();
+    let circuit = BLSCircuit::<BlsSigConfig, FpVar<BaseSNARKField>, BaseSNARKField>::new(
+        Some(params),
+        Some(pk_bls),
+        &msg,
+        Some(sig),
+    );
+    let public_inputs = circuit.get_public_inputs().unwrap();
+
+    // both provers compute the same proof, so the difference is the time of the MSMs
+    let proof = prove_no_zk::<SNARKCurve, IcicleMsm>(&pk, circuit.clone()).unwrap();
+    assert_eq!(
+        proof,
+        prove_no_zk::<SNARKCurve, CpuMsm>(&pk, circuit.clone()).unwrap()
+    );
+    assert!(Groth16::<SNARKCurve>::verify(&vk, &public_inputs, &proof).unwrap());
+
+    println!(
+        "IcicleMsm runs on the {}",
+        if IcicleMsm::is_available() {
+            "GPU"
+        } else {
+            "CPU (no CUDA backend or device)"
+        }
+    );
+    c.bench_function("proof generation with CpuMsm", |b| {
+        b.iter(|| prove_no_zk::<SNARKCurve, CpuMsm>(&pk, circuit.clone()).unwrap());
+    });
+    c.bench_function("proof generation with IcicleMsm", |b| {
+        b.iter(|| prove_no_zk::<SNARKCurve, IcicleMsm>(&pk, circuit.clone()).unwrap());
+    });
+}
+
+// set the minimum possible sample size because running each of them takes time
+criterion_group! {name = benches; config = Criterion::default().sample_size(10); targets = bench_groth16_gpu_msm}
+criterion_main!(benches);
@@ -1,10 +1,17 @@
 use ark_ec::bls12::Bls12Config;
 use ark_groth16::Groth16;
 use ark_r1cs_std::fields::fp::FpVar;
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem};
 use ark_snark::{CircuitSpecificSetupSNARK, SNARK};
 use criterion::{criterion_group, criterion_main, Criterion};
 use rand::thread_rng;
-use sig::bls::{get_bls_instance, BLSCircuit};
+use sig::{
+    bls::{get_bls_instance, BLSCircuit},
+    snark::{
+        generate_witness_only,
+        msm::{prove_no_zk, CpuMsm},
+    },
+};
 
 fn bench_groth16(c: &mut Criterion) {
     type BlsSigConfig = ark_bls12_377::Config;
@@ -61,6 +68,20 @@ fn bench_groth16(c: &mut Criterion) {
     // ===============Get public inputs===============
     let public_inputs = circuit.get_public_inputs().unwrap();
 
+    // ===============Generate the witness===============
+    {
+        c.bench_function("constraint and witness generation", |b| {
+            b.iter(|| {
+                let cs = ConstraintSystem::new_ref();
+                circuit.clone().generate_constraints(cs.clone()).unwrap();
+                cs
+            });
+        });
+        c.bench_function("witness generation only", |b| {
+            b.iter(|| generate_witness_only(circuit.clone()).unwrap());
+        });
+    }
+
     // ===============Create a proof===============
     let proof_gen =
         || Groth16::<SNARKCurve>::create_proof_with_reduction_no_zk(circuit.clone(), &pk).unwrap();
@@ -69,6 +90,9 @@ fn bench_groth16(c: &mut Criterion) {
         c.bench_function("proof generation", |b| {
             b.iter(&proof_gen);
         });
+        c.bench_function("proof generation with CpuMsm", |b| {
+            b.iter(|| prove_no_zk::<SNARKCurve, CpuMsm>(&pk, circuit.clone()).unwrap());
+        });
     }
 
     let proof = proof_gen();
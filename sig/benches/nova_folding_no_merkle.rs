@@ -10,18 +10,13 @@ use ark_mnt4_753::{Fr, G1Projective as G1, MNT4_753 as MNT4};
 use ark_mnt6_753::{G1Projective as G2, MNT6_753 as MNT6};
 
 use ark_groth16::Groth16;
-use ark_r1cs_std::convert::ToConstraintFieldGadget;
-use ark_r1cs_std::R1CSVar;
-use ark_r1cs_std::{alloc::AllocVar, uint64::UInt64};
-use ark_relations::r1cs::ConstraintSystem;
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Compress, Validate};
 use memmap2::Mmap;
 use rand::rngs::StdRng;
 use rand::SeedableRng;
 use sig::{
-    bc::block::gen_blockchain_with_params,
-    bls::Parameters,
-    folding::{bc::CommitteeVar, circuit::BCCircuitNoMerkle},
+    bc::{block::gen_blockchain_with_params, checkpoint::Checkpoint},
+    folding::circuit::{BCCircuitNoMerkle, BCCircuitParams},
 };
 use std::io::Read;
 
@@ -98,10 +93,10 @@ where
 }
 
 fn main() -> Result<(), Error> {
-    let f_circuit = BCCircuitNoMerkle::<Fr>::new(Parameters::setup())?;
+    let f_circuit = BCCircuitNoMerkle::<(G1, G2)>::new(BCCircuitParams::setup())?;
 
     // use Nova as FoldingScheme
-    type FC = BCCircuitNoMerkle<Fr>;
+    type FC = BCCircuitNoMerkle<(G1, G2)>;
     type N = Nova<G1, G2, FC, KZG<'static, MNT4>, KZG<'static, MNT6>, false>;
     type D = NovaDecider<
         G1,
@@ -179,24 +174,8 @@ fn main() -> Result<(), Error> {
     let mut nova = load_or_generate(
         &data_path.join("nova_folding_state.dat"),
         || {
-            let cs = ConstraintSystem::new_ref();
-            let z_0 = {
-                let mut z_0: Vec<_> = CommitteeVar::new_constant(
-                    cs,
-                    bc.get(n_steps_proven).unwrap().committee.clone(),
-                )?
-                .to_constraint_field()?
-                .iter()
-                .map(|fpvar| fpvar.value().unwrap())
-                .collect();
-                z_0.push(
-                    UInt64::constant(bc.get(0).unwrap().epoch)
-                        .to_fp()?
-                        .value()
-                        .unwrap(),
-                );
-                z_0
-            };
+            let block = bc.get(n_steps_proven).unwrap();
+            let z_0 = f_circuit.initial_state(&Checkpoint::from_block(block), &block.committee)?;
 
             timeit!("nova folding init", {
                 N::init(&nova_params, f_circuit, z_0)
@@ -221,7 +200,7 @@ fn main() -> Result<(), Error> {
     println!("nova folding prove step");
     for (i, block) in (0..N_STEPS_TO_PROVE).zip(bc.into_blocks().skip(n_steps_proven + 1)) {
         timeit!(format!("nova prove_step {}", n_steps_proven + i), {
-            nova.prove_step(&mut rng, block, None)?;
+            nova.prove_step(&mut rng, Some(block), None)?;
         })
     }
 
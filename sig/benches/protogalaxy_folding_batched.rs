@@ -0,0 +1,70 @@
+/// This experiment compares the amortised cost of folding one block with Nova and with
+/// ProtoGalaxy, when each folding step validates `K` blocks with `BCCircuitBatched`.
+///
+/// Both schemes fold the same step circuit from the same initial state, so only the
+/// folding backend differs between the runs.
+mod utils;
+
+use ark_mnt4_753::{G1Projective as G1, MNT4_753 as MNT4};
+use ark_mnt6_753::{G1Projective as G2, MNT6_753 as MNT6};
+
+use folding_schemes::{commitment::kzg::KZG, frontend::FCircuit, Error};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use sig::{
+    bc::{block::gen_blockchain_with_params, checkpoint::Checkpoint},
+    folding::{
+        backend::{Folding, FoldingBackend},
+        circuit::{BCCircuitBatched, BCCircuitParams},
+    },
+};
+
+const N_STEPS_TO_PROVE: usize = 2;
+const COMMITTEE_SIZE: usize = 25; // needs to <= MAX_COMMITTEE_SIZE
+
+fn run<const K: usize>(backend: FoldingBackend, rng: &mut StdRng) -> Result<(), Error> {
+    type CS1 = KZG<'static, MNT4>;
+    type CS2 = KZG<'static, MNT6>;
+
+    println!("{backend:?} with {K} blocks per step");
+    let f_circuit = BCCircuitBatched::<(G1, G2), K>::new(BCCircuitParams::setup())?;
+
+    let params = timeit!(format!("{backend:?} preprocess"), {
+        backend.preprocess::<G1, G2, _, CS1, CS2>(&mut *rng, &f_circuit)?
+    });
+
+    let bc = gen_blockchain_with_params(N_STEPS_TO_PROVE * K + 1, COMMITTEE_SIZE, &mut *rng);
+    let genesis = bc.get(0).unwrap();
+    let z_0 = f_circuit.initial_state(&Checkpoint::from_block(genesis), &genesis.committee)?;
+    let mut folding = Folding::init(&params, f_circuit, z_0)?;
+
+    let mut blocks = bc.into_blocks().skip(1);
+    let start = std::time::Instant::now();
+    for i in 0..N_STEPS_TO_PROVE {
+        let step: [_; K] = core::array::from_fn(|_| blocks.next().unwrap());
+        timeit!(format!("{backend:?} prove_step {i}"), {
+            folding.prove_step(&mut *rng, Some(step))?;
+        });
+    }
+    println!(
+        "{backend:?} amortised prove_step per block: {:?}",
+        start.elapsed() / (N_STEPS_TO_PROVE * K) as u32
+    );
+    println!("{backend:?} ivc proof size: {} bytes", folding.proof_size());
+
+    timeit!(format!("{backend:?} verify"), { folding.verify(&params)? });
+
+    Ok(())
+}
+
+fn main() -> Result<(), Error> {
+    let mut rng = StdRng::from_seed([42; 32]); // deterministic seeding
+
+    for backend in [FoldingBackend::Nova, FoldingBackend::ProtoGalaxy] {
+        run::<1>(backend, &mut rng)?;
+        run::<2>(backend, &mut rng)?;
+        run::<4>(backend, &mut rng)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,190 @@
+/// This experiment measures how the memory of the step circuit of `BCCircuitNoMerkle` grows
+/// with the size of the committee, and extrapolates it to committees too large to
+/// synthesize on the machine running it. It only measures: the step is still synthesized
+/// whole, not in chunks, so it does not lower the memory it reports.
+///
+/// For each committee size in `COMMITTEE_SIZES` (comma-separated), it runs itself again in a
+/// fresh process that synthesizes one folding step with its constraints, as the folding
+/// scheme does, and records the time and the peak RSS of the synthesis and the size of the
+/// constraint matrices. The RSS is that of the whole process, so a fresh process keeps the
+/// memory freed by smaller sizes, and the allocator arenas they grew, out of the measurement
+/// of the next one. Every signer adds the same constraints, so these grow linearly with the
+/// committee: the fitted lines give the cost at each size in `EXTRAPOLATED_SIZES`, and the
+/// largest committee whose step fits in `MEMORY_BUDGET_GIB`.
+use std::{env, process::Command};
+
+use ark_mnt4_753::{Fr as CF, G1Projective as G1};
+use ark_mnt6_753::G1Projective as G2;
+use ark_r1cs_std::{alloc::AllocVar, fields::fp::FpVar};
+use ark_relations::r1cs::ConstraintSystem;
+use folding_schemes::frontend::FCircuit;
+use rand::thread_rng;
+use sig::{
+    bc::{checkpoint::Checkpoint, params::BcConfig, testgen::ChainGenerator},
+    folding::{
+        circuit::{BCCircuitNoMerkle, BCCircuitParams},
+        input::StepInputVar,
+    },
+    metrics::{LinearFit, Metrics},
+};
+
+fn env_or<T: std::str::FromStr>(name: &str, default: T) -> T {
+    env::var(name)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(default)
+}
+
+fn sizes_or(name: &str, default: &[usize]) -> Vec<usize> {
+    env::var(name)
+        .ok()
+        .and_then(|sizes| {
+            sizes
+                .split(',')
+                .map(|size| size.trim().parse().ok())
+                .collect()
+        })
+        .unwrap_or_else(|| default.to_vec())
+}
+
+/// Synthesize a folding step with a committee of `committee_size`, and record it in
+/// `metrics`.
+fn synthesize_step(committee_size: usize, metrics: &mut Metrics) {
+    let params = BCCircuitParams {
+        config: BcConfig {
+            max_committee_size: committee_size,
+            ..Default::default()
+        },
+        ..BCCircuitParams::setup()
+    };
+    let (blocks, _) = ChainGenerator::new(2, committee_size)
+        .config(params.config)
+        .generate(&mut thread_rng());
+    let circuit = BCCircuitNoMerkle::<(G1, G2)>::new(params).unwrap();
+    let z_0 = circuit
+        .initial_state(&Checkpoint::from_block(&blocks[0]), &blocks[0].committee)
+        .unwrap();
+
+    let cs = metrics.phase(format!("synthesis, {committee_size} signers"), || {
+        let cs = ConstraintSystem::<CF>::new_ref();
+        let z_i = Vec::<FpVar<CF>>::new_witness(cs.clone(), || Ok(&z_0[..])).unwrap();
+        let block = StepInputVar::new_witness(cs.clone(), || Ok(Some(blocks[1].clone()))).unwrap();
+        circuit
+            .generate_step_constraints(cs.clone(), 0, z_i, block)
+            .unwrap();
+        cs
+    });
+    metrics.constraint_system(format!("step, {committee_size} signers"), &cs);
+}
+
+/// Set in the process that measures a single committee size.
+const CHILD_COMMITTEE_SIZE: &str = "STEP_MEMORY_COMMITTEE_SIZE";
+/// Prefixes the line in which a child reports its measurement to the parent.
+const RESULT_PREFIX: &str = "step_memory:";
+
+/// Measure a step with a committee of `committee_size` in this process, and report the
+/// constraints, the bytes of the matrices and the peak RSS in KiB after `RESULT_PREFIX`.
+fn measure_in_this_process(committee_size: usize) {
+    let mut metrics = Metrics::new();
+    synthesize_step(committee_size, &mut metrics);
+    println!("{metrics}");
+
+    // the phase is followed by the matrices of its constraint system
+    let samples = metrics.samples();
+    if let (Some(rss), Some(matrices)) = (samples[0].rss, samples.get(1).and_then(|s| s.matrices)) {
+        println!(
+            "{RESULT_PREFIX} {} {} {}",
+            matrices.num_constraints, matrices.bytes, rss.peak_kib
+        );
+    }
+}
+
+/// Measure a step with a committee of `committee_size` in a fresh process, and return
+/// the committee size, the constraints, the MiB of the matrices and the peak RSS in MiB.
+fn measure_in_fresh_process(committee_size: usize) -> Option<(f64, f64, f64, f64)> {
+    let output = Command::new(env::current_exe().expect("the benchmark should have a path"))
+        .env(CHILD_COMMITTEE_SIZE, committee_size.to_string())
+        .output()
+        .expect("the benchmark should be able to run itself");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    print!("{stdout}");
+    if !output.status.success() {
+        eprintln!(
+            "measuring {committee_size} signers failed ({}): {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr)
+        );
+        return None;
+    }
+
+    let result: Vec<f64> = stdout
+        .lines()
+        .find_map(|line| line.strip_prefix(RESULT_PREFIX))?
+        .split_whitespace()
+        .map(|n| n.parse().ok())
+        .collect::<Option<_>>()?;
+    let [constraints, bytes, peak_kib] = result[..] else {
+        return None;
+    };
+    Some((
+        committee_size as f64,
+        constraints,
+        bytes / (1 << 20) as f64,
+        peak_kib / 1024.0,
+    ))
+}
+
+fn main() {
+    if let Some(committee_size) = env::var(CHILD_COMMITTEE_SIZE)
+        .ok()
+        .and_then(|size| size.parse().ok())
+    {
+        measure_in_this_process(committee_size);
+        return;
+    }
+
+    let committee_sizes = sizes_or("COMMITTEE_SIZES", &[1, 2, 4, 8]);
+    let extrapolated_sizes = sizes_or("EXTRAPOLATED_SIZES", &[25, 100, 250, 500, 1000]);
+    let budget_gib: f64 = env_or("MEMORY_BUDGET_GIB", 64.0);
+
+    let measured: Vec<_> = committee_sizes
+        .iter()
+        .filter_map(|&size| measure_in_fresh_process(size))
+        .collect();
+    let fit = |points: Vec<(f64, f64)>| {
+        LinearFit::through(&points).expect("at least two committee sizes should be measured")
+    };
+    let constraints = fit(measured.iter().map(|m| (m.0, m.1)).collect());
+    let matrices_mib = fit(measured.iter().map(|m| (m.0, m.2)).collect());
+    let peak_mib = fit(measured.iter().map(|m| (m.0, m.3)).collect());
+
+    // the measured rows show how far the sizes are from the fitted lines
+    println!(
+        "{:>10} | {:>12} | {:>14} | {:>14} | {:>14}",
+        "signers", "", "constraints", "matrices (MiB)", "peak RSS (MiB)"
+    );
+    for &(x, num_constraints, matrices, peak) in &measured {
+        println!(
+            "{:>10} | {:>12} | {:>14.0} | {:>14.1} | {:>14.1}",
+            x, "measured", num_constraints, matrices, peak
+        );
+    }
+    for &committee_size in &extrapolated_sizes {
+        let x = committee_size as f64;
+        println!(
+            "{:>10} | {:>12} | {:>14.0} | {:>14.1} | {:>14.1}",
+            committee_size,
+            "extrapolated",
+            constraints.at(x),
+            matrices_mib.at(x),
+            peak_mib.at(x)
+        );
+    }
+    println!(
+        "per signer: {:.0} constraints, {:.1} MiB of matrices, {:.1} MiB of peak RSS",
+        constraints.slope, matrices_mib.slope, peak_mib.slope
+    );
+    if let Some(max) = peak_mib.max_within(budget_gib * 1024.0) {
+        println!("largest committee synthesized within {budget_gib} GiB: {max:.0} signers");
+    }
+}
@@ -1,6 +1,45 @@
+#[cfg(not(feature = "metrics"))]
+use ark_ff::PrimeField;
+#[cfg(not(feature = "metrics"))]
+use ark_relations::r1cs::ConstraintSystemRef;
+#[cfg(feature = "metrics")]
+#[allow(unused_imports)]
+pub use sig::metrics::Metrics;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, Layer};
 use tracing_tree::HierarchicalLayer;
 
+/// Without the `metrics` feature, phases run without being measured and nothing is recorded.
+#[cfg(not(feature = "metrics"))]
+#[derive(Default)]
+#[allow(dead_code)]
+pub struct Metrics;
+
+#[cfg(not(feature = "metrics"))]
+#[allow(dead_code)]
+impl Metrics {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn phase<T>(&mut self, _: impl Into<String>, f: impl FnOnce() -> T) -> T {
+        f()
+    }
+
+    pub fn constraint_system<F: PrimeField>(
+        &mut self,
+        _: impl Into<String>,
+        _: &ConstraintSystemRef<F>,
+    ) {
+    }
+}
+
+/// Print the table of `metrics` with the `metrics` feature.
+#[allow(dead_code, unused_variables)]
+pub fn print_metrics(metrics: &Metrics) {
+    #[cfg(feature = "metrics")]
+    println!("{metrics}");
+}
+
 #[allow(dead_code)]
 pub fn register_tracing() {
     tracing_subscriber::registry()
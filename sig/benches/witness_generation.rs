@@ -0,0 +1,139 @@
+use ark_mnt4_753::{Fr as CF, G1Projective as G1};
+use ark_mnt6_753::G1Projective as G2;
+use ark_r1cs_std::{alloc::AllocVar, fields::fp::FpVar};
+use ark_relations::r1cs::ConstraintSystem;
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+use folding_schemes::frontend::FCircuit;
+use rand::thread_rng;
+use sig::{
+    bc::{
+        block::Block,
+        checkpoint::Checkpoint,
+        params::{BcConfig, MAX_COMMITTEE_SIZE},
+        testgen::ChainGenerator,
+    },
+    folding::{
+        bc::{BlockVar, CommitteeVar, SignerVar},
+        circuit::{BCCircuitNoMerkle, BCCircuitParams},
+        input::StepInputVar,
+    },
+    snark::witness_only_cs,
+};
+
+/// The clones the allocation of `BlockVar` used to make: the committee and the quorum
+/// signature once in `BlockVar`, and the committee and the signers once more in
+/// `CommitteeVar` and `QuorumSignatureVar`.
+fn clone_parts(block: &Block) {
+    let committee = black_box(block.committee.clone());
+    black_box(committee.signers.clone());
+    let sig = black_box(block.sig.clone());
+    black_box(sig.signers.clone());
+}
+
+fn block_witness_bench(c: &mut Criterion) {
+    let config = BcConfig {
+        max_committee_size: MAX_COMMITTEE_SIZE,
+        ..Default::default()
+    };
+    let (blocks, _) = ChainGenerator::new(1, MAX_COMMITTEE_SIZE)
+        .config(config)
+        .generate(&mut thread_rng());
+    let block = &blocks[0];
+
+    let mut group = c.benchmark_group("Block witness generation");
+    group.bench_function("by reference", |b| {
+        b.iter_batched(
+            ConstraintSystem::<CF>::new_ref,
+            |cs| BlockVar::<CF>::new_witness(cs, || Ok(block)).unwrap(),
+            BatchSize::SmallInput,
+        );
+    });
+    group.bench_function("with the clones of the previous allocation", |b| {
+        b.iter_batched(
+            ConstraintSystem::<CF>::new_ref,
+            |cs| {
+                clone_parts(block);
+                BlockVar::<CF>::new_witness(cs, || Ok(block)).unwrap()
+            },
+            BatchSize::SmallInput,
+        );
+    });
+    group.finish();
+}
+
+/// A folding step of `BCCircuitNoMerkle`, with its constraints as the folding scheme
+/// generates it, and with the witness only, as `generate_witness_only` does.
+fn step_witness_bench(c: &mut Criterion) {
+    let committee_size = 4;
+    let params = BCCircuitParams {
+        config: BcConfig {
+            max_committee_size: committee_size,
+            ..Default::default()
+        },
+        ..BCCircuitParams::setup()
+    };
+    let (blocks, _) = ChainGenerator::new(2, committee_size)
+        .config(params.config)
+        .generate(&mut thread_rng());
+    let circuit = BCCircuitNoMerkle::<(G1, G2)>::new(params).unwrap();
+    let z_0 = circuit
+        .initial_state(&Checkpoint::from_block(&blocks[0]), &blocks[0].committee)
+        .unwrap();
+
+    let mut group = c.benchmark_group("Folding step witness generation");
+    group.sample_size(10);
+    group.bench_function("constraints and witness", |b| {
+        b.iter(|| {
+            let cs = ConstraintSystem::<CF>::new_ref();
+            let z_i = Vec::<FpVar<CF>>::new_witness(cs.clone(), || Ok(&z_0[..])).unwrap();
+            let block =
+                StepInputVar::new_witness(cs.clone(), || Ok(Some(blocks[1].clone()))).unwrap();
+            circuit
+                .generate_step_constraints(cs, 0, z_i, block)
+                .unwrap()
+        });
+    });
+    group.bench_function("witness only", |b| {
+        b.iter(|| circuit.generate_witness_only(&z_0, &blocks[1]).unwrap());
+    });
+    group.finish();
+}
+
+/// The allocation of a committee in `witness_only_cs`, signer by signer as `SignerVar` does,
+/// and from the limbs of the coordinates computed beforehand, as `CommitteeVar` does, in
+/// parallel with the `parallel` feature.
+fn committee_witness_bench(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Committee witness generation");
+    group.sample_size(10);
+    for committee_size in [MAX_COMMITTEE_SIZE, 1000] {
+        let config = BcConfig {
+            max_committee_size: committee_size,
+            ..Default::default()
+        };
+        let (blocks, _) = ChainGenerator::new(1, committee_size)
+            .config(config)
+            .generate(&mut thread_rng());
+        let committee = &blocks[0].committee;
+
+        group.bench_function(format!("signer by signer, {committee_size} signers"), |b| {
+            b.iter(|| {
+                Vec::<SignerVar<CF>>::new_witness(witness_only_cs(), || Ok(&committee.signers[..]))
+                    .unwrap()
+            });
+        });
+        group.bench_function(format!("from hints, {committee_size} signers"), |b| {
+            b.iter(|| {
+                CommitteeVar::<CF>::new_witness(witness_only_cs(), || Ok(committee)).unwrap()
+            });
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    block_witness_bench,
+    step_witness_bench,
+    committee_witness_bench
+);
+criterion_main!(benches);
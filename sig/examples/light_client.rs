@@ -0,0 +1,103 @@
+/// This example runs the whole pipeline of the light client:
+/// - generate a chain of `N_EPOCHS` (100 by default) epochs after the genesis block, where
+///   the committee of `COMMITTEE_SIZE` (4 by default) members rotates every epoch
+/// - check and fold every block with Nova and `BCCircuitNoMerkle`
+/// - compress the folded chain into a decider proof
+/// - verify the decider proof, as the Solidity verifier would
+///
+/// Run it with `cargo run --release --example light_client --features evm`.
+use std::{env, time::Instant};
+
+use folding_schemes::{
+    folding::nova::PreprocessorParam, transcript::poseidon::poseidon_canonical_config, Error,
+    FoldingScheme,
+};
+use rand::{rngs::StdRng, SeedableRng};
+use sig::{
+    bc::{params::BcConfig, testgen::ChainGenerator},
+    folding::{
+        circuit::{BCCircuitNoMerkle, BCCircuitParams},
+        decider::{EvmNova, LightClientDecider},
+        light_client::LightClient,
+    },
+    params::{Bn254Grumpkin, CycleField},
+};
+
+type Fr = CycleField<Bn254Grumpkin>;
+type FC = BCCircuitNoMerkle<Bn254Grumpkin>;
+type N = EvmNova<FC>;
+
+fn env_or<T: std::str::FromStr>(name: &str, default: T) -> T {
+    env::var(name)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(default)
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let n_epochs = env_or("N_EPOCHS", 100);
+    let committee_size = env_or("COMMITTEE_SIZE", 4);
+    let mut rng = StdRng::from_seed([42; 32]); // deterministic seeding
+
+    let params = BCCircuitParams {
+        config: BcConfig {
+            max_committee_size: committee_size,
+            ..Default::default()
+        },
+        ..BCCircuitParams::setup()
+    };
+
+    // 1. simulate the chain
+    let (blocks, _) = ChainGenerator::new(n_epochs + 1, committee_size)
+        .config(params.config)
+        .generate(&mut rng);
+    println!("generated {n_epochs} epochs with a committee of {committee_size}");
+
+    // 2. fold it
+    let circuit = FC::new(params)?;
+    let start = Instant::now();
+    let nova_params = N::preprocess(
+        &mut rng,
+        &PreprocessorParam::new(poseidon_canonical_config::<Fr>(), circuit),
+    )?;
+    println!("preprocessed Nova in {:?}", start.elapsed());
+
+    let mut blocks = blocks.into_iter();
+    let genesis = blocks
+        .next()
+        .expect("the chain starts with the genesis block");
+    let mut client = LightClient::<Bn254Grumpkin, N>::new(genesis, params, nova_params.clone())?;
+
+    let start = Instant::now();
+    for block in blocks {
+        client.process_block(&block, &mut rng)?;
+        if block.epoch % 10 == 0 {
+            println!("folded epoch {} after {:?}", block.epoch, start.elapsed());
+        }
+    }
+    let proof = client.prove();
+    client.verify(proof.clone(), &client.current_state(), client.committee())?;
+    println!(
+        "verified the IVC proof of epoch {}",
+        client.current_state().epoch
+    );
+
+    // 3. decide
+    let start = Instant::now();
+    let decider = LightClientDecider::preprocess(&mut rng, nova_params.clone(), &circuit)?;
+    println!("preprocessed the decider in {:?}", start.elapsed());
+
+    let start = Instant::now();
+    let nova = N::from_ivc_proof(proof, params, nova_params)?;
+    let final_proof = decider.prove_final(&mut rng, &nova)?;
+    println!("generated the decider proof in {:?}", start.elapsed());
+
+    // 4. verify
+    let start = Instant::now();
+    if !decider.verify_final(&final_proof)? {
+        return Err(Error::Other("the decider proof does not verify".to_string()).into());
+    }
+    println!("verified the decider proof in {:?}", start.elapsed());
+
+    Ok(())
+}
@@ -0,0 +1,111 @@
+/// This example composes two layers of Groth16 proofs along the MNT4-753/MNT6-753 cycle, as
+/// an alternative to folding:
+/// - the inner proof, over MNT4-753, shows the knowledge of a square root `x` of `y`
+/// - the outer proof, over MNT6-753, shows that the inner proof verifies for `y`, with
+///   `Groth16VerifierGadget` over the base field of MNT4-753
+/// - the outer proof is verified natively, without the inner proof
+///
+/// Run it with `cargo run --release --example recursive_groth16`.
+use std::time::Instant;
+
+use ark_ff::{Field, PrimeField};
+use ark_groth16::{Groth16, Proof, VerifyingKey};
+use ark_mnt4_753::{constraints::PairingVar, Fq, Fr, MNT4_753};
+use ark_mnt6_753::MNT6_753;
+use ark_r1cs_std::{
+    alloc::AllocVar, convert::ToBitsGadget, eq::EqGadget, fields::fp::FpVar, prelude::Boolean,
+};
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
+use ark_snark::{CircuitSpecificSetupSNARK, SNARK};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use sig::snark::{Groth16VerifierGadget, ProofVar, VerifyingKeyVar};
+
+/// Knowledge of a square root `x` of the public `y`.
+#[derive(Clone, Copy)]
+struct Square(Option<Fr>);
+
+impl ConstraintSynthesizer<Fr> for Square {
+    fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+        let x = FpVar::new_witness(cs.clone(), || {
+            self.0.ok_or(SynthesisError::AssignmentMissing)
+        })?;
+        let y = FpVar::new_input(cs, || {
+            self.0
+                .map(|x| x.square())
+                .ok_or(SynthesisError::AssignmentMissing)
+        })?;
+        x.square()?.enforce_equal(&y)
+    }
+}
+
+/// Knowledge of an inner proof of `Square` for the public `y`, which is smaller than the
+/// modulus of `Fq` and so is a single public input of the outer proof.
+#[derive(Clone)]
+struct VerifySquare {
+    vk: VerifyingKey<MNT4_753>,
+    proof: Option<Proof<MNT4_753>>,
+    y: Option<Fr>,
+}
+
+impl ConstraintSynthesizer<Fq> for VerifySquare {
+    fn generate_constraints(self, cs: ConstraintSystemRef<Fq>) -> Result<(), SynthesisError> {
+        // the inner circuit is fixed, so its key is a constant of the outer one
+        let vk = VerifyingKeyVar::<MNT4_753, PairingVar, Fq>::new_constant(cs.clone(), self.vk)?;
+        let proof = ProofVar::new_witness(cs.clone(), || {
+            self.proof.ok_or(SynthesisError::AssignmentMissing)
+        })?;
+        let y = FpVar::new_input(cs, || {
+            self.y
+                .map(|y| Fq::from(y.into_bigint()))
+                .ok_or(SynthesisError::AssignmentMissing)
+        })?;
+
+        Groth16VerifierGadget::verify(&vk, &[y.to_bits_le()?], &proof)?
+            .enforce_equal(&Boolean::TRUE)
+    }
+}
+
+fn main() -> Result<(), SynthesisError> {
+    let mut rng = StdRng::from_seed([42; 32]); // deterministic seeding
+
+    // 1. the inner proof
+    let start = Instant::now();
+    let (inner_pk, inner_vk) = Groth16::<MNT4_753>::setup(Square(None), &mut rng)?;
+    let x: Fr = rng.gen();
+    let y = x.square();
+    let inner_proof = Groth16::<MNT4_753>::prove(&inner_pk, Square(Some(x)), &mut rng)?;
+    assert!(Groth16::<MNT4_753>::verify(&inner_vk, &[y], &inner_proof)?);
+    println!("inner proof over MNT4-753 in {:?}", start.elapsed());
+
+    // 2. the outer proof of its verification
+    let start = Instant::now();
+    let setup = VerifySquare {
+        vk: inner_vk.clone(),
+        proof: None,
+        y: None,
+    };
+    let (outer_pk, outer_vk) = Groth16::<MNT6_753>::setup(setup, &mut rng)?;
+    println!("outer setup over MNT6-753 in {:?}", start.elapsed());
+
+    let start = Instant::now();
+    let circuit = VerifySquare {
+        vk: inner_vk,
+        proof: Some(inner_proof),
+        y: Some(y),
+    };
+    let outer_proof = Groth16::<MNT6_753>::prove(&outer_pk, circuit, &mut rng)?;
+    println!("outer proof over MNT6-753 in {:?}", start.elapsed());
+
+    // 3. verify the outer proof, which only needs `y`
+    let start = Instant::now();
+    let y = Fq::from(y.into_bigint());
+    assert!(Groth16::<MNT6_753>::verify(&outer_vk, &[y], &outer_proof)?);
+    assert!(!Groth16::<MNT6_753>::verify(
+        &outer_vk,
+        &[y + Fq::ONE],
+        &outer_proof
+    )?);
+    println!("outer proof verified in {:?}", start.elapsed());
+
+    Ok(())
+}
@@ -1,24 +1,31 @@
-use ark_ec::{
-    short_weierstrass::{Affine, SWCurveConfig},
-    CurveGroup,
-};
-use ark_serialize::CanonicalSerialize;
+use ark_std::{borrow::ToOwned, vec, vec::Vec};
 use blake2::Digest;
 use delegate::delegate;
+#[cfg(feature = "std")]
 use rand::Rng;
-use serde::{ser::SerializeTuple, Serialize, Serializer};
+#[cfg(feature = "serde")]
+use serde::Deserialize;
+use serde::Serialize;
 
+#[cfg(feature = "std")]
+use crate::bc::testgen::ChainGenerator;
 use crate::{
-    bc::params::{AuthoritySecretKey, MAX_COMMITTEE_SIZE},
-    bls::Signature,
+    bc::{
+        encoding::to_bytes,
+        padding::PaddingPolicy,
+        params::{BcConfig, MAX_COMMITTEE_SIZE},
+    },
+    bls::{AggregatePublicKey, PartialAggregator, Signature},
+    params::BlsSigConfig,
 };
 
 use super::params::{
     AuthorityAggregatedSignature, AuthorityPublicKey, AuthoritySigParams, HashFunc, Signers,
-    Weight, HASH_OUTPUT_SIZE, STRONG_THRESHOLD, TOTAL_VOTING_POWER,
+    Weight, HASH_OUTPUT_SIZE, STRONG_THRESHOLD,
 };
 
 #[derive(Serialize, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
 pub struct QuorumSignature {
     pub sig: AuthorityAggregatedSignature,
     // a roaring bitmap is a better alternative, but for easy impl of R1CS circuit, we use Vec<bool>
@@ -26,11 +33,13 @@ pub struct QuorumSignature {
 }
 
 #[derive(Serialize, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
 pub struct Committee {
     pub signers: Vec<(AuthorityPublicKey, Weight)>,
 }
 
 #[derive(Serialize, Debug, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
 pub struct Block {
     pub epoch: u64,
 
@@ -53,10 +62,7 @@ pub struct Blockchain {
 impl Default for QuorumSignature {
     // a default quorum signature contains `MAX_COMMITTEE_SIZE` signers
     fn default() -> Self {
-        Self {
-            sig: Default::default(),
-            signers: vec![bool::default(); MAX_COMMITTEE_SIZE],
-        }
+        Self::empty(MAX_COMMITTEE_SIZE)
     }
 }
 
@@ -69,144 +75,176 @@ impl Default for Committee {
     }
 }
 
-fn serialize_curve_point<Config: SWCurveConfig, S: Serializer>(
-    affine: Affine<Config>,
-    serializer: S,
-) -> Result<S::Ok, S::Error> {
-    let mut bytes = vec![];
-    affine
-        .x
-        .serialize_uncompressed(&mut bytes)
-        .map_err(serde::ser::Error::custom)?;
-    affine
-        .y
-        .serialize_uncompressed(&mut bytes)
-        .map_err(serde::ser::Error::custom)?;
-    affine
-        .infinity
-        .serialize_uncompressed(&mut bytes)
-        .map_err(serde::ser::Error::custom)?;
-
-    // The length of the struct is static, so it's safe to use this
-    let mut seq = serializer.serialize_tuple(bytes.len())?;
-    for b in bytes {
-        seq.serialize_element(&b)?;
+impl Committee {
+    /// The hash of the canonical bytes of the committee, which a `Checkpoint` commits to.
+    #[must_use]
+    pub fn digest(&self) -> [u8; HASH_OUTPUT_SIZE] {
+        HashFunc::digest(to_bytes(self).expect("serializing a committee should succeed")).into()
     }
-    seq.end()
 }
 
-/// Serialize is implemented manually because it's easy to match it with `SerializeGadget` implementation
-impl Serialize for AuthorityAggregatedSignature {
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: serde::Serializer,
-    {
-        let affine = self.signature.into_affine();
-        serialize_curve_point(affine, serializer)
+impl QuorumSignature {
+    /// The placeholder signature of a block before it is signed, for a committee padded to
+    /// `max_committee_size`.
+    #[must_use]
+    pub fn empty(max_committee_size: usize) -> Self {
+        Self {
+            sig: Default::default(),
+            signers: vec![false; max_committee_size],
+        }
+    }
+
+    /// Sign `message` with the members of `signers` selected by `bitmap`.
+    pub(crate) fn sign(
+        message: &[u8],
+        signers: &Signers,
+        bitmap: &[bool],
+        params: &AuthoritySigParams,
+    ) -> Self {
+        let sig = AuthorityAggregatedSignature::aggregate_sign(
+            message,
+            &signers
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| *bitmap.get(*i).unwrap_or(&false))
+                .map(|(_, sec)| sec)
+                .cloned()
+                .collect::<Vec<_>>(),
+            params,
+        );
+
+        Self {
+            sig: sig.expect("at least one secret key is provided"),
+            signers: bitmap.to_owned(),
+        }
+    }
+
+    /// Natively check what `BCCircuitNoMerkle` enforces for a quorum signature: the signers
+    /// selected by the bitmap hold at least `threshold` weight, and their aggregate public key
+    /// verifies `self.sig` on `block_bytes`, the exact bytes signed by the committee.
+    ///
+    /// Rejects a bitmap whose length differs from the committee's, and a quorum whose weights
+    /// overflow `Weight` when summed.
+    #[must_use]
+    pub fn verify(
+        &self,
+        block_bytes: &[u8],
+        committee: &Committee,
+        threshold: Weight,
+        params: &AuthoritySigParams,
+    ) -> bool {
+        if self.signers.len() != committee.signers.len() {
+            return false;
+        }
+
+        let (public_keys, weights): (Vec<_>, Vec<_>) = committee
+            .signers
+            .iter()
+            .zip(&self.signers)
+            .filter(|(_, signed)| **signed)
+            .map(|(signer_info, _)| *signer_info)
+            .unzip();
+
+        let weight = weights
+            .iter()
+            .try_fold(Weight::default(), |sum, w| sum.checked_add(*w));
+        if weight.is_none_or(|weight| weight < threshold) {
+            return false;
+        }
+
+        // an empty quorum never verifies
+        AggregatePublicKey::aggregate(&public_keys).is_some_and(|aggregate_pk| {
+            Signature::verify_aggregate(block_bytes, &self.sig, &aggregate_pk, params)
+        })
     }
 }
 
-/// Serialize is implemented manually because it's easy to match it with `SerializeGadget` implementation
-impl Serialize for AuthorityPublicKey {
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: serde::Serializer,
-    {
-        let affine = self.pub_key.into_affine();
-        serialize_curve_point(affine, serializer)
+/// The aggregator must have been created with the public keys of the committee, in order,
+/// so that its signer bitmap lines up with `Committee::signers`.
+impl From<&PartialAggregator<BlsSigConfig>> for QuorumSignature {
+    fn from(aggregator: &PartialAggregator<BlsSigConfig>) -> Self {
+        Self {
+            sig: aggregator.aggregate_signature().unwrap_or_default(),
+            signers: aggregator.signers().to_vec(),
+        }
     }
 }
 
 impl Block {
+    /// The first block of a chain, with `committee` padded to `config.max_committee_size`
+    /// by signers without weight.
     #[must_use]
-    pub fn genesis(data: Committee) -> Self {
+    pub fn genesis(committee: Committee, config: &BcConfig) -> Self {
+        let committee = PaddingPolicy::Pad
+            .committee(committee, config)
+            .unwrap_or_else(|e| panic!("genesis committee cannot be padded: {e}"));
+
         Self {
             epoch: 0,
             prev_digest: Default::default(),
-            sig: Default::default(),
-            committee: data,
+            sig: QuorumSignature::empty(config.max_committee_size),
+            committee,
         }
     }
 
-    fn new(
+    pub(crate) fn new(
         prev: &Self,
         data: Committee,
         signers: &Signers,
         bitmap: &[bool],
         params: &AuthoritySigParams,
-    ) -> Result<Self, Box<bincode::Error>> {
+    ) -> Self {
         assert!(!bitmap.is_empty(), "block must be signed");
 
         let mut block = Self {
-            epoch: prev.epoch + 1_u64,
-            prev_digest: compute_digest(prev),
-            sig: Default::default(),
+            epoch: prev
+                .epoch
+                .checked_add(1)
+                .expect("epoch should not overflow"),
+            prev_digest: prev.digest(),
+            sig: QuorumSignature::empty(bitmap.len()),
             committee: data,
         };
 
-        let mut hasher = HashFunc::new();
-        hasher.update(bincode::serialize(&block)?);
-        let sig = AuthorityAggregatedSignature::aggregate_sign(
-            &Into::<[u8; HASH_OUTPUT_SIZE]>::into(hasher.finalize()),
-            &signers
-                .iter()
-                .enumerate()
-                .filter(|(i, _)| *bitmap.get(*i).unwrap_or(&false))
-                .map(|(_, sec)| sec)
-                .copied()
-                .collect::<Vec<_>>(),
-            params,
-        );
-
-        block.sig = QuorumSignature {
-            sig: sig.expect("at least one secret key is provided"),
-            signers: bitmap.to_owned(),
-        };
-
-        Ok(block)
+        block.sig = QuorumSignature::sign(&block.signing_bytes(), signers, bitmap, params);
+        block
     }
 
     #[must_use]
     pub fn verify(&self, committee: &Committee, epoch: u64, params: &AuthoritySigParams) -> bool {
         assert!(
-            self.epoch == epoch + 1,
+            epoch.checked_add(1) == Some(self.epoch),
             "epoch mismatches: expect {} but get {}",
             self.epoch,
             epoch
         );
 
-        let aggregate_signer_info = committee
-            .signers
-            .iter()
-            .enumerate()
-            .filter(|(i, _)| self.sig.signers[*i])
-            .map(|(_, signer_info)| signer_info)
-            .copied()
-            .reduce(|acc, e| {
-                (
-                    AuthorityPublicKey {
-                        pub_key: acc.0.pub_key + e.0.pub_key,
-                    },
-                    acc.1 + e.1,
-                )
-            });
-
-        // prepare the msg used in signing
-        let mut self_clone = self.clone();
-        self_clone.sig = QuorumSignature::default();
-        let msg = bincode::serialize(&self_clone).expect("serialization should succeed");
-
-        if let Some((aggregate_pk, weights)) = aggregate_signer_info {
-            if weights < STRONG_THRESHOLD {
-                return false;
-            }
-            let mut hasher = HashFunc::new();
-            hasher.update(msg);
-            return Signature::verify(&hasher.finalize(), &self.sig.sig, &aggregate_pk, params);
-        }
+        self.sig
+            .verify(&self.signing_bytes(), committee, STRONG_THRESHOLD, params)
+    }
 
-        // weights == 0 => no quorum signs this block
-        false
+    /// The `bincode` encoding of the block, which `BlockVar::serialize` reproduces
+    /// byte for byte inside the circuit.
+    #[must_use]
+    pub fn to_canonical_bytes(&self) -> Vec<u8> {
+        to_bytes(self).expect("serializing a block should succeed")
+    }
+
+    /// Hash of the canonical bytes, which the next block stores as `prev_digest`.
+    #[must_use]
+    pub fn digest(&self) -> [u8; HASH_OUTPUT_SIZE] {
+        HashFunc::digest(self.to_canonical_bytes()).into()
+    }
+
+    /// The message signed by the committee: the canonical bytes of the block with an
+    /// empty signature, exactly what the folding circuit verifies the signature on.
+    #[must_use]
+    pub fn signing_bytes(&self) -> Vec<u8> {
+        Self {
+            sig: QuorumSignature::empty(self.sig.signers.len()),
+            ..self.clone()
+        }
+        .to_canonical_bytes()
     }
 }
 
@@ -230,10 +268,9 @@ impl Blockchain {
 
             #[must_use] pub fn len(&self) -> usize;
 
+            #[cfg(feature = "std")]
             fn reserve(&mut self, size: usize);
 
-            fn last(&self) -> Option<&Block>;
-
             pub fn get(&self, i: usize) -> Option<&Block>;
 
             #[call(into_iter)]
@@ -248,7 +285,7 @@ impl Blockchain {
         }
 
         let mut committee = &self.blocks[0].committee;
-        let mut prev_digest = compute_digest(&self.blocks[0]);
+        let mut prev_digest = self.blocks[0].digest();
         let mut committee_epoch = self.blocks[0].epoch;
 
         for block in self.blocks.iter().skip(1) {
@@ -257,7 +294,7 @@ impl Blockchain {
             {
                 return false;
             }
-            prev_digest = compute_digest(block);
+            prev_digest = block.digest();
             committee = &block.committee;
             committee_epoch = block.epoch;
         }
@@ -266,134 +303,23 @@ impl Blockchain {
     }
 }
 
-fn compute_digest(block: &Block) -> [u8; HASH_OUTPUT_SIZE] {
-    let bytes = bincode::serialize(&block).unwrap();
-    let mut hasher = HashFunc::new();
-    hasher.update(bytes);
-    hasher.finalize().into()
-}
-
-fn generate_committee<R: Rng>(
-    committee_size: usize,
-    params: &AuthoritySigParams,
-    rng: &mut R,
-) -> (Signers, Committee) {
-    let mut weights = Vec::new();
-    let mut remaining_weight = TOTAL_VOTING_POWER;
-    for _ in 0..committee_size - 1 {
-        let weight = rng.gen_range(0..=remaining_weight);
-        weights.push(weight);
-        remaining_weight -= weight;
-    }
-    weights.push(remaining_weight);
-
-    // fill to `MAX_COMMITTEE_SIZE`
-    weights.extend(std::iter::repeat(0).take(MAX_COMMITTEE_SIZE - committee_size));
-
-    let csk = (0..MAX_COMMITTEE_SIZE)
-        .map(|_| AuthoritySecretKey::new(rng))
-        .collect::<Vec<_>>();
-    let committee = csk
-        .iter()
-        .zip(weights)
-        .map(|(sk, weight)| (AuthorityPublicKey::new(sk, params), weight))
-        .collect::<Vec<_>>();
-
-    (csk, Committee { signers: committee })
-}
-
-fn select_strong_committee<R: Rng>(
-    committee: &Committee,
-    effective_committee_size: usize,
-    rng: &mut R,
-) -> Vec<bool> {
-    let mut selected_indices = vec![false; effective_committee_size];
-    let mut total_weight: u64 = 0;
-    let signers = &committee.signers[0..effective_committee_size];
-
-    while total_weight < STRONG_THRESHOLD {
-        let index = rng.gen_range(0..signers.len());
-        if !selected_indices[index] {
-            selected_indices[index] = true;
-            total_weight += signers[index].1;
-        }
-    }
-
-    // fill to `MAX_COMMITTEE_SIZE`
-    selected_indices
-        .extend(std::iter::repeat(false).take(MAX_COMMITTEE_SIZE - effective_committee_size));
-
-    selected_indices
-}
-
 /// Generate a blockchain with effective committee size `committee_size`.
 /// By effective, it means in the returned blockchain, every block has a committee size of `MAX_COMMITTEE_SIZE`,
 /// but only `committee_size` of them has non-zero weights.
+///
+/// See `ChainGenerator` for more control over the generated chain.
+#[cfg(feature = "std")]
 #[must_use]
 pub fn gen_blockchain_with_params<R: Rng>(
     num_epochs: usize,
     effective_committee_size: usize,
     rng: &mut R,
 ) -> Blockchain {
-    assert!(num_epochs > 0, "num_epochs should > 0");
-    assert!(
-        effective_committee_size > 0,
-        "effective_committee_size should > 0"
-    );
-    assert!(
-        effective_committee_size <= MAX_COMMITTEE_SIZE,
-        "effective_committee_size should <= MAX_COMMITTEE_SIZE {}",
-        MAX_COMMITTEE_SIZE
-    );
-
-    // generate param
-    let params = AuthoritySigParams::setup();
-
-    let mut bc = Blockchain::new(params);
-    bc.reserve(num_epochs);
-
-    // generate genesis block
-    let (signers, committee) = generate_committee(effective_committee_size, &params, rng);
-
-    assert_eq!(
-        committee.signers.len(),
-        MAX_COMMITTEE_SIZE,
-        "committee must have len == MAX_COMMITTEE_SIZE"
-    );
-
-    let genesis_block = Block::genesis(committee.clone());
-    bc.add_block(genesis_block);
-
-    let mut prev_signers = signers;
-    let mut prev_committee = committee;
-    let mut prev_block = &bc.blocks[0];
-
-    // generate blocks for other epochs
-    for _ in 1..num_epochs {
-        let bitmap = select_strong_committee(&prev_committee, effective_committee_size, rng);
+    let (blocks, _) = ChainGenerator::new(num_epochs, effective_committee_size).generate(rng);
 
-        assert_eq!(
-            bitmap.len(),
-            MAX_COMMITTEE_SIZE,
-            "bitmap must have len == MAX_COMMITTEE_SIZE"
-        );
-
-        let (signers, committee) = generate_committee(effective_committee_size, &params, rng);
-
-        let block = Block::new(
-            prev_block,
-            committee.clone(),
-            &prev_signers,
-            &bitmap,
-            &params,
-        )
-        .unwrap();
-        bc.add_block(block);
-        prev_block = bc.last().unwrap();
-
-        prev_committee = committee;
-        prev_signers = signers;
-    }
+    let mut bc = Blockchain::new(AuthoritySigParams::setup());
+    bc.reserve(num_epochs);
+    blocks.into_iter().for_each(|block| bc.add_block(block));
 
     assert_eq!(bc.len(), num_epochs);
     assert!(bc.verify());
@@ -403,12 +329,114 @@ pub fn gen_blockchain_with_params<R: Rng>(
 
 #[cfg(test)]
 mod test {
+    use ark_r1cs_std::{alloc::AllocVar, R1CSVar};
+    use ark_relations::r1cs::ConstraintSystem;
     use rand::thread_rng;
 
-    use super::gen_blockchain_with_params;
+    use crate::{
+        bc::{
+            params::{AuthoritySigParams, Weight, STRONG_THRESHOLD, TOTAL_VOTING_POWER},
+            testgen::ChainGenerator,
+        },
+        bls::{PartialAggregator, Signature},
+        folding::{bc::BlockVar, serialize::SerializeGadget},
+        params::{BlsSigConfig, BlsSigField},
+    };
+
+    use super::{gen_blockchain_with_params, Block, QuorumSignature};
 
     #[test]
     fn test_gen_blockchain() {
         let _ = gen_blockchain_with_params(100, 10, &mut thread_rng());
     }
+
+    #[test]
+    fn test_quorum_signature_verify() {
+        let params = AuthoritySigParams::setup();
+        let bc = gen_blockchain_with_params(3, 10, &mut thread_rng());
+        let prev = bc.get(1).unwrap();
+        let block = bc.get(2).unwrap();
+        let message = block.signing_bytes();
+
+        assert!(block
+            .sig
+            .verify(&message, &prev.committee, STRONG_THRESHOLD, &params));
+        // the quorum cannot exceed the total voting power
+        assert!(!block
+            .sig
+            .verify(&message, &prev.committee, TOTAL_VOTING_POWER + 1, &params));
+        // the signature is bound to the block
+        assert!(!block
+            .sig
+            .verify(&[0; 32], &prev.committee, STRONG_THRESHOLD, &params));
+
+        // the bitmap must cover the committee exactly
+        let mut sig = block.sig.clone();
+        sig.signers.pop();
+        assert!(!sig.verify(&message, &prev.committee, STRONG_THRESHOLD, &params));
+        sig.signers.extend([false, false]);
+        assert!(!sig.verify(&message, &prev.committee, STRONG_THRESHOLD, &params));
+
+        // weights that overflow when summed do not make a quorum
+        let mut committee = prev.committee.clone();
+        for ((_, weight), _) in committee
+            .signers
+            .iter_mut()
+            .zip(&block.sig.signers)
+            .filter(|(_, signed)| **signed)
+        {
+            *weight = Weight::MAX;
+        }
+        assert!(!block
+            .sig
+            .verify(&message, &committee, STRONG_THRESHOLD, &params));
+    }
+
+    #[test]
+    fn test_quorum_signature_from_partial_aggregator() {
+        let mut rng = thread_rng();
+        let params = AuthoritySigParams::setup();
+        let (blocks, signers) = ChainGenerator::new(2, 10).generate(&mut rng);
+        let (signers, committee) = (&signers[0], &blocks[0].committee);
+        let bitmap = &blocks[1].sig.signers;
+        let message = Block::genesis(committee.clone(), &Default::default()).signing_bytes();
+
+        let public_keys = committee.signers.iter().map(|(pk, _)| *pk).collect();
+        let mut aggregator = PartialAggregator::new(&message, public_keys, &params);
+        for (i, sk) in signers.iter().enumerate().filter(|(i, _)| bitmap[*i]) {
+            let share = Signature::sign(&message, sk, &params);
+            aggregator.add_share(i, &share, &params).unwrap();
+        }
+
+        let quorum_sig = QuorumSignature::from(&aggregator);
+        assert_eq!(&quorum_sig.signers, bitmap);
+        assert!(quorum_sig.verify(&message, committee, STRONG_THRESHOLD, &params));
+    }
+
+    #[test]
+    fn test_canonical_bytes_match_circuit() {
+        let bc = gen_blockchain_with_params(4, 10, &mut thread_rng());
+        let block = bc.get(2).unwrap();
+
+        let serialize = |block: &Block| -> Vec<u8> {
+            let cs = ConstraintSystem::<BlsSigField<BlsSigConfig>>::new_ref();
+            BlockVar::<BlsSigField<BlsSigConfig>>::new_constant(cs, block.clone())
+                .unwrap()
+                .serialize()
+                .unwrap()
+                .iter()
+                .map(|v| v.value().unwrap())
+                .collect()
+        };
+
+        assert_eq!(block.to_canonical_bytes(), serialize(block));
+        // the circuit verifies the signature on the block without its signature
+        let unsigned = Block {
+            sig: QuorumSignature::default(),
+            ..block.clone()
+        };
+        assert_eq!(block.signing_bytes(), serialize(&unsigned));
+        assert_ne!(block.digest(), unsigned.digest());
+        assert_eq!(bc.get(3).unwrap().prev_digest, block.digest());
+    }
 }
@@ -0,0 +1,287 @@
+use core::fmt;
+
+use ark_std::{vec, vec::Vec};
+use delegate::delegate;
+
+use super::{
+    block::{Block, Committee, QuorumSignature},
+    params::{AuthoritySigParams, BcConfig, HandoffRule, Weight, TOTAL_VOTING_POWER},
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChainError {
+    /// The block does not point to the digest of the last block.
+    InvalidPrevDigest,
+    /// The block is not in the epoch right after the last block.
+    InvalidEpoch,
+    /// The committee does not have `max_committee_size` signers sharing `TOTAL_VOTING_POWER`.
+    InvalidCommittee,
+    /// The signers do not hold `strong_threshold` of the signing committee's weight.
+    InsufficientQuorum,
+    /// The quorum signature does not verify under the signing committee.
+    InvalidSignature,
+}
+
+impl fmt::Display for ChainError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidPrevDigest => write!(f, "block does not extend the last block"),
+            Self::InvalidEpoch => write!(f, "block is not in the next epoch"),
+            Self::InvalidCommittee => write!(f, "committee is malformed"),
+            Self::InsufficientQuorum => write!(f, "signers do not reach the quorum"),
+            Self::InvalidSignature => write!(f, "quorum signature does not verify"),
+        }
+    }
+}
+
+impl ark_std::error::Error for ChainError {}
+
+/// A committee rotation chain that only ever holds verified blocks.
+///
+/// Unlike `Blockchain`, which is checked as a whole by `Blockchain::verify`, every block is
+/// checked against the last one when it is pushed, so iterating over a `Chain` always
+/// yields a valid chain starting from its genesis.
+#[derive(Debug)]
+pub struct Chain {
+    blocks: Vec<Block>,
+    params: AuthoritySigParams,
+    config: BcConfig,
+}
+
+/// Whether `committee` has the shape the circuit expects.
+fn is_valid_committee(committee: &Committee, config: &BcConfig) -> bool {
+    committee.signers.len() == config.max_committee_size
+        && committee
+            .signers
+            .iter()
+            .try_fold(0, |total: Weight, (_, weight)| total.checked_add(*weight))
+            == Some(TOTAL_VOTING_POWER)
+}
+
+impl Chain {
+    /// Start a chain from a trusted genesis block, whose signature is not checked.
+    pub fn new(genesis: Block, params: AuthoritySigParams) -> Result<Self, ChainError> {
+        Self::with_config(genesis, params, BcConfig::default())
+    }
+
+    pub fn with_config(
+        genesis: Block,
+        params: AuthoritySigParams,
+        config: BcConfig,
+    ) -> Result<Self, ChainError> {
+        if !is_valid_committee(&genesis.committee, &config) {
+            return Err(ChainError::InvalidCommittee);
+        }
+
+        Ok(Self {
+            blocks: vec![genesis],
+            params,
+            config,
+        })
+    }
+
+    /// Build a chain from a genesis block followed by its descendants, in order.
+    pub fn from_blocks(
+        genesis: Block,
+        blocks: impl IntoIterator<Item = Block>,
+        params: AuthoritySigParams,
+    ) -> Result<Self, ChainError> {
+        let mut chain = Self::new(genesis, params)?;
+        blocks
+            .into_iter()
+            .try_for_each(|block| chain.push_block(block))?;
+        Ok(chain)
+    }
+
+    delegate! {
+        to self.blocks {
+            /// Always `false`, since a chain holds at least its genesis block.
+            #[must_use] pub fn is_empty(&self) -> bool;
+
+            #[must_use] pub fn len(&self) -> usize;
+
+            pub fn get(&self, i: usize) -> Option<&Block>;
+
+            /// Iterate over the verified blocks, from genesis.
+            pub fn iter(&self) -> core::slice::Iter<'_, Block>;
+
+            #[call(into_iter)]
+            pub fn into_blocks(self) -> <Vec<Block> as IntoIterator>::IntoIter;
+        }
+    }
+
+    #[must_use]
+    pub fn last(&self) -> &Block {
+        self.blocks.last().expect("a chain has a genesis block")
+    }
+
+    /// The committee that must sign the next block.
+    #[must_use]
+    pub fn committee(&self) -> &Committee {
+        &self.last().committee
+    }
+
+    #[must_use]
+    pub const fn params(&self) -> &AuthoritySigParams {
+        &self.params
+    }
+
+    #[must_use]
+    pub const fn config(&self) -> &BcConfig {
+        &self.config
+    }
+
+    /// Append `block` if it extends the last block, and is signed by a strong quorum of
+    /// the committee handed off by the last block. The chain is unchanged on error.
+    pub fn push_block(&mut self, block: Block) -> Result<(), ChainError> {
+        verify_next_block(self.last(), &block, &self.config, &self.params)?;
+        self.blocks.push(block);
+        Ok(())
+    }
+}
+
+/// Check that `block` extends `last` as `Chain::push_block` requires, without keeping the
+/// rest of the chain around.
+///
+/// The block must be signed by the committee `config.handoff` designates.
+pub fn verify_next_block(
+    last: &Block,
+    block: &Block,
+    config: &BcConfig,
+    params: &AuthoritySigParams,
+) -> Result<(), ChainError> {
+    if block.prev_digest != last.digest() {
+        return Err(ChainError::InvalidPrevDigest);
+    }
+    if last.epoch.checked_add(1) != Some(block.epoch) {
+        return Err(ChainError::InvalidEpoch);
+    }
+    if !is_valid_committee(&block.committee, config)
+        || block.sig.signers.len() != config.max_committee_size
+    {
+        return Err(ChainError::InvalidCommittee);
+    }
+
+    let committee = match config.handoff {
+        HandoffRule::PreviousSignsNext => &last.committee,
+        HandoffRule::NextSignsSelf => &block.committee,
+    };
+    verify_quorum(
+        committee,
+        &block.sig,
+        &block.signing_bytes(),
+        config,
+        params,
+    )
+}
+
+/// Check that `sig` is a signature on `message` by signers holding `config.strong_threshold`
+/// of the weight of `committee`.
+pub(crate) fn verify_quorum(
+    committee: &Committee,
+    sig: &QuorumSignature,
+    message: &[u8],
+    config: &BcConfig,
+    params: &AuthoritySigParams,
+) -> Result<(), ChainError> {
+    // summed as `u128`, like in the circuit, so that it cannot overflow
+    let weight: u128 = committee
+        .signers
+        .iter()
+        .zip(&sig.signers)
+        .filter(|(_, signed)| **signed)
+        .map(|((_, weight), _)| u128::from(*weight))
+        .sum();
+    if weight < config.strong_threshold.into() {
+        return Err(ChainError::InsufficientQuorum);
+    }
+
+    if !sig.verify(message, committee, config.strong_threshold, params) {
+        return Err(ChainError::InvalidSignature);
+    }
+
+    Ok(())
+}
+
+impl<'a> IntoIterator for &'a Chain {
+    type Item = &'a Block;
+    type IntoIter = core::slice::Iter<'a, Block>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::thread_rng;
+
+    use crate::bc::{
+        block::{gen_blockchain_with_params, Block},
+        params::{AuthoritySigParams, BcConfig},
+    };
+
+    use super::{verify_next_block, Chain, ChainError};
+
+    #[test]
+    fn test_push_block() {
+        let params = AuthoritySigParams::setup();
+        let blocks: Vec<_> = gen_blockchain_with_params(4, 10, &mut thread_rng())
+            .into_blocks()
+            .collect();
+
+        let chain = Chain::from_blocks(blocks[0].clone(), blocks[1..].to_vec(), params).unwrap();
+        assert_eq!(chain.len(), blocks.len());
+        assert_eq!(chain.last().epoch, 3);
+
+        let mut chain = Chain::new(blocks[0].clone(), params).unwrap();
+        let push = |chain: &mut Chain, block: Block| {
+            let len = chain.len();
+            let result = chain.push_block(block);
+            assert_eq!(chain.len(), len + usize::from(result.is_ok()));
+            result
+        };
+
+        // skipping a block breaks the linkage
+        assert_eq!(
+            push(&mut chain, blocks[2].clone()),
+            Err(ChainError::InvalidPrevDigest)
+        );
+
+        let mut block = blocks[1].clone();
+        block.epoch += 1;
+        assert_eq!(push(&mut chain, block), Err(ChainError::InvalidEpoch));
+
+        // the epoch after `u64::MAX` does not wrap around to 0
+        let mut last = blocks[0].clone();
+        last.epoch = u64::MAX;
+        let mut block = blocks[1].clone();
+        block.prev_digest = last.digest();
+        block.epoch = 0;
+        assert_eq!(
+            verify_next_block(&last, &block, &BcConfig::default(), &params),
+            Err(ChainError::InvalidEpoch)
+        );
+
+        let mut block = blocks[1].clone();
+        block.committee.signers[0].1 += 1;
+        assert_eq!(push(&mut chain, block), Err(ChainError::InvalidCommittee));
+
+        let mut block = blocks[1].clone();
+        block.sig.signers = vec![false; block.sig.signers.len()];
+        assert_eq!(push(&mut chain, block), Err(ChainError::InsufficientQuorum));
+
+        // a well-formed committee that was not the one signed
+        let mut block = blocks[1].clone();
+        block.committee.signers.swap(0, 1);
+        assert_eq!(push(&mut chain, block), Err(ChainError::InvalidSignature));
+
+        for block in &blocks[1..] {
+            push(&mut chain, block.clone()).unwrap();
+        }
+        assert!(chain
+            .iter()
+            .zip(&blocks)
+            .all(|(a, b)| a.digest() == b.digest()));
+    }
+}
@@ -0,0 +1,67 @@
+#[cfg(feature = "serde")]
+use serde::Deserialize;
+use serde::Serialize;
+
+use super::{
+    block::{Block, Committee},
+    params::HASH_OUTPUT_SIZE,
+};
+
+/// A trusted `(epoch, committee)` pair that a light client can start from instead of the
+/// genesis block.
+///
+/// Only the digest of the committee is stored, so a checkpoint can be distributed
+/// out-of-band and the full committee fetched from an untrusted source.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+pub struct Checkpoint {
+    pub epoch: u64,
+    pub committee_digest: [u8; HASH_OUTPUT_SIZE],
+}
+
+impl Checkpoint {
+    #[must_use]
+    pub fn new(epoch: u64, committee: &Committee) -> Self {
+        Self {
+            epoch,
+            committee_digest: committee.digest(),
+        }
+    }
+
+    /// The checkpoint after `block`, whose committee signs the next block.
+    #[must_use]
+    pub fn from_block(block: &Block) -> Self {
+        Self::new(block.epoch, &block.committee)
+    }
+
+    /// Whether `committee` is the committee this checkpoint commits to.
+    #[must_use]
+    pub fn matches(&self, committee: &Committee) -> bool {
+        self.committee_digest == committee.digest()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::thread_rng;
+
+    use crate::bc::{block::Block, testgen::ChainGenerator};
+
+    use super::Checkpoint;
+
+    #[test]
+    fn test_checkpoint() {
+        let (blocks, _) = ChainGenerator::new(3, 4).generate(&mut thread_rng());
+
+        let genesis = Block::genesis(blocks[0].committee.clone(), &Default::default());
+        assert_eq!(
+            Checkpoint::from_block(&genesis),
+            Checkpoint::from_block(&blocks[0])
+        );
+
+        let checkpoint = Checkpoint::from_block(&blocks[2]);
+        assert_eq!(checkpoint.epoch, 2);
+        assert!(checkpoint.matches(&blocks[2].committee));
+        assert!(!checkpoint.matches(&blocks[1].committee));
+    }
+}
@@ -0,0 +1,216 @@
+use ark_std::vec::Vec;
+#[cfg(feature = "std")]
+use rand::{seq::SliceRandom, Rng};
+
+use super::{
+    block::Committee,
+    padding::PaddingPolicy,
+    params::{AuthorityPublicKey, BcConfig, Weight, TOTAL_VOTING_POWER},
+};
+
+/// An entry of the validator registry, from which committees are sampled.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Validator {
+    pub pk: AuthorityPublicKey,
+    pub stake: u64,
+}
+
+/// Split `TOTAL_VOTING_POWER` proportionally to `stakes`, giving the rounding remainder to
+/// the largest fractional parts so that the weights always sum to `TOTAL_VOTING_POWER`.
+fn voting_power(stakes: &[u64]) -> Vec<Weight> {
+    let total_stake: u128 = stakes.iter().map(|stake| u128::from(*stake)).sum();
+    assert!(total_stake > 0, "committee should have non-zero stake");
+
+    let scaled: Vec<_> = stakes
+        .iter()
+        .map(|stake| u128::from(*stake) * u128::from(TOTAL_VOTING_POWER))
+        .collect();
+    let mut weights: Vec<Weight> = scaled
+        .iter()
+        .map(|scaled| (scaled / total_stake) as Weight)
+        .collect();
+
+    let remainder = TOTAL_VOTING_POWER - weights.iter().sum::<Weight>();
+    let mut by_fraction: Vec<_> = (0..stakes.len()).collect();
+    by_fraction.sort_by_key(|&i| core::cmp::Reverse(scaled[i] % total_stake));
+    for &i in by_fraction.iter().take(remainder as usize) {
+        weights[i] += 1;
+    }
+
+    weights
+}
+
+impl Committee {
+    /// The committee of `registry[indices]`, weighted by stake and padded to
+    /// `config.max_committee_size` by signers without weight.
+    #[must_use]
+    pub fn from_registry(registry: &[Validator], indices: &[usize], config: &BcConfig) -> Self {
+        let stakes: Vec<_> = indices.iter().map(|&i| registry[i].stake).collect();
+        let signers = indices
+            .iter()
+            .zip(voting_power(&stakes))
+            .map(|(&i, weight)| (registry[i].pk, weight))
+            .collect();
+
+        PaddingPolicy::Pad
+            .committee(Self { signers }, config)
+            .unwrap_or_else(|e| panic!("committee cannot be padded: {e}"))
+    }
+
+    /// Sample `size` distinct validators of `registry`, each drawn with probability
+    /// proportional to its stake. Validators without stake are never sampled.
+    ///
+    /// Sampling by stake needs the `std` feature of `rand`, as does `Committee::rotate`.
+    #[cfg(feature = "std")]
+    pub fn sample<R: Rng>(
+        registry: &[Validator],
+        size: usize,
+        config: &BcConfig,
+        rng: &mut R,
+    ) -> Self {
+        let indices = sample_indices(registry, &[], size, rng);
+        assert_eq!(
+            indices.len(),
+            size,
+            "registry has too few staked validators"
+        );
+
+        Self::from_registry(registry, &indices, config)
+    }
+
+    /// The committee of the next epoch, where a `churn_fraction` of the members of `self`
+    /// (rounded to the nearest integer) is replaced by validators sampled from `registry`
+    /// as in `Committee::sample`.
+    ///
+    /// Members are identified by their public keys in `registry`. If there are not enough
+    /// validators left to replace the leaving ones, the committee shrinks.
+    #[cfg(feature = "std")]
+    pub fn rotate<R: Rng>(
+        &self,
+        registry: &[Validator],
+        churn_fraction: f64,
+        config: &BcConfig,
+        rng: &mut R,
+    ) -> Self {
+        assert!(
+            (0.0..=1.0).contains(&churn_fraction),
+            "churn_fraction should be in [0, 1]"
+        );
+
+        let mut members: Vec<_> = self
+            .signers
+            .iter()
+            .filter_map(|(pk, _)| {
+                registry
+                    .iter()
+                    .position(|validator| validator.pk.pub_key == pk.pub_key)
+            })
+            .collect();
+        let churn = (churn_fraction * members.len() as f64).round() as usize;
+
+        let joining = sample_indices(registry, &members, churn, rng);
+        let leaving: Vec<_> = members.choose_multiple(rng, churn).copied().collect();
+        members.retain(|i| !leaving.contains(i));
+        members.extend(joining);
+
+        Self::from_registry(registry, &members, config)
+    }
+}
+
+/// Up to `amount` indices of staked validators in `registry` not in `excluded`, sampled
+/// without replacement proportionally to stake.
+#[cfg(feature = "std")]
+fn sample_indices<R: Rng>(
+    registry: &[Validator],
+    excluded: &[usize],
+    amount: usize,
+    rng: &mut R,
+) -> Vec<usize> {
+    let candidates: Vec<_> = (0..registry.len())
+        .filter(|i| registry[*i].stake > 0 && !excluded.contains(i))
+        .collect();
+
+    candidates
+        .choose_multiple_weighted(rng, amount, |&i| registry[i].stake as f64)
+        .expect("stakes are positive")
+        .copied()
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use rand::{thread_rng, Rng};
+
+    use crate::bc::params::{
+        AuthorityPublicKey, AuthoritySecretKey, AuthoritySigParams, BcConfig, Weight,
+        TOTAL_VOTING_POWER,
+    };
+
+    use super::{voting_power, Committee, Validator};
+
+    fn registry(size: usize) -> Vec<Validator> {
+        let mut rng = thread_rng();
+        let params = AuthoritySigParams::setup();
+        (0..size)
+            .map(|_| Validator {
+                pk: AuthorityPublicKey::new(&AuthoritySecretKey::new(&mut rng), &params),
+                stake: rng.gen_range(1_000..1_000_000),
+            })
+            .collect()
+    }
+
+    fn members(committee: &Committee) -> Vec<AuthorityPublicKey> {
+        committee
+            .signers
+            .iter()
+            .filter(|(_, weight)| *weight > 0)
+            .map(|(pk, _)| *pk)
+            .collect()
+    }
+
+    #[test]
+    fn test_voting_power() {
+        assert_eq!(voting_power(&[1, 1, 1]), vec![3334, 3333, 3333]);
+        assert_eq!(voting_power(&[1, 0, 3]), vec![2500, 0, 7500]);
+        assert_eq!(
+            voting_power(&[u64::MAX, u64::MAX]).iter().sum::<Weight>(),
+            TOTAL_VOTING_POWER
+        );
+    }
+
+    #[test]
+    fn test_sample_and_rotate() {
+        let mut rng = thread_rng();
+        let config = BcConfig::default();
+        let registry = registry(100);
+
+        let committee = Committee::sample(&registry, 10, &config, &mut rng);
+        assert_eq!(committee.signers.len(), config.max_committee_size);
+        assert_eq!(
+            committee.signers.iter().map(|(_, w)| w).sum::<Weight>(),
+            TOTAL_VOTING_POWER
+        );
+        assert_eq!(members(&committee).len(), 10);
+
+        let next = committee.rotate(&registry, 0.3, &config, &mut rng);
+        assert_eq!(members(&next).len(), 10);
+        let staying = members(&next)
+            .iter()
+            .filter(|pk| {
+                members(&committee)
+                    .iter()
+                    .any(|member| member.pub_key == pk.pub_key)
+            })
+            .count();
+        assert_eq!(staying, 7);
+
+        // without churn, the committee is unchanged
+        let same = committee.rotate(&registry, 0.0, &config, &mut rng);
+        assert_eq!(same.signers.len(), committee.signers.len());
+        assert!(same
+            .signers
+            .iter()
+            .zip(&committee.signers)
+            .all(|(a, b)| a.0.pub_key == b.0.pub_key && a.1 == b.1));
+    }
+}
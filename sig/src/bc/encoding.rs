@@ -0,0 +1,401 @@
+//! The canonical bytes of blocks and sync updates, which are hashed into digests and signed.
+//!
+//! They are the encoding of `bincode::serialize` (bincode 1 with its default options):
+//! integers in little endian with a fixed width, sequences, strings and byte slices prefixed
+//! by their length as a `u64`, and enum variants by their index as a `u32`. `SerializeGadget`
+//! reproduces it inside the circuits. It is implemented here because bincode needs the
+//! standard library, and the digests must be the same without it.
+use core::fmt;
+
+use ark_std::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use serde::{ser, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EncodeError {
+    /// A sequence or a map whose length is not known before its elements.
+    UnknownLength,
+    Custom(String),
+}
+
+impl fmt::Display for EncodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownLength => write!(f, "sequences must have a known length"),
+            Self::Custom(msg) => write!(f, "{msg}"),
+        }
+    }
+}
+
+impl ser::StdError for EncodeError {}
+
+impl ser::Error for EncodeError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Self::Custom(msg.to_string())
+    }
+}
+
+/// The bytes of `value`, as `bincode::serialize` encodes it.
+pub fn to_bytes<T: Serialize + ?Sized>(value: &T) -> Result<Vec<u8>, EncodeError> {
+    let mut encoder = Encoder { bytes: Vec::new() };
+    value.serialize(&mut encoder)?;
+    Ok(encoder.bytes)
+}
+
+struct Encoder {
+    bytes: Vec<u8>,
+}
+
+impl Encoder {
+    fn length(&mut self, len: usize) {
+        self.bytes.extend((len as u64).to_le_bytes());
+    }
+
+    fn variant(&mut self, index: u32) {
+        self.bytes.extend(index.to_le_bytes());
+    }
+}
+
+impl ser::Serializer for &mut Encoder {
+    type Ok = ();
+    type Error = EncodeError;
+    type SerializeSeq = Self;
+    type SerializeTuple = Self;
+    type SerializeTupleStruct = Self;
+    type SerializeTupleVariant = Self;
+    type SerializeMap = Self;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = Self;
+
+    fn serialize_bool(self, v: bool) -> Result<(), EncodeError> {
+        self.bytes.push(u8::from(v));
+        Ok(())
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<(), EncodeError> {
+        self.bytes.extend(v.to_le_bytes());
+        Ok(())
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<(), EncodeError> {
+        self.bytes.extend(v.to_le_bytes());
+        Ok(())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<(), EncodeError> {
+        self.bytes.extend(v.to_le_bytes());
+        Ok(())
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<(), EncodeError> {
+        self.bytes.extend(v.to_le_bytes());
+        Ok(())
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<(), EncodeError> {
+        self.bytes.extend(v.to_le_bytes());
+        Ok(())
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<(), EncodeError> {
+        self.bytes.push(v);
+        Ok(())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<(), EncodeError> {
+        self.bytes.extend(v.to_le_bytes());
+        Ok(())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<(), EncodeError> {
+        self.bytes.extend(v.to_le_bytes());
+        Ok(())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<(), EncodeError> {
+        self.bytes.extend(v.to_le_bytes());
+        Ok(())
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<(), EncodeError> {
+        self.bytes.extend(v.to_le_bytes());
+        Ok(())
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<(), EncodeError> {
+        self.bytes.extend(v.to_le_bytes());
+        Ok(())
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<(), EncodeError> {
+        self.bytes.extend(v.to_le_bytes());
+        Ok(())
+    }
+
+    /// As UTF-8, without a length.
+    fn serialize_char(self, v: char) -> Result<(), EncodeError> {
+        self.bytes
+            .extend_from_slice(v.encode_utf8(&mut [0; 4]).as_bytes());
+        Ok(())
+    }
+
+    fn serialize_str(self, v: &str) -> Result<(), EncodeError> {
+        self.serialize_bytes(v.as_bytes())
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<(), EncodeError> {
+        self.length(v.len());
+        self.bytes.extend_from_slice(v);
+        Ok(())
+    }
+
+    fn serialize_none(self) -> Result<(), EncodeError> {
+        self.bytes.push(0);
+        Ok(())
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<(), EncodeError> {
+        self.bytes.push(1);
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<(), EncodeError> {
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, _: &'static str) -> Result<(), EncodeError> {
+        Ok(())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _: &'static str,
+        variant_index: u32,
+        _: &'static str,
+    ) -> Result<(), EncodeError> {
+        self.variant(variant_index);
+        Ok(())
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        value: &T,
+    ) -> Result<(), EncodeError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        variant_index: u32,
+        _: &'static str,
+        value: &T,
+    ) -> Result<(), EncodeError> {
+        self.variant(variant_index);
+        value.serialize(self)
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self, EncodeError> {
+        self.length(len.ok_or(EncodeError::UnknownLength)?);
+        Ok(self)
+    }
+
+    fn serialize_tuple(self, _: usize) -> Result<Self, EncodeError> {
+        Ok(self)
+    }
+
+    fn serialize_tuple_struct(self, _: &'static str, _: usize) -> Result<Self, EncodeError> {
+        Ok(self)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        variant_index: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self, EncodeError> {
+        self.variant(variant_index);
+        Ok(self)
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self, EncodeError> {
+        self.length(len.ok_or(EncodeError::UnknownLength)?);
+        Ok(self)
+    }
+
+    fn serialize_struct(self, _: &'static str, _: usize) -> Result<Self, EncodeError> {
+        Ok(self)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        variant_index: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self, EncodeError> {
+        self.variant(variant_index);
+        Ok(self)
+    }
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+}
+
+/// The elements of sequences, tuples and maps, and the fields of structs, are encoded one
+/// after the other.
+macro_rules! impl_compound {
+    ($($trait:ident :: $method:ident),+) => {
+        $(
+            impl ser::$trait for &mut Encoder {
+                type Ok = ();
+                type Error = EncodeError;
+
+                fn $method<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), EncodeError> {
+                    value.serialize(&mut **self)
+                }
+
+                fn end(self) -> Result<(), EncodeError> {
+                    Ok(())
+                }
+            }
+        )+
+    };
+}
+
+impl_compound!(
+    SerializeSeq::serialize_element,
+    SerializeTuple::serialize_element,
+    SerializeTupleStruct::serialize_field,
+    SerializeTupleVariant::serialize_field
+);
+
+impl ser::SerializeMap for &mut Encoder {
+    type Ok = ();
+    type Error = EncodeError;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), EncodeError> {
+        key.serialize(&mut **self)
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), EncodeError> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), EncodeError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeStruct for &mut Encoder {
+    type Ok = ();
+    type Error = EncodeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        _: &'static str,
+        value: &T,
+    ) -> Result<(), EncodeError> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), EncodeError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeStructVariant for &mut Encoder {
+    type Ok = ();
+    type Error = EncodeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        _: &'static str,
+        value: &T,
+    ) -> Result<(), EncodeError> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), EncodeError> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+
+    use rand::thread_rng;
+    use serde::Serialize;
+
+    use crate::bc::{sync::SYNC_UPDATE_DOMAIN, testgen::ChainGenerator};
+
+    use super::{to_bytes, EncodeError};
+
+    #[derive(Serialize)]
+    enum Shape {
+        Empty,
+        Tagged(u16),
+        Pair(i8, i64),
+        Named { name: String, tag: Option<char> },
+    }
+
+    #[test]
+    fn test_encoding_matches_bincode() {
+        let (blocks, _) = ChainGenerator::new(2, 4).generate(&mut thread_rng());
+        for block in &blocks {
+            assert_eq!(to_bytes(block).unwrap(), bincode::serialize(block).unwrap());
+            assert_eq!(
+                to_bytes(&block.committee).unwrap(),
+                bincode::serialize(&block.committee).unwrap()
+            );
+        }
+        let update = (SYNC_UPDATE_DOMAIN, 7u64, &blocks[1].committee);
+        assert_eq!(
+            to_bytes(&update).unwrap(),
+            bincode::serialize(&update).unwrap()
+        );
+
+        let shapes = vec![
+            Shape::Empty,
+            Shape::Tagged(0xbeef),
+            Shape::Pair(-1, i64::MIN),
+            Shape::Named {
+                name: "signer".to_string(),
+                tag: Some('λ'),
+            },
+            Shape::Named {
+                name: String::new(),
+                tag: None,
+            },
+        ];
+        assert_eq!(
+            to_bytes(&shapes).unwrap(),
+            bincode::serialize(&shapes).unwrap()
+        );
+
+        let map = BTreeMap::from([(1u8, true), (2, false)]);
+        let misc = (map, 1.5f64, 0.25f32, u128::MAX, &b"bytes"[..], ());
+        assert_eq!(to_bytes(&misc).unwrap(), bincode::serialize(&misc).unwrap());
+    }
+
+    /// Like bincode, sequences of unknown length cannot be encoded.
+    #[test]
+    fn test_unknown_length() {
+        struct Unsized;
+
+        impl Serialize for Unsized {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.collect_seq((0..3u8).filter(|i| i % 2 == 0))
+            }
+        }
+
+        assert_eq!(to_bytes(&Unsized), Err(EncodeError::UnknownLength));
+        assert!(bincode::serialize(&Unsized).is_err());
+    }
+}
@@ -0,0 +1,145 @@
+use ark_std::vec::Vec;
+#[cfg(feature = "serde")]
+use serde::Deserialize;
+use serde::Serialize;
+
+use super::{
+    block::{Block, Committee},
+    params::{AuthoritySigParams, BcConfig},
+};
+
+/// Proof that a committee misbehaved, which can be used to slash its members.
+#[derive(Serialize, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+pub enum Evidence {
+    /// Two different blocks for the same epoch, both signed by a quorum of the committee.
+    DoubleSign { block_a: Block, block_b: Block },
+}
+
+impl Evidence {
+    /// Indices in the committee of the signers of both blocks, who are the ones to slash.
+    ///
+    /// As both quorums hold at least `strong_threshold` of the weight, the offenders always
+    /// hold at least `2 * strong_threshold - TOTAL_VOTING_POWER` of it.
+    #[must_use]
+    pub fn offenders(&self) -> Vec<usize> {
+        match self {
+            Self::DoubleSign { block_a, block_b } => block_a
+                .sig
+                .signers
+                .iter()
+                .zip(&block_b.sig.signers)
+                .enumerate()
+                .filter(|(_, (a, b))| **a && **b)
+                .map(|(i, _)| i)
+                .collect(),
+        }
+    }
+}
+
+/// Check `evidence` against `committee`, the committee that signs the blocks of the
+/// evidence, i.e. the committee stored in the block of the previous epoch.
+///
+/// Blocks are compared by the bytes the committee signs, so the same block with two
+/// different aggregate signatures is not evidence of anything.
+#[must_use]
+pub fn verify_evidence(
+    evidence: &Evidence,
+    committee: &Committee,
+    config: &BcConfig,
+    params: &AuthoritySigParams,
+) -> bool {
+    match evidence {
+        Evidence::DoubleSign { block_a, block_b } => {
+            let block_a_bytes = block_a.signing_bytes();
+            let block_b_bytes = block_b.signing_bytes();
+
+            block_a.epoch == block_b.epoch
+                && block_a_bytes != block_b_bytes
+                && block_a
+                    .sig
+                    .verify(&block_a_bytes, committee, config.strong_threshold, params)
+                && block_b
+                    .sig
+                    .verify(&block_b_bytes, committee, config.strong_threshold, params)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::thread_rng;
+
+    use crate::bc::{
+        block::{Block, QuorumSignature},
+        params::{AuthorityAggregatedSignature, AuthoritySigParams, BcConfig},
+        testgen::ChainGenerator,
+    };
+
+    use super::{verify_evidence, Evidence};
+
+    #[test]
+    fn test_double_sign() {
+        let params = AuthoritySigParams::setup();
+        let config = BcConfig::default();
+        let (blocks, signers) = ChainGenerator::new(2, 10)
+            .participation(1.0)
+            .generate(&mut thread_rng());
+        let committee = &blocks[0].committee;
+
+        // the same committee signs a conflicting block for epoch 1
+        let mut block_b = Block {
+            committee: blocks[0].committee.clone(),
+            ..blocks[1].clone()
+        };
+        let bitmap = block_b.sig.signers.clone();
+        let secret_keys: Vec<_> = signers[0]
+            .iter()
+            .zip(&bitmap)
+            .filter(|(_, signed)| **signed)
+            .map(|(sk, _)| sk.clone())
+            .collect();
+        block_b.sig = QuorumSignature {
+            sig: AuthorityAggregatedSignature::aggregate_sign(
+                &block_b.signing_bytes(),
+                &secret_keys,
+                &params,
+            )
+            .unwrap(),
+            signers: bitmap,
+        };
+
+        let evidence = Evidence::DoubleSign {
+            block_a: blocks[1].clone(),
+            block_b: block_b.clone(),
+        };
+        assert!(verify_evidence(&evidence, committee, &config, &params));
+        assert_eq!(evidence.offenders(), (0..10).collect::<Vec<_>>());
+
+        // a block is not conflicting with itself
+        let evidence = Evidence::DoubleSign {
+            block_a: blocks[1].clone(),
+            block_b: blocks[1].clone(),
+        };
+        assert!(!verify_evidence(&evidence, committee, &config, &params));
+
+        // blocks of different epochs do not conflict
+        let mut block_c = block_b.clone();
+        block_c.epoch += 1;
+        let evidence = Evidence::DoubleSign {
+            block_a: blocks[1].clone(),
+            block_b: block_c,
+        };
+        assert!(!verify_evidence(&evidence, committee, &config, &params));
+
+        // both blocks must be signed by the committee
+        let evidence = Evidence::DoubleSign {
+            block_a: blocks[1].clone(),
+            block_b: Block {
+                sig: blocks[1].sig.clone(),
+                ..block_b
+            },
+        };
+        assert!(!verify_evidence(&evidence, committee, &config, &params));
+    }
+}
@@ -1,2 +1,13 @@
 pub mod block;
+pub mod chain;
+pub mod checkpoint;
+pub mod committee;
+pub mod encoding;
+pub mod evidence;
+pub mod padding;
 pub mod params;
+#[cfg(feature = "std")]
+pub mod storage;
+pub mod sync;
+#[cfg(feature = "std")]
+pub mod testgen;
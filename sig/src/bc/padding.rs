@@ -0,0 +1,170 @@
+//! The circuits are specialized for one `BcConfig`, so every committee and signer bitmap they
+//! allocate must hold exactly `max_committee_size` entries. `PaddingPolicy` brings them to
+//! that size natively, before a block is signed and allocated: padding afterwards would change
+//! the bytes the committee signed.
+use core::fmt;
+
+use ark_std::vec;
+
+use super::{
+    block::{Block, Committee, QuorumSignature},
+    params::{AuthorityPublicKey, BcConfig, Weight},
+};
+
+/// How a committee or a signer bitmap shorter than `BcConfig::max_committee_size` is brought
+/// to the fixed size of the circuits.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PaddingPolicy {
+    /// Pad with signers without weight, who never sign.
+    #[default]
+    Pad,
+    /// Accept only what already has the fixed size.
+    Exact,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PaddingError {
+    /// There are more entries than `max_committee_size`.
+    TooLarge(usize),
+    /// There are fewer entries than `max_committee_size`, which `PaddingPolicy::Exact` rejects.
+    NotPadded(usize),
+}
+
+impl fmt::Display for PaddingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooLarge(len) => write!(f, "{len} entries exceed max_committee_size"),
+            Self::NotPadded(len) => write!(f, "{len} entries are not padded to max_committee_size"),
+        }
+    }
+}
+
+impl ark_std::error::Error for PaddingError {}
+
+impl PaddingPolicy {
+    fn check(self, len: usize, config: &BcConfig) -> Result<(), PaddingError> {
+        match self {
+            _ if len > config.max_committee_size => Err(PaddingError::TooLarge(len)),
+            Self::Exact if len < config.max_committee_size => Err(PaddingError::NotPadded(len)),
+            _ => Ok(()),
+        }
+    }
+
+    /// `committee` with `config.max_committee_size` signers.
+    pub fn committee(
+        self,
+        mut committee: Committee,
+        config: &BcConfig,
+    ) -> Result<Committee, PaddingError> {
+        self.check(committee.signers.len(), config)?;
+        committee.signers.resize(
+            config.max_committee_size,
+            (AuthorityPublicKey::default(), Weight::default()),
+        );
+        Ok(committee)
+    }
+
+    /// `sig` with a bitmap of `config.max_committee_size` signers.
+    pub fn quorum_signature(
+        self,
+        mut sig: QuorumSignature,
+        config: &BcConfig,
+    ) -> Result<QuorumSignature, PaddingError> {
+        self.check(sig.signers.len(), config)?;
+        sig.signers.resize(config.max_committee_size, false);
+        Ok(sig)
+    }
+}
+
+impl Committee {
+    /// The committee of `config.max_committee_size` signers without weight, which the
+    /// circuits allocate in place of a missing committee, e.g. when preprocessing.
+    #[must_use]
+    pub fn placeholder(config: &BcConfig) -> Self {
+        Self {
+            signers: vec![
+                (AuthorityPublicKey::default(), Weight::default());
+                config.max_committee_size
+            ],
+        }
+    }
+}
+
+impl Block {
+    /// The unsigned block with a placeholder committee, which the circuits allocate in place
+    /// of a missing block. It has the fixed size of the circuits for `config`.
+    #[must_use]
+    pub fn placeholder(config: &BcConfig) -> Self {
+        Self {
+            epoch: 0,
+            prev_digest: Default::default(),
+            sig: QuorumSignature::empty(config.max_committee_size),
+            committee: Committee::placeholder(config),
+        }
+    }
+
+    /// Check that the committee and the signer bitmap of the block have the fixed size of the
+    /// circuits for `config`, which they must have been padded to before the block was signed.
+    pub fn check_padding(&self, config: &BcConfig) -> Result<(), PaddingError> {
+        PaddingPolicy::Exact.check(self.committee.signers.len(), config)?;
+        PaddingPolicy::Exact.check(self.sig.signers.len(), config)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::thread_rng;
+
+    use crate::bc::{
+        block::{Block, Committee},
+        params::BcConfig,
+        testgen::ChainGenerator,
+    };
+
+    use super::{PaddingError, PaddingPolicy};
+
+    #[test]
+    fn test_padding_policy() {
+        let config = BcConfig {
+            max_committee_size: 4,
+            ..Default::default()
+        };
+        let (blocks, _) = ChainGenerator::new(2, 3)
+            .config(config)
+            .generate(&mut thread_rng());
+        for block in &blocks {
+            block.check_padding(&config).unwrap();
+        }
+
+        let mut committee = blocks[0].committee.clone();
+        committee.signers.truncate(3);
+        assert_eq!(
+            PaddingPolicy::Exact
+                .committee(committee.clone(), &config)
+                .unwrap_err(),
+            PaddingError::NotPadded(3)
+        );
+        let padded = PaddingPolicy::Pad.committee(committee, &config).unwrap();
+        assert_eq!(padded.signers.len(), config.max_committee_size);
+        assert_eq!(padded.signers[3].1, 0);
+
+        let mut sig = blocks[1].sig.clone();
+        sig.signers.push(false);
+        assert_eq!(
+            PaddingPolicy::Pad
+                .quorum_signature(sig, &config)
+                .unwrap_err(),
+            PaddingError::TooLarge(5)
+        );
+        assert_eq!(
+            PaddingPolicy::Pad
+                .committee(Committee::default(), &config)
+                .unwrap_err(),
+            PaddingError::TooLarge(Committee::default().signers.len())
+        );
+
+        // unlike the defaults, the placeholders have the fixed size of the config
+        Block::placeholder(&config).check_padding(&config).unwrap();
+        assert!(Block::default().check_padding(&config).is_err());
+    }
+}
@@ -7,6 +7,8 @@ pub const HASH_OUTPUT_SIZE: usize = <HashFunc as OutputSizeUser>::OutputSize::US
 /* ====================Hash for Block==================== */
 
 /* ====================Sig==================== */
+use ark_std::vec::Vec;
+
 use crate::bls::{Parameters, PublicKey, SecretKey, Signature};
 use crate::params::BlsSigConfig;
 
@@ -21,6 +23,43 @@ pub type Weight = u64;
 pub type Signers = Vec<AuthoritySecretKey>;
 
 pub const TOTAL_VOTING_POWER: u64 = 10_000;
+/// Default of `BcConfig::strong_threshold`.
 pub const STRONG_THRESHOLD: u64 = 6_667;
+/// Default of `BcConfig::max_committee_size`.
 pub const MAX_COMMITTEE_SIZE: usize = 25;
+
+/// Which committee signs the block that hands off to the next committee.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum HandoffRule {
+    /// The committee of the previous block signs the block carrying the next committee, so
+    /// every committee is authorised by the one before it.
+    #[default]
+    PreviousSignsNext,
+    /// The block carrying the next committee is signed by that committee, which attests to
+    /// taking over. The previous committee is not involved, so the committees must be
+    /// authorised by other means, e.g. a stake registry.
+    NextSignsSelf,
+}
+
+/// Committee parameters of a chain, shared by the native checks and the circuit.
+///
+/// The circuit is specialized for one `BcConfig`, so every committee and signer bitmap of
+/// the chain must be padded to `max_committee_size`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BcConfig {
+    pub max_committee_size: usize,
+    /// The weight the signers of a block must hold.
+    pub strong_threshold: Weight,
+    pub handoff: HandoffRule,
+}
+
+impl Default for BcConfig {
+    fn default() -> Self {
+        Self {
+            max_committee_size: MAX_COMMITTEE_SIZE,
+            strong_threshold: STRONG_THRESHOLD,
+            handoff: HandoffRule::default(),
+        }
+    }
+}
 /* ====================Committee==================== */
@@ -0,0 +1,188 @@
+use core::{fmt, ops::Bound, ops::RangeBounds};
+use std::collections::HashMap;
+
+use super::{block::Block, params::HASH_OUTPUT_SIZE};
+
+#[cfg(feature = "sled")]
+mod sled_store;
+#[cfg(feature = "sled")]
+pub use sled_store::*;
+
+#[derive(Debug)]
+pub enum StorageError {
+    /// The block is not in the epoch right after the last stored block.
+    InvalidEpoch,
+    /// A stored block cannot be encoded or decoded.
+    Encoding(bincode::Error),
+    #[cfg(feature = "sled")]
+    Backend(sled::Error),
+}
+
+impl fmt::Display for StorageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidEpoch => write!(f, "block is not in the next epoch"),
+            Self::Encoding(e) => write!(f, "cannot encode or decode block: {e}"),
+            #[cfg(feature = "sled")]
+            Self::Backend(e) => write!(f, "storage backend error: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for StorageError {}
+
+impl From<bincode::Error> for StorageError {
+    fn from(e: bincode::Error) -> Self {
+        Self::Encoding(e)
+    }
+}
+
+/// An append-only store of consecutive blocks, indexed by epoch and by digest.
+///
+/// Blocks are not verified, which is the job of `Chain`; the store only guarantees that
+/// epochs have no gaps, so that a range of epochs can be streamed to the prover.
+pub trait BlockStore {
+    fn get_by_epoch(&self, epoch: u64) -> Result<Option<Block>, StorageError>;
+
+    /// Look up a block by `Block::digest`, e.g. the parent of a block by its `prev_digest`.
+    fn get_by_digest(&self, digest: &[u8; HASH_OUTPUT_SIZE])
+        -> Result<Option<Block>, StorageError>;
+
+    /// Store `block`, which must be in the epoch after the last stored block unless the
+    /// store is empty.
+    fn append(&mut self, block: Block) -> Result<(), StorageError>;
+
+    /// The stored blocks in `epochs`, in order.
+    fn range(
+        &self,
+        epochs: impl RangeBounds<u64>,
+    ) -> Box<dyn Iterator<Item = Result<Block, StorageError>> + '_>;
+
+    /// The epoch of the last stored block.
+    fn last_epoch(&self) -> Option<u64>;
+}
+
+/// `epochs` as a half-open range of `u64`.
+fn epoch_range(epochs: &impl RangeBounds<u64>) -> (u64, Option<u64>) {
+    let start = match epochs.start_bound() {
+        Bound::Included(start) => *start,
+        Bound::Excluded(start) => start.saturating_add(1),
+        Bound::Unbounded => 0,
+    };
+    let end = match epochs.end_bound() {
+        Bound::Included(end) => end.checked_add(1),
+        Bound::Excluded(end) => Some(*end),
+        Bound::Unbounded => None,
+    };
+    (start, end)
+}
+
+/// A `BlockStore` keeping every block in memory.
+#[derive(Debug, Default)]
+pub struct MemoryBlockStore {
+    blocks: Vec<Block>,
+    by_digest: HashMap<[u8; HASH_OUTPUT_SIZE], usize>,
+}
+
+impl MemoryBlockStore {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn index(&self, epoch: u64) -> Option<usize> {
+        let first = self.blocks.first()?.epoch;
+        usize::try_from(epoch.checked_sub(first)?).ok()
+    }
+}
+
+impl BlockStore for MemoryBlockStore {
+    fn get_by_epoch(&self, epoch: u64) -> Result<Option<Block>, StorageError> {
+        Ok(self.index(epoch).and_then(|i| self.blocks.get(i)).cloned())
+    }
+
+    fn get_by_digest(
+        &self,
+        digest: &[u8; HASH_OUTPUT_SIZE],
+    ) -> Result<Option<Block>, StorageError> {
+        Ok(self.by_digest.get(digest).map(|&i| self.blocks[i].clone()))
+    }
+
+    fn append(&mut self, block: Block) -> Result<(), StorageError> {
+        if self
+            .last_epoch()
+            .is_some_and(|last| last.checked_add(1) != Some(block.epoch))
+        {
+            return Err(StorageError::InvalidEpoch);
+        }
+
+        self.by_digest.insert(block.digest(), self.blocks.len());
+        self.blocks.push(block);
+        Ok(())
+    }
+
+    fn range(
+        &self,
+        epochs: impl RangeBounds<u64>,
+    ) -> Box<dyn Iterator<Item = Result<Block, StorageError>> + '_> {
+        let (start, end) = epoch_range(&epochs);
+        Box::new(
+            self.blocks
+                .iter()
+                .skip_while(move |block| block.epoch < start)
+                .take_while(move |block| end.map_or(true, |end| block.epoch < end))
+                .cloned()
+                .map(Ok),
+        )
+    }
+
+    fn last_epoch(&self) -> Option<u64> {
+        self.blocks.last().map(|block| block.epoch)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::thread_rng;
+
+    use crate::bc::{block::Block, testgen::ChainGenerator};
+
+    use super::{BlockStore, MemoryBlockStore, StorageError};
+
+    fn epochs(blocks: impl Iterator<Item = Result<Block, StorageError>>) -> Vec<u64> {
+        blocks.map(|block| block.unwrap().epoch).collect()
+    }
+
+    /// Exercise any `BlockStore`, starting from an empty one.
+    pub(super) fn check_block_store(store: &mut impl BlockStore) {
+        let (blocks, _) = ChainGenerator::new(5, 4).generate(&mut thread_rng());
+
+        assert_eq!(store.last_epoch(), None);
+        for block in &blocks {
+            store.append(block.clone()).unwrap();
+        }
+        assert_eq!(store.last_epoch(), Some(4));
+        assert!(matches!(
+            store.append(blocks[2].clone()),
+            Err(StorageError::InvalidEpoch)
+        ));
+
+        let block = store.get_by_epoch(3).unwrap().unwrap();
+        assert_eq!(block.digest(), blocks[3].digest());
+        assert!(store.get_by_epoch(5).unwrap().is_none());
+
+        let parent = store.get_by_digest(&block.prev_digest).unwrap().unwrap();
+        assert_eq!(parent.epoch, 2);
+        assert!(store.get_by_digest(&[0; 32]).unwrap().is_none());
+
+        assert_eq!(epochs(store.range(1..3)), vec![1, 2]);
+        assert_eq!(epochs(store.range(3..)), vec![3, 4]);
+        assert_eq!(epochs(store.range(..=0)), vec![0]);
+        assert_eq!(store.range(5..).count(), 0);
+    }
+
+    #[test]
+    fn test_memory_block_store() {
+        check_block_store(&mut MemoryBlockStore::new());
+    }
+}
@@ -0,0 +1,132 @@
+use core::ops::{Bound, RangeBounds};
+use std::path::Path;
+
+use super::{epoch_range, BlockStore, StorageError};
+use crate::bc::{block::Block, params::HASH_OUTPUT_SIZE};
+
+impl From<sled::Error> for StorageError {
+    fn from(e: sled::Error) -> Self {
+        Self::Backend(e)
+    }
+}
+
+const EPOCH_PREFIX: u8 = b'e';
+const DIGEST_PREFIX: u8 = b'd';
+
+/// Blocks are keyed by their big-endian epoch, so that the order of the keys is the
+/// order of the epochs.
+fn epoch_key(epoch: u64) -> Vec<u8> {
+    [[EPOCH_PREFIX].as_slice(), &epoch.to_be_bytes()].concat()
+}
+
+fn digest_key(digest: &[u8; HASH_OUTPUT_SIZE]) -> Vec<u8> {
+    [[DIGEST_PREFIX].as_slice(), digest].concat()
+}
+
+/// A `BlockStore` on disk, backed by `sled`.
+///
+/// Blocks are stored in their canonical (`bincode`) encoding under their epoch, and the
+/// digest index maps to the epoch. Both are written in one atomic batch.
+#[derive(Debug)]
+pub struct SledBlockStore {
+    db: sled::Db,
+    last_epoch: Option<u64>,
+}
+
+impl SledBlockStore {
+    /// Open the store at `path`, creating it if it does not exist.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, StorageError> {
+        Self::from_db(sled::open(path)?)
+    }
+
+    /// A store in a temporary directory, removed when the store is dropped.
+    pub fn temporary() -> Result<Self, StorageError> {
+        Self::from_db(sled::Config::new().temporary(true).open()?)
+    }
+
+    fn from_db(db: sled::Db) -> Result<Self, StorageError> {
+        let last_epoch = db
+            .range(epoch_key(0)..=epoch_key(u64::MAX))
+            .next_back()
+            .transpose()?
+            .map(|(_, value)| bincode::deserialize::<Block>(&value).map(|block| block.epoch))
+            .transpose()?;
+
+        Ok(Self { db, last_epoch })
+    }
+
+    /// Flush the appended blocks to disk.
+    pub fn flush(&self) -> Result<(), StorageError> {
+        self.db.flush()?;
+        Ok(())
+    }
+}
+
+impl BlockStore for SledBlockStore {
+    fn get_by_epoch(&self, epoch: u64) -> Result<Option<Block>, StorageError> {
+        self.db
+            .get(epoch_key(epoch))?
+            .map(|value| bincode::deserialize(&value).map_err(StorageError::from))
+            .transpose()
+    }
+
+    fn get_by_digest(
+        &self,
+        digest: &[u8; HASH_OUTPUT_SIZE],
+    ) -> Result<Option<Block>, StorageError> {
+        match self.db.get(digest_key(digest))? {
+            Some(epoch) => self.get_by_epoch(bincode::deserialize(&epoch)?),
+            None => Ok(None),
+        }
+    }
+
+    fn append(&mut self, block: Block) -> Result<(), StorageError> {
+        if self
+            .last_epoch
+            .is_some_and(|last| last.checked_add(1) != Some(block.epoch))
+        {
+            return Err(StorageError::InvalidEpoch);
+        }
+
+        let mut batch = sled::Batch::default();
+        batch.insert(epoch_key(block.epoch), block.to_canonical_bytes());
+        batch.insert(
+            digest_key(&block.digest()),
+            bincode::serialize(&block.epoch)?,
+        );
+        self.db.apply_batch(batch)?;
+
+        self.last_epoch = Some(block.epoch);
+        Ok(())
+    }
+
+    fn range(
+        &self,
+        epochs: impl RangeBounds<u64>,
+    ) -> Box<dyn Iterator<Item = Result<Block, StorageError>> + '_> {
+        let (start, end) = epoch_range(&epochs);
+        let end = end.map_or(Bound::Included(epoch_key(u64::MAX)), |end| {
+            Bound::Excluded(epoch_key(end))
+        });
+
+        Box::new(
+            self.db
+                .range((Bound::Included(epoch_key(start)), end))
+                .map(|entry| Ok(bincode::deserialize(&entry?.1)?)),
+        )
+    }
+
+    fn last_epoch(&self) -> Option<u64> {
+        self.last_epoch
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{super::test::check_block_store, SledBlockStore};
+
+    #[test]
+    fn test_sled_block_store() {
+        check_block_store(&mut SledBlockStore::temporary().unwrap());
+    }
+}
@@ -0,0 +1,162 @@
+//! Sync updates, which let a light client skip epochs, as Ethereum's sync committee updates
+//! skip to the next period.
+//!
+//! Instead of a block per epoch, the committee of some epoch attests to the committee of a
+//! later epoch, at most `max_gap` epochs ahead.
+
+use ark_std::vec::Vec;
+use serde::Serialize;
+
+#[cfg(feature = "serde")]
+use serde::Deserialize;
+
+use super::{
+    block::{Committee, QuorumSignature},
+    chain::{verify_quorum, ChainError},
+    encoding::to_bytes,
+    params::{AuthoritySigParams, BcConfig, Signers},
+};
+
+/// Prefixes the message signed for a sync update, so that it is never the signing bytes of
+/// a block, which start with the epoch.
+pub const SYNC_UPDATE_DOMAIN: [u8; 8] = *b"BCSYNCv1";
+
+#[derive(Serialize, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+pub struct SyncUpdate {
+    /// The epoch skipped to.
+    pub epoch: u64,
+    /// The committee of `epoch`.
+    pub committee: Committee,
+    /// The quorum signature by the committee of the epoch skipped from.
+    pub sig: QuorumSignature,
+}
+
+impl SyncUpdate {
+    /// Sign the hand-off to `committee` at `epoch` with the members of `signers`, the
+    /// committee of the current epoch, selected by `bitmap`.
+    pub fn new(
+        epoch: u64,
+        committee: Committee,
+        signers: &Signers,
+        bitmap: &[bool],
+        params: &AuthoritySigParams,
+    ) -> Self {
+        assert!(!bitmap.is_empty(), "sync update must be signed");
+
+        let sig = QuorumSignature::sign(&Self::message(epoch, &committee), signers, bitmap, params);
+        Self {
+            epoch,
+            committee,
+            sig,
+        }
+    }
+
+    /// The unsigned update to epoch 0 with a placeholder committee, which has the fixed size
+    /// of the circuits for `config`.
+    #[must_use]
+    pub fn placeholder(config: &BcConfig) -> Self {
+        Self {
+            epoch: 0,
+            committee: Committee::placeholder(config),
+            sig: QuorumSignature::empty(config.max_committee_size),
+        }
+    }
+
+    /// The message signed by the committee: the domain, the epoch and the committee, which
+    /// `SyncUpdateVar::message` reproduces byte for byte inside the circuit.
+    #[must_use]
+    pub fn signing_bytes(&self) -> Vec<u8> {
+        Self::message(self.epoch, &self.committee)
+    }
+
+    fn message(epoch: u64, committee: &Committee) -> Vec<u8> {
+        to_bytes(&(SYNC_UPDATE_DOMAIN, epoch, committee))
+            .expect("serializing a sync update should succeed")
+    }
+}
+
+/// Check that `update` is signed by a quorum of `committee`, the committee of `epoch`, and
+/// skips ahead by at least 1 and at most `max_gap` epochs.
+///
+/// Sync updates are always signed by the committee skipped from, whatever the hand-off rule
+/// of `config`.
+pub fn verify_sync_update(
+    epoch: u64,
+    committee: &Committee,
+    update: &SyncUpdate,
+    max_gap: u64,
+    config: &BcConfig,
+    params: &AuthoritySigParams,
+) -> Result<(), ChainError> {
+    if update.epoch <= epoch || update.epoch - epoch > max_gap {
+        return Err(ChainError::InvalidEpoch);
+    }
+    if update.committee.signers.len() != config.max_committee_size
+        || update.sig.signers.len() != config.max_committee_size
+    {
+        return Err(ChainError::InvalidCommittee);
+    }
+
+    verify_quorum(
+        committee,
+        &update.sig,
+        &update.signing_bytes(),
+        config,
+        params,
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use rand::thread_rng;
+
+    use crate::bc::{
+        chain::ChainError,
+        params::{AuthoritySigParams, BcConfig},
+        testgen::ChainGenerator,
+    };
+
+    use super::{verify_sync_update, SyncUpdate};
+
+    #[test]
+    fn test_sync_update() {
+        let params = AuthoritySigParams::setup();
+        let config = BcConfig::default();
+        let (blocks, signers) = ChainGenerator::new(4, 6).generate(&mut thread_rng());
+        let bitmap = blocks[1].sig.signers.clone();
+
+        let update = SyncUpdate::new(
+            3,
+            blocks[3].committee.clone(),
+            &signers[0],
+            &bitmap,
+            &params,
+        );
+        let verify = |update: &SyncUpdate, max_gap| {
+            verify_sync_update(0, &blocks[0].committee, update, max_gap, &config, &params)
+        };
+        assert_eq!(verify(&update, 3), Ok(()));
+        assert_eq!(verify(&update, 2), Err(ChainError::InvalidEpoch));
+
+        // the epoch must advance
+        let mut stale = update.clone();
+        stale.epoch = 0;
+        assert_eq!(verify(&stale, 3), Err(ChainError::InvalidEpoch));
+
+        // the signature covers the epoch
+        let mut forged = update.clone();
+        forged.epoch = 2;
+        assert_eq!(verify(&forged, 3), Err(ChainError::InvalidSignature));
+
+        // signed by a committee that is not the current one
+        let update = SyncUpdate::new(
+            3,
+            blocks[3].committee.clone(),
+            &signers[1],
+            &bitmap,
+            &params,
+        );
+        assert_eq!(verify(&update, 3), Err(ChainError::InvalidSignature));
+    }
+}
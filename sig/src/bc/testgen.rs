@@ -0,0 +1,309 @@
+use rand::{seq::SliceRandom, Rng};
+
+use super::{
+    block::{Block, Committee},
+    chain::Chain,
+    params::{
+        AuthorityPublicKey, AuthoritySecretKey, AuthoritySigParams, BcConfig, HandoffRule, Signers,
+        Weight, TOTAL_VOTING_POWER,
+    },
+};
+
+/// How `TOTAL_VOTING_POWER` is split among the effective members of a committee.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum StakeDistribution {
+    /// Every member gets the same weight, up to rounding.
+    Equal,
+    /// Each member gets a random share of the weight left by the previous ones, so a few
+    /// members usually hold most of the stake.
+    #[default]
+    Random,
+}
+
+/// Generate chains that `Chain` (and so the folding circuit) accepts.
+///
+/// Every committee has `max_committee_size` members, but only the first `committee_size`
+/// of them, the effective members, have non-zero weights.
+#[derive(Clone, Debug)]
+pub struct ChainGenerator {
+    config: BcConfig,
+    num_epochs: usize,
+    committee_size: usize,
+    stake: StakeDistribution,
+    participation: f64,
+    churn: usize,
+}
+
+impl ChainGenerator {
+    /// A generator where the whole committee rotates every epoch, and signers are added
+    /// at random until they reach the strong threshold of the default `BcConfig`.
+    #[must_use]
+    pub fn new(num_epochs: usize, committee_size: usize) -> Self {
+        assert!(num_epochs > 0, "num_epochs should > 0");
+        assert!(committee_size > 0, "committee_size should > 0");
+
+        Self {
+            config: BcConfig::default(),
+            num_epochs,
+            committee_size,
+            stake: StakeDistribution::default(),
+            participation: 0.0,
+            churn: committee_size,
+        }
+    }
+
+    #[must_use]
+    pub const fn config(mut self, config: BcConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    #[must_use]
+    pub const fn stake(mut self, stake: StakeDistribution) -> Self {
+        self.stake = stake;
+        self
+    }
+
+    /// The fraction of the effective members that sign each block. More members are
+    /// added if they do not reach the strong threshold.
+    #[must_use]
+    pub fn participation(mut self, participation: f64) -> Self {
+        assert!(
+            (0.0..=1.0).contains(&participation),
+            "participation should be in [0, 1]"
+        );
+        self.participation = participation;
+        self
+    }
+
+    /// The number of effective members replaced by fresh keys each epoch. The weights
+    /// are drawn again every epoch, even for the members that stay.
+    #[must_use]
+    pub fn churn(mut self, churn: usize) -> Self {
+        assert!(
+            churn <= self.committee_size,
+            "churn should <= committee_size {}",
+            self.committee_size
+        );
+        self.churn = churn;
+        self
+    }
+
+    /// Generate `num_epochs` blocks starting from a genesis block, together with the
+    /// secret keys of the committee stored in each block.
+    pub fn generate<R: Rng>(&self, rng: &mut R) -> (Vec<Block>, Vec<Signers>) {
+        assert!(
+            self.committee_size <= self.config.max_committee_size,
+            "committee_size should <= max_committee_size {}",
+            self.config.max_committee_size
+        );
+        let params = AuthoritySigParams::setup();
+
+        let (mut signers, committee) = self.committee(None, &params, rng);
+        let mut chain =
+            Chain::with_config(Block::genesis(committee, &self.config), params, self.config)
+                .expect("genesis committee is valid");
+        let mut all_signers = Vec::with_capacity(self.num_epochs);
+
+        for _ in 1..self.num_epochs {
+            let (next_signers, committee) = self.committee(Some(&signers), &params, rng);
+
+            let block = match self.config.handoff {
+                HandoffRule::PreviousSignsNext => {
+                    let bitmap = self.select_signers(chain.committee(), rng);
+                    Block::new(chain.last(), committee, &signers, &bitmap, &params)
+                }
+                HandoffRule::NextSignsSelf => {
+                    let bitmap = self.select_signers(&committee, rng);
+                    Block::new(chain.last(), committee, &next_signers, &bitmap, &params)
+                }
+            };
+            chain
+                .push_block(block)
+                .expect("generated block should extend the chain");
+
+            all_signers.push(std::mem::replace(&mut signers, next_signers));
+        }
+        all_signers.push(signers);
+
+        (chain.into_blocks().collect(), all_signers)
+    }
+
+    fn weights<R: Rng>(&self, rng: &mut R) -> Vec<Weight> {
+        let size = self.committee_size as Weight;
+        let mut weights = match self.stake {
+            StakeDistribution::Equal => (0..size)
+                .map(|i| TOTAL_VOTING_POWER / size + Weight::from(i < TOTAL_VOTING_POWER % size))
+                .collect(),
+            StakeDistribution::Random => {
+                let mut weights = Vec::with_capacity(self.committee_size);
+                let mut remaining_weight = TOTAL_VOTING_POWER;
+                for _ in 0..self.committee_size - 1 {
+                    let weight = rng.gen_range(0..=remaining_weight);
+                    weights.push(weight);
+                    remaining_weight -= weight;
+                }
+                weights.push(remaining_weight);
+                weights
+            }
+        };
+
+        // fill to `max_committee_size`
+        weights.resize(self.config.max_committee_size, 0);
+        weights
+    }
+
+    /// A committee where all but `churn` effective members of `prev` stay.
+    fn committee<R: Rng>(
+        &self,
+        prev: Option<&Signers>,
+        params: &AuthoritySigParams,
+        rng: &mut R,
+    ) -> (Signers, Committee) {
+        // derive the new keys from a per-epoch seed (EIP-2333) so that a committee
+        // can be reproduced from its seed alone
+        let mut seed = [0u8; 32];
+        rng.fill_bytes(&mut seed);
+        let master_sk = AuthoritySecretKey::derive_master(&seed).expect("seed is 32 bytes long");
+        let mut csk = (0..self.config.max_committee_size as u32)
+            .map(|index| master_sk.derive_child(index))
+            .collect::<Vec<_>>();
+
+        if let Some(prev) = prev {
+            let mut staying: Vec<_> = (0..self.committee_size).collect();
+            staying.shuffle(rng);
+            for &i in &staying[self.churn..] {
+                csk[i] = prev[i].clone();
+            }
+        }
+
+        let committee = csk
+            .iter()
+            .zip(self.weights(rng))
+            .map(|(sk, weight)| (AuthorityPublicKey::new(sk, params), weight))
+            .collect();
+
+        (csk, Committee { signers: committee })
+    }
+
+    /// A bitmap of signers holding at least the strong threshold of the weight of `committee`.
+    fn select_signers<R: Rng>(&self, committee: &Committee, rng: &mut R) -> Vec<bool> {
+        let mut indices: Vec<_> = (0..self.committee_size).collect();
+        indices.shuffle(rng);
+        let min_signers = (self.participation * self.committee_size as f64).ceil() as usize;
+
+        let mut bitmap = vec![false; self.config.max_committee_size];
+        let mut total_weight: Weight = 0;
+        for (n, i) in indices.into_iter().enumerate() {
+            if n >= min_signers && total_weight >= self.config.strong_threshold {
+                break;
+            }
+            bitmap[i] = true;
+            total_weight += committee.signers[i].1;
+        }
+
+        bitmap
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::thread_rng;
+
+    use crate::bc::{
+        chain::{Chain, ChainError},
+        params::{
+            AuthorityPublicKey, AuthoritySigParams, BcConfig, HandoffRule, MAX_COMMITTEE_SIZE,
+        },
+    };
+
+    use super::{ChainGenerator, StakeDistribution};
+
+    #[test]
+    fn test_chain_generator() {
+        let mut rng = thread_rng();
+        let params = AuthoritySigParams::setup();
+
+        let (blocks, signers) = ChainGenerator::new(4, 6)
+            .stake(StakeDistribution::Equal)
+            .participation(1.0)
+            .churn(2)
+            .generate(&mut rng);
+        assert_eq!(blocks.len(), 4);
+        assert_eq!(signers.len(), 4);
+
+        // the secret keys belong to the committee of their block
+        for (block, signers) in blocks.iter().zip(&signers) {
+            assert_eq!(signers.len(), MAX_COMMITTEE_SIZE);
+            assert!(block
+                .committee
+                .signers
+                .iter()
+                .zip(signers)
+                .all(|((pk, _), sk)| pk.pub_key == AuthorityPublicKey::new(sk, &params).pub_key));
+        }
+
+        for (prev, block) in blocks.iter().zip(&blocks[1..]) {
+            // everyone signs
+            assert_eq!(block.sig.signers.iter().filter(|s| **s).count(), 6);
+            // exactly `churn` effective members are replaced
+            let replaced = (0..6)
+                .filter(|&i| {
+                    prev.committee.signers[i].0.pub_key != block.committee.signers[i].0.pub_key
+                })
+                .count();
+            assert_eq!(replaced, 2);
+            assert!(block.committee.signers[..6]
+                .iter()
+                .all(|(_, weight)| [1666, 1667].contains(weight)));
+        }
+
+        let mut blocks = blocks.into_iter();
+        assert!(Chain::from_blocks(blocks.next().unwrap(), blocks, params).is_ok());
+    }
+
+    #[test]
+    fn test_chain_generator_with_config() {
+        let params = AuthoritySigParams::setup();
+        let config = BcConfig {
+            max_committee_size: 8,
+            strong_threshold: 5_000,
+            ..Default::default()
+        };
+
+        let (blocks, _) = ChainGenerator::new(3, 4)
+            .config(config)
+            .generate(&mut thread_rng());
+        assert!(blocks
+            .iter()
+            .all(|block| block.committee.signers.len() == 8 && block.sig.signers.len() == 8));
+
+        let mut chain = Chain::with_config(blocks[0].clone(), params, config).unwrap();
+        for block in &blocks[1..] {
+            chain.push_block(block.clone()).unwrap();
+        }
+        assert_eq!(
+            Chain::new(blocks[0].clone(), params).unwrap_err(),
+            ChainError::InvalidCommittee
+        );
+    }
+
+    #[test]
+    fn test_chain_generator_next_signs_self() {
+        let params = AuthoritySigParams::setup();
+        let config = BcConfig {
+            handoff: HandoffRule::NextSignsSelf,
+            ..Default::default()
+        };
+
+        let (blocks, _) = ChainGenerator::new(3, 4)
+            .config(config)
+            .generate(&mut thread_rng());
+        assert!(Chain::from_blocks(blocks[0].clone(), blocks[1..].to_vec(), params).is_err());
+
+        let mut chain = Chain::with_config(blocks[0].clone(), params, config).unwrap();
+        for block in &blocks[1..] {
+            chain.push_block(block.clone()).unwrap();
+        }
+    }
+}
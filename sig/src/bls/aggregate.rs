@@ -0,0 +1,280 @@
+use ark_ec::bls12::Bls12Config;
+use ark_ff::AdditiveGroup;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::{cfg_chunks, vec::Vec};
+use derivative::Derivative;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use super::{
+    MinPk, Parameters, PublicKey, PublicKeyGroup, Signature, SignatureGroup, SignatureVariant,
+    SignatureVariantHasher,
+};
+
+const AGGREGATE_CHUNK_SIZE: usize = 64;
+
+/// The sum of a set of public keys.
+///
+/// Keeping the aggregate around lets callers pay for the aggregation once and
+/// update it incrementally when the signer set changes, instead of folding all
+/// public keys again on every `aggregate_verify`.
+#[derive(Derivative, CanonicalSerialize, CanonicalDeserialize)]
+#[derivative(
+    Clone(bound = ""),
+    Copy(bound = ""),
+    Debug(bound = ""),
+    Default(bound = "")
+)]
+pub struct AggregatePublicKey<
+    SigCurveConfig: Bls12Config,
+    V: SignatureVariant<SigCurveConfig> = MinPk,
+> {
+    pub aggregate: PublicKeyGroup<SigCurveConfig, V>,
+}
+
+impl<SigCurveConfig: Bls12Config, V: SignatureVariant<SigCurveConfig>>
+    AggregatePublicKey<SigCurveConfig, V>
+{
+    /// Returns `None` if `public_keys` is empty.
+    #[must_use]
+    pub fn aggregate(public_keys: &[PublicKey<SigCurveConfig, V>]) -> Option<Self> {
+        if public_keys.is_empty() {
+            return None;
+        }
+
+        // sum fixed-size chunks (in parallel if enabled) and then the partial sums in order,
+        // so the additions performed do not depend on the number of threads
+        let partial_sums: Vec<_> = cfg_chunks!(public_keys, AGGREGATE_CHUNK_SIZE)
+            .map(|chunk| {
+                chunk
+                    .iter()
+                    .fold(PublicKeyGroup::<SigCurveConfig, V>::ZERO, |acc, pk| {
+                        acc + pk.pub_key
+                    })
+            })
+            .collect();
+
+        let aggregate = partial_sums
+            .into_iter()
+            .fold(PublicKeyGroup::<SigCurveConfig, V>::ZERO, |acc, sum| {
+                acc + sum
+            });
+        Some(Self { aggregate })
+    }
+
+    pub fn add(&mut self, public_key: &PublicKey<SigCurveConfig, V>) {
+        self.aggregate += public_key.pub_key;
+    }
+
+    /// Remove a public key that was previously added.
+    ///
+    /// Removing a key that is not part of the aggregate silently yields a wrong aggregate.
+    pub fn remove(&mut self, public_key: &PublicKey<SigCurveConfig, V>) {
+        self.aggregate -= public_key.pub_key;
+    }
+
+    #[must_use]
+    pub fn to_public_key(&self) -> PublicKey<SigCurveConfig, V> {
+        PublicKey::from_projective(self.aggregate)
+    }
+}
+
+/// A running sum of signatures.
+///
+/// Signatures can be pushed in any order as they arrive, and popped again if they turn
+/// out to be bad, without re-folding all the signatures received so far.
+#[derive(Derivative, CanonicalSerialize, CanonicalDeserialize)]
+#[derivative(
+    Clone(bound = ""),
+    Copy(bound = ""),
+    Debug(bound = ""),
+    Default(bound = "")
+)]
+pub struct AggregateSignature<
+    SigCurveConfig: Bls12Config,
+    V: SignatureVariant<SigCurveConfig> = MinPk,
+> {
+    pub aggregate: SignatureGroup<SigCurveConfig, V>,
+    /// number of signatures currently in the aggregate
+    pub count: usize,
+}
+
+impl<SigCurveConfig: Bls12Config, V: SignatureVariant<SigCurveConfig>>
+    AggregateSignature<SigCurveConfig, V>
+{
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, signature: &Signature<SigCurveConfig, V>) {
+        self.aggregate += signature.signature;
+        self.count += 1;
+    }
+
+    /// Remove a signature that was previously pushed.
+    ///
+    /// Removing a signature that is not part of the aggregate silently yields a wrong aggregate.
+    pub fn pop(&mut self, signature: &Signature<SigCurveConfig, V>) {
+        self.aggregate -= signature.signature;
+        self.count = self.count.saturating_sub(1);
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Returns `None` if no signature is in the aggregate.
+    #[must_use]
+    pub fn finalize(&self) -> Option<Signature<SigCurveConfig, V>> {
+        (!self.is_empty()).then_some(Signature {
+            signature: self.aggregate,
+        })
+    }
+}
+
+impl<SigCurveConfig: Bls12Config, V: SignatureVariant<SigCurveConfig>>
+    FromIterator<Signature<SigCurveConfig, V>> for AggregateSignature<SigCurveConfig, V>
+{
+    fn from_iter<I: IntoIterator<Item = Signature<SigCurveConfig, V>>>(signatures: I) -> Self {
+        let mut aggregate = Self::new();
+        signatures
+            .into_iter()
+            .for_each(|signature| aggregate.push(&signature));
+        aggregate
+    }
+}
+
+impl<SigCurveConfig: Bls12Config, V: SignatureVariantHasher<SigCurveConfig>>
+    Signature<SigCurveConfig, V>
+{
+    /// Verify an aggregate signature on `message` against a precomputed aggregate public key.
+    #[must_use]
+    pub fn verify_aggregate(
+        message: &[u8],
+        aggregate_signature: &Self,
+        aggregate_public_key: &AggregatePublicKey<SigCurveConfig, V>,
+        params: &Parameters<SigCurveConfig>,
+    ) -> bool {
+        Self::verify(
+            message,
+            aggregate_signature,
+            &aggregate_public_key.to_public_key(),
+            params,
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::bls::get_aggregate_bls_instance;
+
+    use super::*;
+
+    type SigCurveConfig = ark_bls12_381::Config;
+
+    #[test]
+    fn check_aggregate_public_key() {
+        let (msg, params, secret_keys, public_keys, sig) =
+            get_aggregate_bls_instance::<SigCurveConfig>();
+        let msg = msg.as_bytes();
+
+        let mut aggregate_pk = AggregatePublicKey::aggregate(&public_keys).unwrap();
+        assert!(Signature::verify_aggregate(
+            msg,
+            &sig,
+            &aggregate_pk,
+            &params
+        ));
+
+        // dropping the last signer invalidates the old signature but accepts the new one
+        aggregate_pk.remove(public_keys.last().unwrap());
+        assert!(!Signature::verify_aggregate(
+            msg,
+            &sig,
+            &aggregate_pk,
+            &params
+        ));
+        let sig_without_last =
+            Signature::aggregate_sign(msg, &secret_keys[..secret_keys.len() - 1], &params).unwrap();
+        assert!(Signature::verify_aggregate(
+            msg,
+            &sig_without_last,
+            &aggregate_pk,
+            &params
+        ));
+
+        // adding it back restores the original aggregate
+        aggregate_pk.add(public_keys.last().unwrap());
+        assert!(Signature::verify_aggregate(
+            msg,
+            &sig,
+            &aggregate_pk,
+            &params
+        ));
+
+        assert!(AggregatePublicKey::<SigCurveConfig>::aggregate(&[]).is_none());
+    }
+
+    #[test]
+    fn check_aggregate_matches_sequential_sum() {
+        let (msg, params, secret_keys, public_keys, sig) =
+            get_aggregate_bls_instance::<SigCurveConfig>();
+
+        let aggregate_pk = AggregatePublicKey::aggregate(&public_keys).unwrap();
+        let sequential_pk = public_keys
+            .iter()
+            .fold(PublicKeyGroup::<SigCurveConfig, MinPk>::ZERO, |acc, pk| {
+                acc + pk.pub_key
+            });
+        assert_eq!(aggregate_pk.aggregate, sequential_pk);
+
+        let sequential_sig = secret_keys
+            .iter()
+            .map(|sk| Signature::<SigCurveConfig>::sign(msg.as_bytes(), sk, &params))
+            .fold(Signature::<SigCurveConfig>::default(), |acc, s| Signature {
+                signature: acc.signature + s.signature,
+            });
+        assert_eq!(sig.signature, sequential_sig.signature);
+    }
+
+    #[test]
+    fn check_aggregate_signature() {
+        let (msg, params, secret_keys, public_keys, sig) =
+            get_aggregate_bls_instance::<SigCurveConfig>();
+        let msg = msg.as_bytes();
+        let signatures: Vec<_> = secret_keys
+            .iter()
+            .map(|sk| Signature::<SigCurveConfig>::sign(msg, sk, &params))
+            .collect();
+
+        let mut aggregate = AggregateSignature::new();
+        assert!(aggregate.finalize().is_none());
+
+        // out of order, with a signature pushed twice and then popped
+        for signature in signatures.iter().rev() {
+            aggregate.push(signature);
+        }
+        aggregate.push(&signatures[0]);
+        aggregate.pop(&signatures[0]);
+        assert_eq!(aggregate.len(), signatures.len());
+        assert_eq!(aggregate.finalize().unwrap().signature, sig.signature);
+
+        let aggregate_pk = AggregatePublicKey::aggregate(&public_keys).unwrap();
+        assert!(Signature::verify_aggregate(
+            msg,
+            &aggregate.finalize().unwrap(),
+            &aggregate_pk,
+            &params
+        ));
+
+        let collected: AggregateSignature<SigCurveConfig> = signatures.into_iter().collect();
+        assert_eq!(collected.aggregate, aggregate.aggregate);
+    }
+}
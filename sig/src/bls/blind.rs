@@ -0,0 +1,183 @@
+//! Blind BLS signatures.
+//!
+//! The user blinds `H(m)` with a random scalar `r` and sends `r * H(m)` to the signer, who
+//! returns `sk * r * H(m)`. Multiplying by `r^-1` yields the ordinary signature `sk * H(m)`,
+//! while the signer never learns `m` nor can link the final signature to the request.
+use ark_ec::bls12::Bls12Config;
+use ark_ff::{Field, UniformRand, Zero};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use derivative::Derivative;
+use rand::Rng;
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+use super::{
+    ct::mul_secret, params::SecretKeyScalarField, HashedMessage, MinPk, Parameters, PublicKey,
+    SecretKey, Signature, SignatureGroup, SignatureVariant, SignatureVariantHasher,
+};
+
+/// The secret scalar used to blind a message. It must be kept until the signature is unblinded.
+#[derive(Derivative)]
+#[derivative(Clone(bound = ""), Debug(bound = ""))]
+pub struct BlindingFactor<SigCurveConfig: Bls12Config> {
+    factor: SecretKeyScalarField<SigCurveConfig>,
+}
+
+impl<SigCurveConfig: Bls12Config> BlindingFactor<SigCurveConfig> {
+    pub fn new<R: Rng>(rng: &mut R) -> Self {
+        loop {
+            let factor = SecretKeyScalarField::<SigCurveConfig>::rand(rng);
+            if !factor.is_zero() {
+                return Self { factor };
+            }
+        }
+    }
+}
+
+impl<SigCurveConfig: Bls12Config> Drop for BlindingFactor<SigCurveConfig> {
+    fn drop(&mut self) {
+        self.factor.zeroize();
+    }
+}
+
+impl<SigCurveConfig: Bls12Config> ZeroizeOnDrop for BlindingFactor<SigCurveConfig> {}
+
+/// `r * H(m)`, the only thing the signer gets to see.
+#[derive(Derivative, CanonicalSerialize, CanonicalDeserialize)]
+#[derivative(
+    Clone(bound = ""),
+    Copy(bound = ""),
+    Debug(bound = ""),
+    Default(bound = "")
+)]
+pub struct BlindedMessage<SigCurveConfig: Bls12Config, V: SignatureVariant<SigCurveConfig> = MinPk>
+{
+    pub message: SignatureGroup<SigCurveConfig, V>,
+}
+
+/// `sk * r * H(m)`, returned by the signer.
+#[derive(Derivative, CanonicalSerialize, CanonicalDeserialize)]
+#[derivative(
+    Clone(bound = ""),
+    Copy(bound = ""),
+    Debug(bound = ""),
+    Default(bound = "")
+)]
+pub struct BlindedSignature<
+    SigCurveConfig: Bls12Config,
+    V: SignatureVariant<SigCurveConfig> = MinPk,
+> {
+    pub signature: SignatureGroup<SigCurveConfig, V>,
+}
+
+impl<SigCurveConfig: Bls12Config, V: SignatureVariantHasher<SigCurveConfig>>
+    Signature<SigCurveConfig, V>
+{
+    /// Blind `message` with a fresh blinding factor. The factor is needed to `unblind` later.
+    ///
+    /// `public_key` is the signer's, which the message is prefixed with under `MessageAugmentation`.
+    #[must_use]
+    pub fn blind_message<R: Rng>(
+        message: &[u8],
+        public_key: &PublicKey<SigCurveConfig, V>,
+        params: &Parameters<SigCurveConfig>,
+        rng: &mut R,
+    ) -> (
+        BlindedMessage<SigCurveConfig, V>,
+        BlindingFactor<SigCurveConfig>,
+    ) {
+        let blinding_factor = BlindingFactor::new(rng);
+        let blinded = BlindedMessage {
+            message: mul_secret(
+                &HashedMessage::with_public_key(message, public_key, params).hashed_message,
+                &blinding_factor.factor,
+            ),
+        };
+        (blinded, blinding_factor)
+    }
+
+    /// Sign a blinded message without learning the message itself.
+    #[must_use]
+    pub fn sign_blinded(
+        blinded_message: &BlindedMessage<SigCurveConfig, V>,
+        secret_key: &SecretKey<SigCurveConfig>,
+        _: &Parameters<SigCurveConfig>,
+    ) -> BlindedSignature<SigCurveConfig, V> {
+        BlindedSignature {
+            signature: mul_secret(&blinded_message.message, &secret_key.secret_key),
+        }
+    }
+
+    /// Remove the blinding factor, giving an ordinary signature that passes `verify`.
+    #[must_use]
+    pub fn unblind(
+        blinded_signature: &BlindedSignature<SigCurveConfig, V>,
+        blinding_factor: &BlindingFactor<SigCurveConfig>,
+    ) -> Self {
+        let mut inverse = blinding_factor
+            .factor
+            .inverse()
+            .expect("blinding factor is never zero");
+        let signature = mul_secret(&blinded_signature.signature, &inverse);
+        inverse.zeroize();
+        Self { signature }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::thread_rng;
+
+    use crate::bls::{get_bls_instance, MinSig};
+
+    use super::*;
+
+    #[test]
+    fn check_blind_signature() {
+        type SigCurveConfig = ark_bls12_381::Config;
+
+        let mut rng = thread_rng();
+        let (msg, params, sk, pk, sig) = get_bls_instance::<SigCurveConfig>();
+        let msg = msg.as_bytes();
+
+        let (blinded_message, blinding_factor) =
+            Signature::<SigCurveConfig>::blind_message(msg, &pk, &params, &mut rng);
+        let blinded_signature = Signature::sign_blinded(&blinded_message, &sk, &params);
+
+        // the blinded signature does not verify on the message
+        assert!(!Signature::verify(
+            msg,
+            &Signature {
+                signature: blinded_signature.signature
+            },
+            &pk,
+            &params
+        ));
+
+        let unblinded = Signature::unblind(&blinded_signature, &blinding_factor);
+        assert!(Signature::verify(msg, &unblinded, &pk, &params));
+        // BLS signatures are unique, so it is the same as signing directly
+        assert_eq!(unblinded.signature, sig.signature);
+
+        // unblinding with another factor gives garbage
+        let other_factor = BlindingFactor::new(&mut rng);
+        let wrong = Signature::unblind(&blinded_signature, &other_factor);
+        assert!(!Signature::verify(msg, &wrong, &pk, &params));
+    }
+
+    #[test]
+    fn check_blind_signature_min_sig() {
+        type SigCurveConfig = ark_bls12_381::Config;
+
+        let mut rng = thread_rng();
+        let params = Parameters::<SigCurveConfig>::setup();
+        let sk = SecretKey::new(&mut rng);
+        let pk = PublicKey::<SigCurveConfig, MinSig>::new(&sk, &params);
+        let msg = b"blind";
+
+        let (blinded_message, blinding_factor) =
+            Signature::blind_message(msg, &pk, &params, &mut rng);
+        let blinded_signature = Signature::sign_blinded(&blinded_message, &sk, &params);
+        let sig = Signature::unblind(&blinded_signature, &blinding_factor);
+        assert!(Signature::verify(msg, &sig, &pk, &params));
+    }
+}
@@ -1,25 +1,32 @@
-use core::ops::Mul;
-
 use ark_ec::{
-    bls12::{self, Bls12Config},
-    hashing::{
-        curve_maps::wb::{WBConfig, WBMap},
-        map_to_curve_hasher::MapToCurveBasedHasher,
-        HashToCurve,
-    },
-    pairing::{Pairing, PairingOutput},
-    short_weierstrass::SWCurveConfig,
+    bls12::{Bls12Config, G1Prepared, G2Prepared},
+    pairing::PairingOutput,
+    short_weierstrass::{Affine, SWCurveConfig},
+    CurveGroup,
 };
-use ark_ff::{field_hashers::DefaultFieldHasher, AdditiveGroup, UniformRand};
+use ark_ff::{AdditiveGroup, UniformRand};
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
-use blake2::Blake2s256;
+use ark_std::{borrow::Cow, cfg_into_iter, cfg_iter, collections::BTreeMap, vec, vec::Vec};
 use derivative::Derivative;
 use rand::Rng;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+use super::{
+    ct::mul_secret,
+    params::{SecretKeyScalarField, G1, G2},
+    AggregatePublicKey, BLSError, MinPk, PublicKeyGroup, SchemeVariant, SignatureGroup,
+    SignatureVariant, SignatureVariantHasher,
+};
 
-use crate::bls::params::{HashCurveConfig, HashCurveGroup};
-
-use super::params::{SecretKeyScalarField, G1, G2};
-
+/// All BLS types are generic over a `Bls12Config`, so any BLS12 curve with a
+/// `WBConfig` on the hashing group (e.g. BLS12-381 and BLS12-377) can be used.
+///
+/// The R1CS gadgets rely on `ark_r1cs_std::pairing::bls12` and on cofactor clearing
+/// gadgets specialised to BLS12 endomorphisms. For curves outside the BLS12 family
+/// (e.g. BN254), use the native-only types of `bls::pairing`, which are generic over
+/// any `Pairing`.
 #[derive(Derivative, CanonicalSerialize, CanonicalDeserialize)]
 #[derivative(
     Clone(bound = ""),
@@ -30,6 +37,14 @@ use super::params::{SecretKeyScalarField, G1, G2};
 pub struct Parameters<SigCurveConfig: Bls12Config> {
     pub g1_generator: G1<SigCurveConfig>,
     pub g2_generator: G2<SigCurveConfig>,
+    /// Reject identity and out-of-subgroup keys and signatures during verification.
+    ///
+    /// Without this, the identity signature verifies against the identity public key
+    /// for any message. Only turn it off to measure the raw cost of verification.
+    #[derivative(Default(value = "true"))]
+    pub strict: bool,
+    /// Which IETF scheme messages are signed and verified under.
+    pub scheme: SchemeVariant,
 }
 
 #[derive(Derivative, CanonicalSerialize, CanonicalDeserialize)]
@@ -39,8 +54,16 @@ pub struct Parameters<SigCurveConfig: Bls12Config> {
     Debug(bound = ""),
     Default(bound = "")
 )]
-pub struct PublicKey<SigCurveConfig: Bls12Config> {
-    pub pub_key: G1<SigCurveConfig>,
+pub struct PublicKey<SigCurveConfig: Bls12Config, V: SignatureVariant<SigCurveConfig> = MinPk> {
+    pub pub_key: PublicKeyGroup<SigCurveConfig, V>,
+}
+
+/// The secret scalar is wiped from memory when the key is dropped, which is
+/// also why `SecretKey` is `Clone` but not `Copy`.
+#[derive(Derivative, CanonicalSerialize, CanonicalDeserialize)]
+#[derivative(Clone(bound = ""), Debug(bound = ""), Default(bound = ""))]
+pub struct SecretKey<SigCurveConfig: Bls12Config> {
+    pub secret_key: SecretKeyScalarField<SigCurveConfig>,
 }
 
 #[derive(Derivative, CanonicalSerialize, CanonicalDeserialize)]
@@ -50,10 +73,14 @@ pub struct PublicKey<SigCurveConfig: Bls12Config> {
     Debug(bound = ""),
     Default(bound = "")
 )]
-pub struct SecretKey<SigCurveConfig: Bls12Config> {
-    pub secret_key: SecretKeyScalarField<SigCurveConfig>,
+pub struct Signature<SigCurveConfig: Bls12Config, V: SignatureVariant<SigCurveConfig> = MinPk> {
+    pub signature: SignatureGroup<SigCurveConfig, V>,
 }
 
+/// A message already hashed to the signature group.
+///
+/// Hash-to-curve dominates the cost of signing, so hashing once and reusing the point
+/// is much cheaper when the same message is signed or verified many times.
 #[derive(Derivative, CanonicalSerialize, CanonicalDeserialize)]
 #[derivative(
     Clone(bound = ""),
@@ -61,8 +88,47 @@ pub struct SecretKey<SigCurveConfig: Bls12Config> {
     Debug(bound = ""),
     Default(bound = "")
 )]
-pub struct Signature<SigCurveConfig: Bls12Config> {
-    pub signature: G2<SigCurveConfig>,
+pub struct HashedMessage<SigCurveConfig: Bls12Config, V: SignatureVariant<SigCurveConfig> = MinPk> {
+    pub hashed_message: SignatureGroup<SigCurveConfig, V>,
+}
+
+/// `Parameters` with both generators negated and prepared for the Miller loop.
+///
+/// Verification always pairs the negated generator with the signature, so preparing it
+/// once saves that work on every call. This matters most for `MinSig`, where the
+/// generator is on G2 and preparing it means computing all the line coefficients.
+#[derive(Derivative)]
+#[derivative(Clone(bound = ""), Debug(bound = ""))]
+pub struct PreparedParameters<SigCurveConfig: Bls12Config> {
+    pub neg_g1_generator: G1Prepared<SigCurveConfig>,
+    pub neg_g2_generator: G2Prepared<SigCurveConfig>,
+    pub strict: bool,
+}
+
+impl<SigCurveConfig: Bls12Config> From<&Parameters<SigCurveConfig>>
+    for PreparedParameters<SigCurveConfig>
+{
+    fn from(params: &Parameters<SigCurveConfig>) -> Self {
+        Self {
+            neg_g1_generator: (-params.g1_generator).into(),
+            neg_g2_generator: (-params.g2_generator).into(),
+            strict: params.strict,
+        }
+    }
+}
+
+/// Check that an externally supplied point can be used as a key or signature.
+pub(crate) fn validate_point<P: SWCurveConfig>(point: &Affine<P>) -> Result<(), BLSError> {
+    if point.infinity {
+        return Err(BLSError::PointAtInfinity);
+    }
+    if !point.is_on_curve() {
+        return Err(BLSError::PointNotOnCurve);
+    }
+    if !point.is_in_correct_subgroup_assuming_on_curve() {
+        return Err(BLSError::PointNotInSubgroup);
+    }
+    Ok(())
 }
 
 impl<SigCurveConfig: Bls12Config> Parameters<SigCurveConfig> {
@@ -73,18 +139,186 @@ impl<SigCurveConfig: Bls12Config> Parameters<SigCurveConfig> {
                 .into(),
             g2_generator: <<SigCurveConfig as Bls12Config>::G2Config as SWCurveConfig>::GENERATOR
                 .into(),
+            strict: true,
+            scheme: SchemeVariant::default(),
         }
     }
+
+    /// DST used to hash messages to the signature group of `V` under `self.scheme`.
+    #[must_use]
+    pub fn dst<V: SignatureVariant<SigCurveConfig>>(&self) -> Vec<u8> {
+        self.scheme.dst(V::HASH_SUITE)
+    }
 }
 
-impl<SigCurveConfig: Bls12Config> PublicKey<SigCurveConfig> {
+impl<SigCurveConfig: Bls12Config, V: SignatureVariant<SigCurveConfig>>
+    PublicKey<SigCurveConfig, V>
+{
     #[must_use]
     pub fn new(
         secret_key: &SecretKey<SigCurveConfig>,
         params: &Parameters<SigCurveConfig>,
     ) -> Self {
-        let pub_key = params.g1_generator.mul(secret_key.secret_key);
-        Self { pub_key }
+        let pub_key = mul_secret(&V::public_key_generator(params), &secret_key.secret_key);
+        Self::from_projective(pub_key)
+    }
+
+    /// Store the key normalized (`z = 1`), so that the affine conversions done by every
+    /// verification, validity check and serialization are free instead of a field inversion.
+    #[must_use]
+    pub fn from_projective(pub_key: PublicKeyGroup<SigCurveConfig, V>) -> Self {
+        Self {
+            pub_key: pub_key.into_affine().into(),
+        }
+    }
+
+    /// Import a public key produced elsewhere, rejecting points that are off the curve,
+    /// the identity, or outside the prime-order subgroup.
+    pub fn from_affine_checked(point: Affine<V::PublicKeyConfig>) -> Result<Self, BLSError> {
+        validate_point(&point)?;
+        Ok(Self {
+            pub_key: point.into(),
+        })
+    }
+
+    /// Whether the key is a non-identity point in the prime-order subgroup.
+    #[must_use]
+    pub fn is_well_formed(&self) -> bool {
+        validate_point(&self.pub_key.into_affine()).is_ok()
+    }
+
+    /// Compressed encoding of the affine point, also used whenever a public key needs to be hashed.
+    #[must_use]
+    pub fn to_compressed_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![];
+        self.pub_key
+            .into_affine()
+            .serialize_compressed(&mut bytes)
+            .expect("serializing a curve point into a vec should succeed");
+        bytes
+    }
+
+    /// Inverse of `to_compressed_bytes`, with the same checks as `from_affine_checked`.
+    pub fn from_compressed_bytes(bytes: &[u8]) -> Result<Self, BLSError> {
+        if bytes.len() != Affine::<V::PublicKeyConfig>::identity().compressed_size() {
+            return Err(BLSError::InvalidEncoding);
+        }
+        let point = Affine::<V::PublicKeyConfig>::deserialize_compressed_unchecked(bytes)
+            .map_err(|_| BLSError::InvalidEncoding)?;
+        Self::from_affine_checked(point)
+    }
+}
+
+impl<SigCurveConfig: Bls12Config, V: SignatureVariant<SigCurveConfig>>
+    Signature<SigCurveConfig, V>
+{
+    /// Import a signature produced elsewhere, rejecting points that are off the curve,
+    /// the identity, or outside the prime-order subgroup.
+    pub fn from_affine_checked(point: Affine<V::SignatureConfig>) -> Result<Self, BLSError> {
+        validate_point(&point)?;
+        Ok(Self {
+            signature: point.into(),
+        })
+    }
+
+    /// Whether the signature is a non-identity point in the prime-order subgroup.
+    #[must_use]
+    pub fn is_well_formed(&self) -> bool {
+        validate_point(&self.signature.into_affine()).is_ok()
+    }
+
+    #[must_use]
+    pub fn sign_prehashed(
+        hashed_message: &HashedMessage<SigCurveConfig, V>,
+        secret_key: &SecretKey<SigCurveConfig>,
+        _: &Parameters<SigCurveConfig>,
+    ) -> Self {
+        let signature = mul_secret(&hashed_message.hashed_message, &secret_key.secret_key);
+        Self { signature }
+    }
+
+    #[must_use]
+    pub fn verify_prehashed(
+        hashed_message: &HashedMessage<SigCurveConfig, V>,
+        signature: &Self,
+        public_key: &PublicKey<SigCurveConfig, V>,
+        params: &Parameters<SigCurveConfig>,
+    ) -> bool {
+        if params.strict && !(signature.is_well_formed() && public_key.is_well_formed()) {
+            return false;
+        }
+
+        // an optimized way to check pairing equation: e(g1, sig) == e(pk, H(msg))
+        //
+        // e'(g1, sig)^x == e'(pk, H(msg))^x (do miller loop for two sides without final exponentiation)
+        // <=> check e'(g1, sig)^-x * e'(pk, H(msg))^x = 1
+        // <=> check e'(-g1, sig)^x * e'(pk, H(msg))^x = 1
+        let prod = V::multi_pairing(
+            [-V::public_key_generator(params), public_key.pub_key],
+            [signature.signature, hashed_message.hashed_message],
+        );
+
+        prod == PairingOutput::ZERO
+    }
+
+    /// Same as `verify_prehashed`, but reuses the prepared generator of `prepared`.
+    #[must_use]
+    pub fn verify_prepared(
+        hashed_message: &HashedMessage<SigCurveConfig, V>,
+        signature: &Self,
+        public_key: &PublicKey<SigCurveConfig, V>,
+        prepared: &PreparedParameters<SigCurveConfig>,
+    ) -> bool {
+        if prepared.strict && !(signature.is_well_formed() && public_key.is_well_formed()) {
+            return false;
+        }
+
+        let prod = V::verification_pairing(
+            prepared,
+            public_key.pub_key,
+            signature.signature,
+            hashed_message.hashed_message,
+        );
+
+        prod == PairingOutput::ZERO
+    }
+}
+
+impl<SigCurveConfig: Bls12Config, V: SignatureVariantHasher<SigCurveConfig>>
+    HashedMessage<SigCurveConfig, V>
+{
+    /// Hash `message` with the DST of `params.scheme`.
+    ///
+    /// Under `MessageAugmentation` this does not prefix the public key, see `with_public_key`.
+    #[must_use]
+    pub fn new(message: &[u8], params: &Parameters<SigCurveConfig>) -> Self {
+        Self {
+            hashed_message: V::hash_to_curve_with_dst(message, &params.dst::<V>()),
+        }
+    }
+
+    /// Hash `message` as signed by `public_key`, which is prefixed to the message
+    /// under `MessageAugmentation` and ignored otherwise.
+    #[must_use]
+    pub fn with_public_key(
+        message: &[u8],
+        public_key: &PublicKey<SigCurveConfig, V>,
+        params: &Parameters<SigCurveConfig>,
+    ) -> Self {
+        Self::new(&augment(message, public_key, params.scheme), params)
+    }
+}
+
+fn augment<'a, SigCurveConfig: Bls12Config, V: SignatureVariant<SigCurveConfig>>(
+    message: &'a [u8],
+    public_key: &PublicKey<SigCurveConfig, V>,
+    scheme: SchemeVariant,
+) -> Cow<'a, [u8]> {
+    match scheme {
+        SchemeVariant::MessageAugmentation => {
+            Cow::Owned([public_key.to_compressed_bytes().as_slice(), message].concat())
+        }
+        SchemeVariant::Basic | SchemeVariant::ProofOfPossession => Cow::Borrowed(message),
     }
 }
 
@@ -95,33 +329,48 @@ impl<SigCurveConfig: Bls12Config> SecretKey<SigCurveConfig> {
     }
 }
 
-impl<SigCurveConfig: Bls12Config> Signature<SigCurveConfig>
-where
-    <SigCurveConfig as Bls12Config>::G2Config: WBConfig,
-{
-    fn hash_to_curve(message: &[u8]) -> G2<SigCurveConfig> {
-        // safety
-        type FieldHasher = DefaultFieldHasher<Blake2s256, 128>;
-        type CurveMap<SigCurveConfig> = WBMap<HashCurveConfig<SigCurveConfig>>;
-        let hasher: MapToCurveBasedHasher<
-            HashCurveGroup<SigCurveConfig>,
-            FieldHasher,
-            CurveMap<SigCurveConfig>,
-        > = MapToCurveBasedHasher::new(&[]).expect("BLS12 curve supports hash to curve");
-        let hashed_message = hasher.hash(message).unwrap();
+impl<SigCurveConfig: Bls12Config> Zeroize for SecretKey<SigCurveConfig> {
+    fn zeroize(&mut self) {
+        self.secret_key.zeroize();
+    }
+}
 
-        hashed_message.into()
+impl<SigCurveConfig: Bls12Config> Drop for SecretKey<SigCurveConfig> {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl<SigCurveConfig: Bls12Config> ZeroizeOnDrop for SecretKey<SigCurveConfig> {}
+
+impl<SigCurveConfig: Bls12Config, V: SignatureVariantHasher<SigCurveConfig>>
+    Signature<SigCurveConfig, V>
+{
+    /// Hash `message` to the signature group with domain separation tag `dst`.
+    ///
+    /// `sign` and `verify` use `Parameters::dst`, as does the R1CS gadget.
+    pub(crate) fn hash_to_curve_with_dst(
+        message: &[u8],
+        dst: &[u8],
+    ) -> SignatureGroup<SigCurveConfig, V> {
+        V::hash_to_curve_with_dst(message, dst)
     }
 
     #[must_use]
     pub fn sign(
         message: &[u8],
         secret_key: &SecretKey<SigCurveConfig>,
-        _: &Parameters<SigCurveConfig>,
+        params: &Parameters<SigCurveConfig>,
     ) -> Self {
-        let hashed_message = Self::hash_to_curve(message);
-        let signature = hashed_message.mul(secret_key.secret_key);
-        Self { signature }
+        let hashed_message = match params.scheme {
+            SchemeVariant::MessageAugmentation => {
+                HashedMessage::with_public_key(message, &PublicKey::new(secret_key, params), params)
+            }
+            SchemeVariant::Basic | SchemeVariant::ProofOfPossession => {
+                HashedMessage::new(message, params)
+            }
+        };
+        Self::sign_prehashed(&hashed_message, secret_key, params)
     }
 
     #[must_use]
@@ -148,7 +397,24 @@ where
         Some(Signature::sign(message, &sk, params))
         */
 
-        let mut sigs = secret_keys.iter().map(|sk| Self::sign(message, sk, params));
+        if secret_keys.is_empty() {
+            return None;
+        }
+
+        // the message is hashed once and shared by all signers, unless each of them
+        // prefixes it with their own public key
+        let hashed_message = (params.scheme != SchemeVariant::MessageAugmentation)
+            .then(|| HashedMessage::new(message, params));
+
+        // signing is done in parallel, but the signatures are summed up in order
+        // so that the result does not depend on the number of threads
+        let sigs: Vec<_> = cfg_iter!(secret_keys)
+            .map(|sk| match &hashed_message {
+                Some(hashed_message) => Self::sign_prehashed(hashed_message, sk, params),
+                None => Self::sign(message, sk, params),
+            })
+            .collect();
+        let mut sigs = sigs.into_iter();
         let first_sig = sigs.next()?;
 
         Some(sigs.fold(first_sig, |acc, new_sig| Self {
@@ -160,16 +426,19 @@ where
     pub fn verify_slow(
         message: &[u8],
         signature: &Self,
-        public_key: &PublicKey<SigCurveConfig>,
+        public_key: &PublicKey<SigCurveConfig, V>,
         params: &Parameters<SigCurveConfig>,
     ) -> bool {
-        let hashed_message = Self::hash_to_curve(message);
+        if params.strict && !(signature.is_well_formed() && public_key.is_well_formed()) {
+            return false;
+        }
+
+        let hashed_message =
+            HashedMessage::with_public_key(message, public_key, params).hashed_message;
 
         // a naive way to check pairing equation: e(g1, sig) == e(pk, H(msg))
-        let pairing_1 =
-            bls12::Bls12::<SigCurveConfig>::pairing(params.g1_generator, signature.signature);
-        let pairing_2 =
-            ark_ec::bls12::Bls12::<SigCurveConfig>::pairing(public_key.pub_key, hashed_message);
+        let pairing_1 = V::pairing(V::public_key_generator(params), signature.signature);
+        let pairing_2 = V::pairing(public_key.pub_key, hashed_message);
 
         pairing_1 == pairing_2
     }
@@ -178,50 +447,113 @@ where
     pub fn verify(
         message: &[u8],
         signature: &Self,
-        public_key: &PublicKey<SigCurveConfig>,
+        public_key: &PublicKey<SigCurveConfig, V>,
         params: &Parameters<SigCurveConfig>,
     ) -> bool {
-        let hashed_message = Self::hash_to_curve(message);
-
-        // an optimized way to check pairing equation: e(g1, sig) == e(pk, H(msg))
-        //
-        // e'(g1, sig)^x == e'(pk, H(msg))^x (do miller loop for two sides without final exponentiation)
-        // <=> check e'(g1, sig)^-x * e'(pk, H(msg))^x = 1
-        // <=> check e'(-g1, sig)^x * e'(pk, H(msg))^x = 1
-        let prod = ark_ec::bls12::Bls12::<SigCurveConfig>::multi_pairing(
-            [-params.g1_generator, public_key.pub_key],
-            [signature.signature, hashed_message],
-        );
-
-        prod == PairingOutput::ZERO
+        Self::verify_prehashed(
+            &HashedMessage::with_public_key(message, public_key, params),
+            signature,
+            public_key,
+            params,
+        )
     }
 
+    /// Verify an aggregate signature where every signer signs `message`.
+    ///
+    /// Under `Basic` this only accepts a single signer, since the messages must be
+    /// distinct, and under `MessageAugmentation` each signer's message is hashed separately.
     #[must_use]
     pub fn aggregate_verify(
         message: &[u8],
         aggregate_signature: &Self,
-        public_keys: &[PublicKey<SigCurveConfig>],
+        public_keys: &[PublicKey<SigCurveConfig, V>],
+        params: &Parameters<SigCurveConfig>,
+    ) -> Option<bool> {
+        if params.scheme != SchemeVariant::ProofOfPossession {
+            return Self::aggregate_verify_distinct(
+                &vec![message; public_keys.len()],
+                aggregate_signature,
+                public_keys,
+                params,
+            );
+        }
+
+        let pk = AggregatePublicKey::aggregate(public_keys)?.to_public_key();
+
+        Some(Self::verify_slow(message, aggregate_signature, &pk, params))
+    }
+
+    /// Verify an aggregate signature where the i-th signer signs `messages[i]`.
+    ///
+    /// Public keys that sign the same message are aggregated first, so the check
+    /// costs one miller loop per unique message plus one for the signature:
+    /// e(g1, sig) == prod_j e(sum_{i: m_i = m_j} pk_i, H(m_j))
+    ///
+    /// Under `Basic`, repeated messages are rejected instead, and under
+    /// `MessageAugmentation` messages are compared after prefixing the public keys.
+    #[must_use]
+    pub fn aggregate_verify_distinct(
+        messages: &[&[u8]],
+        aggregate_signature: &Self,
+        public_keys: &[PublicKey<SigCurveConfig, V>],
         params: &Parameters<SigCurveConfig>,
     ) -> Option<bool> {
-        if public_keys.is_empty() {
+        if messages.is_empty() || messages.len() != public_keys.len() {
             return None;
         }
 
-        let public_key_0 = *public_keys.first()?;
-        let pk = public_keys
+        if params.strict
+            && !(aggregate_signature.is_well_formed()
+                && public_keys.iter().all(PublicKey::is_well_formed))
+        {
+            return Some(false);
+        }
+
+        // keep the first-seen order of messages so that the pairing inputs are deterministic
+        let messages: Vec<_> = messages
             .iter()
-            .skip(1)
-            .fold(public_key_0, |acc, new_pk| PublicKey {
-                pub_key: acc.pub_key + new_pk.pub_key,
-            });
+            .zip(public_keys)
+            .map(|(message, public_key)| augment(message, public_key, params.scheme))
+            .collect();
+        let mut index_of_message: BTreeMap<&[u8], usize> = BTreeMap::new();
+        let mut grouped: Vec<(&[u8], PublicKeyGroup<SigCurveConfig, V>)> = Vec::new();
+        for (message, public_key) in messages.iter().zip(public_keys) {
+            match index_of_message.get(message.as_ref()) {
+                Some(_) if params.scheme == SchemeVariant::Basic => return Some(false),
+                Some(&i) => grouped[i].1 += public_key.pub_key,
+                None => {
+                    index_of_message.insert(message.as_ref(), grouped.len());
+                    grouped.push((message.as_ref(), public_key.pub_key));
+                }
+            }
+        }
 
-        Some(Self::verify_slow(message, aggregate_signature, &pk, params))
+        // same trick as `verify`: move e(g1, sig) to the other side and check the product is 1
+        let hashed: Vec<_> = cfg_into_iter!(grouped)
+            .map(|(message, pk)| {
+                (
+                    pk,
+                    HashedMessage::<SigCurveConfig, V>::new(message, params).hashed_message,
+                )
+            })
+            .collect();
+        let (pk_side, sig_side): (Vec<_>, Vec<_>) = core::iter::once((
+            -V::public_key_generator(params),
+            aggregate_signature.signature,
+        ))
+        .chain(hashed)
+        .unzip();
+        let prod = V::multi_pairing(pk_side, sig_side);
+
+        Some(prod == PairingOutput::ZERO)
     }
 }
 
 #[cfg(test)]
 mod test {
-    use crate::bls::{get_aggregate_bls_instance, get_bls_instance};
+    use ark_ff::{One, Zero};
+
+    use crate::bls::{get_aggregate_bls_instance, get_bls_instance, MinSig};
 
     use super::*;
 
@@ -232,6 +564,122 @@ mod test {
         assert!(Signature::verify(msg.as_bytes(), &sig, &pk, &params));
     }
 
+    #[test]
+    fn check_signature_bls12_377() {
+        let (msg, params, _, pk, sig) = get_bls_instance::<ark_bls12_377::Config>();
+        assert!(Signature::verify(msg.as_bytes(), &sig, &pk, &params));
+        assert!(!Signature::verify(
+            &[msg.as_bytes(), &[1]].concat(),
+            &sig,
+            &pk,
+            &params
+        ));
+    }
+
+    #[test]
+    fn check_prehashed() {
+        let (msg, params, sk, pk, sig) = get_bls_instance::<ark_bls12_381::Config>();
+        let hashed_message = HashedMessage::new(msg.as_bytes(), &params);
+
+        let prehashed_sig = Signature::sign_prehashed(&hashed_message, &sk, &params);
+        assert_eq!(prehashed_sig.signature, sig.signature);
+        assert!(Signature::verify_prehashed(
+            &hashed_message,
+            &sig,
+            &pk,
+            &params
+        ));
+
+        let other_message = HashedMessage::new(&[msg.as_bytes(), &[1]].concat(), &params);
+        assert!(!Signature::verify_prehashed(
+            &other_message,
+            &sig,
+            &pk,
+            &params
+        ));
+    }
+
+    #[test]
+    fn check_verify_prepared() {
+        let (msg, params, sk, pk, sig) = get_bls_instance::<ark_bls12_381::Config>();
+        let prepared = PreparedParameters::from(&params);
+        let hashed_message = HashedMessage::new(msg.as_bytes(), &params);
+        assert!(Signature::verify_prepared(
+            &hashed_message,
+            &sig,
+            &pk,
+            &prepared
+        ));
+        let other_message = HashedMessage::new(&[msg.as_bytes(), &[1]].concat(), &params);
+        assert!(!Signature::verify_prepared(
+            &other_message,
+            &sig,
+            &pk,
+            &prepared
+        ));
+
+        let pk = PublicKey::<_, MinSig>::new(&sk, &params);
+        let hashed_message = HashedMessage::<_, MinSig>::new(msg.as_bytes(), &params);
+        let sig = Signature::sign_prehashed(&hashed_message, &sk, &params);
+        assert!(Signature::verify_prepared(
+            &hashed_message,
+            &sig,
+            &pk,
+            &prepared
+        ));
+    }
+
+    #[test]
+    fn check_identity_rejected() {
+        let (msg, mut params, _, _, _) = get_bls_instance::<ark_bls12_381::Config>();
+        let pk = PublicKey::<ark_bls12_381::Config>::default();
+        let sig = Signature::<ark_bls12_381::Config>::default();
+
+        assert!(!Signature::verify(msg.as_bytes(), &sig, &pk, &params));
+        assert!(!Signature::verify_slow(msg.as_bytes(), &sig, &pk, &params));
+        assert_eq!(
+            Signature::aggregate_verify_distinct(&[msg.as_bytes()], &sig, &[pk], &params),
+            Some(false)
+        );
+
+        // the trivial forgery goes through once the checks are disabled
+        params.strict = false;
+        assert!(Signature::verify(msg.as_bytes(), &sig, &pk, &params));
+        assert!(Signature::verify_slow(msg.as_bytes(), &sig, &pk, &params));
+    }
+
+    #[test]
+    fn check_public_key_compressed_bytes() {
+        let (_, _, _, public_keys, _) = get_aggregate_bls_instance::<ark_bls12_381::Config>();
+
+        // keys are kept normalized, so converting them to affine is free
+        let aggregate_pk = AggregatePublicKey::aggregate(&public_keys)
+            .unwrap()
+            .to_public_key();
+        for pk in public_keys.iter().chain([&aggregate_pk]) {
+            assert!(pk.pub_key.z.is_one());
+
+            let bytes = pk.to_compressed_bytes();
+            let decoded =
+                PublicKey::<ark_bls12_381::Config>::from_compressed_bytes(&bytes).unwrap();
+            assert_eq!(decoded.pub_key, pk.pub_key);
+            assert!(decoded.pub_key.z.is_one());
+        }
+
+        let bytes = public_keys[0].to_compressed_bytes();
+        assert_eq!(
+            PublicKey::<ark_bls12_381::Config>::from_compressed_bytes(&bytes[1..]).unwrap_err(),
+            BLSError::InvalidEncoding
+        );
+        assert_eq!(
+            PublicKey::<ark_bls12_381::Config>::from_compressed_bytes(
+                &PublicKey::<ark_bls12_381::Config>::default().to_compressed_bytes()
+            )
+            .unwrap_err(),
+            BLSError::PointAtInfinity
+        );
+    }
+
     #[test]
     fn check_verify_failure() {
         let (msg, params, _, pk, sig) = get_bls_instance::<ark_bls12_381::Config>();
@@ -255,4 +703,200 @@ mod test {
             get_aggregate_bls_instance::<ark_bls12_381::Config>();
         assert!(Signature::aggregate_verify(msg.as_bytes(), &sig, &public_keys, &params).unwrap());
     }
+
+    #[test]
+    fn check_aggregate_signature_distinct() {
+        let mut rng = rand::thread_rng();
+        let params = Parameters::<ark_bls12_381::Config>::setup();
+
+        // the last two signers sign the same message
+        let messages: [&[u8]; 4] = [b"epoch 0", b"epoch 1", b"epoch 2", b"epoch 2"];
+        let secret_keys: Vec<_> = (0..messages.len())
+            .map(|_| SecretKey::new(&mut rng))
+            .collect();
+        let public_keys: Vec<_> = secret_keys
+            .iter()
+            .map(|sk| PublicKey::<ark_bls12_381::Config>::new(sk, &params))
+            .collect();
+
+        let sig = messages
+            .iter()
+            .zip(&secret_keys)
+            .map(|(msg, sk)| Signature::sign(msg, sk, &params))
+            .reduce(|acc, sig| Signature {
+                signature: acc.signature + sig.signature,
+            })
+            .unwrap();
+
+        assert!(
+            Signature::aggregate_verify_distinct(&messages, &sig, &public_keys, &params).unwrap()
+        );
+
+        // swapping two messages breaks the signature
+        let swapped: [&[u8]; 4] = [b"epoch 1", b"epoch 0", b"epoch 2", b"epoch 2"];
+        assert!(
+            !Signature::aggregate_verify_distinct(&swapped, &sig, &public_keys, &params).unwrap()
+        );
+
+        // mismatched lengths are rejected
+        assert!(
+            Signature::aggregate_verify_distinct(&messages[1..], &sig, &public_keys, &params)
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn check_min_sig_signature() {
+        let mut rng = rand::thread_rng();
+        let params = Parameters::<ark_bls12_381::Config>::setup();
+        let msg = b"Hello World";
+
+        let secret_keys: Vec<_> = (0..3).map(|_| SecretKey::new(&mut rng)).collect();
+        let public_keys: Vec<_> = secret_keys
+            .iter()
+            .map(|sk| PublicKey::<_, MinSig>::new(sk, &params))
+            .collect();
+
+        let sig = Signature::<_, MinSig>::sign(msg, &secret_keys[0], &params);
+        assert!(Signature::verify_slow(msg, &sig, &public_keys[0], &params));
+        assert!(Signature::verify(msg, &sig, &public_keys[0], &params));
+        assert!(!Signature::verify(msg, &sig, &public_keys[1], &params));
+
+        let aggregate_sig =
+            Signature::<_, MinSig>::aggregate_sign(msg, &secret_keys, &params).unwrap();
+        assert!(Signature::aggregate_verify(msg, &aggregate_sig, &public_keys, &params).unwrap());
+    }
+
+    #[test]
+    fn check_scheme_variants() {
+        let (msg, params, secret_keys, public_keys, _) =
+            get_aggregate_bls_instance::<ark_bls12_381::Config>();
+        let msg = msg.as_bytes();
+        let (secret_keys, public_keys) = (&secret_keys[..4], &public_keys[..4]);
+        let schemes = [
+            SchemeVariant::Basic,
+            SchemeVariant::MessageAugmentation,
+            SchemeVariant::ProofOfPossession,
+        ];
+
+        for scheme in schemes {
+            let params = Parameters { scheme, ..params };
+            let sig = Signature::sign(msg, &secret_keys[0], &params);
+            assert!(Signature::verify(msg, &sig, &public_keys[0], &params));
+            assert!(Signature::verify_slow(msg, &sig, &public_keys[0], &params));
+
+            let min_sig_pk = PublicKey::<_, MinSig>::new(&secret_keys[0], &params);
+            let min_sig = Signature::<_, MinSig>::sign(msg, &secret_keys[0], &params);
+            assert!(Signature::verify(msg, &min_sig, &min_sig_pk, &params));
+
+            // signatures do not carry over to the other schemes
+            for other in schemes.into_iter().filter(|other| *other != scheme) {
+                let other_params = Parameters {
+                    scheme: other,
+                    ..params
+                };
+                assert!(!Signature::verify(
+                    msg,
+                    &sig,
+                    &public_keys[0],
+                    &other_params
+                ));
+            }
+        }
+
+        // signatures on the same message only aggregate once the keys are prefixed
+        let params = Parameters {
+            scheme: SchemeVariant::MessageAugmentation,
+            ..params
+        };
+        let sig = Signature::aggregate_sign(msg, secret_keys, &params).unwrap();
+        assert!(Signature::aggregate_verify(msg, &sig, public_keys, &params).unwrap());
+        assert!(!Signature::aggregate_verify(msg, &sig, &public_keys[1..], &params).unwrap());
+
+        let params = Parameters {
+            scheme: SchemeVariant::Basic,
+            ..params
+        };
+        let sig = Signature::aggregate_sign(msg, secret_keys, &params).unwrap();
+        assert!(!Signature::aggregate_verify(msg, &sig, public_keys, &params).unwrap());
+
+        let messages: Vec<_> = (0..secret_keys.len()).map(|i| vec![i as u8]).collect();
+        let messages: Vec<_> = messages.iter().map(Vec::as_slice).collect();
+        let sig = messages
+            .iter()
+            .zip(secret_keys)
+            .map(|(msg, sk)| Signature::sign(msg, sk, &params))
+            .reduce(|acc, sig| Signature {
+                signature: acc.signature + sig.signature,
+            })
+            .unwrap();
+        assert!(
+            Signature::aggregate_verify_distinct(&messages, &sig, public_keys, &params).unwrap()
+        );
+    }
+
+    #[test]
+    fn check_from_affine_checked() {
+        type G1Affine = ark_bls12_381::G1Affine;
+        type G2Affine = ark_bls12_381::G2Affine;
+
+        let (_, _, _, pk, sig) = get_bls_instance::<ark_bls12_381::Config>();
+        let pk_affine = pk.pub_key.into_affine();
+        let sig_affine = sig.signature.into_affine();
+
+        assert_eq!(
+            PublicKey::<ark_bls12_381::Config>::from_affine_checked(pk_affine)
+                .unwrap()
+                .pub_key,
+            pk.pub_key
+        );
+        assert_eq!(
+            Signature::<ark_bls12_381::Config>::from_affine_checked(sig_affine)
+                .unwrap()
+                .signature,
+            sig.signature
+        );
+
+        assert_eq!(
+            PublicKey::<ark_bls12_381::Config>::from_affine_checked(G1Affine::identity())
+                .unwrap_err(),
+            BLSError::PointAtInfinity
+        );
+        assert_eq!(
+            Signature::<ark_bls12_381::Config>::from_affine_checked(G2Affine::identity())
+                .unwrap_err(),
+            BLSError::PointAtInfinity
+        );
+
+        let off_curve = G1Affine::new_unchecked(pk_affine.x, pk_affine.y + pk_affine.y);
+        assert_eq!(
+            PublicKey::<ark_bls12_381::Config>::from_affine_checked(off_curve).unwrap_err(),
+            BLSError::PointNotOnCurve
+        );
+
+        // BLS12-381 G1 has a non-trivial cofactor, so a random point on the curve is
+        // outside the subgroup with overwhelming probability
+        let mut rng = rand::thread_rng();
+        let not_in_subgroup = loop {
+            let x = ark_bls12_381::Fq::rand(&mut rng);
+            if let Some(p) = G1Affine::get_point_from_x_unchecked(x, false) {
+                if !p.is_in_correct_subgroup_assuming_on_curve() {
+                    break p;
+                }
+            }
+        };
+        assert_eq!(
+            PublicKey::<ark_bls12_381::Config>::from_affine_checked(not_in_subgroup).unwrap_err(),
+            BLSError::PointNotInSubgroup
+        );
+    }
+
+    #[test]
+    fn check_secret_key_zeroize() {
+        let mut rng = rand::thread_rng();
+        let mut sk = SecretKey::<ark_bls12_381::Config>::new(&mut rng);
+        assert!(!sk.secret_key.is_zero());
+        sk.zeroize();
+        assert!(sk.secret_key.is_zero());
+    }
 }
@@ -1,5 +1,6 @@
-use std::marker::PhantomData;
+use core::{cmp::Ordering, marker::PhantomData};
 
+use ark_crypto_primitives::prf::{blake2s::constraints::Blake2sGadget, PRFGadget};
 use ark_ec::{
     bls12::Bls12Config, hashing::curve_maps::wb::WBConfig, short_weierstrass::SWCurveConfig,
     CurveGroup,
@@ -7,12 +8,16 @@ use ark_ec::{
 use ark_ff::PrimeField;
 use ark_r1cs_std::{
     alloc::AllocVar,
-    fields::{FieldOpsBounds, FieldVar},
+    convert::ToConstraintFieldGadget,
+    eq::EqGadget,
+    fields::{fp::FpVar, FieldOpsBounds, FieldVar},
+    groups::{bls12::G1Var, CurveVar},
+    prelude::Boolean,
     uint8::UInt8,
+    R1CSVar,
 };
-use ark_relations::r1cs::{
-    ConstraintSynthesizer, ConstraintSystem, ConstraintSystemRef, SynthesisError,
-};
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
+use ark_std::vec::Vec;
 use derivative::Derivative;
 
 use crate::{
@@ -22,14 +27,30 @@ use crate::{
         map_to_curve::{sqrt::SqrtGadget, to_base_field::ToBaseFieldVarGadget},
     },
     params::BlsSigField,
+    snark::witness_only_cs,
 };
 
 use super::{
     params::{HashCurveConfig, HashCurveGroup, HashCurveVar},
-    BLSAggregateSignatureVerifyGadget, Parameters, ParametersVar, PublicKey, PublicKeyVar,
-    Signature, SignatureVar,
+    BLSAggregateSignatureVerifyGadget, Bls12SubgroupGadget, Parameters, ParametersVar, PublicKey,
+    PublicKeyVar, Signature, SignatureVar,
 };
 
+/// How `BLSCircuit` exposes the message to the verifier.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MessageMode {
+    /// Every byte is a public input.
+    #[default]
+    Bytes,
+    /// The bytes are packed into the public inputs, `(CF::MODULUS_BIT_SIZE - 1) / 8` per
+    /// element, as `ToConstraintField` packs them natively.
+    Packed,
+    /// The message is known at setup (e.g. a fixed domain or a genesis digest) and is
+    /// embedded in the circuit as constants. Only its Blake2s digest is public, packed as
+    /// in `Packed`, so that the verifier can tell which message the keys are for.
+    Constant,
+}
+
 #[derive(Derivative)]
 #[derivative(Clone(bound = ""))]
 pub struct BLSCircuit<
@@ -42,6 +63,7 @@ pub struct BLSCircuit<
     pk: Option<PublicKey<SigCurveConfig>>,
     msg: &'a [Option<u8>],
     sig: Option<Signature<SigCurveConfig>>,
+    msg_mode: MessageMode,
     _fv: PhantomData<(FV, CF)>,
 }
 
@@ -66,29 +88,86 @@ where
             pk,
             msg,
             sig,
+            msg_mode: MessageMode::Bytes,
             _fv: PhantomData,
         }
     }
 
-    pub fn get_public_inputs(&self) -> Result<Vec<CF>, SynthesisError> {
-        // inefficient as we recomputed public input here
-        let cs = ConstraintSystem::new_ref();
+    /// Expose the message as `msg_mode`. With `MessageMode::Constant`, the message must be
+    /// given at setup too.
+    #[must_use]
+    pub const fn msg_mode(mut self, msg_mode: MessageMode) -> Self {
+        self.msg_mode = msg_mode;
+        self
+    }
 
-        let _: Vec<UInt8<CF>> = self
-            .msg
+    /// Allocate `msg` as `msg_mode` exposes it.
+    fn new_message(
+        cs: ConstraintSystemRef<CF>,
+        msg: &[Option<u8>],
+        msg_mode: MessageMode,
+    ) -> Result<Vec<UInt8<CF>>, SynthesisError> {
+        let bytes = msg
             .iter()
-            .map(|b| UInt8::new_input(cs.clone(), || b.ok_or(SynthesisError::AssignmentMissing)))
-            .collect::<Result<_, _>>()?;
+            .map(|b| b.ok_or(SynthesisError::AssignmentMissing));
+
+        match msg_mode {
+            MessageMode::Bytes => bytes.map(|b| UInt8::new_input(cs.clone(), || b)).collect(),
+            MessageMode::Packed => {
+                let msg = bytes
+                    .map(|b| UInt8::new_witness(cs.clone(), || b))
+                    .collect::<Result<Vec<_>, _>>()?;
+                enforce_packed_input(cs, &msg)?;
+                Ok(msg)
+            }
+            MessageMode::Constant => {
+                let msg = bytes
+                    .map(|b| b.map(UInt8::constant))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let mut hasher = Blake2sGadget::default();
+                hasher.update(&msg)?;
+                enforce_packed_input(cs, &hasher.finalize()?.0)?;
+                Ok(msg)
+            }
+        }
+    }
+
+    /// The public inputs of the circuit for `msg` under `pk`, which a verifier computes
+    /// from the public parts alone: only the inputs are allocated, without a witness.
+    pub fn public_inputs_from_parts(
+        params: &Parameters<SigCurveConfig>,
+        pk: &PublicKey<SigCurveConfig>,
+        msg: &[u8],
+        msg_mode: MessageMode,
+    ) -> Result<Vec<CF>, SynthesisError> {
+        let msg: Vec<_> = msg.iter().copied().map(Some).collect();
+        Self::new_public_inputs(Some(params), Some(pk), &msg, msg_mode)
+    }
+
+    pub fn get_public_inputs(&self) -> Result<Vec<CF>, SynthesisError> {
+        Self::new_public_inputs(
+            self.params.as_ref(),
+            self.pk.as_ref(),
+            self.msg,
+            self.msg_mode,
+        )
+    }
+
+    fn new_public_inputs(
+        params: Option<&Parameters<SigCurveConfig>>,
+        pk: Option<&PublicKey<SigCurveConfig>>,
+        msg: &[Option<u8>],
+        msg_mode: MessageMode,
+    ) -> Result<Vec<CF>, SynthesisError> {
+        // only the inputs are allocated, and without the constraints of `enforce_packed_input`
+        let cs = witness_only_cs();
+
+        let _ = Self::new_message(cs.clone(), msg, msg_mode)?;
         let _ = ParametersVar::<SigCurveConfig, FV, CF>::new_input(cs.clone(), || {
-            self.params
-                .as_ref()
-                .ok_or(SynthesisError::AssignmentMissing)
+            params.ok_or(SynthesisError::AssignmentMissing)
         })?;
         let _ = PublicKeyVar::<SigCurveConfig, FV, CF>::new_input(cs.clone(), || {
-            self.pk.as_ref().ok_or(SynthesisError::AssignmentMissing)
-        })?;
-        let _ = SignatureVar::<SigCurveConfig, FV, CF>::new_input(cs.clone(), || {
-            self.sig.as_ref().ok_or(SynthesisError::AssignmentMissing)
+            pk.ok_or(SynthesisError::AssignmentMissing)
         })?;
 
         // `instance_assignment` has a placeholder value at index 0, we need to skip it
@@ -102,6 +181,18 @@ where
     }
 }
 
+/// Allocate the packing of `bytes` into field elements as public inputs, and enforce that
+/// they pack `bytes`.
+fn enforce_packed_input<CF: PrimeField>(
+    cs: ConstraintSystemRef<CF>,
+    bytes: &[UInt8<CF>],
+) -> Result<(), SynthesisError> {
+    for packed in bytes.to_constraint_field()? {
+        FpVar::new_input(cs.clone(), || packed.value())?.enforce_equal(&packed)?;
+    }
+    Ok(())
+}
+
 // impl this trait so that SNARK can operate on this circuit
 impl<
         'b,
@@ -126,13 +217,10 @@ where
     HashCurveVar<SigCurveConfig, FV, CF>:
         FieldVar<<HashCurveGroup<SigCurveConfig> as CurveGroup>::BaseField, CF>,
     HashCurveGroup<SigCurveConfig>: CofactorGadget<HashCurveVar<SigCurveConfig, FV, CF>, CF>,
+    SigCurveConfig: Bls12SubgroupGadget<FV, CF>,
 {
     fn generate_constraints(self, cs: ConstraintSystemRef<CF>) -> Result<(), SynthesisError> {
-        let msg_var: Vec<UInt8<CF>> = self
-            .msg
-            .iter()
-            .map(|b| UInt8::new_input(cs.clone(), || b.ok_or(SynthesisError::AssignmentMissing)))
-            .collect::<Result<_, _>>()?;
+        let msg_var = Self::new_message(cs.clone(), self.msg, self.msg_mode)?;
         let params_var = ParametersVar::<SigCurveConfig, FV, CF>::new_input(cs.clone(), || {
             self.params
                 .as_ref()
@@ -141,7 +229,9 @@ where
         let pk_var = PublicKeyVar::new_input(cs.clone(), || {
             self.pk.as_ref().ok_or(SynthesisError::AssignmentMissing)
         })?;
-        let sig_var = SignatureVar::new_input(cs, || {
+        // the signature is a witness: the proof shows that `pk` signed `msg` without
+        // revealing the signature
+        let sig_var = SignatureVar::new_witness(cs, || {
             self.sig.as_ref().ok_or(SynthesisError::AssignmentMissing)
         })?;
 
@@ -155,3 +245,328 @@ where
         Ok(())
     }
 }
+
+/// The message, parameters and public keys of `BLSAggregateCircuit`.
+type AggregateInputsVar<SigCurveConfig, FV, CF, const N: usize> = (
+    Vec<UInt8<CF>>,
+    ParametersVar<SigCurveConfig, FV, CF>,
+    [PublicKeyVar<SigCurveConfig, FV, CF>; N],
+);
+
+/// A circuit proving that at least `threshold` of `N` public keys, selected by a bitmap,
+/// signed a message with one aggregate signature, as the quorum check of the folding
+/// circuits but without the folding stack, e.g. to prove a committee signature with Groth16.
+///
+/// The message, parameters and public keys are public inputs, while the bitmap and the
+/// signature are witnesses: the proof shows that a quorum signed, not which of the keys did.
+#[derive(Derivative)]
+#[derivative(Clone(bound = ""))]
+pub struct BLSAggregateCircuit<
+    'a,
+    SigCurveConfig: Bls12Config,
+    FV: FieldVar<BlsSigField<SigCurveConfig>, CF>,
+    CF: PrimeField,
+    const N: usize,
+> {
+    params: Option<Parameters<SigCurveConfig>>,
+    pks: Option<[PublicKey<SigCurveConfig>; N]>,
+    signers: Option<[bool; N]>,
+    msg: &'a [Option<u8>],
+    sig: Option<Signature<SigCurveConfig>>,
+    threshold: usize,
+    _fv: PhantomData<(FV, CF)>,
+}
+
+impl<
+        'a,
+        SigCurveConfig: Bls12Config,
+        FV: FieldVar<BlsSigField<SigCurveConfig>, CF>,
+        CF: PrimeField,
+        const N: usize,
+    > BLSAggregateCircuit<'a, SigCurveConfig, FV, CF, N>
+where
+    for<'b> &'b FV: FieldOpsBounds<'b, BlsSigField<SigCurveConfig>, FV>,
+{
+    /// # Panics
+    ///
+    /// Panics if `threshold` is 0 or greater than `N`.
+    #[must_use]
+    pub fn new(
+        params: Option<Parameters<SigCurveConfig>>,
+        pks: Option<[PublicKey<SigCurveConfig>; N]>,
+        signers: Option<[bool; N]>,
+        msg: &'a [Option<u8>],
+        sig: Option<Signature<SigCurveConfig>>,
+        threshold: usize,
+    ) -> Self {
+        assert!(
+            (1..=N).contains(&threshold),
+            "threshold should be in 1..={}",
+            N
+        );
+
+        Self {
+            params,
+            pks,
+            signers,
+            msg,
+            sig,
+            threshold,
+            _fv: PhantomData,
+        }
+    }
+
+    /// Allocate the public inputs in the order of `generate_constraints`.
+    fn new_inputs(
+        cs: ConstraintSystemRef<CF>,
+        params: Option<&Parameters<SigCurveConfig>>,
+        pks: Option<&[PublicKey<SigCurveConfig>; N]>,
+        msg: &[Option<u8>],
+    ) -> Result<AggregateInputsVar<SigCurveConfig, FV, CF, N>, SynthesisError> {
+        let msg_var = msg
+            .iter()
+            .map(|b| UInt8::new_input(cs.clone(), || b.ok_or(SynthesisError::AssignmentMissing)))
+            .collect::<Result<_, _>>()?;
+        let params_var = ParametersVar::new_input(cs.clone(), || {
+            params.ok_or(SynthesisError::AssignmentMissing)
+        })?;
+        let pks_var = <[PublicKeyVar<SigCurveConfig, FV, CF>; N]>::new_input(cs, || {
+            pks.ok_or(SynthesisError::AssignmentMissing)
+        })?;
+
+        Ok((msg_var, params_var, pks_var))
+    }
+
+    /// The public inputs of the circuit for `msg` under `pks`, which a verifier computes
+    /// without the bitmap of the signers or the signature.
+    pub fn public_inputs_from_parts(
+        params: &Parameters<SigCurveConfig>,
+        pks: &[PublicKey<SigCurveConfig>; N],
+        msg: &[u8],
+    ) -> Result<Vec<CF>, SynthesisError> {
+        let msg: Vec<_> = msg.iter().copied().map(Some).collect();
+        Self::new_public_inputs(Some(params), Some(pks), &msg)
+    }
+
+    pub fn get_public_inputs(&self) -> Result<Vec<CF>, SynthesisError> {
+        Self::new_public_inputs(self.params.as_ref(), self.pks.as_ref(), self.msg)
+    }
+
+    fn new_public_inputs(
+        params: Option<&Parameters<SigCurveConfig>>,
+        pks: Option<&[PublicKey<SigCurveConfig>; N]>,
+        msg: &[Option<u8>],
+    ) -> Result<Vec<CF>, SynthesisError> {
+        let cs = witness_only_cs();
+        Self::new_inputs(cs.clone(), params, pks, msg)?;
+
+        // `instance_assignment` has a placeholder value at index 0, we need to skip it
+        let mut public_inputs = cs
+            .into_inner()
+            .ok_or(SynthesisError::MissingCS)?
+            .instance_assignment;
+        public_inputs.remove(0);
+
+        Ok(public_inputs)
+    }
+}
+
+impl<
+        'b,
+        SigCurveConfig: Bls12Config,
+        FV: FieldVar<BlsSigField<SigCurveConfig>, CF>
+            + FromBaseFieldVarGadget<CF>
+            + ToBaseFieldVarGadget<BlsSigField<SigCurveConfig>, CF>
+            + SqrtGadget<BlsSigField<SigCurveConfig>, CF>,
+        CF: PrimeField,
+        const N: usize,
+    > ConstraintSynthesizer<CF> for BLSAggregateCircuit<'b, SigCurveConfig, FV, CF, N>
+where
+    for<'a> &'a FV: FieldOpsBounds<'a, BlsSigField<SigCurveConfig>, FV>,
+    <SigCurveConfig as Bls12Config>::G2Config: WBConfig,
+
+    HashCurveConfig<SigCurveConfig>: SWCurveConfig,
+    for<'a> &'a HashCurveVar<SigCurveConfig, FV, CF>: FieldOpsBounds<
+        'a,
+        <HashCurveGroup<SigCurveConfig> as CurveGroup>::BaseField,
+        HashCurveVar<SigCurveConfig, FV, CF>,
+    >,
+    HashCurveVar<SigCurveConfig, FV, CF>:
+        FieldVar<<HashCurveGroup<SigCurveConfig> as CurveGroup>::BaseField, CF>,
+    HashCurveGroup<SigCurveConfig>: CofactorGadget<HashCurveVar<SigCurveConfig, FV, CF>, CF>,
+    SigCurveConfig: Bls12SubgroupGadget<FV, CF>,
+{
+    fn generate_constraints(self, cs: ConstraintSystemRef<CF>) -> Result<(), SynthesisError> {
+        let (msg_var, params_var, pks_var) = Self::new_inputs(
+            cs.clone(),
+            self.params.as_ref(),
+            self.pks.as_ref(),
+            self.msg,
+        )?;
+        let sig_var = SignatureVar::new_witness(cs.clone(), || {
+            self.sig.as_ref().ok_or(SynthesisError::AssignmentMissing)
+        })?;
+        let signers_var = <[Boolean<CF>; N]>::new_witness(cs, || {
+            self.signers.ok_or(SynthesisError::AssignmentMissing)
+        })?;
+
+        // 1. aggregate the public keys of the signers, with complete additions as the
+        // keys are not known to be distinct
+        let mut pub_key = G1Var::<SigCurveConfig, FV, CF>::zero();
+        for (pk, signed) in pks_var.iter().zip(&signers_var) {
+            pub_key = signed.select(&(pub_key.clone() + &pk.pub_key), &pub_key)?;
+        }
+
+        // 2. check the aggregate signature
+        BLSAggregateSignatureVerifyGadget::<SigCurveConfig, FV, CF>::verify(
+            &params_var,
+            &PublicKeyVar { pub_key },
+            &msg_var,
+            &sig_var,
+        )?;
+
+        // 3. check that at least `threshold` keys signed, where the count is far below
+        // `(p - 1) / 2` as `enforce_cmp` requires
+        let count = signers_var.iter().fold(FpVar::zero(), |count, signed| {
+            count + FpVar::from(signed.clone())
+        });
+        count.enforce_cmp(
+            &FpVar::constant(CF::from(self.threshold as u64)),
+            Ordering::Greater,
+            true,
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ark_ff::ToConstraintField;
+    use ark_r1cs_std::fields::fp::FpVar;
+    use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem};
+    use blake2::{Blake2s256, Digest};
+
+    use crate::{
+        bls::{get_bls_instance, Parameters, PublicKey, SecretKey, Signature},
+        params::BlsSigField,
+        snark::generate_witness_only,
+    };
+
+    use super::{BLSAggregateCircuit, BLSCircuit, MessageMode};
+
+    type BlsSigConfig = ark_bls12_381::Config;
+    type CF = BlsSigField<BlsSigConfig>;
+
+    #[test]
+    fn check_message_modes() {
+        let (msg, params, _, pk, sig) = get_bls_instance::<BlsSigConfig>();
+        let msg_bytes: Vec<_> = msg.as_bytes().iter().copied().map(Some).collect();
+
+        let mut public_inputs = vec![];
+        for msg_mode in [
+            MessageMode::Bytes,
+            MessageMode::Packed,
+            MessageMode::Constant,
+        ] {
+            let circuit = BLSCircuit::<BlsSigConfig, FpVar<CF>, CF>::new(
+                Some(params),
+                Some(pk),
+                &msg_bytes,
+                Some(sig),
+            )
+            .msg_mode(msg_mode);
+
+            let cs = ConstraintSystem::new_ref();
+            circuit.clone().generate_constraints(cs.clone()).unwrap();
+            assert!(cs.is_satisfied().unwrap());
+
+            let inputs = circuit.get_public_inputs().unwrap();
+            assert_eq!(cs.borrow().unwrap().instance_assignment[1..], inputs[..]);
+
+            // the witness-only path assigns the same variables
+            let assignment = generate_witness_only(circuit.clone()).unwrap();
+            assert_eq!(assignment.instance, inputs);
+            assert_eq!(assignment.witness, cs.borrow().unwrap().witness_assignment);
+            assert_eq!(
+                BLSCircuit::<BlsSigConfig, FpVar<CF>, CF>::public_inputs_from_parts(
+                    &params,
+                    &pk,
+                    msg.as_bytes(),
+                    msg_mode
+                )
+                .unwrap(),
+                inputs
+            );
+            public_inputs.push(inputs);
+        }
+
+        let [bytes, packed, constant] = &public_inputs[..] else {
+            unreachable!()
+        };
+        let message_inputs = |inputs: &Vec<CF>| inputs.len() + msg.len() - bytes.len();
+        assert_eq!(
+            packed[..message_inputs(packed)],
+            ToConstraintField::<CF>::to_field_elements(msg.as_bytes()).unwrap()
+        );
+        assert_eq!(
+            constant[..message_inputs(constant)],
+            ToConstraintField::<CF>::to_field_elements(&Blake2s256::digest(msg)[..]).unwrap()
+        );
+    }
+
+    #[test]
+    fn check_aggregate_circuit() {
+        const N: usize = 4;
+
+        let params = Parameters::<BlsSigConfig>::setup();
+        let secret_keys: [SecretKey<BlsSigConfig>; N] =
+            std::array::from_fn(|_| SecretKey::new(&mut rand::thread_rng()));
+        let pks = secret_keys.each_ref().map(|sk| PublicKey::new(sk, &params));
+        let signers = [true, false, true, true];
+        let msg = b"committee";
+        let sig = Signature::aggregate_sign(
+            msg,
+            &secret_keys
+                .iter()
+                .zip(signers)
+                .filter(|(_, signed)| *signed)
+                .map(|(sk, _)| sk.clone())
+                .collect::<Vec<_>>(),
+            &params,
+        )
+        .unwrap();
+        let msg: Vec<_> = msg.iter().copied().map(Some).collect();
+
+        // 3 keys signed, and a bitmap selecting other keys does not match the signature
+        for (signers, threshold, valid) in [
+            (signers, 3, true),
+            (signers, 4, false),
+            ([true, true, false, true], 3, false),
+        ] {
+            let circuit = BLSAggregateCircuit::<BlsSigConfig, FpVar<CF>, CF, N>::new(
+                Some(params),
+                Some(pks),
+                Some(signers),
+                &msg,
+                Some(sig),
+                threshold,
+            );
+
+            let cs = ConstraintSystem::new_ref();
+            circuit.clone().generate_constraints(cs.clone()).unwrap();
+            assert_eq!(cs.is_satisfied().unwrap(), valid);
+            assert_eq!(
+                cs.borrow().unwrap().instance_assignment[1..],
+                circuit.get_public_inputs().unwrap()[..]
+            );
+            assert_eq!(
+                BLSAggregateCircuit::<BlsSigConfig, FpVar<CF>, CF, N>::public_inputs_from_parts(
+                    &params,
+                    &pks,
+                    b"committee"
+                )
+                .unwrap(),
+                circuit.get_public_inputs().unwrap()
+            );
+        }
+    }
+}
@@ -0,0 +1,288 @@
+//! Arithmetic on secret material.
+//!
+//! `Projective::mul` is double-and-add over the bits of the scalar that skips leading
+//! zeros, so the time it takes depends on the secret key. With the `ct` feature, every
+//! multiplication by a secret scalar goes through a Montgomery ladder instead, which
+//! performs the same sequence of group operations for every scalar, and secret keys are
+//! compared in constant time with `subtle`.
+//!
+//! The ladder is only as constant-time as the field arithmetic of arkworks, which is
+//! written without secret-dependent branches but has not been audited for it.
+use ark_ec::short_weierstrass::{Projective, SWCurveConfig};
+#[cfg(not(feature = "ct"))]
+use core::ops::Mul;
+#[cfg(feature = "ct")]
+use {
+    super::{threshold::SecretKeyShare, SecretKey},
+    ark_ec::{bls12::Bls12Config, AdditiveGroup},
+    ark_ff::{BigInteger, Field, PrimeField},
+    ark_serialize::CanonicalSerialize,
+    ark_std::vec,
+    subtle::{Choice, ConstantTimeEq},
+    zeroize::Zeroize,
+};
+
+/// Compute `scalar * point` where `scalar` is secret (e.g. a secret key or a blinding factor).
+pub(crate) fn mul_secret<P: SWCurveConfig>(
+    point: &Projective<P>,
+    scalar: &P::ScalarField,
+) -> Projective<P> {
+    #[cfg(feature = "ct")]
+    {
+        ladder(point, scalar)
+    }
+    #[cfg(not(feature = "ct"))]
+    {
+        point.mul(scalar)
+    }
+}
+
+/// `a + b` over little-endian bit vectors, truncated to `len` bits.
+#[cfg(feature = "ct")]
+fn add_bits(a: &[u8], b: &[u8], len: usize) -> Vec<u8> {
+    let mut carry = 0u8;
+    (0..len)
+        .map(|i| {
+            let (x, y) = (
+                a.get(i).copied().unwrap_or(0),
+                b.get(i).copied().unwrap_or(0),
+            );
+            let sum = x ^ y ^ carry;
+            carry = (x & y) | (carry & (x ^ y));
+            sum
+        })
+        .collect()
+}
+
+/// The bits of `scalar + r` or `scalar + 2r` (`r` being the group order), whichever has bit
+/// `MODULUS_BIT_SIZE` set, little-endian.
+///
+/// Both are congruent to `scalar`, but the result always has the same length and top bit,
+/// so the ladder never starts from (or adds) the identity, which arkworks special-cases.
+#[cfg(feature = "ct")]
+fn fixed_length_bits<F: PrimeField>(scalar: &F) -> Vec<u8> {
+    let len = F::MODULUS_BIT_SIZE as usize + 1;
+    let scalar: Vec<u8> = scalar
+        .into_bigint()
+        .to_bits_le()
+        .into_iter()
+        .map(u8::from)
+        .collect();
+    let modulus: Vec<u8> = F::MODULUS.to_bits_le().into_iter().map(u8::from).collect();
+
+    let plus_r = add_bits(&scalar, &modulus, len);
+    let plus_2r = add_bits(&plus_r, &modulus, len);
+    // 1 if `scalar + r` is too short, in which case `scalar + 2r` has the right length
+    let use_2r = 1 - plus_r[len - 1];
+    plus_r
+        .iter()
+        .zip(&plus_2r)
+        .map(|(a, b)| (a & (1 - use_2r)) | (b & use_2r))
+        .collect()
+}
+
+/// Swap `a` and `b` if `bit == 1`, without branching on `bit`.
+#[cfg(feature = "ct")]
+fn conditional_swap<F: Field>(a: &mut F, b: &mut F, bit: u8) {
+    let delta = (*b - *a) * F::from(u64::from(bit));
+    *a += delta;
+    *b -= delta;
+}
+
+#[cfg(feature = "ct")]
+fn conditional_swap_points<P: SWCurveConfig>(
+    a: &mut Projective<P>,
+    b: &mut Projective<P>,
+    bit: u8,
+) {
+    conditional_swap(&mut a.x, &mut b.x, bit);
+    conditional_swap(&mut a.y, &mut b.y, bit);
+    conditional_swap(&mut a.z, &mut b.z, bit);
+}
+
+/// Montgomery ladder: one addition and one doubling per bit, keeping `r1 = r0 + point`.
+#[cfg(feature = "ct")]
+fn ladder<P: SWCurveConfig>(point: &Projective<P>, scalar: &P::ScalarField) -> Projective<P> {
+    let mut bits = fixed_length_bits(scalar);
+
+    // the top bit is always set
+    let mut r0 = *point;
+    let mut r1 = point.double();
+    for &bit in bits.iter().rev().skip(1) {
+        conditional_swap_points(&mut r0, &mut r1, bit);
+        r1 += r0;
+        r0.double_in_place();
+        conditional_swap_points(&mut r0, &mut r1, bit);
+    }
+
+    bits.zeroize();
+    r0
+}
+
+#[cfg(feature = "ct")]
+impl<SigCurveConfig: Bls12Config> ConstantTimeEq for SecretKey<SigCurveConfig> {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        let mut bytes = vec![];
+        let mut other_bytes = vec![];
+        self.secret_key
+            .serialize_compressed(&mut bytes)
+            .expect("serializing a scalar into a vec should succeed");
+        other
+            .secret_key
+            .serialize_compressed(&mut other_bytes)
+            .expect("serializing a scalar into a vec should succeed");
+
+        let result = bytes.ct_eq(&other_bytes);
+        bytes.zeroize();
+        other_bytes.zeroize();
+        result
+    }
+}
+
+#[cfg(feature = "ct")]
+impl<SigCurveConfig: Bls12Config> PartialEq for SecretKey<SigCurveConfig> {
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_eq(other).into()
+    }
+}
+
+#[cfg(feature = "ct")]
+impl<SigCurveConfig: Bls12Config> Eq for SecretKey<SigCurveConfig> {}
+
+#[cfg(feature = "ct")]
+impl<SigCurveConfig: Bls12Config> ConstantTimeEq for SecretKeyShare<SigCurveConfig> {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.index.ct_eq(&other.index) & self.share.ct_eq(&other.share)
+    }
+}
+
+#[cfg(feature = "ct")]
+impl<SigCurveConfig: Bls12Config> PartialEq for SecretKeyShare<SigCurveConfig> {
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_eq(other).into()
+    }
+}
+
+#[cfg(feature = "ct")]
+impl<SigCurveConfig: Bls12Config> Eq for SecretKeyShare<SigCurveConfig> {}
+
+#[cfg(all(test, feature = "ct"))]
+mod test {
+    use std::time::Instant;
+
+    use ark_ec::PrimeGroup;
+    use ark_ff::{One, UniformRand, Zero};
+    use rand::{thread_rng, Rng};
+
+    use crate::bls::{params::SecretKeyScalarField, HashedMessage, Parameters, Signature};
+
+    use super::*;
+
+    type SigCurveConfig = ark_bls12_381::Config;
+    type ScalarField = SecretKeyScalarField<SigCurveConfig>;
+
+    #[test]
+    fn check_ladder_matches_mul() {
+        let mut rng = thread_rng();
+        let g1 = ark_bls12_381::G1Projective::generator();
+        let g2 = ark_bls12_381::G2Projective::rand(&mut rng);
+
+        let scalars = [
+            ScalarField::zero(),
+            ScalarField::one(),
+            -ScalarField::one(),
+            ScalarField::from(2u64),
+            ScalarField::rand(&mut rng),
+            ScalarField::rand(&mut rng),
+        ];
+        for scalar in scalars {
+            assert_eq!(ladder(&g1, &scalar), g1 * scalar);
+            assert_eq!(ladder(&g2, &scalar), g2 * scalar);
+        }
+    }
+
+    #[test]
+    fn check_secret_key_ct_eq() {
+        let mut rng = thread_rng();
+        let sk = SecretKey::<SigCurveConfig>::new(&mut rng);
+        let other = SecretKey::new(&mut rng);
+
+        assert!(bool::from(sk.ct_eq(&sk.clone())));
+        assert!(!bool::from(sk.ct_eq(&other)));
+        assert_eq!(sk, sk.clone());
+        assert_ne!(sk, other);
+
+        let share = SecretKeyShare {
+            index: 1,
+            share: sk.clone(),
+        };
+        assert_eq!(share, share.clone());
+        assert_ne!(
+            share,
+            SecretKeyShare {
+                index: 2,
+                share: sk
+            }
+        );
+    }
+
+    /// Welch's t-statistic between the two classes, after dropping the slowest 10% of
+    /// measurements (as dudect does) to reduce the noise of interrupts and context switches.
+    fn welch_t(measurements: &[(usize, f64)]) -> f64 {
+        let mut sorted: Vec<_> = measurements.iter().map(|(_, t)| *t).collect();
+        sorted.sort_by(f64::total_cmp);
+        let cutoff = sorted[sorted.len() * 9 / 10];
+
+        let stats = |class: usize| {
+            let samples: Vec<_> = measurements
+                .iter()
+                .filter(|(c, t)| *c == class && *t <= cutoff)
+                .map(|(_, t)| *t)
+                .collect();
+            let n = samples.len() as f64;
+            let mean = samples.iter().sum::<f64>() / n;
+            let var = samples.iter().map(|t| (t - mean).powi(2)).sum::<f64>() / (n - 1.0);
+            (n, mean, var)
+        };
+        let (n0, mean0, var0) = stats(0);
+        let (n1, mean1, var1) = stats(1);
+        (mean0 - mean1) / (var0 / n0 + var1 / n1).sqrt()
+    }
+
+    /// dudect-style check: signing with a low-weight key (class 0) and with random keys
+    /// (class 1) must not be distinguishable by timing.
+    #[test]
+    #[ignore = "timing measurements are noisy, run on an idle machine in release mode"]
+    fn check_sign_timing() {
+        const MEASUREMENTS: usize = 4000;
+        // dudect reports a probable leak above this value
+        const THRESHOLD: f64 = 4.5;
+
+        let mut rng = thread_rng();
+        let params = Parameters::<SigCurveConfig>::setup();
+        let hashed_message = HashedMessage::<SigCurveConfig>::new(b"timing", &params);
+        let fixed_key = SecretKey {
+            secret_key: ScalarField::one(),
+        };
+
+        let measurements: Vec<_> = (0..MEASUREMENTS)
+            .map(|_| {
+                let class = rng.gen_range(0..2);
+                let secret_key = if class == 0 {
+                    fixed_key.clone()
+                } else {
+                    SecretKey::new(&mut rng)
+                };
+
+                let start = Instant::now();
+                let signature = Signature::sign_prehashed(&hashed_message, &secret_key, &params);
+                let elapsed = start.elapsed().as_secs_f64();
+                core::hint::black_box(signature);
+                (class, elapsed)
+            })
+            .collect();
+
+        let t = welch_t(&measurements);
+        assert!(t.abs() < THRESHOLD, "timing leak detected: t = {t}");
+    }
+}
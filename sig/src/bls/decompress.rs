@@ -0,0 +1,275 @@
+//! Decompression of public keys and signatures inside the circuit, from the ZCash compressed
+//! encoding that `ark_bls12_381` serializes points to, and that beacon-chain blocks carry
+//! (see `eth`).
+//!
+//! The three most significant bits of the first byte are flags: the encoding is compressed,
+//! the point is the identity, and `y` is the lexicographically largest of its two candidates.
+//! The rest are the big-endian bytes of `x`, with `x.c1` before `x.c0` on G2.
+//!
+//! As for points allocated as inputs, the decompressed points are on the curve but are not
+//! checked to be in the prime-order subgroup, which `ParametersVar::subgroup_check` adds.
+use ark_bls12_381::{g1, g2, Config, Fq, Fq2Config, G1Affine, G2Affine};
+use ark_ec::short_weierstrass::SWCurveConfig;
+use ark_ff::{BitIteratorBE, PrimeField};
+use ark_r1cs_std::{
+    alloc::AllocVar,
+    convert::{ToBitsGadget, ToBytesGadget},
+    eq::EqGadget,
+    fields::{fp2::Fp2Var, FieldOpsBounds, FieldVar},
+    groups::{
+        bls12::{G1Var, G2Var},
+        curves::short_weierstrass::ProjectiveVar,
+        CurveVar,
+    },
+    prelude::Boolean,
+    uint8::UInt8,
+    R1CSVar,
+};
+use ark_relations::r1cs::SynthesisError;
+use ark_serialize::CanonicalDeserialize;
+use ark_std::{marker::PhantomData, vec, vec::Vec};
+
+use crate::hash::map_to_curve::sqrt::SqrtGadget;
+
+use super::{
+    eth::{PUBLIC_KEY_SIZE, SIGNATURE_SIZE},
+    PublicKeyVar, SignatureVar,
+};
+
+/// The bytes of an element of `Fq`.
+const FQ_BYTES: usize = PUBLIC_KEY_SIZE;
+
+impl<FV: FieldVar<Fq, CF> + SqrtGadget<Fq, CF>, CF: PrimeField> PublicKeyVar<Config, FV, CF>
+where
+    for<'a> &'a FV: FieldOpsBounds<'a, Fq, FV>,
+{
+    /// Decompress a public key from the 48 bytes of its compressed encoding, as given by
+    /// `PublicKey::to_compressed_bytes`.
+    pub fn from_compressed_bytes(bytes: &[UInt8<CF>]) -> Result<Self, SynthesisError> {
+        if bytes.len() != PUBLIC_KEY_SIZE {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+        if bytes.is_constant() {
+            let point = G1Affine::deserialize_compressed_unchecked(bytes.value()?.as_slice())
+                .map_err(|_| SynthesisError::Unsatisfiable)?;
+            return Ok(Self {
+                pub_key: G1Var::constant(point.into()),
+            });
+        }
+
+        let (is_infinity, is_largest, x) = split_flags(bytes)?;
+        Ok(Self {
+            pub_key: decompress::<g1::Config, _, _>(
+                fq_from_be_bytes(&x)?,
+                &is_infinity,
+                &is_largest,
+                is_lexicographically_largest,
+            )?,
+        })
+    }
+}
+
+impl<FV: FieldVar<Fq, CF> + SqrtGadget<Fq, CF>, CF: PrimeField> SignatureVar<Config, FV, CF>
+where
+    for<'a> &'a FV: FieldOpsBounds<'a, Fq, FV>,
+{
+    /// Decompress a signature from the 96 bytes of its compressed encoding, as given by
+    /// `eth::signature_to_bytes`.
+    pub fn from_compressed_bytes(bytes: &[UInt8<CF>]) -> Result<Self, SynthesisError> {
+        if bytes.len() != SIGNATURE_SIZE {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+        if bytes.is_constant() {
+            let point = G2Affine::deserialize_compressed_unchecked(bytes.value()?.as_slice())
+                .map_err(|_| SynthesisError::Unsatisfiable)?;
+            return Ok(Self {
+                signature: G2Var::constant(point.into()),
+                _hasher: PhantomData,
+            });
+        }
+
+        let (is_infinity, is_largest, x) = split_flags(bytes)?;
+        let (c1, c0) = x.split_at(FQ_BYTES);
+        let x = Fp2Var::<Fq2Config, FV, CF>::new(fq_from_be_bytes(c0)?, fq_from_be_bytes(c1)?);
+        Ok(Self {
+            signature: decompress::<g2::Config, _, _>(x, &is_infinity, &is_largest, |y| {
+                // compare `y.c1` first, and `y.c0` only if `y.c1` is 0
+                Ok(is_lexicographically_largest(&y.c1)?
+                    | (y.c1.is_zero()? & is_lexicographically_largest(&y.c0)?))
+            })?,
+            _hasher: PhantomData,
+        })
+    }
+}
+
+/// Enforce that `bytes` is a compressed encoding, and return its infinity and sign flags
+/// along with the bytes of `x`, whose flags are cleared.
+fn split_flags<CF: PrimeField>(
+    bytes: &[UInt8<CF>],
+) -> Result<(Boolean<CF>, Boolean<CF>, Vec<UInt8<CF>>), SynthesisError> {
+    let mut bits = bytes[0].to_bits_le()?;
+    bits[7].enforce_equal(&Boolean::TRUE)?;
+    let (is_infinity, is_largest) = (bits[6].clone(), bits[5].clone());
+    bits[5..].fill(Boolean::FALSE);
+
+    let mut x = vec![UInt8::from_bits_le(&bits)];
+    x.extend_from_slice(&bytes[1..]);
+    Ok((is_infinity, is_largest, x))
+}
+
+/// Allocate the element of `Fq` whose big-endian encoding is `bytes`, which must be canonical.
+fn fq_from_be_bytes<FV: FieldVar<Fq, CF>, CF: PrimeField>(
+    bytes: &[UInt8<CF>],
+) -> Result<FV, SynthesisError> {
+    let value = FV::new_witness(bytes.cs(), || {
+        Ok(Fq::from_be_bytes_mod_order(&bytes.value()?))
+    })?;
+
+    // `to_bytes_le` is canonical, so a non-reduced encoding is rejected
+    let mut le_bytes = bytes.to_vec();
+    le_bytes.reverse();
+    value.to_bytes_le()?[..].enforce_equal(&le_bytes)?;
+    Ok(value)
+}
+
+/// Whether `y > (p - 1) / 2`, i.e. `y` is the largest of `y` and `-y`.
+fn is_lexicographically_largest<FV: FieldVar<Fq, CF>, CF: PrimeField>(
+    y: &FV,
+) -> Result<Boolean<CF>, SynthesisError> {
+    let mut bits = y.to_bits_le()?;
+    bits.resize(FQ_BYTES * 8, Boolean::FALSE);
+
+    // compare the bits from the most significant one, while `y` equals the bound so far
+    let mut is_greater = Boolean::FALSE;
+    let mut is_equal = Boolean::TRUE;
+    for (bit, bound_bit) in bits
+        .iter()
+        .rev()
+        .zip(BitIteratorBE::new(Fq::MODULUS_MINUS_ONE_DIV_TWO))
+    {
+        if bound_bit {
+            is_equal &= bit;
+        } else {
+            is_greater |= &is_equal & bit;
+            is_equal &= !bit;
+        }
+    }
+    Ok(is_greater)
+}
+
+/// The point with `x` whose `y` is the largest root of `x^3 + b` if `is_largest` is set
+/// and the smallest otherwise, or the identity, encoded with `x = 0` and `is_largest` unset,
+/// if `is_infinity` is set.
+fn decompress<P, F, CF>(
+    x: F,
+    is_infinity: &Boolean<CF>,
+    is_largest: &Boolean<CF>,
+    is_lexicographically_largest: impl Fn(&F) -> Result<Boolean<CF>, SynthesisError>,
+) -> Result<ProjectiveVar<P, F, CF>, SynthesisError>
+where
+    P: SWCurveConfig,
+    F: FieldVar<P::BaseField, CF> + SqrtGadget<P::BaseField, CF>,
+    CF: PrimeField,
+    for<'a> &'a F: FieldOpsBounds<'a, P::BaseField, F>,
+{
+    x.conditional_enforce_equal(&F::zero(), is_infinity)?;
+    is_largest.conditional_enforce_equal(&Boolean::FALSE, is_infinity)?;
+
+    // `a` is 0 on both curves of BLS12
+    let (is_on_curve, y) = (x.square()? * &x + P::COEFF_B).sqrt()?;
+    is_on_curve.conditional_enforce_equal(&Boolean::TRUE, &!is_infinity)?;
+    let y = is_lexicographically_largest(&y)?
+        .is_eq(is_largest)?
+        .select(&y, &y.negate()?)?;
+
+    // the identity is (0, 1, 0) in projective coordinates
+    Ok(ProjectiveVar::new(
+        x,
+        is_infinity.select(&F::one(), &y)?,
+        is_infinity.select(&F::zero(), &F::one())?,
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use ark_bls12_381::{Config, Fq};
+    use ark_r1cs_std::{fields::fp::FpVar, uint8::UInt8, R1CSVar};
+    use ark_relations::r1cs::ConstraintSystem;
+    use rand::thread_rng;
+
+    use crate::bls::{
+        eth::{public_key_to_bytes, signature_to_bytes},
+        Parameters, PublicKey, PublicKeyVar, SecretKey, Signature, SignatureVar,
+    };
+
+    type FV = FpVar<Fq>;
+
+    #[test]
+    fn check_decompress_public_key() {
+        let params = Parameters::<Config>::setup();
+        let sk = SecretKey::new(&mut thread_rng());
+        let pk = PublicKey::new(&sk, &params);
+
+        for pk in [pk, PublicKey::default()] {
+            let bytes = public_key_to_bytes(&pk);
+
+            let cs = ConstraintSystem::<Fq>::new_ref();
+            let bytes_var = UInt8::new_witness_vec(cs.clone(), &bytes).unwrap();
+            let pk_var = PublicKeyVar::<Config, FV, Fq>::from_compressed_bytes(&bytes_var).unwrap();
+            assert_eq!(pk_var.pub_key.value().unwrap(), pk.pub_key);
+            assert!(cs.is_satisfied().unwrap());
+
+            let pk_var =
+                PublicKeyVar::<Config, FV, Fq>::from_compressed_bytes(&UInt8::constant_vec(&bytes))
+                    .unwrap();
+            assert_eq!(pk_var.pub_key.value().unwrap(), pk.pub_key);
+        }
+    }
+
+    #[test]
+    fn check_decompress_signature() {
+        let params = Parameters::<Config>::setup();
+        let sk = SecretKey::new(&mut thread_rng());
+        let sig = Signature::sign(b"Hello World", &sk, &params);
+
+        for sig in [sig, Signature::default()] {
+            let bytes = signature_to_bytes(&sig);
+
+            let cs = ConstraintSystem::<Fq>::new_ref();
+            let bytes_var = UInt8::new_witness_vec(cs.clone(), &bytes).unwrap();
+            let sig_var =
+                SignatureVar::<Config, FV, Fq>::from_compressed_bytes(&bytes_var).unwrap();
+            assert_eq!(sig_var.signature.value().unwrap(), sig.signature);
+            assert!(cs.is_satisfied().unwrap());
+        }
+    }
+
+    #[test]
+    fn check_decompress_flags() {
+        let params = Parameters::<Config>::setup();
+        let sk = SecretKey::new(&mut thread_rng());
+        let pk = PublicKey::new(&sk, &params);
+        let bytes = public_key_to_bytes(&pk);
+
+        // flipping the sign flag gives the negation of the key
+        let mut negated = bytes;
+        negated[0] ^= 1 << 5;
+        let cs = ConstraintSystem::<Fq>::new_ref();
+        let bytes_var = UInt8::new_witness_vec(cs.clone(), &negated).unwrap();
+        let pk_var = PublicKeyVar::<Config, FV, Fq>::from_compressed_bytes(&bytes_var).unwrap();
+        assert_eq!(pk_var.pub_key.value().unwrap(), -pk.pub_key);
+        assert!(cs.is_satisfied().unwrap());
+
+        // uncompressed encodings, and identities with a non-zero `x`, are rejected
+        for flag in [1 << 7, 1 << 6] {
+            let mut invalid = bytes;
+            invalid[0] ^= flag;
+            let cs = ConstraintSystem::<Fq>::new_ref();
+            let bytes_var = UInt8::new_witness_vec(cs.clone(), &invalid).unwrap();
+            PublicKeyVar::<Config, FV, Fq>::from_compressed_bytes(&bytes_var).unwrap();
+            assert!(!cs.is_satisfied().unwrap());
+        }
+
+        assert!(PublicKeyVar::<Config, FV, Fq>::from_compressed_bytes(&[]).is_err());
+    }
+}
@@ -0,0 +1,292 @@
+//! serde encodings of keys and signatures.
+//!
+//! Binary serializers (e.g. bincode, which is used to compute block digests) get the
+//! uncompressed affine coordinates followed by the infinity flag, which is the layout
+//! that `SerializeGadget` reproduces inside the circuit.
+//!
+//! With the `serde` feature, human-readable serializers such as JSON get the compressed
+//! point as a hex string instead, and keys and signatures can also be deserialized.
+use ark_ec::{
+    bls12::Bls12Config,
+    short_weierstrass::{Projective, SWCurveConfig},
+    CurveGroup,
+};
+use ark_serialize::CanonicalSerialize;
+use ark_std::{vec, vec::Vec};
+use serde::{ser::SerializeTuple, Serialize, Serializer};
+#[cfg(feature = "serde")]
+use {
+    super::SecretKey,
+    ark_ec::short_weierstrass::Affine,
+    ark_ff::AdditiveGroup,
+    ark_serialize::{CanonicalDeserialize, Valid},
+    core::fmt,
+    serde::{
+        de::{self, SeqAccess, Visitor},
+        Deserialize, Deserializer,
+    },
+    zeroize::Zeroize,
+};
+
+use super::{PublicKey, Signature, SignatureVariant};
+
+fn serialize_bytes<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+    #[cfg(feature = "serde")]
+    if serializer.is_human_readable() {
+        return serializer.serialize_str(&to_hex(bytes));
+    }
+
+    // The length of the struct is static, so it's safe to use this
+    let mut seq = serializer.serialize_tuple(bytes.len())?;
+    for b in bytes {
+        seq.serialize_element(b)?;
+    }
+    seq.end()
+}
+
+fn serialize_curve_point<P: SWCurveConfig, S: Serializer>(
+    point: &Projective<P>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let affine = point.into_affine();
+    let mut bytes = vec![];
+
+    #[cfg(feature = "serde")]
+    if serializer.is_human_readable() {
+        affine
+            .serialize_compressed(&mut bytes)
+            .map_err(serde::ser::Error::custom)?;
+        return serialize_bytes(&bytes, serializer);
+    }
+
+    affine
+        .x
+        .serialize_uncompressed(&mut bytes)
+        .map_err(serde::ser::Error::custom)?;
+    affine
+        .y
+        .serialize_uncompressed(&mut bytes)
+        .map_err(serde::ser::Error::custom)?;
+    affine
+        .infinity
+        .serialize_uncompressed(&mut bytes)
+        .map_err(serde::ser::Error::custom)?;
+    serialize_bytes(&bytes, serializer)
+}
+
+/// Serialize is implemented manually because it's easy to match it with `SerializeGadget` implementation
+impl<SigCurveConfig: Bls12Config, V: SignatureVariant<SigCurveConfig>> Serialize
+    for PublicKey<SigCurveConfig, V>
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_curve_point(&self.pub_key, serializer)
+    }
+}
+
+/// Serialize is implemented manually because it's easy to match it with `SerializeGadget` implementation
+impl<SigCurveConfig: Bls12Config, V: SignatureVariant<SigCurveConfig>> Serialize
+    for Signature<SigCurveConfig, V>
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_curve_point(&self.signature, serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+fn to_hex(bytes: &[u8]) -> String {
+    use core::fmt::Write;
+
+    bytes
+        .iter()
+        .fold(String::with_capacity(2 * bytes.len()), |mut hex, b| {
+            let _ = write!(hex, "{b:02x}");
+            hex
+        })
+}
+
+#[cfg(feature = "serde")]
+pub(crate) fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    let hex = hex.strip_prefix("0x").unwrap_or(hex);
+    if hex.len() % 2 != 0 {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+/// Reads either a hex string (human-readable formats) or a tuple of `len` bytes.
+#[cfg(feature = "serde")]
+struct BytesVisitor {
+    len: usize,
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Visitor<'de> for BytesVisitor {
+    type Value = Vec<u8>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} bytes", self.len)
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        from_hex(v).ok_or_else(|| E::custom("invalid hex string"))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut bytes = Vec::with_capacity(self.len);
+        for i in 0..self.len {
+            bytes.push(
+                seq.next_element()?
+                    .ok_or_else(|| de::Error::invalid_length(i, &self))?,
+            );
+        }
+        Ok(bytes)
+    }
+}
+
+#[cfg(feature = "serde")]
+fn deserialize_bytes<'de, D: Deserializer<'de>>(
+    len: usize,
+    deserializer: D,
+) -> Result<Vec<u8>, D::Error> {
+    if deserializer.is_human_readable() {
+        deserializer.deserialize_str(BytesVisitor { len })
+    } else {
+        deserializer.deserialize_tuple(len, BytesVisitor { len })
+    }
+}
+
+/// Points are checked to be on the curve and in the prime-order subgroup. The identity is
+/// accepted, since it appears in default blocks; verification rejects it separately.
+#[cfg(feature = "serde")]
+fn deserialize_curve_point<'de, P: SWCurveConfig, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Projective<P>, D::Error> {
+    let affine = if deserializer.is_human_readable() {
+        let len = Affine::<P>::identity().compressed_size();
+        let bytes = deserialize_bytes(len, deserializer)?;
+        Affine::<P>::deserialize_compressed(bytes.as_slice()).map_err(de::Error::custom)?
+    } else {
+        let len = 2 * P::BaseField::ZERO.uncompressed_size() + 1;
+        let bytes = deserialize_bytes(len, deserializer)?;
+        let mut reader = bytes.as_slice();
+        let x = P::BaseField::deserialize_uncompressed(&mut reader).map_err(de::Error::custom)?;
+        let y = P::BaseField::deserialize_uncompressed(&mut reader).map_err(de::Error::custom)?;
+        let infinity = bool::deserialize_uncompressed(&mut reader).map_err(de::Error::custom)?;
+
+        let affine = if infinity {
+            Affine::<P>::identity()
+        } else {
+            Affine::<P>::new_unchecked(x, y)
+        };
+        affine.check().map_err(de::Error::custom)?;
+        affine
+    };
+    Ok(affine.into())
+}
+
+#[cfg(feature = "serde")]
+impl<'de, SigCurveConfig: Bls12Config, V: SignatureVariant<SigCurveConfig>> Deserialize<'de>
+    for PublicKey<SigCurveConfig, V>
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(Self {
+            pub_key: deserialize_curve_point(deserializer)?,
+        })
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, SigCurveConfig: Bls12Config, V: SignatureVariant<SigCurveConfig>> Deserialize<'de>
+    for Signature<SigCurveConfig, V>
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(Self {
+            signature: deserialize_curve_point(deserializer)?,
+        })
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<SigCurveConfig: Bls12Config> Serialize for SecretKey<SigCurveConfig> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut bytes = vec![];
+        self.secret_key
+            .serialize_compressed(&mut bytes)
+            .map_err(serde::ser::Error::custom)?;
+        let result = serialize_bytes(&bytes, serializer);
+        bytes.zeroize();
+        result
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, SigCurveConfig: Bls12Config> Deserialize<'de> for SecretKey<SigCurveConfig> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let len = Self::default().compressed_size();
+        let mut bytes = deserialize_bytes(len, deserializer)?;
+        let secret_key = CanonicalDeserialize::deserialize_compressed(bytes.as_slice())
+            .map_err(de::Error::custom);
+        bytes.zeroize();
+        Ok(Self {
+            secret_key: secret_key?,
+        })
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod test {
+    use serde::de::{value, IntoDeserializer};
+
+    use crate::bls::get_bls_instance;
+
+    use super::*;
+
+    type SigCurveConfig = ark_bls12_381::Config;
+
+    #[test]
+    fn check_binary_roundtrip() {
+        let (_, _, sk, pk, sig) = get_bls_instance::<SigCurveConfig>();
+
+        let bytes = bincode::serialize(&pk).unwrap();
+        let decoded: PublicKey<SigCurveConfig> = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(decoded.pub_key, pk.pub_key);
+
+        let bytes = bincode::serialize(&sig).unwrap();
+        let decoded: Signature<SigCurveConfig> = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(decoded.signature, sig.signature);
+
+        let bytes = bincode::serialize(&sk).unwrap();
+        let decoded: SecretKey<SigCurveConfig> = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(decoded.secret_key, sk.secret_key);
+
+        // the identity (used by default blocks) round-trips as well
+        let bytes = bincode::serialize(&Signature::<SigCurveConfig>::default()).unwrap();
+        let decoded: Signature<SigCurveConfig> = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(
+            decoded.signature,
+            Signature::<SigCurveConfig>::default().signature
+        );
+    }
+
+    #[test]
+    fn check_hex_decoding() {
+        let (_, _, _, pk, _) = get_bls_instance::<SigCurveConfig>();
+
+        let hex = to_hex(&pk.to_compressed_bytes());
+        let deserializer: value::StrDeserializer<'_, value::Error> =
+            hex.as_str().into_deserializer();
+        let decoded = PublicKey::<SigCurveConfig>::deserialize(deserializer).unwrap();
+        assert_eq!(decoded.pub_key, pk.pub_key);
+
+        let hex = format!("0x{hex}");
+        let deserializer: value::StrDeserializer<'_, value::Error> =
+            hex.as_str().into_deserializer();
+        assert!(PublicKey::<SigCurveConfig>::deserialize(deserializer).is_ok());
+
+        let deserializer: value::StrDeserializer<'_, value::Error> = "zz".into_deserializer();
+        assert!(PublicKey::<SigCurveConfig>::deserialize(deserializer).is_err());
+    }
+}
@@ -0,0 +1,35 @@
+use core::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BLSError {
+    /// The point does not satisfy the curve equation.
+    PointNotOnCurve,
+    /// The point is the identity, which is never a valid key or signature.
+    PointAtInfinity,
+    /// The point is on the curve but outside the prime-order subgroup.
+    PointNotInSubgroup,
+    /// The bytes are not a valid encoding of a point or scalar.
+    InvalidEncoding,
+    /// The signer index is not part of the committee.
+    UnknownSigner,
+    /// A share from this signer has already been added.
+    DuplicateShare,
+    /// The share does not verify under the signer's public key.
+    InvalidShare,
+}
+
+impl fmt::Display for BLSError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::PointNotOnCurve => write!(f, "point is not on the curve"),
+            Self::PointAtInfinity => write!(f, "point is the identity"),
+            Self::PointNotInSubgroup => write!(f, "point is not in the prime-order subgroup"),
+            Self::InvalidEncoding => write!(f, "invalid encoding"),
+            Self::UnknownSigner => write!(f, "signer is not in the committee"),
+            Self::DuplicateShare => write!(f, "share has already been added"),
+            Self::InvalidShare => write!(f, "share does not verify"),
+        }
+    }
+}
+
+impl ark_std::error::Error for BLSError {}
@@ -0,0 +1,410 @@
+//! BLS signatures as used by the Ethereum consensus layer.
+//!
+//! The beacon chain uses the min-pk variant over BLS12-381 with the
+//! `BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_` ciphersuite of the
+//! [BLS signature draft](https://datatracker.ietf.org/doc/html/draft-irtf-cfrg-bls-signature-05),
+//! and encodes points in the ZCash compressed format. The functions here follow the
+//! [consensus specs](https://github.com/ethereum/consensus-specs/blob/dev/specs/phase0/beacon-chain.md#bls-signatures)
+//! and produce the same bytes as blst and py_ecc, so real beacon-chain keys and
+//! signatures can be imported into the rest of the crate.
+//!
+//! The rest of `crate::bls` hashes with Blake2s, which is much cheaper in R1CS, so signatures
+//! produced here do not verify with `Signature::verify` and vice versa.
+use ark_bls12_381::{Config, G2Affine, G2Projective};
+use ark_ec::{
+    bls12::Bls12,
+    hashing::HashToCurve,
+    pairing::{Pairing, PairingOutput},
+    AffineRepr, CurveGroup,
+};
+use ark_ff::{AdditiveGroup, BigInteger, PrimeField};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::{cfg_iter, vec::Vec};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use crate::hash::hash_to_curve::suites::Bls12381G2XmdSha256SswuRo;
+
+use super::{
+    params::SecretKeyScalarField, AggregatePublicKey, BLSError, HashedMessage, Parameters,
+    PublicKey, SecretKey, Signature,
+};
+
+/// Domain separation tag used for signing.
+pub const DST: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
+/// Domain separation tag used for proofs of possession.
+pub const POP_DST: &[u8] = b"BLS_POP_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
+
+pub const SECRET_KEY_SIZE: usize = 32;
+pub const PUBLIC_KEY_SIZE: usize = 48;
+pub const SIGNATURE_SIZE: usize = 96;
+
+fn hash_to_g2(message: &[u8], dst: &[u8]) -> G2Projective {
+    Bls12381G2XmdSha256SswuRo::new(dst)
+        .and_then(|hasher| hasher.hash(message))
+        .expect("hash to curve should succeed for a non-empty DST")
+        .into()
+}
+
+fn hash_message(message: &[u8]) -> HashedMessage<Config> {
+    HashedMessage {
+        hashed_message: hash_to_g2(message, DST),
+    }
+}
+
+/// Parse a secret key from its 32-byte big-endian encoding. Zero and values that are
+/// not reduced modulo the group order are rejected.
+pub fn secret_key_from_bytes(bytes: &[u8]) -> Result<SecretKey<Config>, BLSError> {
+    if bytes.len() != SECRET_KEY_SIZE {
+        return Err(BLSError::InvalidEncoding);
+    }
+    let mut le_bytes = bytes.to_vec();
+    le_bytes.reverse();
+    let secret_key = SecretKeyScalarField::<Config>::deserialize_compressed(le_bytes.as_slice())
+        .map_err(|_| BLSError::InvalidEncoding)?;
+    if secret_key == SecretKeyScalarField::<Config>::ZERO {
+        return Err(BLSError::InvalidEncoding);
+    }
+    Ok(SecretKey { secret_key })
+}
+
+#[must_use]
+pub fn secret_key_to_bytes(secret_key: &SecretKey<Config>) -> [u8; SECRET_KEY_SIZE] {
+    secret_key
+        .secret_key
+        .into_bigint()
+        .to_bytes_be()
+        .try_into()
+        .expect("scalars of BLS12-381 are 32 bytes long")
+}
+
+/// Parse a compressed public key, performing `KeyValidate`: the point must be on the
+/// curve, in the prime-order subgroup and not the identity.
+pub fn public_key_from_bytes(bytes: &[u8]) -> Result<PublicKey<Config>, BLSError> {
+    PublicKey::from_compressed_bytes(bytes)
+}
+
+#[must_use]
+pub fn public_key_to_bytes(public_key: &PublicKey<Config>) -> [u8; PUBLIC_KEY_SIZE] {
+    public_key
+        .to_compressed_bytes()
+        .try_into()
+        .expect("compressed G1 points are 48 bytes long")
+}
+
+/// Parse a compressed signature. Unlike public keys, the identity is accepted, since the
+/// consensus specs use it as the signature of an empty sync committee aggregate.
+pub fn signature_from_bytes(bytes: &[u8]) -> Result<Signature<Config>, BLSError> {
+    if bytes.len() != SIGNATURE_SIZE {
+        return Err(BLSError::InvalidEncoding);
+    }
+    let point =
+        G2Affine::deserialize_compressed_unchecked(bytes).map_err(|_| BLSError::InvalidEncoding)?;
+    if point.is_zero() {
+        return Ok(Signature::default());
+    }
+    Signature::from_affine_checked(point)
+}
+
+#[must_use]
+pub fn signature_to_bytes(signature: &Signature<Config>) -> [u8; SIGNATURE_SIZE] {
+    let mut bytes = [0u8; SIGNATURE_SIZE];
+    signature
+        .signature
+        .into_affine()
+        .serialize_compressed(bytes.as_mut_slice())
+        .expect("compressed G2 points are 96 bytes long");
+    bytes
+}
+
+#[must_use]
+pub fn sk_to_pk(secret_key: &SecretKey<Config>) -> PublicKey<Config> {
+    PublicKey::new(secret_key, &Parameters::setup())
+}
+
+#[must_use]
+pub fn sign(secret_key: &SecretKey<Config>, message: &[u8]) -> Signature<Config> {
+    Signature::sign_prehashed(&hash_message(message), secret_key, &Parameters::setup())
+}
+
+#[must_use]
+pub fn verify(
+    public_key: &PublicKey<Config>,
+    message: &[u8],
+    signature: &Signature<Config>,
+) -> bool {
+    Signature::verify_prehashed(
+        &hash_message(message),
+        signature,
+        public_key,
+        &Parameters::setup(),
+    )
+}
+
+/// Returns `None` if `signatures` is empty.
+#[must_use]
+pub fn aggregate(signatures: &[Signature<Config>]) -> Option<Signature<Config>> {
+    if signatures.is_empty() {
+        return None;
+    }
+    Some(Signature {
+        signature: signatures.iter().map(|sig| sig.signature).sum(),
+    })
+}
+
+/// Verify an aggregate signature where the i-th signer signs `messages[i]`.
+///
+/// With proofs of possession, messages do not need to be distinct.
+#[must_use]
+pub fn aggregate_verify(
+    public_keys: &[PublicKey<Config>],
+    messages: &[&[u8]],
+    signature: &Signature<Config>,
+) -> bool {
+    if public_keys.is_empty()
+        || public_keys.len() != messages.len()
+        || !public_keys.iter().all(PublicKey::is_well_formed)
+    {
+        return false;
+    }
+
+    // e(g1, sig) == prod_i e(pk_i, H(m_i)), checked as a single product equal to 1
+    let hashed: Vec<_> = cfg_iter!(messages)
+        .map(|message| hash_to_g2(message, DST))
+        .collect();
+    let (g1_side, g2_side): (Vec<_>, Vec<_>) = core::iter::once((
+        -Parameters::<Config>::setup().g1_generator,
+        signature.signature,
+    ))
+    .chain(
+        public_keys
+            .iter()
+            .map(|public_key| public_key.pub_key)
+            .zip(hashed),
+    )
+    .unzip();
+
+    Bls12::<Config>::multi_pairing(g1_side, g2_side) == PairingOutput::ZERO
+}
+
+/// Verify a signature of all `public_keys` on the same `message`.
+#[must_use]
+pub fn fast_aggregate_verify(
+    public_keys: &[PublicKey<Config>],
+    message: &[u8],
+    signature: &Signature<Config>,
+) -> bool {
+    if !public_keys.iter().all(PublicKey::is_well_formed) {
+        return false;
+    }
+    AggregatePublicKey::aggregate(public_keys)
+        .is_some_and(|pk| verify(&pk.to_public_key(), message, signature))
+}
+
+/// `eth_aggregate_pubkeys` from the Altair specs: every key and the aggregate must pass
+/// `KeyValidate`, and the list must not be empty.
+#[must_use]
+pub fn eth_aggregate_pubkeys(public_keys: &[PublicKey<Config>]) -> Option<PublicKey<Config>> {
+    if !public_keys.iter().all(PublicKey::is_well_formed) {
+        return None;
+    }
+    let aggregate = AggregatePublicKey::aggregate(public_keys)?.to_public_key();
+    aggregate.is_well_formed().then_some(aggregate)
+}
+
+/// `eth_fast_aggregate_verify` from the Altair specs, which additionally accepts the
+/// identity signature for an empty set of signers (an empty sync committee aggregate).
+#[must_use]
+pub fn eth_fast_aggregate_verify(
+    public_keys: &[PublicKey<Config>],
+    message: &[u8],
+    signature: &Signature<Config>,
+) -> bool {
+    if public_keys.is_empty() && signature.signature.into_affine().is_zero() {
+        return true;
+    }
+    fast_aggregate_verify(public_keys, message, signature)
+}
+
+/// Sign the compressed public key under `POP_DST`.
+#[must_use]
+pub fn pop_prove(secret_key: &SecretKey<Config>) -> Signature<Config> {
+    let message = public_key_to_bytes(&sk_to_pk(secret_key));
+    Signature::sign_prehashed(
+        &HashedMessage {
+            hashed_message: hash_to_g2(&message, POP_DST),
+        },
+        secret_key,
+        &Parameters::setup(),
+    )
+}
+
+#[must_use]
+pub fn pop_verify(public_key: &PublicKey<Config>, proof: &Signature<Config>) -> bool {
+    Signature::verify_prehashed(
+        &HashedMessage {
+            hashed_message: hash_to_g2(&public_key_to_bytes(public_key), POP_DST),
+        },
+        proof,
+        public_key,
+        &Parameters::setup(),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // Test vectors of `sign` from https://github.com/ethereum/bls12-381-tests
+    // (privkey, pubkey)
+    const KEYS: [(&str, &str); 3] = [
+        (
+            "263dbd792f5b1be47ed85f8938c0f29586af0d3ac7b977f21c278fe1462040e3",
+            "a491d1b0ecd9bb917989f0e74f0dea0422eac4a873e5e2644f368dffb9a6e20fd6e10c1b77654d067c0618f6e5a7f79a",
+        ),
+        (
+            "47b8192d77bf871b62e87859d653922725724a5c031afeabc60bcef5ff665138",
+            "b301803f8b5ac4a1133581fc676dfedc60d891dd5fa99028805e5ea5b08d3491af75d0707adab3b70c6a6a580217bf81",
+        ),
+        (
+            "328388aff0d4a5b7dc9205abd374e7e98f3cd9f3418edb4eafda5fb16473d216",
+            "b53d21a4cfd562c469cc81514d4ce5a6b577d8403d32a394dc265dd190b47fa9f829fdd7963afdf972e5e77854051f6f",
+        ),
+    ];
+
+    const MESSAGES: [[u8; 32]; 3] = [[0x00; 32], [0x56; 32], [0xab; 32]];
+
+    // SIGNATURES[i][j] is the signature of KEYS[i] on MESSAGES[j]
+    const SIGNATURES: [[&str; 3]; 3] = [
+        [
+            "b6ed936746e01f8ecf281f020953fbf1f01debd5657c4a383940b020b26507f6076334f91e2366c96e9ab279fb5158090352ea1c5b0c9274504f4f0e7053af24802e51e4568d164fe986834f41e55c8e850ce1f98458c0cfc9ab380b55285a55",
+            "882730e5d03f6b42c3abc26d3372625034e1d871b65a8a6b900a56dae22da98abbe1b68f85e49fe7652a55ec3d0591c20767677e33e5cbb1207315c41a9ac03be39c2e7668edc043d6cb1d9fd93033caa8a1c5b0e84bedaeb6c64972503a43eb",
+            "91347bccf740d859038fcdcaf233eeceb2a436bcaaee9b2aa3bfb70efe29dfb2677562ccbea1c8e061fb9971b0753c240622fab78489ce96768259fc01360346da5b9f579e5da0d941e4c6ba18a0e64906082375394f337fa1af2b7127b0d121",
+        ],
+        [
+            "b23c46be3a001c63ca711f87a005c200cc550b9429d5f4eb38d74322144f1b63926da3388979e5321012fb1a0526bcd100b5ef5fe72628ce4cd5e904aeaa3279527843fae5ca9ca675f4f51ed8f83bbf7155da9ecc9663100a885d5dc6df96d9",
+            "af1390c3c47acdb37131a51216da683c509fce0e954328a59f93aebda7e4ff974ba208d9a4a2a2389f892a9d418d618418dd7f7a6bc7aa0da999a9d3a5b815bc085e14fd001f6a1948768a3f4afefc8b8240dda329f984cb345c6363272ba4fe",
+            "9674e2228034527f4c083206032b020310face156d4a4685e2fcaec2f6f3665aa635d90347b6ce124eb879266b1e801d185de36a0a289b85e9039662634f2eea1e02e670bc7ab849d006a70b2f93b84597558a05b879c8d445f387a5d5b653df",
+        ],
+        [
+            "948a7cb99f76d616c2c564ce9bf4a519f1bea6b0a624a02276443c245854219fabb8d4ce061d255af5330b078d5380681751aa7053da2c98bae898edc218c75f07e24d8802a17cd1f6833b71e58f5eb5b94208b4d0bb3848cecb075ea21be115",
+            "a4efa926610b8bd1c8330c918b7a5e9bf374e53435ef8b7ec186abf62e1b1f65aeaaeb365677ac1d1172a1f5b44b4e6d022c252c58486c0a759fbdc7de15a756acc4d343064035667a594b4c2a6f0b0b421975977f297dba63ee2f63ffe47bb6",
+            "ae82747ddeefe4fd64cf9cedb9b04ae3e8a43420cd255e3c7cd06a8d88b7c7f8638543719981c5d16fa3527c468c25f0026704a6951bde891360c7e8d12ddee0559004ccdbe6046b55bae1b257ee97f7cdb955773d7cf29adf3ccbb9975e4eb9",
+        ],
+    ];
+
+    fn from_hex(hex: &str) -> Vec<u8> {
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    fn keys() -> Vec<(SecretKey<Config>, PublicKey<Config>)> {
+        KEYS.iter()
+            .map(|(sk, pk)| {
+                (
+                    secret_key_from_bytes(&from_hex(sk)).unwrap(),
+                    public_key_from_bytes(&from_hex(pk)).unwrap(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn check_test_vectors() {
+        for ((sk_hex, pk_hex), (sk, pk)) in KEYS.iter().zip(keys()) {
+            assert_eq!(secret_key_to_bytes(&sk).to_vec(), from_hex(sk_hex));
+            assert_eq!(
+                public_key_to_bytes(&sk_to_pk(&sk)).to_vec(),
+                from_hex(pk_hex)
+            );
+            assert_eq!(sk_to_pk(&sk).pub_key, pk.pub_key);
+        }
+
+        for ((sk, pk), signatures) in keys().iter().zip(SIGNATURES) {
+            for (message, sig_hex) in MESSAGES.iter().zip(signatures) {
+                let sig = sign(sk, message);
+                assert_eq!(signature_to_bytes(&sig).to_vec(), from_hex(sig_hex));
+
+                let decoded = signature_from_bytes(&from_hex(sig_hex)).unwrap();
+                assert!(verify(pk, message, &decoded));
+                assert!(!verify(pk, b"wrong message", &decoded));
+            }
+        }
+    }
+
+    #[test]
+    fn check_aggregate() {
+        let (secret_keys, public_keys): (Vec<_>, Vec<_>) = keys().into_iter().unzip();
+        let message = &MESSAGES[2];
+
+        let signatures: Vec<_> = secret_keys.iter().map(|sk| sign(sk, message)).collect();
+        let sig = aggregate(&signatures).unwrap();
+        assert!(fast_aggregate_verify(&public_keys, message, &sig));
+        assert!(eth_fast_aggregate_verify(&public_keys, message, &sig));
+        assert!(!fast_aggregate_verify(&public_keys[1..], message, &sig));
+        assert!(aggregate_verify(
+            &public_keys,
+            &[message.as_slice(); 3],
+            &sig
+        ));
+
+        let aggregate_pk = eth_aggregate_pubkeys(&public_keys).unwrap();
+        assert!(verify(&aggregate_pk, message, &sig));
+        assert!(eth_aggregate_pubkeys(&[]).is_none());
+        assert!(aggregate(&[]).is_none());
+
+        let messages: Vec<&[u8]> = MESSAGES.iter().map(<[u8; 32]>::as_slice).collect();
+        let signatures: Vec<_> = secret_keys
+            .iter()
+            .zip(&messages)
+            .map(|(sk, message)| sign(sk, message))
+            .collect();
+        let sig = aggregate(&signatures).unwrap();
+        assert!(aggregate_verify(&public_keys, &messages, &sig));
+        assert!(!aggregate_verify(&public_keys[1..], &messages[1..], &sig));
+        assert!(!aggregate_verify(&[], &[], &sig));
+    }
+
+    #[test]
+    fn check_identity() {
+        let mut infinity = [0u8; SIGNATURE_SIZE];
+        infinity[0] = 0xc0;
+
+        // the identity is never a valid public key
+        assert_eq!(
+            public_key_from_bytes(&infinity[..PUBLIC_KEY_SIZE]).unwrap_err(),
+            BLSError::PointAtInfinity
+        );
+
+        // but it is a valid encoding of a signature, accepted for an empty aggregate only
+        let sig = signature_from_bytes(&infinity).unwrap();
+        assert_eq!(signature_to_bytes(&sig), infinity);
+        assert!(eth_fast_aggregate_verify(&[], &MESSAGES[0], &sig));
+        assert!(!fast_aggregate_verify(&[], &MESSAGES[0], &sig));
+        let (_, pk) = &keys()[0];
+        assert!(!eth_fast_aggregate_verify(&[*pk], &MESSAGES[0], &sig));
+
+        assert_eq!(
+            secret_key_from_bytes(&[0u8; SECRET_KEY_SIZE]).unwrap_err(),
+            BLSError::InvalidEncoding
+        );
+        assert_eq!(
+            signature_from_bytes(&infinity[1..]).unwrap_err(),
+            BLSError::InvalidEncoding
+        );
+    }
+
+    #[test]
+    fn check_pop() {
+        let keys = keys();
+        let (sk, pk) = &keys[0];
+        let proof = pop_prove(sk);
+        assert!(pop_verify(pk, &proof));
+        assert!(!pop_verify(&keys[1].1, &proof));
+
+        // a PoP is not a signature on the public key
+        assert!(!verify(pk, &public_key_to_bytes(pk), &proof));
+    }
+}
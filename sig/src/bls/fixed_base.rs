@@ -0,0 +1,227 @@
+use ark_ec::{
+    short_weierstrass::{Affine, Projective, SWCurveConfig},
+    CurveGroup,
+};
+use ark_ff::{AdditiveGroup, BigInteger, PrimeField};
+use ark_r1cs_std::{
+    fields::{FieldOpsBounds, FieldVar},
+    groups::{
+        curves::short_weierstrass::{non_zero_affine::NonZeroAffineVar, ProjectiveVar},
+        CurveVar,
+    },
+    prelude::Boolean,
+    select::TwoBitLookupGadget,
+    R1CSVar,
+};
+use ark_relations::r1cs::SynthesisError;
+use ark_std::vec::Vec;
+
+/// Multiplication by a fixed point (e.g. `Parameters::g1_generator`) with precomputed window
+/// tables, which are circuit constants.
+///
+/// The scalar is split into windows of 2 bits `k_i`, and the `i`-th table holds
+/// `(k_i + 2) * 4^i * G`. Each window then takes a two-bit lookup for each coordinate and an
+/// incomplete affine addition, instead of a conditional addition for each bit.
+///
+/// The offset `2` keeps the partial sums `acc_i = sum_{j < i} (k_j + 2) * 4^j` strictly
+/// between `0` and `2 * 4^i`, so `acc_i * G` is never `±(k_i + 2) * 4^i * G` while
+/// `acc_i + (k_i + 2) * 4^i < r`. The windows after that are multiplied with
+/// `CurveVar::scalar_mul_le`, and the offsets are subtracted at the end.
+pub struct FixedBaseMulGadget<P: SWCurveConfig> {
+    /// `4^i * G` for each window.
+    bases: Vec<Projective<P>>,
+    /// `[(k + 2) * 4^i * G for k in 0..4]` for the windows that use incomplete additions.
+    tables: Vec<[Affine<P>; 4]>,
+}
+
+impl<P: SWCurveConfig> FixedBaseMulGadget<P> {
+    /// Precompute the tables of `generator` for scalars of up to the bit length of
+    /// `P::ScalarField::BigInt` (e.g. 256 bits for BLS12-381).
+    pub fn new(generator: Projective<P>) -> Self {
+        let scalar_bits = P::ScalarField::MODULUS_BIT_SIZE as usize;
+        let max_bits = 64 * <P::ScalarField as PrimeField>::BigInt::NUM_LIMBS;
+        // `acc_i + (k_i + 2) * 4^i < 2 * 4^(i + 1) <= 2^(scalar_bits - 1) < r`
+        let num_tables = (scalar_bits - 2) / 2;
+
+        let bases: Vec<_> = (0..max_bits.div_ceil(2))
+            .scan(generator, |base, _| {
+                let current = *base;
+                *base = current.double().double();
+                Some(current)
+            })
+            .collect();
+        let tables = bases[..num_tables]
+            .iter()
+            .map(|base| {
+                let row = [2u8, 3, 4, 5].map(|k| *base * P::ScalarField::from(k));
+                let row = Projective::normalize_batch(&row);
+                [row[0], row[1], row[2], row[3]]
+            })
+            .collect();
+
+        Self { bases, tables }
+    }
+
+    /// Multiply the fixed point by the scalar whose little-endian bits are `scalar`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `scalar` has more bits than `P::ScalarField::BigInt`.
+    #[tracing::instrument(skip_all)]
+    pub fn mul<FP, CF>(
+        &self,
+        scalar: &[Boolean<CF>],
+    ) -> Result<ProjectiveVar<P, FP, CF>, SynthesisError>
+    where
+        FP: FieldVar<P::BaseField, CF> + TwoBitLookupGadget<CF, TableConstant = P::BaseField>,
+        CF: PrimeField,
+        for<'a> &'a FP: FieldOpsBounds<'a, P::BaseField, FP>,
+    {
+        assert!(
+            scalar.len() <= 2 * self.bases.len(),
+            "The scalar is longer than the scalar field!"
+        );
+        let cs = scalar.cs();
+        tracing::info!(num_constraints = cs.num_constraints());
+
+        let windows: Vec<_> = scalar
+            .chunks(2)
+            .map(|window| {
+                [
+                    window[0].clone(),
+                    window.get(1).cloned().unwrap_or(Boolean::FALSE),
+                ]
+            })
+            .collect();
+        let (affine_windows, projective_windows) =
+            windows.split_at(self.tables.len().min(windows.len()));
+
+        let mut acc: Option<NonZeroAffineVar<P, FP, CF>> = None;
+        for (window, table) in affine_windows.iter().zip(&self.tables) {
+            let x = FP::two_bit_lookup(window, &table.map(|p| p.x))?;
+            let y = FP::two_bit_lookup(window, &table.map(|p| p.y))?;
+            let point = NonZeroAffineVar::new(x, y);
+            acc = Some(match acc {
+                Some(acc) => acc.add_unchecked(&point)?,
+                None => point,
+            });
+        }
+
+        let mut res = match acc {
+            Some(acc) => {
+                let offset = self.bases[..affine_windows.len()]
+                    .iter()
+                    .sum::<Projective<P>>()
+                    .double();
+                acc.into_projective() - offset
+            }
+            None => ProjectiveVar::zero(),
+        };
+        for (window, base) in projective_windows
+            .iter()
+            .zip(&self.bases[affine_windows.len()..])
+        {
+            res += ProjectiveVar::constant(*base).scalar_mul_le(window.iter())?;
+        }
+
+        tracing::info!(num_constraints = cs.num_constraints());
+
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ark_bls12_381::{g1, g2, Fq, Fq2Config, Fr};
+    use ark_ec::{short_weierstrass::Projective, CurveGroup, PrimeGroup};
+    use ark_ff::{BigInteger, PrimeField, UniformRand};
+    use ark_r1cs_std::{
+        alloc::AllocVar,
+        fields::{fp::FpVar, fp2::Fp2Var, FieldOpsBounds, FieldVar},
+        groups::{curves::short_weierstrass::ProjectiveVar, CurveVar},
+        prelude::Boolean,
+        select::TwoBitLookupGadget,
+        R1CSVar,
+    };
+    use ark_relations::r1cs::ConstraintSystem;
+    use rand::thread_rng;
+
+    use super::FixedBaseMulGadget;
+
+    /// Multiply the generator by scalars with `FixedBaseMulGadget` and with `scalar_mul_le`
+    /// on the constant generator, and return the constraints of each.
+    fn check_against_native<P, FP>() -> (usize, usize)
+    where
+        P: ark_ec::short_weierstrass::SWCurveConfig<ScalarField = Fr>,
+        FP: FieldVar<P::BaseField, Fq> + TwoBitLookupGadget<Fq, TableConstant = P::BaseField>,
+        for<'a> &'a FP: FieldOpsBounds<'a, P::BaseField, FP>,
+    {
+        let mut rng = thread_rng();
+        let generator = Projective::<P>::generator();
+        let gadget = FixedBaseMulGadget::new(generator);
+
+        let mut num_constraints = (0, 0);
+        for k in [
+            Fr::rand(&mut rng),
+            Fr::from(0u8),
+            Fr::from(1u8),
+            -Fr::from(1u8),
+        ] {
+            let cs = ConstraintSystem::<Fq>::new_ref();
+            let bits =
+                Vec::<Boolean<Fq>>::new_witness(cs.clone(), || Ok(k.into_bigint().to_bits_le()))
+                    .unwrap();
+
+            let before = cs.num_constraints();
+            let res: ProjectiveVar<P, FP, Fq> = gadget.mul(&bits).unwrap();
+            num_constraints.0 = cs.num_constraints() - before;
+
+            let before = cs.num_constraints();
+            let expected = ProjectiveVar::<P, FP, Fq>::constant(generator)
+                .scalar_mul_le(bits.iter())
+                .unwrap();
+            num_constraints.1 = cs.num_constraints() - before;
+
+            assert!(cs.is_satisfied().unwrap());
+            assert_eq!(
+                res.value().unwrap().into_affine(),
+                (generator * k).into_affine()
+            );
+            assert_eq!(expected.value().unwrap(), res.value().unwrap());
+        }
+
+        num_constraints
+    }
+
+    #[test]
+    fn test_fixed_base_mul_g1() {
+        let (fixed, generic) = check_against_native::<g1::Config, FpVar<Fq>>();
+        println!("G1: {fixed} constraints with tables, {generic} with double-and-add");
+        assert!(fixed < generic);
+    }
+
+    #[test]
+    fn test_fixed_base_mul_g2() {
+        let (fixed, generic) = check_against_native::<g2::Config, Fp2Var<Fq2Config>>();
+        println!("G2: {fixed} constraints with tables, {generic} with double-and-add");
+        assert!(fixed < generic);
+    }
+
+    #[test]
+    fn test_fixed_base_mul_short_scalar() {
+        let mut rng = thread_rng();
+        let cs = ConstraintSystem::<Fq>::new_ref();
+        let generator = Projective::<g1::Config>::generator();
+        let gadget = FixedBaseMulGadget::new(generator);
+
+        // an odd number of bits, and a constant scalar
+        let k = u64::rand(&mut rng) >> 1;
+        let bits: Vec<_> = (0..63)
+            .map(|i| Boolean::constant((k >> i) & 1 == 1))
+            .collect();
+        let res: ProjectiveVar<g1::Config, FpVar<Fq>, Fq> = gadget.mul(&bits).unwrap();
+
+        assert!(cs.is_satisfied().unwrap());
+        assert_eq!(res.value().unwrap(), generator * Fr::from(k));
+    }
+}
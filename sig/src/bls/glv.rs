@@ -0,0 +1,261 @@
+use ark_ec::{scalar_mul::glv::GLVConfig, short_weierstrass::Projective, CurveGroup, PrimeGroup};
+use ark_ff::{BigInteger, Field, One, PrimeField};
+use ark_r1cs_std::{
+    alloc::{AllocVar, AllocationMode},
+    eq::EqGadget,
+    fields::{emulated_fp::EmulatedFpVar, FieldOpsBounds, FieldVar},
+    groups::{
+        curves::short_weierstrass::{non_zero_affine::NonZeroAffineVar, ProjectiveVar},
+        CurveVar,
+    },
+    prelude::Boolean,
+    select::CondSelectGadget,
+    R1CSVar,
+};
+use ark_relations::r1cs::SynthesisError;
+use ark_std::vec::Vec;
+
+use crate::hash::hash_to_field::from_base_field::FromBitsGadget;
+
+/// Seeds of the discrete logarithms (w.r.t. the generator) of the offset points of
+/// `glv_scalar_mul_var`: the one added at each step, and the initial accumulator.
+const GLV_OFFSET_SEEDS: [&[u8]; 2] = [
+    b"GLV scalar multiplication offset",
+    b"GLV initial accumulator",
+];
+
+/// Multiply `p` by the scalar whose little-endian bits are `scalar`, with the GLV method.
+///
+/// The prover decomposes the scalar `k` into `k = k1 + λ * k2 (mod r)`, where `λ` is the
+/// eigenvalue of the endomorphism `φ: (x, y) -> (β * x, y)` (`GLVConfig::LAMBDA`) and `k1`
+/// and `k2` are about half as long as `r`. The decomposition is checked in the scalar field
+/// emulated over `CF`, and `[k1]P + [k2]φ(P)` is computed with a joint double-and-add,
+/// which takes half of the doublings of `CurveVar::scalar_mul_le`.
+///
+/// The check of the decomposition has a fixed cost, so this only pays off when the curve
+/// arithmetic is expensive. For BLS12-381 over `Fq`, G1 takes 3066 constraints (2362 with
+/// `scalar_mul_le`) and G2 takes 5574 (5800). For G1 emulated over `Fr`, a step of the
+/// joint double-and-add takes about 7.8k constraints, against 9.3k for each of the 255
+/// steps of `scalar_mul_le`. `BLSBatchVerifyGadget` therefore uses it for signatures on G2
+/// with full-length challenges, and keeps `scalar_mul_le` for public keys on G1.
+///
+/// `φ` only acts as the multiplication by `λ` on the subgroup of order `r`, so `p` must be
+/// in that subgroup (e.g. a public key or a signature whose subgroup is checked).
+#[tracing::instrument(skip_all)]
+pub fn glv_scalar_mul_var<P, FP, CF>(
+    p: &ProjectiveVar<P, FP, CF>,
+    scalar: &[Boolean<CF>],
+) -> Result<ProjectiveVar<P, FP, CF>, SynthesisError>
+where
+    P: GLVConfig,
+    FP: FieldVar<P::BaseField, CF>,
+    CF: PrimeField,
+    for<'a> &'a FP: FieldOpsBounds<'a, P::BaseField, FP>,
+{
+    let cs = p.cs().or(scalar.cs());
+    tracing::info!(num_constraints = cs.num_constraints());
+
+    // |k1| and |k2| are bounded by the short basis of the lattice of decompositions, which
+    // is about sqrt(r)
+    let half_len = P::ScalarField::MODULUS_BIT_SIZE.div_ceil(2) as usize + 1;
+
+    let decomposition = scalar.value().map(|bits| {
+        let bytes: Vec<u8> = bits
+            .chunks(8)
+            .map(|c| c.iter().rev().fold(0, |acc, &b| (acc << 1) | u8::from(b)))
+            .collect();
+        P::scalar_decomposition(P::ScalarField::from_le_bytes_mod_order(&bytes))
+    });
+    let mode = if scalar.is_constant() {
+        AllocationMode::Constant
+    } else {
+        AllocationMode::Witness
+    };
+    let alloc_part = |i: usize| -> Result<(Boolean<CF>, Vec<Boolean<CF>>), SynthesisError> {
+        let part = decomposition.map(|d| if i == 0 { d.0 } else { d.1 });
+        let sign = Boolean::new_variable(cs.clone(), || part.map(|(s, _)| s), mode)?;
+        let bits = (0..half_len)
+            .map(|j| {
+                Boolean::new_variable(
+                    cs.clone(),
+                    || part.map(|(_, k)| k.into_bigint().get_bit(j)),
+                    mode,
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok((sign, bits))
+    };
+    let (s1, k1) = alloc_part(0)?;
+    let (s2, k2) = alloc_part(1)?;
+
+    // k = ±k1 + λ * (±k2) (mod r)
+    let k = EmulatedFpVar::<P::ScalarField, CF>::from_le_bits(scalar);
+    let k1_var = EmulatedFpVar::from_le_bits(&k1);
+    let k2_var = EmulatedFpVar::from_le_bits(&k2);
+    let k1_var = s1.select(&k1_var, &k1_var.negate()?)?;
+    let k2_var = s2.select(&k2_var, &k2_var.negate()?)?;
+    (k1_var + k2_var * P::LAMBDA).enforce_equal(&k)?;
+
+    // The joint double-and-add runs on affine points with the incomplete addition of
+    // `NonZeroAffineVar`, which is much cheaper than the complete projective formulas. `p`
+    // is replaced by the generator if it is zero. The accumulator starts at a constant point
+    // `Q`, and every step adds a constant offset `R` on top of `[b1]P1 + [b2]P2`, so that
+    // neither the accumulator nor the added point is ever zero or equal to the other. The
+    // offset `2^n * Q + (2^n - 1) * R` is removed at the end.
+    let affine = p.to_affine()?;
+    let generator = P::GENERATOR;
+    let x = affine
+        .infinity
+        .select(&FP::constant(generator.x), &affine.x)?;
+    let y = affine
+        .infinity
+        .select(&FP::constant(generator.y), &affine.y)?;
+
+    // [k]P = [|k1|](±P) + [|k2|](±φ(P))
+    let p1 = NonZeroAffineVar::new(x.clone(), s1.select(&y, &y.negate()?)?);
+    let p2 = NonZeroAffineVar::new(x * P::ENDO_COEFFS[0], s2.select(&y, &y.negate()?)?);
+    let [offset_point, init_point] = GLV_OFFSET_SEEDS
+        .map(|seed| Projective::<P>::generator() * P::ScalarField::from_le_bytes_mod_order(seed));
+    let constant = |point: Projective<P>| {
+        let point = point.into_affine();
+        NonZeroAffineVar::<P, FP, CF>::new(FP::constant(point.x), FP::constant(point.y))
+    };
+    let offset = constant(offset_point);
+
+    // The incomplete addition is only sound if the x-coordinates of the operands differ,
+    // which holds except with negligible probability for an honest prover.
+    let add = |a: &NonZeroAffineVar<P, FP, CF>, b: &NonZeroAffineVar<P, FP, CF>| {
+        a.x.enforce_not_equal(&b.x)?;
+        a.add_unchecked(b)
+    };
+    let offset_p1 = add(&offset, &p1)?;
+    let table = [
+        offset.clone(),
+        offset_p1.clone(),
+        add(&offset, &p2)?,
+        add(&offset_p1, &p2)?,
+    ];
+
+    let mut acc = constant(init_point);
+    for (b1, b2) in k1.iter().zip(&k2).rev() {
+        // acc = 2 * acc + R + [b1]P1 + [b2]P2
+        let t = NonZeroAffineVar::conditionally_select_power_of_two_vector(
+            &[b2.clone(), b1.clone()],
+            &table,
+        )?;
+        // `(acc + t) + acc` can only be exceptional if `t = -2 * acc`, in which case the
+        // constraints of the second addition are unsatisfiable
+        acc.x.enforce_not_equal(&t.x)?;
+        acc = acc.double_and_add_unchecked(&t)?;
+    }
+
+    let two_to_n = P::ScalarField::from(2u8).pow([half_len as u64]);
+    let res = acc.into_projective()
+        - (init_point * two_to_n + offset_point * (two_to_n - P::ScalarField::one()));
+    let res = affine.infinity.select(&ProjectiveVar::zero(), &res)?;
+
+    tracing::info!(num_constraints = cs.num_constraints());
+
+    Ok(res)
+}
+
+#[cfg(test)]
+mod test {
+    use ark_bls12_381::{g1, g2, Fq, Fq2Config, Fr, G1Projective};
+    use ark_ec::{
+        scalar_mul::glv::GLVConfig, short_weierstrass::Projective, CurveGroup, PrimeGroup,
+    };
+    use ark_ff::{BigInteger, PrimeField, UniformRand};
+    use ark_r1cs_std::{
+        alloc::{AllocVar, AllocationMode},
+        fields::{emulated_fp::EmulatedFpVar, fp::FpVar, fp2::Fp2Var, FieldOpsBounds, FieldVar},
+        groups::{curves::short_weierstrass::ProjectiveVar, CurveVar},
+        prelude::Boolean,
+        R1CSVar,
+    };
+    use ark_relations::r1cs::ConstraintSystem;
+    use rand::thread_rng;
+
+    use super::glv_scalar_mul_var;
+
+    /// Multiply random points by random scalars with `glv_scalar_mul_var` and
+    /// `scalar_mul_le`, and return the constraints of each.
+    fn check_against_native<P, FP, CF>() -> (usize, usize)
+    where
+        P: GLVConfig<ScalarField = Fr>,
+        FP: FieldVar<P::BaseField, CF>,
+        CF: PrimeField,
+        for<'a> &'a FP: FieldOpsBounds<'a, P::BaseField, FP>,
+    {
+        let mut rng = thread_rng();
+
+        let mut num_constraints = (0, 0);
+        for k in [Fr::rand(&mut rng), Fr::from(0u8), -Fr::from(1u8)] {
+            let p = Projective::<P>::generator() * Fr::rand(&mut rng);
+
+            let cs = ConstraintSystem::<CF>::new_ref();
+            // `p` is in the prime order subgroup by construction, and its check is as costly
+            // as a scalar multiplication
+            let p_var = ProjectiveVar::<P, FP, CF>::new_variable_omit_prime_order_check(
+                cs.clone(),
+                || Ok(p),
+                AllocationMode::Witness,
+            )
+            .unwrap();
+            let bits =
+                Vec::<Boolean<CF>>::new_witness(cs.clone(), || Ok(k.into_bigint().to_bits_le()))
+                    .unwrap();
+
+            let before = cs.num_constraints();
+            let res = glv_scalar_mul_var(&p_var, &bits).unwrap();
+            num_constraints.0 = cs.num_constraints() - before;
+
+            let before = cs.num_constraints();
+            let expected = p_var.scalar_mul_le(bits.iter()).unwrap();
+            num_constraints.1 = cs.num_constraints() - before;
+
+            assert!(cs.is_satisfied().unwrap());
+            assert_eq!(res.value().unwrap().into_affine(), (p * k).into_affine());
+            assert_eq!(expected.value().unwrap(), res.value().unwrap());
+        }
+
+        num_constraints
+    }
+
+    #[test]
+    fn test_glv_scalar_mul_g1() {
+        let (glv, generic) = check_against_native::<g1::Config, FpVar<Fq>, Fq>();
+        // the check of the decomposition outweighs the saved doublings natively
+        assert!(glv > generic);
+    }
+
+    #[test]
+    fn test_glv_scalar_mul_g2() {
+        let (glv, generic) = check_against_native::<g2::Config, Fp2Var<Fq2Config>, Fq>();
+        assert!(glv < generic);
+    }
+
+    #[test]
+    #[ignore = "field emulation takes a long time and a lot of memory to finish running"]
+    fn test_glv_scalar_mul_g1_emulated() {
+        let (glv, generic) = check_against_native::<g1::Config, EmulatedFpVar<Fq, Fr>, Fr>();
+        assert!(glv < generic);
+    }
+
+    #[test]
+    fn test_glv_scalar_mul_constant() {
+        let mut rng = thread_rng();
+        let cs = ConstraintSystem::<Fq>::new_ref();
+
+        let k = Fr::rand(&mut rng);
+        let p = G1Projective::rand(&mut rng) * Fr::rand(&mut rng);
+        let p_var =
+            ProjectiveVar::<g1::Config, FpVar<Fq>>::new_witness(cs.clone(), || Ok(p)).unwrap();
+        let bits =
+            Vec::<Boolean<Fq>>::new_constant(cs.clone(), k.into_bigint().to_bits_le()).unwrap();
+
+        let res = glv_scalar_mul_var(&p_var, &bits).unwrap();
+        assert!(cs.is_satisfied().unwrap());
+        assert_eq!(res.value().unwrap(), p * k);
+    }
+}
@@ -0,0 +1,212 @@
+//! Key generation helpers built on HKDF-SHA256.
+//!
+//! This module implements the `KeyGen` procedure of the
+//! [BLS signature draft](https://datatracker.ietf.org/doc/html/draft-irtf-cfrg-bls-signature-05#section-2.3)
+//! and the hierarchical deterministic key derivation of
+//! [EIP-2333](https://eips.ethereum.org/EIPS/eip-2333).
+
+use ark_ec::bls12::Bls12Config;
+use ark_ff::{AdditiveGroup, BigInteger, PrimeField};
+use ark_std::{vec, vec::Vec};
+use sha2::{Digest, Sha256};
+
+use super::{params::SecretKeyScalarField, SecretKey};
+
+const SHA256_OUTPUT_SIZE: usize = 32;
+const SHA256_BLOCK_SIZE: usize = 64;
+
+const KEYGEN_SALT: &[u8] = b"BLS-SIG-KEYGEN-SALT-";
+/// ceil((3 * ceil(log2(r))) / 16) for a 255-bit `r`
+const KEYGEN_OKM_LENGTH: usize = 48;
+const LAMPORT_CHUNKS: usize = 255;
+
+fn hmac_sha256(key: &[u8], data: &[&[u8]]) -> [u8; SHA256_OUTPUT_SIZE] {
+    let mut block_key = [0u8; SHA256_BLOCK_SIZE];
+    if key.len() > SHA256_BLOCK_SIZE {
+        block_key[..SHA256_OUTPUT_SIZE].copy_from_slice(&Sha256::digest(key));
+    } else {
+        block_key[..key.len()].copy_from_slice(key);
+    }
+
+    let mut inner = Sha256::new();
+    inner.update(block_key.map(|b| b ^ 0x36));
+    data.iter().for_each(|d| inner.update(d));
+
+    let mut outer = Sha256::new();
+    outer.update(block_key.map(|b| b ^ 0x5c));
+    outer.update(inner.finalize());
+    outer.finalize().into()
+}
+
+fn hkdf_extract(salt: &[u8], ikm: &[u8]) -> [u8; SHA256_OUTPUT_SIZE] {
+    hmac_sha256(salt, &[ikm])
+}
+
+fn hkdf_expand(prk: &[u8], info: &[u8], length: usize) -> Vec<u8> {
+    let mut okm = Vec::with_capacity(length);
+    let mut t: Vec<u8> = vec![];
+    for i in 1..=length.div_ceil(SHA256_OUTPUT_SIZE) {
+        t = hmac_sha256(prk, &[&t, info, &[i as u8]]).to_vec();
+        okm.extend_from_slice(&t);
+    }
+    okm.truncate(length);
+    okm
+}
+
+/// `HKDF_mod_r` from EIP-2333, which is also the core of the IETF `KeyGen`.
+pub(crate) fn hkdf_mod_r<SigCurveConfig: Bls12Config>(
+    ikm: &[u8],
+    key_info: &[u8],
+) -> SecretKeyScalarField<SigCurveConfig> {
+    let mut salt = KEYGEN_SALT.to_vec();
+    let mut sk = SecretKeyScalarField::<SigCurveConfig>::ZERO;
+
+    while sk == SecretKeyScalarField::<SigCurveConfig>::ZERO {
+        salt = Sha256::digest(&salt).to_vec();
+        let prk = hkdf_extract(&salt, &[ikm, &[0]].concat());
+        let info = [key_info, &(KEYGEN_OKM_LENGTH as u16).to_be_bytes()].concat();
+        let okm = hkdf_expand(&prk, &info, KEYGEN_OKM_LENGTH);
+        sk = SecretKeyScalarField::<SigCurveConfig>::from_be_bytes_mod_order(&okm);
+    }
+
+    sk
+}
+
+fn ikm_to_lamport_sk(ikm: &[u8], salt: &[u8]) -> Vec<u8> {
+    let prk = hkdf_extract(salt, ikm);
+    hkdf_expand(&prk, &[], SHA256_OUTPUT_SIZE * LAMPORT_CHUNKS)
+}
+
+fn parent_sk_to_lamport_pk<SigCurveConfig: Bls12Config>(
+    parent_sk: &SecretKey<SigCurveConfig>,
+    index: u32,
+) -> [u8; SHA256_OUTPUT_SIZE] {
+    let salt = index.to_be_bytes();
+
+    // I2OSP(parent_SK, 32)
+    let bytes = parent_sk.secret_key.into_bigint().to_bytes_be();
+    let mut ikm = [0u8; SHA256_OUTPUT_SIZE];
+    let len = bytes.len().min(SHA256_OUTPUT_SIZE);
+    ikm[SHA256_OUTPUT_SIZE - len..].copy_from_slice(&bytes[bytes.len() - len..]);
+    let not_ikm = ikm.map(|b| !b);
+
+    let lamport_0 = ikm_to_lamport_sk(&ikm, &salt);
+    let lamport_1 = ikm_to_lamport_sk(&not_ikm, &salt);
+
+    let mut compressed_pk = Sha256::new();
+    lamport_0
+        .chunks(SHA256_OUTPUT_SIZE)
+        .chain(lamport_1.chunks(SHA256_OUTPUT_SIZE))
+        .for_each(|chunk| compressed_pk.update(Sha256::digest(chunk)));
+    compressed_pk.finalize().into()
+}
+
+impl<SigCurveConfig: Bls12Config> SecretKey<SigCurveConfig> {
+    /// Deterministically generate a secret key from `ikm` following the IETF `KeyGen`
+    /// procedure, so the same `(ikm, key_info)` gives the same key as blst or py_ecc.
+    ///
+    /// Returns `None` if `ikm` is shorter than 32 bytes.
+    #[must_use]
+    pub fn keygen(ikm: &[u8], key_info: &[u8]) -> Option<Self> {
+        if ikm.len() < SHA256_OUTPUT_SIZE {
+            return None;
+        }
+
+        Some(Self {
+            secret_key: hkdf_mod_r::<SigCurveConfig>(ikm, key_info),
+        })
+    }
+
+    /// Derive the master secret key from `seed` as defined in EIP-2333.
+    ///
+    /// Returns `None` if the seed is shorter than 32 bytes.
+    #[must_use]
+    pub fn derive_master(seed: &[u8]) -> Option<Self> {
+        // the master key of EIP-2333 is `KeyGen` with an empty `key_info`
+        Self::keygen(seed, &[])
+    }
+
+    /// Derive the `index`-th child secret key as defined in EIP-2333.
+    #[must_use]
+    pub fn derive_child(&self, index: u32) -> Self {
+        let compressed_lamport_pk = parent_sk_to_lamport_pk(self, index);
+        Self {
+            secret_key: hkdf_mod_r::<SigCurveConfig>(&compressed_lamport_pk, &[]),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use core::str::FromStr;
+
+    use ark_bls12_381::Fr;
+
+    use super::*;
+
+    type SigCurveConfig = ark_bls12_381::Config;
+
+    fn from_hex(hex: &str) -> Vec<u8> {
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn check_eip2333_test_vectors() {
+        // test cases 0 and 1 from EIP-2333
+        let cases = [
+            (
+                "c55257c360c07c72029aebc1b53c05ed0362ada38ead3e3e9efa3708e53495531f09a6987599d18264c1e1c92f2cf141630c7a3c4ab7c81b2f001698e7463b04",
+                "6083874454709270928345386274498605044986640685124978867557563392430687146096",
+                0,
+                "20397789859736650942317412262472558107875392172444076792671091975210932703118",
+            ),
+            (
+                "3141592653589793238462643383279502884197169399375105820974944592",
+                "29757020647961307431480504535336562678282505419141012933316116377660817309383",
+                3141592653,
+                "25457201688850691947727629385191704516744796114925897962676248250929345014287",
+            ),
+        ];
+
+        for (seed, master_sk, index, child_sk) in cases {
+            let master = SecretKey::<SigCurveConfig>::derive_master(&from_hex(seed)).unwrap();
+            assert_eq!(master.secret_key, Fr::from_str(master_sk).unwrap());
+
+            let child = master.derive_child(index);
+            assert_eq!(child.secret_key, Fr::from_str(child_sk).unwrap());
+        }
+    }
+
+    #[test]
+    fn check_short_seed_rejected() {
+        assert!(SecretKey::<SigCurveConfig>::derive_master(&[0u8; 31]).is_none());
+    }
+
+    #[test]
+    fn check_keygen() {
+        let ikm = from_hex("3141592653589793238462643383279502884197169399375105820974944592");
+
+        let sk = SecretKey::<SigCurveConfig>::keygen(&ikm, &[]).unwrap();
+        assert_eq!(
+            sk.secret_key,
+            Fr::from_str(
+                "29757020647961307431480504535336562678282505419141012933316116377660817309383"
+            )
+            .unwrap()
+        );
+        assert_eq!(
+            sk.secret_key,
+            SecretKey::<SigCurveConfig>::keygen(&ikm, &[])
+                .unwrap()
+                .secret_key
+        );
+
+        let other = SecretKey::<SigCurveConfig>::keygen(&ikm, b"key info").unwrap();
+        assert_ne!(sk.secret_key, other.secret_key);
+
+        assert!(SecretKey::<SigCurveConfig>::keygen(&ikm[..31], &[]).is_none());
+    }
+}
@@ -0,0 +1,113 @@
+use ark_ec::bls12::Bls12Config;
+use derivative::Derivative;
+use rand::Rng;
+
+use crate::signature::{Error, Signer, Verifier};
+
+use super::{
+    MinPk, Parameters, PublicKey, SecretKey, Signature, SignatureVariant, SignatureVariantHasher,
+};
+
+/// A secret key together with its public key.
+#[derive(Derivative)]
+#[derivative(Clone(bound = ""), Debug(bound = ""))]
+pub struct KeyPair<SigCurveConfig: Bls12Config, V: SignatureVariant<SigCurveConfig> = MinPk> {
+    pub sk: SecretKey<SigCurveConfig>,
+    pub pk: PublicKey<SigCurveConfig, V>,
+}
+
+impl<SigCurveConfig: Bls12Config, V: SignatureVariant<SigCurveConfig>> KeyPair<SigCurveConfig, V> {
+    #[must_use]
+    pub fn new(sk: SecretKey<SigCurveConfig>, params: &Parameters<SigCurveConfig>) -> Self {
+        let pk = PublicKey::new(&sk, params);
+        Self { sk, pk }
+    }
+
+    pub fn generate<R: Rng>(rng: &mut R, params: &Parameters<SigCurveConfig>) -> Self {
+        Self::new(SecretKey::new(rng), params)
+    }
+}
+
+/// Signing does not depend on the generators, so no `Parameters` are needed.
+impl<SigCurveConfig: Bls12Config, V: SignatureVariantHasher<SigCurveConfig>>
+    Signer<Signature<SigCurveConfig, V>> for SecretKey<SigCurveConfig>
+{
+    fn try_sign(&self, message: &[u8]) -> Result<Signature<SigCurveConfig, V>, Error> {
+        Ok(Signature::sign(message, self, &Parameters::setup()))
+    }
+}
+
+/// Verifies with the standard generators of `Parameters::setup`, in strict mode.
+impl<SigCurveConfig: Bls12Config, V: SignatureVariantHasher<SigCurveConfig>>
+    Verifier<Signature<SigCurveConfig, V>> for PublicKey<SigCurveConfig, V>
+{
+    fn verify(
+        &self,
+        message: &[u8],
+        signature: &Signature<SigCurveConfig, V>,
+    ) -> Result<(), Error> {
+        if Signature::verify(message, signature, self, &Parameters::setup()) {
+            Ok(())
+        } else {
+            Err(Error::new())
+        }
+    }
+}
+
+impl<SigCurveConfig: Bls12Config, V: SignatureVariantHasher<SigCurveConfig>>
+    Signer<Signature<SigCurveConfig, V>> for KeyPair<SigCurveConfig, V>
+{
+    fn try_sign(&self, message: &[u8]) -> Result<Signature<SigCurveConfig, V>, Error> {
+        self.sk.try_sign(message)
+    }
+}
+
+impl<SigCurveConfig: Bls12Config, V: SignatureVariantHasher<SigCurveConfig>>
+    Verifier<Signature<SigCurveConfig, V>> for KeyPair<SigCurveConfig, V>
+{
+    fn verify(
+        &self,
+        message: &[u8],
+        signature: &Signature<SigCurveConfig, V>,
+    ) -> Result<(), Error> {
+        self.pk.verify(message, signature)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::thread_rng;
+
+    use crate::bls::MinSig;
+
+    use super::*;
+
+    type SigCurveConfig = ark_bls12_381::Config;
+
+    /// Only uses the traits, as a benchmark comparing schemes would.
+    fn sign_and_verify<S, K: Signer<S> + Verifier<S>>(key_pair: &K) {
+        let sig = key_pair.sign(b"message");
+        assert!(key_pair.verify(b"message", &sig).is_ok());
+        assert!(key_pair.verify(b"other message", &sig).is_err());
+    }
+
+    #[test]
+    fn check_signer_verifier() {
+        let mut rng = thread_rng();
+        let params = Parameters::setup();
+
+        let key_pair = KeyPair::<SigCurveConfig>::generate(&mut rng, &params);
+        sign_and_verify(&key_pair);
+        sign_and_verify(&KeyPair::<SigCurveConfig, MinSig>::generate(
+            &mut rng, &params,
+        ));
+
+        // the traits agree with the associated functions of `Signature`
+        let sig: Signature<SigCurveConfig> = key_pair.sk.sign(b"message");
+        assert_eq!(
+            sig.signature,
+            Signature::<SigCurveConfig>::sign(b"message", &key_pair.sk, &params).signature
+        );
+        assert!(key_pair.pk.verify(b"message", &sig).is_ok());
+    }
+}
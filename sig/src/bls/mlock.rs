@@ -0,0 +1,141 @@
+use std::{
+    alloc::{alloc, dealloc, handle_alloc_error, Layout},
+    mem::size_of,
+    ops::Deref,
+    ptr::{self, NonNull},
+};
+
+use ark_ec::bls12::Bls12Config;
+use zeroize::Zeroize;
+
+use super::SecretKey;
+
+/// A heap-allocated secret key whose memory is locked into RAM with `mlock(2)`,
+/// so that long-lived keys (e.g. an authority's signing key) are never written to swap.
+///
+/// Locks apply to whole pages and do not nest, so each key gets page-aligned pages of its own:
+/// unlocking them on drop cannot unlock another key or any other allocation.
+/// The key is zeroized before its pages are unlocked on drop.
+pub struct LockedSecretKey<SigCurveConfig: Bls12Config> {
+    secret_key: NonNull<SecretKey<SigCurveConfig>>,
+    layout: Layout,
+    locked: bool,
+}
+
+// SAFETY: `LockedSecretKey` owns the key it points to, like a `Box` would
+unsafe impl<SigCurveConfig: Bls12Config> Send for LockedSecretKey<SigCurveConfig> where
+    SecretKey<SigCurveConfig>: Send
+{
+}
+// SAFETY: `LockedSecretKey` only hands out shared references to the key
+unsafe impl<SigCurveConfig: Bls12Config> Sync for LockedSecretKey<SigCurveConfig> where
+    SecretKey<SigCurveConfig>: Sync
+{
+}
+
+fn page_size() -> usize {
+    // SAFETY: `sysconf` has no preconditions
+    let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) };
+    usize::try_from(page_size).unwrap_or(4096)
+}
+
+impl<SigCurveConfig: Bls12Config> LockedSecretKey<SigCurveConfig> {
+    /// Move `secret_key` to pages of its own and try to lock them in memory.
+    ///
+    /// Locking can fail (e.g. because of `RLIMIT_MEMLOCK`); the key is still usable,
+    /// and `is_locked` reports whether the lock is in place.
+    #[must_use]
+    pub fn new(secret_key: SecretKey<SigCurveConfig>) -> Self {
+        let page_size = page_size();
+        let layout = Layout::from_size_align(
+            size_of::<SecretKey<SigCurveConfig>>().next_multiple_of(page_size),
+            page_size,
+        )
+        .expect("the key fits in a page-aligned allocation");
+
+        // SAFETY: the layout has a non-zero size and a power-of-two alignment
+        let ptr = unsafe { alloc(layout) }.cast::<SecretKey<SigCurveConfig>>();
+        let Some(ptr) = NonNull::new(ptr) else {
+            handle_alloc_error(layout)
+        };
+        // SAFETY: `ptr` is a fresh allocation that is large enough and aligned for the key
+        unsafe { ptr::write(ptr.as_ptr(), secret_key) };
+
+        // SAFETY: the pointer refers to a live allocation of the given size
+        let locked = unsafe { libc::mlock(ptr.as_ptr().cast(), layout.size()) == 0 };
+        Self {
+            secret_key: ptr,
+            layout,
+            locked,
+        }
+    }
+
+    #[must_use]
+    pub fn is_locked(&self) -> bool {
+        self.locked
+    }
+}
+
+impl<SigCurveConfig: Bls12Config> Deref for LockedSecretKey<SigCurveConfig> {
+    type Target = SecretKey<SigCurveConfig>;
+
+    fn deref(&self) -> &Self::Target {
+        // SAFETY: the key is initialized in `new` and only dropped in `drop`
+        unsafe { self.secret_key.as_ref() }
+    }
+}
+
+impl<SigCurveConfig: Bls12Config> Drop for LockedSecretKey<SigCurveConfig> {
+    fn drop(&mut self) {
+        let ptr = self.secret_key.as_ptr();
+        // SAFETY: the key is initialized in `new` and dropped only here; the region is the one
+        // allocated (and locked) in `new`
+        unsafe {
+            (*ptr).zeroize();
+            ptr::drop_in_place(ptr);
+            if self.locked {
+                libc::munlock(ptr.cast(), self.layout.size());
+            }
+            dealloc(ptr.cast(), self.layout);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::thread_rng;
+
+    use crate::bls::{Parameters, PublicKey, SecretKey, Signature};
+
+    use super::LockedSecretKey;
+
+    type SigCurveConfig = ark_bls12_381::Config;
+
+    #[test]
+    fn check_locked_secret_key_signs() {
+        let mut rng = thread_rng();
+        let params = Parameters::<SigCurveConfig>::setup();
+        let sk = SecretKey::new(&mut rng);
+        let pk = PublicKey::<SigCurveConfig>::new(&sk, &params);
+
+        let locked = LockedSecretKey::new(sk);
+        let msg = b"locked";
+        let sig: Signature<SigCurveConfig> = Signature::sign(msg, &locked, &params);
+        assert!(Signature::verify(msg, &sig, &pk, &params));
+    }
+
+    #[test]
+    fn check_locked_secret_keys_do_not_share_pages() {
+        let mut rng = thread_rng();
+        let page_size = super::page_size();
+        let a = LockedSecretKey::<SigCurveConfig>::new(SecretKey::new(&mut rng));
+        let b = LockedSecretKey::<SigCurveConfig>::new(SecretKey::new(&mut rng));
+
+        let page = |key: &LockedSecretKey<SigCurveConfig>| {
+            let addr = &**key as *const SecretKey<SigCurveConfig> as usize;
+            assert_eq!(addr % page_size, 0);
+            addr / page_size
+        };
+        assert_ne!(page(&a), page(&b));
+    }
+}
@@ -7,11 +7,80 @@ mod params;
 mod r1cs;
 pub use r1cs::*;
 
+mod decompress;
+
 mod circuit;
 pub use circuit::*;
 
-use rand::thread_rng;
+mod pop;
+pub use pop::*;
+
+mod multisig;
+pub use multisig::*;
+
+pub mod threshold;
+
+pub mod vrf;
+
+pub mod pairing;
+
+pub mod eth;
+
+mod variant;
+pub use variant::*;
+
+mod keygen;
+
+mod keypair;
+pub use keypair::*;
+
+mod error;
+pub use error::*;
+
+mod aggregate;
+pub use aggregate::*;
+
+mod blind;
+pub use blind::*;
+
+mod partial;
+pub use partial::*;
+
+mod domain;
+pub use domain::*;
+
+mod ct;
+
+mod glv;
+pub use glv::*;
+
+mod fixed_base;
+pub use fixed_base::*;
+
+mod msm;
+pub use msm::*;
+
+pub mod subgroup;
+pub use subgroup::{Bls12SubgroupGadget, SubgroupGadget};
+
+mod encoding;
+#[cfg(feature = "serde")]
+pub(crate) use encoding::from_hex;
+
+#[cfg(feature = "mlock")]
+mod mlock;
+#[cfg(feature = "mlock")]
+pub use mlock::*;
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+#[cfg(feature = "std")]
+use {
+    ark_std::{cfg_iter, vec::Vec},
+    rand::thread_rng,
+};
 
+#[cfg(feature = "std")]
 #[must_use]
 pub fn get_bls_instance<SigCurveConfig: Bls12Config>() -> (
     &'static str,
@@ -35,6 +104,7 @@ where
     (msg, params, sk, pk, sig)
 }
 
+#[cfg(feature = "std")]
 #[must_use]
 pub fn get_aggregate_bls_instance<SigCurveConfig: Bls12Config>() -> (
     &'static str,
@@ -53,8 +123,7 @@ where
 
     let params = Parameters::setup();
     let secret_keys: Vec<_> = (0..N).map(|_| SecretKey::new(&mut rng)).collect();
-    let public_keys: Vec<_> = secret_keys
-        .iter()
+    let public_keys: Vec<_> = cfg_iter!(secret_keys)
         .map(|sk| PublicKey::new(sk, &params))
         .collect();
 
@@ -0,0 +1,209 @@
+use ark_ec::short_weierstrass::SWCurveConfig;
+use ark_ff::{AdditiveGroup, PrimeField, Zero};
+use ark_r1cs_std::{
+    fields::{emulated_fp::EmulatedFpVar, FieldVar},
+    groups::{curves::short_weierstrass::ProjectiveVar, CurveVar},
+    prelude::Boolean,
+    R1CSVar,
+};
+use ark_relations::r1cs::SynthesisError;
+use ark_std::vec::Vec;
+
+type EmulatedProjectiveVar<P, CF> =
+    ProjectiveVar<P, EmulatedFpVar<<P as ark_ec::CurveConfig>::BaseField, CF>, CF>;
+
+/// Sum the `points` whose `selectors` are set, e.g. the public keys of the signers of a
+/// committee.
+///
+/// The selected points are summed with a balanced tree of complete additions. R1CS has no
+/// notion of depth, so the tree takes as many additions as a running sum, but each addition
+/// sums the two products of every output coordinate before reducing them, which saves 3 of
+/// the 12 reductions of `ProjectiveVar`'s addition over emulated coordinates.
+///
+/// # Panics
+///
+/// Panics if the curve has a non-zero `a` coefficient.
+#[tracing::instrument(skip_all)]
+pub fn aggregate_selected_var<P, CF>(
+    points: &[EmulatedProjectiveVar<P, CF>],
+    selectors: &[Boolean<CF>],
+) -> Result<EmulatedProjectiveVar<P, CF>, SynthesisError>
+where
+    P: SWCurveConfig,
+    P::BaseField: PrimeField,
+    CF: PrimeField,
+{
+    assert!(
+        P::COEFF_A.is_zero(),
+        "Only curves with a = 0 are supported!"
+    );
+    let cs = points.cs().or(selectors.cs());
+    tracing::info!(num_constraints = cs.num_constraints());
+
+    let mut layer = selectors
+        .iter()
+        .zip(points)
+        .map(|(selected, point)| selected.select(point, &ProjectiveVar::zero()))
+        .collect::<Result<Vec<_>, _>>()?;
+    while layer.len() > 1 {
+        layer = layer
+            .chunks(2)
+            .map(|pair| match pair {
+                [p, q] => add_lazy(p, q),
+                [p] => Ok(p.clone()),
+                _ => unreachable!(),
+            })
+            .collect::<Result<_, _>>()?;
+    }
+
+    tracing::info!(num_constraints = cs.num_constraints());
+
+    Ok(layer.pop().unwrap_or_else(ProjectiveVar::zero))
+}
+
+/// The complete addition of `ProjectiveVar` (Renes-Costello-Batina 2015, Algorithm 1) for
+/// `a = 0`, where each output coordinate `u * v + w * t` is reduced once.
+fn add_lazy<P, CF>(
+    p: &EmulatedProjectiveVar<P, CF>,
+    q: &EmulatedProjectiveVar<P, CF>,
+) -> Result<EmulatedProjectiveVar<P, CF>, SynthesisError>
+where
+    P: SWCurveConfig,
+    P::BaseField: PrimeField,
+    CF: PrimeField,
+{
+    if p.is_constant() || q.is_constant() {
+        return Ok(p + q);
+    }
+
+    let three_b = P::COEFF_B.double() + P::COEFF_B;
+    let (x1, y1, z1) = (&p.x, &p.y, &p.z);
+    let (x2, y2, z2) = (&q.x, &q.y, &q.z);
+
+    let xx = x1 * x2;
+    let yy = y1 * y2;
+    let zz = z1 * z2;
+    let xy_pairs = ((x1 + y1) * &(x2 + y2)) - (&xx + &yy);
+    let xz_pairs = ((x1 + z1) * &(x2 + z2)) - (&xx + &zz);
+    let yz_pairs = ((y1 + z1) * &(y2 + z2)) - (&yy + &zz);
+
+    let bzz3 = &zz * three_b;
+    let yy_m_bzz3 = &yy - &bzz3;
+    let yy_p_bzz3 = &yy + &bzz3;
+    let xx3 = xx.double()? + &xx;
+    let bxz3 = &xz_pairs * three_b;
+
+    // `u * v + w * t`, reduced once
+    let sum_of_products = |u: &EmulatedFpVar<P::BaseField, CF>,
+                           v: &EmulatedFpVar<P::BaseField, CF>,
+                           w: &EmulatedFpVar<P::BaseField, CF>,
+                           t: &EmulatedFpVar<P::BaseField, CF>| {
+        (u.mul_without_reduce(v)? + w.mul_without_reduce(t)?).reduce()
+    };
+    let x = sum_of_products(&yy_m_bzz3, &xy_pairs, &yz_pairs.negate()?, &bxz3)?;
+    let y = sum_of_products(&yy_p_bzz3, &yy_m_bzz3, &xx3, &bxz3)?;
+    let z = sum_of_products(&yy_p_bzz3, &yz_pairs, &xy_pairs, &xx3)?;
+
+    Ok(ProjectiveVar::new(x, y, z))
+}
+
+#[cfg(test)]
+mod test {
+    use ark_bls12_381::{g1, Fq, Fr, G1Projective};
+    use ark_ec::CurveGroup;
+    use ark_ff::{UniformRand, Zero};
+    use ark_r1cs_std::{
+        alloc::{AllocVar, AllocationMode},
+        fields::emulated_fp::EmulatedFpVar,
+        groups::{curves::short_weierstrass::ProjectiveVar, CurveVar},
+        prelude::Boolean,
+        R1CSVar,
+    };
+    use ark_relations::r1cs::ConstraintSystem;
+    use rand::{thread_rng, Rng};
+
+    use super::aggregate_selected_var;
+
+    type G1Var = ProjectiveVar<g1::Config, EmulatedFpVar<Fq, Fr>, Fr>;
+
+    #[test]
+    fn test_aggregate_selected() {
+        let mut rng = thread_rng();
+
+        for n in [0, 1, 2, 5] {
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let points: Vec<_> = (0..n)
+                .map(|i| match i {
+                    1 => G1Projective::zero(),
+                    _ => G1Projective::rand(&mut rng),
+                })
+                .collect();
+            let selectors: Vec<bool> = (0..n).map(|_| rng.gen()).collect();
+
+            // the identity and points with `z != 1` are added as well
+            let mut points_var: Vec<_> = points
+                .iter()
+                .map(|p| {
+                    G1Var::new_variable_omit_prime_order_check(
+                        cs.clone(),
+                        || Ok(*p),
+                        AllocationMode::Witness,
+                    )
+                    .unwrap()
+                })
+                .collect();
+            if let Some(p) = points_var.first_mut() {
+                *p = p.double().unwrap();
+            }
+            let selectors_var = Vec::new_witness(cs.clone(), || Ok(selectors.clone())).unwrap();
+
+            let sum = aggregate_selected_var(&points_var, &selectors_var).unwrap();
+            let expected: G1Projective = points_var
+                .iter()
+                .zip(&selectors)
+                .filter(|(_, &s)| s)
+                .map(|(p, _)| p.value().unwrap())
+                .sum();
+
+            assert!(cs.is_satisfied().unwrap());
+            assert_eq!(sum.value().unwrap().into_affine(), expected.into_affine());
+        }
+    }
+
+    #[test]
+    fn test_aggregate_selected_constraints() {
+        let mut rng = thread_rng();
+        let cs = ConstraintSystem::<Fr>::new_ref();
+
+        let n = 8;
+        let points_var: Vec<_> = (0..n)
+            .map(|_| {
+                G1Var::new_variable_omit_prime_order_check(
+                    cs.clone(),
+                    || Ok(G1Projective::rand(&mut rng)),
+                    AllocationMode::Witness,
+                )
+                .unwrap()
+            })
+            .collect();
+        let selectors_var: Vec<_> = (0..n)
+            .map(|_| Boolean::new_witness(cs.clone(), || Ok(rng.gen::<bool>())).unwrap())
+            .collect();
+
+        let before = cs.num_constraints();
+        let sum = aggregate_selected_var(&points_var, &selectors_var).unwrap();
+        let tree = cs.num_constraints() - before;
+
+        let before = cs.num_constraints();
+        let mut expected = G1Var::zero();
+        for (selected, point) in selectors_var.iter().zip(&points_var) {
+            expected += selected.select(point, &G1Var::zero()).unwrap();
+        }
+        let running_sum = cs.num_constraints() - before;
+
+        println!("{n} points: {tree} constraints with the tree, {running_sum} with a running sum");
+        assert!(cs.is_satisfied().unwrap());
+        assert_eq!(sum.value().unwrap(), expected.value().unwrap());
+        assert!(tree < running_sum);
+    }
+}
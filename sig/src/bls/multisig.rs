@@ -0,0 +1,155 @@
+use core::ops::Mul;
+
+use ark_ec::{bls12::Bls12Config, hashing::curve_maps::wb::WBConfig};
+use ark_ff::field_hashers::{DefaultFieldHasher, HashToField};
+use ark_std::vec::Vec;
+use blake2::Blake2s256;
+
+use super::{params::SecretKeyScalarField, Parameters, PublicKey, SecretKey, Signature};
+
+/// Domain separation tag used when hashing the public key set into aggregation coefficients.
+pub const MSP_COEFFICIENT_DST: &[u8] = b"BLS_MSP_COEFFICIENT_BLS12G2_XMD:BLAKE2S_";
+
+/// Derive the aggregation coefficient `t_i = H(pk_i, {pk_1, ..., pk_n})` for every key.
+///
+/// The coefficients depend on the whole ordered key set, so an attacker cannot choose
+/// a rogue key that cancels out honest keys in the weighted aggregate (as in MSP/BDN).
+fn aggregation_coefficients<SigCurveConfig: Bls12Config>(
+    public_keys: &[PublicKey<SigCurveConfig>],
+) -> Vec<SecretKeyScalarField<SigCurveConfig>> {
+    let hasher = <DefaultFieldHasher<Blake2s256, 128> as HashToField<
+        SecretKeyScalarField<SigCurveConfig>,
+    >>::new(MSP_COEFFICIENT_DST);
+
+    let encoded_keys: Vec<_> = public_keys
+        .iter()
+        .map(PublicKey::to_compressed_bytes)
+        .collect();
+    let encoded_key_set = encoded_keys.concat();
+
+    encoded_keys
+        .iter()
+        .map(|encoded_key| {
+            let [coefficient] =
+                hasher.hash_to_field::<1>(&[encoded_key.as_slice(), &encoded_key_set].concat());
+            coefficient
+        })
+        .collect()
+}
+
+impl<SigCurveConfig: Bls12Config> PublicKey<SigCurveConfig> {
+    /// Aggregate public keys as `sum_i t_i * pk_i`, where `t_i` are the MSP aggregation coefficients.
+    ///
+    /// The order of `public_keys` matters and must match the one used by `Signature::multi_sign`.
+    #[must_use]
+    pub fn aggregate_with_coefficients(public_keys: &[Self]) -> Option<Self> {
+        let coefficients = aggregation_coefficients(public_keys);
+
+        public_keys
+            .iter()
+            .zip(coefficients)
+            .map(|(pk, t)| pk.pub_key.mul(t))
+            .reduce(|acc, pk| acc + pk)
+            .map(Self::from_projective)
+    }
+}
+
+impl<SigCurveConfig: Bls12Config> Signature<SigCurveConfig>
+where
+    <SigCurveConfig as Bls12Config>::G2Config: WBConfig,
+{
+    /// Produce a rogue-key-safe multi-signature `sum_i t_i * sig_i` on `message`.
+    ///
+    /// As in `aggregate_sign`, each signer signs on its own, and the signatures are then
+    /// combined with the aggregation coefficients derived from the ordered public key set.
+    #[must_use]
+    pub fn multi_sign(
+        message: &[u8],
+        secret_keys: &[SecretKey<SigCurveConfig>],
+        params: &Parameters<SigCurveConfig>,
+    ) -> Option<Self> {
+        let public_keys: Vec<_> = secret_keys
+            .iter()
+            .map(|sk| PublicKey::new(sk, params))
+            .collect();
+        let coefficients = aggregation_coefficients(&public_keys);
+
+        secret_keys
+            .iter()
+            .zip(coefficients)
+            .map(|(sk, t)| Self::sign(message, sk, params).signature.mul(t))
+            .reduce(|acc, sig| acc + sig)
+            .map(|signature| Self { signature })
+    }
+
+    /// Verify a signature produced by `multi_sign`. It does not require proofs of possession.
+    #[must_use]
+    pub fn multi_verify(
+        message: &[u8],
+        multi_signature: &Self,
+        public_keys: &[PublicKey<SigCurveConfig>],
+        params: &Parameters<SigCurveConfig>,
+    ) -> Option<bool> {
+        let pk = PublicKey::aggregate_with_coefficients(public_keys)?;
+        Some(Self::verify(message, multi_signature, &pk, params))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::thread_rng;
+
+    use crate::bls::{Parameters, PublicKey, SecretKey, Signature};
+
+    type SigCurveConfig = ark_bls12_381::Config;
+
+    #[test]
+    fn check_multi_signature() {
+        let mut rng = thread_rng();
+        let msg = b"Hello World";
+        let params = Parameters::<SigCurveConfig>::setup();
+        let secret_keys: Vec<_> = (0..10).map(|_| SecretKey::new(&mut rng)).collect();
+        let public_keys: Vec<_> = secret_keys
+            .iter()
+            .map(|sk| PublicKey::new(sk, &params))
+            .collect();
+
+        let sig = Signature::multi_sign(msg, &secret_keys, &params).unwrap();
+        assert!(Signature::multi_verify(msg, &sig, &public_keys, &params).unwrap());
+
+        // the coefficients bind the order of the key set
+        let mut reordered = public_keys.clone();
+        reordered.swap(0, 1);
+        assert!(!Signature::multi_verify(msg, &sig, &reordered, &params).unwrap());
+
+        // a plain aggregate signature is not a valid multi-signature
+        let aggregate = Signature::aggregate_sign(msg, &secret_keys, &params).unwrap();
+        assert!(!Signature::multi_verify(msg, &aggregate, &public_keys, &params).unwrap());
+
+        assert!(Signature::multi_sign(msg, &[], &params).is_none());
+        assert!(Signature::multi_verify(msg, &sig, &[], &params).is_none());
+    }
+
+    #[test]
+    fn check_rogue_key_rejected() {
+        let mut rng = thread_rng();
+        let msg = b"Hello World";
+        let params = Parameters::<SigCurveConfig>::setup();
+        let honest_pk = PublicKey::<SigCurveConfig>::new(&SecretKey::new(&mut rng), &params);
+
+        // the attacker publishes pk_rogue = g1^x - pk_honest and signs alone with x
+        let attacker_sk = SecretKey::new(&mut rng);
+        let rogue_pk = PublicKey {
+            pub_key: PublicKey::<SigCurveConfig>::new(&attacker_sk, &params).pub_key
+                - honest_pk.pub_key,
+        };
+        let forged = Signature::sign(msg, &attacker_sk, &params);
+
+        // the forgery passes naive aggregation ...
+        assert!(
+            Signature::aggregate_verify(msg, &forged, &[honest_pk, rogue_pk], &params).unwrap()
+        );
+        // ... but not the coefficient-weighted one
+        assert!(!Signature::multi_verify(msg, &forged, &[honest_pk, rogue_pk], &params).unwrap());
+    }
+}
@@ -0,0 +1,415 @@
+//! BLS signatures over any pairing-friendly curve.
+//!
+//! The rest of `bls` is generic over a `Bls12Config`, because its R1CS gadgets are built on
+//! `ark_r1cs_std::pairing::bls12` and the BLS12 cofactor clearing gadgets. The types here
+//! only need a `Pairing` and a `CurveHasher` to its G2 group, so they also cover curves
+//! outside the BLS12 family. Public keys are on G1 and signatures on G2, as in `MinPk`.
+//!
+//! Two instantiations are provided:
+//! - every BLS12 curve with a `WBConfig` on G2 (e.g. BLS12-377), hashing with `MinPk`, so
+//!   signatures are the same as those of `bls::Signature` and can be checked in-circuit by
+//!   `BLSAggregateSignatureVerifyGadget` over the base field of the curve;
+//! - BN254 (feature `bn254`), whose pairing is the one of the EVM precompile. `ark-r1cs-std`
+//!   has no pairing gadget for BN254, so these signatures are only verified natively.
+use ark_ec::{
+    bls12::{Bls12, Bls12Config, G2Projective},
+    hashing::curve_maps::wb::WBConfig,
+    pairing::{Pairing, PairingOutput},
+    CurveGroup, PrimeGroup,
+};
+use ark_ff::{AdditiveGroup, PrimeField, UniformRand};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::{borrow::Cow, marker::PhantomData, vec, vec::Vec};
+use derivative::Derivative;
+use rand::Rng;
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+use super::{MinPk, SchemeVariant, SignatureVariant, SignatureVariantHasher};
+#[cfg(feature = "bn254")]
+use crate::hash::map_to_curve::svdw::SVDWMap;
+
+/// Hashes messages to the group `G` that signatures live in.
+pub trait CurveHasher<G: CurveGroup> {
+    /// Hash-to-curve suite ID, used to build the DST of each scheme.
+    const HASH_SUITE: &'static [u8];
+
+    fn hash_to_curve(message: &[u8], dst: &[u8]) -> G;
+}
+
+/// A pairing with a `CurveHasher` to its G2 group.
+pub trait BlsPairing: Pairing {
+    type CurveHasher: CurveHasher<Self::G2>;
+}
+
+/// The `MinPk` hasher of a BLS12 curve: WB hash-to-curve with Blake2s.
+pub struct Bls12Hasher<SigCurveConfig>(PhantomData<SigCurveConfig>);
+
+impl<SigCurveConfig: Bls12Config> CurveHasher<G2Projective<SigCurveConfig>>
+    for Bls12Hasher<SigCurveConfig>
+where
+    <SigCurveConfig as Bls12Config>::G2Config: WBConfig,
+{
+    const HASH_SUITE: &'static [u8] = <MinPk as SignatureVariant<SigCurveConfig>>::HASH_SUITE;
+
+    fn hash_to_curve(message: &[u8], dst: &[u8]) -> G2Projective<SigCurveConfig> {
+        <MinPk as SignatureVariantHasher<SigCurveConfig>>::hash_to_curve_with_dst(message, dst)
+    }
+}
+
+impl<SigCurveConfig: Bls12Config> BlsPairing for Bls12<SigCurveConfig>
+where
+    <SigCurveConfig as Bls12Config>::G2Config: WBConfig,
+{
+    type CurveHasher = Bls12Hasher<SigCurveConfig>;
+}
+
+/// Hash to the G2 group of BN254 with the Shallue-van de Woestijne map, since arkworks has
+/// no `WBConfig` for BN254, after hashing to the base field with Blake2s.
+#[cfg(feature = "bn254")]
+pub struct Bn254Hasher;
+
+#[cfg(feature = "bn254")]
+impl CurveHasher<ark_bn254::G2Projective> for Bn254Hasher {
+    const HASH_SUITE: &'static [u8] = b"BN254G2_XMD:BLAKE2S_SVDW_RO_";
+
+    fn hash_to_curve(message: &[u8], dst: &[u8]) -> ark_bn254::G2Projective {
+        use ark_ec::hashing::{map_to_curve_hasher::MapToCurveBasedHasher, HashToCurve};
+        use ark_ff::field_hashers::DefaultFieldHasher;
+        use blake2::Blake2s256;
+
+        let hasher = MapToCurveBasedHasher::<
+            ark_bn254::G2Projective,
+            DefaultFieldHasher<Blake2s256, 128>,
+            SVDWMap<ark_bn254::g2::Config>,
+        >::new(dst);
+
+        hasher
+            .expect("the SVDW map supports BN254")
+            .hash(message)
+            .unwrap()
+            .into()
+    }
+}
+
+#[cfg(feature = "bn254")]
+impl BlsPairing for ark_bn254::Bn254 {
+    type CurveHasher = Bn254Hasher;
+}
+
+#[derive(Derivative, CanonicalSerialize, CanonicalDeserialize)]
+#[derivative(
+    Clone(bound = ""),
+    Copy(bound = ""),
+    Debug(bound = ""),
+    Default(bound = "")
+)]
+pub struct Parameters<P: BlsPairing> {
+    pub g1_generator: P::G1,
+    /// Which IETF scheme messages are signed and verified under.
+    pub scheme: SchemeVariant,
+}
+
+#[derive(Derivative, CanonicalSerialize, CanonicalDeserialize)]
+#[derivative(
+    Clone(bound = ""),
+    Copy(bound = ""),
+    Debug(bound = ""),
+    Default(bound = "")
+)]
+pub struct PublicKey<P: BlsPairing> {
+    pub pub_key: P::G1,
+}
+
+/// The secret scalar is wiped from memory when the key is dropped.
+///
+/// Unlike `bls::SecretKey`, multiplications by it do not use the `ct` ladder, which is
+/// specific to short Weierstrass curves.
+#[derive(Derivative, CanonicalSerialize, CanonicalDeserialize)]
+#[derivative(Clone(bound = ""), Debug(bound = ""), Default(bound = ""))]
+pub struct SecretKey<P: BlsPairing> {
+    pub secret_key: P::ScalarField,
+}
+
+#[derive(Derivative, CanonicalSerialize, CanonicalDeserialize)]
+#[derivative(
+    Clone(bound = ""),
+    Copy(bound = ""),
+    Debug(bound = ""),
+    Default(bound = "")
+)]
+pub struct Signature<P: BlsPairing> {
+    pub signature: P::G2,
+}
+
+/// Whether `point` is neither the identity nor outside the prime-order subgroup.
+fn is_well_formed<G: CurveGroup>(point: &G) -> bool {
+    *point != G::ZERO && point.mul_bigint(<G::ScalarField as PrimeField>::MODULUS) == G::ZERO
+}
+
+impl<P: BlsPairing> Parameters<P> {
+    #[must_use]
+    pub fn setup() -> Self {
+        Self {
+            g1_generator: P::G1::generator(),
+            scheme: SchemeVariant::default(),
+        }
+    }
+
+    /// Domain separation tag of `self.scheme`, as in `bls::Parameters::dst`.
+    #[must_use]
+    pub fn dst(&self) -> Vec<u8> {
+        self.scheme
+            .dst(<P::CurveHasher as CurveHasher<P::G2>>::HASH_SUITE)
+    }
+
+    fn hash_to_curve(&self, message: &[u8], public_key: &PublicKey<P>) -> P::G2 {
+        let message = match self.scheme {
+            SchemeVariant::MessageAugmentation => {
+                Cow::Owned([public_key.to_compressed_bytes().as_slice(), message].concat())
+            }
+            SchemeVariant::Basic | SchemeVariant::ProofOfPossession => Cow::Borrowed(message),
+        };
+        <P::CurveHasher as CurveHasher<P::G2>>::hash_to_curve(&message, &self.dst())
+    }
+}
+
+impl<P: BlsPairing> SecretKey<P> {
+    pub fn new<R: Rng>(rng: &mut R) -> Self {
+        Self {
+            secret_key: P::ScalarField::rand(rng),
+        }
+    }
+}
+
+impl<P: BlsPairing> Zeroize for SecretKey<P> {
+    fn zeroize(&mut self) {
+        self.secret_key.zeroize();
+    }
+}
+
+impl<P: BlsPairing> Drop for SecretKey<P> {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl<P: BlsPairing> ZeroizeOnDrop for SecretKey<P> {}
+
+impl<P: BlsPairing> PublicKey<P> {
+    #[must_use]
+    pub fn new(secret_key: &SecretKey<P>, params: &Parameters<P>) -> Self {
+        Self {
+            pub_key: params.g1_generator * secret_key.secret_key,
+        }
+    }
+
+    /// Compressed encoding of the affine point, prefixed to messages under `MessageAugmentation`.
+    #[must_use]
+    pub fn to_compressed_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![];
+        self.pub_key
+            .into_affine()
+            .serialize_compressed(&mut bytes)
+            .expect("serializing a curve point into a vec should succeed");
+        bytes
+    }
+}
+
+impl<P: BlsPairing> Signature<P> {
+    #[must_use]
+    pub fn sign(message: &[u8], secret_key: &SecretKey<P>, params: &Parameters<P>) -> Self {
+        let public_key = PublicKey::new(secret_key, params);
+        Self {
+            signature: params.hash_to_curve(message, &public_key) * secret_key.secret_key,
+        }
+    }
+
+    /// Check e(g1, sig) == e(pk, H(msg)), rejecting identity and out-of-subgroup points.
+    #[must_use]
+    pub fn verify(
+        message: &[u8],
+        signature: &Self,
+        public_key: &PublicKey<P>,
+        params: &Parameters<P>,
+    ) -> bool {
+        Self::aggregate_verify(&[message], signature, &[*public_key], params)
+    }
+
+    /// Sum of `signatures`, or `None` if there are none.
+    #[must_use]
+    pub fn aggregate(signatures: &[Self]) -> Option<Self> {
+        if signatures.is_empty() {
+            return None;
+        }
+        Some(Self {
+            signature: signatures.iter().map(|sig| sig.signature).sum(),
+        })
+    }
+
+    /// Verify an aggregate of signatures by `public_keys[i]` on `messages[i]`.
+    ///
+    /// Under `Basic`, the messages must be distinct, and under `ProofOfPossession`, every key
+    /// must have a verified proof of possession; neither is checked here.
+    #[must_use]
+    pub fn aggregate_verify(
+        messages: &[&[u8]],
+        signature: &Self,
+        public_keys: &[PublicKey<P>],
+        params: &Parameters<P>,
+    ) -> bool {
+        if messages.is_empty()
+            || messages.len() != public_keys.len()
+            || !is_well_formed(&signature.signature)
+            || !public_keys.iter().all(|pk| is_well_formed(&pk.pub_key))
+        {
+            return false;
+        }
+
+        let g1_side = [-params.g1_generator]
+            .into_iter()
+            .chain(public_keys.iter().map(|pk| pk.pub_key));
+        let g2_side = [signature.signature].into_iter().chain(
+            messages
+                .iter()
+                .zip(public_keys)
+                .map(|(message, pk)| params.hash_to_curve(message, pk)),
+        );
+        P::multi_pairing(g1_side, g2_side) == PairingOutput::ZERO
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::thread_rng;
+
+    use crate::bls;
+
+    use super::*;
+
+    fn check_sign_verify<P: BlsPairing>(scheme: SchemeVariant) {
+        let mut rng = thread_rng();
+        let params = Parameters::<P> {
+            scheme,
+            ..Parameters::setup()
+        };
+
+        let sks: Vec<_> = (0..3).map(|_| SecretKey::<P>::new(&mut rng)).collect();
+        let pks: Vec<_> = sks.iter().map(|sk| PublicKey::new(sk, &params)).collect();
+        let messages: [&[u8]; 3] = [b"block 1", b"block 2", b"block 3"];
+
+        let sig = Signature::sign(messages[0], &sks[0], &params);
+        assert!(Signature::verify(messages[0], &sig, &pks[0], &params));
+        assert!(!Signature::verify(messages[1], &sig, &pks[0], &params));
+        assert!(!Signature::verify(messages[0], &sig, &pks[1], &params));
+
+        let sigs: Vec<_> = messages
+            .iter()
+            .zip(&sks)
+            .map(|(message, sk)| Signature::sign(message, sk, &params))
+            .collect();
+        let aggregate = Signature::aggregate(&sigs).unwrap();
+        assert!(Signature::aggregate_verify(
+            &messages, &aggregate, &pks, &params
+        ));
+        assert!(!Signature::aggregate_verify(
+            &messages[..2],
+            &aggregate,
+            &pks[..2],
+            &params
+        ));
+
+        // the identity signature does not verify against the identity key
+        let identity = PublicKey::<P> {
+            pub_key: P::G1::ZERO,
+        };
+        let zero = Signature::<P> {
+            signature: P::G2::ZERO,
+        };
+        assert!(!Signature::verify(messages[0], &zero, &identity, &params));
+    }
+
+    #[test]
+    fn check_bls12_377() {
+        check_sign_verify::<ark_bls12_377::Bls12_377>(SchemeVariant::ProofOfPossession);
+        check_sign_verify::<ark_bls12_377::Bls12_377>(SchemeVariant::MessageAugmentation);
+    }
+
+    #[cfg(feature = "bn254")]
+    #[test]
+    fn check_bn254() {
+        check_sign_verify::<ark_bn254::Bn254>(SchemeVariant::ProofOfPossession);
+        check_sign_verify::<ark_bn254::Bn254>(SchemeVariant::MessageAugmentation);
+    }
+
+    #[cfg(feature = "bn254")]
+    #[test]
+    fn check_bn254_svdw_map() {
+        use ark_ec::hashing::{curve_maps::parity, map_to_curve_hasher::MapToCurve};
+        use ark_ff::Field;
+
+        type Map = SVDWMap<ark_bn254::g2::Config>;
+        Map::check_parameters().unwrap();
+
+        let mut rng = thread_rng();
+        let inputs = [
+            ark_bn254::Fq2::ZERO,
+            ark_bn254::Fq2::ONE,
+            -ark_bn254::Fq2::ONE,
+        ];
+        for u in inputs
+            .into_iter()
+            .chain((0..20).map(|_| ark_bn254::Fq2::rand(&mut rng)))
+        {
+            let point = Map::map_to_curve(u).unwrap();
+            assert!(point.is_on_curve());
+            // the sign of `y` follows the one of `u`
+            assert_eq!(parity(&point.y), parity(&u));
+        }
+    }
+
+    #[cfg(feature = "bn254")]
+    #[test]
+    fn check_bn254_hash_in_subgroup() {
+        let dst = Parameters::<ark_bn254::Bn254>::setup().dst();
+        for message in [b"".as_slice(), b"a", b"Hello World"] {
+            let point = Bn254Hasher::hash_to_curve(message, &dst);
+            assert!(is_well_formed(&point));
+            assert_eq!(Bn254Hasher::hash_to_curve(message, &dst), point);
+        }
+    }
+
+    /// Over BLS12 curves, signatures are the same as those of `bls::Signature`, so
+    /// they can be verified by the BLS12 gadgets.
+    #[test]
+    fn check_bls12_377_matches_bls() {
+        type BlsSigConfig = ark_bls12_377::Config;
+        type P = ark_bls12_377::Bls12_377;
+
+        let mut rng = thread_rng();
+        let message = b"Hello World";
+
+        let params = Parameters::<P>::setup();
+        let sk = SecretKey::<P>::new(&mut rng);
+        let pk = PublicKey::new(&sk, &params);
+        let sig = Signature::sign(message, &sk, &params);
+
+        let bls_params = bls::Parameters::<BlsSigConfig>::setup();
+        let bls_sk = bls::SecretKey::<BlsSigConfig> {
+            secret_key: sk.secret_key,
+        };
+        let bls_pk = bls::PublicKey::new(&bls_sk, &bls_params);
+        let bls_sig = bls::Signature::<BlsSigConfig>::sign(message, &bls_sk, &bls_params);
+
+        assert_eq!(params.dst(), bls_params.dst::<MinPk>());
+        assert_eq!(pk.pub_key, bls_pk.pub_key);
+        assert_eq!(sig.signature, bls_sig.signature);
+        assert!(bls::Signature::verify(
+            message,
+            &bls::Signature::<BlsSigConfig> {
+                signature: sig.signature
+            },
+            &bls_pk,
+            &bls_params
+        ));
+    }
+}
@@ -0,0 +1,276 @@
+use ark_ec::bls12::Bls12Config;
+use ark_std::{vec, vec::Vec};
+use derivative::Derivative;
+
+use super::{
+    AggregatePublicKey, AggregateSignature, BLSError, HashedMessage, MinPk, Parameters, PublicKey,
+    SchemeVariant, Signature, SignatureVariant, SignatureVariantHasher,
+};
+
+impl<SigCurveConfig: Bls12Config, V: SignatureVariantHasher<SigCurveConfig>>
+    Signature<SigCurveConfig, V>
+{
+    /// Check one signer's share before it is aggregated.
+    ///
+    /// Unlike `verify`, malformed shares and keys are always rejected, whatever
+    /// `params.strict` says: a single bad share would otherwise poison the aggregate.
+    #[must_use]
+    pub fn verify_share(
+        message: &[u8],
+        share: &Self,
+        signer_public_key: &PublicKey<SigCurveConfig, V>,
+        params: &Parameters<SigCurveConfig>,
+    ) -> bool {
+        Self::verify_share_prehashed(
+            &HashedMessage::with_public_key(message, signer_public_key, params),
+            share,
+            signer_public_key,
+            params,
+        )
+    }
+
+    fn verify_share_prehashed(
+        hashed_message: &HashedMessage<SigCurveConfig, V>,
+        share: &Self,
+        signer_public_key: &PublicKey<SigCurveConfig, V>,
+        params: &Parameters<SigCurveConfig>,
+    ) -> bool {
+        share.is_well_formed()
+            && signer_public_key.is_well_formed()
+            && Self::verify_prehashed(hashed_message, share, signer_public_key, params)
+    }
+}
+
+/// Collects verified signature shares from a committee on a single message.
+///
+/// Signers are identified by their index in the committee, so `signers` lines up
+/// with the committee and can be used directly as the bitmap of a `QuorumSignature`.
+///
+/// Under `MessageAugmentation` each share is checked against the message prefixed with its
+/// signer's key, and the aggregate is checked with `aggregate_verify`. Under `Basic` shares
+/// still verify one by one, but `aggregate_verify` rejects aggregates of several signers.
+#[derive(Derivative)]
+#[derivative(Clone(bound = ""), Debug(bound = ""))]
+pub struct PartialAggregator<
+    SigCurveConfig: Bls12Config,
+    V: SignatureVariant<SigCurveConfig> = MinPk,
+> {
+    message: Vec<u8>,
+    /// The hashed message shared by all signers, or `None` under `MessageAugmentation`.
+    hashed_message: Option<HashedMessage<SigCurveConfig, V>>,
+    public_keys: Vec<PublicKey<SigCurveConfig, V>>,
+    signature: AggregateSignature<SigCurveConfig, V>,
+    signers: Vec<bool>,
+}
+
+impl<SigCurveConfig: Bls12Config, V: SignatureVariantHasher<SigCurveConfig>>
+    PartialAggregator<SigCurveConfig, V>
+{
+    /// Unless `params.scheme` is `MessageAugmentation`, the message is hashed once here, so
+    /// each share only costs a pairing check.
+    #[must_use]
+    pub fn new(
+        message: &[u8],
+        public_keys: Vec<PublicKey<SigCurveConfig, V>>,
+        params: &Parameters<SigCurveConfig>,
+    ) -> Self {
+        let signers = vec![false; public_keys.len()];
+        let hashed_message = (params.scheme != SchemeVariant::MessageAugmentation)
+            .then(|| HashedMessage::new(message, params));
+        Self {
+            message: message.to_vec(),
+            hashed_message,
+            public_keys,
+            signature: AggregateSignature::new(),
+            signers,
+        }
+    }
+
+    /// Verify the share of the `index`-th signer and add it to the aggregate.
+    ///
+    /// Rejected shares leave the aggregator unchanged.
+    pub fn add_share(
+        &mut self,
+        index: usize,
+        share: &Signature<SigCurveConfig, V>,
+        params: &Parameters<SigCurveConfig>,
+    ) -> Result<(), BLSError> {
+        let public_key = self.public_keys.get(index).ok_or(BLSError::UnknownSigner)?;
+        if self.signers[index] {
+            return Err(BLSError::DuplicateShare);
+        }
+        let hashed_message = self
+            .hashed_message
+            .unwrap_or_else(|| HashedMessage::with_public_key(&self.message, public_key, params));
+        if !Signature::verify_share_prehashed(&hashed_message, share, public_key, params) {
+            return Err(BLSError::InvalidShare);
+        }
+
+        self.signature.push(share);
+        self.signers[index] = true;
+        Ok(())
+    }
+
+    /// Bitmap of the signers whose shares have been added, indexed like the committee.
+    #[must_use]
+    pub fn signers(&self) -> &[bool] {
+        &self.signers
+    }
+
+    #[must_use]
+    pub fn signer_count(&self) -> usize {
+        self.signature.len()
+    }
+
+    /// Returns `None` if no share has been added yet.
+    #[must_use]
+    pub fn aggregate_signature(&self) -> Option<Signature<SigCurveConfig, V>> {
+        self.signature.finalize()
+    }
+
+    /// The aggregate public key of the current signers, or `None` if there are none.
+    #[must_use]
+    pub fn aggregate_public_key(&self) -> Option<AggregatePublicKey<SigCurveConfig, V>> {
+        let public_keys: Vec<_> = self
+            .public_keys
+            .iter()
+            .zip(&self.signers)
+            .filter(|(_, signed)| **signed)
+            .map(|(public_key, _)| *public_key)
+            .collect();
+        AggregatePublicKey::aggregate(&public_keys)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::bls::get_aggregate_bls_instance;
+
+    use super::*;
+
+    type SigCurveConfig = ark_bls12_381::Config;
+
+    #[test]
+    fn check_verify_share() {
+        let (msg, params, secret_keys, public_keys, _) =
+            get_aggregate_bls_instance::<SigCurveConfig>();
+        let msg = msg.as_bytes();
+
+        let share = Signature::sign(msg, &secret_keys[0], &params);
+        assert!(Signature::verify_share(
+            msg,
+            &share,
+            &public_keys[0],
+            &params
+        ));
+        assert!(!Signature::verify_share(
+            msg,
+            &share,
+            &public_keys[1],
+            &params
+        ));
+
+        // malformed shares are rejected even when strict verification is off
+        let mut params = params;
+        params.strict = false;
+        assert!(!Signature::<SigCurveConfig>::verify_share(
+            msg,
+            &Signature::default(),
+            &PublicKey::default(),
+            &params
+        ));
+    }
+
+    #[test]
+    fn check_partial_aggregator() {
+        let (msg, params, secret_keys, public_keys, sig) =
+            get_aggregate_bls_instance::<SigCurveConfig>();
+        let msg = msg.as_bytes();
+
+        let mut aggregator = PartialAggregator::new(msg, public_keys.clone(), &params);
+        assert!(aggregator.aggregate_signature().is_none());
+
+        let shares: Vec<_> = secret_keys
+            .iter()
+            .map(|sk| Signature::sign(msg, sk, &params))
+            .collect();
+        aggregator.add_share(1, &shares[1], &params).unwrap();
+        assert_eq!(aggregator.signer_count(), 1);
+        assert!(aggregator.signers()[1]);
+
+        // wrong signer, duplicate and out-of-range shares change nothing
+        assert_eq!(
+            aggregator.add_share(0, &shares[2], &params),
+            Err(BLSError::InvalidShare)
+        );
+        assert_eq!(
+            aggregator.add_share(1, &shares[1], &params),
+            Err(BLSError::DuplicateShare)
+        );
+        assert_eq!(
+            aggregator.add_share(public_keys.len(), &shares[0], &params),
+            Err(BLSError::UnknownSigner)
+        );
+        assert_eq!(aggregator.signer_count(), 1);
+
+        for (i, share) in shares.iter().enumerate().filter(|(i, _)| *i != 1) {
+            aggregator.add_share(i, share, &params).unwrap();
+        }
+        assert!(aggregator.signers().iter().all(|signed| *signed));
+
+        let aggregate_sig = aggregator.aggregate_signature().unwrap();
+        assert_eq!(aggregate_sig.signature, sig.signature);
+        assert!(Signature::verify_aggregate(
+            msg,
+            &aggregate_sig,
+            &aggregator.aggregate_public_key().unwrap(),
+            &params
+        ));
+    }
+
+    #[test]
+    fn check_partial_aggregator_schemes() {
+        let (msg, params, secret_keys, public_keys, _) =
+            get_aggregate_bls_instance::<SigCurveConfig>();
+        let msg = msg.as_bytes();
+        let (secret_keys, public_keys) = (&secret_keys[..4], &public_keys[..4]);
+
+        for scheme in [
+            SchemeVariant::Basic,
+            SchemeVariant::MessageAugmentation,
+            SchemeVariant::ProofOfPossession,
+        ] {
+            let params = Parameters { scheme, ..params };
+            let mut aggregator = PartialAggregator::new(msg, public_keys.to_vec(), &params);
+            let shares: Vec<_> = secret_keys
+                .iter()
+                .map(|sk| Signature::sign(msg, sk, &params))
+                .collect();
+            for (i, share) in shares.iter().enumerate() {
+                aggregator.add_share(i, share, &params).unwrap();
+            }
+            assert_eq!(
+                aggregator.add_share(0, &shares[1], &params),
+                Err(BLSError::DuplicateShare)
+            );
+
+            // a share on the unprefixed message is not one of the committee's
+            if scheme == SchemeVariant::MessageAugmentation {
+                let mut fresh = PartialAggregator::new(msg, public_keys.to_vec(), &params);
+                let hashed_message = HashedMessage::new(msg, &params);
+                let share = Signature::sign_prehashed(&hashed_message, &secret_keys[0], &params);
+                assert_eq!(
+                    fresh.add_share(0, &share, &params),
+                    Err(BLSError::InvalidShare)
+                );
+            }
+
+            // `Basic` needs distinct messages to aggregate
+            let aggregate_sig = aggregator.aggregate_signature().unwrap();
+            assert_eq!(
+                Signature::aggregate_verify(msg, &aggregate_sig, public_keys, &params),
+                Some(scheme != SchemeVariant::Basic)
+            );
+        }
+    }
+}
@@ -0,0 +1,165 @@
+use ark_ec::{
+    bls12::{Bls12, Bls12Config},
+    hashing::curve_maps::wb::WBConfig,
+    pairing::{Pairing, PairingOutput},
+    CurveGroup,
+};
+use ark_ff::AdditiveGroup;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::vec::Vec;
+use derivative::Derivative;
+
+use super::{
+    bls::validate_point, ct::mul_secret, params::G2, Parameters, PublicKey, SecretKey, Signature,
+};
+
+/// Domain separation tag for proofs of possession. It must differ from the DST used
+/// by `Signature::sign`, otherwise a PoP could be replayed as a signature on the
+/// serialized public key (and vice versa).
+///
+/// Follows the `BLS_POP_` + ciphersuite naming of the IETF BLS signature draft.
+pub const POP_DST: &[u8] = b"BLS_POP_BLS12G2_XMD:BLAKE2S_WB_RO_POP_";
+
+/// A proof that the owner of a public key knows the corresponding secret key.
+///
+/// Aggregating public keys is only safe against rogue-key attacks if every key
+/// comes with a verified PoP.
+#[derive(Derivative, CanonicalSerialize, CanonicalDeserialize)]
+#[derivative(
+    Clone(bound = ""),
+    Copy(bound = ""),
+    Debug(bound = ""),
+    Default(bound = "")
+)]
+pub struct ProofOfPossession<SigCurveConfig: Bls12Config> {
+    pub proof: G2<SigCurveConfig>,
+}
+
+impl<SigCurveConfig: Bls12Config> ProofOfPossession<SigCurveConfig>
+where
+    <SigCurveConfig as Bls12Config>::G2Config: WBConfig,
+{
+    /// The message signed by a PoP is the compressed encoding of the public key.
+    fn message(public_key: &PublicKey<SigCurveConfig>) -> Vec<u8> {
+        public_key.to_compressed_bytes()
+    }
+
+    #[must_use]
+    pub fn prove(
+        secret_key: &SecretKey<SigCurveConfig>,
+        params: &Parameters<SigCurveConfig>,
+    ) -> Self {
+        let public_key = PublicKey::new(secret_key, params);
+        let hashed_pk = Signature::<SigCurveConfig>::hash_to_curve_with_dst(
+            &Self::message(&public_key),
+            POP_DST,
+        );
+        Self {
+            proof: mul_secret(&hashed_pk, &secret_key.secret_key),
+        }
+    }
+
+    #[must_use]
+    pub fn verify(
+        public_key: &PublicKey<SigCurveConfig>,
+        pop: &Self,
+        params: &Parameters<SigCurveConfig>,
+    ) -> bool {
+        if params.strict
+            && !(public_key.is_well_formed() && validate_point(&pop.proof.into_affine()).is_ok())
+        {
+            return false;
+        }
+
+        let hashed_pk = Signature::<SigCurveConfig>::hash_to_curve_with_dst(
+            &Self::message(public_key),
+            POP_DST,
+        );
+
+        // check e(g1, pop) == e(pk, H_pop(pk)) in the same way as `Signature::verify`
+        let prod = Bls12::<SigCurveConfig>::multi_pairing(
+            [-params.g1_generator, public_key.pub_key],
+            [pop.proof, hashed_pk],
+        );
+
+        prod == PairingOutput::ZERO
+    }
+}
+
+impl<SigCurveConfig: Bls12Config> Signature<SigCurveConfig>
+where
+    <SigCurveConfig as Bls12Config>::G2Config: WBConfig,
+{
+    /// Verify an aggregate signature on a single message, assuming every public key
+    /// has already passed `ProofOfPossession::verify`.
+    ///
+    /// Unlike `aggregate_verify`, this uses the optimized pairing check of `verify`.
+    /// Calling it with keys that were never PoP-checked is vulnerable to rogue-key attacks.
+    #[must_use]
+    pub fn aggregate_verify_with_pop(
+        message: &[u8],
+        aggregate_signature: &Self,
+        public_keys: &[PublicKey<SigCurveConfig>],
+        params: &Parameters<SigCurveConfig>,
+    ) -> Option<bool> {
+        let pk = public_keys
+            .iter()
+            .map(|pk| pk.pub_key)
+            .reduce(|acc, pk| acc + pk)
+            .map(PublicKey::from_projective)?;
+
+        Some(Self::verify(message, aggregate_signature, &pk, params))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::thread_rng;
+
+    use crate::bls::{get_aggregate_bls_instance, Parameters, PublicKey, SecretKey, Signature};
+
+    use super::ProofOfPossession;
+
+    type SigCurveConfig = ark_bls12_381::Config;
+
+    #[test]
+    fn check_pop() {
+        let mut rng = thread_rng();
+        let params = Parameters::<SigCurveConfig>::setup();
+        let sk = SecretKey::new(&mut rng);
+        let pk = PublicKey::new(&sk, &params);
+
+        let pop = ProofOfPossession::prove(&sk, &params);
+        assert!(ProofOfPossession::verify(&pk, &pop, &params));
+
+        // a pop does not transfer to another key
+        let other_pk = PublicKey::new(&SecretKey::new(&mut rng), &params);
+        assert!(!ProofOfPossession::verify(&other_pk, &pop, &params));
+    }
+
+    #[test]
+    fn check_pop_domain_separation() {
+        let mut rng = thread_rng();
+        let params = Parameters::<SigCurveConfig>::setup();
+        let sk = SecretKey::new(&mut rng);
+        let pk = PublicKey::new(&sk, &params);
+
+        // a signature on the encoded public key must not be accepted as a pop
+        let msg = ProofOfPossession::message(&pk);
+        let sig: Signature<SigCurveConfig> = Signature::sign(&msg, &sk, &params);
+        let forged = ProofOfPossession {
+            proof: sig.signature,
+        };
+        assert!(!ProofOfPossession::verify(&pk, &forged, &params));
+    }
+
+    #[test]
+    fn check_aggregate_verify_with_pop() {
+        let (msg, params, _, public_keys, sig) = get_aggregate_bls_instance::<SigCurveConfig>();
+        assert!(
+            Signature::aggregate_verify_with_pop(msg.as_bytes(), &sig, &public_keys, &params)
+                .unwrap()
+        );
+        assert!(Signature::aggregate_verify_with_pop(msg.as_bytes(), &sig, &[], &params).is_none());
+    }
+}
@@ -1,16 +1,17 @@
 use core::borrow::Borrow;
-use std::marker::PhantomData;
+use core::marker::PhantomData;
 
-use ark_crypto_primitives::prf::blake2s::constraints::Blake2sGadget;
 use ark_ec::bls12::{Bls12, Bls12Config};
 use ark_ec::hashing::curve_maps::wb::WBConfig;
 use ark_ec::pairing::Pairing;
+use ark_ec::scalar_mul::glv::GLVConfig;
 use ark_ec::short_weierstrass::SWCurveConfig;
-use ark_ec::{CurveConfig, CurveGroup};
+use ark_ec::CurveGroup;
 use ark_ff::{Field, PrimeField};
 use ark_r1cs_std::alloc::{AllocVar, AllocationMode};
 use ark_r1cs_std::eq::EqGadget;
 use ark_r1cs_std::fields::{FieldOpsBounds, FieldVar};
+use ark_r1cs_std::groups::curves::short_weierstrass::ProjectiveVar;
 use ark_r1cs_std::groups::CurveVar;
 use ark_r1cs_std::pairing::bls12;
 use ark_r1cs_std::prelude::{Boolean, PairingVar};
@@ -20,19 +21,26 @@ use ark_relations::r1cs::{Namespace, SynthesisError};
 
 // Assuming the sig is running on BLS12 family of curves
 use ark_r1cs_std::groups::bls12::{G1PreparedVar, G1Var, G2PreparedVar, G2Var};
+use ark_std::vec::Vec;
 use derivative::Derivative;
 
 use crate::hash::hash_to_curve::cofactor::CofactorGadget;
-use crate::hash::hash_to_curve::MapToCurveBasedHasherGadget;
-use crate::hash::hash_to_field::default_hasher::DefaultFieldHasherGadget;
+use crate::hash::hash_to_curve::{HashToCurveGadget, MapToCurveBasedHasherGadget};
 use crate::hash::{
-    hash_to_field::from_base_field::FromBaseFieldVarGadget,
+    hash_to_field::{
+        default_hasher::Blake2sHasher, from_base_field::FromBaseFieldVarGadget, FieldHasher,
+        FieldHasherGadget,
+    },
     map_to_curve::{sqrt::SqrtGadget, to_base_field::ToBaseFieldVarGadget, wb::WBMapGadget},
 };
 use crate::params::BlsSigField;
 
 use super::params::{HashCurveConfig, HashCurveGroup, HashCurveVar};
-use super::{Parameters, PublicKey, Signature};
+use super::subgroup::Bls12SubgroupGadget;
+use super::{
+    glv_scalar_mul_var, MinPk, MinSig, Parameters, PublicKey, SchemeVariant, Signature,
+    SignatureVariant,
+};
 
 #[derive(Derivative)]
 #[derivative(Clone(bound = ""), Debug(bound = ""))]
@@ -45,6 +53,41 @@ pub struct ParametersVar<
 {
     pub g1_generator: G1Var<SigCurveConfig, FV, CF>,
     pub g2_generator: G2Var<SigCurveConfig, FV, CF>,
+    /// Mirrors `Parameters::strict`. This is not allocated, it only decides
+    /// which constraints are generated: it is copied from constant parameters, and is on
+    /// for parameters allocated as inputs or witnesses.
+    pub strict: bool,
+    /// Mirrors `Parameters::scheme`, and is not allocated either: it is copied from
+    /// constant parameters, and is the default scheme otherwise.
+    pub scheme: SchemeVariant,
+    /// Whether verification enforces that the public key and the signature are in the
+    /// prime-order subgroup. `AllocVar` only checks this for witnesses, so it should be
+    /// set whenever they are allocated as inputs. Not allocated, and off by default.
+    pub subgroup_check: bool,
+}
+
+impl<
+        SigCurveConfig: Bls12Config,
+        FV: FieldVar<BlsSigField<SigCurveConfig>, CF>,
+        CF: PrimeField,
+    > ParametersVar<SigCurveConfig, FV, CF>
+where
+    for<'a> &'a FV: FieldOpsBounds<'a, BlsSigField<SigCurveConfig>, FV>,
+{
+    /// Same as `Parameters::dst`, as constants of the circuit.
+    ///
+    /// `MessageAugmentation` is not supported, since prefixing the message would need
+    /// the compressed encoding of the public key inside the circuit.
+    pub fn dst<V: SignatureVariant<SigCurveConfig>>(
+        &self,
+    ) -> Result<Vec<UInt8<CF>>, SynthesisError> {
+        match self.scheme {
+            SchemeVariant::MessageAugmentation => Err(SynthesisError::Unsatisfiable),
+            SchemeVariant::Basic | SchemeVariant::ProofOfPossession => {
+                Ok(UInt8::constant_vec(&self.scheme.dst(V::HASH_SUITE)))
+            }
+        }
+    }
 }
 
 #[derive(Derivative)]
@@ -59,23 +102,54 @@ pub struct PublicKeyVar<
     pub pub_key: G1Var<SigCurveConfig, FV, CF>,
 }
 
+/// A `MinPk` signature, whose messages are hashed to the curve with `H`.
 #[derive(Derivative)]
 #[derivative(Clone(bound = ""), Debug(bound = ""))]
 pub struct SignatureVar<
     SigCurveConfig: Bls12Config,
     FV: FieldVar<BlsSigField<SigCurveConfig>, CF>,
     CF: PrimeField,
+    H = Blake2sHasher,
 > where
     for<'a> &'a FV: FieldOpsBounds<'a, BlsSigField<SigCurveConfig>, FV>,
 {
     pub signature: G2Var<SigCurveConfig, FV, CF>,
+    pub(super) _hasher: PhantomData<H>,
+}
+
+#[derive(Derivative)]
+#[derivative(Clone(bound = ""), Debug(bound = ""))]
+pub struct MinSigPublicKeyVar<
+    SigCurveConfig: Bls12Config,
+    FV: FieldVar<BlsSigField<SigCurveConfig>, CF>,
+    CF: PrimeField,
+> where
+    for<'a> &'a FV: FieldOpsBounds<'a, BlsSigField<SigCurveConfig>, FV>,
+{
+    pub pub_key: G2Var<SigCurveConfig, FV, CF>,
+}
+
+/// A `MinSig` signature, whose messages are hashed to the curve with `H`.
+#[derive(Derivative)]
+#[derivative(Clone(bound = ""), Debug(bound = ""))]
+pub struct MinSigSignatureVar<
+    SigCurveConfig: Bls12Config,
+    FV: FieldVar<BlsSigField<SigCurveConfig>, CF>,
+    CF: PrimeField,
+    H = Blake2sHasher,
+> where
+    for<'a> &'a FV: FieldOpsBounds<'a, BlsSigField<SigCurveConfig>, FV>,
+{
+    pub signature: G1Var<SigCurveConfig, FV, CF>,
+    pub(super) _hasher: PhantomData<H>,
 }
 
 pub struct BLSAggregateSignatureVerifyGadget<
     SigCurveConfig: Bls12Config,
     FV: FieldVar<BlsSigField<SigCurveConfig>, CF>,
     CF: PrimeField,
->(PhantomData<(FV, SigCurveConfig, CF)>);
+    H = Blake2sHasher,
+>(PhantomData<(FV, SigCurveConfig, CF, H)>);
 
 impl<
         SigCurveConfig: Bls12Config,
@@ -84,7 +158,12 @@ impl<
             + ToBaseFieldVarGadget<BlsSigField<SigCurveConfig>, CF>
             + SqrtGadget<BlsSigField<SigCurveConfig>, CF>,
         CF: PrimeField,
-    > BLSAggregateSignatureVerifyGadget<SigCurveConfig, FV, CF>
+        H: FieldHasherGadget<
+            <HashCurveGroup<SigCurveConfig> as CurveGroup>::BaseField,
+            CF,
+            HashCurveVar<SigCurveConfig, FV, CF>,
+        >,
+    > BLSAggregateSignatureVerifyGadget<SigCurveConfig, FV, CF, H>
 where
     for<'a> &'a FV: FieldOpsBounds<'a, BlsSigField<SigCurveConfig>, FV>,
     <SigCurveConfig as Bls12Config>::G2Config: WBConfig,
@@ -98,15 +177,23 @@ where
     HashCurveVar<SigCurveConfig, FV, CF>:
         FieldVar<<HashCurveGroup<SigCurveConfig> as CurveGroup>::BaseField, CF>,
     HashCurveGroup<SigCurveConfig>: CofactorGadget<HashCurveVar<SigCurveConfig, FV, CF>, CF>,
+    SigCurveConfig: Bls12SubgroupGadget<FV, CF>,
 {
     #[tracing::instrument(skip_all)]
     pub fn verify(
         parameters: &ParametersVar<SigCurveConfig, FV, CF>,
         pk: &PublicKeyVar<SigCurveConfig, FV, CF>,
         message: &[UInt8<CF>],
-        signature: &SignatureVar<SigCurveConfig, FV, CF>,
+        signature: &SignatureVar<SigCurveConfig, FV, CF, H>,
     ) -> Result<(), SynthesisError> {
-        let hash_to_curve = Self::hash_to_curve(message)?;
+        if parameters.strict {
+            Self::enforce_not_identity(pk, signature)?;
+        }
+        if parameters.subgroup_check {
+            Self::enforce_in_subgroup(pk, signature)?;
+        }
+
+        let hash_to_curve = Self::hash_to_curve(message, &parameters.dst::<MinPk<H>>()?)?;
 
         // an optimised way to check two pairings are equal
         let prod = bls12::PairingVar::product_of_pairings(
@@ -144,9 +231,16 @@ where
         parameters: &ParametersVar<SigCurveConfig, FV, CF>,
         pk: &PublicKeyVar<SigCurveConfig, FV, CF>,
         message: &[UInt8<CF>],
-        signature: &SignatureVar<SigCurveConfig, FV, CF>,
+        signature: &SignatureVar<SigCurveConfig, FV, CF, H>,
     ) -> Result<(), SynthesisError> {
-        let hash_to_curve = Self::hash_to_curve(message)?;
+        if parameters.strict {
+            Self::enforce_not_identity(pk, signature)?;
+        }
+        if parameters.subgroup_check {
+            Self::enforce_in_subgroup(pk, signature)?;
+        }
+
+        let hash_to_curve = Self::hash_to_curve(message, &parameters.dst::<MinPk<H>>()?)?;
 
         // Verify e(signature, G) == e(aggregated_pk, H(m))
         let signature_paired = bls12::PairingVar::pairing(
@@ -165,6 +259,31 @@ where
         Ok(())
     }
 
+    /// Enforce that neither the public key nor the signature is the identity.
+    ///
+    /// Subgroup membership is enforced separately by `enforce_in_subgroup`.
+    fn enforce_not_identity(
+        pk: &PublicKeyVar<SigCurveConfig, FV, CF>,
+        signature: &SignatureVar<SigCurveConfig, FV, CF, H>,
+    ) -> Result<(), SynthesisError> {
+        // points are kept in projective coordinates, where the identity has z = 0
+        pk.pub_key.z.is_zero()?.enforce_equal(&Boolean::FALSE)?;
+        signature
+            .signature
+            .z
+            .is_zero()?
+            .enforce_equal(&Boolean::FALSE)
+    }
+
+    /// Enforce that the public key and the signature are in the prime-order subgroup.
+    fn enforce_in_subgroup(
+        pk: &PublicKeyVar<SigCurveConfig, FV, CF>,
+        signature: &SignatureVar<SigCurveConfig, FV, CF, H>,
+    ) -> Result<(), SynthesisError> {
+        SigCurveConfig::enforce_in_g1(&pk.pub_key)?;
+        SigCurveConfig::enforce_in_g2(&signature.signature)
+    }
+
     /// Not recommended, public key aggregation can be moved outside the SNARK
     ///
     /// The time complexity will not change as we always need to pay the cost of
@@ -173,7 +292,7 @@ where
         parameters: &ParametersVar<SigCurveConfig, FV, CF>,
         public_keys: &[PublicKeyVar<SigCurveConfig, FV, CF>],
         message: &[UInt8<CF>],
-        signature: &SignatureVar<SigCurveConfig, FV, CF>,
+        signature: &SignatureVar<SigCurveConfig, FV, CF, H>,
     ) -> Result<(), SynthesisError> {
         // Aggregate all public keys
         let aggregated_pk =
@@ -191,25 +310,21 @@ where
     #[tracing::instrument(skip_all)]
     pub fn hash_to_curve(
         msg: &[UInt8<CF>],
+        dst: &[UInt8<CF>],
     ) -> Result<G2Var<SigCurveConfig, FV, CF>, SynthesisError> {
-        type HashGroupBaseField<SigCurveConfig> =
-            <HashCurveConfig<SigCurveConfig> as CurveConfig>::BaseField;
-
-        type FieldHasherGadget<SigCurveConfig, FV, CF> = DefaultFieldHasherGadget<
-            Blake2sGadget<CF>,
-            HashGroupBaseField<SigCurveConfig>,
+        type FieldHasherGadgetOf<SigCurveConfig, FV, CF, H> = <H as FieldHasherGadget<
+            <HashCurveGroup<SigCurveConfig> as CurveGroup>::BaseField,
             CF,
             HashCurveVar<SigCurveConfig, FV, CF>,
-            128,
-        >;
+        >>::Gadget;
 
         // this is slightly different from its counterpart in `bls.rs` because of how WBMapGadget is defined
         type CurveMapGadget<SigCurveConfig> =
             WBMapGadget<<SigCurveConfig as Bls12Config>::G2Config>;
 
-        type HasherGadget<SigCurveConfig, FV, CF> = MapToCurveBasedHasherGadget<
+        type HasherGadget<SigCurveConfig, FV, CF, H> = MapToCurveBasedHasherGadget<
             HashCurveGroup<SigCurveConfig>,
-            FieldHasherGadget<SigCurveConfig, FV, CF>,
+            FieldHasherGadgetOf<SigCurveConfig, FV, CF, H>,
             CurveMapGadget<SigCurveConfig>,
             CF,
             HashCurveVar<SigCurveConfig, FV, CF>,
@@ -218,7 +333,7 @@ where
         let cs = msg.cs();
         tracing::info!(num_constraints = cs.num_constraints());
 
-        let hasher_gadget = HasherGadget::<SigCurveConfig, FV, CF>::new(&[]);
+        let hasher_gadget = HasherGadget::<SigCurveConfig, FV, CF, H>::new(dst);
         let hash = hasher_gadget.hash(msg);
 
         tracing::info!(num_constraints = cs.num_constraints());
@@ -227,16 +342,318 @@ where
     }
 }
 
+/// Verification gadget for a batch of `MinPk` signatures on different messages, which
+/// checks all of them with a single product of pairings.
+pub struct BLSBatchVerifyGadget<
+    SigCurveConfig: Bls12Config,
+    FV: FieldVar<BlsSigField<SigCurveConfig>, CF>,
+    CF: PrimeField,
+    H = Blake2sHasher,
+>(PhantomData<(FV, SigCurveConfig, CF, H)>);
+
+impl<
+        SigCurveConfig: Bls12Config,
+        FV: FieldVar<BlsSigField<SigCurveConfig>, CF>
+            + FromBaseFieldVarGadget<CF>
+            + ToBaseFieldVarGadget<BlsSigField<SigCurveConfig>, CF>
+            + SqrtGadget<BlsSigField<SigCurveConfig>, CF>,
+        CF: PrimeField,
+        H: FieldHasherGadget<
+            <HashCurveGroup<SigCurveConfig> as CurveGroup>::BaseField,
+            CF,
+            HashCurveVar<SigCurveConfig, FV, CF>,
+        >,
+    > BLSBatchVerifyGadget<SigCurveConfig, FV, CF, H>
+where
+    for<'a> &'a FV: FieldOpsBounds<'a, BlsSigField<SigCurveConfig>, FV>,
+    <SigCurveConfig as Bls12Config>::G2Config: WBConfig + GLVConfig,
+
+    HashCurveConfig<SigCurveConfig>: SWCurveConfig,
+    for<'a> &'a HashCurveVar<SigCurveConfig, FV, CF>: FieldOpsBounds<
+        'a,
+        <HashCurveGroup<SigCurveConfig> as CurveGroup>::BaseField,
+        HashCurveVar<SigCurveConfig, FV, CF>,
+    >,
+    HashCurveVar<SigCurveConfig, FV, CF>:
+        FieldVar<<HashCurveGroup<SigCurveConfig> as CurveGroup>::BaseField, CF>,
+    HashCurveGroup<SigCurveConfig>: CofactorGadget<HashCurveVar<SigCurveConfig, FV, CF>, CF>,
+    SigCurveConfig: Bls12SubgroupGadget<FV, CF>,
+{
+    /// Verify that `signatures[i]` is a signature on `messages[i]` by `public_keys[i]` for
+    /// every `i`, by checking
+    /// `e(-G, sum_i r_i * signatures[i]) * prod_i e(r_i * public_keys[i], H(messages[i])) = 1`,
+    /// where `r_i` is the (little-endian) `challenges[i]`.
+    ///
+    /// This takes `K + 1` Miller loops and one final exponentiation for `K` signatures,
+    /// against `2K` Miller loops and `K` final exponentiations for `K` calls to
+    /// `BLSAggregateSignatureVerifyGadget::verify`. An invalid signature passes with
+    /// probability about `2^-n` for `n`-bit challenges, as long as the challenges are
+    /// unpredictable to the signers, e.g. sampled by the verifier or derived from the public
+    /// inputs with Fiat-Shamir. The challenges are enforced to be non-zero, since a zero
+    /// challenge drops its signature from the check.
+    ///
+    /// With full-length challenges and `parameters.subgroup_check`, the signatures are
+    /// multiplied with `glv_scalar_mul_var`, which is cheaper on G2 for such scalars.
+    #[tracing::instrument(skip_all)]
+    pub fn verify(
+        parameters: &ParametersVar<SigCurveConfig, FV, CF>,
+        public_keys: &[PublicKeyVar<SigCurveConfig, FV, CF>],
+        messages: &[Vec<UInt8<CF>>],
+        signatures: &[SignatureVar<SigCurveConfig, FV, CF, H>],
+        challenges: &[Vec<Boolean<CF>>],
+    ) -> Result<(), SynthesisError> {
+        let num_signatures = public_keys.len();
+        if messages.len() != num_signatures
+            || signatures.len() != num_signatures
+            || challenges.len() != num_signatures
+            || challenges.iter().any(Vec::is_empty)
+        {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+        if num_signatures == 0 {
+            return Ok(());
+        }
+
+        let dst = parameters.dst::<MinPk<H>>()?;
+        let mut g1 = Vec::with_capacity(num_signatures + 1);
+        let mut g2 = Vec::with_capacity(num_signatures + 1);
+        let mut signatures_var = Vec::with_capacity(num_signatures);
+        for (((pk, message), sig), challenge) in public_keys
+            .iter()
+            .zip(messages)
+            .zip(signatures)
+            .zip(challenges)
+        {
+            Boolean::kary_or(challenge)?.enforce_equal(&Boolean::TRUE)?;
+            if parameters.strict {
+                BLSAggregateSignatureVerifyGadget::enforce_not_identity(pk, sig)?;
+            }
+            if parameters.subgroup_check {
+                BLSAggregateSignatureVerifyGadget::enforce_in_subgroup(pk, sig)?;
+            }
+
+            let hash_to_curve =
+                BLSAggregateSignatureVerifyGadget::<SigCurveConfig, FV, CF, H>::hash_to_curve(
+                    message, &dst,
+                )?;
+            g1.push(G1PreparedVar::<SigCurveConfig, FV, CF>::from_group_var(
+                &pk.pub_key.scalar_mul_le(challenge.iter())?,
+            )?);
+            g2.push(G2PreparedVar::<SigCurveConfig, FV, CF>::from_group_var(
+                &hash_to_curve,
+            )?);
+            signatures_var.push(g2_scalar_mul(
+                &sig.signature,
+                challenge,
+                parameters.subgroup_check,
+            )?);
+        }
+        g1.push(G1PreparedVar::<SigCurveConfig, FV, CF>::from_group_var(
+            &parameters.g1_generator.negate()?,
+        )?);
+        let signature = signatures_var.into_iter().reduce(|acc, s| acc + s);
+        g2.push(G2PreparedVar::<SigCurveConfig, FV, CF>::from_group_var(
+            &signature.expect("there is at least one signature"),
+        )?);
+
+        let prod = bls12::PairingVar::product_of_pairings(&g1, &g2)?;
+
+        let cs = prod.cs();
+
+        prod.is_eq(
+            &<bls12::PairingVar<SigCurveConfig, FV, CF> as PairingVar<
+                Bls12<SigCurveConfig>,
+                CF,
+            >>::GTVar::new_constant(
+                cs.clone(),
+                <<Bls12<SigCurveConfig> as Pairing>::TargetField as Field>::ONE,
+            )?,
+        )?
+        .enforce_equal(&Boolean::TRUE)?;
+
+        tracing::info!(num_constraints = cs.num_constraints());
+
+        Ok(())
+    }
+}
+
+/// `[scalar]p` on G2, outside of the bounds of `BLSBatchVerifyGadget`, under which rustc
+/// fails to normalize the scalar field of G2.
+///
+/// GLV only saves constraints over `scalar_mul_le` for full-length scalars, and needs `p` to
+/// be in the prime-order subgroup.
+fn g2_scalar_mul<P, F, CF>(
+    p: &ProjectiveVar<P, F, CF>,
+    scalar: &[Boolean<CF>],
+    in_subgroup: bool,
+) -> Result<ProjectiveVar<P, F, CF>, SynthesisError>
+where
+    P: GLVConfig,
+    F: FieldVar<P::BaseField, CF>,
+    CF: PrimeField,
+    for<'a> &'a F: FieldOpsBounds<'a, P::BaseField, F>,
+{
+    if in_subgroup && scalar.len() >= P::ScalarField::MODULUS_BIT_SIZE as usize {
+        glv_scalar_mul_var(p, scalar)
+    } else {
+        p.scalar_mul_le(scalar.iter())
+    }
+}
+
+/// Verification gadget for the `MinSig` variant: public keys on G2, signatures on G1.
+///
+/// Hashing to G1 avoids the Fp2 arithmetic of hashing to G2, which makes this variant
+/// noticeably cheaper to verify inside the circuit.
+pub struct BLSMinSigVerifyGadget<
+    SigCurveConfig: Bls12Config,
+    FV: FieldVar<BlsSigField<SigCurveConfig>, CF>,
+    CF: PrimeField,
+    H = Blake2sHasher,
+>(PhantomData<(FV, SigCurveConfig, CF, H)>);
+
+impl<
+        SigCurveConfig: Bls12Config,
+        FV: FieldVar<BlsSigField<SigCurveConfig>, CF>
+            + FromBaseFieldVarGadget<CF>
+            + ToBaseFieldVarGadget<BlsSigField<SigCurveConfig>, CF>
+            + SqrtGadget<BlsSigField<SigCurveConfig>, CF>,
+        CF: PrimeField,
+        H: FieldHasherGadget<BlsSigField<SigCurveConfig>, CF, FV>,
+    > BLSMinSigVerifyGadget<SigCurveConfig, FV, CF, H>
+where
+    for<'a> &'a FV: FieldOpsBounds<'a, BlsSigField<SigCurveConfig>, FV>,
+    SigCurveConfig: HashToG1Gadget<FV, CF> + Bls12SubgroupGadget<FV, CF>,
+{
+    #[tracing::instrument(skip_all)]
+    pub fn verify(
+        parameters: &ParametersVar<SigCurveConfig, FV, CF>,
+        pk: &MinSigPublicKeyVar<SigCurveConfig, FV, CF>,
+        message: &[UInt8<CF>],
+        signature: &MinSigSignatureVar<SigCurveConfig, FV, CF, H>,
+    ) -> Result<(), SynthesisError> {
+        if parameters.strict {
+            pk.pub_key.z.is_zero()?.enforce_equal(&Boolean::FALSE)?;
+            signature
+                .signature
+                .z
+                .is_zero()?
+                .enforce_equal(&Boolean::FALSE)?;
+        }
+        if parameters.subgroup_check {
+            SigCurveConfig::enforce_in_g2(&pk.pub_key)?;
+            SigCurveConfig::enforce_in_g1(&signature.signature)?;
+        }
+
+        let hash_to_curve = Self::hash_to_curve(message, &parameters.dst::<MinSig<H>>()?)?;
+
+        // check e(sig, g2) == e(H(m), pk) as e(sig, -g2) * e(H(m), pk) == 1
+        let prod = bls12::PairingVar::product_of_pairings(
+            &[
+                G1PreparedVar::<SigCurveConfig, FV, CF>::from_group_var(&signature.signature)?,
+                G1PreparedVar::<SigCurveConfig, FV, CF>::from_group_var(&hash_to_curve)?,
+            ],
+            &[
+                G2PreparedVar::<SigCurveConfig, FV, CF>::from_group_var(
+                    &parameters.g2_generator.negate()?,
+                )?,
+                G2PreparedVar::<SigCurveConfig, FV, CF>::from_group_var(&pk.pub_key)?,
+            ],
+        )?;
+
+        let cs = prod.cs();
+
+        prod.is_eq(
+            &<bls12::PairingVar<SigCurveConfig, FV, CF> as PairingVar<
+                Bls12<SigCurveConfig>,
+                CF,
+            >>::GTVar::new_constant(
+                cs.clone(),
+                <<Bls12<SigCurveConfig> as Pairing>::TargetField as Field>::ONE,
+            )?,
+        )?
+        .enforce_equal(&Boolean::TRUE)?;
+
+        tracing::info!(num_constraints = cs.num_constraints());
+
+        Ok(())
+    }
+
+    pub fn hash_to_curve(
+        msg: &[UInt8<CF>],
+        dst: &[UInt8<CF>],
+    ) -> Result<G1Var<SigCurveConfig, FV, CF>, SynthesisError> {
+        SigCurveConfig::hash_to_g1::<H>(msg, dst)
+    }
+}
+
+/// R1CS counterpart of `MinSig<H>::hash_to_curve_with_dst`.
+///
+/// This is implemented per curve rather than for any `Bls12Config` because a generic
+/// `G1<SigCurveConfig>: CofactorGadget<FV, CF>` bound hides the normalisation of
+/// `<G1<SigCurveConfig> as CurveGroup>::BaseField`, which then conflicts with the `FV` bounds.
+pub trait HashToG1Gadget<FV: FieldVar<BlsSigField<Self>, CF>, CF: PrimeField>: Bls12Config
+where
+    for<'a> &'a FV: FieldOpsBounds<'a, BlsSigField<Self>, FV>,
+{
+    fn hash_to_g1<H: FieldHasherGadget<BlsSigField<Self>, CF, FV>>(
+        msg: &[UInt8<CF>],
+        dst: &[UInt8<CF>],
+    ) -> Result<G1Var<Self, FV, CF>, SynthesisError>;
+}
+
+macro_rules! impl_hash_to_g1_gadget {
+    ($curve:ident) => {
+        impl<
+                FV: FieldVar<$curve::Fq, CF>
+                    + FromBaseFieldVarGadget<CF>
+                    + ToBaseFieldVarGadget<$curve::Fq, CF>
+                    + SqrtGadget<$curve::Fq, CF>,
+                CF: PrimeField,
+            > HashToG1Gadget<FV, CF> for $curve::Config
+        where
+            for<'a> &'a FV: FieldOpsBounds<'a, $curve::Fq, FV>,
+        {
+            #[tracing::instrument(skip_all)]
+            fn hash_to_g1<H: FieldHasherGadget<BlsSigField<Self>, CF, FV>>(
+                msg: &[UInt8<CF>],
+                dst: &[UInt8<CF>],
+            ) -> Result<G1Var<Self, FV, CF>, SynthesisError> {
+                type HasherGadget<FV, CF, H> = MapToCurveBasedHasherGadget<
+                    $curve::G1Projective,
+                    <H as FieldHasherGadget<$curve::Fq, CF, FV>>::Gadget,
+                    WBMapGadget<$curve::g1::Config>,
+                    CF,
+                    FV,
+                >;
+
+                let cs = msg.cs();
+                tracing::info!(num_constraints = cs.num_constraints());
+
+                let hasher_gadget = HasherGadget::<FV, CF, H>::new(dst);
+                let hash = hasher_gadget.hash(msg);
+
+                tracing::info!(num_constraints = cs.num_constraints());
+
+                hash.map(|h| G1Var::<Self, FV, CF>::new(h.x, h.y, h.z))
+            }
+        }
+    };
+}
+
+impl_hash_to_g1_gadget!(ark_bls12_381);
+impl_hash_to_g1_gadget!(ark_bls12_377);
+
 impl<
         SigCurveConfig: Bls12Config,
         FV: FieldVar<BlsSigField<SigCurveConfig>, SNARKField>,
         SNARKField: PrimeField,
-    > AllocVar<Signature<SigCurveConfig>, SNARKField>
-    for SignatureVar<SigCurveConfig, FV, SNARKField>
+        H: FieldHasher,
+    > AllocVar<Signature<SigCurveConfig, MinPk<H>>, SNARKField>
+    for SignatureVar<SigCurveConfig, FV, SNARKField, H>
 where
     for<'a> &'a FV: FieldOpsBounds<'a, BlsSigField<SigCurveConfig>, FV>,
 {
-    fn new_variable<T: Borrow<Signature<SigCurveConfig>>>(
+    fn new_variable<T: Borrow<Signature<SigCurveConfig, MinPk<H>>>>(
         cs: impl Into<Namespace<SNARKField>>,
         f: impl FnOnce() -> Result<T, SynthesisError>,
         mode: AllocationMode,
@@ -247,6 +664,7 @@ where
                 || f().map(|value| value.borrow().signature),
                 mode,
             )?,
+            _hasher: PhantomData,
         })
     }
 }
@@ -298,6 +716,56 @@ where
     }
 }
 
+impl<
+        SigCurveConfig: Bls12Config,
+        FV: FieldVar<BlsSigField<SigCurveConfig>, SNARKField>,
+        SNARKField: PrimeField,
+        H: FieldHasher,
+    > AllocVar<Signature<SigCurveConfig, MinSig<H>>, SNARKField>
+    for MinSigSignatureVar<SigCurveConfig, FV, SNARKField, H>
+where
+    for<'a> &'a FV: FieldOpsBounds<'a, BlsSigField<SigCurveConfig>, FV>,
+{
+    fn new_variable<T: Borrow<Signature<SigCurveConfig, MinSig<H>>>>(
+        cs: impl Into<Namespace<SNARKField>>,
+        f: impl FnOnce() -> Result<T, SynthesisError>,
+        mode: AllocationMode,
+    ) -> Result<Self, SynthesisError> {
+        Ok(Self {
+            signature: G1Var::<SigCurveConfig, _, _>::new_variable(
+                cs,
+                || f().map(|value| value.borrow().signature),
+                mode,
+            )?,
+            _hasher: PhantomData,
+        })
+    }
+}
+
+impl<
+        SigCurveConfig: Bls12Config,
+        FV: FieldVar<BlsSigField<SigCurveConfig>, SNARKField>,
+        SNARKField: PrimeField,
+    > AllocVar<PublicKey<SigCurveConfig, MinSig>, SNARKField>
+    for MinSigPublicKeyVar<SigCurveConfig, FV, SNARKField>
+where
+    for<'a> &'a FV: FieldOpsBounds<'a, BlsSigField<SigCurveConfig>, FV>,
+{
+    fn new_variable<T: Borrow<PublicKey<SigCurveConfig, MinSig>>>(
+        cs: impl Into<Namespace<SNARKField>>,
+        f: impl FnOnce() -> Result<T, SynthesisError>,
+        mode: AllocationMode,
+    ) -> Result<Self, SynthesisError> {
+        Ok(Self {
+            pub_key: G2Var::<SigCurveConfig, _, _>::new_variable(
+                cs,
+                || f().map(|value| value.borrow().pub_key),
+                mode,
+            )?,
+        })
+    }
+}
+
 impl<
         SigCurveConfig: Bls12Config,
         FV: FieldVar<BlsSigField<SigCurveConfig>, SNARKField>,
@@ -314,6 +782,13 @@ where
     ) -> Result<Self, SynthesisError> {
         let cs = cs.into();
         let value = f();
+        // `strict` and `scheme` decide which constraints are generated, so they are part of
+        // the circuit: they are only taken from constants, and otherwise set by the caller,
+        // never by an assignment
+        let (strict, scheme) = match (&value, mode) {
+            (Ok(value), AllocationMode::Constant) => (value.borrow().strict, value.borrow().scheme),
+            _ => (true, SchemeVariant::default()),
+        };
 
         Ok(Self {
             g1_generator: G1Var::<SigCurveConfig, _, _>::new_variable(
@@ -336,6 +811,9 @@ where
                 },
                 mode,
             )?,
+            strict,
+            scheme,
+            subgroup_check: false,
         })
     }
 }
@@ -344,18 +822,24 @@ where
 mod test {
     use crate::{
         bls::{
-            get_bls_instance, BLSAggregateSignatureVerifyGadget, ParametersVar, PublicKeyVar,
-            SignatureVar,
+            get_bls_instance, BLSAggregateSignatureVerifyGadget, BLSBatchVerifyGadget,
+            BLSMinSigVerifyGadget, MinPk, MinSig, MinSigPublicKeyVar, MinSigSignatureVar,
+            Parameters, ParametersVar, PublicKey, PublicKeyVar, SchemeVariant, SecretKey,
+            Signature, SignatureVar,
         },
+        hash::hash_to_field::poseidon::PoseidonHasher,
         params::BlsSigField,
     };
 
+    use ark_ff::PrimeField;
     use ark_r1cs_std::{
         alloc::AllocVar,
         fields::{emulated_fp::EmulatedFpVar, fp::FpVar},
+        prelude::Boolean,
         uint8::UInt8,
     };
     use ark_relations::r1cs::ConstraintSystem;
+    use rand::Rng;
 
     #[test]
     fn check_r1cs_native() {
@@ -385,6 +869,290 @@ mod test {
         println!("RC1S is satisfied!");
     }
 
+    #[test]
+    fn check_r1cs_poseidon_native() {
+        type BlsSigConfig = ark_bls12_377::Config;
+        type BaseSigCurveField = BlsSigField<BlsSigConfig>;
+        type BaseSNARKField = BaseSigCurveField;
+        type V = MinPk<PoseidonHasher<BaseSNARKField>>;
+
+        let (msg, params, sk, pk, blake2s_sig) = get_bls_instance::<BlsSigConfig>();
+        let sig = Signature::<BlsSigConfig, V>::sign(msg.as_bytes(), &sk, &params);
+        let poseidon_pk = PublicKey::<BlsSigConfig, V>::new(&sk, &params);
+        assert!(Signature::verify(
+            msg.as_bytes(),
+            &sig,
+            &poseidon_pk,
+            &params
+        ));
+        // keys do not depend on the hasher, but signatures do
+        assert_eq!(poseidon_pk.pub_key, pk.pub_key);
+        assert_ne!(sig.signature, blake2s_sig.signature);
+
+        for (msg, valid) in [(msg, true), ("Goodbye World", false)] {
+            let cs = ConstraintSystem::new_ref();
+            let msg_var: Vec<UInt8<BaseSNARKField>> =
+                UInt8::new_input_vec(cs.clone(), msg.as_bytes()).unwrap();
+            let params_var: ParametersVar<BlsSigConfig, FpVar<BaseSigCurveField>, BaseSNARKField> =
+                ParametersVar::new_input(cs.clone(), || Ok(params)).unwrap();
+            let pk_var = PublicKeyVar::new_input(cs.clone(), || Ok(pk)).unwrap();
+            let sig_var = SignatureVar::new_input(cs.clone(), || Ok(sig)).unwrap();
+
+            BLSAggregateSignatureVerifyGadget::verify(&params_var, &pk_var, &msg_var, &sig_var)
+                .unwrap();
+            assert_eq!(cs.is_satisfied().unwrap(), valid);
+        }
+    }
+
+    #[test]
+    fn check_r1cs_scheme_variants() {
+        type BlsSigConfig = ark_bls12_377::Config;
+        type BaseSigCurveField = BlsSigField<BlsSigConfig>;
+        type BaseSNARKField = BaseSigCurveField;
+
+        let (msg, params, sk, _, _) = get_bls_instance::<BlsSigConfig>();
+
+        for scheme in [SchemeVariant::Basic, SchemeVariant::MessageAugmentation] {
+            let cs = ConstraintSystem::new_ref();
+            let params = Parameters { scheme, ..params };
+            let pk = PublicKey::new(&sk, &params);
+            let sig = Signature::<BlsSigConfig>::sign(msg.as_bytes(), &sk, &params);
+
+            let msg_var: Vec<UInt8<BaseSNARKField>> =
+                UInt8::new_input_vec(cs.clone(), msg.as_bytes()).unwrap();
+            let params_var: ParametersVar<BlsSigConfig, FpVar<BaseSigCurveField>, BaseSNARKField> =
+                ParametersVar::new_constant(cs.clone(), params).unwrap();
+            assert_eq!(params_var.scheme, scheme);
+            let pk_var = PublicKeyVar::new_input(cs.clone(), || Ok(pk)).unwrap();
+            let sig_var = SignatureVar::new_input(cs.clone(), || Ok(sig)).unwrap();
+
+            let result =
+                BLSAggregateSignatureVerifyGadget::verify(&params_var, &pk_var, &msg_var, &sig_var);
+            if scheme == SchemeVariant::MessageAugmentation {
+                assert!(result.is_err());
+            } else {
+                result.unwrap();
+                assert!(cs.is_satisfied().unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn check_r1cs_parameters_not_from_assignment() {
+        type BlsSigConfig = ark_bls12_377::Config;
+        type BaseSigCurveField = BlsSigField<BlsSigConfig>;
+        type BaseSNARKField = BaseSigCurveField;
+
+        let params = Parameters::<BlsSigConfig> {
+            strict: false,
+            scheme: SchemeVariant::Basic,
+            ..Parameters::setup()
+        };
+
+        // the prover cannot turn off the checks of the circuit with its assignment
+        let cs = ConstraintSystem::new_ref();
+        let params_var: ParametersVar<BlsSigConfig, FpVar<BaseSigCurveField>, BaseSNARKField> =
+            ParametersVar::new_witness(cs.clone(), || Ok(params)).unwrap();
+        assert!(params_var.strict);
+        assert_eq!(params_var.scheme, SchemeVariant::default());
+
+        let params_var: ParametersVar<BlsSigConfig, FpVar<BaseSigCurveField>, BaseSNARKField> =
+            ParametersVar::new_constant(cs, params).unwrap();
+        assert!(!params_var.strict);
+        assert_eq!(params_var.scheme, SchemeVariant::Basic);
+    }
+
+    #[test]
+    fn check_r1cs_identity_rejected() {
+        type BlsSigConfig = ark_bls12_377::Config;
+        type BaseSigCurveField = BlsSigField<BlsSigConfig>;
+        type BaseSNARKField = BaseSigCurveField;
+
+        let cs = ConstraintSystem::new_ref();
+        let (msg, params, _, _, _) = get_bls_instance::<BlsSigConfig>();
+        let pk = PublicKey::<BlsSigConfig>::default();
+        let sig = Signature::<BlsSigConfig>::default();
+
+        let msg_var: Vec<UInt8<BaseSNARKField>> = msg
+            .as_bytes()
+            .iter()
+            .map(|b| UInt8::new_input(cs.clone(), || Ok(b)).unwrap())
+            .collect();
+        let params_var: ParametersVar<BlsSigConfig, FpVar<BaseSigCurveField>, BaseSNARKField> =
+            ParametersVar::new_input(cs.clone(), || Ok(params)).unwrap();
+        assert!(params_var.strict);
+        let pk_var = PublicKeyVar::new_input(cs.clone(), || Ok(pk)).unwrap();
+        let sig_var = SignatureVar::new_input(cs.clone(), || Ok(sig)).unwrap();
+
+        BLSAggregateSignatureVerifyGadget::verify(&params_var, &pk_var, &msg_var, &sig_var)
+            .unwrap();
+
+        assert!(!cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn check_r1cs_subgroup_check() {
+        type BlsSigConfig = ark_bls12_381::Config;
+        type BaseSigCurveField = BlsSigField<BlsSigConfig>;
+        type BaseSNARKField = BaseSigCurveField;
+
+        let (msg, params, _, pk, sig) = get_bls_instance::<BlsSigConfig>();
+
+        let mut num_constraints = vec![];
+        for subgroup_check in [false, true] {
+            let cs = ConstraintSystem::new_ref();
+            let msg_var: Vec<UInt8<BaseSNARKField>> =
+                UInt8::new_input_vec(cs.clone(), msg.as_bytes()).unwrap();
+            let mut params_var: ParametersVar<
+                BlsSigConfig,
+                FpVar<BaseSigCurveField>,
+                BaseSNARKField,
+            > = ParametersVar::new_input(cs.clone(), || Ok(params)).unwrap();
+            assert!(!params_var.subgroup_check);
+            params_var.subgroup_check = subgroup_check;
+            let pk_var = PublicKeyVar::new_input(cs.clone(), || Ok(pk)).unwrap();
+            let sig_var = SignatureVar::new_input(cs.clone(), || Ok(sig)).unwrap();
+
+            BLSAggregateSignatureVerifyGadget::verify(&params_var, &pk_var, &msg_var, &sig_var)
+                .unwrap();
+            assert!(cs.is_satisfied().unwrap());
+            num_constraints.push(cs.num_constraints());
+        }
+
+        assert!(num_constraints[0] < num_constraints[1]);
+    }
+
+    #[test]
+    fn check_r1cs_constant_parameters_native() {
+        type BlsSigConfig = ark_bls12_381::Config;
+        type BaseSigCurveField = BlsSigField<BlsSigConfig>;
+        type BaseSNARKField = BaseSigCurveField;
+
+        let params = Parameters::<BlsSigConfig>::setup();
+        let sk = SecretKey::new(&mut rand::thread_rng());
+        let pk = PublicKey::new(&sk, &params);
+        let sig = Signature::<BlsSigConfig>::sign(b"Hello World", &sk, &params);
+
+        // the Miller loop evaluates the lines at the constant generator with precomputed
+        // coefficients
+        for (msg, valid) in [("Hello World", true), ("Goodbye World", false)] {
+            let cs = ConstraintSystem::new_ref();
+            let msg_var: Vec<UInt8<BaseSNARKField>> =
+                UInt8::new_input_vec(cs.clone(), msg.as_bytes()).unwrap();
+            let params_var: ParametersVar<BlsSigConfig, FpVar<BaseSigCurveField>, BaseSNARKField> =
+                ParametersVar::new_constant(cs.clone(), params).unwrap();
+            let pk_var = PublicKeyVar::new_input(cs.clone(), || Ok(pk)).unwrap();
+            let sig_var = SignatureVar::new_input(cs.clone(), || Ok(sig)).unwrap();
+
+            BLSAggregateSignatureVerifyGadget::verify(&params_var, &pk_var, &msg_var, &sig_var)
+                .unwrap();
+            assert_eq!(cs.is_satisfied().unwrap(), valid);
+        }
+    }
+
+    /// Verify two signatures on different messages with `BLSBatchVerifyGadget` and
+    /// `challenge_len`-bit challenges, with the signatures swapped if `swap`, and return the
+    /// number of constraints and whether they are satisfied.
+    ///
+    /// This runs over BLS12-381, whose G2 implements `GLVConfig`.
+    fn check_r1cs_batch(swap: bool, zero_challenge: bool, challenge_len: usize) -> (usize, bool) {
+        type BlsSigConfig = ark_bls12_381::Config;
+        type BaseSigCurveField = BlsSigField<BlsSigConfig>;
+        type BaseSNARKField = BaseSigCurveField;
+
+        let mut rng = rand::thread_rng();
+        let cs = ConstraintSystem::new_ref();
+        let params = Parameters::<BlsSigConfig>::setup();
+        let msgs = ["Hello World", "Goodbye World"];
+        let sks = [SecretKey::new(&mut rng), SecretKey::new(&mut rng)];
+        let pks = sks.each_ref().map(|sk| PublicKey::new(sk, &params));
+        let mut sigs =
+            [0, 1].map(|i| Signature::<BlsSigConfig>::sign(msgs[i].as_bytes(), &sks[i], &params));
+        if swap {
+            sigs.swap(0, 1);
+        }
+
+        let params_var: ParametersVar<BlsSigConfig, FpVar<BaseSigCurveField>, BaseSNARKField> =
+            ParametersVar::new_input(cs.clone(), || Ok(params)).unwrap();
+        let pk_vars: Vec<_> = pks
+            .iter()
+            .map(|pk| PublicKeyVar::new_input(cs.clone(), || Ok(*pk)).unwrap())
+            .collect();
+        let msg_vars: Vec<_> = msgs
+            .iter()
+            .map(|msg| UInt8::new_input_vec(cs.clone(), msg.as_bytes()).unwrap())
+            .collect();
+        let sig_vars: Vec<_> = sigs
+            .iter()
+            .map(|sig| SignatureVar::new_input(cs.clone(), || Ok(*sig)).unwrap())
+            .collect();
+        let challenges: Vec<_> = (0..2)
+            .map(|i| {
+                // the lowest bit is set so that the challenge is non-zero
+                let r: Vec<bool> = if zero_challenge && i == 1 {
+                    vec![false; challenge_len]
+                } else {
+                    core::iter::once(true)
+                        .chain((1..challenge_len).map(|_| rng.gen()))
+                        .collect()
+                };
+                Vec::<Boolean<BaseSNARKField>>::new_witness(cs.clone(), || Ok(r)).unwrap()
+            })
+            .collect();
+
+        BLSBatchVerifyGadget::verify(&params_var, &pk_vars, &msg_vars, &sig_vars, &challenges)
+            .unwrap();
+
+        (cs.num_constraints(), cs.is_satisfied().unwrap())
+    }
+
+    #[test]
+    fn check_r1cs_batch_native() {
+        type BlsSigConfig = ark_bls12_381::Config;
+        type BaseSigCurveField = BlsSigField<BlsSigConfig>;
+        type BaseSNARKField = BaseSigCurveField;
+
+        let (batch, satisfied) = check_r1cs_batch(false, false, 64);
+        assert!(satisfied);
+
+        // two separate verifications of the same instances
+        let cs = ConstraintSystem::new_ref();
+        let (msg, params, _, pk, sig) = get_bls_instance::<BlsSigConfig>();
+        let params_var: ParametersVar<BlsSigConfig, FpVar<BaseSigCurveField>, BaseSNARKField> =
+            ParametersVar::new_input(cs.clone(), || Ok(params)).unwrap();
+        for _ in 0..2 {
+            let msg_var = UInt8::new_input_vec(cs.clone(), msg.as_bytes()).unwrap();
+            let pk_var = PublicKeyVar::new_input(cs.clone(), || Ok(pk)).unwrap();
+            let sig_var = SignatureVar::new_input(cs.clone(), || Ok(sig)).unwrap();
+            BLSAggregateSignatureVerifyGadget::verify(&params_var, &pk_var, &msg_var, &sig_var)
+                .unwrap();
+        }
+        let separate = cs.num_constraints();
+
+        println!("2 signatures: {batch} constraints batched, {separate} separately");
+        assert!(batch < separate);
+    }
+
+    #[test]
+    fn check_r1cs_batch_swapped_signatures() {
+        let (_, satisfied) = check_r1cs_batch(true, false, 64);
+        assert!(!satisfied);
+    }
+
+    #[test]
+    fn check_r1cs_batch_zero_challenge() {
+        let (_, satisfied) = check_r1cs_batch(false, true, 64);
+        assert!(!satisfied);
+    }
+
+    /// Full-length challenges multiply the signatures with GLV.
+    #[test]
+    fn check_r1cs_batch_full_length_challenges() {
+        let len = ark_bls12_381::Fr::MODULUS_BIT_SIZE as usize;
+        assert!(check_r1cs_batch(false, false, len).1);
+        assert!(!check_r1cs_batch(true, false, len).1);
+    }
+
     #[test]
     #[ignore = "field emulation takes a long time to finish running"]
     fn check_r1cs_emulated() {
@@ -416,4 +1184,34 @@ mod test {
 
         println!("RC1S is satisfied!");
     }
+
+    #[test]
+    fn check_r1cs_min_sig_native() {
+        type BlsSigConfig = ark_bls12_381::Config;
+        type BaseSigCurveField = BlsSigField<BlsSigConfig>;
+        type BaseSNARKField = BaseSigCurveField;
+
+        let cs = ConstraintSystem::new_ref();
+        let msg = "Hello World";
+        let params = Parameters::<BlsSigConfig>::setup();
+        let sk = SecretKey::new(&mut rand::thread_rng());
+        let pk = PublicKey::<BlsSigConfig, MinSig>::new(&sk, &params);
+        let sig = Signature::<BlsSigConfig, MinSig>::sign(msg.as_bytes(), &sk, &params);
+        assert!(Signature::verify(msg.as_bytes(), &sig, &pk, &params));
+
+        let msg_var: Vec<UInt8<BaseSNARKField>> = msg
+            .as_bytes()
+            .iter()
+            .map(|b| UInt8::new_input(cs.clone(), || Ok(b)).unwrap())
+            .collect();
+        let params_var: ParametersVar<BlsSigConfig, FpVar<BaseSigCurveField>, BaseSNARKField> =
+            ParametersVar::new_input(cs.clone(), || Ok(params)).unwrap();
+        let pk_var = MinSigPublicKeyVar::new_input(cs.clone(), || Ok(pk)).unwrap();
+        let sig_var = MinSigSignatureVar::new_input(cs.clone(), || Ok(sig)).unwrap();
+
+        BLSMinSigVerifyGadget::verify(&params_var, &pk_var, &msg_var, &sig_var).unwrap();
+
+        println!("Number of constraints: {}", cs.num_constraints());
+        assert!(cs.is_satisfied().unwrap());
+    }
 }
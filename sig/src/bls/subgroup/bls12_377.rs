@@ -0,0 +1,22 @@
+use ark_bls12_377::{Fq, Fq2Config};
+use ark_ec::short_weierstrass::Projective;
+use ark_ff::{Fp2ConfigWrapper, PrimeField};
+use ark_r1cs_std::fields::{quadratic_extension::QuadExtVar, FieldOpsBounds, FieldVar};
+
+use super::SubgroupGadget;
+
+// No faster check is implemented for BLS12-377, which falls back to `[r]P = 0`.
+impl<FP: FieldVar<Fq, CF>, CF: PrimeField> SubgroupGadget<FP, CF>
+    for Projective<ark_bls12_377::g1::Config>
+where
+    for<'b> &'b FP: FieldOpsBounds<'b, Fq, FP>,
+{
+}
+
+impl<FP: FieldVar<Fq, CF>, CF: PrimeField>
+    SubgroupGadget<QuadExtVar<FP, Fp2ConfigWrapper<Fq2Config>, CF>, CF>
+    for Projective<ark_bls12_377::g2::Config>
+where
+    for<'b> &'b FP: FieldOpsBounds<'b, Fq, FP>,
+{
+}
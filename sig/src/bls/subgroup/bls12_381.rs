@@ -0,0 +1,192 @@
+use ark_bls12_381::{Fq, Fq2Config, FqConfig};
+use ark_ec::{bls12::Bls12Config, short_weierstrass::Projective};
+use ark_ff::{Fp2ConfigWrapper, MontBackend, PrimeField, QuadExtConfig};
+use ark_r1cs_std::{
+    eq::EqGadget,
+    fields::{quadratic_extension::QuadExtVar, FieldOpsBounds, FieldVar},
+    groups::{curves::short_weierstrass::ProjectiveVar, CurveVar},
+    R1CSVar,
+};
+use ark_relations::r1cs::SynthesisError;
+
+use super::{mul_by_constant_var, SubgroupGadget};
+use crate::hash::hash_to_curve::cofactor::bls12_381::p_power_endomorphism_var;
+
+impl<FP: FieldVar<Fq, CF>, CF: PrimeField> SubgroupGadget<FP, CF>
+    for Projective<ark_bls12_381::g1::Config>
+where
+    for<'b> &'b FP: FieldOpsBounds<'b, Fq, FP>,
+{
+    #[tracing::instrument(skip_all)]
+    fn enforce_in_subgroup_var(
+        p: &ProjectiveVar<Self::Config, FP, CF>,
+    ) -> Result<(), SynthesisError> {
+        let cs = p.cs();
+        tracing::info!(num_constraints = cs.num_constraints());
+
+        // Based on Section 6 of https://eprint.iacr.org/2021/1130.pdf, which is also what
+        // `ark_bls12_381::g1::Config::is_in_correct_subgroup_assuming_on_curve` does:
+        // P is in G1 iff φ(P) = [-x^2]P, where φ(x, y) = (βx, y)
+        let x = <ark_bls12_381::Config as Bls12Config>::X;
+        let x2_p = mul_by_constant_var(&mul_by_constant_var(p, x)?, x)?;
+        let phi_p = ProjectiveVar::new(&p.x * ark_bls12_381::g1::BETA, p.y.clone(), p.z.clone());
+        phi_p.enforce_equal(&x2_p.negate()?)?;
+
+        tracing::info!(num_constraints = cs.num_constraints());
+
+        Ok(())
+    }
+}
+
+impl<
+        FP: FieldVar<<Fp2ConfigWrapper<Fq2Config> as QuadExtConfig>::BaseField, CF>,
+        CF: PrimeField,
+    > SubgroupGadget<QuadExtVar<FP, Fp2ConfigWrapper<Fq2Config>, CF>, CF>
+    for Projective<ark_bls12_381::g2::Config>
+where
+    for<'b> &'b FP: FieldOpsBounds<'b, ark_ff::Fp<MontBackend<FqConfig, 6>, 6>, FP>,
+{
+    #[tracing::instrument(skip_all)]
+    fn enforce_in_subgroup_var(
+        p: &ProjectiveVar<Self::Config, QuadExtVar<FP, Fp2ConfigWrapper<Fq2Config>, CF>, CF>,
+    ) -> Result<(), SynthesisError> {
+        let cs = p.cs();
+        tracing::info!(num_constraints = cs.num_constraints());
+
+        // Based on Section 4 of https://eprint.iacr.org/2021/1130.pdf, which is also what
+        // `ark_bls12_381::g2::Config::is_in_correct_subgroup_assuming_on_curve` does:
+        // P is in G2 iff ψ(P) = [x]P
+        //
+        // x is negative, so [x]P = -[|x|]P
+        let x_p = mul_by_constant_var::<_, QuadExtVar<FP, Fp2ConfigWrapper<Fq2Config>, CF>, _>(
+            p,
+            <ark_bls12_381::Config as Bls12Config>::X,
+        )?;
+        p_power_endomorphism_var(p)?.enforce_equal(&x_p.negate()?)?;
+
+        tracing::info!(num_constraints = cs.num_constraints());
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ark_bls12_381::{g1, g2, Fq, Fq2Config};
+    use ark_ec::{
+        short_weierstrass::{Affine, Projective, SWCurveConfig},
+        AffineRepr, CurveGroup,
+    };
+    use ark_ff::{PrimeField, UniformRand};
+    use ark_r1cs_std::{
+        alloc::AllocationMode,
+        fields::{fp::FpVar, fp2::Fp2Var, FieldOpsBounds, FieldVar},
+        groups::{curves::short_weierstrass::ProjectiveVar, CurveVar},
+        R1CSVar,
+    };
+    use ark_relations::r1cs::{ConstraintSystem, SynthesisError};
+    use rand::thread_rng;
+
+    use crate::bls::subgroup::{mul_by_constant_var, SubgroupGadget};
+
+    /// A random point on the curve of `P`, which is not in the prime-order subgroup except
+    /// with negligible probability.
+    fn sample_unchecked<P: SWCurveConfig>() -> Affine<P> {
+        let mut rng = thread_rng();
+        loop {
+            let x = P::BaseField::rand(&mut rng);
+            if let Some(p) = Affine::get_point_from_x_unchecked(x, false) {
+                return p;
+            }
+        }
+    }
+
+    /// Check a point in the subgroup and a point outside of it with `enforce_in_subgroup`,
+    /// and return the constraints of the check and of the default `[r]P = 0`.
+    fn check_subgroup<P, FP>(
+        enforce_in_subgroup: impl Fn(&ProjectiveVar<P, FP, Fq>) -> Result<(), SynthesisError>,
+    ) -> (usize, usize)
+    where
+        P: SWCurveConfig,
+        FP: FieldVar<P::BaseField, Fq>,
+        for<'a> &'a FP: FieldOpsBounds<'a, P::BaseField, FP>,
+    {
+        let mut num_constraints = (0, 0);
+        for point in [
+            Projective::<P>::rand(&mut thread_rng()),
+            sample_unchecked::<P>().into(),
+        ] {
+            let cs = ConstraintSystem::<Fq>::new_ref();
+            let point_var = ProjectiveVar::<P, FP, Fq>::new_variable_omit_prime_order_check(
+                cs.clone(),
+                || Ok(point),
+                AllocationMode::Input,
+            )
+            .unwrap();
+
+            let before = cs.num_constraints();
+            enforce_in_subgroup(&point_var).unwrap();
+            num_constraints.0 = cs.num_constraints() - before;
+            assert_eq!(
+                cs.is_satisfied().unwrap(),
+                point
+                    .into_affine()
+                    .is_in_correct_subgroup_assuming_on_curve()
+            );
+
+            let cs = ConstraintSystem::<Fq>::new_ref();
+            let point_var = ProjectiveVar::<P, FP, Fq>::new_variable_omit_prime_order_check(
+                cs.clone(),
+                || Ok(point),
+                AllocationMode::Input,
+            )
+            .unwrap();
+            let before = cs.num_constraints();
+            let modulus = <P::ScalarField as PrimeField>::MODULUS;
+            let r_p = mul_by_constant_var(&point_var, modulus.as_ref()).unwrap();
+            num_constraints.1 = cs.num_constraints() - before;
+            assert_eq!(
+                r_p.is_zero().unwrap().value().unwrap(),
+                point
+                    .into_affine()
+                    .is_in_correct_subgroup_assuming_on_curve()
+            );
+        }
+
+        num_constraints
+    }
+
+    #[test]
+    fn test_subgroup_g1() {
+        let (fast, default) = check_subgroup::<g1::Config, FpVar<Fq>>(
+            Projective::<g1::Config>::enforce_in_subgroup_var,
+        );
+        println!("G1: {fast} constraints with the endomorphism, {default} with [r]P");
+        assert!(fast < default);
+    }
+
+    #[test]
+    fn test_subgroup_g2() {
+        let (fast, default) = check_subgroup::<g2::Config, Fp2Var<Fq2Config>>(
+            Projective::<g2::Config>::enforce_in_subgroup_var,
+        );
+        println!("G2: {fast} constraints with the endomorphism, {default} with [r]P");
+        assert!(fast < default);
+    }
+
+    #[test]
+    fn test_subgroup_identity() {
+        let cs = ConstraintSystem::<Fq>::new_ref();
+        let zero = Projective::<g1::Config>::default();
+        assert!(zero.into_affine().is_zero());
+        let zero_var =
+            ProjectiveVar::<g1::Config, FpVar<Fq>, Fq>::new_variable_omit_prime_order_check(
+                cs.clone(),
+                || Ok(zero),
+                AllocationMode::Input,
+            )
+            .unwrap();
+        Projective::<g1::Config>::enforce_in_subgroup_var(&zero_var).unwrap();
+        assert!(cs.is_satisfied().unwrap());
+    }
+}
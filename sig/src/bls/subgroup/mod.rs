@@ -0,0 +1,100 @@
+pub mod bls12_377;
+pub mod bls12_381;
+
+use ark_ec::{bls12::Bls12Config, short_weierstrass::SWCurveConfig, CurveConfig, CurveGroup};
+use ark_ff::{BitIteratorBE, PrimeField};
+use ark_r1cs_std::{
+    eq::EqGadget,
+    fields::{FieldOpsBounds, FieldVar},
+    groups::{
+        bls12::{G1Var, G2Var},
+        curves::short_weierstrass::ProjectiveVar,
+        CurveVar,
+    },
+};
+use ark_relations::r1cs::SynthesisError;
+
+use crate::params::BlsSigField;
+
+/// Trait for checking that a point is in the prime-order subgroup of its curve. The trait
+/// provides a default implementation that checks `[r]P = 0`, but faster methods exist for
+/// some curves, as for `CofactorGadget`.
+///
+/// Points allocated as witnesses are already checked by `AllocVar`, but points allocated as
+/// inputs (or with `new_variable_omit_prime_order_check`) are not.
+pub trait SubgroupGadget<FP: FieldVar<Self::BaseField, CF>, CF: PrimeField>: CurveGroup
+where
+    for<'a> &'a FP: FieldOpsBounds<'a, <Self as CurveGroup>::BaseField, FP>,
+    <Self as CurveGroup>::Config: SWCurveConfig,
+{
+    fn enforce_in_subgroup_var(
+        point: &ProjectiveVar<Self::Config, FP, CF>,
+    ) -> Result<(), SynthesisError> {
+        let modulus = <Self::Config as CurveConfig>::ScalarField::MODULUS;
+        mul_by_constant_var(point, modulus.as_ref())?.enforce_equal(&ProjectiveVar::zero())
+    }
+}
+
+/// Subgroup checks of the G1 and G2 points of a BLS12 curve, used by the verification
+/// gadgets when `ParametersVar::subgroup_check` is set.
+///
+/// Like `HashToG1Gadget`, this is implemented per curve rather than for any `Bls12Config`,
+/// because a generic `G1<SigCurveConfig>: SubgroupGadget<FV, CF>` bound hides the
+/// normalisation of `<G1<SigCurveConfig> as CurveGroup>::BaseField`.
+pub trait Bls12SubgroupGadget<FV: FieldVar<BlsSigField<Self>, CF>, CF: PrimeField>:
+    Bls12Config
+where
+    for<'a> &'a FV: FieldOpsBounds<'a, BlsSigField<Self>, FV>,
+{
+    fn enforce_in_g1(point: &G1Var<Self, FV, CF>) -> Result<(), SynthesisError>;
+
+    fn enforce_in_g2(point: &G2Var<Self, FV, CF>) -> Result<(), SynthesisError>;
+}
+
+macro_rules! impl_bls12_subgroup_gadget {
+    ($curve:ident) => {
+        impl<FV: FieldVar<$curve::Fq, CF>, CF: PrimeField> Bls12SubgroupGadget<FV, CF>
+            for $curve::Config
+        where
+            for<'a> &'a FV: FieldOpsBounds<'a, $curve::Fq, FV>,
+        {
+            fn enforce_in_g1(point: &G1Var<Self, FV, CF>) -> Result<(), SynthesisError> {
+                $curve::G1Projective::enforce_in_subgroup_var(point)
+            }
+
+            fn enforce_in_g2(point: &G2Var<Self, FV, CF>) -> Result<(), SynthesisError> {
+                $curve::G2Projective::enforce_in_subgroup_var(point)
+            }
+        }
+    };
+}
+
+impl_bls12_subgroup_gadget!(ark_bls12_381);
+impl_bls12_subgroup_gadget!(ark_bls12_377);
+
+/// Multiply `point` by the constant whose little-endian limbs are `scalar` with the complete
+/// projective formulas.
+///
+/// `CurveVar::scalar_mul_le` adds affine points with incomplete formulas, whose exceptional
+/// cases leave the slope unconstrained. A point outside of the subgroup can run into them, so
+/// they cannot be used to check that it is in the subgroup.
+pub(crate) fn mul_by_constant_var<
+    P: SWCurveConfig,
+    FP: FieldVar<P::BaseField, CF>,
+    CF: PrimeField,
+>(
+    point: &ProjectiveVar<P, FP, CF>,
+    scalar: &[u64],
+) -> Result<ProjectiveVar<P, FP, CF>, SynthesisError>
+where
+    for<'a> &'a FP: FieldOpsBounds<'a, P::BaseField, FP>,
+{
+    let mut res = ProjectiveVar::zero();
+    for bit in BitIteratorBE::without_leading_zeros(scalar) {
+        res.double_in_place()?;
+        if bit {
+            res += point;
+        }
+    }
+    Ok(res)
+}
@@ -0,0 +1,224 @@
+//! t-of-n threshold BLS.
+//!
+//! A dealer splits a secret key with Shamir secret sharing over the scalar field. Any `t`
+//! share holders can then produce partial signatures which are combined into an ordinary
+//! `Signature` under the original public key via Lagrange interpolation in the exponent.
+use core::ops::Mul;
+
+use ark_ec::{bls12::Bls12Config, hashing::curve_maps::wb::WBConfig};
+use ark_ff::{Field, One, UniformRand, Zero};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::{collections::BTreeSet, vec::Vec};
+use derivative::Derivative;
+use rand::Rng;
+use zeroize::Zeroize;
+
+use super::{params::SecretKeyScalarField, Parameters, PublicKey, SecretKey, Signature};
+
+/// The share of a secret key held by the participant with (1-based) `index`.
+#[derive(Derivative, CanonicalSerialize, CanonicalDeserialize)]
+#[derivative(Clone(bound = ""), Debug(bound = ""), Default(bound = ""))]
+pub struct SecretKeyShare<SigCurveConfig: Bls12Config> {
+    pub index: u64,
+    pub share: SecretKey<SigCurveConfig>,
+}
+
+/// A signature produced with a single `SecretKeyShare`.
+#[derive(Derivative, CanonicalSerialize, CanonicalDeserialize)]
+#[derivative(
+    Clone(bound = ""),
+    Copy(bound = ""),
+    Debug(bound = ""),
+    Default(bound = "")
+)]
+pub struct PartialSignature<SigCurveConfig: Bls12Config> {
+    pub index: u64,
+    pub signature: Signature<SigCurveConfig>,
+}
+
+/// Split `secret_key` into `n` shares such that any `t` of them can sign.
+///
+/// Returns `None` if `t == 0` or `t > n`.
+pub fn generate_shares<SigCurveConfig: Bls12Config, R: Rng>(
+    secret_key: &SecretKey<SigCurveConfig>,
+    t: usize,
+    n: usize,
+    rng: &mut R,
+) -> Option<Vec<SecretKeyShare<SigCurveConfig>>> {
+    if t == 0 || t > n {
+        return None;
+    }
+
+    // f(x) = sk + a_1 x + ... + a_{t-1} x^{t-1}
+    let mut coefficients: Vec<_> = core::iter::once(secret_key.secret_key)
+        .chain((1..t).map(|_| SecretKeyScalarField::<SigCurveConfig>::rand(rng)))
+        .collect();
+
+    let shares = (1..=n as u64)
+        .map(|index| {
+            let x = SecretKeyScalarField::<SigCurveConfig>::from(index);
+            // Horner's rule
+            let share = coefficients
+                .iter()
+                .rev()
+                .fold(SecretKeyScalarField::<SigCurveConfig>::zero(), |acc, c| {
+                    acc * x + c
+                });
+            SecretKeyShare {
+                index,
+                share: SecretKey { secret_key: share },
+            }
+        })
+        .collect();
+
+    coefficients.zeroize();
+
+    Some(shares)
+}
+
+impl<SigCurveConfig: Bls12Config> SecretKeyShare<SigCurveConfig> {
+    /// The public key `g1^{f(index)}` used to check partial signatures from this share.
+    #[must_use]
+    pub fn public_key(&self, params: &Parameters<SigCurveConfig>) -> PublicKey<SigCurveConfig> {
+        PublicKey::new(&self.share, params)
+    }
+}
+
+impl<SigCurveConfig: Bls12Config> PartialSignature<SigCurveConfig>
+where
+    <SigCurveConfig as Bls12Config>::G2Config: WBConfig,
+{
+    #[must_use]
+    pub fn sign(
+        message: &[u8],
+        share: &SecretKeyShare<SigCurveConfig>,
+        params: &Parameters<SigCurveConfig>,
+    ) -> Self {
+        Self {
+            index: share.index,
+            signature: Signature::sign(message, &share.share, params),
+        }
+    }
+
+    #[must_use]
+    pub fn verify(
+        message: &[u8],
+        partial_signature: &Self,
+        public_key_share: &PublicKey<SigCurveConfig>,
+        params: &Parameters<SigCurveConfig>,
+    ) -> bool {
+        Signature::verify(
+            message,
+            &partial_signature.signature,
+            public_key_share,
+            params,
+        )
+    }
+}
+
+impl<SigCurveConfig: Bls12Config> Signature<SigCurveConfig> {
+    /// Combine partial signatures with Lagrange interpolation at 0:
+    /// sig = sum_i lambda_i * sig_i, where lambda_i = prod_{j != i} x_j / (x_j - x_i).
+    ///
+    /// The result is only valid if at least `t` correct partial signatures are provided.
+    /// Returns `None` if `partial_signatures` is empty or contains a zero or duplicated index.
+    #[must_use]
+    pub fn combine(partial_signatures: &[PartialSignature<SigCurveConfig>]) -> Option<Self> {
+        let mut seen = BTreeSet::new();
+        if partial_signatures
+            .iter()
+            .any(|partial| partial.index == 0 || !seen.insert(partial.index))
+        {
+            return None;
+        }
+
+        let xs: Vec<_> = partial_signatures
+            .iter()
+            .map(|partial| SecretKeyScalarField::<SigCurveConfig>::from(partial.index))
+            .collect();
+
+        partial_signatures
+            .iter()
+            .zip(&xs)
+            .map(|(partial, x_i)| {
+                let (num, den) = xs.iter().filter(|x_j| *x_j != x_i).fold(
+                    (
+                        SecretKeyScalarField::<SigCurveConfig>::one(),
+                        SecretKeyScalarField::<SigCurveConfig>::one(),
+                    ),
+                    |(num, den), x_j| (num * x_j, den * (*x_j - x_i)),
+                );
+                // safety: indices are distinct, so `den` is non-zero
+                let lambda = num * den.inverse().expect("indices are distinct");
+                partial.signature.signature.mul(lambda)
+            })
+            .reduce(|acc, sig| acc + sig)
+            .map(|signature| Self { signature })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::thread_rng;
+
+    use crate::bls::{Parameters, PublicKey, SecretKey, Signature};
+
+    use super::{generate_shares, PartialSignature};
+
+    type SigCurveConfig = ark_bls12_381::Config;
+
+    #[test]
+    fn check_threshold_signature() {
+        let mut rng = thread_rng();
+        let msg = b"Hello World";
+        let params = Parameters::<SigCurveConfig>::setup();
+        let sk = SecretKey::new(&mut rng);
+        let pk = PublicKey::new(&sk, &params);
+
+        let shares = generate_shares(&sk, 3, 5, &mut rng).unwrap();
+        let partials: Vec<_> = shares
+            .iter()
+            .map(|share| PartialSignature::sign(msg, share, &params))
+            .collect();
+
+        for (partial, share) in partials.iter().zip(&shares) {
+            assert!(PartialSignature::verify(
+                msg,
+                partial,
+                &share.public_key(&params),
+                &params
+            ));
+        }
+
+        // any 3 out of 5 shares reconstruct a valid signature
+        for subset in [[0, 1, 2], [0, 2, 4], [4, 3, 1]] {
+            let subset: Vec<_> = subset.iter().map(|i| partials[*i]).collect();
+            let sig = Signature::combine(&subset).unwrap();
+            assert!(Signature::verify(msg, &sig, &pk, &params));
+        }
+
+        // using more shares than the threshold still works
+        let sig = Signature::combine(&partials).unwrap();
+        assert!(Signature::verify(msg, &sig, &pk, &params));
+
+        // 2 shares are not enough
+        let sig = Signature::combine(&partials[..2]).unwrap();
+        assert!(!Signature::verify(msg, &sig, &pk, &params));
+    }
+
+    #[test]
+    fn check_invalid_inputs() {
+        let mut rng = thread_rng();
+        let msg = b"Hello World";
+        let params = Parameters::<SigCurveConfig>::setup();
+        let sk = SecretKey::new(&mut rng);
+
+        assert!(generate_shares(&sk, 0, 5, &mut rng).is_none());
+        assert!(generate_shares(&sk, 6, 5, &mut rng).is_none());
+
+        let shares = generate_shares(&sk, 2, 3, &mut rng).unwrap();
+        let partial = PartialSignature::sign(msg, &shares[0], &params);
+        assert!(Signature::<SigCurveConfig>::combine(&[]).is_none());
+        assert!(Signature::combine(&[partial, partial]).is_none());
+    }
+}
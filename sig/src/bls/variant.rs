@@ -0,0 +1,283 @@
+use ark_ec::{
+    bls12::{Bls12, Bls12Config, G1Prepared, G2Prepared},
+    hashing::{
+        curve_maps::wb::{WBConfig, WBMap},
+        map_to_curve_hasher::MapToCurveBasedHasher,
+        HashToCurve,
+    },
+    pairing::{Pairing, PairingOutput},
+    short_weierstrass::{Projective, SWCurveConfig},
+};
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalSerialize, Compress, Read, SerializationError, Valid, Validate,
+    Write,
+};
+use ark_std::{marker::PhantomData, vec::Vec};
+use derivative::Derivative;
+
+use crate::hash::hash_to_field::{default_hasher::Blake2sHasher, FieldHasher};
+
+use super::{params::SecretKeyScalarField, Parameters, PreparedParameters};
+
+pub type PublicKeyGroup<SigCurveConfig, V> =
+    Projective<<V as SignatureVariant<SigCurveConfig>>::PublicKeyConfig>;
+pub type SignatureGroup<SigCurveConfig, V> =
+    Projective<<V as SignatureVariant<SigCurveConfig>>::SignatureConfig>;
+
+/// Selects which group of the pairing holds public keys and which holds signatures.
+///
+/// - `MinPk`: public keys on G1, signatures on G2 (the default used everywhere in the crate)
+/// - `MinSig`: public keys on G2, signatures on G1, which halves the signature size
+///   and makes signing cheaper at the cost of larger public keys
+///
+/// Both hash messages to the curve with the `FieldHasher` `H`, Blake2s by default. A
+/// signature only verifies with the hasher that produced it.
+pub trait SignatureVariant<SigCurveConfig: Bls12Config>: 'static + Sized {
+    /// Hash-to-curve suite ID of the signature group, used to build the DST of each scheme.
+    const HASH_SUITE: &'static [u8];
+
+    type PublicKeyConfig: SWCurveConfig<ScalarField = SecretKeyScalarField<SigCurveConfig>>;
+    type SignatureConfig: SWCurveConfig<ScalarField = SecretKeyScalarField<SigCurveConfig>>;
+
+    fn public_key_generator(
+        params: &Parameters<SigCurveConfig>,
+    ) -> PublicKeyGroup<SigCurveConfig, Self>;
+
+    /// Compute e(pk, sig) with the arguments placed in the right groups.
+    fn pairing(
+        public_key_side: PublicKeyGroup<SigCurveConfig, Self>,
+        signature_side: SignatureGroup<SigCurveConfig, Self>,
+    ) -> PairingOutput<Bls12<SigCurveConfig>>;
+
+    /// Compute prod_i e(pk_i, sig_i) with the arguments placed in the right groups.
+    fn multi_pairing(
+        public_key_side: impl IntoIterator<Item = PublicKeyGroup<SigCurveConfig, Self>>,
+        signature_side: impl IntoIterator<Item = SignatureGroup<SigCurveConfig, Self>>,
+    ) -> PairingOutput<Bls12<SigCurveConfig>>;
+
+    /// Compute e(-g, sig) * e(pk, H(m)), taking the prepared generator from `prepared`.
+    fn verification_pairing(
+        prepared: &PreparedParameters<SigCurveConfig>,
+        public_key: PublicKeyGroup<SigCurveConfig, Self>,
+        signature: SignatureGroup<SigCurveConfig, Self>,
+        hashed_message: SignatureGroup<SigCurveConfig, Self>,
+    ) -> PairingOutput<Bls12<SigCurveConfig>>;
+}
+
+/// Hash-to-curve into the signature group of a `SignatureVariant`.
+///
+/// This is split from `SignatureVariant` because it requires the signature group to
+/// implement `WBConfig`, while the key and signature types should exist for any BLS12 curve.
+pub trait SignatureVariantHasher<SigCurveConfig: Bls12Config>:
+    SignatureVariant<SigCurveConfig>
+{
+    fn hash_to_curve_with_dst(message: &[u8], dst: &[u8]) -> SignatureGroup<SigCurveConfig, Self>;
+}
+
+/// The three schemes of the IETF BLS signature draft, which differ in how aggregation
+/// is protected against rogue-key attacks.
+///
+/// - `Basic`: aggregate signatures are only accepted over distinct messages
+/// - `MessageAugmentation`: every message is prefixed with the signer's public key,
+///   so messages signed by different keys are always distinct
+/// - `ProofOfPossession`: every key must come with a verified `ProofOfPossession`,
+///   after which signatures on the same message can be aggregated
+///
+/// Each scheme hashes messages with its own DST, so a signature only verifies under the
+/// scheme that produced it. Threshold and multi-signatures combine signatures on the same
+/// message from different keys, so they do not work with `MessageAugmentation`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SchemeVariant {
+    Basic,
+    MessageAugmentation,
+    #[default]
+    ProofOfPossession,
+}
+
+impl SchemeVariant {
+    /// `BLS_SIG_` + `hash_suite` + the scheme tag, following the naming of the IETF draft.
+    #[must_use]
+    pub fn dst(self, hash_suite: &[u8]) -> Vec<u8> {
+        let tag: &[u8] = match self {
+            Self::Basic => b"NUL_",
+            Self::MessageAugmentation => b"AUG_",
+            Self::ProofOfPossession => b"POP_",
+        };
+        [b"BLS_SIG_".as_slice(), hash_suite, tag].concat()
+    }
+}
+
+impl CanonicalSerialize for SchemeVariant {
+    fn serialize_with_mode<W: Write>(
+        &self,
+        writer: W,
+        compress: Compress,
+    ) -> Result<(), SerializationError> {
+        (*self as u8).serialize_with_mode(writer, compress)
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        (*self as u8).serialized_size(compress)
+    }
+}
+
+impl Valid for SchemeVariant {
+    fn check(&self) -> Result<(), SerializationError> {
+        Ok(())
+    }
+}
+
+impl CanonicalDeserialize for SchemeVariant {
+    fn deserialize_with_mode<R: Read>(
+        reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        match u8::deserialize_with_mode(reader, compress, validate)? {
+            0 => Ok(Self::Basic),
+            1 => Ok(Self::MessageAugmentation),
+            2 => Ok(Self::ProofOfPossession),
+            _ => Err(SerializationError::InvalidData),
+        }
+    }
+}
+
+#[derive(Derivative)]
+#[derivative(
+    Clone(bound = ""),
+    Copy(bound = ""),
+    Debug(bound = ""),
+    Default(bound = "")
+)]
+pub struct MinPk<H = Blake2sHasher>(PhantomData<H>);
+
+#[derive(Derivative)]
+#[derivative(
+    Clone(bound = ""),
+    Copy(bound = ""),
+    Debug(bound = ""),
+    Default(bound = "")
+)]
+pub struct MinSig<H = Blake2sHasher>(PhantomData<H>);
+
+impl<SigCurveConfig: Bls12Config, H: FieldHasher> SignatureVariant<SigCurveConfig> for MinPk<H> {
+    const HASH_SUITE: &'static [u8] = H::G2_SUITE;
+
+    type PublicKeyConfig = <SigCurveConfig as Bls12Config>::G1Config;
+    type SignatureConfig = <SigCurveConfig as Bls12Config>::G2Config;
+
+    fn public_key_generator(
+        params: &Parameters<SigCurveConfig>,
+    ) -> PublicKeyGroup<SigCurveConfig, Self> {
+        params.g1_generator
+    }
+
+    fn pairing(
+        public_key_side: PublicKeyGroup<SigCurveConfig, Self>,
+        signature_side: SignatureGroup<SigCurveConfig, Self>,
+    ) -> PairingOutput<Bls12<SigCurveConfig>> {
+        Bls12::<SigCurveConfig>::pairing(public_key_side, signature_side)
+    }
+
+    fn multi_pairing(
+        public_key_side: impl IntoIterator<Item = PublicKeyGroup<SigCurveConfig, Self>>,
+        signature_side: impl IntoIterator<Item = SignatureGroup<SigCurveConfig, Self>>,
+    ) -> PairingOutput<Bls12<SigCurveConfig>> {
+        Bls12::<SigCurveConfig>::multi_pairing(public_key_side, signature_side)
+    }
+
+    fn verification_pairing(
+        prepared: &PreparedParameters<SigCurveConfig>,
+        public_key: PublicKeyGroup<SigCurveConfig, Self>,
+        signature: SignatureGroup<SigCurveConfig, Self>,
+        hashed_message: SignatureGroup<SigCurveConfig, Self>,
+    ) -> PairingOutput<Bls12<SigCurveConfig>> {
+        let miller_loop = Bls12::<SigCurveConfig>::multi_miller_loop(
+            [prepared.neg_g1_generator.clone(), public_key.into()],
+            [
+                G2Prepared::<SigCurveConfig>::from(signature),
+                hashed_message.into(),
+            ],
+        );
+        Bls12::<SigCurveConfig>::final_exponentiation(miller_loop)
+            .expect("final exponentiation of a miller loop output should succeed")
+    }
+}
+
+impl<SigCurveConfig: Bls12Config, H: FieldHasher> SignatureVariant<SigCurveConfig> for MinSig<H> {
+    const HASH_SUITE: &'static [u8] = H::G1_SUITE;
+
+    type PublicKeyConfig = <SigCurveConfig as Bls12Config>::G2Config;
+    type SignatureConfig = <SigCurveConfig as Bls12Config>::G1Config;
+
+    fn public_key_generator(
+        params: &Parameters<SigCurveConfig>,
+    ) -> PublicKeyGroup<SigCurveConfig, Self> {
+        params.g2_generator
+    }
+
+    fn pairing(
+        public_key_side: PublicKeyGroup<SigCurveConfig, Self>,
+        signature_side: SignatureGroup<SigCurveConfig, Self>,
+    ) -> PairingOutput<Bls12<SigCurveConfig>> {
+        Bls12::<SigCurveConfig>::pairing(signature_side, public_key_side)
+    }
+
+    fn multi_pairing(
+        public_key_side: impl IntoIterator<Item = PublicKeyGroup<SigCurveConfig, Self>>,
+        signature_side: impl IntoIterator<Item = SignatureGroup<SigCurveConfig, Self>>,
+    ) -> PairingOutput<Bls12<SigCurveConfig>> {
+        Bls12::<SigCurveConfig>::multi_pairing(signature_side, public_key_side)
+    }
+
+    fn verification_pairing(
+        prepared: &PreparedParameters<SigCurveConfig>,
+        public_key: PublicKeyGroup<SigCurveConfig, Self>,
+        signature: SignatureGroup<SigCurveConfig, Self>,
+        hashed_message: SignatureGroup<SigCurveConfig, Self>,
+    ) -> PairingOutput<Bls12<SigCurveConfig>> {
+        let miller_loop = Bls12::<SigCurveConfig>::multi_miller_loop(
+            [
+                G1Prepared::<SigCurveConfig>::from(signature),
+                hashed_message.into(),
+            ],
+            [prepared.neg_g2_generator.clone(), public_key.into()],
+        );
+        Bls12::<SigCurveConfig>::final_exponentiation(miller_loop)
+            .expect("final exponentiation of a miller loop output should succeed")
+    }
+}
+
+/// Hash `message` to the curve of `P` with the Wahby-Boneh map, after hashing it to the base
+/// field with `H`.
+fn wb_hash_to_curve<P: WBConfig, H: FieldHasher>(message: &[u8], dst: &[u8]) -> Projective<P> {
+    let hasher =
+        MapToCurveBasedHasher::<Projective<P>, H::Native<P::BaseField>, WBMap<P>>::new(dst);
+
+    let hashed_message = hasher
+        .expect("BLS12 curve supports hash to curve")
+        .hash(message)
+        .unwrap();
+
+    hashed_message.into()
+}
+
+impl<SigCurveConfig: Bls12Config, H: FieldHasher> SignatureVariantHasher<SigCurveConfig>
+    for MinPk<H>
+where
+    <SigCurveConfig as Bls12Config>::G2Config: WBConfig,
+{
+    fn hash_to_curve_with_dst(message: &[u8], dst: &[u8]) -> SignatureGroup<SigCurveConfig, Self> {
+        wb_hash_to_curve::<_, H>(message, dst)
+    }
+}
+
+impl<SigCurveConfig: Bls12Config, H: FieldHasher> SignatureVariantHasher<SigCurveConfig>
+    for MinSig<H>
+where
+    <SigCurveConfig as Bls12Config>::G1Config: WBConfig,
+{
+    fn hash_to_curve_with_dst(message: &[u8], dst: &[u8]) -> SignatureGroup<SigCurveConfig, Self> {
+        wb_hash_to_curve::<_, H>(message, dst)
+    }
+}
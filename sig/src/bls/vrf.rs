@@ -0,0 +1,230 @@
+//! A verifiable random function built on BLS signatures.
+//!
+//! BLS signatures are unique: for a fixed key and message there is exactly one valid
+//! signature. The proof of a VRF evaluation is therefore simply a signature on the
+//! (domain-separated) input, and the output is a hash of that signature. Anyone holding the
+//! public key can check the proof, and the signer cannot bias the output.
+use core::marker::PhantomData;
+
+use ark_crypto_primitives::prf::{blake2s::constraints::Blake2sGadget, PRFGadget};
+use ark_ec::{
+    bls12::Bls12Config, hashing::curve_maps::wb::WBConfig, short_weierstrass::SWCurveConfig,
+    CurveGroup,
+};
+use ark_ff::{BigInteger, Field, PrimeField};
+use ark_r1cs_std::{
+    fields::{FieldOpsBounds, FieldVar},
+    prelude::ToBytesGadget,
+    uint8::UInt8,
+};
+use ark_relations::r1cs::SynthesisError;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::vec::Vec;
+use blake2::{Blake2s256, Digest};
+use derivative::Derivative;
+
+use crate::{
+    hash::{
+        hash_to_curve::cofactor::CofactorGadget,
+        hash_to_field::from_base_field::FromBaseFieldVarGadget,
+        map_to_curve::{sqrt::SqrtGadget, to_base_field::ToBaseFieldVarGadget},
+    },
+    params::BlsSigField,
+};
+
+use super::{
+    params::{HashCurveConfig, HashCurveGroup, HashCurveVar},
+    BLSAggregateSignatureVerifyGadget, Bls12SubgroupGadget, Parameters, ParametersVar, PublicKey,
+    PublicKeyVar, SecretKey, Signature, SignatureVar,
+};
+
+/// Prefixed to every VRF input before signing, so that a VRF proof is never a valid
+/// signature on the raw input (and vice versa).
+pub const VRF_INPUT_DOMAIN: &[u8] = b"BLS_VRF_INPUT_";
+/// Prefixed to the encoded proof when hashing it into the VRF output.
+pub const VRF_OUTPUT_DOMAIN: &[u8] = b"BLS_VRF_OUTPUT_";
+
+pub type VrfOutput = [u8; 32];
+
+#[derive(Derivative, CanonicalSerialize, CanonicalDeserialize)]
+#[derivative(
+    Clone(bound = ""),
+    Copy(bound = ""),
+    Debug(bound = ""),
+    Default(bound = "")
+)]
+pub struct VrfProof<SigCurveConfig: Bls12Config> {
+    pub proof: Signature<SigCurveConfig>,
+}
+
+pub struct Vrf<SigCurveConfig: Bls12Config>(PhantomData<SigCurveConfig>);
+
+impl<SigCurveConfig: Bls12Config> Vrf<SigCurveConfig>
+where
+    <SigCurveConfig as Bls12Config>::G2Config: WBConfig,
+{
+    fn message(input: &[u8]) -> Vec<u8> {
+        [VRF_INPUT_DOMAIN, input].concat()
+    }
+
+    /// Evaluate the VRF on `input`, returning the output and a proof of its correctness.
+    #[must_use]
+    pub fn prove(
+        secret_key: &SecretKey<SigCurveConfig>,
+        input: &[u8],
+        params: &Parameters<SigCurveConfig>,
+    ) -> (VrfOutput, VrfProof<SigCurveConfig>) {
+        let proof = VrfProof {
+            proof: Signature::sign(&Self::message(input), secret_key, params),
+        };
+        (Self::proof_to_output(&proof), proof)
+    }
+
+    #[must_use]
+    pub fn verify(
+        public_key: &PublicKey<SigCurveConfig>,
+        input: &[u8],
+        output: &VrfOutput,
+        proof: &VrfProof<SigCurveConfig>,
+        params: &Parameters<SigCurveConfig>,
+    ) -> bool {
+        Self::proof_to_output(proof) == *output
+            && Signature::verify(&Self::message(input), &proof.proof, public_key, params)
+    }
+
+    /// Hash the proof into the VRF output.
+    ///
+    /// The proof is encoded in the same way as `ToBytesGadget` encodes curve points
+    /// (little-endian affine coordinates followed by the infinity flag), so that
+    /// `VrfVerifyGadget` can recompute the output inside the circuit.
+    #[must_use]
+    pub fn proof_to_output(proof: &VrfProof<SigCurveConfig>) -> VrfOutput {
+        let point = proof.proof.signature.into_affine();
+
+        let mut hasher = Blake2s256::new();
+        hasher.update(VRF_OUTPUT_DOMAIN);
+        for coordinate in [point.x, point.y] {
+            coordinate
+                .to_base_prime_field_elements()
+                .for_each(|c| hasher.update(c.into_bigint().to_bytes_le()));
+        }
+        hasher.update([u8::from(point.infinity)]);
+        hasher.finalize().into()
+    }
+}
+
+pub struct VrfVerifyGadget<
+    SigCurveConfig: Bls12Config,
+    FV: FieldVar<BlsSigField<SigCurveConfig>, CF>,
+    CF: PrimeField,
+>(PhantomData<(FV, SigCurveConfig, CF)>);
+
+impl<
+        SigCurveConfig: Bls12Config,
+        FV: FieldVar<BlsSigField<SigCurveConfig>, CF>
+            + FromBaseFieldVarGadget<CF>
+            + ToBaseFieldVarGadget<BlsSigField<SigCurveConfig>, CF>
+            + SqrtGadget<BlsSigField<SigCurveConfig>, CF>,
+        CF: PrimeField,
+    > VrfVerifyGadget<SigCurveConfig, FV, CF>
+where
+    for<'a> &'a FV: FieldOpsBounds<'a, BlsSigField<SigCurveConfig>, FV>,
+    <SigCurveConfig as Bls12Config>::G2Config: WBConfig,
+
+    HashCurveConfig<SigCurveConfig>: SWCurveConfig,
+    for<'a> &'a HashCurveVar<SigCurveConfig, FV, CF>: FieldOpsBounds<
+        'a,
+        <HashCurveGroup<SigCurveConfig> as CurveGroup>::BaseField,
+        HashCurveVar<SigCurveConfig, FV, CF>,
+    >,
+    HashCurveVar<SigCurveConfig, FV, CF>:
+        FieldVar<<HashCurveGroup<SigCurveConfig> as CurveGroup>::BaseField, CF>,
+    HashCurveGroup<SigCurveConfig>: CofactorGadget<HashCurveVar<SigCurveConfig, FV, CF>, CF>,
+    SigCurveConfig: Bls12SubgroupGadget<FV, CF>,
+{
+    /// Enforce that `proof` is a valid VRF proof for `input` under `pk`, and return the
+    /// VRF output as bytes.
+    #[tracing::instrument(skip_all)]
+    pub fn verify(
+        parameters: &ParametersVar<SigCurveConfig, FV, CF>,
+        pk: &PublicKeyVar<SigCurveConfig, FV, CF>,
+        input: &[UInt8<CF>],
+        proof: &SignatureVar<SigCurveConfig, FV, CF>,
+    ) -> Result<Vec<UInt8<CF>>, SynthesisError> {
+        let message: Vec<_> = UInt8::constant_vec(VRF_INPUT_DOMAIN)
+            .into_iter()
+            .chain(input.iter().cloned())
+            .collect();
+        BLSAggregateSignatureVerifyGadget::verify(parameters, pk, &message, proof)?;
+
+        let mut hasher = Blake2sGadget::<CF>::default();
+        hasher.update(&UInt8::constant_vec(VRF_OUTPUT_DOMAIN))?;
+        hasher.update(&proof.signature.to_bytes_le()?)?;
+        Ok(hasher.finalize()?.0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ark_r1cs_std::{alloc::AllocVar, fields::fp::FpVar, R1CSVar};
+    use ark_relations::r1cs::ConstraintSystem;
+
+    use crate::bls::get_bls_instance;
+
+    use super::*;
+
+    #[test]
+    fn check_vrf() {
+        type SigCurveConfig = ark_bls12_381::Config;
+
+        let (_, params, sk, pk, _) = get_bls_instance::<SigCurveConfig>();
+        let input = b"epoch 1";
+
+        let (output, proof) = Vrf::prove(&sk, input, &params);
+        assert!(Vrf::verify(&pk, input, &output, &proof, &params));
+
+        // the output is a deterministic function of the key and the input
+        assert_eq!(Vrf::prove(&sk, input, &params).0, output);
+        assert_ne!(Vrf::prove(&sk, b"epoch 2", &params).0, output);
+
+        assert!(!Vrf::verify(&pk, b"epoch 2", &output, &proof, &params));
+        let mut wrong_output = output;
+        wrong_output[0] ^= 1;
+        assert!(!Vrf::verify(&pk, input, &wrong_output, &proof, &params));
+
+        // a signature on the raw input is not a VRF proof
+        let sig = Signature::sign(input, &sk, &params);
+        let proof = VrfProof { proof: sig };
+        assert!(!Vrf::verify(
+            &pk,
+            input,
+            &Vrf::proof_to_output(&proof),
+            &proof,
+            &params
+        ));
+    }
+
+    #[test]
+    fn check_r1cs_vrf_native() {
+        type BlsSigConfig = ark_bls12_377::Config;
+        type BaseSNARKField = BlsSigField<BlsSigConfig>;
+
+        let (_, params, sk, pk, _) = get_bls_instance::<BlsSigConfig>();
+        let input = b"epoch 1";
+        let (output, proof) = Vrf::prove(&sk, input, &params);
+
+        let cs = ConstraintSystem::new_ref();
+        let input_var: Vec<UInt8<BaseSNARKField>> =
+            UInt8::new_input_vec(cs.clone(), input).unwrap();
+        let params_var: ParametersVar<BlsSigConfig, FpVar<BaseSNARKField>, BaseSNARKField> =
+            ParametersVar::new_input(cs.clone(), || Ok(params)).unwrap();
+        let pk_var = PublicKeyVar::new_input(cs.clone(), || Ok(pk)).unwrap();
+        let proof_var = SignatureVar::new_witness(cs.clone(), || Ok(proof.proof)).unwrap();
+
+        let output_var =
+            VrfVerifyGadget::verify(&params_var, &pk_var, &input_var, &proof_var).unwrap();
+
+        assert_eq!(output_var.value().unwrap(), output);
+        assert!(cs.is_satisfied().unwrap());
+    }
+}
@@ -0,0 +1,14 @@
+//! Schnorr signatures (EdDSA-style, with deterministic nonces) on `ed_on_bls12_377`.
+//!
+//! This is a baseline for the BLS gadgets rather than a scheme used by the chain: its base
+//! field is the scalar field of BLS12-377, so the verification gadget works on native field
+//! elements without any pairing, at the cost of one verification per signer.
+
+mod params;
+pub use params::*;
+
+mod scheme;
+pub use scheme::*;
+
+mod r1cs;
+pub use r1cs::*;
@@ -0,0 +1,13 @@
+use ark_ec::twisted_edwards::{Affine, Projective};
+use ark_r1cs_std::fields::fp::FpVar;
+
+pub type EdConfig = ark_ed_on_bls12_377::EdwardsConfig;
+pub type EdAffine = Affine<EdConfig>;
+pub type EdProjective = Projective<EdConfig>;
+/// Base field of the curve, i.e. the field the verification gadget is native in.
+pub type EdBaseField = ark_ed_on_bls12_377::Fq;
+pub type EdScalarField = ark_ed_on_bls12_377::Fr;
+
+// R1CS
+pub type EdVar = ark_ed_on_bls12_377::constraints::EdwardsVar;
+pub type EdBaseFieldVar = FpVar<EdBaseField>;
@@ -0,0 +1,262 @@
+use core::borrow::Borrow;
+
+use ark_crypto_primitives::prf::{blake2s::constraints::Blake2sGadget, PRFGadget};
+use ark_ec::{twisted_edwards::TECurveConfig, AffineRepr, CurveGroup};
+use ark_ff::{BigInteger, PrimeField};
+use ark_r1cs_std::{
+    alloc::{AllocVar, AllocationMode},
+    boolean::Boolean,
+    convert::{ToBitsGadget, ToBytesGadget},
+    eq::EqGadget,
+    groups::CurveVar,
+    uint8::UInt8,
+    R1CSVar,
+};
+use ark_relations::{
+    ns,
+    r1cs::{Namespace, SynthesisError},
+};
+
+use super::{EdBaseField, EdConfig, EdScalarField, EdVar, Parameters, PublicKey, Signature};
+
+#[derive(Clone, Debug)]
+pub struct ParametersVar {
+    pub generator: EdVar,
+}
+
+#[derive(Clone, Debug)]
+pub struct PublicKeyVar {
+    pub pub_key: EdVar,
+}
+
+/// `s` is kept as its little-endian bits, which is all the scalar multiplication needs.
+#[derive(Clone, Debug)]
+pub struct SignatureVar {
+    pub r: EdVar,
+    pub s: Vec<Boolean<EdBaseField>>,
+}
+
+impl AllocVar<Parameters, EdBaseField> for ParametersVar {
+    fn new_variable<T: Borrow<Parameters>>(
+        cs: impl Into<Namespace<EdBaseField>>,
+        f: impl FnOnce() -> Result<T, SynthesisError>,
+        mode: AllocationMode,
+    ) -> Result<Self, SynthesisError> {
+        Ok(Self {
+            generator: EdVar::new_variable(cs, || f().map(|value| value.borrow().generator), mode)?,
+        })
+    }
+}
+
+impl AllocVar<PublicKey, EdBaseField> for PublicKeyVar {
+    fn new_variable<T: Borrow<PublicKey>>(
+        cs: impl Into<Namespace<EdBaseField>>,
+        f: impl FnOnce() -> Result<T, SynthesisError>,
+        mode: AllocationMode,
+    ) -> Result<Self, SynthesisError> {
+        Ok(Self {
+            pub_key: EdVar::new_variable(cs, || f().map(|value| value.borrow().pub_key), mode)?,
+        })
+    }
+}
+
+impl AllocVar<Signature, EdBaseField> for SignatureVar {
+    fn new_variable<T: Borrow<Signature>>(
+        cs: impl Into<Namespace<EdBaseField>>,
+        f: impl FnOnce() -> Result<T, SynthesisError>,
+        mode: AllocationMode,
+    ) -> Result<Self, SynthesisError> {
+        let cs = cs.into().cs();
+        let value = f().map(|value| *value.borrow());
+
+        let r = EdVar::new_variable(
+            cs.clone(),
+            || {
+                value
+                    .as_ref()
+                    .map(|value| value.r)
+                    .map_err(SynthesisError::clone)
+            },
+            mode,
+        )?;
+        // allocate bit by bit, so that the number of variables does not depend on the value
+        let bits = value.map(|value| value.s.into_bigint().to_bits_le());
+        let s = (0..EdScalarField::MODULUS_BIT_SIZE as usize)
+            .map(|i| {
+                Boolean::new_variable(
+                    cs.clone(),
+                    || {
+                        bits.as_ref()
+                            .map(|bits| bits[i])
+                            .map_err(SynthesisError::clone)
+                    },
+                    mode,
+                )
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self { r, s })
+    }
+}
+
+/// Enforce that `point` is in the prime-order subgroup.
+///
+/// The group of `ed_on_bls12_377` is cyclic with cofactor 4, so the subgroup consists of the
+/// multiples of 4: witnessing `point / 4` and doubling it twice costs two doublings instead
+/// of a scalar multiplication by the group order.
+fn enforce_in_prime_order_subgroup(point: &EdVar) -> Result<(), SynthesisError> {
+    if point.is_constant() {
+        // there is no constraint system to allocate the witness in
+        let point = point.value()?.into_affine();
+        return if point.is_in_correct_subgroup_assuming_on_curve() {
+            Ok(())
+        } else {
+            Err(SynthesisError::Unsatisfiable)
+        };
+    }
+
+    let quarter = EdVar::new_witness(ns!(point.cs(), "quarter"), || {
+        Ok(point.value()? * EdConfig::COFACTOR_INV)
+    })?;
+    quarter.double()?.double()?.enforce_equal(point)
+}
+
+/// Verification gadget for `Signature`, in the base field of `ed_on_bls12_377`.
+///
+/// Unlike the BLS gadgets, a committee of `n` signers costs `n` verifications, since
+/// Schnorr signatures on the same message do not aggregate.
+pub struct EdDSAVerifyGadget;
+
+impl EdDSAVerifyGadget {
+    /// Enforce `s * G == R + H(R || pk || m) * pk`, with `R` and `pk` in the prime-order
+    /// subgroup as in `Signature::verify`.
+    #[tracing::instrument(skip_all)]
+    pub fn verify(
+        parameters: &ParametersVar,
+        pk: &PublicKeyVar,
+        message: &[UInt8<EdBaseField>],
+        signature: &SignatureVar,
+    ) -> Result<(), SynthesisError> {
+        enforce_in_prime_order_subgroup(&pk.pub_key)?;
+        enforce_in_prime_order_subgroup(&signature.r)?;
+
+        let mut hasher = Blake2sGadget::default();
+        hasher.update(&signature.r.to_bytes_le()?)?;
+        hasher.update(&pk.pub_key.to_bytes_le()?)?;
+        hasher.update(message)?;
+        // multiplying by the full 256-bit digest reduces it mod the group order for free
+        let challenge = hasher.finalize()?.to_bytes_le()?.to_bits_le()?;
+
+        let lhs = parameters.generator.scalar_mul_le(signature.s.iter())?;
+        let rhs = pk.pub_key.scalar_mul_le(challenge.iter())? + &signature.r;
+        lhs.enforce_equal(&rhs)?;
+
+        tracing::info!(num_constraints = lhs.cs().num_constraints());
+
+        Ok(())
+    }
+
+    /// Verify one signature per committee member, each on its own message.
+    pub fn verify_committee(
+        parameters: &ParametersVar,
+        public_keys: &[PublicKeyVar],
+        messages: &[Vec<UInt8<EdBaseField>>],
+        signatures: &[SignatureVar],
+    ) -> Result<(), SynthesisError> {
+        if public_keys.len() != messages.len() || public_keys.len() != signatures.len() {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+
+        public_keys
+            .iter()
+            .zip(messages)
+            .zip(signatures)
+            .try_for_each(|((pk, message), signature)| {
+                Self::verify(parameters, pk, message, signature)
+            })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ark_ff::{One, Zero};
+    use ark_relations::r1cs::ConstraintSystem;
+    use ark_serialize::CanonicalSerialize;
+    use blake2::{Blake2s256, Digest};
+    use rand::thread_rng;
+
+    use crate::eddsa::{scheme::challenge, EdAffine, SecretKey};
+
+    use super::*;
+
+    #[test]
+    fn check_r1cs_eddsa() {
+        let mut rng = thread_rng();
+        let params = Parameters::setup();
+        let sk = SecretKey::new(&mut rng);
+        let pk = PublicKey::new(&sk, &params);
+        let msg = b"Hello World";
+        let sig = Signature::sign(msg, &sk, &params);
+
+        let verify = |msg: &[u8], sig: Signature| {
+            let cs = ConstraintSystem::new_ref();
+            let params_var = ParametersVar::new_constant(cs.clone(), params).unwrap();
+            let pk_var = PublicKeyVar::new_input(cs.clone(), || Ok(pk)).unwrap();
+            let msg_var = UInt8::new_input_vec(cs.clone(), msg).unwrap();
+            let sig_var = SignatureVar::new_witness(cs.clone(), || Ok(sig)).unwrap();
+
+            EdDSAVerifyGadget::verify(&params_var, &pk_var, &msg_var, &sig_var).unwrap();
+            cs.is_satisfied().unwrap()
+        };
+
+        assert!(verify(msg, sig));
+        assert!(!verify(b"other message", sig));
+        assert!(!verify(
+            msg,
+            Signature {
+                s: sig.s + EdScalarField::from(1u64),
+                ..sig
+            }
+        ));
+    }
+
+    #[test]
+    fn check_r1cs_eddsa_rejects_small_order_points() {
+        let params = Parameters::setup();
+        // `(0, -1)` has order 2, so `s * G == R + c * pk` holds for `R = pk = (0, -1)`, `s = 0`
+        // and any odd `c`
+        let small = EdAffine::new_unchecked(EdBaseField::zero(), -EdBaseField::one());
+        let pk = PublicKey { pub_key: small };
+        let sig = Signature {
+            r: small,
+            s: EdScalarField::zero(),
+        };
+
+        // the gadget multiplies by the unreduced digest, so both it and `c` must be odd
+        let digest_is_odd = |msg: &[u8]| {
+            let mut bytes = vec![];
+            (small, small).serialize_uncompressed(&mut bytes).unwrap();
+            Blake2s256::new()
+                .chain_update(bytes)
+                .chain_update(msg)
+                .finalize()[0]
+                & 1
+                == 1
+        };
+        let msg = (0u8..=u8::MAX)
+            .map(|i| vec![i])
+            .find(|msg| digest_is_odd(msg) && challenge(&small, &small, msg).into_bigint().is_odd())
+            .unwrap();
+        assert!((params.generator * sig.s).is_zero());
+        assert!((small.into_group() + small * challenge(&small, &small, &msg)).is_zero());
+        assert!(!Signature::verify(&msg, &sig, &pk, &params));
+
+        let cs = ConstraintSystem::new_ref();
+        let params_var = ParametersVar::new_constant(cs.clone(), params).unwrap();
+        let pk_var = PublicKeyVar::new_input(cs.clone(), || Ok(pk)).unwrap();
+        let msg_var = UInt8::new_input_vec(cs.clone(), &msg).unwrap();
+        let sig_var = SignatureVar::new_witness(cs.clone(), || Ok(sig)).unwrap();
+        EdDSAVerifyGadget::verify(&params_var, &pk_var, &msg_var, &sig_var).unwrap();
+        assert!(!cs.is_satisfied().unwrap());
+    }
+}
@@ -0,0 +1,195 @@
+use ark_ec::{twisted_edwards::TECurveConfig, AffineRepr, CurveGroup};
+use ark_ff::{
+    field_hashers::{DefaultFieldHasher, HashToField},
+    PrimeField, UniformRand,
+};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use blake2::{Blake2s256, Digest};
+use rand::Rng;
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+use crate::signature::{Error, Signer, Verifier};
+
+use super::{EdAffine, EdConfig, EdProjective, EdScalarField};
+
+/// Domain separation tag for deriving the nonce from the secret key and the message.
+pub const NONCE_DST: &[u8] = b"EDDSA_NONCE_ED_ON_BLS12_377_XMD:BLAKE2S_";
+
+#[derive(Clone, Copy, Debug, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Parameters {
+    pub generator: EdProjective,
+}
+
+#[derive(Clone, Debug, Default, CanonicalSerialize, CanonicalDeserialize)]
+pub struct SecretKey {
+    pub secret_key: EdScalarField,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct PublicKey {
+    pub pub_key: EdAffine,
+}
+
+/// `(R, s)` with `s * G == R + H(R, pk, m) * pk`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Signature {
+    pub r: EdAffine,
+    pub s: EdScalarField,
+}
+
+impl Parameters {
+    #[must_use]
+    pub fn setup() -> Self {
+        Self {
+            generator: EdConfig::GENERATOR.into(),
+        }
+    }
+}
+
+impl SecretKey {
+    pub fn new<R: Rng>(rng: &mut R) -> Self {
+        Self {
+            secret_key: EdScalarField::rand(rng),
+        }
+    }
+}
+
+impl Zeroize for SecretKey {
+    fn zeroize(&mut self) {
+        self.secret_key.zeroize();
+    }
+}
+
+impl Drop for SecretKey {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for SecretKey {}
+
+impl PublicKey {
+    #[must_use]
+    pub fn new(secret_key: &SecretKey, params: &Parameters) -> Self {
+        Self {
+            pub_key: (params.generator * secret_key.secret_key).into_affine(),
+        }
+    }
+}
+
+/// Whether `point` is on the curve and in the prime-order subgroup.
+fn is_valid_point(point: &EdAffine) -> bool {
+    point.is_on_curve() && point.is_in_correct_subgroup_assuming_on_curve()
+}
+
+/// `H(R || pk || m)`, where points are encoded uncompressed (`x || y`, little-endian) so
+/// that the gadget can feed the same bytes to Blake2s.
+///
+/// The 256-bit digest is reduced mod the group order. The small bias this introduces
+/// does not matter for a public challenge.
+pub(super) fn challenge(r: &EdAffine, public_key: &EdAffine, message: &[u8]) -> EdScalarField {
+    let mut bytes = vec![];
+    r.serialize_uncompressed(&mut bytes)
+        .expect("serializing a curve point into a vec should succeed");
+    public_key
+        .serialize_uncompressed(&mut bytes)
+        .expect("serializing a curve point into a vec should succeed");
+    let digest = Blake2s256::new()
+        .chain_update(bytes)
+        .chain_update(message)
+        .finalize();
+    EdScalarField::from_le_bytes_mod_order(&digest)
+}
+
+impl Signature {
+    /// The nonce is derived from the secret key and the message as in EdDSA, so signing
+    /// needs no randomness and never reuses a nonce for two different messages.
+    #[must_use]
+    pub fn sign(message: &[u8], secret_key: &SecretKey, params: &Parameters) -> Self {
+        let mut key_bytes = vec![];
+        secret_key
+            .secret_key
+            .serialize_compressed(&mut key_bytes)
+            .expect("serializing a scalar into a vec should succeed");
+        let hasher =
+            <DefaultFieldHasher<Blake2s256, 128> as HashToField<EdScalarField>>::new(NONCE_DST);
+        let [mut nonce]: [EdScalarField; 1] =
+            hasher.hash_to_field::<1>(&[key_bytes.as_slice(), message].concat());
+        key_bytes.zeroize();
+
+        let public_key = PublicKey::new(secret_key, params);
+        let r = (params.generator * nonce).into_affine();
+        let s = nonce + challenge(&r, &public_key.pub_key, message) * secret_key.secret_key;
+        nonce.zeroize();
+
+        Self { r, s }
+    }
+
+    /// Rejects keys and nonce commitments outside the prime-order subgroup, which would
+    /// otherwise make signatures malleable by small-order components.
+    #[must_use]
+    pub fn verify(
+        message: &[u8],
+        signature: &Self,
+        public_key: &PublicKey,
+        params: &Parameters,
+    ) -> bool {
+        if !(is_valid_point(&public_key.pub_key) && is_valid_point(&signature.r)) {
+            return false;
+        }
+
+        let e = challenge(&signature.r, &public_key.pub_key, message);
+        params.generator * signature.s == signature.r.into_group() + public_key.pub_key * e
+    }
+}
+
+/// Signing does not depend on the generator, so `Parameters::setup` is used.
+impl Signer<Signature> for SecretKey {
+    fn try_sign(&self, message: &[u8]) -> Result<Signature, Error> {
+        Ok(Signature::sign(message, self, &Parameters::setup()))
+    }
+}
+
+impl Verifier<Signature> for PublicKey {
+    fn verify(&self, message: &[u8], signature: &Signature) -> Result<(), Error> {
+        if Signature::verify(message, signature, self, &Parameters::setup()) {
+            Ok(())
+        } else {
+            Err(Error::new())
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::thread_rng;
+
+    use super::*;
+
+    #[test]
+    fn check_signature() {
+        let mut rng = thread_rng();
+        let params = Parameters::setup();
+        let sk = SecretKey::new(&mut rng);
+        let pk = PublicKey::new(&sk, &params);
+
+        let sig = Signature::sign(b"message", &sk, &params);
+        assert!(Signature::verify(b"message", &sig, &pk, &params));
+        assert!(!Signature::verify(b"other message", &sig, &pk, &params));
+        assert!(!Signature::verify(
+            b"message",
+            &sig,
+            &PublicKey::new(&SecretKey::new(&mut rng), &params),
+            &params
+        ));
+
+        // nonces are deterministic
+        assert_eq!(sig, Signature::sign(b"message", &sk, &params));
+        assert_ne!(sig.r, Signature::sign(b"other message", &sk, &params).r);
+
+        // the same check through the scheme-agnostic traits
+        let sig: Signature = sk.sign(b"message");
+        assert!(pk.verify(b"message", &sig).is_ok());
+        assert!(pk.verify(b"other message", &sig).is_err());
+    }
+}
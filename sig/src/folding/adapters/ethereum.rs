@@ -0,0 +1,514 @@
+//! Blocks from the light-client data of the Ethereum beacon chain.
+//!
+//! A beacon node serves, through the
+//! [light-client API](https://ethereum.github.io/beacon-APIs/#/Beacon), a
+//! `LightClientBootstrap` with the sync committee of some period, and a `LightClientUpdate`
+//! per later period, where the sync committee of the period signs a header carrying the
+//! sync committee of the next one. This is `HandoffRule::PreviousSignsNext` with a period
+//! per epoch: the bootstrap becomes the genesis block, and every update a block whose
+//! committee is the next sync committee, signed by the sync aggregate.
+//!
+//! Members of a sync committee have equal weight, so the committee is weighted like a
+//! registry of equal stakes, and `BcConfig::max_committee_size` must be at least the size of
+//! the sync committee.
+//!
+//! The sync aggregate signs the signing root of the attested header with the ciphersuite of
+//! `crate::bls::eth`, not the `signing_bytes` of the block, so the blocks have real keys,
+//! bitfields and signatures but do not pass `Block::verify` nor satisfy the folding circuit.
+//! They exercise the witness generation and the constraint counts on real network data.
+//!
+//! Folding real updates into a valid proof is out of scope. It needs a step circuit that
+//! checks the sync aggregate against the signing root: the SSZ `hash_tree_root` of the
+//! attested header, which commits to the next sync committee through a Merkle branch,
+//! computed with SHA-256 in the circuit, and mixed with the sync-committee domain.
+
+use core::fmt;
+
+use serde::{de, Deserialize, Deserializer};
+
+use crate::{
+    bc::{
+        block::{Block, Committee, QuorumSignature},
+        committee::Validator,
+        params::BcConfig,
+    },
+    bls::{
+        eth::{public_key_from_bytes, signature_from_bytes},
+        from_hex, BLSError,
+    },
+};
+
+/// The sizes of a beacon-chain preset that the conversion depends on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Preset {
+    pub sync_committee_size: usize,
+    /// `SLOTS_PER_EPOCH * EPOCHS_PER_SYNC_COMMITTEE_PERIOD`
+    pub slots_per_period: u64,
+}
+
+impl Preset {
+    pub const MAINNET: Self = Self {
+        sync_committee_size: 512,
+        slots_per_period: 32 * 256,
+    };
+    /// The preset of the consensus spec tests and of local devnets.
+    pub const MINIMAL: Self = Self {
+        sync_committee_size: 32,
+        slots_per_period: 8 * 8,
+    };
+
+    #[must_use]
+    pub const fn period(&self, slot: u64) -> u64 {
+        slot / self.slots_per_period
+    }
+}
+
+#[derive(Debug)]
+pub enum EthereumError {
+    /// The response is not valid JSON of the expected shape.
+    Json(serde_json::Error),
+    /// A public key or signature is not a valid point.
+    InvalidPoint(BLSError),
+    /// The sync committee does not have the size of the preset.
+    InvalidCommitteeSize(usize),
+    /// The participation bitfield does not have a bit per member of the sync committee.
+    InvalidBitfield,
+    /// The sync committee does not fit in `BcConfig::max_committee_size`.
+    CommitteeTooLarge,
+    /// The update is not for the period right after the last block.
+    NonConsecutivePeriod { expected: u64, period: u64 },
+}
+
+impl fmt::Display for EthereumError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Json(e) => write!(f, "invalid light-client response: {e}"),
+            Self::InvalidPoint(e) => write!(f, "invalid public key or signature: {e}"),
+            Self::InvalidCommitteeSize(size) => {
+                write!(f, "sync committee has {size} members")
+            }
+            Self::InvalidBitfield => write!(f, "bitfield does not match the sync committee"),
+            Self::CommitteeTooLarge => write!(f, "sync committee exceeds max_committee_size"),
+            Self::NonConsecutivePeriod { expected, period } => {
+                write!(f, "update is for period {period} but expected {expected}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for EthereumError {}
+
+impl From<serde_json::Error> for EthereumError {
+    fn from(e: serde_json::Error) -> Self {
+        Self::Json(e)
+    }
+}
+
+impl From<BLSError> for EthereumError {
+    fn from(e: BLSError) -> Self {
+        Self::InvalidPoint(e)
+    }
+}
+
+/// The beacon API encodes integers as decimal strings.
+fn quoted_u64<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+    String::deserialize(deserializer)?
+        .parse()
+        .map_err(de::Error::custom)
+}
+
+fn hex_bytes<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+    from_hex(&String::deserialize(deserializer)?)
+        .ok_or_else(|| de::Error::custom("invalid hex string"))
+}
+
+fn hex_bytes_vec<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Vec<u8>>, D::Error> {
+    Vec::<String>::deserialize(deserializer)?
+        .iter()
+        .map(|hex| from_hex(hex).ok_or_else(|| de::Error::custom("invalid hex string")))
+        .collect()
+}
+
+/// The fields of a beacon block header that the conversion uses; the others are ignored.
+#[derive(Clone, Debug, Deserialize)]
+pub struct BeaconBlockHeader {
+    #[serde(deserialize_with = "quoted_u64")]
+    pub slot: u64,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct LightClientHeader {
+    pub beacon: BeaconBlockHeader,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct SyncCommittee {
+    /// The compressed public keys of the members, in committee order.
+    #[serde(deserialize_with = "hex_bytes_vec")]
+    pub pubkeys: Vec<Vec<u8>>,
+    #[serde(deserialize_with = "hex_bytes")]
+    pub aggregate_pubkey: Vec<u8>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct SyncAggregate {
+    /// An SSZ `Bitvector`: bit `i` is bit `i % 8` of byte `i / 8`.
+    #[serde(deserialize_with = "hex_bytes")]
+    pub sync_committee_bits: Vec<u8>,
+    #[serde(deserialize_with = "hex_bytes")]
+    pub sync_committee_signature: Vec<u8>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct LightClientBootstrap {
+    pub header: LightClientHeader,
+    pub current_sync_committee: SyncCommittee,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct LightClientUpdate {
+    pub attested_header: LightClientHeader,
+    pub next_sync_committee: SyncCommittee,
+    pub sync_aggregate: SyncAggregate,
+    #[serde(deserialize_with = "quoted_u64")]
+    pub signature_slot: u64,
+}
+
+/// The beacon API wraps every object with the fork it belongs to.
+#[derive(Deserialize)]
+struct Versioned<T> {
+    data: T,
+}
+
+impl SyncCommittee {
+    /// The members with equal weights, padded to `config.max_committee_size`.
+    pub fn to_committee(
+        &self,
+        preset: &Preset,
+        config: &BcConfig,
+    ) -> Result<Committee, EthereumError> {
+        if self.pubkeys.len() != preset.sync_committee_size {
+            return Err(EthereumError::InvalidCommitteeSize(self.pubkeys.len()));
+        }
+        if self.pubkeys.len() > config.max_committee_size {
+            return Err(EthereumError::CommitteeTooLarge);
+        }
+
+        // a validator may sit in the committee several times, and weighs as many members
+        let registry = self
+            .pubkeys
+            .iter()
+            .map(|pk| {
+                Ok(Validator {
+                    pk: public_key_from_bytes(pk)?,
+                    stake: 1,
+                })
+            })
+            .collect::<Result<Vec<_>, BLSError>>()?;
+        let indices: Vec<_> = (0..registry.len()).collect();
+
+        Ok(Committee::from_registry(&registry, &indices, config))
+    }
+}
+
+impl SyncAggregate {
+    /// The participation bitfield, padded to `config.max_committee_size` by members who
+    /// did not sign, and the aggregate signature.
+    pub fn to_quorum_signature(
+        &self,
+        preset: &Preset,
+        config: &BcConfig,
+    ) -> Result<QuorumSignature, EthereumError> {
+        if self.sync_committee_bits.len() != preset.sync_committee_size.div_ceil(8) {
+            return Err(EthereumError::InvalidBitfield);
+        }
+        if preset.sync_committee_size > config.max_committee_size {
+            return Err(EthereumError::CommitteeTooLarge);
+        }
+
+        let mut signers: Vec<_> = (0..preset.sync_committee_size)
+            .map(|i| self.sync_committee_bits[i / 8] >> (i % 8) & 1 == 1)
+            .collect();
+        signers.resize(config.max_committee_size, false);
+
+        Ok(QuorumSignature {
+            sig: signature_from_bytes(&self.sync_committee_signature)?,
+            signers,
+        })
+    }
+}
+
+impl LightClientBootstrap {
+    /// The genesis block, whose epoch is the period of the bootstrap header.
+    pub fn to_block(&self, preset: &Preset, config: &BcConfig) -> Result<Block, EthereumError> {
+        Ok(Block {
+            epoch: preset.period(self.header.beacon.slot),
+            ..Block::genesis(
+                self.current_sync_committee.to_committee(preset, config)?,
+                config,
+            )
+        })
+    }
+}
+
+impl LightClientUpdate {
+    /// The block after `prev` handing off to the next sync committee, which must be that of
+    /// the period after `prev.epoch`.
+    pub fn to_block(
+        &self,
+        prev: &Block,
+        preset: &Preset,
+        config: &BcConfig,
+    ) -> Result<Block, EthereumError> {
+        let expected = prev.epoch + 1;
+        let period = preset.period(self.attested_header.beacon.slot) + 1;
+        if period != expected {
+            return Err(EthereumError::NonConsecutivePeriod { expected, period });
+        }
+
+        Ok(Block {
+            epoch: period,
+            prev_digest: prev.digest(),
+            sig: self.sync_aggregate.to_quorum_signature(preset, config)?,
+            committee: self.next_sync_committee.to_committee(preset, config)?,
+        })
+    }
+}
+
+/// The chain of a bootstrap and the updates of the following periods, as returned by the
+/// `/eth/v1/beacon/light_client/bootstrap/{block_root}` and
+/// `/eth/v1/beacon/light_client/updates` endpoints.
+pub fn blocks_from_json(
+    bootstrap: &str,
+    updates: &str,
+    preset: &Preset,
+    config: &BcConfig,
+) -> Result<Vec<Block>, EthereumError> {
+    let bootstrap: Versioned<LightClientBootstrap> = serde_json::from_str(bootstrap)?;
+    let updates: Vec<Versioned<LightClientUpdate>> = serde_json::from_str(updates)?;
+
+    let mut blocks = vec![bootstrap.data.to_block(preset, config)?];
+    for update in updates {
+        let block = update
+            .data
+            .to_block(&blocks[blocks.len() - 1], preset, config)?;
+        blocks.push(block);
+    }
+
+    Ok(blocks)
+}
+
+#[cfg(test)]
+mod test {
+    use core::fmt::Write;
+
+    use crate::{
+        bc::params::{BcConfig, TOTAL_VOTING_POWER},
+        bls::{
+            eth::{
+                aggregate, eth_fast_aggregate_verify, public_key_to_bytes, secret_key_from_bytes,
+                sign, signature_to_bytes, sk_to_pk,
+            },
+            SecretKey,
+        },
+        params::BlsSigConfig,
+    };
+
+    use super::{blocks_from_json, EthereumError, Preset};
+
+    const PRESET: Preset = Preset::MINIMAL;
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().fold(String::new(), |mut hex, b| {
+            let _ = write!(hex, "{b:02x}");
+            hex
+        })
+    }
+
+    fn sync_committee_json(keys: &[SecretKey<BlsSigConfig>]) -> String {
+        let pubkeys: Vec<_> = keys
+            .iter()
+            .map(|sk| format!("\"0x{}\"", hex(&public_key_to_bytes(&sk_to_pk(sk)))))
+            .collect();
+        format!(
+            r#"{{"pubkeys":[{}],"aggregate_pubkey":"0x{}"}}"#,
+            pubkeys.join(","),
+            hex(&public_key_to_bytes(&sk_to_pk(&keys[0])))
+        )
+    }
+
+    fn update_json(
+        slot: u64,
+        signers: &[SecretKey<BlsSigConfig>],
+        next: &[SecretKey<BlsSigConfig>],
+        bits: &[u8],
+        message: &[u8],
+    ) -> String {
+        let signatures: Vec<_> = signers
+            .iter()
+            .zip(
+                bits.iter()
+                    .flat_map(|byte| (0..8).map(move |i| byte >> i & 1 == 1)),
+            )
+            .filter(|(_, signed)| *signed)
+            .map(|(sk, _)| sign(sk, message))
+            .collect();
+        format!(
+            r#"{{"version":"deneb","data":{{
+                "attested_header":{{"beacon":{{"slot":"{slot}","proposer_index":"1"}}}},
+                "next_sync_committee":{},
+                "sync_aggregate":{{"sync_committee_bits":"0x{}","sync_committee_signature":"0x{}"}},
+                "signature_slot":"{}"}}}}"#,
+            sync_committee_json(next),
+            hex(bits),
+            hex(&signature_to_bytes(&aggregate(&signatures).unwrap())),
+            slot + 1
+        )
+    }
+
+    fn committee_keys(seed: u8) -> Vec<SecretKey<BlsSigConfig>> {
+        (0..PRESET.sync_committee_size)
+            .map(|i| secret_key_from_bytes(&[[seed; 31].as_slice(), &[i as u8 + 1]].concat()))
+            .collect::<Result<_, _>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_blocks_from_json() {
+        let config = BcConfig {
+            max_committee_size: 40,
+            ..Default::default()
+        };
+        let committees: Vec<_> = (1..=3).map(committee_keys).collect();
+        let message = [0x42; 32];
+        // the first 24 members of the first committee sign, then every member but the last
+        let bits = [[0xff, 0xff, 0xff, 0x00], [0xff, 0xff, 0xff, 0x7f]];
+
+        let bootstrap = format!(
+            r#"{{"version":"deneb","data":{{
+                "header":{{"beacon":{{"slot":"{}"}}}},
+                "current_sync_committee":{}}}}}"#,
+            5 * PRESET.slots_per_period + 3,
+            sync_committee_json(&committees[0])
+        );
+        let updates = format!(
+            "[{},{}]",
+            update_json(
+                5 * PRESET.slots_per_period + 10,
+                &committees[0],
+                &committees[1],
+                &bits[0],
+                &message
+            ),
+            update_json(
+                6 * PRESET.slots_per_period,
+                &committees[1],
+                &committees[2],
+                &bits[1],
+                &message
+            ),
+        );
+
+        let blocks = blocks_from_json(&bootstrap, &updates, &PRESET, &config).unwrap();
+        assert_eq!(
+            blocks.iter().map(|block| block.epoch).collect::<Vec<_>>(),
+            [5, 6, 7]
+        );
+
+        for (i, block) in blocks.iter().enumerate() {
+            let signers = &block.committee.signers;
+            assert_eq!(signers.len(), config.max_committee_size);
+            assert_eq!(
+                signers.iter().map(|(_, weight)| weight).sum::<u64>(),
+                TOTAL_VOTING_POWER
+            );
+            assert!(signers[PRESET.sync_committee_size..]
+                .iter()
+                .all(|(_, weight)| *weight == 0));
+            assert_eq!(signers[0].0.pub_key, sk_to_pk(&committees[i][0]).pub_key);
+        }
+
+        for (i, block) in blocks.iter().enumerate().skip(1) {
+            assert_eq!(block.prev_digest, blocks[i - 1].digest());
+            assert_eq!(
+                block.sig.signers.iter().filter(|signed| **signed).count(),
+                [24, 31][i - 1]
+            );
+            assert!(!block.sig.signers[PRESET.sync_committee_size - 1]);
+
+            // the aggregate signature is the one of the participating members
+            let public_keys: Vec<_> = committees[i - 1]
+                .iter()
+                .zip(&block.sig.signers)
+                .filter(|(_, signed)| **signed)
+                .map(|(sk, _)| sk_to_pk(sk))
+                .collect();
+            assert!(eth_fast_aggregate_verify(
+                &public_keys,
+                &message,
+                &block.sig.sig
+            ));
+        }
+    }
+
+    #[test]
+    fn test_invalid_updates() {
+        let config = BcConfig {
+            max_committee_size: PRESET.sync_committee_size,
+            ..Default::default()
+        };
+        let keys = committee_keys(1);
+        let message = [0x42; 32];
+        let bootstrap = format!(
+            r#"{{"version":"deneb","data":{{
+                "header":{{"beacon":{{"slot":"0"}}}},
+                "current_sync_committee":{}}}}}"#,
+            sync_committee_json(&keys)
+        );
+        let update =
+            |slot, bits: &[u8]| format!("[{}]", update_json(slot, &keys, &keys, bits, &message));
+
+        // a period is skipped
+        assert!(matches!(
+            blocks_from_json(
+                &bootstrap,
+                &update(PRESET.slots_per_period, &[0xff; 4]),
+                &PRESET,
+                &config
+            ),
+            Err(EthereumError::NonConsecutivePeriod {
+                expected: 1,
+                period: 2
+            })
+        ));
+        assert!(matches!(
+            blocks_from_json(&bootstrap, &update(0, &[0xff; 5]), &PRESET, &config),
+            Err(EthereumError::InvalidBitfield)
+        ));
+        assert!(matches!(
+            blocks_from_json(
+                &bootstrap,
+                &update(0, &[0xff; 4]),
+                &Preset::MAINNET,
+                &config
+            ),
+            Err(EthereumError::InvalidCommitteeSize(32))
+        ));
+        assert!(matches!(
+            blocks_from_json(
+                &bootstrap,
+                "[]",
+                &PRESET,
+                &BcConfig {
+                    max_committee_size: 16,
+                    ..config
+                }
+            ),
+            Err(EthereumError::CommitteeTooLarge)
+        ));
+        assert!(matches!(
+            blocks_from_json("{}", "[]", &PRESET, &config),
+            Err(EthereumError::Json(_))
+        ));
+    }
+}
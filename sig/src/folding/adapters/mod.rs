@@ -0,0 +1,3 @@
+//! Conversions from the data of other chains into the blocks folded by the circuits.
+
+pub mod ethereum;
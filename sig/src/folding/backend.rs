@@ -0,0 +1,327 @@
+//! Folding a step circuit with a scheme selected at runtime, to compare the cost of folding
+//! and the size of the proofs across schemes with the same circuit.
+//!
+//! HyperNova is instantiated with `MU = NU = 1`, folding one instance per step as Nova does.
+//!
+//! Every scheme here folds a single incoming instance per step, ProtoGalaxy included:
+//! multi-instance folding with ProtoGalaxy is not implemented, because sonobe's IVC with
+//! ProtoGalaxy takes no other instances in `prove_step`. Several blocks are instead validated
+//! by one instance of a multi-block step circuit, `BCCircuitBatched`, whose cost ProtoGalaxy
+//! amortises better than Nova. This gives a per-block comparison of the schemes, but it is
+//! not the folding of `K` block instances at once, which is still open.
+
+use ark_ec::CurveGroup;
+use ark_ff::PrimeField;
+use ark_serialize::CanonicalSerialize;
+use folding_schemes::{
+    commitment::CommitmentScheme,
+    folding::{
+        hypernova::{self, HyperNova},
+        nova::{self, Nova},
+        protogalaxy::{self, ProtoGalaxy},
+    },
+    frontend::FCircuit,
+    transcript::poseidon::poseidon_canonical_config,
+    Error, FoldingScheme,
+};
+use rand::RngCore;
+
+pub type NovaFolding<C1, C2, FC, CS1, CS2> = Nova<C1, C2, FC, CS1, CS2, false>;
+
+pub type HyperNovaFolding<C1, C2, FC, CS1, CS2> = HyperNova<C1, C2, FC, CS1, CS2, 1, 1, false>;
+
+pub type ProtoGalaxyFolding<C1, C2, FC, CS1, CS2> = ProtoGalaxy<C1, C2, FC, CS1, CS2>;
+
+type Params<FS, C1, C2, FC> = (
+    <FS as FoldingScheme<C1, C2, FC>>::ProverParam,
+    <FS as FoldingScheme<C1, C2, FC>>::VerifierParam,
+);
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FoldingBackend {
+    #[default]
+    Nova,
+    HyperNova,
+    ProtoGalaxy,
+}
+
+/// The parameters of a folding scheme, as output by `FoldingBackend::preprocess`.
+pub enum FoldingParams<C1, C2, FC, CS1, CS2>
+where
+    C1: CurveGroup<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    C2: CurveGroup,
+    C2::BaseField: PrimeField,
+    FC: FCircuit<C1::ScalarField>,
+    CS1: CommitmentScheme<C1, false>,
+    CS2: CommitmentScheme<C2, false>,
+    NovaFolding<C1, C2, FC, CS1, CS2>: FoldingScheme<C1, C2, FC>,
+    HyperNovaFolding<C1, C2, FC, CS1, CS2>: FoldingScheme<C1, C2, FC>,
+    ProtoGalaxyFolding<C1, C2, FC, CS1, CS2>: FoldingScheme<C1, C2, FC>,
+{
+    Nova(Params<NovaFolding<C1, C2, FC, CS1, CS2>, C1, C2, FC>),
+    HyperNova(Params<HyperNovaFolding<C1, C2, FC, CS1, CS2>, C1, C2, FC>),
+    ProtoGalaxy(Params<ProtoGalaxyFolding<C1, C2, FC, CS1, CS2>, C1, C2, FC>),
+}
+
+/// The step circuit `FC` folded with the scheme of a `FoldingBackend`.
+pub enum Folding<C1, C2, FC, CS1, CS2>
+where
+    C1: CurveGroup<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    C2: CurveGroup,
+    C2::BaseField: PrimeField,
+    FC: FCircuit<C1::ScalarField>,
+    CS1: CommitmentScheme<C1, false>,
+    CS2: CommitmentScheme<C2, false>,
+    NovaFolding<C1, C2, FC, CS1, CS2>: FoldingScheme<C1, C2, FC>,
+    HyperNovaFolding<C1, C2, FC, CS1, CS2>: FoldingScheme<C1, C2, FC>,
+    ProtoGalaxyFolding<C1, C2, FC, CS1, CS2>: FoldingScheme<C1, C2, FC>,
+{
+    Nova(NovaFolding<C1, C2, FC, CS1, CS2>),
+    HyperNova(HyperNovaFolding<C1, C2, FC, CS1, CS2>),
+    ProtoGalaxy(ProtoGalaxyFolding<C1, C2, FC, CS1, CS2>),
+}
+
+impl FoldingBackend {
+    /// Generate the parameters of the scheme for `circuit`, with the Poseidon configuration
+    /// of sonobe's transcripts.
+    pub fn preprocess<C1, C2, FC, CS1, CS2>(
+        self,
+        rng: impl RngCore,
+        circuit: &FC,
+    ) -> Result<FoldingParams<C1, C2, FC, CS1, CS2>, Error>
+    where
+        C1: CurveGroup<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+        C2: CurveGroup,
+        C2::BaseField: PrimeField,
+        FC: FCircuit<C1::ScalarField>,
+        CS1: CommitmentScheme<C1, false>,
+        CS2: CommitmentScheme<C2, false>,
+        NovaFolding<C1, C2, FC, CS1, CS2>: FoldingScheme<
+            C1,
+            C2,
+            FC,
+            PreprocessorParam = nova::PreprocessorParam<C1, C2, FC, CS1, CS2, false>,
+        >,
+        HyperNovaFolding<C1, C2, FC, CS1, CS2>: FoldingScheme<
+            C1,
+            C2,
+            FC,
+            PreprocessorParam = hypernova::PreprocessorParam<C1, C2, FC, CS1, CS2, false>,
+        >,
+        ProtoGalaxyFolding<C1, C2, FC, CS1, CS2>: FoldingScheme<
+            C1,
+            C2,
+            FC,
+            PreprocessorParam = protogalaxy::PreprocessorParam<C1, C2, FC, CS1, CS2>,
+        >,
+    {
+        let poseidon_config = poseidon_canonical_config::<C1::ScalarField>();
+        Ok(match self {
+            Self::Nova => FoldingParams::Nova(NovaFolding::preprocess(
+                rng,
+                &nova::PreprocessorParam::new(poseidon_config, circuit.clone()),
+            )?),
+            Self::HyperNova => FoldingParams::HyperNova(HyperNovaFolding::preprocess(
+                rng,
+                &hypernova::PreprocessorParam::new(poseidon_config, circuit.clone()),
+            )?),
+            Self::ProtoGalaxy => FoldingParams::ProtoGalaxy(ProtoGalaxyFolding::preprocess(
+                rng,
+                &protogalaxy::PreprocessorParam::new(poseidon_config, circuit.clone()),
+            )?),
+        })
+    }
+}
+
+impl<C1, C2, FC, CS1, CS2> FoldingParams<C1, C2, FC, CS1, CS2>
+where
+    C1: CurveGroup<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    C2: CurveGroup,
+    C2::BaseField: PrimeField,
+    FC: FCircuit<C1::ScalarField>,
+    CS1: CommitmentScheme<C1, false>,
+    CS2: CommitmentScheme<C2, false>,
+    NovaFolding<C1, C2, FC, CS1, CS2>: FoldingScheme<C1, C2, FC>,
+    HyperNovaFolding<C1, C2, FC, CS1, CS2>: FoldingScheme<C1, C2, FC>,
+    ProtoGalaxyFolding<C1, C2, FC, CS1, CS2>: FoldingScheme<C1, C2, FC>,
+{
+    #[must_use]
+    pub const fn backend(&self) -> FoldingBackend {
+        match self {
+            Self::Nova(_) => FoldingBackend::Nova,
+            Self::HyperNova(_) => FoldingBackend::HyperNova,
+            Self::ProtoGalaxy(_) => FoldingBackend::ProtoGalaxy,
+        }
+    }
+
+    /// The compressed size of the prover and the verifier parameters.
+    #[must_use]
+    pub fn compressed_size(&self) -> (usize, usize) {
+        match self {
+            Self::Nova((pp, vp)) => (pp.compressed_size(), vp.compressed_size()),
+            Self::HyperNova((pp, vp)) => (pp.compressed_size(), vp.compressed_size()),
+            Self::ProtoGalaxy((pp, vp)) => (pp.compressed_size(), vp.compressed_size()),
+        }
+    }
+}
+
+impl<C1, C2, FC, CS1, CS2> Folding<C1, C2, FC, CS1, CS2>
+where
+    C1: CurveGroup<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    C2: CurveGroup,
+    C2::BaseField: PrimeField,
+    FC: FCircuit<C1::ScalarField>,
+    CS1: CommitmentScheme<C1, false>,
+    CS2: CommitmentScheme<C2, false>,
+    NovaFolding<C1, C2, FC, CS1, CS2>: FoldingScheme<C1, C2, FC>,
+    HyperNovaFolding<C1, C2, FC, CS1, CS2>: FoldingScheme<C1, C2, FC>,
+    ProtoGalaxyFolding<C1, C2, FC, CS1, CS2>: FoldingScheme<C1, C2, FC>,
+{
+    /// Start folding `circuit` from `z_0` with the scheme `params` were generated for.
+    pub fn init(
+        params: &FoldingParams<C1, C2, FC, CS1, CS2>,
+        circuit: FC,
+        z_0: Vec<C1::ScalarField>,
+    ) -> Result<Self, Error> {
+        Ok(match params {
+            FoldingParams::Nova(params) => Self::Nova(NovaFolding::init(params, circuit, z_0)?),
+            FoldingParams::HyperNova(params) => {
+                Self::HyperNova(HyperNovaFolding::init(params, circuit, z_0)?)
+            }
+            FoldingParams::ProtoGalaxy(params) => {
+                Self::ProtoGalaxy(ProtoGalaxyFolding::init(params, circuit, z_0)?)
+            }
+        })
+    }
+
+    #[must_use]
+    pub const fn backend(&self) -> FoldingBackend {
+        match self {
+            Self::Nova(_) => FoldingBackend::Nova,
+            Self::HyperNova(_) => FoldingBackend::HyperNova,
+            Self::ProtoGalaxy(_) => FoldingBackend::ProtoGalaxy,
+        }
+    }
+
+    pub fn prove_step(
+        &mut self,
+        rng: impl RngCore,
+        external_inputs: FC::ExternalInputs,
+    ) -> Result<(), Error> {
+        match self {
+            Self::Nova(folding) => folding.prove_step(rng, external_inputs, None),
+            Self::HyperNova(folding) => folding.prove_step(rng, external_inputs, None),
+            Self::ProtoGalaxy(folding) => folding.prove_step(rng, external_inputs, None),
+        }
+    }
+
+    #[must_use]
+    pub fn state(&self) -> Vec<C1::ScalarField> {
+        match self {
+            Self::Nova(folding) => folding.state(),
+            Self::HyperNova(folding) => folding.state(),
+            Self::ProtoGalaxy(folding) => folding.state(),
+        }
+    }
+
+    /// Verify the IVC proof of the current state against `params`.
+    pub fn verify(&self, params: &FoldingParams<C1, C2, FC, CS1, CS2>) -> Result<(), Error> {
+        match (self, params) {
+            (Self::Nova(folding), FoldingParams::Nova((_, vp))) => {
+                NovaFolding::verify(vp.clone(), folding.ivc_proof())
+            }
+            (Self::HyperNova(folding), FoldingParams::HyperNova((_, vp))) => {
+                HyperNovaFolding::verify(vp.clone(), folding.ivc_proof())
+            }
+            (Self::ProtoGalaxy(folding), FoldingParams::ProtoGalaxy((_, vp))) => {
+                ProtoGalaxyFolding::verify(vp.clone(), folding.ivc_proof())
+            }
+            _ => Err(Error::Other(
+                "the parameters are for another folding scheme".to_string(),
+            )),
+        }
+    }
+
+    /// The compressed size of the IVC proof of the current state.
+    #[must_use]
+    pub fn proof_size(&self) -> usize {
+        match self {
+            Self::Nova(folding) => folding.ivc_proof().compressed_size(),
+            Self::HyperNova(folding) => folding.ivc_proof().compressed_size(),
+            Self::ProtoGalaxy(folding) => folding.ivc_proof().compressed_size(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ark_mnt4_753::{G1Projective as G1, MNT4_753 as MNT4};
+    use ark_mnt6_753::{G1Projective as G2, MNT6_753 as MNT6};
+    use folding_schemes::{commitment::kzg::KZG, frontend::FCircuit};
+    use rand::thread_rng;
+
+    use crate::{
+        bc::{checkpoint::Checkpoint, params::BcConfig, testgen::ChainGenerator},
+        folding::circuit::{BCCircuitNoMerkle, BCCircuitParams},
+    };
+
+    use super::{Folding, FoldingBackend};
+
+    type FC = BCCircuitNoMerkle<(G1, G2)>;
+    type CS1 = KZG<'static, MNT4>;
+    type CS2 = KZG<'static, MNT6>;
+
+    /// Fold two blocks with `backend`, then verify the proof and check the state it reaches.
+    fn check_round_trip(backend: FoldingBackend) {
+        let params = BCCircuitParams {
+            config: BcConfig {
+                max_committee_size: 4,
+                ..Default::default()
+            },
+            ..BCCircuitParams::setup()
+        };
+        let mut rng = thread_rng();
+        let (blocks, _) = ChainGenerator::new(3, 4)
+            .config(params.config)
+            .generate(&mut rng);
+        let circuit = FC::new(params).unwrap();
+        let state = |i: usize| {
+            circuit
+                .initial_state(&Checkpoint::from_block(&blocks[i]), &blocks[i].committee)
+                .unwrap()
+        };
+
+        let folding_params = backend
+            .preprocess::<G1, G2, FC, CS1, CS2>(&mut rng, &circuit)
+            .unwrap();
+        assert_eq!(folding_params.backend(), backend);
+
+        let mut folding = Folding::init(&folding_params, circuit, state(0)).unwrap();
+        assert_eq!(folding.backend(), backend);
+        for block in &blocks[1..] {
+            folding.prove_step(&mut rng, Some(block.clone())).unwrap();
+        }
+
+        folding.verify(&folding_params).unwrap();
+        assert_eq!(folding.state(), state(2));
+        assert!(folding.proof_size() > 0);
+    }
+
+    #[test]
+    #[ignore = "folding over MNT4/MNT6 takes a long time to finish running"]
+    fn test_nova_round_trip() {
+        check_round_trip(FoldingBackend::Nova);
+    }
+
+    #[test]
+    #[ignore = "folding over MNT4/MNT6 takes a long time to finish running"]
+    fn test_hypernova_round_trip() {
+        check_round_trip(FoldingBackend::HyperNova);
+    }
+
+    #[test]
+    #[ignore = "folding over MNT4/MNT6 takes a long time to finish running"]
+    fn test_protogalaxy_round_trip() {
+        check_round_trip(FoldingBackend::ProtoGalaxy);
+    }
+}
@@ -1,51 +1,94 @@
-use ark_ff::PrimeField;
+use ark_crypto_primitives::prf::{blake2s::constraints::Blake2sGadget, PRFGadget};
+use ark_ec::{bls12::Bls12Config, AffineRepr, CurveGroup};
+use ark_ff::{One, PrimeField, Zero};
 use ark_r1cs_std::{
-    alloc::AllocVar, fields::emulated_fp::EmulatedFpVar, prelude::Boolean, uint64::UInt64,
+    alloc::{AllocVar, AllocationMode},
+    fields::{FieldOpsBounds, FieldVar},
+    groups::{bls12::G1Var, CurveVar},
+    prelude::Boolean,
+    uint64::UInt64,
     uint8::UInt8,
 };
-use ark_relations::r1cs::SynthesisError;
+use ark_relations::r1cs::{Namespace, OptimizationGoal, SynthesisError};
+use ark_std::cfg_iter;
 use derivative::Derivative;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 
 use crate::{
     bc::{
         block::{Block, Committee, QuorumSignature},
-        params::{HASH_OUTPUT_SIZE, MAX_COMMITTEE_SIZE},
+        params::HASH_OUTPUT_SIZE,
     },
     bls::{PublicKey, PublicKeyVar, SignatureVar},
-    params::{BlsSigConfig, BlsSigField},
+    params::{BlsSigConfig, BlsSigField, BlsSigFieldVar},
 };
 
+use super::{hint::AllocHint, serialize::SerializeGadget};
+
+/// The signer gadgets default to the keys of the chain, `BlsSigConfig` emulated in `CF`, but
+/// they can hold the keys of any BLS12 curve, e.g. those of BLS12-377 as `FpVar`s of a circuit
+/// over BW6-761.
 #[derive(Derivative)]
 #[derivative(Clone(bound = ""), Debug(bound = ""))]
-pub struct SignerVar<CF: PrimeField> {
+pub struct SignerVar<
+    CF: PrimeField,
+    SigCurveConfig: Bls12Config = BlsSigConfig,
+    FV: FieldVar<BlsSigField<SigCurveConfig>, CF> = BlsSigFieldVar<CF>,
+> where
+    for<'a> &'a FV: FieldOpsBounds<'a, BlsSigField<SigCurveConfig>, FV>,
+{
     /// This field was originally used with on curve check and on prime order subgroup check enabled.
     /// Because of the excessive number of constraints generated, it now disables on these checks.
     /// But it is still safe, and you can see the safety argument in `BlockVar` and `from_constraint_field`
     /// function of `PublicKeyVar`.
-    pub pk: PublicKeyVar<BlsSigConfig, EmulatedFpVar<BlsSigField<BlsSigConfig>, CF>, CF>,
+    pub pk: PublicKeyVar<SigCurveConfig, FV, CF>,
     pub weight: UInt64<CF>,
 }
 
 #[derive(Derivative)]
 #[derivative(Clone(bound = ""), Debug(bound = ""))]
-pub struct CommitteeVar<CF: PrimeField> {
-    pub committee: Vec<SignerVar<CF>>,
+pub struct CommitteeVar<
+    CF: PrimeField,
+    SigCurveConfig: Bls12Config = BlsSigConfig,
+    FV: FieldVar<BlsSigField<SigCurveConfig>, CF> = BlsSigFieldVar<CF>,
+> where
+    for<'a> &'a FV: FieldOpsBounds<'a, BlsSigField<SigCurveConfig>, FV>,
+{
+    pub committee: Vec<SignerVar<CF, SigCurveConfig, FV>>,
 }
 
 #[derive(Derivative)]
 #[derivative(Clone(bound = ""), Debug(bound = ""))]
-pub struct QuorumSignatureVar<CF: PrimeField> {
-    pub sig: SignatureVar<BlsSigConfig, EmulatedFpVar<BlsSigField<BlsSigConfig>, CF>, CF>,
+pub struct QuorumSignatureVar<
+    CF: PrimeField,
+    SigCurveConfig: Bls12Config = BlsSigConfig,
+    FV: FieldVar<BlsSigField<SigCurveConfig>, CF> = BlsSigFieldVar<CF>,
+> where
+    for<'a> &'a FV: FieldOpsBounds<'a, BlsSigField<SigCurveConfig>, FV>,
+{
+    pub sig: SignatureVar<SigCurveConfig, FV, CF>,
     pub signers: Vec<Boolean<CF>>,
 }
 
 /// Copied from `sig/src/bc/block.rs`
+///
+/// The keys and the signature are represented by `FV`, which can be chosen with a
+/// `FieldVarSelector`, e.g. `SelectedFieldVar<NativeFieldVar, _, _>` in circuits over the
+/// base field of the signature curve. A `Block` holds keys of `BlsSigConfig`, so it is only
+/// allocated for that curve, but with any `FV`.
 #[derive(Derivative)]
 #[derivative(Clone(bound = ""), Debug(bound = ""))]
-pub struct BlockVar<CF: PrimeField> {
+pub struct BlockVar<
+    CF: PrimeField,
+    SigCurveConfig: Bls12Config = BlsSigConfig,
+    FV: FieldVar<BlsSigField<SigCurveConfig>, CF> = BlsSigFieldVar<CF>,
+> where
+    for<'a> &'a FV: FieldOpsBounds<'a, BlsSigField<SigCurveConfig>, FV>,
+{
     pub epoch: UInt64<CF>,
     pub prev_digest: [UInt8<CF>; HASH_OUTPUT_SIZE],
-    pub sig: QuorumSignatureVar<CF>,
+    pub sig: QuorumSignatureVar<CF, SigCurveConfig, FV>,
 
     /// This field was originally used with on curve check and on prime order subgroup check enabled
     /// for every committee member, which significantly grows the number of constraints
@@ -53,11 +96,18 @@ pub struct BlockVar<CF: PrimeField> {
     /// all the checks because the committee/blockchain consensus is responsible for ensuring the security
     /// (pks reside on the curve and the prime order subgroup) of the first committee and new blocks signed
     /// by the majority of the committee.
-    pub committee: CommitteeVar<CF>,
+    pub committee: CommitteeVar<CF, SigCurveConfig, FV>,
 }
 
-impl<CF: PrimeField> AllocVar<(PublicKey<BlsSigConfig>, u64), CF> for SignerVar<CF> {
-    fn new_variable<T: std::borrow::Borrow<(PublicKey<BlsSigConfig>, u64)>>(
+impl<
+        CF: PrimeField,
+        SigCurveConfig: Bls12Config,
+        FV: FieldVar<BlsSigField<SigCurveConfig>, CF>,
+    > AllocVar<(PublicKey<SigCurveConfig>, u64), CF> for SignerVar<CF, SigCurveConfig, FV>
+where
+    for<'a> &'a FV: FieldOpsBounds<'a, BlsSigField<SigCurveConfig>, FV>,
+{
+    fn new_variable<T: std::borrow::Borrow<(PublicKey<SigCurveConfig>, u64)>>(
         cs: impl Into<ark_relations::r1cs::Namespace<CF>>,
         f: impl FnOnce() -> Result<T, SynthesisError>,
         mode: ark_r1cs_std::prelude::AllocationMode,
@@ -91,48 +141,109 @@ impl<CF: PrimeField> AllocVar<(PublicKey<BlsSigConfig>, u64), CF> for SignerVar<
     }
 }
 
-impl<CF: PrimeField> AllocVar<Committee, CF> for CommitteeVar<CF> {
-    fn new_variable<T: std::borrow::Borrow<Committee>>(
-        cs: impl Into<ark_relations::r1cs::Namespace<CF>>,
-        f: impl FnOnce() -> Result<T, SynthesisError>,
-        mode: ark_r1cs_std::prelude::AllocationMode,
-    ) -> Result<Self, SynthesisError> {
-        let cs = cs.into();
+/// The hints of the coordinates of the public key of a signer, and its weight.
+struct SignerHint<H> {
+    x: H,
+    y: H,
+    z: H,
+    weight: u64,
+}
 
-        let committee = f();
+impl<
+        CF: PrimeField,
+        SigCurveConfig: Bls12Config,
+        FV: AllocHint<BlsSigField<SigCurveConfig>, CF>,
+    > SignerVar<CF, SigCurveConfig, FV>
+where
+    for<'a> &'a FV: FieldOpsBounds<'a, BlsSigField<SigCurveConfig>, FV>,
+{
+    /// The hints to allocate `signer` in a constraint system with `optimization_goal`.
+    fn hint(
+        signer: &(PublicKey<SigCurveConfig>, u64),
+        optimization_goal: OptimizationGoal,
+    ) -> Result<SignerHint<FV::Hint>, SynthesisError> {
+        // the coordinates that `new_variable_omit_on_curve_check` allocates
+        let (x, y, z) = match signer.0.pub_key.into_affine().xy() {
+            Some((x, y)) => (x, y, BlsSigField::<SigCurveConfig>::one()),
+            None => (Zero::zero(), One::one(), Zero::zero()),
+        };
+        Ok(SignerHint {
+            x: FV::hint(x, optimization_goal)?,
+            y: FV::hint(y, optimization_goal)?,
+            z: FV::hint(z, optimization_goal)?,
+            weight: signer.1,
+        })
+    }
 
-        let committee_var = Vec::<SignerVar<CF>>::new_variable(
+    /// Allocate a signer from its hints, as `new_variable` allocates it from its key and
+    /// weight.
+    fn new_variable_with_hint(
+        cs: impl Into<Namespace<CF>>,
+        hint: Result<&SignerHint<FV::Hint>, SynthesisError>,
+        mode: AllocationMode,
+    ) -> Result<Self, SynthesisError> {
+        let cs = cs.into().cs();
+        let x = FV::new_variable_with_hint(
             cs.clone(),
-            || {
-                committee
-                    .as_ref()
-                    .map(|value| value.borrow().clone().signers)
-                    .map_err(SynthesisError::clone)
-            },
+            hint.as_ref()
+                .map(|hint| &hint.x)
+                .map_err(SynthesisError::clone),
+            mode,
+        )?;
+        let y = FV::new_variable_with_hint(
+            cs.clone(),
+            hint.as_ref()
+                .map(|hint| &hint.y)
+                .map_err(SynthesisError::clone),
+            mode,
+        )?;
+        let z = FV::new_variable_with_hint(
+            cs.clone(),
+            hint.as_ref()
+                .map(|hint| &hint.z)
+                .map_err(SynthesisError::clone),
             mode,
         )?;
 
-        assert_eq!(
-            committee_var.len(),
-            MAX_COMMITTEE_SIZE,
-            "committee_var must have len == MAX_COMMITTEE_SIZE"
-        );
+        Ok(Self {
+            // safety: see `pk`
+            pk: PublicKeyVar {
+                pub_key: G1Var::<SigCurveConfig, FV, CF>::new(x, y, z),
+            },
+            weight: UInt64::new_variable(cs, || hint.map(|hint| hint.weight), mode)?,
+        })
+    }
+}
 
-        // similar to `QuorumSignatureVar`, we need to fill committee_var
-        //
-        // safety: committee_var.len() <= MAX_COMMITTEE_SIZE
-        // committee_var.extend(
-        //     std::iter::repeat(SignerVar::new_variable(
-        //         cs,
-        //         // it's ok to use default values as they will not be used
-        //         || Ok((PublicKey::default(), u64::default())),
-        //         mode,
-        //     )?)
-        //     .take(MAX_COMMITTEE_SIZE - committee_var.len()),
-        // );
-        //
-        // Update: It's not correct to extend it here. Rather, we need to enforce all the state outside the circuit has
-        // fixed size. Otherwise, the hash of those states will never match their circuit counterpart.
+impl<CF: PrimeField, FV: AllocHint<BlsSigField<BlsSigConfig>, CF>> AllocVar<Committee, CF>
+    for CommitteeVar<CF, BlsSigConfig, FV>
+where
+    for<'a> &'a FV: FieldOpsBounds<'a, BlsSigField<BlsSigConfig>, FV>,
+{
+    /// The signers are allocated one after the other, as the constraint system is not
+    /// `Sync`, but the limbs of their coordinates, the native part of the allocation of an
+    /// emulated committee, are computed before, in parallel with the `parallel` feature.
+    fn new_variable<T: std::borrow::Borrow<Committee>>(
+        cs: impl Into<ark_relations::r1cs::Namespace<CF>>,
+        f: impl FnOnce() -> Result<T, SynthesisError>,
+        mode: ark_r1cs_std::prelude::AllocationMode,
+    ) -> Result<Self, SynthesisError> {
+        let cs = cs.into().cs();
+
+        // the number of signers is needed even at setup
+        let committee = f()?;
+        let optimization_goal = cs.optimization_goal();
+        let hints = cfg_iter!(committee.borrow().signers)
+            .map(|signer| SignerVar::<CF, BlsSigConfig, FV>::hint(signer, optimization_goal))
+            .collect::<Result<Vec<_>, _>>()?;
+        let committee_var = hints
+            .iter()
+            .map(|hint| SignerVar::new_variable_with_hint(cs.clone(), Ok(hint), mode))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // the length is not known here, so `BCCircuitNoMerkle` checks it against its `BcConfig`.
+        // Padding here would not help: the state outside the circuit must have the same fixed
+        // size for its hash to match, so the committee is padded natively by `PaddingPolicy`.
 
         Ok(Self {
             committee: committee_var,
@@ -140,7 +251,39 @@ impl<CF: PrimeField> AllocVar<Committee, CF> for CommitteeVar<CF> {
     }
 }
 
-impl<CF: PrimeField> AllocVar<QuorumSignature, CF> for QuorumSignatureVar<CF> {
+impl<
+        CF: PrimeField,
+        SigCurveConfig: Bls12Config,
+        FV: FieldVar<BlsSigField<SigCurveConfig>, CF>,
+    > CommitteeVar<CF, SigCurveConfig, FV>
+where
+    for<'a> &'a FV: FieldOpsBounds<'a, BlsSigField<SigCurveConfig>, FV>,
+{
+    /// The sum of the public keys of the signers selected by `signers`, with complete
+    /// additions, which works for any representation of the coordinates.
+    ///
+    /// `aggregate_selected_var` is cheaper when the coordinates are emulated.
+    pub fn aggregate_public_key(
+        &self,
+        signers: &[Boolean<CF>],
+    ) -> Result<PublicKeyVar<SigCurveConfig, FV, CF>, SynthesisError> {
+        if signers.len() != self.committee.len() {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+
+        let mut pub_key = G1Var::<SigCurveConfig, FV, CF>::zero();
+        for (signer, signed) in self.committee.iter().zip(signers) {
+            pub_key = signed.select(&(pub_key.clone() + &signer.pk.pub_key), &pub_key)?;
+        }
+        Ok(PublicKeyVar { pub_key })
+    }
+}
+
+impl<CF: PrimeField, FV: FieldVar<BlsSigField<BlsSigConfig>, CF>> AllocVar<QuorumSignature, CF>
+    for QuorumSignatureVar<CF, BlsSigConfig, FV>
+where
+    for<'a> &'a FV: FieldOpsBounds<'a, BlsSigField<BlsSigConfig>, FV>,
+{
     fn new_variable<T: std::borrow::Borrow<QuorumSignature>>(
         cs: impl Into<ark_relations::r1cs::Namespace<CF>>,
         f: impl FnOnce() -> Result<T, SynthesisError>,
@@ -155,7 +298,7 @@ impl<CF: PrimeField> AllocVar<QuorumSignature, CF> for QuorumSignatureVar<CF> {
             || {
                 quorum_signature
                     .as_ref()
-                    .map(|qsig| qsig.borrow().sig)
+                    .map(|qsig| &qsig.borrow().sig)
                     .map_err(SynthesisError::clone)
             },
             mode,
@@ -166,36 +309,25 @@ impl<CF: PrimeField> AllocVar<QuorumSignature, CF> for QuorumSignatureVar<CF> {
             || {
                 quorum_signature
                     .as_ref()
-                    .map(|qsig| qsig.borrow().signers.clone())
+                    .map(|qsig| qsig.borrow().signers.as_slice())
                     .map_err(SynthesisError::clone)
             },
             mode,
         )?;
 
-        assert_eq!(
-            signers.len(),
-            MAX_COMMITTEE_SIZE,
-            "signers must have len == MAX_COMMITTEE_SIZE"
-        );
-
-        // needs to fill it to `MAX_COMMITTEE_SIZE` as the number of constraints needed should be fixed,
-        // irrespective of which state it is currently in.
-        // - otherwise nova `preprocess` will fail
-        //
-        // safety: signers.len() <= MAX_COMMITTEE_SIZE
-        // signers.extend(
-        //     std::iter::repeat(Boolean::new_variable(cs, || Ok(false), mode)?)
-        //         .take(MAX_COMMITTEE_SIZE - signers.len()),
-        // );
-        //
-        // Update: It's not correct to extend it here. Rather, we need to enforce all the state outside the circuit has
-        // fixed size. Otherwise, the hash of those states will never match their circuit counterpart.
+        // the length is not known here, so `BCCircuitNoMerkle` checks it against its `BcConfig`,
+        // and the number of constraints must not depend on it (or Nova's `preprocess` fails),
+        // so the bitmap is padded natively by `PaddingPolicy` as for `CommitteeVar`.
 
         Ok(Self { sig, signers })
     }
 }
 
-impl<CF: PrimeField> AllocVar<Block, CF> for BlockVar<CF> {
+impl<CF: PrimeField, FV: AllocHint<BlsSigField<BlsSigConfig>, CF>> AllocVar<Block, CF>
+    for BlockVar<CF, BlsSigConfig, FV>
+where
+    for<'a> &'a FV: FieldOpsBounds<'a, BlsSigField<BlsSigConfig>, FV>,
+{
     fn new_variable<T: std::borrow::Borrow<Block>>(
         cs: impl Into<ark_relations::r1cs::Namespace<CF>>,
         f: impl FnOnce() -> Result<T, ark_relations::r1cs::SynthesisError>,
@@ -232,7 +364,7 @@ impl<CF: PrimeField> AllocVar<Block, CF> for BlockVar<CF> {
             || {
                 block
                     .as_ref()
-                    .map(|block| block.borrow().sig.clone())
+                    .map(|block| &block.borrow().sig)
                     .map_err(SynthesisError::clone)
             },
             mode,
@@ -243,10 +375,7 @@ impl<CF: PrimeField> AllocVar<Block, CF> for BlockVar<CF> {
             || {
                 block
                     .as_ref()
-                    .map(|block| {
-                        let block = block.borrow();
-                        block.committee.clone()
-                    })
+                    .map(|block| &block.borrow().committee)
                     .map_err(SynthesisError::clone)
             },
             mode,
@@ -260,3 +389,155 @@ impl<CF: PrimeField> AllocVar<Block, CF> for BlockVar<CF> {
         })
     }
 }
+
+impl<
+        CF: PrimeField,
+        SigCurveConfig: Bls12Config,
+        FV: FieldVar<BlsSigField<SigCurveConfig>, CF>,
+    > BlockVar<CF, SigCurveConfig, FV>
+where
+    for<'a> &'a FV: FieldOpsBounds<'a, BlsSigField<SigCurveConfig>, FV>,
+{
+    /// The digest of the block, as computed natively by `Block::digest`.
+    pub fn digest(&self) -> Result<[UInt8<CF>; HASH_OUTPUT_SIZE], SynthesisError> {
+        let mut hasher = Blake2sGadget::default();
+        hasher.update(&self.serialize()?)?;
+        Ok(hasher
+            .finalize()?
+            .0
+            .try_into()
+            .expect("blake2s outputs HASH_OUTPUT_SIZE bytes"))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ark_r1cs_std::{alloc::AllocVar, fields::fp::FpVar, uint8::UInt8, R1CSVar};
+    use ark_relations::r1cs::ConstraintSystem;
+    use rand::thread_rng;
+
+    use crate::{
+        bc::testgen::ChainGenerator,
+        bls::{
+            BLSAggregateSignatureVerifyGadget, Parameters, ParametersVar, PublicKey, SecretKey,
+            Signature, SignatureVar,
+        },
+        params::{BlsSigConfig, BlsSigField, EmulatedFieldVar, NativeFieldVar, SelectedFieldVar},
+    };
+
+    use super::{BlockVar, CommitteeVar, QuorumSignatureVar, SignerVar};
+
+    type CF = BlsSigField<BlsSigConfig>;
+
+    #[test]
+    fn test_block_digest() {
+        let (blocks, _) = ChainGenerator::new(2, 4).generate(&mut thread_rng());
+
+        let cs = ConstraintSystem::<CF>::new_ref();
+        let block: BlockVar<CF> = BlockVar::new_constant(cs, &blocks[0]).unwrap();
+        let digest = block.digest().unwrap().value().unwrap();
+        assert_eq!(digest, blocks[0].digest());
+        assert_eq!(digest, blocks[1].prev_digest);
+    }
+
+    /// `CF` is the base field of the signature curve, so the keys can be native.
+    #[test]
+    fn test_block_native_field_var() {
+        type Native = SelectedFieldVar<NativeFieldVar, BlsSigField<BlsSigConfig>, CF>;
+        type Emulated = SelectedFieldVar<EmulatedFieldVar, BlsSigField<BlsSigConfig>, CF>;
+
+        let (blocks, _) = ChainGenerator::new(2, 4).generate(&mut thread_rng());
+
+        let cs = ConstraintSystem::<CF>::new_ref();
+        let block =
+            BlockVar::<CF, BlsSigConfig, Native>::new_input(cs.clone(), || Ok(&blocks[0])).unwrap();
+        assert_eq!(block.digest().unwrap().value().unwrap(), blocks[0].digest());
+        assert!(cs.is_satisfied().unwrap());
+
+        let emulated_cs = ConstraintSystem::<CF>::new_ref();
+        let block = BlockVar::<CF, BlsSigConfig, Emulated>::new_input(emulated_cs.clone(), || {
+            Ok(&blocks[0])
+        })
+        .unwrap();
+        assert_eq!(block.digest().unwrap().value().unwrap(), blocks[0].digest());
+        assert!(cs.num_constraints() < emulated_cs.num_constraints());
+    }
+
+    /// Allocating the committee from the hints of its signers assigns the same variables as
+    /// allocating the signers one by one.
+    #[test]
+    fn test_committee_hints() {
+        let (blocks, _) = ChainGenerator::new(1, 4).generate(&mut thread_rng());
+        let committee = &blocks[0].committee;
+
+        let cs = ConstraintSystem::<CF>::new_ref();
+        Vec::<SignerVar<CF>>::new_witness(cs.clone(), || Ok(&committee.signers[..])).unwrap();
+        let hinted_cs = ConstraintSystem::<CF>::new_ref();
+        let committee_var =
+            CommitteeVar::<CF>::new_witness(hinted_cs.clone(), || Ok(committee)).unwrap();
+
+        for (signer, (pk, weight)) in committee_var.committee.iter().zip(&committee.signers) {
+            assert_eq!(signer.pk.pub_key.value().unwrap(), pk.pub_key);
+            assert_eq!(signer.weight.value().unwrap(), *weight);
+        }
+        assert!(hinted_cs.is_satisfied().unwrap());
+        assert_eq!(cs.num_constraints(), hinted_cs.num_constraints());
+        assert_eq!(
+            cs.borrow().unwrap().witness_assignment,
+            hinted_cs.borrow().unwrap().witness_assignment
+        );
+    }
+
+    /// The keys of BLS12-377 as `FpVar`s of its base field, the scalar field of BW6-761.
+    #[test]
+    fn test_bls12_377_quorum() {
+        type Config = ark_bls12_377::Config;
+        type Fq = ark_bls12_377::Fq;
+
+        let mut rng = thread_rng();
+        let params = Parameters::<Config>::setup();
+        let secret_keys: Vec<SecretKey<Config>> =
+            (0..3).map(|_| SecretKey::new(&mut rng)).collect();
+        let signed = vec![true, false, true];
+        let msg = b"block";
+        let sig = Signature::aggregate_sign(
+            msg,
+            &secret_keys
+                .iter()
+                .zip(&signed)
+                .filter(|(_, signed)| **signed)
+                .map(|(sk, _)| sk.clone())
+                .collect::<Vec<_>>(),
+            &params,
+        )
+        .unwrap();
+
+        let cs = ConstraintSystem::<Fq>::new_ref();
+        let committee = CommitteeVar::<Fq, Config, FpVar<Fq>> {
+            committee: secret_keys
+                .iter()
+                .map(|sk| {
+                    SignerVar::new_witness(cs.clone(), || Ok((PublicKey::new(sk, &params), 1)))
+                })
+                .collect::<Result<_, _>>()
+                .unwrap(),
+        };
+        let quorum = QuorumSignatureVar::<Fq, Config, FpVar<Fq>> {
+            sig: SignatureVar::new_witness(cs.clone(), || Ok(sig)).unwrap(),
+            signers: Vec::new_witness(cs.clone(), || Ok(signed)).unwrap(),
+        };
+
+        let params_var = ParametersVar::new_constant(cs.clone(), params).unwrap();
+        let pk = committee.aggregate_public_key(&quorum.signers).unwrap();
+        BLSAggregateSignatureVerifyGadget::verify(
+            &params_var,
+            &pk,
+            &UInt8::constant_vec(msg),
+            &quorum.sig,
+        )
+        .unwrap();
+        assert!(cs.is_satisfied().unwrap());
+
+        assert!(committee.aggregate_public_key(&[]).is_err());
+    }
+}
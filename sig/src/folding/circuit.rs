@@ -1,143 +1,1271 @@
 use std::{cmp::Ordering, marker::PhantomData};
 
+use ark_crypto_primitives::sponge::{poseidon::PoseidonConfig, Absorb};
 use ark_ff::PrimeField;
 use ark_r1cs_std::{
     alloc::AllocVar,
-    convert::ToConstraintFieldGadget,
     eq::EqGadget,
-    fields::{emulated_fp::EmulatedFpVar, fp::FpVar, FieldVar},
-    groups::{bls12::G1Var, CurveVar},
+    fields::{fp::FpVar, FieldVar},
     prelude::Boolean,
     uint64::UInt64,
+    uint8::UInt8,
+    R1CSVar,
 };
-use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
+use ark_relations::r1cs::{ConstraintSystem, ConstraintSystemRef, SynthesisError};
+use derivative::Derivative;
 use folding_schemes::{frontend::FCircuit, Error};
 
 use crate::{
     bc::{
-        block::{Block, QuorumSignature},
-        params::STRONG_THRESHOLD,
+        block::{Block, Committee, QuorumSignature},
+        checkpoint::Checkpoint,
+        params::{BcConfig, HandoffRule},
+        sync::SyncUpdate,
     },
-    bls::{BLSAggregateSignatureVerifyGadget, Parameters, ParametersVar, PublicKeyVar},
-    folding::bc::{CommitteeVar, QuorumSignatureVar},
-    params::BlsSigConfig,
+    bls::{
+        aggregate_selected_var, BLSAggregateSignatureVerifyGadget, Parameters, ParametersVar,
+        PublicKeyVar,
+    },
+    folding::bc::{CommitteeVar, QuorumSignatureVar, SignerVar},
+    hash::poseidon::poseidon_config,
+    params::{BlsSigConfig, BlsSigFieldVar, CurveCycleConfig, CycleField},
+    snark::witness_only_cs,
 };
 
 use super::{
-    bc::BlockVar, from_constraint_field::FromConstraintFieldGadget, serialize::SerializeGadget,
+    bc::BlockVar,
+    commitment::{committee_commitment, CommittedBlock, CommittedBlockVar},
+    input::StepInputVar,
+    merkle::{tree_depth, CommitteeTree, MerkleBlock, MerkleBlockVar},
+    serialize::SerializeGadget,
+    state::StateSerde,
+    sync::SyncUpdateVar,
 };
 
+/// The state of `BCCircuitNoMerkle`: the committee followed by the epoch.
+type CommitteeState<CF> = (CommitteeVar<CF>, UInt64<CF>);
+
+/// The state of the circuits that only carry a digest of the committee, e.g. a Merkle root,
+/// followed by the epoch.
+type DigestState<CF> = (FpVar<CF>, UInt64<CF>);
+
 #[derive(Clone, Copy, Debug)]
-pub struct BCCircuitNoMerkle<CF: PrimeField> {
+pub struct BCCircuitParams {
+    pub sig_params: Parameters<BlsSigConfig>,
+    pub config: BcConfig,
+}
+
+impl BCCircuitParams {
+    /// Standard signature parameters with the default `BcConfig`.
+    #[must_use]
+    pub fn setup() -> Self {
+        Self {
+            sig_params: Parameters::setup(),
+            config: BcConfig::default(),
+        }
+    }
+}
+
+/// Enforce `next = epoch + 1` without wrapping around: the addition is done in `CF`, where
+/// both sides are below `2^64 + 1`, so `next` cannot be 0 when `epoch` is `u64::MAX`.
+fn enforce_next_epoch<CF: PrimeField>(
+    epoch: &UInt64<CF>,
+    next: &UInt64<CF>,
+) -> Result<(), SynthesisError> {
+    next.to_fp()?
+        .enforce_equal(&(epoch.to_fp()? + FpVar::one()))
+}
+
+/// Enforce that `block` is the block after `epoch`, signed by a quorum of the committee
+/// `config.handoff` designates: `previous`, the committee of the state, or the committee
+/// of `block`. Members of that committee that did not sign are ignored.
+///
+/// This is the hand-off: the signature covers the committee of `block`, which callers
+/// must make the committee of the new state.
+fn enforce_next_block<CF: PrimeField>(
+    cs: ConstraintSystemRef<CF>,
+    params: &ParametersVar<BlsSigConfig, BlsSigFieldVar<CF>, CF>,
+    config: &BcConfig,
+    epoch: &UInt64<CF>,
+    previous: &[SignerVar<CF>],
+    block: &BlockVar<CF>,
+) -> Result<(), SynthesisError> {
+    tracing::info!(num_constraints = cs.num_constraints());
+
+    // 1. enforce epoch of new committee = epoch of old committee + 1
+    tracing::info!("start enforcing epoch of new committee = epoch of old committee + 1");
+
+    enforce_next_epoch(epoch, &block.epoch)?;
+
+    tracing::info!(num_constraints = cs.num_constraints());
+
+    // 2. enforce the signature matches
+    tracing::info!("start enforcing signature matches");
+    let committee = match config.handoff {
+        HandoffRule::PreviousSignsNext => previous,
+        HandoffRule::NextSignsSelf => &block.committee.committee,
+    };
+
+    let mut block_without_sig = block.clone();
+    block_without_sig.sig = QuorumSignatureVar::new_constant(
+        cs.clone(),
+        QuorumSignature::empty(config.max_committee_size),
+    )?;
+    enforce_quorum(
+        cs,
+        params,
+        config,
+        committee,
+        &block.sig,
+        &block_without_sig.serialize()?,
+    )
+}
+
+/// Enforce that `sig` is a signature on `message` by signers holding the strong threshold
+/// of the weight of `committee`.
+fn enforce_quorum<CF: PrimeField>(
+    cs: ConstraintSystemRef<CF>,
+    params: &ParametersVar<BlsSigConfig, BlsSigFieldVar<CF>, CF>,
+    config: &BcConfig,
+    committee: &[SignerVar<CF>],
+    sig: &QuorumSignatureVar<CF>,
+    message: &[UInt8<CF>],
+) -> Result<(), SynthesisError> {
+    // 1. aggregate public keys
+    tracing::info!("start aggregating public keys");
+
+    let public_keys: Vec<_> = committee
+        .iter()
+        .map(|signer| signer.pk.pub_key.clone())
+        .collect();
+    let aggregate_pk = PublicKeyVar {
+        pub_key: aggregate_selected_var(&public_keys, &sig.signers)?,
+    };
+
+    tracing::info!(num_constraints = cs.num_constraints());
+
+    // 2. check signature
+    tracing::info!("start checking signatures");
+
+    BLSAggregateSignatureVerifyGadget::verify(params, &aggregate_pk, message, &sig.sig)?;
+
+    tracing::info!(num_constraints = cs.num_constraints());
+
+    // 3. check weight >= threshold
+    tracing::info!("start checking weight >= threshold");
+
+    enforce_weight_threshold(committee, &sig.signers, config.strong_threshold)?;
+
+    tracing::info!(num_constraints = cs.num_constraints());
+
+    Ok(())
+}
+
+#[derive(Derivative)]
+#[derivative(Clone(bound = ""), Copy(bound = ""), Debug(bound = ""))]
+pub struct BCCircuitNoMerkle<C: CurveCycleConfig> {
     params: Parameters<BlsSigConfig>,
-    _cf: PhantomData<CF>,
+    config: BcConfig,
+    _cycle: PhantomData<fn() -> C>,
+}
+
+impl<C: CurveCycleConfig> BCCircuitNoMerkle<C> {
+    /// The initial folding state `z_0` of a light client trusting `checkpoint`, given the
+    /// committee it commits to: the committee followed by the epoch.
+    pub fn initial_state(
+        &self,
+        checkpoint: &Checkpoint,
+        committee: &Committee,
+    ) -> Result<Vec<CycleField<C>>, SynthesisError> {
+        if committee.signers.len() != self.config.max_committee_size
+            || !checkpoint.matches(committee)
+        {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+
+        let cs = ConstraintSystem::new_ref();
+        let z_0 = (
+            CommitteeVar::new_constant(cs, committee)?,
+            UInt64::constant(checkpoint.epoch),
+        )
+            .to_state()?;
+        z_0.iter().map(R1CSVar::value).collect()
+    }
+
+    /// The state after `block` from the state `z_i`, computed by the step circuit in
+    /// `witness_only_cs`: the witness generation of a folding step, without the cost of
+    /// building its constraints.
+    pub fn generate_witness_only(
+        &self,
+        z_i: &[CycleField<C>],
+        block: &Block,
+    ) -> Result<Vec<CycleField<C>>, SynthesisError> {
+        let cs = witness_only_cs();
+        let z_i = Vec::new_witness(cs.clone(), || Ok(z_i))?;
+        let block = StepInputVar::new_witness(cs.clone(), || Ok(Some(block.clone())))?;
+        self.generate_step_constraints(cs, 0, z_i, block)?
+            .iter()
+            .map(R1CSVar::value)
+            .collect()
+    }
 }
 
-impl<CF: PrimeField> FCircuit<CF> for BCCircuitNoMerkle<CF> {
-    type Params = Parameters<BlsSigConfig>;
-    type ExternalInputs = Block;
-    type ExternalInputsVar = BlockVar<CF>;
+/// The external input of a step is the next block, or `None` for a placeholder (see
+/// `folding::input`).
+impl<C: CurveCycleConfig> FCircuit<CycleField<C>> for BCCircuitNoMerkle<C> {
+    type Params = BCCircuitParams;
+    type ExternalInputs = Option<Block>;
+    type ExternalInputsVar = StepInputVar<CycleField<C>, Block>;
 
     fn new(params: Self::Params) -> Result<Self, Error> {
         Ok(Self {
-            params,
-            _cf: PhantomData,
+            params: params.sig_params,
+            config: params.config,
+            _cycle: PhantomData,
         })
     }
 
     fn state_len(&self) -> usize {
-        CommitteeVar::<CF>::num_constraint_var_needed() + 1
+        CommitteeState::<CycleField<C>>::state_len((self.config, ()))
     }
 
     /// generates the constraints for the step of F for the given z_i
     #[tracing::instrument(skip_all)]
     fn generate_step_constraints(
         &self,
-        cs: ConstraintSystemRef<CF>,
+        cs: ConstraintSystemRef<CycleField<C>>,
         _: usize,
-        z_i: Vec<FpVar<CF>>,
+        z_i: Vec<FpVar<CycleField<C>>>,
         external_inputs: Self::ExternalInputsVar,
-    ) -> Result<Vec<FpVar<CF>>, SynthesisError> {
+    ) -> Result<Vec<FpVar<CycleField<C>>>, SynthesisError> {
         tracing::info!("start reconstructing committee and epoch");
 
         // reconstruct epoch and committee from z_i
-        let mut iter = z_i.into_iter();
-        let committee = CommitteeVar::from_constraint_field(iter.by_ref())?;
-        let epoch = UInt64::from_constraint_field(iter.by_ref())?;
+        let (committee, epoch) = CommitteeState::from_state(z_i.into_iter(), (self.config, ()))?;
+        let block: BlockVar<CycleField<C>> =
+            external_inputs.allocate(|| Block::placeholder(&self.config))?;
 
-        tracing::info!(num_constraints = cs.num_constraints());
+        // the block must be padded as the committee in the state, so that the number of
+        // constraints does not depend on the block
+        if block.sig.signers.len() != self.config.max_committee_size
+            || block.committee.committee.len() != self.config.max_committee_size
+        {
+            return Err(SynthesisError::Unsatisfiable);
+        }
 
-        // 1. enforce epoch of new committee = epoch of old committee + 1
-        tracing::info!("start enforcing epoch of new committee = epoch of old committee + 1");
+        enforce_next_block(
+            cs.clone(),
+            &ParametersVar::new_constant(cs.clone(), self.params)?,
+            &self.config,
+            &epoch,
+            &committee.committee,
+            &block,
+        )?;
+
+        // 3. return the new state
+        tracing::info!("start returning the new state");
 
-        external_inputs
-            .epoch
-            .is_eq(&(epoch.wrapping_add(&UInt64::constant(1))))?
-            .enforce_equal(&Boolean::TRUE)?;
+        let state = (block.committee, block.epoch).to_state()?;
 
         tracing::info!(num_constraints = cs.num_constraints());
 
-        // 2. enforce the signature matches
-        tracing::info!("start enforcing signature matches");
-        let sig = &external_inputs.sig.sig;
-        let signers = &external_inputs.sig.signers;
-
-        // 2.1 aggregate public keys
-        tracing::info!("start aggregating public keys");
-
-        let mut weight = UInt64::constant(0);
-        let mut aggregate_pk = G1Var::<BlsSigConfig, EmulatedFpVar<_, CF>, CF>::zero();
-        for (signed, signer) in signers.iter().zip(committee.committee) {
-            let pk = signed.select(
-                &(signer.pk.pub_key),
-                &G1Var::<BlsSigConfig, EmulatedFpVar<_, CF>, CF>::zero(),
+        Ok(state)
+    }
+}
+
+/// A variant of `BCCircuitNoMerkle` that validates `K` consecutive blocks per folding step,
+/// so that the committee is reconstructed from the state once every `K` blocks.
+///
+/// Besides the checks of `BCCircuitNoMerkle`, each block must point to the digest of the
+/// previous block of the step.
+#[derive(Derivative)]
+#[derivative(Clone(bound = ""), Copy(bound = ""), Debug(bound = ""))]
+pub struct BCCircuitBatched<C: CurveCycleConfig, const K: usize> {
+    inner: BCCircuitNoMerkle<C>,
+}
+
+impl<C: CurveCycleConfig, const K: usize> BCCircuitBatched<C, K> {
+    /// The same initial state as `BCCircuitNoMerkle::initial_state`.
+    pub fn initial_state(
+        &self,
+        checkpoint: &Checkpoint,
+        committee: &Committee,
+    ) -> Result<Vec<CycleField<C>>, SynthesisError> {
+        self.inner.initial_state(checkpoint, committee)
+    }
+}
+
+impl<C: CurveCycleConfig, const K: usize> FCircuit<CycleField<C>> for BCCircuitBatched<C, K> {
+    type Params = BCCircuitParams;
+    type ExternalInputs = Option<[Block; K]>;
+    type ExternalInputsVar = StepInputVar<CycleField<C>, [Block; K]>;
+
+    fn new(params: Self::Params) -> Result<Self, Error> {
+        if K == 0 {
+            return Err(Error::Other("K should > 0".to_string()));
+        }
+
+        Ok(Self {
+            inner: BCCircuitNoMerkle::new(params)?,
+        })
+    }
+
+    fn state_len(&self) -> usize {
+        self.inner.state_len()
+    }
+
+    #[tracing::instrument(skip_all)]
+    fn generate_step_constraints(
+        &self,
+        cs: ConstraintSystemRef<CycleField<C>>,
+        _: usize,
+        z_i: Vec<FpVar<CycleField<C>>>,
+        external_inputs: Self::ExternalInputsVar,
+    ) -> Result<Vec<FpVar<CycleField<C>>>, SynthesisError> {
+        tracing::info!("start reconstructing committee and epoch");
+
+        let config = &self.inner.config;
+        let (mut committee, mut epoch) =
+            CommitteeState::from_state(z_i.into_iter(), (*config, ()))?;
+        let params = ParametersVar::new_constant(cs.clone(), self.inner.params)?;
+        let blocks: [BlockVar<CycleField<C>>; K] =
+            external_inputs.allocate(|| core::array::from_fn(|_| Block::placeholder(config)))?;
+
+        let mut prev_digest = None;
+        for (i, block) in blocks.into_iter().enumerate() {
+            tracing::info!("start enforcing block {i} of the step");
+
+            if block.sig.signers.len() != config.max_committee_size
+                || block.committee.committee.len() != config.max_committee_size
+            {
+                return Err(SynthesisError::Unsatisfiable);
+            }
+
+            // the first block is chained to the state by its epoch only, as in `BCCircuitNoMerkle`
+            if let Some(prev_digest) = &prev_digest {
+                block.prev_digest.enforce_equal(prev_digest)?;
+            }
+
+            enforce_next_block(
+                cs.clone(),
+                &params,
+                config,
+                &epoch,
+                &committee.committee,
+                &block,
             )?;
-            let w = signed.select(&(signer.weight), &UInt64::constant(0))?;
-            aggregate_pk += pk;
-            weight.wrapping_add_in_place(&w);
+
+            // the digest of the last block is not needed
+            if i + 1 < K {
+                prev_digest = Some(block.digest()?);
+            }
+            epoch = block.epoch;
+            committee = block.committee;
         }
-        let aggregate_pk = PublicKeyVar {
-            pub_key: aggregate_pk,
+
+        // return the new state
+        tracing::info!("start returning the new state");
+
+        let state = (committee, epoch).to_state()?;
+
+        tracing::info!(num_constraints = cs.num_constraints());
+
+        Ok(state)
+    }
+}
+
+/// Enforce that the members of `committee` selected by `signed` hold at least `threshold`
+/// of its weight.
+fn enforce_weight_threshold<CF: PrimeField>(
+    committee: &[SignerVar<CF>],
+    signed: &[Boolean<CF>],
+    threshold: u64,
+) -> Result<(), SynthesisError> {
+    // the weights are summed in `CF`, where the sum of `committee.len()` weights cannot wrap
+    // around, unlike the sum of `UInt64`s
+    let mut weight = FpVar::zero();
+    for (signed, signer) in signed.iter().zip(committee) {
+        weight += signer.weight.to_fp()? * FpVar::from(signed.clone());
+    }
+
+    // each weight is below `2^64`, so the sum has at most `64 + bit length of len` bits
+    let num_bits = 64 + (usize::BITS - committee.len().leading_zeros()) as usize;
+    let (weight, _) = weight.to_bits_le_with_top_bits_zero(num_bits)?;
+    is_ge_constant(&weight, threshold)?.enforce_equal(&Boolean::TRUE)
+}
+
+/// Whether the unsigned integer with little-endian `bits` is at least `constant`, which
+/// takes at most one constraint per bit instead of the comparison of two field elements of
+/// `enforce_cmp`. `bits` can be longer than 64, e.g. for the sum of several `UInt64`s.
+fn is_ge_constant<CF: PrimeField>(
+    bits: &[Boolean<CF>],
+    constant: u64,
+) -> Result<Boolean<CF>, SynthesisError> {
+    // from the least significant bit, whether the bits so far are at least those of
+    // `constant`: a differing bit decides, an equal one keeps the lower bits' answer
+    let mut ge = Boolean::TRUE;
+    for (i, bit) in bits.iter().enumerate() {
+        ge = if i < 64 && (constant >> i) & 1 == 1 {
+            bit & &ge
+        } else {
+            bit | &ge
         };
+    }
+
+    // `constant` has bits past the end of `bits`
+    if bits.len() < 64 && constant >> bits.len() != 0 {
+        return Ok(Boolean::FALSE);
+    }
+    Ok(ge)
+}
+
+/// Enforce `epoch < next <= epoch + max_gap`. As in `enforce_next_epoch`, the difference
+/// is computed in `CF`, where it is above `(p - 1) / 2` if `next < epoch`.
+fn enforce_epoch_gap<CF: PrimeField>(
+    epoch: &UInt64<CF>,
+    next: &UInt64<CF>,
+    max_gap: u64,
+) -> Result<(), SynthesisError> {
+    let gap = next.to_fp()? - epoch.to_fp()?;
+    gap.enforce_cmp(&FpVar::zero(), Ordering::Greater, false)?;
+    gap.enforce_cmp(&FpVar::constant(max_gap.into()), Ordering::Less, true)
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct BCCircuitSkippingParams {
+    pub params: BCCircuitParams,
+    /// The most epochs a sync update can skip.
+    pub max_gap: u64,
+}
+
+/// A variant of `BCCircuitNoMerkle` that folds sync updates (see `bc::sync`) instead of
+/// blocks, so the epoch advances by up to `max_gap` per step.
+///
+/// The state is the same as `BCCircuitNoMerkle`. Each update is signed by the committee of
+/// the state, and its committee becomes the committee of the new state.
+#[derive(Derivative)]
+#[derivative(Clone(bound = ""), Copy(bound = ""), Debug(bound = ""))]
+pub struct BCCircuitSkipping<C: CurveCycleConfig> {
+    inner: BCCircuitNoMerkle<C>,
+    max_gap: u64,
+}
+
+impl<C: CurveCycleConfig> BCCircuitSkipping<C> {
+    /// The same initial state as `BCCircuitNoMerkle::initial_state`.
+    pub fn initial_state(
+        &self,
+        checkpoint: &Checkpoint,
+        committee: &Committee,
+    ) -> Result<Vec<CycleField<C>>, SynthesisError> {
+        self.inner.initial_state(checkpoint, committee)
+    }
+}
+
+impl<C: CurveCycleConfig> FCircuit<CycleField<C>> for BCCircuitSkipping<C> {
+    type Params = BCCircuitSkippingParams;
+    type ExternalInputs = Option<SyncUpdate>;
+    type ExternalInputsVar = StepInputVar<CycleField<C>, SyncUpdate>;
+
+    fn new(params: Self::Params) -> Result<Self, Error> {
+        if params.max_gap == 0 {
+            return Err(Error::Other("max_gap should > 0".to_string()));
+        }
+
+        Ok(Self {
+            inner: BCCircuitNoMerkle::new(params.params)?,
+            max_gap: params.max_gap,
+        })
+    }
+
+    fn state_len(&self) -> usize {
+        self.inner.state_len()
+    }
+
+    #[tracing::instrument(skip_all)]
+    fn generate_step_constraints(
+        &self,
+        cs: ConstraintSystemRef<CycleField<C>>,
+        _: usize,
+        z_i: Vec<FpVar<CycleField<C>>>,
+        external_inputs: Self::ExternalInputsVar,
+    ) -> Result<Vec<FpVar<CycleField<C>>>, SynthesisError> {
+        tracing::info!("start reconstructing committee and epoch");
+
+        let config = &self.inner.config;
+        let (committee, epoch) = CommitteeState::from_state(z_i.into_iter(), (*config, ()))?;
+
+        let update: SyncUpdateVar<CycleField<C>> =
+            external_inputs.allocate(|| SyncUpdate::placeholder(config))?;
+        if update.sig.signers.len() != config.max_committee_size
+            || update.committee.committee.len() != config.max_committee_size
+        {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+
+        // 1. enforce the epoch skips ahead by at most `max_gap`
+        tracing::info!("start enforcing epoch gap");
+
+        enforce_epoch_gap(&epoch, &update.epoch, self.max_gap)?;
 
         tracing::info!(num_constraints = cs.num_constraints());
 
-        // 2.2 check signature
-        tracing::info!("start checking signatures");
-
-        let params = ParametersVar::new_constant(cs.clone(), self.params)?;
-        let mut external_inputs_without_sig = external_inputs.clone();
-        external_inputs_without_sig.sig =
-            QuorumSignatureVar::new_constant(cs.clone(), QuorumSignature::default())?;
-        BLSAggregateSignatureVerifyGadget::verify(
-            &params,
-            &aggregate_pk,
-            &external_inputs_without_sig.serialize()?,
-            sig,
+        // 2. enforce the update is signed by the committee of the state
+        tracing::info!("start enforcing signature matches");
+
+        enforce_quorum(
+            cs.clone(),
+            &ParametersVar::new_constant(cs.clone(), self.inner.params)?,
+            config,
+            &committee.committee,
+            &update.sig,
+            &update.message()?,
         )?;
 
+        // 3. return the new state
+        tracing::info!("start returning the new state");
+
+        let state = (update.committee, update.epoch).to_state()?;
+
         tracing::info!(num_constraints = cs.num_constraints());
 
-        // 2.3 check weight > threshold
-        tracing::info!("start checking weight > threshold");
+        Ok(state)
+    }
+}
+
+/// A variant of `BCCircuitNoMerkle` whose state only carries the Merkle root of the
+/// committee (see `folding::merkle`) followed by the epoch.
+///
+/// The external inputs prove the signers of each block against the root, so the state has
+/// a constant size and Nova hashes two field elements per step instead of the whole
+/// committee.
+#[derive(Derivative)]
+#[derivative(Clone(bound = ""), Debug(bound = ""))]
+pub struct BCCircuitMerkle<C: CurveCycleConfig> {
+    params: Parameters<BlsSigConfig>,
+    config: BcConfig,
+    poseidon: PoseidonConfig<CycleField<C>>,
+}
+
+impl<C: CurveCycleConfig> BCCircuitMerkle<C> {
+    /// The Poseidon parameters of the committee tree.
+    #[must_use]
+    pub const fn poseidon_config(&self) -> &PoseidonConfig<CycleField<C>> {
+        &self.poseidon
+    }
+
+    /// The initial folding state `z_0` of a light client trusting `checkpoint`, given the
+    /// committee it commits to: the root of the committee followed by the epoch.
+    pub fn initial_state(
+        &self,
+        checkpoint: &Checkpoint,
+        committee: &Committee,
+    ) -> Result<Vec<CycleField<C>>, SynthesisError> {
+        if committee.signers.len() != self.config.max_committee_size
+            || !checkpoint.matches(committee)
+        {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+
+        let root = CommitteeTree::new(committee, &self.poseidon)?.root();
+        Ok(vec![root, CycleField::<C>::from(checkpoint.epoch)])
+    }
+
+    /// The external inputs of the step proving `block`, signed by `committee`, the
+    /// committee of the previous block.
+    pub fn external_inputs(
+        &self,
+        block: Block,
+        committee: &Committee,
+    ) -> Result<MerkleBlock<CycleField<C>>, SynthesisError> {
+        MerkleBlock::new(block, committee, &self.poseidon)
+    }
+}
 
-        weight.to_fp()?.enforce_cmp(
-            &FpVar::constant(STRONG_THRESHOLD.into()),
-            Ordering::Greater,
-            true,
+impl<C: CurveCycleConfig> FCircuit<CycleField<C>> for BCCircuitMerkle<C> {
+    type Params = BCCircuitParams;
+    type ExternalInputs = Option<MerkleBlock<CycleField<C>>>;
+    type ExternalInputsVar = StepInputVar<CycleField<C>, MerkleBlock<CycleField<C>>>;
+
+    fn new(params: Self::Params) -> Result<Self, Error> {
+        // the external inputs carry the previous committee, which would not sign
+        if params.config.handoff != HandoffRule::PreviousSignsNext {
+            return Err(Error::Other(
+                "only the previous committee can sign the next one".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            params: params.sig_params,
+            config: params.config,
+            poseidon: poseidon_config(),
+        })
+    }
+
+    fn state_len(&self) -> usize {
+        DigestState::<CycleField<C>>::state_len(((), ()))
+    }
+
+    #[tracing::instrument(skip_all)]
+    fn generate_step_constraints(
+        &self,
+        cs: ConstraintSystemRef<CycleField<C>>,
+        _: usize,
+        z_i: Vec<FpVar<CycleField<C>>>,
+        external_inputs: Self::ExternalInputsVar,
+    ) -> Result<Vec<FpVar<CycleField<C>>>, SynthesisError> {
+        tracing::info!("start reconstructing committee root and epoch");
+
+        let (root, epoch) = DigestState::from_state(z_i.into_iter(), ((), ()))?;
+
+        let MerkleBlockVar { block, signers } =
+            external_inputs.allocate(|| MerkleBlock::placeholder(&self.config))?;
+        let depth = tree_depth(self.config.max_committee_size);
+        if block.sig.signers.len() != self.config.max_committee_size
+            || block.committee.committee.len() != self.config.max_committee_size
+            || signers.len() != self.config.max_committee_size
+            || signers.iter().any(|(_, path)| path.siblings.len() != depth)
+        {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+
+        // 1. enforce the signers are members of the committee
+        tracing::info!("start enforcing signers are members of the committee");
+
+        for (i, (signed, (signer, path))) in block.sig.signers.iter().zip(&signers).enumerate() {
+            path.verify(cs.clone(), &self.poseidon, &root, i, signer)?
+                .conditional_enforce_equal(&Boolean::TRUE, signed)?;
+        }
+
+        tracing::info!(num_constraints = cs.num_constraints());
+
+        // 2. enforce the block is signed by a quorum of them
+        let committee: Vec<_> = signers.into_iter().map(|(signer, _)| signer).collect();
+        enforce_next_block(
+            cs.clone(),
+            &ParametersVar::new_constant(cs.clone(), self.params)?,
+            &self.config,
+            &epoch,
+            &committee,
+            &block,
         )?;
 
+        // 3. return the new state
+        tracing::info!("start returning the new state");
+
+        let root = block.committee.merkle_root(cs.clone(), &self.poseidon)?;
+        let state = (root, block.epoch).to_state()?;
+
         tracing::info!(num_constraints = cs.num_constraints());
 
+        Ok(state)
+    }
+}
+
+/// A variant of `BCCircuitNoMerkle` whose state only carries a Poseidon commitment to the
+/// committee (see `folding::commitment`) followed by the epoch.
+///
+/// The committee signing each block is passed again as an external input, which the circuit
+/// hashes and compares with the commitment.
+#[derive(Derivative)]
+#[derivative(Clone(bound = ""), Debug(bound = ""))]
+pub struct BCCircuitPoseidon<C: CurveCycleConfig> {
+    params: Parameters<BlsSigConfig>,
+    config: BcConfig,
+    poseidon: PoseidonConfig<CycleField<C>>,
+}
+
+impl<C: CurveCycleConfig> BCCircuitPoseidon<C>
+where
+    CycleField<C>: Absorb,
+{
+    /// The initial folding state `z_0` of a light client trusting `checkpoint`, given the
+    /// committee it commits to: the commitment to the committee followed by the epoch.
+    pub fn initial_state(
+        &self,
+        checkpoint: &Checkpoint,
+        committee: &Committee,
+    ) -> Result<Vec<CycleField<C>>, SynthesisError> {
+        if committee.signers.len() != self.config.max_committee_size
+            || !checkpoint.matches(committee)
+        {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+
+        Ok(vec![
+            committee_commitment(committee, &self.poseidon)?,
+            CycleField::<C>::from(checkpoint.epoch),
+        ])
+    }
+}
+
+impl<C: CurveCycleConfig> FCircuit<CycleField<C>> for BCCircuitPoseidon<C> {
+    type Params = BCCircuitParams;
+    type ExternalInputs = Option<CommittedBlock>;
+    type ExternalInputsVar = StepInputVar<CycleField<C>, CommittedBlock>;
+
+    fn new(params: Self::Params) -> Result<Self, Error> {
+        // the external inputs carry the previous committee, which would not sign
+        if params.config.handoff != HandoffRule::PreviousSignsNext {
+            return Err(Error::Other(
+                "only the previous committee can sign the next one".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            params: params.sig_params,
+            config: params.config,
+            poseidon: poseidon_config(),
+        })
+    }
+
+    fn state_len(&self) -> usize {
+        DigestState::<CycleField<C>>::state_len(((), ()))
+    }
+
+    #[tracing::instrument(skip_all)]
+    fn generate_step_constraints(
+        &self,
+        cs: ConstraintSystemRef<CycleField<C>>,
+        _: usize,
+        z_i: Vec<FpVar<CycleField<C>>>,
+        external_inputs: Self::ExternalInputsVar,
+    ) -> Result<Vec<FpVar<CycleField<C>>>, SynthesisError> {
+        tracing::info!("start reconstructing committee commitment and epoch");
+
+        let (commitment, epoch) = DigestState::from_state(z_i.into_iter(), ((), ()))?;
+
+        let CommittedBlockVar { block, committee } =
+            external_inputs.allocate(|| CommittedBlock::placeholder(&self.config))?;
+        if block.sig.signers.len() != self.config.max_committee_size
+            || block.committee.committee.len() != self.config.max_committee_size
+            || committee.committee.len() != self.config.max_committee_size
+        {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+
+        // 1. enforce the hint is the committee in the state
+        tracing::info!("start enforcing hint matches the committee commitment");
+
+        committee
+            .commitment(cs.clone(), &self.poseidon)?
+            .enforce_equal(&commitment)?;
+
+        tracing::info!(num_constraints = cs.num_constraints());
+
+        // 2. enforce the block is signed by a quorum of the committee
+        enforce_next_block(
+            cs.clone(),
+            &ParametersVar::new_constant(cs.clone(), self.params)?,
+            &self.config,
+            &epoch,
+            &committee.committee,
+            &block,
+        )?;
+
         // 3. return the new state
         tracing::info!("start returning the new state");
 
-        let mut committee = external_inputs.committee.to_constraint_field()?;
-        let epoch = external_inputs.epoch.to_fp()?;
-        committee.push(epoch);
+        let commitment = block.committee.commitment(cs.clone(), &self.poseidon)?;
+        let state = (commitment, block.epoch).to_state()?;
 
         tracing::info!(num_constraints = cs.num_constraints());
 
-        Ok(committee)
+        Ok(state)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ark_mnt4_753::{Fr, G1Projective as G1, MNT4_753 as MNT4};
+    use ark_mnt6_753::{G1Projective as G2, MNT6_753 as MNT6};
+    use ark_r1cs_std::{
+        alloc::AllocVar, convert::ToBitsGadget, fields::fp::FpVar, prelude::Boolean,
+        uint64::UInt64, uint8::UInt8, R1CSVar,
+    };
+    use ark_relations::r1cs::{ConstraintSystem, ConstraintSystemRef};
+    use folding_schemes::{
+        commitment::kzg::KZG,
+        folding::nova::{Nova, PreprocessorParam},
+        frontend::FCircuit,
+        transcript::poseidon::poseidon_canonical_config,
+        FoldingScheme,
+    };
+    use rand::{thread_rng, Rng};
+
+    use crate::{
+        bc::{
+            block::Block,
+            checkpoint::Checkpoint,
+            params::{BcConfig, HandoffRule},
+            sync::SyncUpdate,
+            testgen::ChainGenerator,
+        },
+        bls::{PublicKey, PublicKeyVar},
+        folding::{bc::SignerVar, commitment::CommittedBlock},
+        params::{BlsSigConfig, BlsSigField},
+    };
+
+    use super::{
+        enforce_epoch_gap, enforce_next_epoch, enforce_weight_threshold, is_ge_constant,
+        BCCircuitBatched, BCCircuitMerkle, BCCircuitNoMerkle, BCCircuitParams, BCCircuitPoseidon,
+        BCCircuitSkipping, BCCircuitSkippingParams,
+    };
+
+    type CF = BlsSigField<BlsSigConfig>;
+
+    type N<FC> = Nova<G1, G2, FC, KZG<'static, MNT4>, KZG<'static, MNT6>, false>;
+
+    /// A config smaller than the default one, whose committees are not the size of
+    /// `Committee::default()`.
+    fn small_params() -> BCCircuitParams {
+        BCCircuitParams {
+            config: BcConfig {
+                max_committee_size: 4,
+                ..Default::default()
+            },
+            ..BCCircuitParams::setup()
+        }
+    }
+
+    /// Preprocess `circuit` for Nova over MNT4/MNT6, fold `inputs` from `z_0` and verify the
+    /// IVC proof. Returns the state reached.
+    fn fold<FC: FCircuit<Fr>>(
+        circuit: FC,
+        z_0: Vec<Fr>,
+        inputs: Vec<FC::ExternalInputs>,
+    ) -> Vec<Fr> {
+        let mut rng = thread_rng();
+        let params = N::<FC>::preprocess(
+            &mut rng,
+            &PreprocessorParam::new(poseidon_canonical_config::<Fr>(), circuit.clone()),
+        )
+        .unwrap();
+
+        let mut nova = N::<FC>::init(&params, circuit, z_0).unwrap();
+        for input in inputs {
+            nova.prove_step(&mut rng, input, None).unwrap();
+        }
+        N::<FC>::verify(params.1, nova.ivc_proof()).unwrap();
+        nova.state()
+    }
+
+    #[test]
+    #[ignore = "folding over MNT4/MNT6 takes a long time to finish running"]
+    fn test_fold_no_merkle() {
+        let params = small_params();
+        let circuit = BCCircuitNoMerkle::<(G1, G2)>::new(params).unwrap();
+        // the committees are padded from 3 to 4 signers
+        let (blocks, _) = ChainGenerator::new(3, 3)
+            .config(params.config)
+            .generate(&mut thread_rng());
+
+        let state = |block: &Block| {
+            circuit
+                .initial_state(&Checkpoint::from_block(block), &block.committee)
+                .unwrap()
+        };
+        let inputs = blocks[1..].iter().cloned().map(Some).collect();
+        assert_eq!(fold(circuit, state(&blocks[0]), inputs), state(&blocks[2]));
+    }
+
+    #[test]
+    #[ignore = "folding over MNT4/MNT6 takes a long time to finish running"]
+    fn test_fold_merkle() {
+        let params = small_params();
+        let circuit = BCCircuitMerkle::<(G1, G2)>::new(params).unwrap();
+        // a tree of depth 2, over committees padded from 3 to 4 signers
+        let (blocks, _) = ChainGenerator::new(3, 3)
+            .config(params.config)
+            .generate(&mut thread_rng());
+
+        let state = |block: &Block| {
+            circuit
+                .initial_state(&Checkpoint::from_block(block), &block.committee)
+                .unwrap()
+        };
+        let (z_0, z_n) = (state(&blocks[0]), state(&blocks[2]));
+        let inputs = blocks
+            .windows(2)
+            .map(|pair| {
+                Some(
+                    circuit
+                        .external_inputs(pair[1].clone(), &pair[0].committee)
+                        .unwrap(),
+                )
+            })
+            .collect();
+        assert_eq!(fold(circuit, z_0, inputs), z_n);
+    }
+
+    /// Synthesize a step of `circuit` from `z_i` with `input`, and return the constraint
+    /// system with the next state.
+    fn step<FC: FCircuit<Fr>>(
+        circuit: &FC,
+        z_i: &[Fr],
+        input: FC::ExternalInputs,
+    ) -> (ConstraintSystemRef<Fr>, Vec<Fr>) {
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let z_i = Vec::<FpVar<Fr>>::new_witness(cs.clone(), || Ok(z_i)).unwrap();
+        let input = FC::ExternalInputsVar::new_witness(cs.clone(), || Ok(input)).unwrap();
+        let z_next = circuit
+            .generate_step_constraints(cs.clone(), 0, z_i, input)
+            .unwrap();
+        (cs, z_next.value().unwrap())
+    }
+
+    #[test]
+    #[ignore = "field emulation takes a long time to finish running"]
+    fn test_poseidon_step() {
+        let params = small_params();
+        let circuit = BCCircuitPoseidon::<(G1, G2)>::new(params).unwrap();
+        let (blocks, _) = ChainGenerator::new(3, 3)
+            .config(params.config)
+            .generate(&mut thread_rng());
+        let state = |block: &Block| {
+            circuit
+                .initial_state(&Checkpoint::from_block(block), &block.committee)
+                .unwrap()
+        };
+        let input = |block: &Block, committee: &Block| {
+            Some(CommittedBlock {
+                block: block.clone(),
+                committee: committee.committee.clone(),
+            })
+        };
+
+        let (cs, z_1) = step(&circuit, &state(&blocks[0]), input(&blocks[1], &blocks[0]));
+        assert!(cs.is_satisfied().unwrap());
+        assert_eq!(z_1, state(&blocks[1]));
+
+        // the placeholder has the shape of any padded input
+        let (placeholder, _) = step(&circuit, &state(&blocks[0]), None);
+        assert_eq!(placeholder.num_constraints(), cs.num_constraints());
+        assert_eq!(
+            placeholder.num_witness_variables(),
+            cs.num_witness_variables()
+        );
+
+        // the hint must be the committee in the state
+        let (cs, _) = step(&circuit, &state(&blocks[0]), input(&blocks[1], &blocks[1]));
+        assert!(!cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    #[ignore = "folding over MNT4/MNT6 takes a long time to finish running"]
+    fn test_fold_poseidon() {
+        let params = small_params();
+        let circuit = BCCircuitPoseidon::<(G1, G2)>::new(params).unwrap();
+        let (blocks, _) = ChainGenerator::new(3, 3)
+            .config(params.config)
+            .generate(&mut thread_rng());
+
+        let state = |block: &Block| {
+            circuit
+                .initial_state(&Checkpoint::from_block(block), &block.committee)
+                .unwrap()
+        };
+        let (z_0, z_n) = (state(&blocks[0]), state(&blocks[2]));
+        let inputs = blocks
+            .windows(2)
+            .map(|pair| {
+                Some(CommittedBlock {
+                    block: pair[1].clone(),
+                    committee: pair[0].committee.clone(),
+                })
+            })
+            .collect();
+        assert_eq!(fold(circuit, z_0, inputs), z_n);
+    }
+
+    #[test]
+    #[ignore = "folding over MNT4/MNT6 takes a long time to finish running"]
+    fn test_fold_batched() {
+        let params = small_params();
+        let circuit = BCCircuitBatched::<(G1, G2), 2>::new(params).unwrap();
+        let (blocks, _) = ChainGenerator::new(5, 3)
+            .config(params.config)
+            .generate(&mut thread_rng());
+
+        let state = |block: &Block| {
+            circuit
+                .initial_state(&Checkpoint::from_block(block), &block.committee)
+                .unwrap()
+        };
+        let (z_0, z_n) = (state(&blocks[0]), state(&blocks[4]));
+        let inputs = blocks[1..]
+            .chunks_exact(2)
+            .map(|pair| Some([pair[0].clone(), pair[1].clone()]))
+            .collect();
+        assert_eq!(fold(circuit, z_0, inputs), z_n);
+    }
+
+    #[test]
+    #[ignore = "field emulation takes a long time to finish running"]
+    fn test_skipping_step() {
+        let params = small_params();
+        let circuit =
+            BCCircuitSkipping::<(G1, G2)>::new(BCCircuitSkippingParams { params, max_gap: 2 })
+                .unwrap();
+        let (blocks, signers) = ChainGenerator::new(4, 3)
+            .config(params.config)
+            .generate(&mut thread_rng());
+        let state = |block: &Block| {
+            circuit
+                .initial_state(&Checkpoint::from_block(block), &block.committee)
+                .unwrap()
+        };
+        // the committee of epoch 0 hands off to the one of `epoch`
+        let update = |epoch: usize| {
+            Some(SyncUpdate::new(
+                epoch as u64,
+                blocks[epoch].committee.clone(),
+                &signers[0],
+                &blocks[1].sig.signers,
+                &params.sig_params,
+            ))
+        };
+
+        let (cs, z_1) = step(&circuit, &state(&blocks[0]), update(2));
+        assert!(cs.is_satisfied().unwrap());
+        assert_eq!(z_1, state(&blocks[2]));
+
+        // the placeholder has the shape of any padded update
+        let (placeholder, _) = step(&circuit, &state(&blocks[0]), None);
+        assert_eq!(placeholder.num_constraints(), cs.num_constraints());
+        assert_eq!(
+            placeholder.num_witness_variables(),
+            cs.num_witness_variables()
+        );
+
+        // more than `max_gap` epochs
+        let (cs, _) = step(&circuit, &state(&blocks[0]), update(3));
+        assert!(!cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    #[ignore = "folding over MNT4/MNT6 takes a long time to finish running"]
+    fn test_fold_skipping() {
+        let params = small_params();
+        let circuit =
+            BCCircuitSkipping::<(G1, G2)>::new(BCCircuitSkippingParams { params, max_gap: 2 })
+                .unwrap();
+        let (blocks, signers) = ChainGenerator::new(5, 3)
+            .config(params.config)
+            .generate(&mut thread_rng());
+        let state = |block: &Block| {
+            circuit
+                .initial_state(&Checkpoint::from_block(block), &block.committee)
+                .unwrap()
+        };
+
+        // skip from epoch 0 to 2, then to 4
+        let inputs = [0, 2]
+            .into_iter()
+            .map(|from| {
+                Some(SyncUpdate::new(
+                    from as u64 + 2,
+                    blocks[from + 2].committee.clone(),
+                    &signers[from],
+                    &blocks[from + 1].sig.signers,
+                    &params.sig_params,
+                ))
+            })
+            .collect();
+        assert_eq!(fold(circuit, state(&blocks[0]), inputs), state(&blocks[4]));
+    }
+
+    #[test]
+    fn test_initial_state() {
+        let circuit = BCCircuitNoMerkle::<(G1, G2)>::new(BCCircuitParams::setup()).unwrap();
+        let (blocks, _) = ChainGenerator::new(2, 4).generate(&mut thread_rng());
+
+        let checkpoint = Checkpoint::from_block(&blocks[1]);
+        let z_0 = circuit
+            .initial_state(&checkpoint, &blocks[1].committee)
+            .unwrap();
+        assert_eq!(z_0.len(), circuit.state_len());
+        assert_eq!(z_0.last(), Some(&Fr::from(1u64)));
+
+        // the committee must be the one committed to by the checkpoint
+        assert!(circuit
+            .initial_state(&checkpoint, &blocks[0].committee)
+            .is_err());
+    }
+
+    #[test]
+    fn test_merkle_initial_state() {
+        let circuit = BCCircuitMerkle::<(G1, G2)>::new(BCCircuitParams::setup()).unwrap();
+        let mut params = BCCircuitParams::setup();
+        params.config.handoff = HandoffRule::NextSignsSelf;
+        assert!(BCCircuitMerkle::<(G1, G2)>::new(params).is_err());
+        let (blocks, _) = ChainGenerator::new(2, 4).generate(&mut thread_rng());
+
+        let z_0 = circuit
+            .initial_state(&Checkpoint::from_block(&blocks[0]), &blocks[0].committee)
+            .unwrap();
+        assert_eq!(z_0.len(), circuit.state_len());
+        assert_eq!(z_0[1], Fr::from(0u64));
+
+        // only the signers of the block are proven, the other slots carry no weight
+        let inputs = circuit
+            .external_inputs(blocks[1].clone(), &blocks[0].committee)
+            .unwrap();
+        assert_eq!(inputs.signers.len(), blocks[0].committee.signers.len());
+        for ((signed, (signer, _)), member) in blocks[1]
+            .sig
+            .signers
+            .iter()
+            .zip(&inputs.signers)
+            .zip(&blocks[0].committee.signers)
+        {
+            if *signed {
+                assert!(signer.0.pub_key == member.0.pub_key && signer.1 == member.1);
+            } else {
+                assert_eq!(signer.1, 0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_poseidon_initial_state() {
+        let circuit = BCCircuitPoseidon::<(G1, G2)>::new(BCCircuitParams::setup()).unwrap();
+        let (blocks, _) = ChainGenerator::new(2, 4).generate(&mut thread_rng());
+
+        let checkpoint = Checkpoint::from_block(&blocks[1]);
+        let z_0 = circuit
+            .initial_state(&checkpoint, &blocks[1].committee)
+            .unwrap();
+        assert_eq!(z_0.len(), circuit.state_len());
+        assert_eq!(z_0[1], Fr::from(1u64));
+        assert!(circuit
+            .initial_state(&checkpoint, &blocks[0].committee)
+            .is_err());
+    }
+
+    #[test]
+    fn test_batched_state() {
+        let params = BCCircuitParams::setup();
+        let circuit = BCCircuitBatched::<(G1, G2), 4>::new(params).unwrap();
+        let no_merkle = BCCircuitNoMerkle::<(G1, G2)>::new(params).unwrap();
+        assert_eq!(circuit.state_len(), no_merkle.state_len());
+        assert!(BCCircuitBatched::<(G1, G2), 0>::new(params).is_err());
+
+        let (blocks, _) = ChainGenerator::new(1, 4).generate(&mut thread_rng());
+        let checkpoint = Checkpoint::from_block(&blocks[0]);
+        assert_eq!(
+            circuit
+                .initial_state(&checkpoint, &blocks[0].committee)
+                .unwrap(),
+            no_merkle
+                .initial_state(&checkpoint, &blocks[0].committee)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_next_epoch() {
+        let cases = [
+            (0, 1, true),
+            (41, 42, true),
+            (1, 1, false),
+            (u64::MAX, 0, false),
+        ];
+        for (epoch, next, satisfied) in cases {
+            let cs = ConstraintSystem::<CF>::new_ref();
+            let epoch = UInt64::new_witness(cs.clone(), || Ok(epoch)).unwrap();
+            let next = UInt64::new_witness(cs.clone(), || Ok(next)).unwrap();
+            enforce_next_epoch(&epoch, &next).unwrap();
+            assert_eq!(cs.is_satisfied().unwrap(), satisfied);
+        }
+    }
+
+    #[test]
+    fn test_epoch_gap() {
+        let params = BCCircuitParams::setup();
+        assert!(
+            BCCircuitSkipping::<(G1, G2)>::new(BCCircuitSkippingParams { params, max_gap: 0 })
+                .is_err()
+        );
+
+        let cases = [
+            (0, 1, true),
+            (10, 14, true),
+            (10, 15, false),
+            (10, 10, false),
+            (10, 9, false),
+            (u64::MAX - 1, u64::MAX, true),
+        ];
+        for (epoch, next, satisfied) in cases {
+            let cs = ConstraintSystem::<CF>::new_ref();
+            let epoch = UInt64::new_witness(cs.clone(), || Ok(epoch)).unwrap();
+            let next = UInt64::new_witness(cs.clone(), || Ok(next)).unwrap();
+            enforce_epoch_gap(&epoch, &next, 4).unwrap();
+            assert_eq!(cs.is_satisfied().unwrap(), satisfied);
+        }
+    }
+
+    #[test]
+    fn test_is_ge_constant() {
+        let cs = ConstraintSystem::<CF>::new_ref();
+
+        // every 5-bit value against constants around it, and past its width
+        for value in 0..32u8 {
+            let bits = UInt8::new_witness(cs.clone(), || Ok(value))
+                .unwrap()
+                .to_bits_le()
+                .unwrap();
+            for constant in 0..40 {
+                let ge = is_ge_constant(&bits[..5], constant).unwrap();
+                assert_eq!(ge.value().unwrap(), u64::from(value) >= constant);
+            }
+        }
+
+        let mut rng = thread_rng();
+        for _ in 0..10 {
+            let (value, constant) = (rng.gen::<u64>(), rng.gen::<u64>());
+            let mut bits = UInt64::new_witness(cs.clone(), || Ok(value))
+                .unwrap()
+                .to_bits_le()
+                .unwrap();
+            assert_eq!(
+                is_ge_constant(&bits, constant).unwrap().value().unwrap(),
+                value >= constant
+            );
+
+            // a bit above 64 is worth more than any constant
+            bits.push(Boolean::new_witness(cs.clone(), || Ok(true)).unwrap());
+            assert!(is_ge_constant(&bits, constant).unwrap().value().unwrap());
+        }
+        assert!(cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_weight_threshold() {
+        let cases = [
+            (vec![5, 6], vec![true, false], 6, false),
+            (vec![5, 6], vec![true, true], 11, true),
+            (vec![5, 6], vec![true, true], 12, false),
+            // the sum overflows `u64`
+            (
+                vec![u64::MAX, u64::MAX, 1],
+                vec![true, true, false],
+                u64::MAX,
+                true,
+            ),
+        ];
+        for (weights, signed, threshold, satisfied) in cases {
+            let cs = ConstraintSystem::<CF>::new_ref();
+            let committee = weights
+                .into_iter()
+                .map(|weight| SignerVar {
+                    pk: PublicKeyVar::new_constant(cs.clone(), PublicKey::default()).unwrap(),
+                    weight: UInt64::new_witness(cs.clone(), || Ok(weight)).unwrap(),
+                })
+                .collect::<Vec<_>>();
+            let signed = Vec::<Boolean<CF>>::new_witness(cs.clone(), || Ok(signed)).unwrap();
+
+            enforce_weight_threshold(&committee, &signed, threshold).unwrap();
+            assert_eq!(cs.is_satisfied().unwrap(), satisfied);
+        }
     }
 }
@@ -0,0 +1,152 @@
+use std::borrow::Borrow;
+
+use ark_crypto_primitives::sponge::{
+    poseidon::{PoseidonConfig, PoseidonSponge},
+    Absorb, CryptographicSponge,
+};
+use ark_ff::PrimeField;
+use ark_r1cs_std::{
+    alloc::{AllocVar, AllocationMode},
+    convert::ToConstraintFieldGadget,
+    fields::fp::FpVar,
+    R1CSVar,
+};
+use ark_relations::r1cs::{ConstraintSystem, ConstraintSystemRef, Namespace, SynthesisError};
+use derivative::Derivative;
+
+use crate::bc::{
+    block::{Block, Committee},
+    params::BcConfig,
+};
+
+use super::{
+    bc::{BlockVar, CommitteeVar},
+    merkle::hash,
+};
+
+/// Domain separation of the commitment to a committee from the nodes of `folding::merkle`.
+const COMMITMENT_TAG: u64 = 3;
+
+impl<CF: PrimeField> CommitteeVar<CF> {
+    /// The Poseidon hash of all the signers of the committee.
+    pub fn commitment(
+        &self,
+        cs: ConstraintSystemRef<CF>,
+        params: &PoseidonConfig<CF>,
+    ) -> Result<FpVar<CF>, SynthesisError> {
+        hash(cs, params, COMMITMENT_TAG, &self.to_constraint_field()?)
+    }
+}
+
+/// Natively compute `CommitteeVar::commitment`.
+pub fn committee_commitment<CF: PrimeField + Absorb>(
+    committee: &Committee,
+    params: &PoseidonConfig<CF>,
+) -> Result<CF, SynthesisError> {
+    // the field elements of the emulated public keys are only defined through the gadgets
+    let input: Vec<CF> = CommitteeVar::new_constant(ConstraintSystem::new_ref(), committee)?
+        .to_constraint_field()?
+        .value()?;
+
+    let mut sponge = PoseidonSponge::new(params);
+    sponge.absorb(&CF::from(COMMITMENT_TAG));
+    sponge.absorb(&input);
+    Ok(sponge.squeeze_field_elements(1)[0])
+}
+
+/// The external inputs of `BCCircuitPoseidon`: a block, and the committee signing it as a
+/// hint, which the circuit checks against the commitment in the state.
+#[derive(Clone, Debug)]
+pub struct CommittedBlock {
+    pub block: Block,
+    pub committee: Committee,
+}
+
+impl CommittedBlock {
+    /// The placeholder block and committee, which have the fixed size of
+    /// `BCCircuitPoseidon` for `config`.
+    #[must_use]
+    pub fn placeholder(config: &BcConfig) -> Self {
+        Self {
+            block: Block::placeholder(config),
+            committee: Committee::placeholder(config),
+        }
+    }
+}
+
+#[derive(Derivative)]
+#[derivative(Clone(bound = ""), Debug(bound = ""))]
+pub struct CommittedBlockVar<CF: PrimeField> {
+    pub block: BlockVar<CF>,
+    pub committee: CommitteeVar<CF>,
+}
+
+impl<CF: PrimeField> AllocVar<CommittedBlock, CF> for CommittedBlockVar<CF> {
+    fn new_variable<T: Borrow<CommittedBlock>>(
+        cs: impl Into<Namespace<CF>>,
+        f: impl FnOnce() -> Result<T, SynthesisError>,
+        mode: AllocationMode,
+    ) -> Result<Self, SynthesisError> {
+        let cs = cs.into().cs();
+        let committed_block = f();
+
+        let block = BlockVar::new_variable(
+            cs.clone(),
+            || {
+                committed_block
+                    .as_ref()
+                    .map(|value| value.borrow().block.clone())
+                    .map_err(SynthesisError::clone)
+            },
+            mode,
+        )?;
+        let committee = CommitteeVar::new_variable(
+            cs,
+            || {
+                committed_block
+                    .as_ref()
+                    .map(|value| value.borrow().committee.clone())
+                    .map_err(SynthesisError::clone)
+            },
+            mode,
+        )?;
+
+        Ok(Self { block, committee })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ark_r1cs_std::{alloc::AllocVar, R1CSVar};
+    use ark_relations::r1cs::ConstraintSystem;
+    use rand::thread_rng;
+
+    use crate::{
+        bc::testgen::ChainGenerator,
+        folding::bc::CommitteeVar,
+        hash::poseidon::poseidon_config,
+        params::{BlsSigConfig, BlsSigField},
+    };
+
+    use super::committee_commitment;
+
+    type CF = BlsSigField<BlsSigConfig>;
+
+    #[test]
+    fn test_committee_commitment() {
+        let params = poseidon_config::<CF>();
+        let (blocks, _) = ChainGenerator::new(2, 4).generate(&mut thread_rng());
+
+        let cs = ConstraintSystem::new_ref();
+        let committee = CommitteeVar::new_witness(cs.clone(), || Ok(&blocks[0].committee)).unwrap();
+        let commitment = committee.commitment(cs.clone(), &params).unwrap();
+        assert!(cs.is_satisfied().unwrap());
+
+        let expected = committee_commitment(&blocks[0].committee, &params).unwrap();
+        assert_eq!(commitment.value().unwrap(), expected);
+        assert_ne!(
+            committee_commitment(&blocks[1].committee, &params).unwrap(),
+            expected
+        );
+    }
+}
@@ -0,0 +1,293 @@
+//! A packed representation of the committee in the state.
+//!
+//! `CommitteeVar` keeps every projective coordinate of a public key as the limbs of an
+//! `EmulatedFpVar`, and the weight as one more element, which takes 3 times the number of
+//! limbs plus 1 field elements per signer. `CompressedCommitteeVar`
+//! keeps the bytes the committee is serialized to instead, packed as many as fit in a field
+//! element, so the state is several times shorter. Unpacking it decomposes every element
+//! into bytes, and the public keys are rebuilt from their bytes with linear combinations.
+//!
+//! The bytes are those of `SerializeGadget`, which a step circuit computes anyway for the
+//! signed message, so packing the new committee adds almost no constraints.
+
+use std::borrow::Borrow;
+
+use ark_ff::{BigInteger, PrimeField};
+use ark_r1cs_std::{
+    alloc::{AllocVar, AllocationMode},
+    convert::ToBitsGadget,
+    eq::EqGadget,
+    fields::{
+        emulated_fp::{
+            params::{get_params, OptimizationType},
+            AllocatedEmulatedFpVar, EmulatedFpVar,
+        },
+        fp::FpVar,
+        FieldVar,
+    },
+    groups::bls12::G1Var,
+    prelude::Boolean,
+    uint64::UInt64,
+    uint8::UInt8,
+    R1CSVar,
+};
+use ark_relations::r1cs::{Namespace, OptimizationGoal, SynthesisError};
+use derivative::Derivative;
+
+use crate::{
+    bc::{block::Committee, params::BcConfig},
+    bls::PublicKeyVar,
+    params::{BlsSigConfig, BlsSigField, BlsSigFieldVar},
+};
+
+use super::{
+    bc::{CommitteeVar, SignerVar},
+    serialize::SerializeGadget,
+    state::StateSerde,
+};
+
+/// The bytes of a coordinate of a public key.
+const COORDINATE_BYTES: usize = BlsSigField::<BlsSigConfig>::MODULUS_BIT_SIZE.div_ceil(8) as usize;
+
+/// The bytes of a signer: the affine coordinates and the infinity flag of its public key,
+/// followed by its weight, as `bincode` serializes them.
+pub const SIGNER_BYTES: usize = 2 * COORDINATE_BYTES + 1 + 8;
+
+/// The number of bytes packed in one field element, which must stay below the modulus.
+fn bytes_per_element<CF: PrimeField>() -> usize {
+    (CF::MODULUS_BIT_SIZE as usize - 1) / 8
+}
+
+#[derive(Derivative)]
+#[derivative(Clone(bound = ""), Debug(bound = ""))]
+pub struct CompressedCommitteeVar<CF: PrimeField> {
+    /// The signers serialized one after the other, without the length prefix of the
+    /// committee.
+    pub bytes: Vec<UInt8<CF>>,
+}
+
+impl<CF: PrimeField> CompressedCommitteeVar<CF> {
+    pub fn from_committee(committee: &CommitteeVar<CF>) -> Result<Self, SynthesisError> {
+        Ok(Self {
+            bytes: committee.committee.serialize()?,
+        })
+    }
+
+    /// Rebuild the signers from their bytes.
+    ///
+    /// As with `from_constraint_field`, the public keys are not checked to be on the curve,
+    /// since the bytes are either trusted or signed by the committee.
+    pub fn committee(&self) -> Result<CommitteeVar<CF>, SynthesisError> {
+        Ok(CommitteeVar {
+            committee: self
+                .bytes
+                .chunks(SIGNER_BYTES)
+                .map(signer_from_bytes)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+fn signer_from_bytes<CF: PrimeField>(bytes: &[UInt8<CF>]) -> Result<SignerVar<CF>, SynthesisError> {
+    let (x, rest) = bytes.split_at(COORDINATE_BYTES);
+    let (y, rest) = rest.split_at(COORDINATE_BYTES);
+    let (infinity, weight) = rest.split_at(1);
+    let infinity = &infinity[0].to_bits_le()?[0];
+
+    // the identity is serialized with x = y = 0, and is (0, 1, 0) in projective coordinates
+    let pub_key = G1Var::<BlsSigConfig, _, CF>::new(
+        emulated_from_bytes(x)?,
+        infinity.select(&EmulatedFpVar::one(), &emulated_from_bytes(y)?)?,
+        infinity.select(&EmulatedFpVar::zero(), &EmulatedFpVar::one())?,
+    );
+
+    Ok(SignerVar {
+        pk: PublicKeyVar { pub_key },
+        weight: UInt64::from_bytes_le(weight)?,
+    })
+}
+
+/// Regroup the little-endian bits of `bytes` into the limbs of an `EmulatedFpVar`, which
+/// takes no constraint besides those forcing the bits past the last limb to be 0.
+///
+/// The bytes must be the canonical encoding of the element, which `to_bytes_le` gives.
+fn emulated_from_bytes<CF: PrimeField>(
+    bytes: &[UInt8<CF>],
+) -> Result<BlsSigFieldVar<CF>, SynthesisError> {
+    if bytes.is_constant() {
+        return Ok(EmulatedFpVar::constant(
+            BlsSigField::<BlsSigConfig>::from_le_bytes_mod_order(&bytes.value()?),
+        ));
+    }
+
+    let cs = bytes.cs();
+    let optimization_type = match cs.optimization_goal() {
+        OptimizationGoal::Weight => OptimizationType::Weight,
+        OptimizationGoal::None | OptimizationGoal::Constraints => OptimizationType::Constraints,
+    };
+    let params = get_params(
+        <BlsSigField<BlsSigConfig> as PrimeField>::MODULUS_BIT_SIZE as usize,
+        CF::MODULUS_BIT_SIZE as usize,
+        optimization_type,
+    );
+
+    let bits = bytes.to_bits_le()?;
+    let (bits, high) = bits.split_at(bits.len().min(params.num_limbs * params.bits_per_limb));
+    for bit in high {
+        bit.enforce_equal(&Boolean::FALSE)?;
+    }
+
+    // limbs are stored with the most significant one first
+    let mut limbs = bits
+        .chunks(params.bits_per_limb)
+        .map(Boolean::le_bits_to_fp)
+        .collect::<Result<Vec<_>, _>>()?;
+    limbs.reverse();
+
+    Ok(EmulatedFpVar::Var(AllocatedEmulatedFpVar {
+        cs,
+        limbs,
+        num_of_additions_over_normal_form: CF::zero(),
+        is_in_the_normal_form: true,
+        target_phantom: std::marker::PhantomData,
+    }))
+}
+
+/// Decompose `fp` into its `len` low bytes, and enforce that it has no other bits.
+fn unpack_bytes<CF: PrimeField>(
+    fp: &FpVar<CF>,
+    len: usize,
+) -> Result<Vec<UInt8<CF>>, SynthesisError> {
+    let value = fp.value().map(|v| v.into_bigint().to_bytes_le());
+    let mode = if fp.is_constant() {
+        AllocationMode::Constant
+    } else {
+        AllocationMode::Witness
+    };
+
+    let bytes = (0..len)
+        .map(|i| {
+            UInt8::new_variable(
+                fp.cs(),
+                || value.as_ref().map(|v| v[i]).map_err(SynthesisError::clone),
+                mode,
+            )
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // `len` bytes are fewer bits than the modulus, so the decomposition is unique
+    Boolean::le_bits_to_fp(&bytes.to_bits_le()?)?.enforce_equal(fp)?;
+    Ok(bytes)
+}
+
+impl<CF: PrimeField> StateSerde<CF> for CompressedCommitteeVar<CF> {
+    type Shape = BcConfig;
+
+    fn state_len(config: BcConfig) -> usize {
+        (config.max_committee_size * SIGNER_BYTES).div_ceil(bytes_per_element::<CF>())
+    }
+
+    fn to_state(&self) -> Result<Vec<FpVar<CF>>, SynthesisError> {
+        self.bytes
+            .chunks(bytes_per_element::<CF>())
+            .map(|chunk| Boolean::le_bits_to_fp(&chunk.to_bits_le()?))
+            .collect()
+    }
+
+    fn from_state(
+        mut iter: impl Iterator<Item = FpVar<CF>>,
+        config: BcConfig,
+    ) -> Result<Self, SynthesisError> {
+        let mut len = config.max_committee_size * SIGNER_BYTES;
+        let mut bytes = Vec::with_capacity(len);
+
+        while len > 0 {
+            let fp = iter.next().ok_or(SynthesisError::Unsatisfiable)?;
+            let n = len.min(bytes_per_element::<CF>());
+            bytes.extend(unpack_bytes(&fp, n)?);
+            len -= n;
+        }
+
+        Ok(Self { bytes })
+    }
+}
+
+impl<CF: PrimeField> AllocVar<Committee, CF> for CompressedCommitteeVar<CF> {
+    fn new_variable<T: Borrow<Committee>>(
+        cs: impl Into<Namespace<CF>>,
+        f: impl FnOnce() -> Result<T, SynthesisError>,
+        mode: AllocationMode,
+    ) -> Result<Self, SynthesisError> {
+        // `bincode` prefixes the signers with their number as a `u64`
+        let bytes = f().map(|committee| {
+            bincode::serialize(&committee.borrow().signers)
+                .expect("serializing a committee should succeed")
+                .split_off(8)
+        });
+
+        Ok(Self {
+            bytes: Vec::new_variable(cs, || bytes, mode)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ark_r1cs_std::{alloc::AllocVar, fields::fp::FpVar, R1CSVar};
+    use ark_relations::r1cs::ConstraintSystem;
+    use rand::thread_rng;
+
+    use crate::{
+        bc::{params::BcConfig, testgen::ChainGenerator},
+        folding::{bc::CommitteeVar, serialize::SerializeGadget, state::StateSerde},
+        params::{BlsSigConfig, BlsSigField},
+    };
+
+    use super::CompressedCommitteeVar;
+
+    type CF = BlsSigField<BlsSigConfig>;
+
+    #[test]
+    fn test_compressed_committee() {
+        let config = BcConfig {
+            max_committee_size: 4,
+            ..Default::default()
+        };
+        let (blocks, _) = ChainGenerator::new(1, 4)
+            .config(config)
+            .generate(&mut thread_rng());
+        let committee = &blocks[0].committee;
+
+        let cs = ConstraintSystem::<CF>::new_ref();
+        let compressed = CompressedCommitteeVar::new_witness(cs.clone(), || Ok(committee)).unwrap();
+        assert_eq!(
+            compressed.serialize().unwrap().value().unwrap(),
+            bincode::serialize(committee).unwrap()
+        );
+
+        // the state is shorter than that of `CommitteeVar`
+        let z_i = compressed
+            .to_state()
+            .unwrap()
+            .iter()
+            .map(|x| FpVar::new_witness(cs.clone(), || x.value()))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(z_i.len(), CompressedCommitteeVar::<CF>::state_len(config));
+        assert!(z_i.len() < CommitteeVar::<CF>::state_len(config));
+
+        // the committee rebuilt from the state serializes to the same bytes
+        let rebuilt = CompressedCommitteeVar::from_state(z_i.into_iter(), config)
+            .unwrap()
+            .committee()
+            .unwrap();
+        assert_eq!(
+            rebuilt.serialize().unwrap().value().unwrap(),
+            bincode::serialize(committee).unwrap()
+        );
+        assert!(cs.is_satisfied().unwrap());
+
+        // the state is too short
+        assert!(CompressedCommitteeVar::<CF>::from_state(Vec::new().into_iter(), config).is_err());
+    }
+}
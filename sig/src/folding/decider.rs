@@ -0,0 +1,105 @@
+//! Succinct final proofs of a folded chain with sonobe's Ethereum decider, and the Solidity
+//! contract verifying them.
+//!
+//! The decider only supports Nova over BN254/Grumpkin, the only cycle with precompiles on
+//! the EVM, so the step circuit is synthesized over the scalar field of BN254.
+
+use std::{fs, path::Path};
+
+use ark_bn254::{Bn254, Fr, G1Projective as G1};
+use ark_groth16::Groth16;
+use ark_grumpkin::Projective as G2;
+use folding_schemes::{
+    commitment::{kzg::KZG, pedersen::Pedersen},
+    folding::{
+        nova::{decider_eth::Decider as DeciderEth, Nova},
+        traits::CommittedInstanceOps,
+    },
+    frontend::FCircuit,
+    Decider, Error, FoldingScheme,
+};
+use rand::{CryptoRng, RngCore};
+use solidity_verifiers::{get_decider_template_for_cyclefold_decider, NovaCycleFoldVerifierKey};
+
+/// Nova over BN254/Grumpkin, which can be decided with `EvmDecider`.
+pub type EvmNova<FC> = Nova<G1, G2, FC, KZG<'static, Bn254>, Pedersen<G2>, false>;
+
+pub type EvmDecider<FC> =
+    DeciderEth<G1, G2, FC, KZG<'static, Bn254>, Pedersen<G2>, Groth16<Bn254>, EvmNova<FC>>;
+
+type NovaParams<FC> = (
+    <EvmNova<FC> as FoldingScheme<G1, G2, FC>>::ProverParam,
+    <EvmNova<FC> as FoldingScheme<G1, G2, FC>>::VerifierParam,
+);
+type DeciderProverParam<FC> = <EvmDecider<FC> as Decider<G1, G2, FC, EvmNova<FC>>>::ProverParam;
+type DeciderVerifierParam<FC> = <EvmDecider<FC> as Decider<G1, G2, FC, EvmNova<FC>>>::VerifierParam;
+type DeciderProof<FC> = <EvmDecider<FC> as Decider<G1, G2, FC, EvmNova<FC>>>::Proof;
+
+/// A decider proof, with the public inputs it is verified against: the number of folded
+/// steps, the initial and the last states.
+pub struct FinalProof<FC: FCircuit<Fr>> {
+    pub proof: DeciderProof<FC>,
+    pub i: Fr,
+    pub z_0: Vec<Fr>,
+    pub z_i: Vec<Fr>,
+    pub running_commitments: Vec<G1>,
+    pub incoming_commitments: Vec<G1>,
+}
+
+/// The parameters of the decider for the step circuit `FC`.
+pub struct LightClientDecider<FC: FCircuit<Fr>> {
+    pp: DeciderProverParam<FC>,
+    vp: DeciderVerifierParam<FC>,
+    state_len: usize,
+}
+
+impl<FC: FCircuit<Fr>> LightClientDecider<FC> {
+    /// Set up the decider for `circuit`, folded with `nova_params`.
+    pub fn preprocess(
+        mut rng: impl RngCore + CryptoRng,
+        nova_params: NovaParams<FC>,
+        circuit: &FC,
+    ) -> Result<Self, Error> {
+        let state_len = circuit.state_len();
+        let (pp, vp) = EvmDecider::<FC>::preprocess(&mut rng, (nova_params, state_len))?;
+        Ok(Self { pp, vp, state_len })
+    }
+
+    /// Compress the folded state `ivc_state` into a succinct proof.
+    pub fn prove_final(
+        &self,
+        mut rng: impl RngCore + CryptoRng,
+        ivc_state: &EvmNova<FC>,
+    ) -> Result<FinalProof<FC>, Error> {
+        let proof = EvmDecider::<FC>::prove(&mut rng, self.pp.clone(), ivc_state.clone())?;
+
+        Ok(FinalProof {
+            proof,
+            i: ivc_state.i,
+            z_0: ivc_state.z_0.clone(),
+            z_i: ivc_state.z_i.clone(),
+            running_commitments: ivc_state.U_i.get_commitments(),
+            incoming_commitments: ivc_state.u_i.get_commitments(),
+        })
+    }
+
+    /// Natively verify `proof`, as the Solidity verifier would.
+    pub fn verify_final(&self, proof: &FinalProof<FC>) -> Result<bool, Error> {
+        EvmDecider::<FC>::verify(
+            self.vp.clone(),
+            proof.i,
+            proof.z_0.clone(),
+            proof.z_i.clone(),
+            &proof.running_commitments,
+            &proof.incoming_commitments,
+            &proof.proof,
+        )
+    }
+
+    /// Write the Solidity contract verifying the proofs of `prove_final` to `path`.
+    pub fn export_solidity_verifier(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        let vk = NovaCycleFoldVerifierKey::from((self.vp.clone(), self.state_len));
+        fs::write(path, get_decider_template_for_cyclefold_decider(vk))?;
+        Ok(())
+    }
+}
@@ -14,9 +14,9 @@ use ark_r1cs_std::{
 use ark_relations::r1cs::SynthesisError;
 
 use crate::{
-    bc::params::MAX_COMMITTEE_SIZE,
+    bc::params::BcConfig,
     bls::PublicKeyVar,
-    params::{BlsSigConfig, BlsSigField},
+    params::{BlsSigConfig, BlsSigField, BlsSigFieldVar},
 };
 
 use super::bc::{CommitteeVar, SignerVar};
@@ -32,6 +32,18 @@ pub trait FromConstraintFieldGadget<CF: PrimeField>: Sized {
         -> Result<Self, SynthesisError>;
 }
 
+impl<CF: PrimeField> FromConstraintFieldGadget<CF> for FpVar<CF> {
+    fn from_constraint_field(
+        mut iter: impl Iterator<Item = FpVar<CF>>,
+    ) -> Result<Self, SynthesisError> {
+        iter.next().ok_or(SynthesisError::Unsatisfiable)
+    }
+
+    fn num_constraint_var_needed() -> usize {
+        1
+    }
+}
+
 impl<CF: PrimeField> FromConstraintFieldGadget<CF> for UInt64<CF> {
     fn from_constraint_field(
         mut iter: impl Iterator<Item = FpVar<CF>>,
@@ -45,9 +57,7 @@ impl<CF: PrimeField> FromConstraintFieldGadget<CF> for UInt64<CF> {
     }
 }
 
-impl<CF: PrimeField> FromConstraintFieldGadget<CF>
-    for EmulatedFpVar<BlsSigField<BlsSigConfig>, CF>
-{
+impl<CF: PrimeField> FromConstraintFieldGadget<CF> for BlsSigFieldVar<CF> {
     fn from_constraint_field(
         iter: impl Iterator<Item = FpVar<CF>>,
     ) -> Result<Self, SynthesisError> {
@@ -81,7 +91,7 @@ impl<CF: PrimeField> FromConstraintFieldGadget<CF>
 }
 
 impl<CF: PrimeField> FromConstraintFieldGadget<CF>
-    for PublicKeyVar<BlsSigConfig, EmulatedFpVar<BlsSigField<BlsSigConfig>, CF>, CF>
+    for PublicKeyVar<BlsSigConfig, BlsSigFieldVar<CF>, CF>
 {
     fn from_constraint_field(
         mut iter: impl Iterator<Item = FpVar<CF>>,
@@ -91,7 +101,7 @@ impl<CF: PrimeField> FromConstraintFieldGadget<CF>
         // signed by some committee. Since the committee is the trusted base of the safety of the circuit,
         // we don't need to perform additional check here.
         Ok(Self {
-            pub_key: G1Var::<BlsSigConfig, EmulatedFpVar<BlsSigField<BlsSigConfig>, CF>, CF>::new(
+            pub_key: G1Var::<BlsSigConfig, BlsSigFieldVar<CF>, CF>::new(
                 EmulatedFpVar::from_constraint_field(iter.by_ref())?,
                 EmulatedFpVar::from_constraint_field(iter.by_ref())?,
                 EmulatedFpVar::from_constraint_field(iter.by_ref())?,
@@ -100,7 +110,7 @@ impl<CF: PrimeField> FromConstraintFieldGadget<CF>
     }
 
     fn num_constraint_var_needed() -> usize {
-        3 * EmulatedFpVar::<BlsSigField<BlsSigConfig>, CF>::num_constraint_var_needed()
+        3 * BlsSigFieldVar::<CF>::num_constraint_var_needed()
     }
 }
 
@@ -135,18 +145,23 @@ impl<CF: PrimeField> FromConstraintFieldGadget<CF> for SignerVar<CF> {
     }
 
     fn num_constraint_var_needed() -> usize {
-        PublicKeyVar::<BlsSigConfig, EmulatedFpVar<BlsSigField<BlsSigConfig>, CF>, CF>::num_constraint_var_needed() + UInt64::<CF>::num_constraint_var_needed()
+        PublicKeyVar::<BlsSigConfig, BlsSigFieldVar<CF>, CF>::num_constraint_var_needed()
+            + UInt64::<CF>::num_constraint_var_needed()
     }
 }
 
-impl<CF: PrimeField> FromConstraintFieldGadget<CF> for CommitteeVar<CF> {
-    fn from_constraint_field(
+/// Unlike the other variables, the size of a committee depends on the `BcConfig` of the
+/// circuit, so `CommitteeVar` cannot implement `FromConstraintFieldGadget`. It implements
+/// `StateSerde` instead, with the `BcConfig` as its shape.
+impl<CF: PrimeField> CommitteeVar<CF> {
+    pub fn from_constraint_field(
         mut iter: impl Iterator<Item = FpVar<CF>>,
+        config: &BcConfig,
     ) -> Result<Self, SynthesisError> {
         let mut committee = Vec::new();
-        committee.reserve_exact(MAX_COMMITTEE_SIZE);
+        committee.reserve_exact(config.max_committee_size);
 
-        for _ in 0..MAX_COMMITTEE_SIZE {
+        for _ in 0..config.max_committee_size {
             let signer = SignerVar::from_constraint_field(iter.by_ref())?;
             committee.push(signer);
         }
@@ -154,7 +169,8 @@ impl<CF: PrimeField> FromConstraintFieldGadget<CF> for CommitteeVar<CF> {
         Ok(Self { committee })
     }
 
-    fn num_constraint_var_needed() -> usize {
-        SignerVar::<CF>::num_constraint_var_needed() * MAX_COMMITTEE_SIZE
+    #[must_use]
+    pub fn num_constraint_var_needed(config: &BcConfig) -> usize {
+        SignerVar::<CF>::num_constraint_var_needed() * config.max_committee_size
     }
 }
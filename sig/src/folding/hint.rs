@@ -0,0 +1,143 @@
+//! Native hints to allocate the coordinates of many keys, computed before the allocation.
+//!
+//! The constraint system is not `Sync`, so variables are allocated one after the other, but
+//! most of the work to allocate an emulated coordinate is native: its decomposition into
+//! limbs. `AllocHint` separates this work from the allocation, so that `CommitteeVar`
+//! computes the hints of all its signers at once, in parallel with the `parallel` feature.
+use ark_ff::PrimeField;
+use ark_r1cs_std::{
+    alloc::{AllocVar, AllocationMode},
+    fields::{
+        emulated_fp::{params::OptimizationType, AllocatedEmulatedFpVar, EmulatedFpVar},
+        fp::FpVar,
+        FieldVar,
+    },
+};
+use ark_relations::r1cs::{ConstraintSystemRef, Namespace, OptimizationGoal, SynthesisError};
+
+/// A field var that can be allocated from a hint computed natively from its value.
+pub trait AllocHint<F: PrimeField, CF: PrimeField>: FieldVar<F, CF> {
+    type Hint: Send + Sync;
+
+    /// The hint of `value` for a constraint system with `optimization_goal`, which does not
+    /// need the constraint system itself, so it can be computed on another thread.
+    fn hint(value: F, optimization_goal: OptimizationGoal) -> Result<Self::Hint, SynthesisError>;
+
+    /// Allocate the variable from the hint of its value, as `new_variable` allocates it from
+    /// the value.
+    fn new_variable_with_hint(
+        cs: impl Into<Namespace<CF>>,
+        hint: Result<&Self::Hint, SynthesisError>,
+        mode: AllocationMode,
+    ) -> Result<Self, SynthesisError>;
+}
+
+/// A native field element is its own hint.
+impl<F: PrimeField> AllocHint<F, F> for FpVar<F> {
+    type Hint = F;
+
+    fn hint(value: F, _: OptimizationGoal) -> Result<Self::Hint, SynthesisError> {
+        Ok(value)
+    }
+
+    fn new_variable_with_hint(
+        cs: impl Into<Namespace<F>>,
+        hint: Result<&Self::Hint, SynthesisError>,
+        mode: AllocationMode,
+    ) -> Result<Self, SynthesisError> {
+        Self::new_variable(cs, || hint.copied(), mode)
+    }
+}
+
+/// The hint of an emulated field element is its value and its limbs.
+impl<F: PrimeField, CF: PrimeField> AllocHint<F, CF> for EmulatedFpVar<F, CF> {
+    type Hint = (F, Vec<CF>);
+
+    fn hint(value: F, optimization_goal: OptimizationGoal) -> Result<Self::Hint, SynthesisError> {
+        let optimization_type = match optimization_goal {
+            OptimizationGoal::Weight => OptimizationType::Weight,
+            OptimizationGoal::None | OptimizationGoal::Constraints => OptimizationType::Constraints,
+        };
+        let limbs =
+            AllocatedEmulatedFpVar::<F, CF>::get_limbs_representations(&value, optimization_type)?;
+        Ok((value, limbs))
+    }
+
+    fn new_variable_with_hint(
+        cs: impl Into<Namespace<CF>>,
+        hint: Result<&Self::Hint, SynthesisError>,
+        mode: AllocationMode,
+    ) -> Result<Self, SynthesisError> {
+        let ns = cs.into();
+        let cs = ns.cs();
+
+        // as `new_variable`, which does not decompose constants
+        if cs == ConstraintSystemRef::None || mode == AllocationMode::Constant {
+            return Ok(Self::Constant(hint?.0));
+        }
+        AllocatedEmulatedFpVar::new_variable_with_limbs(
+            cs,
+            || hint.map(|(_, limbs)| limbs.as_slice()),
+            mode,
+        )
+        .map(Self::Var)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ark_r1cs_std::{
+        alloc::{AllocVar, AllocationMode},
+        fields::emulated_fp::EmulatedFpVar,
+        R1CSVar,
+    };
+    use ark_relations::r1cs::{ConstraintSystem, OptimizationGoal, SynthesisError};
+    use ark_std::UniformRand;
+    use rand::thread_rng;
+
+    use crate::params::{BlsSigConfig, BlsSigField};
+
+    use super::AllocHint;
+
+    type F = BlsSigField<BlsSigConfig>;
+    type CF = ark_bls12_377::Fr;
+
+    /// Allocating from the hint assigns the same variables and constraints as `new_variable`.
+    #[test]
+    fn check_emulated_hint() {
+        let value = F::rand(&mut thread_rng());
+        for goal in [OptimizationGoal::Constraints, OptimizationGoal::Weight] {
+            for mode in [AllocationMode::Input, AllocationMode::Witness] {
+                let cs = ConstraintSystem::<CF>::new_ref();
+                cs.set_optimization_goal(goal);
+                EmulatedFpVar::<F, CF>::new_variable(cs.clone(), || Ok(value), mode).unwrap();
+
+                let hinted_cs = ConstraintSystem::<CF>::new_ref();
+                hinted_cs.set_optimization_goal(goal);
+                let hint = EmulatedFpVar::<F, CF>::hint(value, goal).unwrap();
+                let var = EmulatedFpVar::<F, CF>::new_variable_with_hint(
+                    hinted_cs.clone(),
+                    Ok(&hint),
+                    mode,
+                )
+                .unwrap();
+
+                assert_eq!(var.value().unwrap(), value);
+                assert!(hinted_cs.is_satisfied().unwrap());
+                let (cs, hinted_cs) = (cs.borrow().unwrap(), hinted_cs.borrow().unwrap());
+                assert_eq!(cs.instance_assignment, hinted_cs.instance_assignment);
+                assert_eq!(cs.witness_assignment, hinted_cs.witness_assignment);
+                assert_eq!(cs.num_constraints, hinted_cs.num_constraints);
+            }
+        }
+
+        // the limbs of another optimization goal do not fit
+        let cs = ConstraintSystem::<CF>::new_ref();
+        cs.set_optimization_goal(OptimizationGoal::Constraints);
+        let hint = EmulatedFpVar::<F, CF>::hint(value, OptimizationGoal::Weight).unwrap();
+        assert!(matches!(
+            EmulatedFpVar::<F, CF>::new_variable_with_hint(cs, Ok(&hint), AllocationMode::Witness),
+            Err(SynthesisError::Unsatisfiable)
+        ));
+    }
+}
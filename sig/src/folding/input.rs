@@ -0,0 +1,92 @@
+//! The external inputs of the step circuits, which are allocated by the circuits themselves.
+//!
+//! Sonobe synthesizes a step circuit with `ExternalInputs::default()` to preprocess it, but
+//! the blocks a circuit accepts must be padded to the `BcConfig` of the circuit, which a
+//! `Default` cannot know. So the circuits take their inputs as an `Option`, and
+//! `StepInputVar` only records the value: the circuit allocates it, or a placeholder padded
+//! to its `BcConfig` for `None`, so that the constraints are the same at preprocessing and
+//! at every step.
+use std::borrow::Borrow;
+
+use ark_ff::PrimeField;
+use ark_r1cs_std::alloc::{AllocVar, AllocationMode};
+use ark_relations::r1cs::{ConstraintSystemRef, Namespace, SynthesisError};
+use derivative::Derivative;
+
+#[derive(Derivative)]
+#[derivative(Clone(bound = "T: Clone"), Debug(bound = "T: core::fmt::Debug"))]
+pub struct StepInputVar<CF: PrimeField, T> {
+    cs: ConstraintSystemRef<CF>,
+    mode: AllocationMode,
+    value: Option<T>,
+}
+
+impl<CF: PrimeField, T: Clone> AllocVar<Option<T>, CF> for StepInputVar<CF, T> {
+    fn new_variable<V: Borrow<Option<T>>>(
+        cs: impl Into<Namespace<CF>>,
+        f: impl FnOnce() -> Result<V, SynthesisError>,
+        mode: AllocationMode,
+    ) -> Result<Self, SynthesisError> {
+        let value = match f() {
+            Ok(value) => value.borrow().clone(),
+            // in setup mode, where the placeholder has the same shape as any input
+            Err(SynthesisError::AssignmentMissing) => None,
+            Err(e) => return Err(e),
+        };
+
+        Ok(Self {
+            cs: cs.into().cs(),
+            mode,
+            value,
+        })
+    }
+}
+
+impl<CF: PrimeField, T> StepInputVar<CF, T> {
+    /// Allocate the input as a `V`, or `placeholder()` if there is none.
+    pub fn allocate<V: AllocVar<T, CF>>(
+        self,
+        placeholder: impl FnOnce() -> T,
+    ) -> Result<V, SynthesisError> {
+        V::new_variable(
+            self.cs,
+            || Ok(self.value.unwrap_or_else(placeholder)),
+            self.mode,
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ark_r1cs_std::{alloc::AllocVar, uint64::UInt64, R1CSVar};
+    use ark_relations::r1cs::{ConstraintSystem, SynthesisError, SynthesisMode};
+
+    use crate::params::{BlsSigConfig, BlsSigField};
+
+    use super::StepInputVar;
+
+    type CF = BlsSigField<BlsSigConfig>;
+
+    #[test]
+    fn test_step_input() {
+        let cs = ConstraintSystem::<CF>::new_ref();
+        let input = StepInputVar::<CF, u64>::new_witness(cs.clone(), || Ok(Some(7u64))).unwrap();
+        let var: UInt64<CF> = input.allocate(|| 0).unwrap();
+        assert_eq!(var.value().unwrap(), 7);
+
+        let input = StepInputVar::<CF, u64>::new_witness(cs.clone(), || Ok(None)).unwrap();
+        let var: UInt64<CF> = input.allocate(|| 3).unwrap();
+        assert_eq!(var.value().unwrap(), 3);
+        assert_eq!(cs.num_witness_variables(), 128);
+
+        // without assignments, the placeholder only gives the shape
+        let cs = ConstraintSystem::<CF>::new_ref();
+        cs.set_mode(SynthesisMode::Setup);
+        let input = StepInputVar::<CF, u64>::new_witness(cs.clone(), || {
+            Err(SynthesisError::AssignmentMissing)
+        })
+        .unwrap();
+        let _: UInt64<CF> = input.allocate(|| 3).unwrap();
+        assert_eq!(cs.num_witness_variables(), 64);
+    }
+}
@@ -0,0 +1,256 @@
+use core::fmt;
+
+use ark_ec::CurveGroup;
+use ark_relations::r1cs::SynthesisError;
+use folding_schemes::{folding::nova, frontend::FCircuit, Error, FoldingScheme};
+use rand::RngCore;
+
+use crate::{
+    bc::{
+        block::{Block, Committee},
+        chain::{verify_next_block, ChainError},
+        checkpoint::Checkpoint,
+    },
+    params::{CurveCycleConfig, CycleField},
+};
+
+use super::circuit::{BCCircuitNoMerkle, BCCircuitParams};
+
+#[derive(Debug)]
+pub enum LightClientError {
+    /// The block does not extend the chain followed by the client.
+    InvalidBlock(ChainError),
+    /// The proof does not verify, does not start from the trusted block, or does not reach
+    /// the expected state.
+    InvalidProof,
+    Folding(Error),
+}
+
+impl fmt::Display for LightClientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidBlock(e) => write!(f, "invalid block: {e}"),
+            Self::InvalidProof => write!(f, "invalid proof"),
+            Self::Folding(e) => write!(f, "folding error: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for LightClientError {}
+
+impl From<ChainError> for LightClientError {
+    fn from(e: ChainError) -> Self {
+        Self::InvalidBlock(e)
+    }
+}
+
+impl From<Error> for LightClientError {
+    fn from(e: Error) -> Self {
+        Self::Folding(e)
+    }
+}
+
+impl From<SynthesisError> for LightClientError {
+    fn from(e: SynthesisError) -> Self {
+        Self::Folding(e.into())
+    }
+}
+
+/// An IVC proof whose initial state can be read, so that a verifier can check it against
+/// its own trusted block: `FoldingScheme::verify` only checks the proof against the initial
+/// state it carries.
+pub trait InitialState<F> {
+    fn initial_state(&self) -> &[F];
+}
+
+impl<C1: CurveGroup, C2: CurveGroup> InitialState<C1::ScalarField> for nova::IVCProof<C1, C2> {
+    fn initial_state(&self) -> &[C1::ScalarField] {
+        &self.z_0
+    }
+}
+
+/// A light client that follows the committee rotation chain from a trusted block, and
+/// proves with the folding scheme `FS` over the cycle `C` that every block it accepted was
+/// signed by the committee handed off by its parent.
+///
+/// This hides the state `z_i` of the folding scheme, which `BCCircuitNoMerkle` lays out
+/// as the committee followed by the epoch.
+pub struct LightClient<C, FS>
+where
+    C: CurveCycleConfig,
+    FS: FoldingScheme<C::C1, C::C2, BCCircuitNoMerkle<C>>,
+{
+    circuit: BCCircuitNoMerkle<C>,
+    params: BCCircuitParams,
+    folding_params: (FS::ProverParam, FS::VerifierParam),
+    folding: FS,
+    /// The state of the trusted block.
+    z_0: Vec<CycleField<C>>,
+    last: Block,
+}
+
+impl<C, FS> LightClient<C, FS>
+where
+    C: CurveCycleConfig,
+    FS: FoldingScheme<C::C1, C::C2, BCCircuitNoMerkle<C>>,
+{
+    /// Start from `genesis`, which is trusted, e.g. the genesis block or the block of a
+    /// checkpoint. `folding_params` are the output of `FS::preprocess` for the circuit
+    /// with `params`.
+    pub fn new(
+        genesis: Block,
+        params: BCCircuitParams,
+        folding_params: (FS::ProverParam, FS::VerifierParam),
+    ) -> Result<Self, LightClientError> {
+        let circuit = BCCircuitNoMerkle::new(params)?;
+        let z_0 = circuit.initial_state(&Checkpoint::from_block(&genesis), &genesis.committee)?;
+        let folding = FS::init(&folding_params, circuit, z_0.clone())?;
+
+        Ok(Self {
+            circuit,
+            params,
+            folding_params,
+            folding,
+            z_0,
+            last: genesis,
+        })
+    }
+
+    /// Check `block` natively, then fold it into the proof. Invalid blocks are rejected
+    /// before running the prover, and leave the client unchanged.
+    pub fn process_block<R: RngCore>(
+        &mut self,
+        block: &Block,
+        rng: &mut R,
+    ) -> Result<(), LightClientError> {
+        verify_next_block(
+            &self.last,
+            block,
+            &self.params.config,
+            &self.params.sig_params,
+        )?;
+        self.folding.prove_step(rng, Some(block.clone()), None)?;
+        self.last = block.clone();
+        Ok(())
+    }
+
+    /// The epoch and committee the client has reached.
+    #[must_use]
+    pub fn current_state(&self) -> Checkpoint {
+        Checkpoint::from_block(&self.last)
+    }
+
+    /// The committee handed off by the last accepted block.
+    #[must_use]
+    pub fn committee(&self) -> &Committee {
+        &self.last.committee
+    }
+
+    /// A proof that the chain reaches `current_state()` from the trusted block.
+    #[must_use]
+    pub fn prove(&self) -> FS::IVCProof {
+        self.folding.ivc_proof()
+    }
+
+    /// Verify `proof`, that it starts from the trusted block of the client, and that it
+    /// reaches `checkpoint` with `committee`.
+    pub fn verify(
+        &self,
+        proof: FS::IVCProof,
+        checkpoint: &Checkpoint,
+        committee: &Committee,
+    ) -> Result<(), LightClientError>
+    where
+        FS::IVCProof: InitialState<CycleField<C>>,
+    {
+        if proof.initial_state() != self.z_0 {
+            return Err(LightClientError::InvalidProof);
+        }
+        FS::verify(self.folding_params.1.clone(), proof.clone())
+            .map_err(|_| LightClientError::InvalidProof)?;
+
+        let folded = FS::from_ivc_proof(proof, self.params, self.folding_params.clone())?;
+        if folded.state() != self.circuit.initial_state(checkpoint, committee)? {
+            return Err(LightClientError::InvalidProof);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ark_mnt4_753::{Fr, G1Projective as G1, MNT4_753 as MNT4};
+    use ark_mnt6_753::{G1Projective as G2, MNT6_753 as MNT6};
+    use folding_schemes::{
+        commitment::kzg::KZG,
+        folding::nova::{Nova, PreprocessorParam},
+        frontend::FCircuit,
+        transcript::poseidon::poseidon_canonical_config,
+        FoldingScheme,
+    };
+    use rand::thread_rng;
+
+    use crate::bc::{params::BcConfig, testgen::ChainGenerator};
+
+    use super::{BCCircuitNoMerkle, BCCircuitParams, LightClient, LightClientError};
+
+    type FC = BCCircuitNoMerkle<(G1, G2)>;
+    type N = Nova<G1, G2, FC, KZG<'static, MNT4>, KZG<'static, MNT6>, false>;
+
+    #[test]
+    #[ignore = "folding over MNT4/MNT6 takes a long time to finish running"]
+    fn test_light_client() {
+        // the default committee size of `examples/light_client.rs`
+        let params = BCCircuitParams {
+            config: BcConfig {
+                max_committee_size: 4,
+                ..Default::default()
+            },
+            ..BCCircuitParams::setup()
+        };
+        let mut rng = thread_rng();
+        let (blocks, _) = ChainGenerator::new(3, 4)
+            .config(params.config)
+            .generate(&mut rng);
+        let nova_params = N::preprocess(
+            &mut rng,
+            &PreprocessorParam::new(poseidon_canonical_config::<Fr>(), FC::new(params).unwrap()),
+        )
+        .unwrap();
+
+        let mut client =
+            LightClient::<(G1, G2), N>::new(blocks[0].clone(), params, nova_params).unwrap();
+        for block in &blocks[1..] {
+            client.process_block(block, &mut rng).unwrap();
+        }
+        // a block that does not extend the chain is rejected before folding
+        assert!(client.process_block(&blocks[1], &mut rng).is_err());
+
+        let proof = client.prove();
+        client
+            .verify(proof, &client.current_state(), client.committee())
+            .unwrap();
+        assert_eq!(client.current_state().epoch, 2);
+
+        // a valid proof of another chain does not start from the trusted block
+        let (other_blocks, _) = ChainGenerator::new(2, 4)
+            .config(params.config)
+            .generate(&mut rng);
+        let mut other = LightClient::<(G1, G2), N>::new(
+            other_blocks[0].clone(),
+            params,
+            client.folding_params.clone(),
+        )
+        .unwrap();
+        other.process_block(&other_blocks[1], &mut rng).unwrap();
+        let proof = other.prove();
+        other
+            .verify(proof.clone(), &other.current_state(), other.committee())
+            .unwrap();
+        assert!(matches!(
+            client.verify(proof, &other.current_state(), other.committee()),
+            Err(LightClientError::InvalidProof)
+        ));
+    }
+}
@@ -0,0 +1,572 @@
+//! A Poseidon Merkle tree over the signers of a committee, so that the folding state only
+//! needs to carry the root of the committee instead of all of its public keys.
+//!
+//! The native tree is computed with the same gadgets as the circuit (on constants), so that
+//! both always agree on the leaves and on how the committee is padded.
+//!
+//! `MerkleTree` is the same tree over arbitrary leaves with a fixed depth, whose paths are
+//! checked by `MerkleTreeGadget` against an index known only to the prover.
+
+use std::{borrow::Borrow, marker::PhantomData};
+
+use ark_crypto_primitives::sponge::{
+    constraints::CryptographicSpongeVar,
+    poseidon::{constraints::PoseidonSpongeVar, PoseidonConfig, PoseidonSponge},
+    Absorb, CryptographicSponge,
+};
+use ark_ff::PrimeField;
+use ark_r1cs_std::{
+    alloc::{AllocVar, AllocationMode},
+    convert::ToConstraintFieldGadget,
+    eq::EqGadget,
+    fields::{fp::FpVar, FieldVar},
+    prelude::Boolean,
+    R1CSVar,
+};
+use ark_relations::r1cs::{ConstraintSystem, ConstraintSystemRef, Namespace, SynthesisError};
+use derivative::Derivative;
+
+use crate::bc::{
+    block::{Block, Committee},
+    params::{AuthorityPublicKey, BcConfig, Weight},
+};
+
+use super::bc::{BlockVar, CommitteeVar, SignerVar};
+
+/// Domain separation of leaves and inner nodes, absorbed before their inputs. Leaves are
+/// also prefixed by their length, since absorbing `[a]` and `[a, 0]` leaves the sponge in the
+/// same state.
+const LEAF_TAG: u64 = 1;
+const NODE_TAG: u64 = 2;
+
+/// The depth of the tree of a committee of `committee_size` signers, which is padded with
+/// signers without weight to the next power of two.
+#[must_use]
+pub const fn tree_depth(committee_size: usize) -> usize {
+    committee_size.next_power_of_two().trailing_zeros() as usize
+}
+
+pub(super) fn hash<CF: PrimeField>(
+    cs: ConstraintSystemRef<CF>,
+    params: &PoseidonConfig<CF>,
+    tag: u64,
+    input: &[FpVar<CF>],
+) -> Result<FpVar<CF>, SynthesisError> {
+    let mut sponge = PoseidonSpongeVar::new(cs, params);
+    sponge.absorb(&FpVar::constant(tag.into()))?;
+    sponge.absorb(&input)?;
+    Ok(sponge.squeeze_field_elements(1)?.remove(0))
+}
+
+/// Natively compute `hash`.
+fn native_hash<CF: PrimeField + Absorb>(params: &PoseidonConfig<CF>, tag: u64, input: &[CF]) -> CF {
+    let mut sponge = PoseidonSponge::new(params);
+    sponge.absorb(&CF::from(tag));
+    sponge.absorb(&input);
+    sponge.squeeze_field_elements(1)[0]
+}
+
+fn leaf_hash<CF: PrimeField>(
+    cs: ConstraintSystemRef<CF>,
+    params: &PoseidonConfig<CF>,
+    leaf: &[FpVar<CF>],
+) -> Result<FpVar<CF>, SynthesisError> {
+    let len = FpVar::constant(CF::from(leaf.len() as u64));
+    hash(cs, params, LEAF_TAG, &[&[len], leaf].concat())
+}
+
+/// Natively compute `leaf_hash`.
+fn native_leaf_hash<CF: PrimeField + Absorb>(params: &PoseidonConfig<CF>, leaf: &[CF]) -> CF {
+    native_hash(
+        params,
+        LEAF_TAG,
+        &[&[CF::from(leaf.len() as u64)], leaf].concat(),
+    )
+}
+
+fn node_hash<CF: PrimeField>(
+    cs: ConstraintSystemRef<CF>,
+    params: &PoseidonConfig<CF>,
+    left: &FpVar<CF>,
+    right: &FpVar<CF>,
+) -> Result<FpVar<CF>, SynthesisError> {
+    hash(cs, params, NODE_TAG, &[left.clone(), right.clone()])
+}
+
+impl<CF: PrimeField> CommitteeVar<CF> {
+    /// All the levels of the tree, from the leaves up to the root.
+    fn merkle_levels(
+        &self,
+        cs: ConstraintSystemRef<CF>,
+        params: &PoseidonConfig<CF>,
+    ) -> Result<Vec<Vec<FpVar<CF>>>, SynthesisError> {
+        let padding = SignerVar::new_constant(
+            cs.clone(),
+            (AuthorityPublicKey::default(), Weight::default()),
+        )?;
+        let num_leaves = self.committee.len().next_power_of_two();
+
+        let mut level = self
+            .committee
+            .iter()
+            .chain(std::iter::repeat(&padding))
+            .take(num_leaves)
+            .map(|signer| leaf_hash(cs.clone(), params, &signer.to_constraint_field()?))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut levels = Vec::with_capacity(tree_depth(self.committee.len()) + 1);
+        while level.len() > 1 {
+            let next = level
+                .chunks_exact(2)
+                .map(|pair| node_hash(cs.clone(), params, &pair[0], &pair[1]))
+                .collect::<Result<Vec<_>, _>>()?;
+            levels.push(std::mem::replace(&mut level, next));
+        }
+        levels.push(level);
+
+        Ok(levels)
+    }
+
+    /// The root of the tree of the committee.
+    pub fn merkle_root(
+        &self,
+        cs: ConstraintSystemRef<CF>,
+        params: &PoseidonConfig<CF>,
+    ) -> Result<FpVar<CF>, SynthesisError> {
+        let mut levels = self.merkle_levels(cs, params)?;
+        Ok(levels.pop().and_then(|mut root| root.pop()).unwrap())
+    }
+}
+
+/// The siblings of a leaf, from the bottom of the tree up to the root.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MerklePath<CF: PrimeField> {
+    pub siblings: Vec<CF>,
+}
+
+#[derive(Derivative)]
+#[derivative(Clone(bound = ""), Debug(bound = ""))]
+pub struct MerklePathVar<CF: PrimeField> {
+    pub siblings: Vec<FpVar<CF>>,
+}
+
+impl<CF: PrimeField> AllocVar<MerklePath<CF>, CF> for MerklePathVar<CF> {
+    fn new_variable<T: Borrow<MerklePath<CF>>>(
+        cs: impl Into<Namespace<CF>>,
+        f: impl FnOnce() -> Result<T, SynthesisError>,
+        mode: AllocationMode,
+    ) -> Result<Self, SynthesisError> {
+        let siblings =
+            Vec::new_variable(cs, || f().map(|path| path.borrow().siblings.clone()), mode)?;
+        Ok(Self { siblings })
+    }
+}
+
+impl<CF: PrimeField> MerklePathVar<CF> {
+    /// Whether `signer` is the leaf at `index` of the tree with `root`.
+    ///
+    /// The index is a constant of the circuit, so a path cannot be reused for another leaf.
+    pub fn verify(
+        &self,
+        cs: ConstraintSystemRef<CF>,
+        params: &PoseidonConfig<CF>,
+        root: &FpVar<CF>,
+        index: usize,
+        signer: &SignerVar<CF>,
+    ) -> Result<Boolean<CF>, SynthesisError> {
+        let mut node = leaf_hash(cs.clone(), params, &signer.to_constraint_field()?)?;
+        for (level, sibling) in self.siblings.iter().enumerate() {
+            node = if (index >> level) & 1 == 0 {
+                node_hash(cs.clone(), params, &node, sibling)?
+            } else {
+                node_hash(cs.clone(), params, sibling, &node)?
+            };
+        }
+        node.is_eq(root)
+    }
+}
+
+/// The native tree of a committee.
+#[derive(Clone, Debug)]
+pub struct CommitteeTree<CF: PrimeField> {
+    levels: Vec<Vec<CF>>,
+}
+
+impl<CF: PrimeField> CommitteeTree<CF> {
+    pub fn new(committee: &Committee, params: &PoseidonConfig<CF>) -> Result<Self, SynthesisError> {
+        let cs = ConstraintSystem::new_ref();
+        let levels = CommitteeVar::new_constant(cs.clone(), committee)?
+            .merkle_levels(cs, params)?
+            .iter()
+            .map(|level| level.iter().map(R1CSVar::value).collect())
+            .collect::<Result<_, _>>()?;
+        Ok(Self { levels })
+    }
+
+    #[must_use]
+    pub fn root(&self) -> CF {
+        self.levels[self.levels.len() - 1][0]
+    }
+
+    /// The path of the signer at `index` of the committee.
+    #[must_use]
+    pub fn path(&self, index: usize) -> MerklePath<CF> {
+        MerklePath {
+            siblings: self.levels[..self.levels.len() - 1]
+                .iter()
+                .enumerate()
+                .map(|(level, nodes)| nodes[(index >> level) ^ 1])
+                .collect(),
+        }
+    }
+}
+
+impl<CF: PrimeField + Absorb> MerklePath<CF> {
+    /// Natively check that `leaf` is the leaf at `index` of the tree with `root`.
+    #[must_use]
+    pub fn verify(&self, params: &PoseidonConfig<CF>, root: CF, index: usize, leaf: &[CF]) -> bool {
+        let node = self.siblings.iter().enumerate().fold(
+            native_leaf_hash(params, leaf),
+            |node, (level, sibling)| {
+                if (index >> level) & 1 == 0 {
+                    native_hash(params, NODE_TAG, &[node, *sibling])
+                } else {
+                    native_hash(params, NODE_TAG, &[*sibling, node])
+                }
+            },
+        );
+        index >> self.siblings.len() == 0 && node == root
+    }
+}
+
+/// A native Poseidon tree with `2^DEPTH` leaves, each a list of field elements hashed like
+/// the signers of the committee tree. Missing leaves are empty lists.
+///
+/// All the nodes are kept in memory, so this is meant for small depths, e.g. a tree over
+/// the validators of a committee.
+#[derive(Clone, Debug)]
+pub struct MerkleTree<CF: PrimeField, const DEPTH: usize> {
+    levels: Vec<Vec<CF>>,
+}
+
+impl<CF: PrimeField + Absorb, const DEPTH: usize> MerkleTree<CF, DEPTH> {
+    /// The number of leaves of the tree.
+    pub const CAPACITY: usize = 1 << DEPTH;
+
+    /// # Panics
+    ///
+    /// Panics if there are more than `Self::CAPACITY` leaves.
+    #[must_use]
+    pub fn new(leaves: &[Vec<CF>], params: &PoseidonConfig<CF>) -> Self {
+        assert!(
+            leaves.len() <= Self::CAPACITY,
+            "a tree of depth {DEPTH} has at most {} leaves",
+            Self::CAPACITY
+        );
+
+        let mut level: Vec<_> = leaves
+            .iter()
+            .map(Vec::as_slice)
+            .chain(std::iter::repeat(&[][..]))
+            .take(Self::CAPACITY)
+            .map(|leaf| native_leaf_hash(params, leaf))
+            .collect();
+        let mut levels = Vec::with_capacity(DEPTH + 1);
+        while level.len() > 1 {
+            let next = level
+                .chunks_exact(2)
+                .map(|pair| native_hash(params, NODE_TAG, pair))
+                .collect();
+            levels.push(std::mem::replace(&mut level, next));
+        }
+        levels.push(level);
+
+        Self { levels }
+    }
+
+    #[must_use]
+    pub fn root(&self) -> CF {
+        self.levels[DEPTH][0]
+    }
+
+    /// The path of the leaf at `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is not less than `Self::CAPACITY`.
+    #[must_use]
+    pub fn prove(&self, index: usize) -> MerklePath<CF> {
+        assert!(index < Self::CAPACITY, "leaf {index} is out of the tree");
+        MerklePath {
+            siblings: self.levels[..DEPTH]
+                .iter()
+                .enumerate()
+                .map(|(level, nodes)| nodes[(index >> level) ^ 1])
+                .collect(),
+        }
+    }
+}
+
+/// Verification of the paths of `MerkleTree<CF, DEPTH>` in the circuit.
+pub struct MerkleTreeGadget<CF: PrimeField, const DEPTH: usize>(PhantomData<CF>);
+
+impl<CF: PrimeField, const DEPTH: usize> MerkleTreeGadget<CF, DEPTH> {
+    /// Whether `leaf` is the leaf of the tree with `root` at the index whose little-endian
+    /// bits are `index`.
+    ///
+    /// Unlike `MerklePathVar::verify`, the index is a variable, so one circuit can check the
+    /// inclusion of any leaf. Fails if `index` or `path` do not have `DEPTH` elements.
+    pub fn verify(
+        cs: ConstraintSystemRef<CF>,
+        params: &PoseidonConfig<CF>,
+        root: &FpVar<CF>,
+        index: &[Boolean<CF>],
+        leaf: &[FpVar<CF>],
+        path: &MerklePathVar<CF>,
+    ) -> Result<Boolean<CF>, SynthesisError> {
+        if index.len() != DEPTH || path.siblings.len() != DEPTH {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+
+        let mut node = leaf_hash(cs.clone(), params, leaf)?;
+        for (is_right, sibling) in index.iter().zip(&path.siblings) {
+            let left = is_right.select(sibling, &node)?;
+            let right = is_right.select(&node, sibling)?;
+            node = node_hash(cs.clone(), params, &left, &right)?;
+        }
+        node.is_eq(root)
+    }
+}
+
+/// The external inputs of `BCCircuitMerkle`: a block, and the signers of the committee
+/// signing it with their Merkle paths.
+///
+/// Only the members that signed the block need a path. The other slots are filled with
+/// signers without weight and dummy paths of the same length, which are never checked.
+#[derive(Clone, Debug)]
+pub struct MerkleBlock<CF: PrimeField> {
+    pub block: Block,
+    pub signers: Vec<((AuthorityPublicKey, Weight), MerklePath<CF>)>,
+}
+
+impl<CF: PrimeField> MerkleBlock<CF> {
+    /// Prove the signers of `block` against the tree of `committee`, the committee of the
+    /// previous block.
+    pub fn new(
+        block: Block,
+        committee: &Committee,
+        params: &PoseidonConfig<CF>,
+    ) -> Result<Self, SynthesisError> {
+        let tree = CommitteeTree::new(committee, params)?;
+        let dummy = MerklePath {
+            siblings: vec![CF::zero(); tree_depth(committee.signers.len())],
+        };
+
+        let signers = block
+            .sig
+            .signers
+            .iter()
+            .zip(&committee.signers)
+            .enumerate()
+            .map(|(i, (signed, signer))| {
+                if *signed {
+                    (*signer, tree.path(i))
+                } else {
+                    (Default::default(), dummy.clone())
+                }
+            })
+            .collect();
+
+        Ok(Self { block, signers })
+    }
+
+    /// The placeholder block whose signers have no weight and dummy paths, which has the
+    /// fixed size of `BCCircuitMerkle` for `config`: `config.max_committee_size` signers,
+    /// each with a path of `tree_depth(config.max_committee_size)` siblings.
+    #[must_use]
+    pub fn placeholder(config: &BcConfig) -> Self {
+        let dummy = MerklePath {
+            siblings: vec![CF::zero(); tree_depth(config.max_committee_size)],
+        };
+        Self {
+            block: Block::placeholder(config),
+            signers: vec![(Default::default(), dummy); config.max_committee_size],
+        }
+    }
+}
+
+#[derive(Derivative)]
+#[derivative(Clone(bound = ""), Debug(bound = ""))]
+pub struct MerkleBlockVar<CF: PrimeField> {
+    pub block: BlockVar<CF>,
+    pub signers: Vec<(SignerVar<CF>, MerklePathVar<CF>)>,
+}
+
+impl<CF: PrimeField> AllocVar<MerkleBlock<CF>, CF> for MerkleBlockVar<CF> {
+    fn new_variable<T: Borrow<MerkleBlock<CF>>>(
+        cs: impl Into<Namespace<CF>>,
+        f: impl FnOnce() -> Result<T, SynthesisError>,
+        mode: AllocationMode,
+    ) -> Result<Self, SynthesisError> {
+        let cs = cs.into().cs();
+        let merkle_block = f();
+
+        let block = BlockVar::new_variable(
+            cs.clone(),
+            || {
+                merkle_block
+                    .as_ref()
+                    .map(|value| value.borrow().block.clone())
+                    .map_err(SynthesisError::clone)
+            },
+            mode,
+        )?;
+
+        // the number of signers is not known here, so `BCCircuitMerkle` allocates a
+        // placeholder of the size of its `BcConfig` when there is no value, and checks the
+        // value against it otherwise
+        let signers = merkle_block
+            .as_ref()
+            .map_err(SynthesisError::clone)?
+            .borrow()
+            .signers
+            .iter()
+            .map(|(signer, path)| {
+                Ok((
+                    SignerVar::new_variable(cs.clone(), || Ok(signer), mode)?,
+                    MerklePathVar::new_variable(cs.clone(), || Ok(path), mode)?,
+                ))
+            })
+            .collect::<Result<_, SynthesisError>>()?;
+
+        Ok(Self { block, signers })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ark_ff::{UniformRand, Zero};
+    use ark_r1cs_std::{alloc::AllocVar, fields::fp::FpVar, prelude::Boolean, R1CSVar};
+    use ark_relations::r1cs::ConstraintSystem;
+    use rand::thread_rng;
+
+    use crate::{
+        bc::testgen::ChainGenerator,
+        folding::bc::{CommitteeVar, SignerVar},
+        hash::poseidon::poseidon_config,
+        params::{BlsSigConfig, BlsSigField},
+    };
+
+    use super::{tree_depth, CommitteeTree, MerklePathVar, MerkleTree, MerkleTreeGadget};
+
+    type CF = BlsSigField<BlsSigConfig>;
+
+    #[test]
+    fn test_committee_tree() {
+        let params = poseidon_config::<CF>();
+        let (blocks, _) = ChainGenerator::new(2, 4).generate(&mut thread_rng());
+        let committee = &blocks[0].committee;
+        let tree = CommitteeTree::<CF>::new(committee, &params).unwrap();
+
+        let cs = ConstraintSystem::new_ref();
+        let committee_var = CommitteeVar::new_witness(cs.clone(), || Ok(committee)).unwrap();
+        let root = committee_var.merkle_root(cs.clone(), &params).unwrap();
+        assert_eq!(root.value().unwrap(), tree.root());
+
+        for i in [0, 3, committee.signers.len() - 1] {
+            let path = tree.path(i);
+            assert_eq!(path.siblings.len(), tree_depth(committee.signers.len()));
+
+            let path = MerklePathVar::new_witness(cs.clone(), || Ok(path)).unwrap();
+            let signer = SignerVar::new_witness(cs.clone(), || Ok(committee.signers[i])).unwrap();
+            assert!(path
+                .verify(cs.clone(), &params, &root, i, &signer)
+                .unwrap()
+                .value()
+                .unwrap());
+            // the path is bound to its index (the padding signers are all the same)
+            assert!(
+                i >= 4
+                    || !path
+                        .verify(cs.clone(), &params, &root, i ^ 1, &signer)
+                        .unwrap()
+                        .value()
+                        .unwrap()
+            );
+        }
+        assert!(cs.is_satisfied().unwrap());
+
+        let other = CommitteeTree::<CF>::new(&blocks[1].committee, &params).unwrap();
+        assert_ne!(other.root(), tree.root());
+    }
+
+    #[test]
+    fn test_merkle_tree() {
+        const DEPTH: usize = 3;
+
+        let params = poseidon_config::<CF>();
+        let mut rng = thread_rng();
+        let leaves: Vec<Vec<CF>> = (0..5)
+            .map(|i| (0..=i).map(|_| CF::rand(&mut rng)).collect())
+            .collect();
+        let tree = MerkleTree::<CF, DEPTH>::new(&leaves, &params);
+
+        // the leaves after the fifth are empty
+        for (i, leaf) in leaves.iter().chain(&[vec![]]).enumerate() {
+            let path = tree.prove(i);
+            assert_eq!(path.siblings.len(), DEPTH);
+            assert!(path.verify(&params, tree.root(), i, leaf));
+            assert!(!path.verify(&params, tree.root(), i ^ 1, leaf));
+            assert!(!path.verify(
+                &params,
+                tree.root(),
+                i + MerkleTree::<CF, DEPTH>::CAPACITY,
+                leaf
+            ));
+
+            let cs = ConstraintSystem::new_ref();
+            let root = FpVar::new_input(cs.clone(), || Ok(tree.root())).unwrap();
+            let leaf_var: Vec<FpVar<CF>> =
+                Vec::new_witness(cs.clone(), || Ok(leaf.clone())).unwrap();
+            let path_var = MerklePathVar::new_witness(cs.clone(), || Ok(path)).unwrap();
+            for (index, expected) in [(i, true), (i ^ 1, false)] {
+                let index_var: Vec<Boolean<CF>> = Vec::new_witness(cs.clone(), || {
+                    Ok((0..DEPTH)
+                        .map(|j| (index >> j) & 1 == 1)
+                        .collect::<Vec<_>>())
+                })
+                .unwrap();
+                let valid = MerkleTreeGadget::<CF, DEPTH>::verify(
+                    cs.clone(),
+                    &params,
+                    &root,
+                    &index_var,
+                    &leaf_var,
+                    &path_var,
+                )
+                .unwrap();
+                assert_eq!(valid.value().unwrap(), expected);
+            }
+            assert!(MerkleTreeGadget::<CF, DEPTH>::verify(
+                cs.clone(),
+                &params,
+                &root,
+                &[Boolean::FALSE; DEPTH - 1],
+                &leaf_var,
+                &path_var,
+            )
+            .is_err());
+            assert!(cs.is_satisfied().unwrap());
+        }
+
+        // trailing zeros change the leaf, so the padding leaves cannot be opened to `[0]`
+        let path = tree.prove(0);
+        assert!(!path.verify(
+            &params,
+            tree.root(),
+            0,
+            &[&leaves[0][..], &[CF::zero()]].concat()
+        ));
+        let path = tree.prove(leaves.len());
+        assert!(!path.verify(&params, tree.root(), leaves.len(), &[CF::zero()]));
+    }
+}
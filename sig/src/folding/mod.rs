@@ -1,6 +1,22 @@
-mod serialize;
+pub(crate) mod serialize;
 
+#[cfg(feature = "ethereum")]
+pub mod adapters;
+pub mod backend;
 pub mod bc;
 pub mod circuit;
+pub mod commitment;
+pub mod compressed;
+#[cfg(feature = "evm")]
+pub mod decider;
 pub mod from_constraint_field;
+pub mod hint;
+pub mod input;
+pub mod light_client;
+pub mod merkle;
+pub mod parallel;
+pub mod profiler;
+pub mod session;
+pub mod state;
+pub mod sync;
 pub mod to_constraint_field;
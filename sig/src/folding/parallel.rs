@@ -0,0 +1,211 @@
+//! Proving disjoint ranges of a chain on different threads, one IVC proof per range.
+//!
+//! Each worker folds its range with Nova from the state reached at the block preceding the
+//! range, and the result is one IVC proof per range, which `RangeProofs::verify` chains by
+//! checking that every range starts from the state the previous one reached.
+//!
+//! Merging the ranges into a single IVC proof is out of scope: it needs a PCD scheme that
+//! folds two running instances with different initial states, which sonobe does not have.
+//! The proof of a chain folded in `n` ranges is therefore `n` times larger, and so is the
+//! time to verify it.
+
+use std::thread;
+
+use derivative::Derivative;
+use folding_schemes::{folding::nova::IVCProof, Error, FoldingScheme};
+use rand::{rngs::StdRng, RngCore, SeedableRng};
+
+use crate::{
+    bc::{block::Block, checkpoint::Checkpoint},
+    params::{CurveCycleConfig, CycleField},
+};
+
+use super::circuit::BCCircuitNoMerkle;
+
+/// The IVC proofs of consecutive ranges of a chain folded over the cycle `C`, in order.
+#[derive(Derivative)]
+#[derivative(
+    Clone(bound = ""),
+    Debug(bound = ""),
+    PartialEq(bound = ""),
+    Eq(bound = "")
+)]
+pub struct RangeProofs<C: CurveCycleConfig> {
+    pub proofs: Vec<IVCProof<C::C1, C::C2>>,
+}
+
+/// Prove `blocks[1..]` starting from the trusted `blocks[0]`, splitting the blocks into
+/// `num_workers` ranges of about the same length that are folded in parallel, each into its
+/// own IVC proof. The proofs are not merged.
+///
+/// `FS` is Nova, whose IVC proofs expose their initial state, and `params` are the output
+/// of `FS::preprocess` for `circuit`. The blocks are not checked natively, so an invalid
+/// block makes the worker folding it fail.
+pub fn prove_ranges_parallel<C, FS>(
+    params: &(FS::ProverParam, FS::VerifierParam),
+    circuit: BCCircuitNoMerkle<C>,
+    blocks: &[Block],
+    num_workers: usize,
+    rng: &mut impl RngCore,
+) -> Result<RangeProofs<C>, Error>
+where
+    C: CurveCycleConfig,
+    FS: FoldingScheme<C::C1, C::C2, BCCircuitNoMerkle<C>, IVCProof = IVCProof<C::C1, C::C2>>,
+    FS::ProverParam: Sync,
+    FS::VerifierParam: Sync,
+    IVCProof<C::C1, C::C2>: Send,
+{
+    let steps = blocks.len().saturating_sub(1);
+    if steps == 0 || num_workers == 0 {
+        return Err(Error::Other(
+            "there should be at least one block to fold and one worker".to_string(),
+        ));
+    }
+
+    // the first `steps % num_workers` ranges get one more block
+    let num_workers = num_workers.min(steps);
+    let (len, rem) = (steps / num_workers, steps % num_workers);
+    let mut ranges = Vec::with_capacity(num_workers);
+    let mut start = 0;
+    for j in 0..num_workers {
+        let end = start + len + usize::from(j < rem);
+        let mut seed = [0; 32];
+        rng.fill_bytes(&mut seed);
+        ranges.push((&blocks[start..=end], seed));
+        start = end;
+    }
+
+    let proofs = thread::scope(|s| {
+        let workers: Vec<_> = ranges
+            .into_iter()
+            .map(|(range, seed)| s.spawn(move || fold_range::<C, FS>(params, circuit, range, seed)))
+            .collect();
+        workers
+            .into_iter()
+            .map(|worker| worker.join().expect("folding worker panicked"))
+            .collect::<Result<Vec<_>, _>>()
+    })?;
+
+    Ok(RangeProofs { proofs })
+}
+
+/// Fold `range[1..]` from `range[0]`.
+fn fold_range<C, FS>(
+    params: &(FS::ProverParam, FS::VerifierParam),
+    circuit: BCCircuitNoMerkle<C>,
+    range: &[Block],
+    seed: [u8; 32],
+) -> Result<IVCProof<C::C1, C::C2>, Error>
+where
+    C: CurveCycleConfig,
+    FS: FoldingScheme<C::C1, C::C2, BCCircuitNoMerkle<C>, IVCProof = IVCProof<C::C1, C::C2>>,
+{
+    let mut rng = StdRng::from_seed(seed);
+    let z_0 = circuit.initial_state(&Checkpoint::from_block(&range[0]), &range[0].committee)?;
+    let mut folding = FS::init(params, circuit, z_0)?;
+    for block in &range[1..] {
+        folding.prove_step(&mut rng, Some(block.clone()), None)?;
+    }
+    Ok(folding.ivc_proof())
+}
+
+impl<C: CurveCycleConfig> RangeProofs<C> {
+    /// Verify every proof against `vp`, and that each range starts from the state the
+    /// previous one reached, so that together they prove the whole chain.
+    pub fn verify<FS>(&self, vp: &FS::VerifierParam) -> Result<(), Error>
+    where
+        FS: FoldingScheme<C::C1, C::C2, BCCircuitNoMerkle<C>, IVCProof = IVCProof<C::C1, C::C2>>,
+    {
+        if self.proofs.is_empty() {
+            return Err(Error::Other("there is no range to verify".to_string()));
+        }
+        if self.proofs.windows(2).any(|w| w[0].z_i != w[1].z_0) {
+            return Err(Error::Other(
+                "a range does not start from the state reached by the previous one".to_string(),
+            ));
+        }
+        for proof in &self.proofs {
+            FS::verify(vp.clone(), proof.clone())?;
+        }
+
+        Ok(())
+    }
+
+    /// The state the first range starts from.
+    #[must_use]
+    pub fn initial_state(&self) -> &[CycleField<C>] {
+        self.proofs.first().map_or(&[], |proof| &proof.z_0)
+    }
+
+    /// The state the last range reaches.
+    #[must_use]
+    pub fn state(&self) -> &[CycleField<C>] {
+        self.proofs.last().map_or(&[], |proof| &proof.z_i)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ark_mnt4_753::{Fr, G1Projective as G1, MNT4_753 as MNT4};
+    use ark_mnt6_753::{G1Projective as G2, MNT6_753 as MNT6};
+    use folding_schemes::{
+        commitment::kzg::KZG,
+        folding::nova::{Nova, PreprocessorParam},
+        frontend::FCircuit,
+        transcript::poseidon::poseidon_canonical_config,
+        FoldingScheme,
+    };
+    use rand::thread_rng;
+
+    use crate::{
+        bc::{block::Block, checkpoint::Checkpoint, params::BcConfig, testgen::ChainGenerator},
+        folding::circuit::BCCircuitParams,
+    };
+
+    use super::{prove_ranges_parallel, BCCircuitNoMerkle};
+
+    type FC = BCCircuitNoMerkle<(G1, G2)>;
+    type N = Nova<G1, G2, FC, KZG<'static, MNT4>, KZG<'static, MNT6>, false>;
+
+    #[test]
+    #[ignore = "folding over MNT4/MNT6 takes a long time to finish running"]
+    fn test_prove_two_ranges() {
+        let params = BCCircuitParams {
+            config: BcConfig {
+                max_committee_size: 4,
+                ..Default::default()
+            },
+            ..BCCircuitParams::setup()
+        };
+        let mut rng = thread_rng();
+        let (blocks, _) = ChainGenerator::new(5, 4)
+            .config(params.config)
+            .generate(&mut rng);
+        let circuit = FC::new(params).unwrap();
+        let nova_params = N::preprocess(
+            &mut rng,
+            &PreprocessorParam::new(poseidon_canonical_config::<Fr>(), circuit),
+        )
+        .unwrap();
+
+        let ranges =
+            prove_ranges_parallel::<(G1, G2), N>(&nova_params, circuit, &blocks, 2, &mut rng)
+                .unwrap();
+        assert_eq!(ranges.proofs.len(), 2);
+        ranges.verify::<N>(&nova_params.1).unwrap();
+
+        // the ranges prove the whole chain, from the trusted block to the last one
+        let initial_state = |block: &Block| {
+            circuit
+                .initial_state(&Checkpoint::from_block(block), &block.committee)
+                .unwrap()
+        };
+        assert_eq!(ranges.initial_state(), initial_state(&blocks[0]));
+        assert_eq!(ranges.proofs[1].z_0, initial_state(&blocks[2]));
+
+        // ranges that do not follow each other are rejected
+        let mut swapped = ranges.clone();
+        swapped.proofs.reverse();
+        assert!(swapped.verify::<N>(&nova_params.1).is_err());
+    }
+}
@@ -0,0 +1,336 @@
+//! Per-region constraint counts, to catch regressions in the cost of the circuits.
+//!
+//! Regions are labelled parts of the synthesis, e.g. hashing to the curve or the pairing
+//! check. Their cost is compared against budgets, so that a change making a region more
+//! expensive than its budget plus some tolerance fails the tests measuring it.
+
+use core::fmt;
+
+use ark_ff::PrimeField;
+use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
+
+/// The number of constraints and witness variables added by a region.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RegionCost {
+    pub constraints: usize,
+    pub witnesses: usize,
+}
+
+impl RegionCost {
+    #[must_use]
+    pub const fn new(constraints: usize, witnesses: usize) -> Self {
+        Self {
+            constraints,
+            witnesses,
+        }
+    }
+
+    /// Whether `self` is more than `tolerance_percent` percent above `budget` in either
+    /// count.
+    #[must_use]
+    pub const fn exceeds(&self, budget: &Self, tolerance_percent: usize) -> bool {
+        const fn allowed(budget: usize, tolerance_percent: usize) -> usize {
+            budget + budget * tolerance_percent / 100
+        }
+
+        self.constraints > allowed(budget.constraints, tolerance_percent)
+            || self.witnesses > allowed(budget.witnesses, tolerance_percent)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ProfileError {
+    /// No region with this label was profiled.
+    UnknownRegion(&'static str),
+    /// The region costs more than its budget plus the tolerance.
+    Regression {
+        label: &'static str,
+        cost: RegionCost,
+        budget: RegionCost,
+    },
+}
+
+impl fmt::Display for ProfileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownRegion(label) => write!(f, "region {label:?} was not profiled"),
+            Self::Regression {
+                label,
+                cost,
+                budget,
+            } => write!(
+                f,
+                "region {label:?} regressed: {} constraints and {} witnesses, \
+                 over a budget of {} constraints and {} witnesses",
+                cost.constraints, cost.witnesses, budget.constraints, budget.witnesses
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ProfileError {}
+
+/// Wraps a `ConstraintSystemRef` and records the cost of each region synthesized through
+/// `region`.
+///
+/// A label profiled several times accumulates the cost of every region. Nested regions
+/// count toward both the inner and the outer label.
+pub struct ConstraintProfiler<CF: PrimeField> {
+    cs: ConstraintSystemRef<CF>,
+    tolerance_percent: usize,
+    regions: Vec<(&'static str, RegionCost)>,
+}
+
+impl<CF: PrimeField> ConstraintProfiler<CF> {
+    #[must_use]
+    pub const fn new(cs: ConstraintSystemRef<CF>) -> Self {
+        Self {
+            cs,
+            tolerance_percent: 0,
+            regions: Vec::new(),
+        }
+    }
+
+    /// Allow the regions to cost up to `tolerance_percent` percent more than their budget.
+    #[must_use]
+    pub const fn tolerance(mut self, tolerance_percent: usize) -> Self {
+        self.tolerance_percent = tolerance_percent;
+        self
+    }
+
+    #[must_use]
+    pub fn cs(&self) -> ConstraintSystemRef<CF> {
+        self.cs.clone()
+    }
+
+    /// Synthesize `f` and record the constraints and witnesses it adds under `label`.
+    pub fn region<T>(
+        &mut self,
+        label: &'static str,
+        f: impl FnOnce(ConstraintSystemRef<CF>) -> Result<T, SynthesisError>,
+    ) -> Result<T, SynthesisError> {
+        let constraints = self.cs.num_constraints();
+        let witnesses = self.cs.num_witness_variables();
+
+        let value = f(self.cs.clone())?;
+
+        let cost = RegionCost::new(
+            self.cs.num_constraints() - constraints,
+            self.cs.num_witness_variables() - witnesses,
+        );
+        match self.regions.iter_mut().find(|(l, _)| *l == label) {
+            Some((_, total)) => {
+                total.constraints += cost.constraints;
+                total.witnesses += cost.witnesses;
+            }
+            None => self.regions.push((label, cost)),
+        }
+
+        Ok(value)
+    }
+
+    /// The cost recorded under `label`, if it was profiled.
+    #[must_use]
+    pub fn cost(&self, label: &str) -> Option<RegionCost> {
+        self.regions
+            .iter()
+            .find_map(|(l, cost)| (*l == label).then_some(*cost))
+    }
+
+    /// The profiled regions, in the order they were first synthesized.
+    pub fn regions(&self) -> impl Iterator<Item = (&'static str, RegionCost)> + '_ {
+        self.regions.iter().copied()
+    }
+
+    /// Check every region of `budgets` against its budget, up to the tolerance.
+    pub fn check(&self, budgets: &[(&'static str, RegionCost)]) -> Result<(), ProfileError> {
+        for &(label, budget) in budgets {
+            let cost = self.cost(label).ok_or(ProfileError::UnknownRegion(label))?;
+            if cost.exceeds(&budget, self.tolerance_percent) {
+                return Err(ProfileError::Regression {
+                    label,
+                    cost,
+                    budget,
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<CF: PrimeField> fmt::Display for ConstraintProfiler<CF> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (label, cost) in &self.regions {
+            writeln!(
+                f,
+                "{label}: {} constraints, {} witnesses",
+                cost.constraints, cost.witnesses
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ark_r1cs_std::{
+        alloc::AllocVar,
+        eq::EqGadget,
+        fields::{fp::FpVar, FieldVar},
+        groups::{
+            bls12::{G1PreparedVar, G2PreparedVar},
+            CurveVar,
+        },
+        pairing::{bls12, PairingVar},
+        uint8::UInt8,
+        R1CSVar,
+    };
+    use ark_relations::r1cs::ConstraintSystem;
+    use rand::thread_rng;
+
+    use crate::{
+        bc::{params::BcConfig, testgen::ChainGenerator},
+        bls::{
+            BLSMinSigVerifyGadget, MinSig, MinSigPublicKeyVar, MinSigSignatureVar, Parameters,
+            ParametersVar, PublicKey, SecretKey, Signature,
+        },
+        folding::{bc::CommitteeVar, serialize::SerializeGadget, state::StateSerde},
+        params::{BlsSigConfig, BlsSigField},
+    };
+
+    use super::{ConstraintProfiler, ProfileError, RegionCost};
+
+    type CF = BlsSigField<BlsSigConfig>;
+
+    #[test]
+    fn test_check() {
+        let cs = ConstraintSystem::<CF>::new_ref();
+        let mut profiler = ConstraintProfiler::new(cs).tolerance(10);
+
+        for _ in 0..2 {
+            profiler
+                .region("square", |cs| {
+                    let x = FpVar::new_witness(cs, || Ok(CF::from(3u64)))?;
+                    x.square().map(drop)
+                })
+                .unwrap();
+        }
+        assert_eq!(profiler.cost("square"), Some(RegionCost::new(2, 4)));
+
+        assert_eq!(profiler.check(&[("square", RegionCost::new(2, 4))]), Ok(()));
+        assert_eq!(
+            profiler.check(&[("square", RegionCost::new(1, 4))]),
+            Err(ProfileError::Regression {
+                label: "square",
+                cost: RegionCost::new(2, 4),
+                budget: RegionCost::new(1, 4),
+            })
+        );
+        assert_eq!(
+            profiler.check(&[("cube", RegionCost::default())]),
+            Err(ProfileError::UnknownRegion("cube"))
+        );
+        // within the tolerance
+        assert!(!RegionCost::new(110, 0).exceeds(&RegionCost::new(100, 0), 10));
+        assert!(RegionCost::new(110, 0).exceeds(&RegionCost::new(100, 0), 5));
+    }
+
+    #[test]
+    fn test_committee_regions() {
+        let config = BcConfig {
+            max_committee_size: 4,
+            ..Default::default()
+        };
+        let (blocks, _) = ChainGenerator::new(1, 4)
+            .config(config)
+            .generate(&mut thread_rng());
+
+        let committee = &blocks[0].committee;
+
+        let cs = ConstraintSystem::<CF>::new_ref();
+        let z_i = CommitteeVar::new_constant(cs.clone(), committee)
+            .unwrap()
+            .to_state()
+            .unwrap()
+            .iter()
+            .map(|x| FpVar::new_witness(cs.clone(), || x.value()))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let committee_var: CommitteeVar<CF> =
+            CommitteeVar::new_witness(cs.clone(), || Ok(committee)).unwrap();
+
+        let mut profiler = ConstraintProfiler::new(cs.clone()).tolerance(5);
+        profiler
+            .region("committee reconstruction", |_| {
+                CommitteeVar::from_state(z_i.into_iter(), config)
+            })
+            .unwrap();
+        profiler
+            .region("serialization", |_| committee_var.serialize())
+            .unwrap();
+        assert!(cs.is_satisfied().unwrap());
+
+        println!("{profiler}");
+        profiler
+            .check(&[
+                ("committee reconstruction", RegionCost::new(260, 256)),
+                ("serialization", RegionCost::new(29_728, 28_224)),
+            ])
+            .unwrap();
+    }
+
+    #[test]
+    fn test_signature_regions() {
+        type Config = ark_bls12_381::Config;
+        type F = BlsSigField<Config>;
+
+        let msg = "Hello World";
+        let params = Parameters::<Config>::setup();
+        let sk = SecretKey::new(&mut thread_rng());
+        let pk = PublicKey::<Config, MinSig>::new(&sk, &params);
+        let sig = Signature::<Config, MinSig>::sign(msg.as_bytes(), &sk, &params);
+
+        let cs = ConstraintSystem::<F>::new_ref();
+        let msg_var = UInt8::new_witness_vec(cs.clone(), msg.as_bytes()).unwrap();
+        let params_var: ParametersVar<Config, FpVar<F>, F> =
+            ParametersVar::new_constant(cs.clone(), params).unwrap();
+        let pk_var = MinSigPublicKeyVar::new_witness(cs.clone(), || Ok(pk)).unwrap();
+        let sig_var = MinSigSignatureVar::new_witness(cs.clone(), || Ok(sig)).unwrap();
+
+        let mut profiler = ConstraintProfiler::new(cs.clone()).tolerance(5);
+        let hash = profiler
+            .region("hash-to-curve", |_| {
+                BLSMinSigVerifyGadget::<Config, FpVar<F>, F>::hash_to_curve(
+                    &msg_var,
+                    &params_var.dst::<MinSig>()?,
+                )
+            })
+            .unwrap();
+        profiler
+            .region("pairing", |_| {
+                // e(sig, -g2) * e(H(m), pk) == 1, as in `BLSMinSigVerifyGadget::verify`
+                let prod = bls12::PairingVar::<Config, FpVar<F>, F>::product_of_pairings(
+                    &[
+                        G1PreparedVar::from_group_var(&sig_var.signature)?,
+                        G1PreparedVar::from_group_var(&hash)?,
+                    ],
+                    &[
+                        G2PreparedVar::from_group_var(&params_var.g2_generator.negate()?)?,
+                        G2PreparedVar::from_group_var(&pk_var.pub_key)?,
+                    ],
+                )?;
+                prod.enforce_equal(&FieldVar::one())
+            })
+            .unwrap();
+        assert!(cs.is_satisfied().unwrap());
+
+        println!("{profiler}");
+        profiler
+            .check(&[
+                ("hash-to-curve", RegionCost::new(199_395, 195_908)),
+                ("pairing", RegionCost::new(15_395, 15_382)),
+            ])
+            .unwrap();
+    }
+}
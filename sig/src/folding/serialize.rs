@@ -1,6 +1,7 @@
+use ark_ec::bls12::Bls12Config;
 use ark_ff::PrimeField;
 use ark_r1cs_std::{
-    fields::emulated_fp::EmulatedFpVar,
+    fields::{FieldOpsBounds, FieldVar},
     prelude::{Boolean, ToBytesGadget},
     uint64::UInt64,
     uint8::UInt8,
@@ -9,10 +10,13 @@ use ark_relations::r1cs::SynthesisError;
 
 use crate::{
     bls::{PublicKeyVar, SignatureVar},
-    params::{BlsSigConfig, BlsSigField},
+    params::BlsSigField,
 };
 
-use super::bc::{BlockVar, CommitteeVar, QuorumSignatureVar, SignerVar};
+use super::{
+    bc::{BlockVar, CommitteeVar, QuorumSignatureVar, SignerVar},
+    compressed::{CompressedCommitteeVar, SIGNER_BYTES},
+};
 
 /// Serialize a R1CS variable to a canonical byte representation
 /// Implementation should match the result of `bincode::serialize`.
@@ -46,16 +50,26 @@ impl<CF: PrimeField> SerializeGadget<CF> for [UInt8<CF>] {
     }
 }
 
-impl<CF: PrimeField> SerializeGadget<CF>
-    for SignatureVar<BlsSigConfig, EmulatedFpVar<BlsSigField<BlsSigConfig>, CF>, CF>
+impl<
+        CF: PrimeField,
+        SigCurveConfig: Bls12Config,
+        FV: FieldVar<BlsSigField<SigCurveConfig>, CF>,
+    > SerializeGadget<CF> for SignatureVar<SigCurveConfig, FV, CF>
+where
+    for<'a> &'a FV: FieldOpsBounds<'a, BlsSigField<SigCurveConfig>, FV>,
 {
     fn serialize(&self) -> Result<Vec<UInt8<CF>>, SynthesisError> {
         self.signature.to_bytes_le()
     }
 }
 
-impl<CF: PrimeField> SerializeGadget<CF>
-    for PublicKeyVar<BlsSigConfig, EmulatedFpVar<BlsSigField<BlsSigConfig>, CF>, CF>
+impl<
+        CF: PrimeField,
+        SigCurveConfig: Bls12Config,
+        FV: FieldVar<BlsSigField<SigCurveConfig>, CF>,
+    > SerializeGadget<CF> for PublicKeyVar<SigCurveConfig, FV, CF>
+where
+    for<'a> &'a FV: FieldOpsBounds<'a, BlsSigField<SigCurveConfig>, FV>,
 {
     fn serialize(&self) -> Result<Vec<UInt8<CF>>, SynthesisError> {
         self.pub_key.to_bytes_le()
@@ -66,7 +80,14 @@ impl<CF: PrimeField> SerializeGadget<CF>
 `.to_bytes_le()` should not exist after this line
 */
 
-impl<CF: PrimeField> SerializeGadget<CF> for SignerVar<CF> {
+impl<
+        CF: PrimeField,
+        SigCurveConfig: Bls12Config,
+        FV: FieldVar<BlsSigField<SigCurveConfig>, CF>,
+    > SerializeGadget<CF> for SignerVar<CF, SigCurveConfig, FV>
+where
+    for<'a> &'a FV: FieldOpsBounds<'a, BlsSigField<SigCurveConfig>, FV>,
+{
     fn serialize(&self) -> Result<Vec<UInt8<CF>>, SynthesisError> {
         let mut pk = self.pk.serialize()?;
         let weight = self.weight.serialize()?;
@@ -75,7 +96,14 @@ impl<CF: PrimeField> SerializeGadget<CF> for SignerVar<CF> {
     }
 }
 
-impl<CF: PrimeField> SerializeGadget<CF> for [SignerVar<CF>] {
+impl<
+        CF: PrimeField,
+        SigCurveConfig: Bls12Config,
+        FV: FieldVar<BlsSigField<SigCurveConfig>, CF>,
+    > SerializeGadget<CF> for [SignerVar<CF, SigCurveConfig, FV>]
+where
+    for<'a> &'a FV: FieldOpsBounds<'a, BlsSigField<SigCurveConfig>, FV>,
+{
     fn serialize(&self) -> Result<Vec<UInt8<CF>>, SynthesisError> {
         self.iter()
             .map(|v| v.serialize())
@@ -84,7 +112,14 @@ impl<CF: PrimeField> SerializeGadget<CF> for [SignerVar<CF>] {
     }
 }
 
-impl<CF: PrimeField> SerializeGadget<CF> for QuorumSignatureVar<CF> {
+impl<
+        CF: PrimeField,
+        SigCurveConfig: Bls12Config,
+        FV: FieldVar<BlsSigField<SigCurveConfig>, CF>,
+    > SerializeGadget<CF> for QuorumSignatureVar<CF, SigCurveConfig, FV>
+where
+    for<'a> &'a FV: FieldOpsBounds<'a, BlsSigField<SigCurveConfig>, FV>,
+{
     fn serialize(&self) -> Result<Vec<UInt8<CF>>, SynthesisError> {
         let mut sig = self.sig.serialize()?;
         // `bincode` serializes `usize` as `u64`
@@ -97,7 +132,14 @@ impl<CF: PrimeField> SerializeGadget<CF> for QuorumSignatureVar<CF> {
     }
 }
 
-impl<CF: PrimeField> SerializeGadget<CF> for CommitteeVar<CF> {
+impl<
+        CF: PrimeField,
+        SigCurveConfig: Bls12Config,
+        FV: FieldVar<BlsSigField<SigCurveConfig>, CF>,
+    > SerializeGadget<CF> for CommitteeVar<CF, SigCurveConfig, FV>
+where
+    for<'a> &'a FV: FieldOpsBounds<'a, BlsSigField<SigCurveConfig>, FV>,
+{
     fn serialize(&self) -> Result<Vec<UInt8<CF>>, SynthesisError> {
         // `bincode` serializes `usize` as `u64`
         let mut committee_len = UInt64::constant(self.committee.len() as u64).serialize()?;
@@ -108,7 +150,25 @@ impl<CF: PrimeField> SerializeGadget<CF> for CommitteeVar<CF> {
     }
 }
 
-impl<CF: PrimeField> SerializeGadget<CF> for BlockVar<CF> {
+/// The bytes are already serialized, so this only prefixes them with the committee length.
+impl<CF: PrimeField> SerializeGadget<CF> for CompressedCommitteeVar<CF> {
+    fn serialize(&self) -> Result<Vec<UInt8<CF>>, SynthesisError> {
+        // `bincode` serializes `usize` as `u64`
+        let mut committee_len =
+            UInt64::constant((self.bytes.len() / SIGNER_BYTES) as u64).serialize()?;
+        committee_len.extend_from_slice(&self.bytes);
+        Ok(committee_len)
+    }
+}
+
+impl<
+        CF: PrimeField,
+        SigCurveConfig: Bls12Config,
+        FV: FieldVar<BlsSigField<SigCurveConfig>, CF>,
+    > SerializeGadget<CF> for BlockVar<CF, SigCurveConfig, FV>
+where
+    for<'a> &'a FV: FieldOpsBounds<'a, BlsSigField<SigCurveConfig>, FV>,
+{
     fn serialize(&self) -> Result<Vec<UInt8<CF>>, SynthesisError> {
         let mut epoch = self.epoch.serialize()?;
         let prev_digest = self.prev_digest.serialize()?;
@@ -132,7 +192,7 @@ mod test {
         bc::block::{Block, Committee, QuorumSignature},
         bls::{Parameters, PublicKey, SecretKey, Signature, SignatureVar},
         folding::bc::{BlockVar, CommitteeVar, QuorumSignatureVar, SignerVar},
-        params::{BlsSigConfig, BlsSigField},
+        params::{BlsSigConfig, BlsSigField, BlsSigFieldVar},
     };
 
     use super::SerializeGadget;
@@ -179,7 +239,8 @@ mod test {
         let cs = ConstraintSystem::<CF>::new_ref();
 
         let x = Signature::default();
-        let xv = SignatureVar::new_constant(cs, x).unwrap();
+        let xv: SignatureVar<BlsSigConfig, BlsSigFieldVar<CF>, CF> =
+            SignatureVar::new_constant(cs, x).unwrap();
 
         let xs = bincode::serialize(&x).unwrap();
         let xvs: Vec<u8> = xv
@@ -197,7 +258,7 @@ mod test {
         let cs = ConstraintSystem::<CF>::new_ref();
 
         let x = QuorumSignature::default();
-        let xv = QuorumSignatureVar::new_constant(cs, x.clone()).unwrap();
+        let xv: QuorumSignatureVar<CF> = QuorumSignatureVar::new_constant(cs, x.clone()).unwrap();
 
         let xs = bincode::serialize(&x).unwrap();
         let xvs: Vec<u8> = xv
@@ -218,7 +279,7 @@ mod test {
             PublicKey::new(&SecretKey::default(), &Parameters::setup()),
             42,
         );
-        let xv = SignerVar::new_constant(cs, x.clone()).unwrap();
+        let xv: SignerVar<CF> = SignerVar::new_constant(cs, x.clone()).unwrap();
 
         let xs = bincode::serialize(&x).unwrap();
         let xvs: Vec<u8> = xv
@@ -236,7 +297,7 @@ mod test {
         let cs = ConstraintSystem::<CF>::new_ref();
 
         let x = Committee::default();
-        let xv = CommitteeVar::new_constant(cs, x.clone()).unwrap();
+        let xv: CommitteeVar<CF> = CommitteeVar::new_constant(cs, x.clone()).unwrap();
 
         let xs = bincode::serialize(&x).unwrap();
         let xvs: Vec<u8> = xv
@@ -254,7 +315,7 @@ mod test {
         let cs = ConstraintSystem::<CF>::new_ref();
 
         let x = Block::default();
-        let xv = BlockVar::new_constant(cs, x.clone()).unwrap();
+        let xv: BlockVar<CF> = BlockVar::new_constant(cs, x.clone()).unwrap();
 
         let xs = bincode::serialize(&x).unwrap();
         let xvs: Vec<u8> = xv
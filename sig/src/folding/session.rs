@@ -0,0 +1,274 @@
+use core::fmt;
+use std::{
+    fs::File,
+    io::{self, Read, Write},
+    marker::PhantomData,
+    path::Path,
+};
+
+use ark_ec::CurveGroup;
+use ark_ff::PrimeField;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError};
+use blake2::Digest;
+use folding_schemes::{frontend::FCircuit, Error, FoldingScheme};
+use rand::RngCore;
+use tempfile::NamedTempFile;
+
+use crate::bc::params::{HashFunc, HASH_OUTPUT_SIZE};
+
+/// Identifies snapshot files, followed by the version of their layout.
+const MAGIC: &[u8; 8] = b"BCFOLD\x00\x01";
+
+#[derive(Debug)]
+pub enum SessionError {
+    Io(io::Error),
+    Serialization(SerializationError),
+    /// The snapshot is not one written by `FoldingSession::save`, or it was modified.
+    Corrupted,
+    Folding(Error),
+}
+
+impl fmt::Display for SessionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "io error: {e}"),
+            Self::Serialization(e) => write!(f, "serialization error: {e}"),
+            Self::Corrupted => write!(f, "corrupted snapshot"),
+            Self::Folding(e) => write!(f, "folding error: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for SessionError {}
+
+impl From<io::Error> for SessionError {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<SerializationError> for SessionError {
+    fn from(e: SerializationError) -> Self {
+        Self::Serialization(e)
+    }
+}
+
+impl From<Error> for SessionError {
+    fn from(e: Error) -> Self {
+        Self::Folding(e)
+    }
+}
+
+/// A folding scheme `FS` that can be snapshotted to disk and resumed, so that a crash does
+/// not lose the steps folded so far.
+///
+/// A snapshot is the IVC proof of `FS`, which holds the running and incoming instances with
+/// their witnesses, `z_0`, `z_i` and the step counter. The parameters of `FS` are not part
+/// of it, as they are usually large and do not change between snapshots.
+pub struct FoldingSession<C1, C2, FC, FS>
+where
+    C1: CurveGroup<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    C2: CurveGroup,
+    C2::BaseField: PrimeField,
+    FC: FCircuit<C1::ScalarField>,
+    FS: FoldingScheme<C1, C2, FC>,
+{
+    folding: FS,
+    params: (FS::ProverParam, FS::VerifierParam),
+    _circuit: PhantomData<(C1, C2, FC)>,
+}
+
+impl<C1, C2, FC, FS> FoldingSession<C1, C2, FC, FS>
+where
+    C1: CurveGroup<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    C2: CurveGroup,
+    C2::BaseField: PrimeField,
+    FC: FCircuit<C1::ScalarField>,
+    FS: FoldingScheme<C1, C2, FC>,
+{
+    pub fn init(
+        params: (FS::ProverParam, FS::VerifierParam),
+        circuit: FC,
+        z_0: Vec<C1::ScalarField>,
+    ) -> Result<Self, SessionError> {
+        let folding = FS::init(&params, circuit, z_0)?;
+        Ok(Self {
+            folding,
+            params,
+            _circuit: PhantomData,
+        })
+    }
+
+    pub fn prove_step(
+        &mut self,
+        rng: impl RngCore,
+        external_inputs: FC::ExternalInputs,
+    ) -> Result<(), SessionError> {
+        Ok(self.folding.prove_step(rng, external_inputs, None)?)
+    }
+
+    #[must_use]
+    pub const fn folding(&self) -> &FS {
+        &self.folding
+    }
+
+    #[must_use]
+    pub fn into_folding(self) -> FS {
+        self.folding
+    }
+
+    /// Write a snapshot to `path`.
+    ///
+    /// The snapshot is first written to a new temporary file in the directory of `path` and
+    /// then renamed, so a crash while saving leaves the previous snapshot intact, and sessions
+    /// saving to different paths in the same directory never share a temporary file.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), SessionError> {
+        let path = path.as_ref();
+        let mut payload = Vec::new();
+        self.folding
+            .ivc_proof()
+            .serialize_compressed(&mut payload)?;
+
+        let dir = path
+            .parent()
+            .filter(|dir| !dir.as_os_str().is_empty())
+            .unwrap_or(Path::new("."));
+        let mut file = NamedTempFile::new_in(dir)?;
+        file.write_all(MAGIC)?;
+        file.write_all(&HashFunc::digest(&payload))?;
+        file.write_all(&payload)?;
+        file.as_file().sync_all()?;
+        file.persist(path).map_err(|e| e.error)?;
+
+        Ok(())
+    }
+
+    /// Resume from the snapshot at `path`, written by a session over the same circuit with
+    /// `fcircuit_params` and the same folding parameters `params`.
+    ///
+    /// Besides the checksum of the snapshot, the IVC proof it holds is verified against
+    /// `params`, so a session never resumes from a state that cannot be proven.
+    pub fn resume(
+        path: impl AsRef<Path>,
+        fcircuit_params: FC::Params,
+        params: (FS::ProverParam, FS::VerifierParam),
+    ) -> Result<Self, SessionError> {
+        let mut bytes = Vec::new();
+        File::open(path)?.read_to_end(&mut bytes)?;
+
+        let (magic, rest) = bytes
+            .split_at_checked(MAGIC.len())
+            .ok_or(SessionError::Corrupted)?;
+        let (checksum, payload) = rest
+            .split_at_checked(HASH_OUTPUT_SIZE)
+            .ok_or(SessionError::Corrupted)?;
+        if magic != MAGIC || checksum != HashFunc::digest(payload).as_slice() {
+            return Err(SessionError::Corrupted);
+        }
+
+        let ivc_proof = FS::IVCProof::deserialize_compressed(payload)?;
+        FS::verify(params.1.clone(), ivc_proof.clone()).map_err(|_| SessionError::Corrupted)?;
+        let folding = FS::from_ivc_proof(ivc_proof, fcircuit_params, params.clone())?;
+
+        Ok(Self {
+            folding,
+            params,
+            _circuit: PhantomData,
+        })
+    }
+
+    /// The parameters of the folding scheme, e.g. for a decider.
+    #[must_use]
+    pub const fn params(&self) -> &(FS::ProverParam, FS::VerifierParam) {
+        &self.params
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use ark_bn254::{Fr, G1Projective as G1};
+    use ark_ff::{One, Zero};
+    use ark_grumpkin::Projective as G2;
+    use ark_r1cs_std::fields::{fp::FpVar, FieldVar};
+    use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
+    use folding_schemes::{
+        commitment::pedersen::Pedersen,
+        folding::nova::{Nova, PreprocessorParam},
+        frontend::FCircuit,
+        transcript::poseidon::poseidon_canonical_config,
+        Error, FoldingScheme,
+    };
+    use rand::thread_rng;
+
+    use super::{FoldingSession, SessionError};
+
+    /// Counts the steps, so that the state after resuming tells how many were folded.
+    #[derive(Clone, Copy, Debug)]
+    struct Counter;
+
+    impl FCircuit<Fr> for Counter {
+        type Params = ();
+        type ExternalInputs = ();
+        type ExternalInputsVar = ();
+
+        fn new(_: ()) -> Result<Self, Error> {
+            Ok(Self)
+        }
+
+        fn state_len(&self) -> usize {
+            1
+        }
+
+        fn generate_step_constraints(
+            &self,
+            _: ConstraintSystemRef<Fr>,
+            _: usize,
+            z_i: Vec<FpVar<Fr>>,
+            _: (),
+        ) -> Result<Vec<FpVar<Fr>>, SynthesisError> {
+            Ok(vec![&z_i[0] + FpVar::one()])
+        }
+    }
+
+    type N = Nova<G1, G2, Counter, Pedersen<G1>, Pedersen<G2>, false>;
+    type Session = FoldingSession<G1, G2, Counter, N>;
+
+    #[test]
+    fn test_save_and_resume() {
+        let mut rng = thread_rng();
+        let params = N::preprocess(
+            &mut rng,
+            &PreprocessorParam::new(poseidon_canonical_config::<Fr>(), Counter),
+        )
+        .unwrap();
+
+        let mut session = Session::init(params.clone(), Counter, vec![Fr::zero()]).unwrap();
+        for _ in 0..3 {
+            session.prove_step(&mut rng, ()).unwrap();
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session");
+        session.save(&path).unwrap();
+        // saving again replaces the snapshot without leaving temporary files behind
+        session.save(&path).unwrap();
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+
+        let mut resumed = Session::resume(&path, (), params.clone()).unwrap();
+        assert_eq!(resumed.folding().state(), session.folding().state());
+        resumed.prove_step(&mut rng, ()).unwrap();
+        assert_eq!(resumed.folding().state(), vec![Fr::from(4u64)]);
+        N::verify(params.1.clone(), resumed.folding().ivc_proof()).unwrap();
+
+        // a modified snapshot is rejected
+        let mut bytes = fs::read(&path).unwrap();
+        *bytes.last_mut().unwrap() ^= 1;
+        fs::write(&path, bytes).unwrap();
+        assert!(matches!(
+            Session::resume(&path, (), params),
+            Err(SessionError::Corrupted | SessionError::Serialization(_))
+        ));
+    }
+}
@@ -0,0 +1,228 @@
+use ark_ff::PrimeField;
+use ark_r1cs_std::{convert::ToConstraintFieldGadget, fields::fp::FpVar, uint64::UInt64};
+use ark_relations::r1cs::SynthesisError;
+
+use crate::{
+    bc::params::BcConfig,
+    bls::PublicKeyVar,
+    params::{BlsSigConfig, BlsSigFieldVar},
+};
+
+use super::{
+    bc::{CommitteeVar, SignerVar},
+    from_constraint_field::FromConstraintFieldGadget,
+};
+
+/// Specifies how to pack `Self` into the state `z_i` of a folding scheme, and how to unpack
+/// it from there.
+///
+/// The number of field elements taken by `Self` must only depend on its `Shape`, so that
+/// the state has the same length at every step. Tuples, arrays and `Vec` of components
+/// implement it, so a state made of several components does not need any packing code.
+pub trait StateSerde<CF: PrimeField>: Sized {
+    /// What the length of `Self` depends on besides its type, e.g. the `BcConfig` for a
+    /// committee.
+    type Shape: Copy;
+
+    fn state_len(shape: Self::Shape) -> usize;
+
+    fn to_state(&self) -> Result<Vec<FpVar<CF>>, SynthesisError>;
+
+    fn from_state(
+        iter: impl Iterator<Item = FpVar<CF>>,
+        shape: Self::Shape,
+    ) -> Result<Self, SynthesisError>;
+}
+
+/// Variables of a fixed length, which already implement `FromConstraintFieldGadget`.
+macro_rules! impl_state_serde {
+    ($($T:ty),+) => {$(
+        impl<CF: PrimeField> StateSerde<CF> for $T {
+            type Shape = ();
+
+            fn state_len((): ()) -> usize {
+                Self::num_constraint_var_needed()
+            }
+
+            fn to_state(&self) -> Result<Vec<FpVar<CF>>, SynthesisError> {
+                self.to_constraint_field()
+            }
+
+            fn from_state(
+                iter: impl Iterator<Item = FpVar<CF>>,
+                (): (),
+            ) -> Result<Self, SynthesisError> {
+                Self::from_constraint_field(iter)
+            }
+        }
+    )+};
+}
+
+impl_state_serde!(
+    FpVar<CF>,
+    BlsSigFieldVar<CF>,
+    PublicKeyVar<BlsSigConfig, BlsSigFieldVar<CF>, CF>,
+    SignerVar<CF>
+);
+
+impl<CF: PrimeField> StateSerde<CF> for UInt64<CF> {
+    type Shape = ();
+
+    fn state_len((): ()) -> usize {
+        Self::num_constraint_var_needed()
+    }
+
+    fn to_state(&self) -> Result<Vec<FpVar<CF>>, SynthesisError> {
+        Ok(vec![self.to_fp()?])
+    }
+
+    fn from_state(iter: impl Iterator<Item = FpVar<CF>>, (): ()) -> Result<Self, SynthesisError> {
+        Self::from_constraint_field(iter)
+    }
+}
+
+impl<CF: PrimeField> StateSerde<CF> for CommitteeVar<CF> {
+    type Shape = BcConfig;
+
+    fn state_len(config: BcConfig) -> usize {
+        Self::num_constraint_var_needed(&config)
+    }
+
+    fn to_state(&self) -> Result<Vec<FpVar<CF>>, SynthesisError> {
+        self.to_constraint_field()
+    }
+
+    fn from_state(
+        iter: impl Iterator<Item = FpVar<CF>>,
+        config: BcConfig,
+    ) -> Result<Self, SynthesisError> {
+        Self::from_constraint_field(iter, &config)
+    }
+}
+
+impl<CF: PrimeField, T: StateSerde<CF>, const N: usize> StateSerde<CF> for [T; N] {
+    type Shape = T::Shape;
+
+    fn state_len(shape: T::Shape) -> usize {
+        T::state_len(shape) * N
+    }
+
+    fn to_state(&self) -> Result<Vec<FpVar<CF>>, SynthesisError> {
+        let mut state = Vec::new();
+        for x in self {
+            state.extend(x.to_state()?);
+        }
+        Ok(state)
+    }
+
+    fn from_state(
+        mut iter: impl Iterator<Item = FpVar<CF>>,
+        shape: T::Shape,
+    ) -> Result<Self, SynthesisError> {
+        (0..N)
+            .map(|_| T::from_state(iter.by_ref(), shape))
+            .collect::<Result<Vec<_>, _>>()?
+            .try_into()
+            .map_err(|_| SynthesisError::Unsatisfiable)
+    }
+}
+
+/// A `Vec` of a fixed length, which is part of its shape.
+impl<CF: PrimeField, T: StateSerde<CF>> StateSerde<CF> for Vec<T> {
+    type Shape = (usize, T::Shape);
+
+    fn state_len((len, shape): Self::Shape) -> usize {
+        T::state_len(shape) * len
+    }
+
+    fn to_state(&self) -> Result<Vec<FpVar<CF>>, SynthesisError> {
+        let mut state = Vec::new();
+        for x in self {
+            state.extend(x.to_state()?);
+        }
+        Ok(state)
+    }
+
+    fn from_state(
+        mut iter: impl Iterator<Item = FpVar<CF>>,
+        (len, shape): Self::Shape,
+    ) -> Result<Self, SynthesisError> {
+        (0..len)
+            .map(|_| T::from_state(iter.by_ref(), shape))
+            .collect()
+    }
+}
+
+macro_rules! impl_state_serde_for_tuple {
+    ($($T:ident $shape:ident),+) => {
+        impl<CF: PrimeField, $($T: StateSerde<CF>),+> StateSerde<CF> for ($($T,)+) {
+            type Shape = ($($T::Shape,)+);
+
+            fn state_len(($($shape,)+): Self::Shape) -> usize {
+                0 $(+ $T::state_len($shape))+
+            }
+
+            fn to_state(&self) -> Result<Vec<FpVar<CF>>, SynthesisError> {
+                let ($($shape,)+) = self;
+                let mut state = Vec::new();
+                $(state.extend($shape.to_state()?);)+
+                Ok(state)
+            }
+
+            fn from_state(
+                mut iter: impl Iterator<Item = FpVar<CF>>,
+                ($($shape,)+): Self::Shape,
+            ) -> Result<Self, SynthesisError> {
+                Ok(($($T::from_state(iter.by_ref(), $shape)?,)+))
+            }
+        }
+    };
+}
+
+impl_state_serde_for_tuple!(A a);
+impl_state_serde_for_tuple!(A a, B b);
+impl_state_serde_for_tuple!(A a, B b, C c);
+impl_state_serde_for_tuple!(A a, B b, C c, D d);
+
+#[cfg(test)]
+mod test {
+    use ark_r1cs_std::{alloc::AllocVar, fields::fp::FpVar, uint64::UInt64, R1CSVar};
+    use ark_relations::r1cs::ConstraintSystem;
+
+    use crate::params::{BlsSigConfig, BlsSigField};
+
+    use super::StateSerde;
+
+    type CF = BlsSigField<BlsSigConfig>;
+
+    #[test]
+    fn test_state_serde() {
+        type State = (FpVar<CF>, [UInt64<CF>; 2], Vec<UInt64<CF>>);
+        let shape = ((), (), (3, ()));
+
+        let cs = ConstraintSystem::<CF>::new_ref();
+        let root = FpVar::new_witness(cs.clone(), || Ok(CF::from(42u64))).unwrap();
+        let counters = [1, 2].map(|i| UInt64::new_witness(cs.clone(), || Ok(i)).unwrap());
+        let epochs = (3..6)
+            .map(|i| UInt64::new_witness(cs.clone(), || Ok(i)).unwrap())
+            .collect();
+        let state: State = (root, counters, epochs);
+
+        let z_i = state.to_state().unwrap();
+        assert_eq!(z_i.len(), State::state_len(shape));
+
+        let (root, counters, epochs) = State::from_state(z_i.into_iter(), shape).unwrap();
+        assert_eq!(root.value().unwrap(), CF::from(42u64));
+        assert_eq!(counters.map(|c| c.value().unwrap()), [1, 2]);
+        assert_eq!(
+            epochs
+                .iter()
+                .map(|e| e.value().unwrap())
+                .collect::<Vec<_>>(),
+            [3, 4, 5]
+        );
+
+        // the state is too short
+        assert!(State::from_state(Vec::new().into_iter(), shape).is_err());
+    }
+}
@@ -0,0 +1,118 @@
+use std::borrow::Borrow;
+
+use ark_ff::PrimeField;
+use ark_r1cs_std::{
+    alloc::{AllocVar, AllocationMode},
+    uint64::UInt64,
+    uint8::UInt8,
+};
+use ark_relations::r1cs::{Namespace, SynthesisError};
+use derivative::Derivative;
+
+use crate::bc::sync::{SyncUpdate, SYNC_UPDATE_DOMAIN};
+
+use super::{
+    bc::{CommitteeVar, QuorumSignatureVar},
+    serialize::SerializeGadget,
+};
+
+#[derive(Derivative)]
+#[derivative(Clone(bound = ""), Debug(bound = ""))]
+pub struct SyncUpdateVar<CF: PrimeField> {
+    pub epoch: UInt64<CF>,
+    pub committee: CommitteeVar<CF>,
+    pub sig: QuorumSignatureVar<CF>,
+}
+
+impl<CF: PrimeField> SyncUpdateVar<CF> {
+    /// The message signed by the committee, as `SyncUpdate::signing_bytes`.
+    pub fn message(&self) -> Result<Vec<UInt8<CF>>, SynthesisError> {
+        let mut message = UInt8::constant_vec(&SYNC_UPDATE_DOMAIN);
+        message.extend(self.epoch.serialize()?);
+        message.extend(self.committee.serialize()?);
+        Ok(message)
+    }
+}
+
+impl<CF: PrimeField> AllocVar<SyncUpdate, CF> for SyncUpdateVar<CF> {
+    fn new_variable<T: Borrow<SyncUpdate>>(
+        cs: impl Into<Namespace<CF>>,
+        f: impl FnOnce() -> Result<T, SynthesisError>,
+        mode: AllocationMode,
+    ) -> Result<Self, SynthesisError> {
+        let cs = cs.into().cs();
+        let update = f();
+
+        let epoch = UInt64::new_variable(
+            cs.clone(),
+            || {
+                update
+                    .as_ref()
+                    .map(|value| value.borrow().epoch)
+                    .map_err(SynthesisError::clone)
+            },
+            mode,
+        )?;
+        let committee = CommitteeVar::new_variable(
+            cs.clone(),
+            || {
+                update
+                    .as_ref()
+                    .map(|value| value.borrow().committee.clone())
+                    .map_err(SynthesisError::clone)
+            },
+            mode,
+        )?;
+        let sig = QuorumSignatureVar::new_variable(
+            cs,
+            || {
+                update
+                    .as_ref()
+                    .map(|value| value.borrow().sig.clone())
+                    .map_err(SynthesisError::clone)
+            },
+            mode,
+        )?;
+
+        Ok(Self {
+            epoch,
+            committee,
+            sig,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ark_r1cs_std::{alloc::AllocVar, R1CSVar};
+    use ark_relations::r1cs::ConstraintSystem;
+    use rand::thread_rng;
+
+    use crate::{
+        bc::{params::AuthoritySigParams, sync::SyncUpdate, testgen::ChainGenerator},
+        params::{BlsSigConfig, BlsSigField},
+    };
+
+    use super::SyncUpdateVar;
+
+    type CF = BlsSigField<BlsSigConfig>;
+
+    #[test]
+    fn test_sync_update_message() {
+        let (blocks, signers) = ChainGenerator::new(3, 4).generate(&mut thread_rng());
+        let update = SyncUpdate::new(
+            2,
+            blocks[2].committee.clone(),
+            &signers[0],
+            &blocks[1].sig.signers,
+            &AuthoritySigParams::setup(),
+        );
+
+        let cs = ConstraintSystem::<CF>::new_ref();
+        let message = SyncUpdateVar::new_constant(cs, &update)
+            .unwrap()
+            .message()
+            .unwrap();
+        assert_eq!(message.value().unwrap(), update.signing_bytes());
+    }
+}
@@ -0,0 +1,85 @@
+//! Expansion of a message into uniform bytes (`expand_message` of
+//! [RFC 9380](https://www.rfc-editor.org/rfc/rfc9380.html#section-5.3)), which is the first
+//! step of hashing to a field.
+//!
+//! The gadgets agree with `ark_ff::field_hashers::expander`, so they can be reused by any
+//! circuit that needs to derive uniform bytes from a message and a domain separation tag.
+use ark_crypto_primitives::prf::XofGadget;
+use ark_ff::{
+    field_hashers::expander::{LONG_DST_PREFIX, MAX_DST_LENGTH},
+    PrimeField,
+};
+use ark_r1cs_std::{uint8::UInt8, R1CSVar};
+use ark_relations::r1cs::SynthesisError;
+use ark_std::vec::Vec;
+use arrayvec::ArrayVec;
+
+mod xmd;
+pub use xmd::*;
+
+mod xof;
+pub use xof::*;
+
+/// R1CS equivalent of `ark_ff::field_hashers::expander::Expander`.
+pub trait ExpandMsgGadget<F: PrimeField> {
+    /// Expand `msg` into `len_in_bytes` uniform bytes.
+    fn expand(
+        &self,
+        msg: &[UInt8<F>],
+        len_in_bytes: usize,
+    ) -> Result<Vec<UInt8<F>>, SynthesisError>;
+}
+
+/// R1CS equivalent of `ark_ff::field_hashers::expander::DST`, i.e. a domain separation tag
+/// of at most `MAX_DST_LENGTH` bytes, where longer tags are hashed as in section 5.3.3 of
+/// RFC 9380.
+pub struct DSTGadget<F: PrimeField>(ArrayVec<UInt8<F>, MAX_DST_LENGTH>);
+
+impl<F: PrimeField> DSTGadget<F> {
+    /// Build the tag of `expand_message_xmd`, where a long `dst` is replaced by its hash.
+    #[tracing::instrument(skip_all)]
+    pub fn new_xmd<H: XofGadget<F>>(dst: &[UInt8<F>]) -> Result<Self, SynthesisError> {
+        Self::new::<H>(dst, H::MAX_OUTPUT_SIZE)
+    }
+
+    /// Build the tag of `expand_message_xof` for the security level `k` (in bits), where a
+    /// long `dst` is replaced by `ceil(2 * k / 8)` bytes squeezed from its hash.
+    #[tracing::instrument(skip_all)]
+    pub fn new_xof<H: XofGadget<F>>(dst: &[UInt8<F>], k: usize) -> Result<Self, SynthesisError> {
+        Self::new::<H>(dst, (2 * k + 7) >> 3)
+    }
+
+    fn new<H: XofGadget<F>>(dst: &[UInt8<F>], long_dst_len: usize) -> Result<Self, SynthesisError> {
+        let cs = dst.cs();
+        tracing::info!(num_constraints = cs.num_constraints());
+
+        let array = if dst.len() > MAX_DST_LENGTH {
+            let mut hasher = H::default();
+            let long_dst_prefix = LONG_DST_PREFIX.map(|value| UInt8::constant(value));
+            hasher.absorb(&long_dst_prefix)?;
+            hasher.absorb(dst)?;
+            let out = hasher.squeeze(long_dst_len)?;
+            ArrayVec::try_from(&*out).expect(
+                "supplied hash function should produce an output with length smaller than 255",
+            )
+        } else {
+            ArrayVec::try_from(dst).expect(
+                "supplied hash function should produce an output with length smaller than 255",
+            )
+        };
+
+        tracing::info!(num_constraints = cs.num_constraints());
+
+        Ok(Self(array))
+    }
+
+    /// The bytes absorbed after the message, i.e. `DST || I2OSP(len(DST), 1)`.
+    pub fn get_update(&self) -> Vec<UInt8<F>> {
+        // I2OSP(len,1) https://www.rfc-editor.org/rfc/rfc8017.txt
+        let mut val = self.0.to_vec();
+        // self.0.len() is guaranteed to be smaller than MAX_DST_LENGTH (255)
+        #[expect(clippy::cast_possible_truncation)]
+        val.push(UInt8::constant(self.0.len() as u8));
+        val
+    }
+}
@@ -1,66 +1,34 @@
-use ark_crypto_primitives::prf::PRFGadget;
-use ark_ff::{
-    field_hashers::expander::{LONG_DST_PREFIX, MAX_DST_LENGTH, Z_PAD},
-    PrimeField,
-};
-use ark_r1cs_std::{prelude::ToBytesGadget, uint8::UInt8, R1CSVar};
+use ark_crypto_primitives::prf::XofGadget;
+use ark_ff::{field_hashers::expander::Z_PAD, PrimeField};
+use ark_r1cs_std::{uint8::UInt8, R1CSVar};
 use ark_relations::r1cs::SynthesisError;
-use arrayvec::ArrayVec;
+use ark_std::vec::Vec;
 use core::{marker::PhantomData, ops::BitXor};
 
-struct DSTGadget<F: PrimeField>(ArrayVec<UInt8<F>, MAX_DST_LENGTH>);
+use super::{DSTGadget, ExpandMsgGadget};
 
-impl<F: PrimeField> DSTGadget<F> {
-    #[tracing::instrument(skip_all)]
-    pub fn new_xmd<H: PRFGadget<F> + Default>(dst: &[UInt8<F>]) -> Result<Self, SynthesisError> {
-        let cs = dst.cs();
-        tracing::info!(num_constraints = cs.num_constraints());
-
-        let array = if dst.len() > MAX_DST_LENGTH {
-            let mut hasher = H::default();
-            let long_dst_prefix = LONG_DST_PREFIX.map(|value| UInt8::constant(value));
-            hasher.update(&long_dst_prefix)?;
-            hasher.update(dst)?;
-            let out = hasher.finalize()?.to_bytes_le()?;
-            ArrayVec::try_from(&*out).expect(
-                "supplied hash function should produce an output with length smaller than 255",
-            )
-        } else {
-            ArrayVec::try_from(dst).expect(
-                "supplied hash function should produce an output with length smaller than 255",
-            )
-        };
-
-        tracing::info!(num_constraints = cs.num_constraints());
-
-        Ok(Self(array))
-    }
-
-    pub fn get_update(&self) -> ArrayVec<UInt8<F>, MAX_DST_LENGTH> {
-        // I2OSP(len,1) https://www.rfc-editor.org/rfc/rfc8017.txt
-        let mut val = self.0.clone();
-        // self.0.len() is guaranteed to be smaller than MAX_DST_LENGTH (255)
-        #[expect(clippy::cast_possible_truncation)]
-        val.push(UInt8::constant(self.0.len() as u8));
-        val
-    }
-}
-
-// Implement expander as it is in corresponding implementation in expander::ExpanderXmd
-pub struct ExpanderXmdGadget<H: PRFGadget<F> + Default, F: PrimeField> {
+/// R1CS equivalent of `ark_ff::field_hashers::expander::ExpanderXmd`, i.e.
+/// `expand_message_xmd` with the hash function `H` producing blocks of
+/// `H::MAX_OUTPUT_SIZE` bytes.
+///
+/// As in `ExpanderXmd`, `block_size` is the number of zero bytes `Z_pad` prepended to the
+/// message, which is the length of a field element in `DefaultFieldHasher` rather than the
+/// input block size of `H` required by RFC 9380.
+pub struct ExpanderXmdGadget<H: XofGadget<F>, F: PrimeField> {
     pub hasher: PhantomData<H>,
     pub dst: Vec<UInt8<F>>,
     pub block_size: usize,
 }
 
-impl<H: PRFGadget<F> + Default, F: PrimeField> ExpanderXmdGadget<H, F> {
+impl<H: XofGadget<F>, F: PrimeField> ExpandMsgGadget<F> for ExpanderXmdGadget<H, F> {
     #[tracing::instrument(skip_all)]
-    pub fn expand(&self, msg: &[UInt8<F>], n: usize) -> Result<Vec<UInt8<F>>, SynthesisError> {
+    fn expand(&self, msg: &[UInt8<F>], n: usize) -> Result<Vec<UInt8<F>>, SynthesisError> {
         let cs = msg.cs();
         tracing::info!(num_constraints = cs.num_constraints());
 
-        // output size of the hash function, e.g. 32 bytes = 256 bits for sha2::Sha256
-        let b_len = H::OUTPUT_SIZE;
+        // output size of the hash function, e.g. 32 bytes = 256 bits for sha2::Sha256, or
+        // 64 bytes for Blake2b-512, which halves the number of blocks `ell` to hash
+        let b_len = H::MAX_OUTPUT_SIZE;
         let ell = (n + (b_len - 1)) / b_len;
         assert!(
             ell <= 255,
@@ -77,44 +45,42 @@ impl<H: PRFGadget<F> + Default, F: PrimeField> ExpanderXmdGadget<H, F> {
         let dst_prime_data = DSTGadget::<F>::new_xmd::<H>(&self.dst)?.get_update();
 
         let mut hasher = H::default();
-        hasher.update(
+        hasher.absorb(
             &Z_PAD[0..self.block_size]
                 .iter()
                 .map(|b| UInt8::constant(*b))
                 .collect::<Vec<_>>(),
         )?;
-        hasher.update(msg)?;
-        hasher.update(&lib_str.map(|b| UInt8::constant(b)))?;
-        hasher.update(&[UInt8::constant(0u8)])?;
-        hasher.update(&dst_prime_data)?;
-        let b0 = hasher.finalize()?.to_bytes_le()?;
-
-        let mut hasher = H::default();
-        hasher.update(&b0)?;
-        hasher.update(&[UInt8::constant(1u8)])?;
-        hasher.update(&dst_prime_data)?;
-        let mut bi = hasher.finalize()?.to_bytes_le()?;
+        hasher.absorb(msg)?;
+        hasher.absorb(&lib_str.map(|b| UInt8::constant(b)))?;
+        hasher.absorb(&[UInt8::constant(0u8)])?;
+        hasher.absorb(&dst_prime_data)?;
+        let b0 = hasher.squeeze(b_len)?;
 
         let mut uniform_bytes: Vec<UInt8<F>> = Vec::with_capacity(n);
-        uniform_bytes.extend_from_slice(&bi);
-        for i in 2..=ell {
-            // update the hasher with xor of b_0 and b_i elements
+        for i in 1..=ell {
+            // b_1 = H(b_0 || 1 || DST'), and b_i = H((b_0 xor b_(i-1)) || i || DST') after
             let mut hasher = H::default();
-            hasher.update(
-                &bi.iter()
-                    .zip(&b0)
-                    .map(|(l, r)| l.bitxor(r))
-                    .collect::<Vec<_>>(),
-            )?;
-            // i < ell <= 255
+            if i == 1 {
+                hasher.absorb(&b0)?;
+            } else {
+                hasher.absorb(
+                    &uniform_bytes[(i - 2) * b_len..]
+                        .iter()
+                        .zip(&b0)
+                        .map(|(l, r)| l.bitxor(r))
+                        .collect::<Vec<_>>(),
+                )?;
+            }
+            // i <= ell <= 255
             #[expect(clippy::cast_possible_truncation)]
-            hasher.update(&[UInt8::constant(i as u8)])?;
-            hasher.update(&dst_prime_data)?;
-            bi = hasher.finalize()?.to_bytes_le()?;
-            uniform_bytes.extend_from_slice(&bi);
-        }
+            hasher.absorb(&[UInt8::constant(i as u8)])?;
+            hasher.absorb(&dst_prime_data)?;
 
-        uniform_bytes.truncate(n);
+            // only squeeze the bytes of the last block that are part of the output
+            let len = b_len.min(n - uniform_bytes.len());
+            uniform_bytes.extend(hasher.squeeze(len)?);
+        }
 
         tracing::info!(num_constraints = cs.num_constraints());
 
@@ -126,17 +92,20 @@ impl<H: PRFGadget<F> + Default, F: PrimeField> ExpanderXmdGadget<H, F> {
 mod test {
     use core::marker::PhantomData;
 
-    use ark_crypto_primitives::prf::blake2s::constraints::Blake2sGadget;
+    use ark_crypto_primitives::prf::{
+        blake2b::constraints::Blake2bGadget, blake2s::constraints::Blake2sGadget,
+    };
     use ark_ff::field_hashers::{
         expander::{Expander, ExpanderXmd},
         get_len_per_elem,
     };
     use ark_r1cs_std::{alloc::AllocVar, uint8::UInt8, R1CSVar};
     use ark_relations::r1cs::ConstraintSystem;
-    use blake2::{digest::Update, Blake2s256, Digest};
+    use blake2::{digest::Update, Blake2b512, Blake2s256, Digest};
     use rand::{thread_rng, Rng};
 
     use super::ExpanderXmdGadget;
+    use crate::hash::expand::ExpandMsgGadget;
 
     // This function is to validate how blake2 hash works.
     // So, I can implement the corresponding R1CS version.
@@ -266,4 +235,84 @@ mod test {
             }
         }
     }
+
+    #[test]
+    fn test_expander_blake2b() {
+        use ark_bls12_381::Fr as F;
+
+        let mut rng = thread_rng();
+
+        let len_per_base_elem = get_len_per_elem::<F, 128>();
+        let dst: [u8; 16] = [0; 16];
+
+        let expander: ExpanderXmd<Blake2b512> = ExpanderXmd {
+            hasher: PhantomData,
+            dst: dst.to_vec(),
+            block_size: len_per_base_elem,
+        };
+
+        let hasher: PhantomData<Blake2bGadget<F>> = PhantomData;
+        let expander_gadget = ExpanderXmdGadget {
+            hasher,
+            dst: UInt8::constant_vec(&dst),
+            block_size: len_per_base_elem,
+        };
+
+        for input_len in [0, 32, 100] {
+            for len_in_bytes in [16, 64, 128, 200] {
+                let cs = ConstraintSystem::new_ref();
+                let mut msg = vec![0u8; input_len];
+                rng.fill(&mut *msg);
+                let msg_var = UInt8::new_witness_vec(cs.clone(), &msg).unwrap();
+
+                let s1 = expander.expand(&msg, len_in_bytes);
+                let s2 = expander_gadget.expand(&msg_var, len_in_bytes).unwrap();
+
+                assert!(cs.is_satisfied().unwrap());
+                assert_eq!(s1, s2.value().unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn test_expander_constraints() {
+        use ark_bls12_381::Fq as F;
+
+        // expand a 32-byte message to the 128 bytes hashed to 2 elements of Fq
+        let len_per_base_elem = get_len_per_elem::<F, 128>();
+        let dst = UInt8::constant_vec(&[0; 16]);
+        let (msg, len_in_bytes) = ([0; 32], 2 * len_per_base_elem);
+
+        let blake2s = {
+            let cs = ConstraintSystem::<F>::new_ref();
+            let msg_var = UInt8::new_witness_vec(cs.clone(), &msg).unwrap();
+            ExpanderXmdGadget::<Blake2sGadget<F>, F> {
+                hasher: PhantomData,
+                dst: dst.clone(),
+                block_size: len_per_base_elem,
+            }
+            .expand(&msg_var, len_in_bytes)
+            .unwrap();
+            cs.num_constraints()
+        };
+        let blake2b = {
+            let cs = ConstraintSystem::<F>::new_ref();
+            let msg_var = UInt8::new_witness_vec(cs.clone(), &msg).unwrap();
+            ExpanderXmdGadget::<Blake2bGadget<F>, F> {
+                hasher: PhantomData,
+                dst,
+                block_size: len_per_base_elem,
+            }
+            .expand(&msg_var, len_in_bytes)
+            .unwrap();
+            cs.num_constraints()
+        };
+
+        println!("expanding to {len_in_bytes} bytes takes {blake2s} constraints with Blake2s-256 and {blake2b} with Blake2b-512");
+        // Blake2b-512 hashes 3 blocks instead of 6, but each compression works on 64-bit
+        // words over 12 rounds instead of 32-bit words over 10, so it takes more constraints
+        assert_eq!(len_in_bytes, 128);
+        assert_eq!(blake2s, 107_424);
+        assert_eq!(blake2b, 147_841);
+    }
 }
@@ -0,0 +1,99 @@
+use ark_crypto_primitives::prf::XofGadget;
+use ark_ff::PrimeField;
+use ark_r1cs_std::{uint8::UInt8, R1CSVar};
+use ark_relations::r1cs::SynthesisError;
+use ark_std::vec::Vec;
+use core::marker::PhantomData;
+
+use super::{DSTGadget, ExpandMsgGadget};
+
+/// R1CS equivalent of `ark_ff::field_hashers::expander::ExpanderXof`, i.e.
+/// `expand_message_xof` with the extendable-output function `H`, e.g. `Shake128Gadget`, at
+/// the security level `k` (in bits).
+pub struct ExpanderXofGadget<H: XofGadget<F>, F: PrimeField> {
+    pub hasher: PhantomData<H>,
+    pub dst: Vec<UInt8<F>>,
+    pub k: usize,
+}
+
+impl<H: XofGadget<F>, F: PrimeField> ExpandMsgGadget<F> for ExpanderXofGadget<H, F> {
+    #[tracing::instrument(skip_all)]
+    fn expand(&self, msg: &[UInt8<F>], n: usize) -> Result<Vec<UInt8<F>>, SynthesisError> {
+        let cs = msg.cs();
+        tracing::info!(num_constraints = cs.num_constraints());
+
+        // I2OSP(len,2) https://www.rfc-editor.org/rfc/rfc8017.txt
+        let lib_str: [u8; 2] = u16::try_from(n)
+            .map_err(|_| SynthesisError::Unsatisfiable)?
+            .to_be_bytes();
+
+        let mut hasher = H::default();
+        hasher.absorb(msg)?;
+        hasher.absorb(&lib_str.map(UInt8::constant))?;
+        hasher.absorb(&DSTGadget::<F>::new_xof::<H>(&self.dst, self.k)?.get_update())?;
+        let uniform_bytes = hasher.squeeze(n)?;
+
+        tracing::info!(num_constraints = cs.num_constraints());
+
+        Ok(uniform_bytes)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use core::marker::PhantomData;
+
+    use ark_bls12_381::Fr as F;
+    use ark_crypto_primitives::prf::keccak::constraints::Shake128Gadget;
+    use ark_ff::field_hashers::expander::{Expander, ExpanderXof};
+    use ark_r1cs_std::{uint8::UInt8, R1CSVar};
+    use ark_relations::r1cs::{ConstraintSystem, SynthesisError};
+    use rand::{thread_rng, Rng};
+    use sha3::Shake128;
+
+    use super::ExpanderXofGadget;
+    use crate::hash::expand::ExpandMsgGadget;
+
+    #[test]
+    fn test_expander_xof() {
+        let mut rng = thread_rng();
+
+        for dst_len in [16, 300] {
+            let dst = vec![1u8; dst_len];
+            let expander = ExpanderXof {
+                xofer: PhantomData::<Shake128>,
+                dst: dst.clone(),
+                k: 128,
+            };
+            let expander_gadget = ExpanderXofGadget::<Shake128Gadget<F>, F> {
+                hasher: PhantomData,
+                dst: UInt8::constant_vec(&dst),
+                k: 128,
+            };
+
+            // outputs longer than the rate of SHAKE128 (168 bytes) need several permutations
+            for (input_len, len_in_bytes) in [(0, 32), (32, 128), (100, 200)] {
+                let cs = ConstraintSystem::new_ref();
+                let mut msg = vec![0u8; input_len];
+                rng.fill(&mut *msg);
+                let msg_var = UInt8::new_witness_vec(cs.clone(), &msg).unwrap();
+
+                let s1 = expander.expand(&msg, len_in_bytes);
+                let s2 = expander_gadget.expand(&msg_var, len_in_bytes).unwrap();
+
+                assert!(cs.is_satisfied().unwrap());
+                assert_eq!(s1, s2.value().unwrap());
+            }
+        }
+
+        let expander_gadget = ExpanderXofGadget::<Shake128Gadget<F>, F> {
+            hasher: PhantomData,
+            dst: UInt8::constant_vec(&[1; 16]),
+            k: 128,
+        };
+        assert!(matches!(
+            expander_gadget.expand(&[], 1 << 16),
+            Err(SynthesisError::Unsatisfiable)
+        ));
+    }
+}
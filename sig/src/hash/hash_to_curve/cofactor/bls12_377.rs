@@ -1,7 +1,7 @@
 use ark_bls12_377::g2::G2Projective;
 use ark_bls12_377::{Fq, Fq2, Fq2Config};
 use ark_ec::bls12::Bls12Config;
-use ark_ec::short_weierstrass::SWCurveConfig;
+use ark_ec::short_weierstrass::{Projective, SWCurveConfig};
 use ark_ec::CurveGroup;
 use ark_ff::{
     AdditiveGroup, BigInteger, BigInteger64, Fp2ConfigWrapper, MontBackend, MontFp, PrimeField,
@@ -15,6 +15,7 @@ use ark_r1cs_std::{
     groups::curves::short_weierstrass::ProjectiveVar,
 };
 use ark_relations::r1cs::SynthesisError;
+use ark_std::vec::Vec;
 
 use super::CofactorGadget;
 
@@ -125,7 +126,8 @@ where
 impl<
         FP: FieldVar<<Fp2ConfigWrapper<Fq2Config> as QuadExtConfig>::BaseField, CF>,
         CF: PrimeField,
-    > CofactorGadget<QuadExtVar<FP, Fp2ConfigWrapper<Fq2Config>, CF>, CF> for G2Projective
+    > CofactorGadget<QuadExtVar<FP, Fp2ConfigWrapper<Fq2Config>, CF>, CF>
+    for Projective<ark_bls12_377::g2::Config>
 where
     <Self as CurveGroup>::Config: SWCurveConfig,
     for<'b> &'b FP: FieldOpsBounds<'b, ark_ff::Fp<MontBackend<ark_bls12_377::FqConfig, 6>, 6>, FP>,
@@ -189,6 +191,16 @@ where
     }
 }
 
+// `ark_bls12_377::g1::Config` does not specialise `clear_cofactor`,
+// so the default multiplication by the cofactor matches the native hasher.
+impl<FP: FieldVar<Fq, CF>, CF: PrimeField> CofactorGadget<FP, CF>
+    for Projective<ark_bls12_377::g1::Config>
+where
+    <Self as CurveGroup>::Config: SWCurveConfig,
+    for<'b> &'b FP: FieldOpsBounds<'b, Fq, FP>,
+{
+}
+
 #[cfg(test)]
 mod test {
     use std::ops::Neg;
@@ -1,6 +1,6 @@
 use ark_bls12_381::{Fq, Fq2, Fq2Config, FqConfig, G2Projective};
 use ark_ec::bls12::Bls12Config;
-use ark_ec::short_weierstrass::SWCurveConfig;
+use ark_ec::short_weierstrass::{Projective, SWCurveConfig};
 use ark_ec::CurveGroup;
 use ark_ff::{
     AdditiveGroup, BigInteger, BigInteger64, Fp2ConfigWrapper, MontBackend, MontFp, PrimeField,
@@ -14,6 +14,7 @@ use ark_r1cs_std::{
     groups::curves::short_weierstrass::ProjectiveVar,
 };
 use ark_relations::r1cs::SynthesisError;
+use ark_std::vec::Vec;
 
 use super::CofactorGadget;
 
@@ -127,7 +128,8 @@ where
 impl<
         FP: FieldVar<<Fp2ConfigWrapper<Fq2Config> as QuadExtConfig>::BaseField, CF>,
         CF: PrimeField,
-    > CofactorGadget<QuadExtVar<FP, Fp2ConfigWrapper<Fq2Config>, CF>, CF> for G2Projective
+    > CofactorGadget<QuadExtVar<FP, Fp2ConfigWrapper<Fq2Config>, CF>, CF>
+    for Projective<ark_bls12_381::g2::Config>
 where
     <Self as CurveGroup>::Config: SWCurveConfig,
     for<'b> &'b FP: FieldOpsBounds<'b, ark_ff::Fp<MontBackend<FqConfig, 6>, 6>, FP>,
@@ -191,24 +193,66 @@ where
     }
 }
 
+impl<FP: FieldVar<Fq, CF>, CF: PrimeField> CofactorGadget<FP, CF>
+    for Projective<ark_bls12_381::g1::Config>
+where
+    <Self as CurveGroup>::Config: SWCurveConfig,
+    for<'b> &'b FP: FieldOpsBounds<'b, Fq, FP>,
+{
+    #[tracing::instrument(skip_all)]
+    fn clear_cofactor_var(
+        p: &ProjectiveVar<Self::Config, FP, CF>,
+    ) -> Result<ProjectiveVar<Self::Config, FP, CF>, SynthesisError> {
+        let cs = p.cs();
+        tracing::info!(num_constraints = cs.num_constraints());
+
+        // Based on Section 5 of https://eprint.iacr.org/2019/403.pdf
+        // multiplying by the effective cofactor h_eff = 1 - x is enough,
+        // which is also what `ark_bls12_381::g1::Config::clear_cofactor` does.
+        //
+        // x is negative, so [1 - x]P = P + [|x|]P
+        let x: &'static [u64] = <CurveConfig as Bls12Config>::X;
+        let x = x
+            .iter()
+            .flat_map(|value| {
+                BigInteger64::from(*value)
+                    .to_bits_le()
+                    .into_iter()
+                    .map(Boolean::constant)
+            })
+            .collect::<Vec<_>>();
+
+        let x_p = p.scalar_mul_le_unchecked(x.iter())?;
+        let ret = Ok(x_p.add_unchecked(p));
+
+        tracing::info!(num_constraints = cs.num_constraints());
+
+        ret
+    }
+}
+
 #[cfg(test)]
 mod test {
     use std::ops::Neg;
 
     use ark_bls12_381::{g2::Config, Fq, Fq2, Fq2Config};
     use ark_ec::{
-        short_weierstrass::{Affine, Projective},
-        AffineRepr,
+        short_weierstrass::{Affine, Projective, SWCurveConfig},
+        AffineRepr, CurveGroup,
     };
     use ark_ff::{AdditiveGroup, Field, Fp2ConfigWrapper, MontFp, UniformRand};
     use ark_r1cs_std::{
+        alloc::AllocationMode,
         fields::{fp::FpVar, quadratic_extension::QuadExtVar},
         groups::{curves::short_weierstrass::ProjectiveVar, CurveVar},
+        R1CSVar,
     };
+    use ark_relations::r1cs::ConstraintSystem;
     use rand::{thread_rng, Rng};
 
-    use crate::hash::hash_to_curve::cofactor::bls12_381::{
-        double_p_power_endomorphism_var, p_power_endomorphism_var,
+    use crate::hash::hash_to_curve::cofactor::{
+        bls12_381::{double_p_power_endomorphism_var, p_power_endomorphism_var},
+        mul_by_cofactor_var, CofactorGadget,
     };
 
     // PSI_X = 1/(u+1)^((p-1)/3)
@@ -319,4 +363,44 @@ mod test {
 
         assert_eq!(psi_p, psi_p_var);
     }
+
+    #[test]
+    fn test_clear_cofactor() {
+        type G2Var =
+            ProjectiveVar<Config, QuadExtVar<FpVar<Fq>, Fp2ConfigWrapper<Fq2Config>, Fq>, Fq>;
+
+        let p = sample_unchecked();
+        let cs = ConstraintSystem::<Fq>::new_ref();
+        let p_var = G2Var::new_variable_omit_prime_order_check(
+            cs.clone(),
+            || Ok(p.into_group()),
+            AllocationMode::Witness,
+        )
+        .unwrap();
+
+        // Budroni-Pintore: [h(ψ)]P with two multiplications by |x|, which is 64 bits long
+        let start = cs.num_constraints();
+        let cleared = Projective::<Config>::clear_cofactor_var(&p_var).unwrap();
+        let endomorphism_constraints = cs.num_constraints() - start;
+
+        // the naive way: double-and-add over the cofactor of G2, which is over 500 bits long
+        let start = cs.num_constraints();
+        let multiplied = mul_by_cofactor_var(&p_var).unwrap();
+        let naive_constraints = cs.num_constraints() - start;
+
+        assert!(cs.is_satisfied().unwrap());
+        assert_eq!(cleared.value().unwrap(), Config::clear_cofactor(&p));
+        assert_eq!(multiplied.value().unwrap(), p.mul_by_cofactor());
+        assert!(cleared
+            .value()
+            .unwrap()
+            .into_affine()
+            .is_in_correct_subgroup_assuming_on_curve());
+
+        println!(
+            "Clearing the cofactor of G2: {} constraints with the endomorphism, {} with double-and-add",
+            endomorphism_constraints, naive_constraints
+        );
+        assert!(endomorphism_constraints < naive_constraints);
+    }
 }
@@ -1,7 +1,7 @@
 pub mod bls12_377;
 pub mod bls12_381;
 
-use ark_ec::{short_weierstrass::SWCurveConfig, CurveConfig, CurveGroup};
+use ark_ec::{short_weierstrass::SWCurveConfig, CurveGroup};
 use ark_ff::{BigInteger, BigInteger64, PrimeField};
 use ark_r1cs_std::{
     fields::{FieldOpsBounds, FieldVar},
@@ -9,6 +9,7 @@ use ark_r1cs_std::{
     prelude::Boolean,
 };
 use ark_relations::r1cs::SynthesisError;
+use ark_std::vec::Vec;
 
 /// Trait for clearing cofactor. When implementing this trait for different `CurveGroup`,
 /// remember to check how they specialize in clearing the cofactor. Here, the trait provides
@@ -22,16 +23,29 @@ where
     fn clear_cofactor_var(
         point: &ProjectiveVar<Self::Config, FP, CF>,
     ) -> Result<ProjectiveVar<Self::Config, FP, CF>, SynthesisError> {
-        let cofactor_bits: Vec<_> = <Self::Config as CurveConfig>::COFACTOR
-            .iter()
-            .flat_map(|value| {
-                BigInteger64::from(*value)
-                    .to_bits_le()
-                    .into_iter()
-                    .map(Boolean::constant)
-            })
-            .collect();
-
-        point.scalar_mul_le_unchecked(cofactor_bits.iter())
+        mul_by_cofactor_var(point)
     }
 }
+
+/// Multiply `point` by the full cofactor of its curve with double-and-add.
+///
+/// This is the default of `CofactorGadget::clear_cofactor_var`, and the baseline the
+/// specialized implementations are measured against.
+pub fn mul_by_cofactor_var<P: SWCurveConfig, FP: FieldVar<P::BaseField, CF>, CF: PrimeField>(
+    point: &ProjectiveVar<P, FP, CF>,
+) -> Result<ProjectiveVar<P, FP, CF>, SynthesisError>
+where
+    for<'a> &'a FP: FieldOpsBounds<'a, P::BaseField, FP>,
+{
+    let cofactor_bits: Vec<_> = P::COFACTOR
+        .iter()
+        .flat_map(|value| {
+            BigInteger64::from(*value)
+                .to_bits_le()
+                .into_iter()
+                .map(Boolean::constant)
+        })
+        .collect();
+
+    point.scalar_mul_le_unchecked(cofactor_bits.iter())
+}
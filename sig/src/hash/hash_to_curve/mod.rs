@@ -1,6 +1,7 @@
 pub mod cofactor;
+pub mod suites;
 
-use std::marker::PhantomData;
+use core::marker::PhantomData;
 
 use ark_ec::{short_weierstrass::SWCurveConfig, CurveGroup};
 use ark_ff::PrimeField;
@@ -15,6 +16,21 @@ use ark_relations::r1cs::SynthesisError;
 use super::{hash_to_field::HashToFieldGadget, map_to_curve::MapToCurveGadget};
 use cofactor::CofactorGadget;
 
+/// Trait for hashing an arbitrary message to a point of the curve `T` in R1CS, the
+/// counterpart of `ark_ec::hashing::HashToCurve`.
+pub trait HashToCurveGadget<T: CurveGroup, CF: PrimeField, FP: FieldVar<T::BaseField, CF>>:
+    Sized
+{
+    /// Initialises a new hasher with the domain separation tag `domain`.
+    fn new(domain: &[UInt8<CF>]) -> Self;
+
+    /// Hash `msg` to a point in the prime-order subgroup of `T`.
+    fn hash(&self, msg: &[UInt8<CF>]) -> Result<ProjectiveVar<T::Config, FP, CF>, SynthesisError>
+    where
+        <T as CurveGroup>::Config: SWCurveConfig,
+        for<'a> &'a FP: FieldOpsBounds<'a, <T as CurveGroup>::BaseField, FP>;
+}
+
 /// Helper struct that can be used to construct elements on the elliptic curve
 /// from arbitrary messages, by first hashing the message onto a field element
 /// and then mapping it to the elliptic curve defined over that field.
@@ -30,7 +46,8 @@ where
     _phantom: PhantomData<(T, M2C, CF, FP)>,
 }
 
-impl<T, H2F, M2C, CF, FP> MapToCurveBasedHasherGadget<T, H2F, M2C, CF, FP>
+impl<T, H2F, M2C, CF, FP> HashToCurveGadget<T, CF, FP>
+    for MapToCurveBasedHasherGadget<T, H2F, M2C, CF, FP>
 where
     T: CurveGroup + CofactorGadget<FP, CF>,
     H2F: HashToFieldGadget<T::BaseField, CF, FP>,
@@ -40,8 +57,7 @@ where
     for<'a> &'a FP: FieldOpsBounds<'a, <T as CurveGroup>::BaseField, FP>,
     <T as CurveGroup>::Config: SWCurveConfig,
 {
-    #[must_use]
-    pub fn new(domain: &[UInt8<CF>]) -> Self {
+    fn new(domain: &[UInt8<CF>]) -> Self {
         Self {
             field_hasher: H2F::new(domain),
             _phantom: PhantomData,
@@ -53,10 +69,7 @@ where
     /// oracle encoding (hash_to_curve) defined by combining these components.
     /// See <https://tools.ietf.org/html/draft-irtf-cfrg-hash-to-curve-09#section-3>
     #[tracing::instrument(skip_all)]
-    pub fn hash(
-        &self,
-        msg: &[UInt8<CF>],
-    ) -> Result<ProjectiveVar<T::Config, FP, CF>, SynthesisError>
+    fn hash(&self, msg: &[UInt8<CF>]) -> Result<ProjectiveVar<T::Config, FP, CF>, SynthesisError>
     where
         <T as CurveGroup>::Config: SWCurveConfig,
         for<'a> &'a FP: FieldOpsBounds<'a, <T as CurveGroup>::BaseField, FP>,
@@ -119,14 +132,20 @@ mod test {
         CurveConfig, CurveGroup,
     };
     use ark_ff::{field_hashers::DefaultFieldHasher, Field};
-    use ark_r1cs_std::{alloc::AllocVar, fields::fp2::Fp2Var, uint8::UInt8, R1CSVar};
+    use ark_r1cs_std::{
+        alloc::AllocVar,
+        fields::{emulated_fp::EmulatedFpVar, fp::FpVar, fp2::Fp2Var},
+        uint8::UInt8,
+        R1CSVar,
+    };
     use ark_relations::r1cs::ConstraintSystem;
     use blake2::Blake2s256;
     use rand::{thread_rng, RngCore};
 
     use crate::hash::{
-        hash_to_curve::MapToCurveBasedHasherGadget,
-        hash_to_field::default_hasher::DefaultFieldHasherGadget, map_to_curve::wb::WBMapGadget,
+        hash_to_curve::{HashToCurveGadget, MapToCurveBasedHasherGadget},
+        hash_to_field::default_hasher::DefaultFieldHasherGadget,
+        map_to_curve::wb::WBMapGadget,
     };
 
     macro_rules! generate_hash_to_curve_tests {
@@ -271,6 +290,12 @@ mod test {
         };
     }
 
+    generate_hash_to_curve_tests!(
+        test_hash_to_curve_bls12_381_g1,
+        FpVar<ark_bls12_381::Fq>,
+        ark_bls12_381::G1Projective
+    );
+
     generate_hash_to_curve_tests!(
         test_hash_to_curve_bls12_381_g2,
         Fp2Var<ark_bls12_381::Fq2Config>,
@@ -282,4 +307,35 @@ mod test {
         Fp2Var<ark_bls12_377::Fq2Config>,
         ark_bls12_377::G2Projective
     );
+
+    #[test]
+    #[ignore = "field emulation takes a long time to finish running"]
+    fn test_hash_to_curve_bls12_381_g2_emulated() {
+        use ark_bls12_381::{Fq, Fq2Config, Fr};
+
+        // the base field is emulated, as in the folding circuits
+        type FieldVar = Fp2Var<Fq2Config, EmulatedFpVar<Fq, Fr>, Fr>;
+        type FieldHasher = DefaultFieldHasher<Blake2s256, 128>;
+        type CurveMap = WBMap<ark_bls12_381::g2::Config>;
+        type Hasher = MapToCurveBasedHasher<ark_bls12_381::G2Projective, FieldHasher, CurveMap>;
+        type HasherGadget = MapToCurveBasedHasherGadget<
+            ark_bls12_381::G2Projective,
+            DefaultFieldHasherGadget<Blake2sGadget<Fr>, ark_bls12_381::Fq2, Fr, FieldVar, 128>,
+            WBMapGadget<ark_bls12_381::g2::Config>,
+            Fr,
+            FieldVar,
+        >;
+
+        let cs = ConstraintSystem::new_ref();
+        let dst = b"BLS_SIG_TEST";
+        let msg = b"Hello World";
+        let dst_var: Vec<_> = dst.iter().copied().map(UInt8::constant).collect();
+        let msg_var = UInt8::new_witness_vec(cs.clone(), msg).unwrap();
+
+        let hash = Hasher::new(dst).unwrap().hash(msg).unwrap();
+        let hash_var = HasherGadget::new(&dst_var).hash(&msg_var).unwrap();
+
+        assert_eq!(hash_var.value().unwrap(), hash);
+        assert!(cs.is_satisfied().unwrap());
+    }
 }
@@ -0,0 +1,118 @@
+//! Ciphersuites from [RFC 9380](https://www.rfc-editor.org/rfc/rfc9380.html).
+//!
+//! The BLS signature in `crate::bls` uses Blake2s because it is cheaper in R1CS.
+//! The suites here follow the RFC exactly, so they can be used whenever
+//! interoperability with other implementations matters more than circuit size.
+
+use ark_bls12_381::{g2, Fq2, G2Projective};
+use ark_crypto_primitives::prf::sha256::constraints::Sha256Gadget;
+use ark_ec::hashing::{curve_maps::wb::WBMap, map_to_curve_hasher::MapToCurveBasedHasher};
+use ark_ff::field_hashers::DefaultFieldHasher;
+use sha2::Sha256;
+
+use crate::hash::{
+    hash_to_field::default_hasher::DefaultFieldHasherGadget, map_to_curve::wb::WBMapGadget,
+};
+
+use super::MapToCurveBasedHasherGadget;
+
+/// Suite ID of `BLS12381G2_XMD:SHA-256_SSWU_RO_`
+pub const BLS12381G2_XMD_SHA256_SSWU_RO: &[u8] = b"BLS12381G2_XMD:SHA-256_SSWU_RO_";
+
+/// Native hasher for `BLS12381G2_XMD:SHA-256_SSWU_RO_`
+pub type Bls12381G2XmdSha256SswuRo =
+    MapToCurveBasedHasher<G2Projective, DefaultFieldHasher<Sha256, 128>, WBMap<g2::Config>>;
+
+/// R1CS hasher for `BLS12381G2_XMD:SHA-256_SSWU_RO_`
+pub type Bls12381G2XmdSha256SswuRoGadget<CF, FP> = MapToCurveBasedHasherGadget<
+    G2Projective,
+    DefaultFieldHasherGadget<Sha256Gadget<CF>, Fq2, CF, FP, 128>,
+    WBMapGadget<g2::Config>,
+    CF,
+    FP,
+>;
+
+#[cfg(test)]
+mod test {
+    use ark_bls12_381::{Fq, Fq2, G2Affine};
+    use ark_ec::{hashing::HashToCurve, CurveGroup};
+    use ark_ff::PrimeField;
+    use ark_r1cs_std::{alloc::AllocVar, fields::fp2::Fp2Var, uint8::UInt8, R1CSVar};
+    use ark_relations::r1cs::ConstraintSystem;
+
+    use crate::hash::hash_to_curve::HashToCurveGadget;
+
+    use super::{Bls12381G2XmdSha256SswuRo, Bls12381G2XmdSha256SswuRoGadget};
+
+    // RFC 9380, Appendix J.10.1
+    const DST: &[u8] = b"QUUX-V01-CS02-with-BLS12381G2_XMD:SHA-256_SSWU_RO_";
+
+    // (msg, P.x.c0, P.x.c1, P.y.c0, P.y.c1)
+    const VECTORS: [(&str, &str, &str, &str, &str); 2] = [
+        (
+            "",
+            "0141ebfbdca40eb85b87142e130ab689c673cf60f1a3e98d69335266f30d9b8d4ac44c1038e9dcdd5393faf5c41fb78a",
+            "05cb8437535e20ecffaef7752baddf98034139c38452458baeefab379ba13dff5bf5dd71b72418717047f5b0f37da03d",
+            "0503921d7f6a12805e72940b963c0cf3471c7b2a524950ca195d11062ee75ec076daf2d4bc358c4b190c0c98064fdd92",
+            "12424ac32561493f3fe3c260708a12b7c620e7be00099a974e259ddc7d1f6395c3c811cdd19f1e8dbf3e9ecfdcbab8d6",
+        ),
+        (
+            "abc",
+            "02c2d18e033b960562aae3cab37a27ce00d80ccd5ba4b7fe0e7a210245129dbec7780ccc7954725f4168aff2787776e6",
+            "139cddbccdc5e91b9623efd38c49f81a6f83f175e80b06fc374de9eb4b41dfe4ca3a230ed250fbe3a2acf73a41177fd8",
+            "1787327b68159716a37440985269cf584bcb1e621d3a7202be6ea05c4cfe244aeb197642555a0645fb87bf7466b2ba48",
+            "00aa65dae3c8d732d10ecd2c50f8a1baf3001578f71c694e03866e9f3d49ac1e1ce70dd94a733534f106d4cec0eddd16",
+        ),
+    ];
+
+    fn fq_from_hex(hex: &str) -> Fq {
+        let bytes: Vec<u8> = (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect();
+        Fq::from_be_bytes_mod_order(&bytes)
+    }
+
+    fn expected_point(vector: &(&str, &str, &str, &str, &str)) -> G2Affine {
+        let (_, x0, x1, y0, y1) = *vector;
+        let point = G2Affine::new_unchecked(
+            Fq2::new(fq_from_hex(x0), fq_from_hex(x1)),
+            Fq2::new(fq_from_hex(y0), fq_from_hex(y1)),
+        );
+        assert!(point.is_on_curve() && point.is_in_correct_subgroup_assuming_on_curve());
+        point
+    }
+
+    #[test]
+    fn check_native_test_vectors() {
+        let hasher = Bls12381G2XmdSha256SswuRo::new(DST).unwrap();
+        for vector in &VECTORS {
+            assert_eq!(
+                hasher.hash(vector.0.as_bytes()).unwrap(),
+                expected_point(vector)
+            );
+        }
+    }
+
+    #[test]
+    fn check_gadget_test_vectors() {
+        type HasherGadget = Bls12381G2XmdSha256SswuRoGadget<Fq, Fp2Var<ark_bls12_381::Fq2Config>>;
+
+        for vector in &VECTORS {
+            let cs = ConstraintSystem::new_ref();
+            let dst: Vec<_> = DST.iter().copied().map(UInt8::constant).collect();
+            let msg: Vec<_> = vector
+                .0
+                .as_bytes()
+                .iter()
+                .map(|b| UInt8::new_input(cs.clone(), || Ok(b)).unwrap())
+                .collect();
+
+            let hasher_gadget = HasherGadget::new(&dst);
+            let hash = hasher_gadget.hash(&msg).unwrap();
+
+            assert_eq!(hash.value().unwrap().into_affine(), expected_point(vector));
+            assert!(cs.is_satisfied().unwrap());
+        }
+    }
+}
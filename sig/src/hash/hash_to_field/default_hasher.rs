@@ -1,13 +1,37 @@
 use core::marker::PhantomData;
 
 use super::{
-    expander::ExpanderXmdGadget, from_base_field::FromBaseFieldVarGadget,
-    from_base_field::FromBitsGadget, HashToFieldGadget,
+    from_base_field::FromBaseFieldVarGadget, from_base_field::FromBitsGadget, FieldHasher,
+    FieldHasherGadget, HashToFieldGadget,
+};
+use crate::hash::expand::{ExpandMsgGadget, ExpanderXmdGadget};
+use ark_crypto_primitives::prf::{blake2s::constraints::Blake2sGadget, XofGadget};
+use ark_ff::{
+    field_hashers::{get_len_per_elem, DefaultFieldHasher},
+    Field, PrimeField,
 };
-use ark_crypto_primitives::prf::PRFGadget;
-use ark_ff::{field_hashers::get_len_per_elem, Field, PrimeField};
 use ark_r1cs_std::{fields::FieldVar, prelude::ToBitsGadget, uint8::UInt8, R1CSVar};
 use ark_relations::r1cs::SynthesisError;
+use ark_std::vec::Vec;
+use blake2::Blake2s256;
+
+/// `DefaultFieldHasher` with Blake2s as the `FieldHasher` of the signature schemes, which
+/// follows the IRTF hash to curve specification.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Blake2sHasher;
+
+impl FieldHasher for Blake2sHasher {
+    const G1_SUITE: &'static [u8] = b"BLS12G1_XMD:BLAKE2S_WB_RO_";
+    const G2_SUITE: &'static [u8] = b"BLS12G2_XMD:BLAKE2S_WB_RO_";
+
+    type Native<TF: Field> = DefaultFieldHasher<Blake2s256, 128>;
+}
+
+impl<TF: Field, CF: PrimeField, FP: FieldVar<TF, CF> + FromBaseFieldVarGadget<CF>>
+    FieldHasherGadget<TF, CF, FP> for Blake2sHasher
+{
+    type Gadget = DefaultFieldHasherGadget<Blake2sGadget<CF>, TF, CF, FP, 128>;
+}
 
 /// This struct implements R1CS equivalent of `DefaultFieldHasher`. It works as follow
 /// - Use `ExpanderGadget` to derive a vector of uniform bytes
@@ -18,7 +42,7 @@ use ark_relations::r1cs::SynthesisError;
 ///   `BasePrimeFieldVar` elements (the method to construct them from bits is defined in
 ///   `FromBaseFieldGadget` trait))
 pub struct DefaultFieldHasherGadget<
-    H: PRFGadget<CF> + Default,
+    H: XofGadget<CF>,
     TF: Field,
     CF: PrimeField,
     FP: FieldVar<TF, CF>,
@@ -30,7 +54,7 @@ pub struct DefaultFieldHasherGadget<
 }
 
 impl<
-        H: PRFGadget<CF> + Default,
+        H: XofGadget<CF>,
         TF: Field,
         CF: PrimeField,
         FP: FieldVar<TF, CF> + FromBaseFieldVarGadget<CF>,
@@ -95,13 +119,24 @@ impl<
 #[cfg(test)]
 mod test {
     use ark_crypto_primitives::prf::blake2s::constraints::Blake2sGadget;
-    use ark_ff::field_hashers::{DefaultFieldHasher, HashToField};
-    use ark_r1cs_std::{alloc::AllocVar, fields::fp::FpVar, uint8::UInt8, R1CSVar};
+    use ark_ff::{
+        field_hashers::{DefaultFieldHasher, HashToField},
+        Field, PrimeField,
+    };
+    use ark_r1cs_std::{
+        alloc::AllocVar,
+        fields::{emulated_fp::EmulatedFpVar, fp::FpVar, fp2::Fp2Var, FieldVar},
+        uint8::UInt8,
+        R1CSVar,
+    };
     use ark_relations::r1cs::ConstraintSystem;
     use blake2::Blake2s256;
     use rand::{thread_rng, Rng};
 
-    use crate::hash::hash_to_field::{default_hasher::DefaultFieldHasherGadget, HashToFieldGadget};
+    use crate::hash::hash_to_field::{
+        default_hasher::DefaultFieldHasherGadget, from_base_field::FromBaseFieldVarGadget,
+        HashToFieldGadget,
+    };
 
     #[test]
     fn test_hash_to_field_constant() {
@@ -110,11 +145,10 @@ mod test {
         let mut rng = thread_rng();
 
         let dst: [u8; 16] = [0; 16];
-        let dst_var: [UInt8<F>; 16] = dst.map(UInt8::constant);
 
         let hasher = <DefaultFieldHasher<Blake2s256, 128> as HashToField<F>>::new(&dst);
         let hasher_gadget =
-            DefaultFieldHasherGadget::<Blake2sGadget<F>, F, F, FpVar<F>, 128>::new(&dst_var);
+            DefaultFieldHasherGadget::<Blake2sGadget<F>, F, F, FpVar<F>, 128>::new_constant(&dst);
 
         let input_lens = (0..32).chain(32..256).filter(|a| a % 8 == 0);
 
@@ -143,11 +177,10 @@ mod test {
         let mut rng = thread_rng();
 
         let dst: [u8; 16] = [0; 16];
-        let dst_var: [UInt8<F>; 16] = dst.map(UInt8::constant);
 
         let hasher = <DefaultFieldHasher<Blake2s256, 128> as HashToField<F>>::new(&dst);
         let hasher_gadget =
-            DefaultFieldHasherGadget::<Blake2sGadget<F>, F, F, FpVar<F>, 128>::new(&dst_var);
+            DefaultFieldHasherGadget::<Blake2sGadget<F>, F, F, FpVar<F>, 128>::new_constant(&dst);
 
         let input_lens = (0..32).chain(32..128).filter(|a| a % 16 == 0);
 
@@ -172,4 +205,78 @@ mod test {
             );
         }
     }
+
+    #[test]
+    fn test_hash_to_field_variable_dst() {
+        use ark_bls12_381::Fr as F;
+
+        let mut rng = thread_rng();
+        let cs = ConstraintSystem::new_ref();
+
+        let mut dst = [0u8; 16];
+        rng.fill(&mut dst);
+        let dst_var = UInt8::new_witness_vec(cs.clone(), &dst).unwrap();
+        let msg = UInt8::new_witness_vec(cs.clone(), b"Hello World").unwrap();
+
+        let hasher = <DefaultFieldHasher<Blake2s256, 128> as HashToField<F>>::new(&dst);
+        let hasher_gadget =
+            DefaultFieldHasherGadget::<Blake2sGadget<F>, F, F, FpVar<F>>::new(&dst_var);
+
+        let s1: [F; 2] = hasher.hash_to_field::<2>(b"Hello World");
+        let s2: [FpVar<F>; 2] = hasher_gadget.hash_to_field::<2>(&msg).unwrap();
+
+        assert!(cs.is_satisfied().unwrap());
+        assert_eq!(s1.to_vec(), s2.value().unwrap().to_vec());
+    }
+
+    /// Hash random messages of `input_lens` bytes, allocated as witnesses, to 2 elements of
+    /// `TF`, and compare them with `DefaultFieldHasher`.
+    fn check_against_native<TF, CF, FV>(input_lens: &[usize])
+    where
+        TF: Field,
+        CF: PrimeField,
+        FV: FieldVar<TF, CF> + FromBaseFieldVarGadget<CF>,
+    {
+        let mut rng = thread_rng();
+
+        let dst: [u8; 16] = [0; 16];
+
+        let hasher = <DefaultFieldHasher<Blake2s256, 128> as HashToField<TF>>::new(&dst);
+        let hasher_gadget =
+            DefaultFieldHasherGadget::<Blake2sGadget<CF>, TF, CF, FV, 128>::new_constant(&dst);
+
+        for &input_len in input_lens {
+            let cs = ConstraintSystem::new_ref();
+            let mut msg = vec![0u8; input_len];
+            rng.fill(&mut *msg);
+            let msg_var = UInt8::new_witness_vec(cs.clone(), &msg).unwrap();
+
+            let s1: [TF; 2] = hasher.hash_to_field::<2>(&msg);
+            let s2: [FV; 2] = hasher_gadget.hash_to_field::<2>(&msg_var).unwrap();
+
+            assert_eq!(
+                s1.to_vec(),
+                s2.iter()
+                    .map(|value| value.value().unwrap())
+                    .collect::<Vec<_>>()
+            );
+            assert!(cs.is_satisfied().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_hash_to_field_emulated() {
+        use ark_bls12_381::{Fq, Fr};
+
+        check_against_native::<Fq, Fr, EmulatedFpVar<Fq, Fr>>(&[0, 16, 64]);
+    }
+
+    #[test]
+    fn test_hash_to_field_extension() {
+        use ark_bls12_381::{Fq, Fq2, Fq2Config, Fr};
+
+        // native and emulated base prime fields
+        check_against_native::<Fq2, Fq, Fp2Var<Fq2Config>>(&[0, 16, 64]);
+        check_against_native::<Fq2, Fr, Fp2Var<Fq2Config, EmulatedFpVar<Fq, Fr>, Fr>>(&[0, 32]);
+    }
 }
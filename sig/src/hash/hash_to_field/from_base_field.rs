@@ -1,14 +1,20 @@
+use core::marker::PhantomData;
+
 use ark_ff::{CubicExtConfig, PrimeField, QuadExtConfig};
 use ark_r1cs_std::{
     fields::{
         cubic_extension::{CubicExtVar, CubicExtVarConfig},
-        emulated_fp::EmulatedFpVar,
+        emulated_fp::{
+            params::{get_params, OptimizationType},
+            AllocatedEmulatedFpVar, EmulatedFpVar,
+        },
         fp::FpVar,
         quadratic_extension::{QuadExtVar, QuadExtVarConfig},
     },
     prelude::*,
 };
-use ark_relations::r1cs::SynthesisError;
+use ark_relations::r1cs::{OptimizationGoal, SynthesisError};
+use ark_std::vec::Vec;
 
 pub trait FromBitsGadget<CF: PrimeField>: Sized {
     fn from_le_bits(bits: &[Boolean<CF>]) -> Self;
@@ -72,21 +78,50 @@ impl<F: PrimeField, CF: PrimeField> FromBitsGadget<CF> for EmulatedFpVar<F, CF>
                 .collect::<Vec<_>>();
             Self::constant(F::from_le_bytes_mod_order(&bytes))
         } else {
+            let cs = bits.cs();
+            let optimization_type = match cs.optimization_goal() {
+                OptimizationGoal::Weight => OptimizationType::Weight,
+                OptimizationGoal::None | OptimizationGoal::Constraints => {
+                    OptimizationType::Constraints
+                }
+            };
+            let params = get_params(
+                F::MODULUS_BIT_SIZE as usize,
+                CF::MODULUS_BIT_SIZE as usize,
+                optimization_type,
+            );
+
+            // Split the bits into parts shorter than the modulus, which are reduced, so that
+            // the limbs of a part are its bits regrouped, without any constraint. The parts
+            // are then combined with a multiplication by a constant per part, instead of one
+            // per bit.
+            let part_len = F::MODULUS_BIT_SIZE as usize - 1;
+            let shift = F::from(2u8).pow([part_len as u64]);
             let mut power = F::one();
-            // Compute a linear combination for the new field variable, again
-            // via double and add.
 
-            let combined = bits
-                .iter()
-                .map(|b| {
-                    let result = Self::from(b.clone()) * power;
-                    power.double_in_place();
+            bits.chunks(part_len)
+                .map(|part| {
+                    // limbs are stored with the most significant one first
+                    let mut limbs = part
+                        .chunks(params.bits_per_limb)
+                        .map(FpVar::from_le_bits)
+                        .collect::<Vec<_>>();
+                    limbs.resize(params.num_limbs, FpVar::zero());
+                    limbs.reverse();
+
+                    let part = Self::Var(AllocatedEmulatedFpVar {
+                        cs: cs.clone(),
+                        limbs,
+                        num_of_additions_over_normal_form: CF::zero(),
+                        is_in_the_normal_form: true,
+                        target_phantom: PhantomData,
+                    });
+                    let result = if power.is_one() { part } else { part * power };
+                    power *= shift;
                     result
                 })
                 .reduce(core::ops::Add::add)
-                .unwrap_or_else(Self::zero);
-
-            combined
+                .unwrap_or_else(Self::zero)
         }
     }
 }
@@ -97,9 +132,27 @@ impl<F: PrimeField, CF: PrimeField> FromBitsGadget<CF> for EmulatedFpVar<F, CF>
 pub trait FromBaseFieldVarGadget<CF: PrimeField>: Sized {
     type BasePrimeFieldVar: FromBaseFieldVarGadget<CF> + FromBitsGadget<CF>;
 
+    /// Construct `Self` from the first base prime field variables of `iter`, leaving the
+    /// remaining ones in `iter`.
     fn from_base_field_var(
         iter: impl Iterator<Item = Self::BasePrimeFieldVar>,
     ) -> Result<Self, SynthesisError>;
+
+    /// R1CS equivalent of `Field::from_base_prime_field_elems`, e.g. to build an `Fp2Var`,
+    /// `Fp6Var` or `Fp12Var` from 2, 6 or 12 `FpVar`s or `EmulatedFpVar`s.
+    ///
+    /// It fails if `elems` does not hold exactly as many variables as the extension degree
+    /// of `Self` over its base prime field.
+    fn from_base_prime_field_vars(
+        elems: impl IntoIterator<Item = Self::BasePrimeFieldVar>,
+    ) -> Result<Self, SynthesisError> {
+        let mut iter = elems.into_iter();
+        let res = Self::from_base_field_var(iter.by_ref())?;
+        match iter.next() {
+            Some(_) => Err(SynthesisError::Unsatisfiable),
+            None => Ok(res),
+        }
+    }
 }
 
 impl<CF: PrimeField> FromBaseFieldVarGadget<CF> for FpVar<CF> {
@@ -162,3 +215,84 @@ where
         Ok(Self::new(c0, c1, c2))
     }
 }
+
+#[cfg(test)]
+mod test {
+    use ark_bls12_381::{Fq, Fq12, Fq12Config, Fq2, Fq2Config, Fq6, Fq6Config, Fr};
+    use ark_ff::{Field, PrimeField, UniformRand};
+    use ark_r1cs_std::{
+        alloc::AllocVar,
+        fields::{
+            emulated_fp::EmulatedFpVar, fp::FpVar, fp12::Fp12Var, fp2::Fp2Var, fp6_3over2::Fp6Var,
+            FieldVar,
+        },
+        R1CSVar,
+    };
+    use ark_relations::r1cs::ConstraintSystem;
+    use rand::thread_rng;
+
+    use super::FromBaseFieldVarGadget;
+    use crate::hash::map_to_curve::to_base_field::ToBaseFieldVarGadget;
+
+    /// Build an element of `TF` from random witnesses of its base prime field, and compare it
+    /// with `Field::from_base_prime_field_elems`.
+    fn check_against_native<TF, CF, FV>()
+    where
+        TF: Field,
+        CF: PrimeField,
+        FV: FieldVar<TF, CF>
+            + FromBaseFieldVarGadget<CF>
+            + ToBaseFieldVarGadget<TF::BasePrimeField, CF>,
+        TF::BasePrimeField: PrimeField,
+        <FV as FromBaseFieldVarGadget<CF>>::BasePrimeFieldVar: FieldVar<TF::BasePrimeField, CF>,
+    {
+        let mut rng = thread_rng();
+        let cs = ConstraintSystem::<CF>::new_ref();
+
+        let degree = usize::try_from(TF::extension_degree()).unwrap();
+        let elems: Vec<_> = (0..degree)
+            .map(|_| TF::BasePrimeField::rand(&mut rng))
+            .collect();
+        let elem_vars: Vec<_> = elems
+            .iter()
+            .map(|e| AllocVar::new_witness(cs.clone(), || Ok(*e)).unwrap())
+            .collect();
+
+        let expected = TF::from_base_prime_field_elems(elems.iter().copied()).unwrap();
+        let var = FV::from_base_prime_field_vars(elem_vars.clone()).unwrap();
+        assert_eq!(var.value().unwrap(), expected);
+
+        // round trip through `ToBaseFieldVarGadget`
+        let base_field_vars = var.to_base_field_vars().unwrap();
+        assert_eq!(base_field_vars.value().unwrap(), elems);
+
+        // too few or too many variables
+        assert!(FV::from_base_prime_field_vars(elem_vars[1..].to_vec()).is_err());
+        assert!(FV::from_base_prime_field_vars(
+            elem_vars
+                .iter()
+                .chain(&elem_vars[..1])
+                .cloned()
+                .collect::<Vec<_>>()
+        )
+        .is_err());
+
+        assert!(cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_from_base_prime_field_vars() {
+        check_against_native::<Fq, Fq, FpVar<Fq>>();
+        check_against_native::<Fq2, Fq, Fp2Var<Fq2Config>>();
+        check_against_native::<Fq6, Fq, Fp6Var<Fq6Config, FpVar<Fq>, Fq>>();
+        check_against_native::<Fq12, Fq, Fp12Var<Fq12Config, FpVar<Fq>, Fq>>();
+    }
+
+    #[test]
+    fn test_from_base_prime_field_vars_emulated() {
+        type F = EmulatedFpVar<Fq, Fr>;
+
+        check_against_native::<Fq2, Fr, Fp2Var<Fq2Config, F, Fr>>();
+        check_against_native::<Fq12, Fr, Fp12Var<Fq12Config, F, Fr>>();
+    }
+}
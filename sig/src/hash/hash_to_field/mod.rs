@@ -1,21 +1,42 @@
-use ark_ff::{Field, PrimeField};
+use ark_ff::{field_hashers::HashToField, Field, PrimeField};
 use ark_r1cs_std::{fields::FieldVar, uint8::UInt8};
 use ark_relations::r1cs::SynthesisError;
 
-mod expander;
-mod poseidon;
-
 pub mod default_hasher;
 pub mod from_base_field;
+pub mod poseidon;
 
 pub trait HashToFieldGadget<TF: Field, CF: PrimeField, FP: FieldVar<TF, CF>>: Sized {
     /// Initialises a new hash-to-field helper struct.
     ///
     /// # Arguments
     ///
-    /// * `domain` - bytes that get concatenated with the `msg` during hashing, in order to separate potentially interfering instantiations of the hasher.
+    /// * `domain` - bytes that get concatenated with the `msg` during hashing, in order to separate potentially interfering instantiations of the hasher. They can be constants or variables allocated in the circuit.
     fn new(domain: &[UInt8<CF>]) -> Self;
 
+    /// Initialises a new hash-to-field helper struct with a constant `domain`, as
+    /// `HashToField::new` does natively.
+    fn new_constant(domain: &[u8]) -> Self {
+        Self::new(&UInt8::constant_vec(domain))
+    }
+
     /// Hash an arbitrary `msg` to `N` elements of the field `F`.
     fn hash_to_field<const N: usize>(&self, msg: &[UInt8<CF>]) -> Result<[FP; N], SynthesisError>;
 }
+
+/// A hash to field function, used to hash messages to the curve in the signature schemes
+/// of the crate, e.g. as the `H` of `MinPk<H>`.
+pub trait FieldHasher: 'static {
+    /// Hash-to-curve suite IDs of the G1 and G2 groups of BLS12 curves, with this hash to
+    /// field function and the Wahby-Boneh map.
+    const G1_SUITE: &'static [u8];
+    const G2_SUITE: &'static [u8];
+
+    /// The hash to field function over `TF`.
+    type Native<TF: Field>: HashToField<TF>;
+}
+
+/// The R1CS equivalent of a `FieldHasher`, hashing to `TF` inside circuits over `CF`.
+pub trait FieldHasherGadget<TF: Field, CF: PrimeField, FP: FieldVar<TF, CF>>: FieldHasher {
+    type Gadget: HashToFieldGadget<TF, CF, FP>;
+}
@@ -1,55 +1,96 @@
-/// A hash to field implementation based on Poseidon hash function.
+/// A hash to field implementation based on Poseidon hash function, as a SNARK-friendly
+/// alternative to `DefaultFieldHasher` (whose Blake2s expander dominates the constraints of
+/// hashing to the curve).
 ///
-/// Because of the following reasons, this mod is not used in any other files in this project.
+/// It follows the structure of `DefaultFieldHasher`, with a Poseidon sponge as the expander:
+/// - absorb the domain and the message (each prefixed with its length)
+/// - squeeze `L = ceil((ceil(log2(p)) + SEC_PARAM) / 8)` bytes per base field element, so that
+///   reducing them modulo `p` is close to uniform
+/// - read each chunk of `L` bytes as a little-endian integer modulo `p`
+///
+/// Note that
 /// - Poseidon hash is not mentioned in IRTF's hash to curve specification
-/// - It requires curve-dependent setup parameter selection.
-///   - Specifically, this implementation only works for `MontBackend::<_, 4>` right now because `PoseidonDefacultConfig`
-///     is only implemented for that.
+/// - the sponge works over a prime field `F` with the parameters of `poseidon_config`, so
+///   the gadget only agrees with the native hasher when the constraint field is `F`
 use core::{array, marker::PhantomData};
 
 use ark_crypto_primitives::sponge::{
-    poseidon::{PoseidonConfig, PoseidonDefaultConfigField, PoseidonSponge},
+    constraints::CryptographicSpongeVar,
+    poseidon::{constraints::PoseidonSpongeVar, PoseidonConfig, PoseidonSponge},
     CryptographicSponge,
 };
-use ark_ff::{field_hashers::HashToField, Field};
+use ark_ff::{
+    field_hashers::{get_len_per_elem, HashToField},
+    Field, PrimeField,
+};
+use ark_r1cs_std::{fields::FieldVar, prelude::ToBitsGadget, uint8::UInt8, R1CSVar};
+use ark_relations::r1cs::SynthesisError;
+use ark_std::vec::Vec;
+use derivative::Derivative;
+
+use super::{
+    from_base_field::{FromBaseFieldVarGadget, FromBitsGadget},
+    FieldHasher, FieldHasherGadget, HashToFieldGadget,
+};
+use crate::hash::poseidon::poseidon_config;
+
+/// `PoseidonFieldHasher` over `F` as the `FieldHasher` of the signature schemes.
+///
+/// Its gadget is only implemented over the constraint field `F`, where it agrees with the
+/// native hasher, e.g. `F` is the base field of the signature curve to verify signatures
+/// in circuits over that field.
+#[derive(Derivative)]
+#[derivative(
+    Clone(bound = ""),
+    Copy(bound = ""),
+    Debug(bound = ""),
+    Default(bound = "")
+)]
+pub struct PoseidonHasher<F: PrimeField>(PhantomData<F>);
+
+impl<F: PrimeField> FieldHasher for PoseidonHasher<F> {
+    const G1_SUITE: &'static [u8] = b"BLS12G1_POSEIDON_WB_RO_";
+    const G2_SUITE: &'static [u8] = b"BLS12G2_POSEIDON_WB_RO_";
+
+    type Native<TF: Field> = PoseidonFieldHasher<F, 128>;
+}
+
+impl<TF: Field, F: PrimeField, FP: FieldVar<TF, F> + FromBaseFieldVarGadget<F>>
+    FieldHasherGadget<TF, F, FP> for PoseidonHasher<F>
+{
+    type Gadget = PoseidonHashToFieldGadget<TF, F, FP, 128>;
+}
 
 /// Implement `PoseidonFieldHasher` to enable interopability with arkworks.
-pub struct PoseidonFieldHasher<F: PoseidonDefaultConfigField, const SEC_LEVEL: usize = 128> {
+pub struct PoseidonFieldHasher<F: PrimeField, const SEC_PARAM: usize = 128> {
     config: PoseidonConfig<F>,
     domain: Vec<u8>,
-    _params: PhantomData<F>,
+    len_per_base_elem: usize,
 }
 
-impl<TF: Field, F: PoseidonDefaultConfigField, const SEC_LEVEL: usize> HashToField<TF>
-    for PoseidonFieldHasher<F, SEC_LEVEL>
+impl<TF: Field, F: PrimeField, const SEC_PARAM: usize> HashToField<TF>
+    for PoseidonFieldHasher<F, SEC_PARAM>
 {
     fn new(domain: &[u8]) -> Self {
-        // set capacity based on the suggestion at https://www.poseidon-hash.info
-        // ensure that the hash provides at least 128 bit security level
-        //
-        // capacity = ceil(SEC_LEVEL * 2 / MODULUS_BIT_SIZE)
-        let mut config = F::get_default_poseidon_parameters(2, false).unwrap();
-        config.capacity = ((SEC_LEVEL << 1) + F::BasePrimeField::MODULUS_BIT_SIZE as usize - 1)
-            / (F::BasePrimeField::MODULUS_BIT_SIZE) as usize;
-
         Self {
-            config,
+            config: poseidon_config(),
             domain: domain.into(),
-            _params: PhantomData,
+            len_per_base_elem: get_len_per_elem::<TF, SEC_PARAM>(),
         }
     }
 
     fn hash_to_field<const N: usize>(&self, msg: &[u8]) -> [TF; N] {
-        // let msg: Vec<F::BasePrimeField> = msg.to_field_elements().unwrap();
         let mut sponge = PoseidonSponge::new(&self.config);
         sponge.absorb(&self.domain);
         sponge.absorb(&msg);
 
         let ext_degree = usize::try_from(TF::extension_degree())
             .expect("extension degree should be able to store in usize");
-        // if TF::BasePrimeFielf is the same as F, this will be equivalent to squeeze_native_field_elements
-        // (with a runtime type check)
-        let res: Vec<TF::BasePrimeField> = sponge.squeeze_field_elements(N * ext_degree);
+        let uniform_bytes = sponge.squeeze_bytes(N * ext_degree * self.len_per_base_elem);
+        let res: Vec<TF::BasePrimeField> = uniform_bytes
+            .chunks(self.len_per_base_elem)
+            .map(TF::BasePrimeField::from_le_bytes_mod_order)
+            .collect();
 
         let cb = |i| {
             TF::from_base_prime_field_elems((0..ext_degree).map(|j| res[i * ext_degree + j]))
@@ -59,3 +100,156 @@ impl<TF: Field, F: PoseidonDefaultConfigField, const SEC_LEVEL: usize> HashToFie
         array::from_fn::<TF, N, _>(cb)
     }
 }
+
+/// R1CS equivalent of `PoseidonFieldHasher` over the constraint field `CF`.
+pub struct PoseidonHashToFieldGadget<
+    TF: Field,
+    CF: PrimeField,
+    FP: FieldVar<TF, CF>,
+    const SEC_PARAM: usize = 128,
+> {
+    config: PoseidonConfig<CF>,
+    domain: Vec<UInt8<CF>>,
+    len_per_base_elem: usize,
+    _params: PhantomData<(TF, FP)>,
+}
+
+impl<
+        TF: Field,
+        CF: PrimeField,
+        FP: FieldVar<TF, CF> + FromBaseFieldVarGadget<CF>,
+        const SEC_PARAM: usize,
+    > HashToFieldGadget<TF, CF, FP> for PoseidonHashToFieldGadget<TF, CF, FP, SEC_PARAM>
+{
+    fn new(domain: &[UInt8<CF>]) -> Self {
+        Self {
+            config: poseidon_config(),
+            domain: domain.to_vec(),
+            len_per_base_elem: get_len_per_elem::<TF, SEC_PARAM>(),
+            _params: PhantomData,
+        }
+    }
+
+    #[tracing::instrument(skip_all)]
+    fn hash_to_field<const N: usize>(&self, msg: &[UInt8<CF>]) -> Result<[FP; N], SynthesisError> {
+        let cs = msg.cs().or(self.domain.cs());
+        tracing::info!(num_constraints = cs.num_constraints());
+
+        let mut sponge = PoseidonSpongeVar::new(cs.clone(), &self.config);
+        sponge.absorb(&self.domain.as_slice())?;
+        sponge.absorb(&msg)?;
+
+        let m = usize::try_from(TF::extension_degree())
+            .expect("extension degree should be able to store in usize");
+        let uniform_bytes = sponge.squeeze_bytes(N * m * self.len_per_base_elem)?;
+
+        // collect this first to deal with the error
+        let bits_iter: Vec<_> = uniform_bytes
+            .chunks(self.len_per_base_elem)
+            .map(|chunk| chunk.to_bits_le())
+            .collect::<Result<_, _>>()?;
+
+        let mut base_field_var_iter = bits_iter
+            .into_iter()
+            .map(|bits| FP::BasePrimeFieldVar::from_le_bits(&bits));
+
+        // can replace this with `array::try_from` once it becomes stable
+        let f = |_| FP::from_base_field_var(&mut base_field_var_iter);
+        let array = array_util::try_from_fn::<Result<FP, SynthesisError>, N, _>(f);
+
+        tracing::info!(num_constraints = cs.num_constraints());
+
+        array
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ark_crypto_primitives::prf::blake2s::constraints::Blake2sGadget;
+    use ark_ff::{field_hashers::HashToField, Field, PrimeField};
+    use ark_r1cs_std::{
+        fields::{emulated_fp::EmulatedFpVar, fp::FpVar, fp2::Fp2Var, FieldVar},
+        uint8::UInt8,
+        R1CSVar,
+    };
+    use ark_relations::r1cs::ConstraintSystem;
+    use rand::{thread_rng, Rng};
+
+    use super::{PoseidonFieldHasher, PoseidonHashToFieldGadget};
+    use crate::hash::hash_to_field::{
+        default_hasher::DefaultFieldHasherGadget, from_base_field::FromBaseFieldVarGadget,
+        HashToFieldGadget,
+    };
+
+    /// Hash random messages of `input_lens` bytes, allocated as witnesses, to 2 elements of
+    /// `TF` with a sponge over `CF`, and compare them with `PoseidonFieldHasher`.
+    fn check_against_native<TF, CF, FV>(input_lens: &[usize])
+    where
+        TF: Field,
+        CF: PrimeField,
+        FV: FieldVar<TF, CF> + FromBaseFieldVarGadget<CF>,
+    {
+        let mut rng = thread_rng();
+
+        let dst: [u8; 16] = [0; 16];
+
+        let hasher = <PoseidonFieldHasher<CF> as HashToField<TF>>::new(&dst);
+        let hasher_gadget = PoseidonHashToFieldGadget::<TF, CF, FV>::new_constant(&dst);
+
+        for &input_len in input_lens {
+            let cs = ConstraintSystem::new_ref();
+            let mut msg = vec![0u8; input_len];
+            rng.fill(&mut *msg);
+            let msg_var = UInt8::new_witness_vec(cs.clone(), &msg).unwrap();
+
+            let s1: [TF; 2] = hasher.hash_to_field::<2>(&msg);
+            let s2: [FV; 2] = hasher_gadget.hash_to_field::<2>(&msg_var).unwrap();
+
+            assert!(cs.is_satisfied().unwrap());
+            assert_eq!(s1.to_vec(), s2.value().unwrap().to_vec());
+        }
+    }
+
+    #[test]
+    fn test_hash_to_field() {
+        use ark_bls12_381::Fq;
+
+        check_against_native::<Fq, Fq, FpVar<Fq>>(&[0, 16, 64, 128]);
+        check_against_native::<_, Fq, Fp2Var<ark_bls12_381::Fq2Config>>(&[0, 32]);
+    }
+
+    #[test]
+    fn test_hash_to_field_emulated() {
+        use ark_bls12_381::{Fq, Fr};
+
+        check_against_native::<Fq, Fr, EmulatedFpVar<Fq, Fr>>(&[0, 32]);
+    }
+
+    #[test]
+    fn test_fewer_constraints_than_blake2s() {
+        use ark_bls12_381::Fq as F;
+
+        let dst = [0; 16];
+        let msg = [0; 32];
+
+        let poseidon = {
+            let cs = ConstraintSystem::new_ref();
+            let msg_var = UInt8::new_witness_vec(cs.clone(), &msg).unwrap();
+            let _: [FpVar<F>; 2] = PoseidonHashToFieldGadget::<F, F, FpVar<F>>::new_constant(&dst)
+                .hash_to_field(&msg_var)
+                .unwrap();
+            cs.num_constraints()
+        };
+        let blake2s = {
+            let cs = ConstraintSystem::new_ref();
+            let msg_var = UInt8::new_witness_vec(cs.clone(), &msg).unwrap();
+            let _: [FpVar<F>; 2] =
+                DefaultFieldHasherGadget::<Blake2sGadget<F>, F, F, FpVar<F>>::new_constant(&dst)
+                    .hash_to_field(&msg_var)
+                    .unwrap();
+            cs.num_constraints()
+        };
+
+        assert!(poseidon < blake2s);
+    }
+}
@@ -1,4 +1,4 @@
-use std::marker::PhantomData;
+use core::marker::PhantomData;
 
 use ark_ec::{
     hashing::curve_maps::wb::WBConfig,
@@ -13,6 +13,7 @@ use ark_r1cs_std::{
     prelude::Boolean,
 };
 use ark_relations::r1cs::SynthesisError;
+use ark_std::vec::Vec;
 
 type Domain<P> = <P as WBConfig>::IsogenousCurve;
 type CoDomain<P> = P;
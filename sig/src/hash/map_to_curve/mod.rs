@@ -1,6 +1,7 @@
 mod isogeny_map;
 mod norm;
 pub mod sqrt;
+pub mod svdw;
 mod swu;
 pub mod to_base_field;
 pub mod wb;
@@ -0,0 +1,105 @@
+//! The Shallue-van de Woestijne map of [RFC 9380](https://www.rfc-editor.org/rfc/rfc9380.html#section-6.6.1),
+//! which arkworks does not provide.
+//!
+//! Unlike the simplified SWU and Wahby-Boneh maps, it applies to any short Weierstrass curve,
+//! including curves with `A = 0` and no isogenous curve in arkworks, such as the G2 group of
+//! BN254. There is no gadget for it yet.
+use core::marker::PhantomData;
+
+use ark_ec::{
+    hashing::{curve_maps::parity, map_to_curve_hasher::MapToCurve, HashToCurveError},
+    short_weierstrass::{Affine, Projective, SWCurveConfig},
+};
+use ark_ff::{Field, Zero};
+
+/// Parameters of the Shallue-van de Woestijne map for the curve `y^2 = x^3 + A * x + B`.
+pub trait SVDWConfig: SWCurveConfig {
+    /// The constant `Z` of the map, as found by `find_z_svdw` of RFC 9380 (appendix H.1).
+    const Z: Self::BaseField;
+}
+
+#[cfg(feature = "bn254")]
+impl SVDWConfig for ark_bn254::g2::Config {
+    // the first candidate of `find_z_svdw`
+    const Z: ark_bn254::Fq2 = ark_bn254::Fq2::ONE;
+}
+
+pub struct SVDWMap<P: SVDWConfig>(PhantomData<fn() -> P>);
+
+/// The right-hand side of the curve equation, `x^3 + A * x + B`.
+fn g<P: SWCurveConfig>(x: P::BaseField) -> P::BaseField {
+    (x.square() + P::COEFF_A) * x + P::COEFF_B
+}
+
+fn is_square<F: Field>(x: F) -> bool {
+    !x.legendre().is_qnr()
+}
+
+impl<P: SVDWConfig> MapToCurve<Projective<P>> for SVDWMap<P> {
+    /// Check the four criteria of `find_z_svdw`, which make the map defined on every input.
+    fn check_parameters() -> Result<(), HashToCurveError> {
+        let (z, [two, three, four]) = (P::Z, [2u64, 3, 4].map(P::BaseField::from));
+        let g_z = g::<P>(z);
+        let h_z = -(three * z.square() + four * P::COEFF_A);
+
+        if g_z.is_zero() || h_z.is_zero() {
+            return Err(HashToCurveError::MapToCurveError(
+                "g(Z) and 3 * Z^2 + 4 * A should not be 0".into(),
+            ));
+        }
+        if !is_square(h_z / (four * g_z)) {
+            return Err(HashToCurveError::MapToCurveError(
+                "-(3 * Z^2 + 4 * A) / (4 * g(Z)) should be a square".into(),
+            ));
+        }
+        if !is_square(g_z) && !is_square(g::<P>(-z / two)) {
+            return Err(HashToCurveError::MapToCurveError(
+                "g(Z) or g(-Z / 2) should be a square".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// The straight-line implementation of appendix F.1.
+    fn map_to_curve(u: P::BaseField) -> Result<Affine<P>, HashToCurveError> {
+        let (z, [two, three, four]) = (P::Z, [2u64, 3, 4].map(P::BaseField::from));
+        let ok_or = |x: Option<P::BaseField>| {
+            x.ok_or_else(|| {
+                HashToCurveError::MapToCurveError("Z does not satisfy find_z_svdw".into())
+            })
+        };
+
+        // constants
+        let c1 = g::<P>(z);
+        let c2 = -z / two;
+        let h_z = three * z.square() + four * P::COEFF_A;
+        let mut c3 = ok_or((-c1 * h_z).sqrt())?;
+        if parity(&c3) {
+            c3 = -c3;
+        }
+        let c4 = -four * c1 / h_z;
+
+        let tv1 = u.square() * c1;
+        let tv2 = P::BaseField::ONE + tv1;
+        let tv1 = P::BaseField::ONE - tv1;
+        let tv3 = (tv1 * tv2).inverse().unwrap_or_default();
+        let tv4 = u * tv1 * tv3 * c3;
+
+        let x1 = c2 - tv4;
+        let x2 = c2 + tv4;
+        let x3 = (tv2.square() * tv3).square() * c4 + z;
+        let x = if is_square(g::<P>(x1)) {
+            x1
+        } else if is_square(g::<P>(x2)) {
+            x2
+        } else {
+            x3
+        };
+
+        let mut y = ok_or(g::<P>(x).sqrt())?;
+        if parity(&u) != parity(&y) {
+            y = -y;
+        }
+        Ok(Affine::new_unchecked(x, y))
+    }
+}
@@ -7,6 +7,7 @@ use ark_r1cs_std::fields::{
     FieldOpsBounds, FieldVar,
 };
 use ark_relations::r1cs::SynthesisError;
+use ark_std::{vec, vec::Vec};
 
 /// Trait for converting any R1CS variable to a vector of `FieldVar<F: PrimeField, CF: PrimeField>`.
 ///
@@ -1,4 +1,4 @@
-use std::marker::PhantomData;
+use core::marker::PhantomData;
 
 use ark_ec::{
     hashing::curve_maps::wb::WBConfig, short_weierstrass::Projective, CurveConfig, CurveGroup,
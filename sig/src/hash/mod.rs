@@ -1,3 +1,5 @@
+pub mod expand;
 pub mod hash_to_curve;
 pub mod hash_to_field;
 pub mod map_to_curve;
+pub mod poseidon;
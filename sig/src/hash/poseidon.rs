@@ -0,0 +1,27 @@
+use ark_crypto_primitives::sponge::poseidon::{find_poseidon_ark_and_mds, PoseidonConfig};
+use ark_ff::PrimeField;
+
+/// Poseidon parameters of the crate, the same as the ones Nova uses to hash its state.
+///
+/// They are used to hash messages to fields with `PoseidonHasher`, and by the Merkle trees
+/// and commitments of the folding circuits.
+#[must_use]
+pub fn poseidon_config<F: PrimeField>() -> PoseidonConfig<F> {
+    let (full_rounds, partial_rounds, alpha, rate) = (8, 60, 5, 4);
+    let (ark, mds) = find_poseidon_ark_and_mds::<F>(
+        u64::from(F::MODULUS_BIT_SIZE),
+        rate,
+        full_rounds,
+        partial_rounds,
+        0,
+    );
+    PoseidonConfig::new(
+        full_rounds as usize,
+        partial_rounds as usize,
+        alpha,
+        mds,
+        ark,
+        rate,
+        1,
+    )
+}
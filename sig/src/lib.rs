@@ -1,6 +1,16 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
 pub mod bc;
 pub mod bls;
+#[cfg(feature = "std")]
+pub mod eddsa;
+#[cfg(feature = "std")]
 pub mod folding;
 pub mod hash;
+#[cfg(feature = "metrics")]
+pub mod metrics;
 pub mod params;
+pub mod signature;
+pub mod snark;
+#[cfg(feature = "std")]
 mod tests;
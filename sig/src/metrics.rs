@@ -0,0 +1,349 @@
+//! Memory and constraint-matrix metrics of the benchmarks, so that a run that approaches the
+//! limits of the machine, e.g. folding committees of `MAX_COMMITTEE_SIZE`, shows where the
+//! memory goes before it runs out.
+//!
+//! `Metrics` records labelled samples: the time and the peak resident set size (RSS) of a
+//! phase such as preprocessing or a folding step, and the sizes of the constraint matrices
+//! of a constraint system. Each sample is also emitted as a `tracing` event with the target
+//! `sig::metrics`, and `Metrics` displays all of them as a table.
+//!
+//! The RSS is read from `/proc/self/status`, so it is only available on Linux. The peak of a
+//! phase is sampled by another thread every `SAMPLE_INTERVAL`, so a shorter spike can be
+//! missed.
+use core::fmt;
+use std::{
+    fs,
+    mem::size_of,
+    sync::atomic::{AtomicBool, Ordering},
+    thread,
+    time::{Duration, Instant},
+};
+
+use ark_ff::PrimeField;
+use ark_relations::r1cs::{ConstraintMatrices, ConstraintSystemRef};
+
+/// How often the RSS is sampled during a phase.
+pub const SAMPLE_INTERVAL: Duration = Duration::from_millis(10);
+
+/// The resident set size of the process, in KiB.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Rss {
+    pub current_kib: u64,
+    /// The peak since the process started, or during the phase of a `Sample`.
+    pub peak_kib: u64,
+}
+
+impl Rss {
+    /// The resident set size as reported by Linux, or `None` elsewhere.
+    #[must_use]
+    pub fn read() -> Option<Self> {
+        let status = fs::read_to_string("/proc/self/status").ok()?;
+        let field = |name: &str| -> Option<u64> {
+            status
+                .lines()
+                .find_map(|line| line.strip_prefix(name))?
+                .trim()
+                .trim_end_matches("kB")
+                .trim()
+                .parse()
+                .ok()
+        };
+        Some(Self {
+            current_kib: field("VmRSS:")?,
+            peak_kib: field("VmHWM:")?,
+        })
+    }
+
+    /// Run `f` while another thread samples the RSS, and return its result with the RSS
+    /// after it and the highest RSS sampled while it ran.
+    fn during<T>(f: impl FnOnce() -> T) -> (T, Option<Self>) {
+        let done = AtomicBool::new(false);
+        let (result, peak_kib) = thread::scope(|scope| {
+            let sampler = scope.spawn(|| {
+                let mut peak_kib = None;
+                while !done.load(Ordering::Relaxed) {
+                    if let Some(rss) = Self::read() {
+                        peak_kib = peak_kib.max(Some(rss.current_kib));
+                    }
+                    thread::sleep(SAMPLE_INTERVAL);
+                }
+                peak_kib
+            });
+            let result = f();
+            done.store(true, Ordering::Relaxed);
+            (
+                result,
+                sampler.join().expect("the sampler should not panic"),
+            )
+        });
+
+        let rss = Self::read().map(|rss| Self {
+            current_kib: rss.current_kib,
+            peak_kib: peak_kib.map_or(rss.current_kib, |peak| peak.max(rss.current_kib)),
+        });
+        (result, rss)
+    }
+}
+
+/// The sizes of the `A`, `B` and `C` matrices of a constraint system.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MatrixSize {
+    pub num_constraints: usize,
+    /// Including the constant `1`.
+    pub num_instance_variables: usize,
+    pub num_witness_variables: usize,
+    /// The number of non-zero entries of the three matrices.
+    pub num_non_zero: usize,
+    /// The memory taken by the entries, each a coefficient and the index of its variable.
+    pub bytes: usize,
+}
+
+impl MatrixSize {
+    #[must_use]
+    pub fn of<F: PrimeField>(matrices: &ConstraintMatrices<F>) -> Self {
+        let num_non_zero =
+            matrices.a_num_non_zero + matrices.b_num_non_zero + matrices.c_num_non_zero;
+        Self {
+            num_constraints: matrices.num_constraints,
+            num_instance_variables: matrices.num_instance_variables,
+            num_witness_variables: matrices.num_witness_variables,
+            num_non_zero,
+            bytes: num_non_zero * size_of::<(F, usize)>(),
+        }
+    }
+}
+
+/// A labelled measurement. A phase has a time and the peak RSS while it ran; a constraint
+/// system has the sizes of its matrices and the RSS once they are built.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Sample {
+    pub label: String,
+    pub elapsed: Option<Duration>,
+    pub rss: Option<Rss>,
+    pub matrices: Option<MatrixSize>,
+}
+
+/// The least-squares line through measurements `(x, y)`, to extrapolate a cost that grows
+/// linearly, e.g. the memory of a step circuit with the size of its committee, beyond the
+/// sizes that can be measured.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LinearFit {
+    pub intercept: f64,
+    pub slope: f64,
+}
+
+impl LinearFit {
+    /// The line through `points`, or `None` without two distinct `x`.
+    #[must_use]
+    pub fn through(points: &[(f64, f64)]) -> Option<Self> {
+        let n = points.len() as f64;
+        let mean_x = points.iter().map(|(x, _)| x).sum::<f64>() / n;
+        let mean_y = points.iter().map(|(_, y)| y).sum::<f64>() / n;
+        let (covariance, variance) =
+            points
+                .iter()
+                .fold((0.0, 0.0), |(covariance, variance), (x, y)| {
+                    (
+                        covariance + (x - mean_x) * (y - mean_y),
+                        variance + (x - mean_x) * (x - mean_x),
+                    )
+                });
+        if variance == 0.0 {
+            return None;
+        }
+
+        let slope = covariance / variance;
+        Some(Self {
+            intercept: mean_y - slope * mean_x,
+            slope,
+        })
+    }
+
+    #[must_use]
+    pub fn at(&self, x: f64) -> f64 {
+        self.intercept + self.slope * x
+    }
+
+    /// The largest `x` whose `y` is at most `budget`, or `None` if the line does not grow.
+    #[must_use]
+    pub fn max_within(&self, budget: f64) -> Option<f64> {
+        (self.slope > 0.0).then(|| ((budget - self.intercept) / self.slope).max(0.0))
+    }
+}
+
+/// The samples of a benchmark, in the order they were taken.
+#[derive(Clone, Debug, Default)]
+pub struct Metrics {
+    samples: Vec<Sample>,
+}
+
+impl Metrics {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Run `f` as the phase `label`, in a span of the same name, and record its time and
+    /// the peak RSS while it ran.
+    pub fn phase<T>(&mut self, label: impl Into<String>, f: impl FnOnce() -> T) -> T {
+        let label = label.into();
+        let span = tracing::info_span!(target: "sig::metrics", "phase", label = label.as_str());
+        let _enter = span.enter();
+
+        let start = Instant::now();
+        let (result, rss) = Rss::during(f);
+        self.record(Sample {
+            label,
+            elapsed: Some(start.elapsed()),
+            rss,
+            matrices: None,
+        });
+        result
+    }
+
+    /// Record the sizes of the matrices of `cs`, which is finalized to build them.
+    ///
+    /// Nothing is recorded for a constraint system that does not construct its matrices,
+    /// e.g. `snark::witness_only_cs`.
+    pub fn constraint_system<F: PrimeField>(
+        &mut self,
+        label: impl Into<String>,
+        cs: &ConstraintSystemRef<F>,
+    ) {
+        cs.finalize();
+        if let Some(matrices) = cs.to_matrices() {
+            self.record(Sample {
+                label: label.into(),
+                elapsed: None,
+                rss: Rss::read(),
+                matrices: Some(MatrixSize::of(&matrices)),
+            });
+        }
+    }
+
+    #[must_use]
+    pub fn samples(&self) -> &[Sample] {
+        &self.samples
+    }
+
+    fn record(&mut self, sample: Sample) {
+        tracing::info!(
+            target: "sig::metrics",
+            label = sample.label.as_str(),
+            elapsed_s = sample.elapsed.map(|elapsed| elapsed.as_secs_f64()),
+            rss_kib = sample.rss.map(|rss| rss.current_kib),
+            peak_rss_kib = sample.rss.map(|rss| rss.peak_kib),
+            num_constraints = sample.matrices.map(|m| m.num_constraints),
+            num_non_zero = sample.matrices.map(|m| m.num_non_zero),
+        );
+        self.samples.push(sample);
+    }
+}
+
+/// A table with one row per sample, with sizes in MiB.
+impl fmt::Display for Metrics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn cell<T: fmt::Display>(value: Option<T>) -> String {
+            value.map_or_else(|| "-".to_string(), |value| value.to_string())
+        }
+        fn mib(kib: u64) -> String {
+            format!("{:.1}", kib as f64 / 1024.0)
+        }
+
+        let width = self
+            .samples
+            .iter()
+            .map(|sample| sample.label.len())
+            .max()
+            .unwrap_or(0)
+            .max("label".len());
+        writeln!(
+            f,
+            "{:width$} | {:>10} | {:>10} | {:>10} | {:>12} | {:>12} | {:>12} | {:>12}",
+            "label",
+            "time (s)",
+            "RSS",
+            "peak RSS",
+            "constraints",
+            "witnesses",
+            "non-zero",
+            "matrices"
+        )?;
+        for sample in &self.samples {
+            let matrices = sample.matrices;
+            writeln!(
+                f,
+                "{:width$} | {:>10} | {:>10} | {:>10} | {:>12} | {:>12} | {:>12} | {:>12}",
+                sample.label,
+                cell(
+                    sample
+                        .elapsed
+                        .map(|elapsed| format!("{:.3}", elapsed.as_secs_f64()))
+                ),
+                cell(sample.rss.map(|rss| mib(rss.current_kib))),
+                cell(sample.rss.map(|rss| mib(rss.peak_kib))),
+                cell(matrices.map(|m| m.num_constraints)),
+                cell(matrices.map(|m| m.num_witness_variables)),
+                cell(matrices.map(|m| m.num_non_zero)),
+                cell(matrices.map(|m| mib((m.bytes / 1024) as u64))),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ark_bls12_381::Fr;
+    use ark_r1cs_std::{alloc::AllocVar, eq::EqGadget, fields::fp::FpVar};
+    use ark_relations::r1cs::ConstraintSystem;
+
+    use crate::snark::witness_only_cs;
+
+    use super::{LinearFit, Metrics};
+
+    #[test]
+    fn test_metrics() {
+        let mut metrics = Metrics::new();
+        let cs = metrics.phase("synthesis", || {
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let x = FpVar::new_witness(cs.clone(), || Ok(Fr::from(3))).unwrap();
+            let y = FpVar::new_input(cs.clone(), || Ok(Fr::from(9))).unwrap();
+            x.square().unwrap().enforce_equal(&y).unwrap();
+            cs
+        });
+        metrics.constraint_system("square", &cs);
+        metrics.constraint_system("witness only", &witness_only_cs::<Fr>());
+
+        let samples = metrics.samples();
+        assert_eq!(samples.len(), 2);
+        assert!(samples[0].elapsed.is_some() && samples[0].matrices.is_none());
+        assert_eq!(samples[0].rss.is_some(), cfg!(target_os = "linux"));
+        let matrices = samples[1].matrices.unwrap();
+        assert_eq!(matrices.num_constraints, cs.num_constraints());
+        assert_eq!(matrices.num_instance_variables, 2);
+        assert!(matrices.num_non_zero > 0);
+        if cfg!(target_os = "linux") {
+            let rss = samples[1].rss.unwrap();
+            assert!(rss.current_kib > 0 && rss.peak_kib >= rss.current_kib);
+        }
+
+        let table = metrics.to_string();
+        assert_eq!(table.lines().count(), 3);
+        assert!(table.lines().nth(2).unwrap().starts_with("square"));
+    }
+
+    #[test]
+    fn test_linear_fit() {
+        let fit = LinearFit::through(&[(1.0, 5.0), (2.0, 7.0), (4.0, 11.0)]).unwrap();
+        assert!((fit.slope - 2.0).abs() < 1e-9 && (fit.intercept - 3.0).abs() < 1e-9);
+        assert!((fit.at(10.0) - 23.0).abs() < 1e-9);
+        assert!((fit.max_within(23.0).unwrap() - 10.0).abs() < 1e-9);
+        assert_eq!(fit.max_within(1.0), Some(0.0));
+
+        assert_eq!(LinearFit::through(&[(1.0, 5.0), (1.0, 7.0)]), None);
+        assert_eq!(LinearFit::through(&[]), None);
+        let flat = LinearFit::through(&[(1.0, 5.0), (2.0, 5.0)]).unwrap();
+        assert_eq!(flat.max_within(10.0), None);
+    }
+}
@@ -1,5 +1,76 @@
-use ark_ec::bls12::Bls12Config;
+use ark_ec::{bls12::Bls12Config, CurveGroup, PrimeGroup};
+use ark_ff::PrimeField;
+use ark_r1cs_std::fields::{emulated_fp::EmulatedFpVar, fp::FpVar, FieldVar};
 
 pub type BlsSigField<SigCurveConfig> = <SigCurveConfig as Bls12Config>::Fp;
 
 pub type BlsSigConfig = ark_bls12_381::Config;
+
+/// The cycle of curves a chain is folded over: the circuits of `crate::folding` are
+/// synthesized over the scalar field of `C1`, and CycleFold proves the operations on `C1`
+/// over the scalar field of `C2`, which is the base field of `C1`.
+///
+/// The cycle trades constraints against deciders. The coordinates of keys and signatures
+/// are emulated in `CycleField`, which takes fewer limbs in a larger field (e.g. MNT4-753),
+/// but only BN254/Grumpkin can be decided on the EVM.
+///
+/// A pair `(C1, C2)` of curves forming a cycle is a `CurveCycleConfig`.
+pub trait CurveCycleConfig {
+    type C1: CurveGroup<
+        BaseField = <Self::C2 as PrimeGroup>::ScalarField,
+        ScalarField = <Self::C2 as CurveGroup>::BaseField,
+    >;
+    type C2: CurveGroup<BaseField: PrimeField>;
+}
+
+impl<C1, C2> CurveCycleConfig for (C1, C2)
+where
+    C1: CurveGroup<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    C2: CurveGroup,
+    C2::BaseField: PrimeField,
+{
+    type C1 = C1;
+    type C2 = C2;
+}
+
+/// The field the circuits folded over the cycle `C`, e.g. `BCCircuitNoMerkle<C>`, are
+/// synthesized over, which parameterizes `BlockVar` and the other gadgets.
+pub type CycleField<C> = <<C as CurveCycleConfig>::C1 as PrimeGroup>::ScalarField;
+
+/// The base field of the signature curve emulated in the constraint field `CF`.
+pub type BlsSigFieldVar<CF> = EmulatedFpVar<BlsSigField<BlsSigConfig>, CF>;
+
+/// How the coordinates of keys and signatures, elements of `F`, are represented in circuits
+/// over `CF`: `NativeFieldVar` when the two fields coincide, e.g. BLS12-377 over BW6-761,
+/// and `EmulatedFieldVar` otherwise.
+///
+/// `PublicKeyVar`, `SignatureVar`, `ParametersVar` and `BlockVar` take the chosen
+/// `SelectedFieldVar` as their field var. They and their `AllocVar` impls are generic over
+/// the field var rather than the selector, which would not be constrained by their types,
+/// so they accept the var of any selector.
+pub trait FieldVarSelector<F: PrimeField, CF: PrimeField> {
+    type Var: FieldVar<F, CF>;
+}
+
+/// Selects `FpVar`, which is only possible when `F = CF`.
+#[derive(Clone, Copy, Debug)]
+pub struct NativeFieldVar;
+
+impl<F: PrimeField> FieldVarSelector<F, F> for NativeFieldVar {
+    type Var = FpVar<F>;
+}
+
+/// Selects `EmulatedFpVar`, which works for any pair of fields.
+#[derive(Clone, Copy, Debug)]
+pub struct EmulatedFieldVar;
+
+impl<F: PrimeField, CF: PrimeField> FieldVarSelector<F, CF> for EmulatedFieldVar {
+    type Var = EmulatedFpVar<F, CF>;
+}
+
+/// The field var chosen by the selector `S` for `F` in circuits over `CF`.
+pub type SelectedFieldVar<S, F, CF> = <S as FieldVarSelector<F, CF>>::Var;
+
+/// The cycle of `folding::decider`.
+#[cfg(feature = "evm")]
+pub type Bn254Grumpkin = (ark_bn254::G1Projective, ark_grumpkin::Projective);
@@ -0,0 +1,10 @@
+//! Scheme-agnostic signing traits.
+//!
+//! The key types of this crate implement `Signer` and `Verifier` from RustCrypto's
+//! `signature` crate, so code that is generic over them (e.g. benchmarks) can run BLS side by
+//! side with other schemes such as EdDSA or ECDSA. The traits are re-exported here so that
+//! callers do not need a direct dependency on a matching version of that crate.
+//!
+//! Verification failures are reported with the crate's opaque `Error`, which deliberately
+//! carries no detail about why verification failed.
+pub use ::signature::{Error, Signer, Verifier};
@@ -0,0 +1,209 @@
+//! A common interface to the SNARKs that can prove the standalone circuits, so that the
+//! circuit-specific setup of Groth16 can be swapped for a universal one.
+//!
+//! A backend runs a setup for a bound on the size of the circuits, whose SRS is then indexed
+//! for each circuit into its proving and verifying keys. For a universal SNARK, one SRS would
+//! cover every circuit within the bound, such as `BLSCircuit` for messages of any length up
+//! to the bound; for Groth16, the SRS is empty and indexing is the trusted setup of the
+//! circuit.
+//!
+//! Groth16 is the only backend, so every circuit still needs its own trusted setup. Marlin
+//! is not wired up: `ark-marlin` has no release for arkworks 0.5, which the circuits are
+//! written against, and neither has any other universal-SRS SNARK. Once one has, it is
+//! added by implementing `SnarkBackend` with `UNIVERSAL = true`, and `prove_and_verify` in
+//! the tests below checks it end to end.
+use ark_ec::pairing::Pairing;
+use ark_ff::PrimeField;
+use ark_groth16::{Groth16, PreparedVerifyingKey, Proof, ProvingKey};
+use ark_relations::r1cs::{ConstraintSynthesizer, SynthesisError};
+use rand::RngCore;
+
+use super::setup_matrices;
+
+/// The size of a circuit, which bounds the circuits a universal SRS covers.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CircuitBounds {
+    pub num_constraints: usize,
+    /// Including the constant `1`.
+    pub num_instance_variables: usize,
+    pub num_witness_variables: usize,
+    /// The largest number of non-zero entries of the `A`, `B` and `C` matrices.
+    pub num_non_zero: usize,
+}
+
+impl CircuitBounds {
+    /// The size of `circuit`, which does not need to be assigned.
+    pub fn of<F: PrimeField>(
+        circuit: impl ConstraintSynthesizer<F>,
+    ) -> Result<Self, SynthesisError> {
+        let matrices = setup_matrices(circuit)?;
+        Ok(Self {
+            num_constraints: matrices.num_constraints,
+            num_instance_variables: matrices.num_instance_variables,
+            num_witness_variables: matrices.num_witness_variables,
+            num_non_zero: matrices
+                .a_num_non_zero
+                .max(matrices.b_num_non_zero)
+                .max(matrices.c_num_non_zero),
+        })
+    }
+
+    /// Whether a circuit of size `other` is within these bounds.
+    #[must_use]
+    pub const fn covers(&self, other: &Self) -> bool {
+        self.num_constraints >= other.num_constraints
+            && self.num_instance_variables >= other.num_instance_variables
+            && self.num_witness_variables >= other.num_witness_variables
+            && self.num_non_zero >= other.num_non_zero
+    }
+
+    /// The smallest bounds covering both `self` and `other`.
+    #[must_use]
+    pub fn max(self, other: Self) -> Self {
+        Self {
+            num_constraints: self.num_constraints.max(other.num_constraints),
+            num_instance_variables: self
+                .num_instance_variables
+                .max(other.num_instance_variables),
+            num_witness_variables: self.num_witness_variables.max(other.num_witness_variables),
+            num_non_zero: self.num_non_zero.max(other.num_non_zero),
+        }
+    }
+}
+
+pub trait SnarkBackend {
+    /// The field of the circuits.
+    type Field: PrimeField;
+    type Srs;
+    type ProvingKey;
+    type VerifyingKey;
+    type Proof;
+    type Error: std::error::Error + From<SynthesisError>;
+
+    /// Whether an SRS covers every circuit within its bounds, rather than a single circuit.
+    const UNIVERSAL: bool;
+
+    /// The SRS for circuits within `bounds`, which is the only trusted setup of a universal
+    /// backend.
+    fn universal_setup(
+        bounds: CircuitBounds,
+        rng: &mut impl RngCore,
+    ) -> Result<Self::Srs, Self::Error>;
+
+    /// The proving and verifying keys of `circuit`, which does not need to be assigned.
+    fn index<C: ConstraintSynthesizer<Self::Field>>(
+        srs: &Self::Srs,
+        circuit: C,
+        rng: &mut impl RngCore,
+    ) -> Result<(Self::ProvingKey, Self::VerifyingKey), Self::Error>;
+
+    fn prove<C: ConstraintSynthesizer<Self::Field>>(
+        pk: &Self::ProvingKey,
+        circuit: C,
+        rng: &mut impl RngCore,
+    ) -> Result<Self::Proof, Self::Error>;
+
+    fn verify(
+        vk: &Self::VerifyingKey,
+        public_inputs: &[Self::Field],
+        proof: &Self::Proof,
+    ) -> Result<bool, Self::Error>;
+}
+
+/// Groth16 has no universal SRS: `index` runs the trusted setup of each circuit.
+impl<E: Pairing> SnarkBackend for Groth16<E> {
+    type Field = E::ScalarField;
+    type Srs = ();
+    type ProvingKey = ProvingKey<E>;
+    type VerifyingKey = PreparedVerifyingKey<E>;
+    type Proof = Proof<E>;
+    type Error = SynthesisError;
+
+    const UNIVERSAL: bool = false;
+
+    fn universal_setup(_: CircuitBounds, _: &mut impl RngCore) -> Result<(), SynthesisError> {
+        Ok(())
+    }
+
+    fn index<C: ConstraintSynthesizer<E::ScalarField>>(
+        _: &(),
+        circuit: C,
+        rng: &mut impl RngCore,
+    ) -> Result<(ProvingKey<E>, PreparedVerifyingKey<E>), SynthesisError> {
+        let pk = Self::generate_random_parameters_with_reduction(circuit, rng)?;
+        let pvk = Self::process_vk(&pk.vk)?;
+        Ok((pk, pvk))
+    }
+
+    fn prove<C: ConstraintSynthesizer<E::ScalarField>>(
+        pk: &ProvingKey<E>,
+        circuit: C,
+        rng: &mut impl RngCore,
+    ) -> Result<Proof<E>, SynthesisError> {
+        Self::create_random_proof_with_reduction(circuit, pk, rng)
+    }
+
+    fn verify(
+        pvk: &PreparedVerifyingKey<E>,
+        public_inputs: &[E::ScalarField],
+        proof: &Proof<E>,
+    ) -> Result<bool, SynthesisError> {
+        Self::verify_proof(pvk, proof, public_inputs)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ark_ec::bls12::Bls12Config;
+    use ark_ff::Field;
+    use ark_groth16::Groth16;
+    use ark_r1cs_std::fields::fp::FpVar;
+    use rand::thread_rng;
+
+    use crate::bls::{get_bls_instance, BLSCircuit};
+
+    use super::{CircuitBounds, SnarkBackend};
+
+    type BlsSigConfig = ark_bls12_377::Config;
+    type CF = <BlsSigConfig as Bls12Config>::Fp;
+    type Circuit<'a> = BLSCircuit<'a, BlsSigConfig, FpVar<CF>, CF>;
+
+    #[test]
+    fn check_circuit_bounds() {
+        let short = [None; 8];
+        let long = [None; 64];
+        let short = CircuitBounds::of(Circuit::new(None, None, &short, None)).unwrap();
+        let long = CircuitBounds::of(Circuit::new(None, None, &long, None)).unwrap();
+
+        // longer messages take more constraints and public inputs
+        assert!(long.covers(&short));
+        assert!(!short.covers(&long));
+        assert_eq!(short.max(long), long);
+    }
+
+    /// Prove a signature end to end with any backend, whose SRS is set up for the circuit.
+    fn prove_and_verify<S: SnarkBackend<Field = CF>>() {
+        let mut rng = thread_rng();
+        let (msg, params, _, pk, sig) = get_bls_instance::<BlsSigConfig>();
+        let msg: Vec<_> = msg.as_bytes().iter().copied().map(Some).collect();
+        let unassigned = vec![None; msg.len()];
+        let setup = Circuit::new(None, None, &unassigned, None);
+        let circuit = Circuit::new(Some(params), Some(pk), &msg, Some(sig));
+
+        let srs = S::universal_setup(CircuitBounds::of(setup.clone()).unwrap(), &mut rng).unwrap();
+        let (proving_key, verifying_key) = S::index(&srs, setup, &mut rng).unwrap();
+        let public_inputs = circuit.get_public_inputs().unwrap();
+        let proof = S::prove(&proving_key, circuit, &mut rng).unwrap();
+
+        assert!(S::verify(&verifying_key, &public_inputs, &proof).unwrap());
+        let mut public_inputs = public_inputs;
+        public_inputs[0] += CF::ONE;
+        assert!(!S::verify(&verifying_key, &public_inputs, &proof).unwrap());
+    }
+
+    #[test]
+    #[ignore = "the setup and proof over BW6-761 take a long time"]
+    fn check_groth16_backend() {
+        prove_and_verify::<Groth16<ark_bw6_761::BW6_761>>();
+    }
+}
@@ -0,0 +1,111 @@
+//! Verification of many Groth16 proofs under the same verifying key at once, e.g. by a
+//! relayer checking the proof of each block.
+//!
+//! Each proof must satisfy `e(A, B) = e(alpha, beta) * e(vk_x, gamma) * e(C, delta)`. With a
+//! random `r_i` for each proof, the product of these equations raised to `r_i` is
+//! `prod e(r_i A_i, B_i) * e(sum r_i vk_x_i, -gamma) * e(sum r_i C_i, -delta)
+//! = e(alpha, beta)^(sum r_i)`, which takes `N + 2` Miller loops and one final exponentiation
+//! instead of `N` pairing checks. An invalid proof passes with probability at most `2^-128`.
+use ark_ec::{pairing::Pairing, CurveGroup, VariableBaseMSM};
+use ark_ff::{Field, PrimeField};
+use ark_groth16::{Groth16, PreparedVerifyingKey, Proof};
+use ark_relations::r1cs::SynthesisError;
+use rand::Rng;
+
+/// Whether every proof of `proofs` is valid for its public inputs under `pvk`.
+pub fn batch_verify<E: Pairing, I: AsRef<[E::ScalarField]>>(
+    pvk: &PreparedVerifyingKey<E>,
+    proofs: &[(Proof<E>, I)],
+    rng: &mut impl Rng,
+) -> Result<bool, SynthesisError> {
+    if proofs.is_empty() {
+        return Ok(true);
+    }
+
+    let r: Vec<E::ScalarField> = proofs
+        .iter()
+        .map(|_| E::ScalarField::from(rng.gen::<u128>()))
+        .collect();
+    let vk_x = proofs
+        .iter()
+        .map(|(_, inputs)| Groth16::<E>::prepare_inputs(pvk, inputs.as_ref()))
+        .collect::<Result<Vec<_>, _>>()?;
+    let vk_x = E::G1::msm_unchecked(&E::G1::normalize_batch(&vk_x), &r);
+    let c = proofs.iter().map(|(proof, _)| proof.c).collect::<Vec<_>>();
+    let c = E::G1::msm_unchecked(&c, &r);
+
+    let g1: Vec<E::G1Prepared> = proofs
+        .iter()
+        .zip(&r)
+        .map(|((proof, _), r)| (proof.a * r).into())
+        .chain([vk_x.into(), c.into()])
+        .collect();
+    let g2: Vec<E::G2Prepared> = proofs
+        .iter()
+        .map(|(proof, _)| proof.b.into())
+        .chain([pvk.gamma_g2_neg_pc.clone(), pvk.delta_g2_neg_pc.clone()])
+        .collect();
+    let lhs = E::final_exponentiation(E::multi_miller_loop(g1, g2))
+        .ok_or(SynthesisError::UnexpectedIdentity)?;
+
+    let r_sum: E::ScalarField = r.iter().sum();
+    Ok(lhs.0 == pvk.alpha_g1_beta_g2.pow(r_sum.into_bigint()))
+}
+
+#[cfg(test)]
+mod test {
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_ff::Field;
+    use ark_groth16::Groth16;
+    use ark_r1cs_std::{alloc::AllocVar, eq::EqGadget, fields::fp::FpVar};
+    use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
+    use rand::{thread_rng, Rng};
+
+    use super::batch_verify;
+
+    /// Knowledge of a square root `x` of the public `y`.
+    #[derive(Clone, Copy)]
+    struct Square(Option<Fr>);
+
+    impl ConstraintSynthesizer<Fr> for Square {
+        fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+            let x = FpVar::new_witness(cs.clone(), || {
+                self.0.ok_or(SynthesisError::AssignmentMissing)
+            })?;
+            let y = FpVar::new_input(cs, || {
+                self.0
+                    .map(|x| x.square())
+                    .ok_or(SynthesisError::AssignmentMissing)
+            })?;
+            x.square()?.enforce_equal(&y)
+        }
+    }
+
+    #[test]
+    fn check_batch_verify() {
+        let mut rng = thread_rng();
+        let pk =
+            Groth16::<Bls12_381>::generate_random_parameters_with_reduction(Square(None), &mut rng)
+                .unwrap();
+        let pvk = Groth16::<Bls12_381>::process_vk(&pk.vk).unwrap();
+
+        let mut proofs: Vec<_> = (0..4)
+            .map(|_| {
+                let x: Fr = rng.gen();
+                let proof = Groth16::<Bls12_381>::create_random_proof_with_reduction(
+                    Square(Some(x)),
+                    &pk,
+                    &mut rng,
+                )
+                .unwrap();
+                (proof, [x.square()])
+            })
+            .collect();
+        assert!(batch_verify(&pvk, &proofs, &mut rng).unwrap());
+        assert!(batch_verify::<Bls12_381, [Fr; 1]>(&pvk, &[], &mut rng).unwrap());
+
+        // a single proof for another statement invalidates the batch
+        proofs[2].1[0] += Fr::ONE;
+        assert!(!batch_verify(&pvk, &proofs, &mut rng).unwrap());
+    }
+}
@@ -0,0 +1,692 @@
+//! The Groth16 setup of a circuit such as `BLSCircuit` as a multi-party ceremony, so that its
+//! proving key is sound as long as one participant discards their secrets.
+//!
+//! Phase 1, `PowersOfTau`, does not depend on the circuit: each participant multiplies
+//! `tau`, `alpha` and `beta` by secrets. Phase 2 starts from a verified phase 1 transcript:
+//! `Phase2::from_powers_of_tau` derives the key of a circuit with `gamma = delta = 1` from
+//! the powers of `tau` in the Lagrange basis, and each participant then multiplies `delta`
+//! by a secret `d`, which multiplies `delta_g1` and `delta_g2` by `d` and divides the `h` and
+//! `l` queries by `d`.
+//!
+//! Every secret `x` comes with a proof of knowledge: a random `s` and `x * s` in G1, and
+//! `x * r` in G2, where `r` is hashed from the previous value, `s` and `x * s`, so a
+//! participant cannot reuse the contribution of another one.
+use core::fmt;
+
+use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup, VariableBaseMSM};
+use ark_ff::{Field, One, UniformRand, Zero};
+use ark_groth16::{ProvingKey, VerifyingKey};
+use ark_poly::{domain::DomainCoeff, EvaluationDomain, Radix2EvaluationDomain};
+use ark_relations::r1cs::{ConstraintSynthesizer, SynthesisError};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use blake2::Digest;
+use derivative::Derivative;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use super::setup_matrices;
+use crate::bc::params::{HashFunc, HASH_OUTPUT_SIZE};
+
+/// The secret each proof of knowledge is for, hashed into its `r`.
+const TAU: u8 = 0;
+const ALPHA: u8 = 1;
+const BETA: u8 = 2;
+const DELTA: u8 = 3;
+
+#[derive(Debug)]
+pub enum CeremonyError {
+    /// The proof of knowledge of the contribution at this index does not verify, or it does
+    /// not follow the previous one.
+    InvalidContribution(usize),
+    /// The powers of tau are not powers of the same `tau`, do not follow from the
+    /// contributions, or have no contributions.
+    InvalidPowers,
+    /// The circuit needs more powers of tau than the transcript has.
+    CircuitTooLarge,
+    /// The key does not follow from the initial key and the contributions, or has no
+    /// contributions.
+    InconsistentKey,
+    Synthesis(SynthesisError),
+}
+
+impl fmt::Display for CeremonyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidContribution(i) => write!(f, "contribution {i} is invalid"),
+            Self::InvalidPowers => write!(f, "the powers of tau are invalid"),
+            Self::CircuitTooLarge => {
+                write!(f, "the circuit needs more powers of tau than there are")
+            }
+            Self::InconsistentKey => {
+                write!(f, "the key does not follow from the contributions")
+            }
+            Self::Synthesis(e) => write!(f, "synthesis error: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for CeremonyError {}
+
+impl From<SynthesisError> for CeremonyError {
+    fn from(e: SynthesisError) -> Self {
+        Self::Synthesis(e)
+    }
+}
+
+/// A proof of knowledge of `x` with `next = x * previous`.
+#[derive(Derivative, CanonicalSerialize, CanonicalDeserialize)]
+#[derivative(Clone(bound = ""), Debug(bound = ""), PartialEq(bound = ""))]
+pub struct KnowledgeProof<E: Pairing> {
+    pub s: E::G1Affine,
+    pub s_x: E::G1Affine,
+    pub r_x: E::G2Affine,
+}
+
+impl<E: Pairing> KnowledgeProof<E> {
+    /// The point `r` of the proof, whose discrete logarithm nobody knows.
+    fn r(secret: u8, previous: &E::G1Affine, s: &E::G1Affine, s_x: &E::G1Affine) -> E::G2Affine {
+        let mut bytes = Vec::new();
+        (secret, previous, s, s_x)
+            .serialize_compressed(&mut bytes)
+            .expect("serializing points should succeed");
+        // sampling a point picks a random `x`, so `r` is not a known multiple of a generator
+        E::G2::rand(&mut StdRng::from_seed(HashFunc::digest(bytes).into())).into_affine()
+    }
+
+    fn prove(secret: u8, x: E::ScalarField, previous: &E::G1Affine, rng: &mut impl Rng) -> Self {
+        let s = E::G1::rand(rng).into_affine();
+        let s_x = (s * x).into_affine();
+        let r = Self::r(secret, previous, &s, &s_x);
+        Self {
+            s,
+            s_x,
+            r_x: (r * x).into_affine(),
+        }
+    }
+
+    /// Whether the proof is a proof of knowledge of a non-zero `x` with
+    /// `next = x * previous`.
+    fn verify(&self, secret: u8, previous: &E::G1Affine, next: &E::G1Affine) -> bool {
+        let r = Self::r(secret, previous, &self.s, &self.s_x);
+        !self.s.is_zero()
+            && !next.is_zero()
+            && same_ratio::<E>((self.s, self.s_x), (r, self.r_x))
+            && same_ratio::<E>((*previous, *next), (r, self.r_x))
+    }
+}
+
+/// Hash a contribution into the digest a participant publishes to check that their
+/// contribution is in the transcript.
+fn digest(contribution: &impl CanonicalSerialize) -> [u8; HASH_OUTPUT_SIZE] {
+    let mut bytes = Vec::new();
+    contribution
+        .serialize_compressed(&mut bytes)
+        .expect("serializing a contribution should succeed");
+    HashFunc::digest(bytes).into()
+}
+
+#[derive(Derivative, CanonicalSerialize, CanonicalDeserialize)]
+#[derivative(Clone(bound = ""), Debug(bound = ""), PartialEq(bound = ""))]
+pub struct Phase1Contribution<E: Pairing> {
+    /// `tau * G1` after the contribution.
+    pub tau_g1: E::G1Affine,
+    /// `alpha * G1` after the contribution.
+    pub alpha_g1: E::G1Affine,
+    /// `beta * G1` after the contribution.
+    pub beta_g1: E::G1Affine,
+    pub tau_proof: KnowledgeProof<E>,
+    pub alpha_proof: KnowledgeProof<E>,
+    pub beta_proof: KnowledgeProof<E>,
+}
+
+impl<E: Pairing> Phase1Contribution<E> {
+    /// Whether the proofs of knowledge follow from the previous `tau`, `alpha` and `beta`
+    /// in G1.
+    fn verify(&self, previous: &[E::G1Affine; 3]) -> bool {
+        self.tau_proof.verify(TAU, &previous[0], &self.tau_g1)
+            && self.alpha_proof.verify(ALPHA, &previous[1], &self.alpha_g1)
+            && self.beta_proof.verify(BETA, &previous[2], &self.beta_g1)
+    }
+
+    #[must_use]
+    pub fn digest(&self) -> [u8; HASH_OUTPUT_SIZE] {
+        digest(self)
+    }
+}
+
+/// The powers of tau of phase 1, passed from one participant to the next, which support
+/// circuits whose QAP domain has at most `degree` points.
+#[derive(Derivative, CanonicalSerialize, CanonicalDeserialize)]
+#[derivative(Clone(bound = ""), Debug(bound = ""), PartialEq(bound = ""))]
+pub struct PowersOfTau<E: Pairing> {
+    /// `tau^i * G1` for `i < 2 * degree - 1`.
+    pub tau_g1: Vec<E::G1Affine>,
+    /// `tau^i * G2` for `i < degree`.
+    pub tau_g2: Vec<E::G2Affine>,
+    /// `alpha * tau^i * G1` for `i < degree`.
+    pub alpha_tau_g1: Vec<E::G1Affine>,
+    /// `beta * tau^i * G1` for `i < degree`.
+    pub beta_tau_g1: Vec<E::G1Affine>,
+    pub beta_g2: E::G2Affine,
+    pub contributions: Vec<Phase1Contribution<E>>,
+}
+
+impl<E: Pairing> PowersOfTau<E> {
+    /// Start phase 1 with `tau = alpha = beta = 1`, which the first contribution randomizes.
+    #[must_use]
+    pub fn new(degree: usize) -> Self {
+        assert!(degree >= 2, "powers of tau need a degree of at least 2");
+
+        let (g1, g2) = (E::G1Affine::generator(), E::G2Affine::generator());
+        Self {
+            tau_g1: vec![g1; 2 * degree - 1],
+            tau_g2: vec![g2; degree],
+            alpha_tau_g1: vec![g1; degree],
+            beta_tau_g1: vec![g1; degree],
+            beta_g2: g2,
+            contributions: vec![],
+        }
+    }
+
+    /// The size of the largest QAP domain the powers support.
+    #[must_use]
+    pub fn degree(&self) -> usize {
+        self.tau_g2.len()
+    }
+
+    /// Contribute secrets drawn from `rng`, which should be local entropy and are dropped
+    /// before returning. Returns the digest of the contribution.
+    pub fn contribute(&mut self, rng: &mut impl Rng) -> [u8; HASH_OUTPUT_SIZE] {
+        let tau = E::ScalarField::rand(rng);
+        let alpha = E::ScalarField::rand(rng);
+        let beta = E::ScalarField::rand(rng);
+        let previous = [self.tau_g1[1], self.alpha_tau_g1[0], self.beta_tau_g1[0]];
+
+        scale_powers::<E::G1>(&mut self.tau_g1, E::ScalarField::one(), tau);
+        scale_powers::<E::G2>(&mut self.tau_g2, E::ScalarField::one(), tau);
+        scale_powers::<E::G1>(&mut self.alpha_tau_g1, alpha, tau);
+        scale_powers::<E::G1>(&mut self.beta_tau_g1, beta, tau);
+        self.beta_g2 = (self.beta_g2 * beta).into_affine();
+
+        let contribution = Phase1Contribution {
+            tau_g1: self.tau_g1[1],
+            alpha_g1: self.alpha_tau_g1[0],
+            beta_g1: self.beta_tau_g1[0],
+            tau_proof: KnowledgeProof::prove(TAU, tau, &previous[0], rng),
+            alpha_proof: KnowledgeProof::prove(ALPHA, alpha, &previous[1], rng),
+            beta_proof: KnowledgeProof::prove(BETA, beta, &previous[2], rng),
+        };
+        let digest = contribution.digest();
+        self.contributions.push(contribution);
+        digest
+    }
+
+    /// Check that the transcript has at least one contribution, that the powers follow from
+    /// the contributions, and that they are powers of the same `tau`.
+    ///
+    /// The powers are checked with random linear combinations drawn from `rng`.
+    pub fn verify(&self, rng: &mut impl Rng) -> Result<(), CeremonyError> {
+        let (g1, g2) = (E::G1Affine::generator(), E::G2Affine::generator());
+        let degree = self.degree();
+        if degree < 2
+            || self.tau_g1.len() != 2 * degree - 1
+            || self.alpha_tau_g1.len() != degree
+            || self.beta_tau_g1.len() != degree
+        {
+            return Err(CeremonyError::InvalidPowers);
+        }
+
+        let mut previous = [g1; 3];
+        for (i, contribution) in self.contributions.iter().enumerate() {
+            if !contribution.verify(&previous) {
+                return Err(CeremonyError::InvalidContribution(i));
+            }
+            previous = [
+                contribution.tau_g1,
+                contribution.alpha_g1,
+                contribution.beta_g1,
+            ];
+        }
+
+        // without contributions, `tau = alpha = beta = 1`
+        let consistent = !self.contributions.is_empty()
+            && previous == [self.tau_g1[1], self.alpha_tau_g1[0], self.beta_tau_g1[0]]
+            && self.tau_g1[0] == g1
+            && self.tau_g2[0] == g2
+            && same_ratio::<E>((g1, self.tau_g1[1]), (g2, self.tau_g2[1]))
+            && same_ratio::<E>((g1, self.beta_tau_g1[0]), (g2, self.beta_g2));
+        if !consistent {
+            return Err(CeremonyError::InvalidPowers);
+        }
+
+        // each point is `tau` times the previous one
+        let tau_g2 = (self.tau_g2[0], self.tau_g2[1]);
+        for powers in [&self.tau_g1, &self.alpha_tau_g1, &self.beta_tau_g1] {
+            if !same_ratio::<E>(shifted_combinations::<E::G1>(powers, rng), tau_g2) {
+                return Err(CeremonyError::InvalidPowers);
+            }
+        }
+        let tau_g1 = (self.tau_g1[0], self.tau_g1[1]);
+        if !same_ratio::<E>(tau_g1, shifted_combinations::<E::G2>(&self.tau_g2, rng)) {
+            return Err(CeremonyError::InvalidPowers);
+        }
+
+        Ok(())
+    }
+}
+
+/// Multiply the `i`-th point of `points` by `factor * x^i`.
+fn scale_powers<G: CurveGroup>(
+    points: &mut Vec<G::Affine>,
+    factor: G::ScalarField,
+    x: G::ScalarField,
+) {
+    let mut scalar = factor;
+    let scaled: Vec<G> = points
+        .iter()
+        .map(|p| {
+            let scaled = *p * scalar;
+            scalar *= x;
+            scaled
+        })
+        .collect();
+    *points = G::normalize_batch(&scaled);
+}
+
+/// The same random linear combination of `points` without its last point, and without its
+/// first point, which have the same ratio as consecutive points if they all do.
+fn shifted_combinations<G: CurveGroup>(
+    points: &[G::Affine],
+    rng: &mut impl Rng,
+) -> (G::Affine, G::Affine) {
+    let rho: Vec<_> = points[1..]
+        .iter()
+        .map(|_| G::ScalarField::rand(rng))
+        .collect();
+    (
+        G::msm_unchecked(&points[..points.len() - 1], &rho).into_affine(),
+        G::msm_unchecked(&points[1..], &rho).into_affine(),
+    )
+}
+
+/// `L_i(tau)` times the generator for every Lagrange polynomial `L_i` of `domain`, from the
+/// first `domain.size()` powers of `tau`.
+fn lagrange_basis<G: CurveGroup + DomainCoeff<G::ScalarField>>(
+    domain: &Radix2EvaluationDomain<G::ScalarField>,
+    powers: &[G::Affine],
+) -> Vec<G> {
+    // `L_i(X) = sum_j (X / w^i)^j / n`, the inverse FFT of the powers
+    let mut points: Vec<G> = powers[..domain.size()]
+        .iter()
+        .map(|p| p.into_group())
+        .collect();
+    domain.ifft_in_place(&mut points);
+    points
+}
+
+/// The key of `circuit` with `gamma = delta = 1` from `powers`, as
+/// `Groth16::generate_random_parameters_with_reduction` computes it from `tau`, `alpha` and
+/// `beta` with `LibsnarkReduction`.
+fn initial_key<E: Pairing, C: ConstraintSynthesizer<E::ScalarField>>(
+    circuit: C,
+    powers: &PowersOfTau<E>,
+) -> Result<ProvingKey<E>, CeremonyError>
+where
+    E::G1: DomainCoeff<E::ScalarField>,
+    E::G2: DomainCoeff<E::ScalarField>,
+{
+    let matrices = setup_matrices(circuit)?;
+    let num_instance = matrices.num_instance_variables;
+    let num_variables = num_instance + matrices.num_witness_variables;
+    let domain =
+        Radix2EvaluationDomain::<E::ScalarField>::new(matrices.num_constraints + num_instance)
+            .ok_or(SynthesisError::PolynomialDegreeTooLarge)?;
+    let n = domain.size();
+    if n > powers.degree() {
+        return Err(CeremonyError::CircuitTooLarge);
+    }
+
+    let l_g1 = lagrange_basis::<E::G1>(&domain, &powers.tau_g1);
+    let l_g2 = lagrange_basis::<E::G2>(&domain, &powers.tau_g2);
+    let alpha_l_g1 = lagrange_basis::<E::G1>(&domain, &powers.alpha_tau_g1);
+    let beta_l_g1 = lagrange_basis::<E::G1>(&domain, &powers.beta_tau_g1);
+
+    // `u_i(tau)`, `v_i(tau)` and `beta * u_i(tau) + alpha * v_i(tau) + w_i(tau)` of every
+    // variable, where the instance variables are also constrained by the rows after the
+    // constraints
+    let mut a = vec![E::G1::zero(); num_variables];
+    let mut b_g1 = vec![E::G1::zero(); num_variables];
+    let mut b_g2 = vec![E::G2::zero(); num_variables];
+    let mut abc = vec![E::G1::zero(); num_variables];
+    for i in 0..num_instance {
+        let row = matrices.num_constraints + i;
+        a[i] += l_g1[row];
+        abc[i] += beta_l_g1[row];
+    }
+    for (row, ((row_a, row_b), row_c)) in matrices
+        .a
+        .iter()
+        .zip(&matrices.b)
+        .zip(&matrices.c)
+        .enumerate()
+    {
+        for &(coeff, i) in row_a {
+            a[i] += l_g1[row] * coeff;
+            abc[i] += beta_l_g1[row] * coeff;
+        }
+        for &(coeff, i) in row_b {
+            b_g1[i] += l_g1[row] * coeff;
+            b_g2[i] += l_g2[row] * coeff;
+            abc[i] += alpha_l_g1[row] * coeff;
+        }
+        for &(coeff, i) in row_c {
+            abc[i] += l_g1[row] * coeff;
+        }
+    }
+
+    // `tau^i * t(tau)` with `t(X) = X^n - 1`, the vanishing polynomial of the domain
+    let h: Vec<_> = (0..n - 1)
+        .map(|i| powers.tau_g1[i + n].into_group() - powers.tau_g1[i])
+        .collect();
+
+    let (g1, g2) = (E::G1Affine::generator(), E::G2Affine::generator());
+    Ok(ProvingKey {
+        vk: VerifyingKey {
+            alpha_g1: powers.alpha_tau_g1[0],
+            beta_g2: powers.beta_g2,
+            gamma_g2: g2,
+            delta_g2: g2,
+            gamma_abc_g1: E::G1::normalize_batch(&abc[..num_instance]),
+        },
+        beta_g1: powers.beta_tau_g1[0],
+        delta_g1: g1,
+        a_query: E::G1::normalize_batch(&a),
+        b_g1_query: E::G1::normalize_batch(&b_g1),
+        b_g2_query: E::G2::normalize_batch(&b_g2),
+        h_query: E::G1::normalize_batch(&h),
+        l_query: E::G1::normalize_batch(&abc[num_instance..]),
+    })
+}
+
+#[derive(Derivative, CanonicalSerialize, CanonicalDeserialize)]
+#[derivative(Clone(bound = ""), Debug(bound = ""), PartialEq(bound = ""))]
+pub struct Contribution<E: Pairing> {
+    /// `delta_g1` after the contribution.
+    pub delta_g1: E::G1Affine,
+    pub proof: KnowledgeProof<E>,
+}
+
+impl<E: Pairing> Contribution<E> {
+    /// The digest a participant publishes to check that their contribution is in the
+    /// transcript.
+    #[must_use]
+    pub fn digest(&self) -> [u8; HASH_OUTPUT_SIZE] {
+        digest(self)
+    }
+}
+
+/// The parameters of phase 2, passed from one participant to the next.
+#[derive(Derivative, CanonicalSerialize, CanonicalDeserialize)]
+#[derivative(Clone(bound = ""), Debug(bound = ""), PartialEq(bound = ""))]
+pub struct Phase2<E: Pairing> {
+    pub pk: ProvingKey<E>,
+    pub contributions: Vec<Contribution<E>>,
+}
+
+impl<E: Pairing> Phase2<E>
+where
+    E::G1: DomainCoeff<E::ScalarField>,
+    E::G2: DomainCoeff<E::ScalarField>,
+{
+    /// Start phase 2 for `circuit`, which does not need to be assigned, from `powers`, which
+    /// are verified with random linear combinations drawn from `rng`.
+    pub fn from_powers_of_tau<C: ConstraintSynthesizer<E::ScalarField>>(
+        circuit: C,
+        powers: &PowersOfTau<E>,
+        rng: &mut impl Rng,
+    ) -> Result<Self, CeremonyError> {
+        powers.verify(rng)?;
+        Ok(Self {
+            pk: initial_key(circuit, powers)?,
+            contributions: vec![],
+        })
+    }
+
+    /// Contribute a secret drawn from `rng`, which should be local entropy and is dropped
+    /// before returning. Returns the digest of the contribution.
+    pub fn contribute(&mut self, rng: &mut impl Rng) -> [u8; HASH_OUTPUT_SIZE] {
+        let d = E::ScalarField::rand(rng);
+        let d_inv = d.inverse().expect("a random scalar should not be 0");
+
+        let previous_delta_g1 = self.pk.delta_g1;
+        self.pk.delta_g1 = (self.pk.delta_g1 * d).into_affine();
+        self.pk.vk.delta_g2 = (self.pk.vk.delta_g2 * d).into_affine();
+        for query in [&mut self.pk.h_query, &mut self.pk.l_query] {
+            let scaled: Vec<_> = query.iter().map(|p| *p * d_inv).collect();
+            *query = E::G1::normalize_batch(&scaled);
+        }
+
+        let contribution = Contribution {
+            delta_g1: self.pk.delta_g1,
+            proof: KnowledgeProof::prove(DELTA, d, &previous_delta_g1, rng),
+        };
+        let digest = contribution.digest();
+        self.contributions.push(contribution);
+        digest
+    }
+
+    /// Check that `powers` are valid, and that `self.pk` follows from the key of `circuit`
+    /// derived from them through every contribution of the transcript.
+    ///
+    /// The powers and the `h` and `l` queries are checked with random linear combinations
+    /// drawn from `rng`.
+    pub fn verify<C: ConstraintSynthesizer<E::ScalarField>>(
+        &self,
+        circuit: C,
+        powers: &PowersOfTau<E>,
+        rng: &mut impl Rng,
+    ) -> Result<(), CeremonyError> {
+        // without contributions, `delta = gamma = 1` and proofs are malleable
+        if self.contributions.is_empty() {
+            return Err(CeremonyError::InconsistentKey);
+        }
+        powers.verify(rng)?;
+        let initial = initial_key(circuit, powers)?;
+
+        let mut delta_g1 = initial.delta_g1;
+        for (i, contribution) in self.contributions.iter().enumerate() {
+            if !contribution
+                .proof
+                .verify(DELTA, &delta_g1, &contribution.delta_g1)
+            {
+                return Err(CeremonyError::InvalidContribution(i));
+            }
+            delta_g1 = contribution.delta_g1;
+        }
+        let (pk, g2) = (&self.pk, E::G2Affine::generator());
+        let unchanged = pk.vk.alpha_g1 == initial.vk.alpha_g1
+            && pk.vk.beta_g2 == initial.vk.beta_g2
+            && pk.vk.gamma_g2 == initial.vk.gamma_g2
+            && pk.vk.gamma_abc_g1 == initial.vk.gamma_abc_g1
+            && pk.beta_g1 == initial.beta_g1
+            && pk.a_query == initial.a_query
+            && pk.b_g1_query == initial.b_g1_query
+            && pk.b_g2_query == initial.b_g2_query
+            && pk.h_query.len() == initial.h_query.len()
+            && pk.l_query.len() == initial.l_query.len();
+        if !unchanged
+            || pk.delta_g1 != delta_g1
+            || !same_ratio::<E>(
+                (E::G1Affine::generator(), pk.delta_g1),
+                (g2, pk.vk.delta_g2),
+            )
+        {
+            return Err(CeremonyError::InconsistentKey);
+        }
+
+        // every point of the queries is divided by the same `delta / delta_initial`
+        for (query, initial_query) in [
+            (&pk.h_query, &initial.h_query),
+            (&pk.l_query, &initial.l_query),
+        ] {
+            let rho: Vec<_> = query.iter().map(|_| E::ScalarField::rand(rng)).collect();
+            let combined = E::G1::msm_unchecked(query, &rho).into_affine();
+            let initial_combined = E::G1::msm_unchecked(initial_query, &rho).into_affine();
+            if !same_ratio::<E>(
+                (combined, initial_combined),
+                (initial.vk.delta_g2, pk.vk.delta_g2),
+            ) {
+                return Err(CeremonyError::InconsistentKey);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Verify the transcript as `verify` does, and return the proving key, whose `vk` is the
+    /// verifying key.
+    pub fn finalize<C: ConstraintSynthesizer<E::ScalarField>>(
+        self,
+        circuit: C,
+        powers: &PowersOfTau<E>,
+        rng: &mut impl Rng,
+    ) -> Result<ProvingKey<E>, CeremonyError> {
+        self.verify(circuit, powers, rng)?;
+        Ok(self.pk)
+    }
+}
+
+/// Whether `g1.1 = x * g1.0` and `g2.1 = x * g2.0` for the same `x`, i.e.
+/// `e(g1.0, g2.1) = e(g1.1, g2.0)`.
+fn same_ratio<E: Pairing>(g1: (E::G1Affine, E::G1Affine), g2: (E::G2Affine, E::G2Affine)) -> bool {
+    E::pairing(g1.0, g2.1) == E::pairing(g1.1, g2.0)
+}
+
+#[cfg(test)]
+mod test {
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_ec::CurveGroup;
+    use ark_ff::Field;
+    use ark_groth16::Groth16;
+    use ark_r1cs_std::{alloc::AllocVar, eq::EqGadget, fields::fp::FpVar};
+    use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
+    use rand::{thread_rng, Rng};
+
+    use super::{CeremonyError, Phase2, PowersOfTau};
+
+    /// Knowledge of a square root `x` of the public `y`.
+    #[derive(Clone, Copy)]
+    struct Square(Option<Fr>);
+
+    impl ConstraintSynthesizer<Fr> for Square {
+        fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+            let x = FpVar::new_witness(cs.clone(), || {
+                self.0.ok_or(SynthesisError::AssignmentMissing)
+            })?;
+            let y = FpVar::new_input(cs, || {
+                self.0
+                    .map(|x| x.square())
+                    .ok_or(SynthesisError::AssignmentMissing)
+            })?;
+            x.square()?.enforce_equal(&y)
+        }
+    }
+
+    #[test]
+    fn check_powers_of_tau() {
+        let mut rng = thread_rng();
+        let mut powers = PowersOfTau::<Bls12_381>::new(4);
+        // nobody contributed, so `tau = 1`
+        assert!(matches!(
+            powers.verify(&mut rng),
+            Err(CeremonyError::InvalidPowers)
+        ));
+
+        let digests: Vec<_> = (0..2).map(|_| powers.contribute(&mut rng)).collect();
+        assert_eq!(powers.contributions[1].digest(), digests[1]);
+        powers.verify(&mut rng).unwrap();
+
+        let mut copied = powers.clone();
+        copied.contributions[1] = copied.contributions[0].clone();
+        assert!(matches!(
+            copied.verify(&mut rng),
+            Err(CeremonyError::InvalidContribution(1))
+        ));
+
+        // a power that is not `tau` times the previous one
+        let mut tampered = powers.clone();
+        tampered.tau_g1[5] = (tampered.tau_g1[5] + tampered.tau_g1[5]).into_affine();
+        assert!(matches!(
+            tampered.verify(&mut rng),
+            Err(CeremonyError::InvalidPowers)
+        ));
+        let mut tampered = powers.clone();
+        tampered.alpha_tau_g1[3] = tampered.alpha_tau_g1[2];
+        assert!(matches!(
+            tampered.verify(&mut rng),
+            Err(CeremonyError::InvalidPowers)
+        ));
+    }
+
+    #[test]
+    fn check_ceremony() {
+        let mut rng = thread_rng();
+        let mut powers = PowersOfTau::<Bls12_381>::new(4);
+        powers.contribute(&mut rng);
+
+        // 1 constraint and 2 instance variables need a domain of 4
+        let mut small = PowersOfTau::<Bls12_381>::new(2);
+        small.contribute(&mut rng);
+        assert!(matches!(
+            Phase2::from_powers_of_tau(Square(None), &small, &mut rng),
+            Err(CeremonyError::CircuitTooLarge)
+        ));
+
+        let mut phase2 = Phase2::from_powers_of_tau(Square(None), &powers, &mut rng).unwrap();
+        let initial = phase2.pk.clone();
+        assert!(matches!(
+            phase2.clone().finalize(Square(None), &powers, &mut rng),
+            Err(CeremonyError::InconsistentKey)
+        ));
+
+        let digests: Vec<_> = (0..3).map(|_| phase2.contribute(&mut rng)).collect();
+        assert_eq!(phase2.contributions[1].digest(), digests[1]);
+        assert_ne!(phase2.pk.vk.delta_g2, initial.vk.delta_g2);
+        phase2.verify(Square(None), &powers, &mut rng).unwrap();
+
+        // a contribution copied from another participant does not verify
+        let mut copied = phase2.clone();
+        copied.contributions[2] = copied.contributions[1].clone();
+        assert!(matches!(
+            copied.verify(Square(None), &powers, &mut rng),
+            Err(CeremonyError::InvalidContribution(2))
+        ));
+
+        // nor does a key whose queries do not follow its `delta`
+        let mut tampered = phase2.clone();
+        tampered.pk.h_query[0] = (tampered.pk.h_query[0] + tampered.pk.h_query[0]).into_affine();
+        assert!(matches!(
+            tampered.verify(Square(None), &powers, &mut rng),
+            Err(CeremonyError::InconsistentKey)
+        ));
+
+        // nor a key that does not start from the powers of tau
+        let mut other = PowersOfTau::<Bls12_381>::new(4);
+        other.contribute(&mut rng);
+        assert!(matches!(
+            phase2.verify(Square(None), &other, &mut rng),
+            Err(CeremonyError::InconsistentKey)
+        ));
+
+        let pk = phase2.finalize(Square(None), &powers, &mut rng).unwrap();
+        let pvk = Groth16::<Bls12_381>::process_vk(&pk.vk).unwrap();
+        let x: Fr = rng.gen();
+        let proof = Groth16::<Bls12_381>::create_random_proof_with_reduction(
+            Square(Some(x)),
+            &pk,
+            &mut rng,
+        )
+        .unwrap();
+        assert!(Groth16::<Bls12_381>::verify_proof(&pvk, &proof, &[x.square()]).unwrap());
+        assert!(!Groth16::<Bls12_381>::verify_proof(&pvk, &proof, &[x]).unwrap());
+    }
+}
@@ -0,0 +1,561 @@
+//! Encodings of Groth16 proofs, verifying keys and public inputs for verifiers outside this
+//! crate.
+//!
+//! `Calldata` lays them out as the words a Solidity verifier takes: each coordinate is a
+//! big-endian word of 32 bytes, or of the next multiple of 32 bytes for larger fields, as in
+//! EIP-2537. Coordinates over a quadratic extension put the imaginary part first with
+//! 32-byte words, as the BN254 precompiles of EIP-197 expect, and the real part first with
+//! larger words, as the BLS12 precompiles of EIP-2537 expect. The identity is encoded as zeros.
+//!
+//! With the `serde` feature, `Json` writes them as the `proof.json`, `verification_key.json`
+//! and `public.json` of snarkjs: decimal strings, with points in projective coordinates.
+use core::fmt;
+
+use ark_ec::{pairing::Pairing, AffineRepr};
+use ark_ff::{BigInteger, Field, PrimeField, Zero};
+use ark_groth16::{Proof, VerifyingKey};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+#[cfg(feature = "serde")]
+use {
+    ark_ff::One,
+    serde::{Deserialize, Serialize},
+};
+
+#[derive(Debug)]
+pub enum EncodingError {
+    /// The input has missing or extra bytes or elements.
+    InvalidLength,
+    /// A field element is not canonical.
+    InvalidElement,
+    /// A point is not in the prime-order subgroup of its curve.
+    InvalidPoint,
+    /// The JSON is for another protocol or curve.
+    Unsupported { protocol: String, curve: String },
+    #[cfg(feature = "serde")]
+    Json(serde_json::Error),
+}
+
+impl fmt::Display for EncodingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidLength => write!(f, "invalid length"),
+            Self::InvalidElement => write!(f, "invalid field element"),
+            Self::InvalidPoint => write!(f, "invalid point"),
+            Self::Unsupported { protocol, curve } => {
+                write!(f, "unsupported protocol {protocol} on curve {curve}")
+            }
+            #[cfg(feature = "serde")]
+            Self::Json(e) => write!(f, "json error: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for EncodingError {}
+
+#[cfg(feature = "serde")]
+impl From<serde_json::Error> for EncodingError {
+    fn from(e: serde_json::Error) -> Self {
+        Self::Json(e)
+    }
+}
+
+/// The name snarkjs gives to the curve of a pairing.
+pub trait SnarkjsCurve: Pairing {
+    const NAME: &'static str;
+}
+
+impl SnarkjsCurve for ark_bls12_381::Bls12_381 {
+    const NAME: &'static str = "bls12381";
+}
+
+impl SnarkjsCurve for ark_bls12_377::Bls12_377 {
+    const NAME: &'static str = "bls12377";
+}
+
+#[cfg(feature = "evm")]
+impl SnarkjsCurve for ark_bn254::Bn254 {
+    const NAME: &'static str = "bn128";
+}
+
+/// The base prime field of the coordinates of `A`.
+type BasePrimeField<A> = <<A as AffineRepr>::BaseField as Field>::BasePrimeField;
+
+/// The point with coordinates `x` and `y` over the base prime field, which must be in the
+/// prime-order subgroup.
+fn from_coordinates<A: AffineRepr>(
+    x: Vec<BasePrimeField<A>>,
+    y: Vec<BasePrimeField<A>>,
+) -> Result<A, EncodingError> {
+    let mut bytes = vec![];
+    for c in [x, y] {
+        A::BaseField::from_base_prime_field_elems(c)
+            .ok_or(EncodingError::InvalidLength)?
+            .serialize_uncompressed(&mut bytes)
+            .map_err(|_| EncodingError::InvalidElement)?;
+    }
+    // without flags, the uncompressed encoding of a point is that of `x` and `y`
+    A::deserialize_uncompressed(bytes.as_slice()).map_err(|_| EncodingError::InvalidPoint)
+}
+
+/// The size of the calldata word of an element of `F`.
+fn word_size<F: PrimeField>() -> usize {
+    (F::MODULUS_BIT_SIZE as usize)
+        .div_ceil(8)
+        .next_multiple_of(32)
+}
+
+fn push_word<F: PrimeField>(bytes: &mut Vec<u8>, f: &F) {
+    let be = f.into_bigint().to_bytes_be();
+    let value = &be[be.len().saturating_sub(word_size::<F>())..];
+    bytes.resize(bytes.len() + word_size::<F>() - value.len(), 0);
+    bytes.extend_from_slice(value);
+}
+
+/// Whether the elements of extension field coordinates are written in reverse, i.e. the
+/// imaginary part first, which is the order of EIP-197 but not of EIP-2537.
+fn imaginary_first<A: AffineRepr>() -> bool {
+    word_size::<BasePrimeField<A>>() == 32
+}
+
+fn push_point<A: AffineRepr>(bytes: &mut Vec<u8>, point: &A) {
+    let degree = A::BaseField::extension_degree() as usize;
+    let Some((x, y)) = point.xy() else {
+        let zero = BasePrimeField::<A>::zero();
+        (0..2 * degree).for_each(|_| push_word(bytes, &zero));
+        return;
+    };
+    for c in [x, y] {
+        let mut elems: Vec<_> = c.to_base_prime_field_elements().collect();
+        if imaginary_first::<A>() {
+            elems.reverse();
+        }
+        elems.iter().for_each(|e| push_word(bytes, e));
+    }
+}
+
+/// Reads calldata words from the front of `bytes`.
+struct Words<'a>(&'a [u8]);
+
+impl Words<'_> {
+    fn field<F: PrimeField>(&mut self) -> Result<F, EncodingError> {
+        let (word, rest) = self
+            .0
+            .split_at_checked(word_size::<F>())
+            .ok_or(EncodingError::InvalidLength)?;
+        self.0 = rest;
+
+        let f = F::from_be_bytes_mod_order(word);
+        let mut canonical = vec![];
+        push_word(&mut canonical, &f);
+        if canonical != word {
+            return Err(EncodingError::InvalidElement);
+        }
+        Ok(f)
+    }
+
+    fn point<A: AffineRepr>(&mut self) -> Result<A, EncodingError> {
+        let degree = A::BaseField::extension_degree() as usize;
+        let mut coordinate = || {
+            let mut elems = (0..degree)
+                .map(|_| self.field())
+                .collect::<Result<Vec<_>, _>>()?;
+            if imaginary_first::<A>() {
+                elems.reverse();
+            }
+            Ok::<_, EncodingError>(elems)
+        };
+        let (x, y): (Vec<BasePrimeField<A>>, _) = (coordinate()?, coordinate()?);
+        if x.iter().chain(&y).all(Zero::is_zero) {
+            return Ok(A::zero());
+        }
+        from_coordinates(x, y)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+pub trait Calldata: Sized {
+    fn to_calldata(&self) -> Vec<u8>;
+
+    /// Decode `bytes`, which must be exactly the encoding of a value.
+    fn from_calldata(bytes: &[u8]) -> Result<Self, EncodingError>;
+
+    /// The calldata as a `0x`-prefixed hex string.
+    fn to_calldata_hex(&self) -> String {
+        use core::fmt::Write;
+
+        self.to_calldata()
+            .iter()
+            .fold(String::from("0x"), |mut hex, b| {
+                let _ = write!(hex, "{b:02x}");
+                hex
+            })
+    }
+}
+
+/// `a`, `b` and `c`.
+impl<E: Pairing> Calldata for Proof<E> {
+    fn to_calldata(&self) -> Vec<u8> {
+        let mut bytes = vec![];
+        push_point(&mut bytes, &self.a);
+        push_point(&mut bytes, &self.b);
+        push_point(&mut bytes, &self.c);
+        bytes
+    }
+
+    fn from_calldata(bytes: &[u8]) -> Result<Self, EncodingError> {
+        let mut words = Words(bytes);
+        let proof = Self {
+            a: words.point()?,
+            b: words.point()?,
+            c: words.point()?,
+        };
+        words
+            .is_empty()
+            .then_some(proof)
+            .ok_or(EncodingError::InvalidLength)
+    }
+}
+
+/// `alpha_g1`, `beta_g2`, `gamma_g2` and `delta_g2`, followed by `gamma_abc_g1`, whose length
+/// is that of the rest of the calldata.
+impl<E: Pairing> Calldata for VerifyingKey<E> {
+    fn to_calldata(&self) -> Vec<u8> {
+        let mut bytes = vec![];
+        push_point(&mut bytes, &self.alpha_g1);
+        push_point(&mut bytes, &self.beta_g2);
+        push_point(&mut bytes, &self.gamma_g2);
+        push_point(&mut bytes, &self.delta_g2);
+        for point in &self.gamma_abc_g1 {
+            push_point(&mut bytes, point);
+        }
+        bytes
+    }
+
+    fn from_calldata(bytes: &[u8]) -> Result<Self, EncodingError> {
+        let mut words = Words(bytes);
+        let mut vk = Self {
+            alpha_g1: words.point()?,
+            beta_g2: words.point()?,
+            gamma_g2: words.point()?,
+            delta_g2: words.point()?,
+            gamma_abc_g1: vec![],
+        };
+        while !words.is_empty() {
+            vk.gamma_abc_g1.push(words.point()?);
+        }
+        Ok(vk)
+    }
+}
+
+/// One word per public input.
+impl<F: PrimeField> Calldata for Vec<F> {
+    fn to_calldata(&self) -> Vec<u8> {
+        let mut bytes = vec![];
+        self.iter().for_each(|f| push_word(&mut bytes, f));
+        bytes
+    }
+
+    fn from_calldata(bytes: &[u8]) -> Result<Self, EncodingError> {
+        let mut words = Words(bytes);
+        let mut inputs = vec![];
+        while !words.is_empty() {
+            inputs.push(words.field()?);
+        }
+        Ok(inputs)
+    }
+}
+
+#[cfg(feature = "serde")]
+pub trait Json: Sized {
+    fn to_json(&self) -> String;
+
+    fn from_json(json: &str) -> Result<Self, EncodingError>;
+}
+
+/// The projective coordinates of a point, each as the decimal strings of its elements over
+/// the base prime field. The identity is `(0, 1, 0)`.
+#[cfg(feature = "serde")]
+type JsonPoint = [Vec<String>; 3];
+
+#[cfg(feature = "serde")]
+fn point_to_json<A: AffineRepr>(point: &A) -> JsonPoint {
+    let strings = |c: A::BaseField| -> Vec<String> {
+        c.to_base_prime_field_elements()
+            .map(|e| e.to_string())
+            .collect()
+    };
+    match point.xy() {
+        Some((x, y)) => [strings(x), strings(y), strings(A::BaseField::one())],
+        None => [
+            strings(A::BaseField::zero()),
+            strings(A::BaseField::one()),
+            strings(A::BaseField::zero()),
+        ],
+    }
+}
+
+#[cfg(feature = "serde")]
+fn point_from_json<A: AffineRepr>(point: &JsonPoint) -> Result<A, EncodingError> {
+    let [x, y, z] = point.each_ref().map(|c| {
+        c.iter()
+            .map(|s| {
+                let e: BasePrimeField<A> = s.parse().map_err(|_| EncodingError::InvalidElement)?;
+                // parsing reduces modulo the characteristic
+                (e.to_string() == *s)
+                    .then_some(e)
+                    .ok_or(EncodingError::InvalidElement)
+            })
+            .collect::<Result<Vec<_>, _>>()
+    });
+    let z = A::BaseField::from_base_prime_field_elems(z?).ok_or(EncodingError::InvalidLength)?;
+    if z.is_zero() {
+        return Ok(A::zero());
+    }
+    if !z.is_one() {
+        return Err(EncodingError::InvalidPoint);
+    }
+    from_coordinates(x?, y?)
+}
+
+/// G1 points are written with the strings of their coordinates, and G2 points with the
+/// lists of the strings of theirs.
+#[cfg(feature = "serde")]
+fn g1_to_json<A: AffineRepr>(point: &A) -> Vec<String> {
+    point_to_json(point).into_iter().flatten().collect()
+}
+
+#[cfg(feature = "serde")]
+fn g1_from_json<A: AffineRepr>(point: &[String]) -> Result<A, EncodingError> {
+    let point: [String; 3] = point
+        .to_vec()
+        .try_into()
+        .map_err(|_| EncodingError::InvalidLength)?;
+    point_from_json(&point.map(|c| vec![c]))
+}
+
+#[cfg(feature = "serde")]
+#[derive(Serialize, Deserialize)]
+struct ProofJson {
+    pi_a: Vec<String>,
+    pi_b: JsonPoint,
+    pi_c: Vec<String>,
+    protocol: String,
+    curve: String,
+}
+
+#[cfg(feature = "serde")]
+#[derive(Serialize, Deserialize)]
+struct VerifyingKeyJson {
+    protocol: String,
+    curve: String,
+    #[serde(rename = "nPublic")]
+    n_public: usize,
+    vk_alpha_1: Vec<String>,
+    vk_beta_2: JsonPoint,
+    vk_gamma_2: JsonPoint,
+    vk_delta_2: JsonPoint,
+    #[serde(rename = "IC")]
+    ic: Vec<Vec<String>>,
+}
+
+#[cfg(feature = "serde")]
+const PROTOCOL: &str = "groth16";
+
+#[cfg(feature = "serde")]
+fn check_protocol<E: SnarkjsCurve>(protocol: String, curve: String) -> Result<(), EncodingError> {
+    if protocol != PROTOCOL || curve != E::NAME {
+        return Err(EncodingError::Unsupported { protocol, curve });
+    }
+    Ok(())
+}
+
+#[cfg(feature = "serde")]
+impl<E: SnarkjsCurve> Json for Proof<E> {
+    fn to_json(&self) -> String {
+        serde_json::to_string_pretty(&ProofJson {
+            pi_a: g1_to_json(&self.a),
+            pi_b: point_to_json(&self.b),
+            pi_c: g1_to_json(&self.c),
+            protocol: PROTOCOL.to_string(),
+            curve: E::NAME.to_string(),
+        })
+        .expect("serializing a proof should succeed")
+    }
+
+    fn from_json(json: &str) -> Result<Self, EncodingError> {
+        let proof: ProofJson = serde_json::from_str(json)?;
+        check_protocol::<E>(proof.protocol, proof.curve)?;
+        Ok(Self {
+            a: g1_from_json(&proof.pi_a)?,
+            b: point_from_json(&proof.pi_b)?,
+            c: g1_from_json(&proof.pi_c)?,
+        })
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<E: SnarkjsCurve> Json for VerifyingKey<E> {
+    fn to_json(&self) -> String {
+        serde_json::to_string_pretty(&VerifyingKeyJson {
+            protocol: PROTOCOL.to_string(),
+            curve: E::NAME.to_string(),
+            n_public: self.gamma_abc_g1.len().saturating_sub(1),
+            vk_alpha_1: g1_to_json(&self.alpha_g1),
+            vk_beta_2: point_to_json(&self.beta_g2),
+            vk_gamma_2: point_to_json(&self.gamma_g2),
+            vk_delta_2: point_to_json(&self.delta_g2),
+            ic: self.gamma_abc_g1.iter().map(g1_to_json).collect(),
+        })
+        .expect("serializing a verifying key should succeed")
+    }
+
+    fn from_json(json: &str) -> Result<Self, EncodingError> {
+        let vk: VerifyingKeyJson = serde_json::from_str(json)?;
+        check_protocol::<E>(vk.protocol, vk.curve)?;
+        if vk.ic.len() != vk.n_public + 1 {
+            return Err(EncodingError::InvalidLength);
+        }
+        Ok(Self {
+            alpha_g1: g1_from_json(&vk.vk_alpha_1)?,
+            beta_g2: point_from_json(&vk.vk_beta_2)?,
+            gamma_g2: point_from_json(&vk.vk_gamma_2)?,
+            delta_g2: point_from_json(&vk.vk_delta_2)?,
+            gamma_abc_g1: vk
+                .ic
+                .iter()
+                .map(|p| g1_from_json(p))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+/// The decimal strings of the public inputs.
+#[cfg(feature = "serde")]
+impl<F: PrimeField> Json for Vec<F> {
+    fn to_json(&self) -> String {
+        let inputs: Vec<_> = self.iter().map(F::to_string).collect();
+        serde_json::to_string_pretty(&inputs).expect("serializing strings should succeed")
+    }
+
+    fn from_json(json: &str) -> Result<Self, EncodingError> {
+        let inputs: Vec<String> = serde_json::from_str(json)?;
+        inputs
+            .iter()
+            .map(|s| {
+                let f: F = s.parse().map_err(|_| EncodingError::InvalidElement)?;
+                (f.to_string() == *s)
+                    .then_some(f)
+                    .ok_or(EncodingError::InvalidElement)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ark_bls12_381::{Bls12_381, Fr, G1Affine};
+    use ark_ec::AffineRepr;
+    use ark_ff::Field;
+    use ark_groth16::{Groth16, Proof, VerifyingKey};
+    use ark_r1cs_std::{alloc::AllocVar, eq::EqGadget, fields::fp::FpVar};
+    use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
+    use rand::{thread_rng, Rng};
+
+    use super::{push_word, Calldata, EncodingError};
+
+    /// Knowledge of a square root `x` of the public `y`.
+    #[derive(Clone, Copy)]
+    struct Square(Option<Fr>);
+
+    impl ConstraintSynthesizer<Fr> for Square {
+        fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+            let x = FpVar::new_witness(cs.clone(), || {
+                self.0.ok_or(SynthesisError::AssignmentMissing)
+            })?;
+            let y = FpVar::new_input(cs, || {
+                self.0
+                    .map(|x| x.square())
+                    .ok_or(SynthesisError::AssignmentMissing)
+            })?;
+            x.square()?.enforce_equal(&y)
+        }
+    }
+
+    fn proof() -> (VerifyingKey<Bls12_381>, Proof<Bls12_381>, Vec<Fr>) {
+        let mut rng = thread_rng();
+        let pk =
+            Groth16::<Bls12_381>::generate_random_parameters_with_reduction(Square(None), &mut rng)
+                .unwrap();
+        let x: Fr = rng.gen();
+        let proof = Groth16::<Bls12_381>::create_random_proof_with_reduction(
+            Square(Some(x)),
+            &pk,
+            &mut rng,
+        )
+        .unwrap();
+        (pk.vk, proof, vec![x.square()])
+    }
+
+    #[test]
+    fn check_calldata() {
+        let (vk, proof, inputs) = proof();
+
+        // 2 words of 64 bytes for each G1 point and 4 for each G2 point
+        let bytes = proof.to_calldata();
+        assert_eq!(bytes.len(), 8 * 64);
+        assert_eq!(Proof::from_calldata(&bytes).unwrap(), proof);
+        // G2 coordinates are written real part first, as EIP-2537 expects
+        let mut b_x_c0 = vec![];
+        push_word(&mut b_x_c0, &proof.b.x.c0);
+        assert_eq!(&bytes[128..192], b_x_c0.as_slice());
+        assert!(proof.to_calldata_hex().starts_with("0x"));
+        assert!(matches!(
+            Proof::<Bls12_381>::from_calldata(&bytes[1..]),
+            Err(EncodingError::InvalidLength)
+        ));
+
+        assert_eq!(VerifyingKey::from_calldata(&vk.to_calldata()).unwrap(), vk);
+        assert_eq!(
+            Vec::<Fr>::from_calldata(&inputs.to_calldata()).unwrap(),
+            inputs
+        );
+
+        // the identity is encoded as zeros
+        let identity = Proof::<Bls12_381> {
+            a: G1Affine::zero(),
+            ..proof
+        };
+        let bytes = identity.to_calldata();
+        assert!(bytes[..128].iter().all(|b| *b == 0));
+        assert_eq!(Proof::from_calldata(&bytes).unwrap(), identity);
+
+        // non-canonical elements are rejected
+        let bytes = vec![0xff; 32];
+        assert!(matches!(
+            Vec::<Fr>::from_calldata(&bytes),
+            Err(EncodingError::InvalidElement)
+        ));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn check_json() {
+        use super::Json;
+
+        let (vk, proof, inputs) = proof();
+
+        let json = proof.to_json();
+        assert!(json.contains("\"curve\": \"bls12381\""));
+        assert_eq!(Proof::from_json(&json).unwrap(), proof);
+        assert_eq!(VerifyingKey::from_json(&vk.to_json()).unwrap(), vk);
+        assert_eq!(Vec::<Fr>::from_json(&inputs.to_json()).unwrap(), inputs);
+
+        assert!(matches!(
+            Proof::<ark_bls12_377::Bls12_377>::from_json(&json),
+            Err(EncodingError::Unsupported { .. })
+        ));
+    }
+}
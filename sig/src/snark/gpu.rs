@@ -0,0 +1,152 @@
+//! An `MsmBackend` that runs the MSMs of the Groth16 prover on a GPU with ICICLE.
+//!
+//! The standalone BLS circuits over BLS12-377 are proved with Groth16 on BW6-761, whose
+//! scalar field is the base field of BLS12-377, and ICICLE supports both groups of BW6-761.
+//! The first MSM loads the CUDA backend of ICICLE (from `ICICLE_BACKEND_INSTALL_DIR`, or
+//! `/opt/icicle/lib/backend` by default). If it cannot be loaded, or there is no device,
+//! or an MSM fails on the device, the MSMs fall back to `CpuMsm`.
+use std::sync::OnceLock;
+
+use ark_bw6_761::{Fq, BW6_761};
+use ark_ec::{pairing::Pairing, short_weierstrass::SWCurveConfig, AffineRepr};
+use ark_ff::{BigInteger, PrimeField, Zero};
+use icicle_bw6_761::curve::{CurveCfg, G2CurveCfg};
+use icicle_core::{
+    curve::{Affine, Curve, Projective},
+    msm::{msm, MSMConfig, MSM},
+    traits::FieldImpl,
+};
+use icicle_runtime::{memory::HostSlice, runtime::load_backend_from_env_or_default, Device};
+
+use super::msm::{CpuMsm, MsmBackend};
+
+type BigInt = <<BW6_761 as Pairing>::ScalarField as PrimeField>::BigInt;
+
+/// The device ICICLE runs the MSMs on.
+const DEVICE_TYPE: &str = "CUDA";
+
+/// The MSMs of ICICLE on the first CUDA device, or those of `CpuMsm` without one.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct IcicleMsm;
+
+impl IcicleMsm {
+    /// Whether the MSMs run on the GPU, i.e. the CUDA backend is loaded and has a device.
+    #[must_use]
+    pub fn is_available() -> bool {
+        static AVAILABLE: OnceLock<bool> = OnceLock::new();
+        *AVAILABLE.get_or_init(|| {
+            load_backend_from_env_or_default().is_ok()
+                && icicle_runtime::is_device_available(&Device::new(DEVICE_TYPE, 0))
+        })
+    }
+}
+
+fn to_icicle_field<F: FieldImpl>(bigint: impl BigInteger) -> F {
+    F::from_bytes_le(&bigint.to_bytes_le())
+}
+
+fn to_icicle_affine<C: Curve, P: SWCurveConfig<BaseField = Fq>>(
+    point: &ark_ec::short_weierstrass::Affine<P>,
+) -> Affine<C> {
+    // ICICLE encodes the point at infinity as (0, 0), as do the coordinates of arkworks
+    Affine {
+        x: to_icicle_field(point.x.into_bigint()),
+        y: to_icicle_field(point.y.into_bigint()),
+    }
+}
+
+fn from_icicle_projective<C: Curve, P: SWCurveConfig<BaseField = Fq>>(
+    point: Projective<C>,
+) -> ark_ec::short_weierstrass::Projective<P> {
+    let point = Affine::<C>::from(point);
+    let x = Fq::from_le_bytes_mod_order(&point.x.to_bytes_le());
+    let y = Fq::from_le_bytes_mod_order(&point.y.to_bytes_le());
+    if x.is_zero() && y.is_zero() {
+        ark_ec::short_weierstrass::Projective::zero()
+    } else {
+        ark_ec::short_weierstrass::Affine::new_unchecked(x, y).into_group()
+    }
+}
+
+/// `sum_i scalars[i] * bases[i]` on the device, or `None` if ICICLE fails.
+fn icicle_msm<C: Curve + MSM<C>, P: SWCurveConfig<BaseField = Fq>>(
+    bases: &[ark_ec::short_weierstrass::Affine<P>],
+    scalars: &[BigInt],
+) -> Option<ark_ec::short_weierstrass::Projective<P>> {
+    // `set_device` only applies to the calling thread
+    icicle_runtime::set_device(&Device::new(DEVICE_TYPE, 0)).ok()?;
+
+    let len = bases.len().min(scalars.len());
+    let bases: Vec<Affine<C>> = bases[..len].iter().map(to_icicle_affine).collect();
+    let scalars: Vec<C::ScalarField> = scalars[..len].iter().map(|s| to_icicle_field(*s)).collect();
+
+    let mut result = [Projective::<C>::zero()];
+    msm(
+        HostSlice::from_slice(&scalars),
+        HostSlice::from_slice(&bases),
+        &MSMConfig::default(),
+        HostSlice::from_mut_slice(&mut result),
+    )
+    .ok()?;
+    Some(from_icicle_projective(result[0]))
+}
+
+impl MsmBackend<BW6_761> for IcicleMsm {
+    fn msm_g1(
+        bases: &[<BW6_761 as Pairing>::G1Affine],
+        scalars: &[BigInt],
+    ) -> <BW6_761 as Pairing>::G1 {
+        Self::is_available()
+            .then(|| icicle_msm::<CurveCfg, _>(bases, scalars))
+            .flatten()
+            .unwrap_or_else(|| <CpuMsm as MsmBackend<BW6_761>>::msm_g1(bases, scalars))
+    }
+
+    fn msm_g2(
+        bases: &[<BW6_761 as Pairing>::G2Affine],
+        scalars: &[BigInt],
+    ) -> <BW6_761 as Pairing>::G2 {
+        Self::is_available()
+            .then(|| icicle_msm::<G2CurveCfg, _>(bases, scalars))
+            .flatten()
+            .unwrap_or_else(|| <CpuMsm as MsmBackend<BW6_761>>::msm_g2(bases, scalars))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ark_bw6_761::{Fr, G1Affine, G2Affine};
+    use ark_ec::CurveGroup;
+    use ark_ff::UniformRand;
+    use rand::thread_rng;
+
+    use super::*;
+
+    /// On a machine with a GPU, the MSMs of ICICLE agree with those of arkworks, including
+    /// on the point at infinity; elsewhere, this checks the fallback.
+    #[test]
+    fn check_icicle_msm() {
+        const N: usize = 1 << 10;
+
+        let mut rng = thread_rng();
+        let mut g1: Vec<G1Affine> = (0..N)
+            .map(|_| (G1Affine::generator() * Fr::rand(&mut rng)).into_affine())
+            .collect();
+        let g2: Vec<G2Affine> = (0..N)
+            .map(|_| (G2Affine::generator() * Fr::rand(&mut rng)).into_affine())
+            .collect();
+        let scalars: Vec<BigInt> = (0..N).map(|_| Fr::rand(&mut rng).into_bigint()).collect();
+        g1[0] = G1Affine::identity();
+
+        println!("IcicleMsm on the GPU: {}", IcicleMsm::is_available());
+        assert_eq!(
+            <IcicleMsm as MsmBackend<BW6_761>>::msm_g1(&g1, &scalars),
+            <CpuMsm as MsmBackend<BW6_761>>::msm_g1(&g1, &scalars)
+        );
+        assert_eq!(
+            <IcicleMsm as MsmBackend<BW6_761>>::msm_g2(&g2, &scalars),
+            <CpuMsm as MsmBackend<BW6_761>>::msm_g2(&g2, &scalars)
+        );
+        assert!(<IcicleMsm as MsmBackend<BW6_761>>::msm_g1(&g1[..1], &scalars[..1]).is_zero());
+    }
+}
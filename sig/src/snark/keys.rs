@@ -0,0 +1,257 @@
+//! On-disk cache of Groth16 keys, whose setup takes minutes for the BLS circuits over
+//! MNT4-753.
+//!
+//! A key file is `MAGIC`, the checksum of the payload and the compressed key. Keys are cached
+//! under the digest of the constraint matrices of their circuit, so that a change of the
+//! circuit never picks up stale keys.
+use core::fmt;
+use std::{
+    fs::{self, File},
+    io::{self, Read, Write},
+    path::Path,
+};
+
+use ark_ec::pairing::Pairing;
+use ark_ff::PrimeField;
+use ark_groth16::{Groth16, ProvingKey};
+use ark_relations::r1cs::{ConstraintSynthesizer, SynthesisError};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError};
+use blake2::Digest;
+use rand::RngCore;
+
+use crate::bc::params::{HashFunc, HASH_OUTPUT_SIZE};
+
+use super::setup_matrices;
+
+/// Identifies key files, followed by the version of their layout.
+const MAGIC: &[u8; 8] = b"G16KEY\x00\x01";
+
+#[derive(Debug)]
+pub enum KeyError {
+    Io(io::Error),
+    Serialization(SerializationError),
+    /// The file is not one written by `save`, or it was modified.
+    Corrupted,
+    Synthesis(SynthesisError),
+}
+
+impl fmt::Display for KeyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "io error: {e}"),
+            Self::Serialization(e) => write!(f, "serialization error: {e}"),
+            Self::Corrupted => write!(f, "corrupted key file"),
+            Self::Synthesis(e) => write!(f, "synthesis error: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for KeyError {}
+
+impl From<io::Error> for KeyError {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<SerializationError> for KeyError {
+    fn from(e: SerializationError) -> Self {
+        Self::Serialization(e)
+    }
+}
+
+impl From<SynthesisError> for KeyError {
+    fn from(e: SynthesisError) -> Self {
+        Self::Synthesis(e)
+    }
+}
+
+/// Write `key`, e.g. a `ProvingKey`, `VerifyingKey` or `PreparedVerifyingKey`, to `path`.
+///
+/// The key is first written next to `path` and then renamed, so a crash while saving never
+/// leaves a truncated key behind.
+pub fn save<T: CanonicalSerialize>(key: &T, path: impl AsRef<Path>) -> Result<(), KeyError> {
+    let path = path.as_ref();
+    let mut payload = Vec::new();
+    key.serialize_compressed(&mut payload)?;
+
+    let tmp = path.with_extension("tmp");
+    let mut file = File::create(&tmp)?;
+    file.write_all(MAGIC)?;
+    file.write_all(&HashFunc::digest(&payload))?;
+    file.write_all(&payload)?;
+    file.sync_all()?;
+    fs::rename(tmp, path)?;
+
+    Ok(())
+}
+
+/// Read a key written by `save` from `path`.
+///
+/// The points of the key are not checked to be in the prime-order subgroups, which would
+/// take about as long as the setup for large keys: the checksum only detects corruption,
+/// so keys must only be loaded from a trusted cache.
+pub fn load<T: CanonicalDeserialize>(path: impl AsRef<Path>) -> Result<T, KeyError> {
+    let mut bytes = Vec::new();
+    File::open(path)?.read_to_end(&mut bytes)?;
+
+    let (magic, rest) = bytes
+        .split_at_checked(MAGIC.len())
+        .ok_or(KeyError::Corrupted)?;
+    let (checksum, payload) = rest
+        .split_at_checked(HASH_OUTPUT_SIZE)
+        .ok_or(KeyError::Corrupted)?;
+    if magic != MAGIC || checksum != HashFunc::digest(payload).as_slice() {
+        return Err(KeyError::Corrupted);
+    }
+
+    Ok(T::deserialize_compressed_unchecked(payload)?)
+}
+
+/// The digest of the constraint matrices of `circuit`, as synthesized for a Groth16 setup,
+/// which identifies its keys. `circuit` does not need to be assigned.
+pub fn circuit_digest<F: PrimeField>(
+    circuit: impl ConstraintSynthesizer<F>,
+) -> Result<[u8; HASH_OUTPUT_SIZE], KeyError> {
+    let matrices = setup_matrices(circuit)?;
+
+    let mut hasher = HashFunc::new();
+    for len in [
+        matrices.num_instance_variables,
+        matrices.num_witness_variables,
+        matrices.num_constraints,
+    ] {
+        hasher.update((len as u64).to_le_bytes());
+    }
+
+    let mut coeff_bytes = Vec::new();
+    for matrix in [&matrices.a, &matrices.b, &matrices.c] {
+        for row in matrix {
+            hasher.update((row.len() as u64).to_le_bytes());
+            for (coeff, index) in row {
+                coeff_bytes.clear();
+                coeff.serialize_compressed(&mut coeff_bytes)?;
+                hasher.update(&coeff_bytes);
+                hasher.update((*index as u64).to_le_bytes());
+            }
+        }
+    }
+
+    Ok(hasher.finalize().into())
+}
+
+/// The proving key of `circuit`, whose `vk` is its verifying key, loaded from `cache_dir` if
+/// it was generated before, or generated with `rng` and cached otherwise.
+///
+/// `circuit` only gives the shape of the circuit, so it can be unassigned as for a setup.
+/// A key file that cannot be loaded is replaced.
+pub fn get_or_generate<E, C>(
+    cache_dir: impl AsRef<Path>,
+    circuit: C,
+    rng: &mut impl RngCore,
+) -> Result<ProvingKey<E>, KeyError>
+where
+    E: Pairing,
+    C: ConstraintSynthesizer<E::ScalarField> + Clone,
+{
+    let digest = circuit_digest(circuit.clone())?;
+    let file_name = digest
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect::<String>();
+    let path = cache_dir.as_ref().join(file_name).with_extension("pk");
+
+    if let Ok(pk) = load(&path) {
+        return Ok(pk);
+    }
+
+    let pk = Groth16::<E>::generate_random_parameters_with_reduction(circuit, rng)?;
+    fs::create_dir_all(cache_dir)?;
+    save(&pk, &path)?;
+    Ok(pk)
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_groth16::{Groth16, PreparedVerifyingKey, ProvingKey, VerifyingKey};
+    use ark_r1cs_std::{alloc::AllocVar, eq::EqGadget, fields::fp::FpVar};
+    use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
+    use rand::{thread_rng, Rng};
+
+    use super::{circuit_digest, get_or_generate, load, save, KeyError};
+
+    /// Knowledge of `x` with `x^power = y` for the public `y`.
+    #[derive(Clone, Copy)]
+    struct Power {
+        x: Option<Fr>,
+        power: usize,
+    }
+
+    impl ConstraintSynthesizer<Fr> for Power {
+        fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+            let x = FpVar::new_witness(cs.clone(), || {
+                self.x.ok_or(SynthesisError::AssignmentMissing)
+            })?;
+            let y = FpVar::new_input(cs, || {
+                self.x
+                    .map(|x| (1..self.power).fold(x, |y, _| y * x))
+                    .ok_or(SynthesisError::AssignmentMissing)
+            })?;
+            (1..self.power)
+                .fold(x.clone(), |y, _| y * &x)
+                .enforce_equal(&y)
+        }
+    }
+
+    #[test]
+    fn test_key_cache() {
+        let mut rng = thread_rng();
+        let cache_dir = std::env::temp_dir().join(format!("sig-keys-{}", rng.gen::<u64>()));
+        let square = Power { x: None, power: 2 };
+        let cube = Power { x: None, power: 3 };
+
+        assert_ne!(
+            circuit_digest(square).unwrap(),
+            circuit_digest(cube).unwrap()
+        );
+        assert_eq!(
+            circuit_digest(square).unwrap(),
+            circuit_digest(Power {
+                x: Some(Fr::from(3)),
+                power: 2
+            })
+            .unwrap()
+        );
+
+        // the second call loads the keys of the first one instead of a new setup
+        let pk: ProvingKey<Bls12_381> = get_or_generate(&cache_dir, square, &mut rng).unwrap();
+        assert_eq!(get_or_generate(&cache_dir, square, &mut rng).unwrap(), pk);
+        assert_ne!(get_or_generate(&cache_dir, cube, &mut rng).unwrap(), pk);
+
+        let vk_path = cache_dir.join("square.vk");
+        save(&pk.vk, &vk_path).unwrap();
+        assert_eq!(load::<VerifyingKey<Bls12_381>>(&vk_path).unwrap(), pk.vk);
+
+        let pvk = Groth16::<Bls12_381>::process_vk(&pk.vk).unwrap();
+        let pvk_path = cache_dir.join("square.pvk");
+        save(&pvk, &pvk_path).unwrap();
+        assert_eq!(
+            load::<PreparedVerifyingKey<Bls12_381>>(&pvk_path).unwrap(),
+            pvk
+        );
+
+        let mut bytes = fs::read(&vk_path).unwrap();
+        let last = bytes.len() - 1;
+        bytes[last] ^= 1;
+        fs::write(&vk_path, bytes).unwrap();
+        assert!(matches!(
+            load::<VerifyingKey<Bls12_381>>(&vk_path),
+            Err(KeyError::Corrupted)
+        ));
+
+        fs::remove_dir_all(cache_dir).unwrap();
+    }
+}
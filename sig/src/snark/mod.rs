@@ -0,0 +1,79 @@
+//! Helpers for proving the standalone circuits of `crate::bls` with a SNARK, Groth16 by default.
+//!
+//! Only the synthesis of witnesses is available without the `std` feature.
+use ark_ff::PrimeField;
+#[cfg(feature = "std")]
+use ark_relations::r1cs::{ConstraintMatrices, OptimizationGoal};
+use ark_relations::r1cs::{
+    ConstraintSynthesizer, ConstraintSystem, ConstraintSystemRef, SynthesisError, SynthesisMode,
+};
+use ark_std::vec::Vec;
+
+#[cfg(feature = "std")]
+pub mod backend;
+#[cfg(feature = "std")]
+mod batch;
+#[cfg(feature = "std")]
+pub use batch::*;
+#[cfg(feature = "std")]
+pub mod ceremony;
+#[cfg(feature = "std")]
+pub mod encoding;
+#[cfg(feature = "gpu")]
+pub mod gpu;
+#[cfg(feature = "std")]
+pub mod keys;
+#[cfg(feature = "std")]
+pub mod msm;
+
+#[cfg(feature = "std")]
+mod r1cs;
+#[cfg(feature = "std")]
+pub use r1cs::*;
+
+/// The constraint matrices of `circuit`, as synthesized for a setup, which only depend on
+/// the shape of the circuit.
+#[cfg(feature = "std")]
+fn setup_matrices<F: PrimeField>(
+    circuit: impl ConstraintSynthesizer<F>,
+) -> Result<ConstraintMatrices<F>, SynthesisError> {
+    let cs = ConstraintSystem::new_ref();
+    cs.set_optimization_goal(OptimizationGoal::Constraints);
+    cs.set_mode(SynthesisMode::Setup);
+    circuit.generate_constraints(cs.clone())?;
+    cs.finalize();
+    cs.to_matrices().ok_or(SynthesisError::MissingCS)
+}
+
+/// A constraint system that only assigns the variables of a circuit, as a prover needs,
+/// without keeping the constraints to build the matrices, which only the setup needs.
+#[must_use]
+pub fn witness_only_cs<F: PrimeField>() -> ConstraintSystemRef<F> {
+    let cs = ConstraintSystem::new_ref();
+    cs.set_mode(SynthesisMode::Prove {
+        construct_matrices: false,
+    });
+    cs
+}
+
+/// The assignment of the variables of a circuit.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Assignment<F: PrimeField> {
+    /// The public inputs, without the constant `1`.
+    pub instance: Vec<F>,
+    pub witness: Vec<F>,
+}
+
+/// The assignment of `circuit`, generated in `witness_only_cs`, which separates the cost of
+/// witness generation from that of building the constraints.
+pub fn generate_witness_only<F: PrimeField>(
+    circuit: impl ConstraintSynthesizer<F>,
+) -> Result<Assignment<F>, SynthesisError> {
+    let cs = witness_only_cs();
+    circuit.generate_constraints(cs.clone())?;
+    let cs = cs.into_inner().ok_or(SynthesisError::MissingCS)?;
+    Ok(Assignment {
+        instance: cs.instance_assignment[1..].to_vec(),
+        witness: cs.witness_assignment,
+    })
+}
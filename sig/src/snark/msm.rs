@@ -0,0 +1,165 @@
+//! A Groth16 prover whose multi-scalar multiplications (MSMs), which dominate the time to
+//! prove, go through an `MsmBackend`, so they can run on an accelerator.
+//!
+//! `CpuMsm` runs the MSMs of arkworks, as `Groth16` does. An accelerated backend implements
+//! `MsmBackend` for the curves its bindings support, and falls back to `CpuMsm` when no
+//! device is available, as `snark::gpu::IcicleMsm` does for BW6-761 with the `gpu` feature.
+//! ICICLE does not support MNT4-753 or MNT6-753, the curves the folding proofs are on.
+use ark_ec::{pairing::Pairing, CurveGroup, VariableBaseMSM};
+use ark_ff::{PrimeField, UniformRand, Zero};
+use ark_groth16::{
+    r1cs_to_qap::{LibsnarkReduction, R1CSToQAP},
+    Proof, ProvingKey,
+};
+use ark_poly::GeneralEvaluationDomain;
+use ark_relations::r1cs::{
+    ConstraintSynthesizer, ConstraintSystem, OptimizationGoal, SynthesisError,
+};
+use rand::Rng;
+
+type BigInt<E> = <<E as Pairing>::ScalarField as PrimeField>::BigInt;
+
+pub trait MsmBackend<E: Pairing> {
+    fn msm_g1(bases: &[E::G1Affine], scalars: &[BigInt<E>]) -> E::G1;
+    fn msm_g2(bases: &[E::G2Affine], scalars: &[BigInt<E>]) -> E::G2;
+}
+
+/// The MSMs of arkworks, in parallel with the `parallel` feature of `ark-ec`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CpuMsm;
+
+impl<E: Pairing> MsmBackend<E> for CpuMsm {
+    fn msm_g1(bases: &[E::G1Affine], scalars: &[BigInt<E>]) -> E::G1 {
+        E::G1::msm_bigint(bases, scalars)
+    }
+
+    fn msm_g2(bases: &[E::G2Affine], scalars: &[BigInt<E>]) -> E::G2 {
+        E::G2::msm_bigint(bases, scalars)
+    }
+}
+
+/// A proof of `circuit` with the MSMs of `M`, randomized as
+/// `Groth16::create_random_proof_with_reduction` does.
+pub fn prove<E: Pairing, M: MsmBackend<E>>(
+    pk: &ProvingKey<E>,
+    circuit: impl ConstraintSynthesizer<E::ScalarField>,
+    rng: &mut impl Rng,
+) -> Result<Proof<E>, SynthesisError> {
+    let r = E::ScalarField::rand(rng);
+    let s = E::ScalarField::rand(rng);
+    create_proof::<E, M>(pk, circuit, r, s)
+}
+
+/// The proof of `circuit` without zero-knowledge, with the MSMs of `M`, which is the same
+/// as `Groth16::create_proof_with_reduction_no_zk`.
+pub fn prove_no_zk<E: Pairing, M: MsmBackend<E>>(
+    pk: &ProvingKey<E>,
+    circuit: impl ConstraintSynthesizer<E::ScalarField>,
+) -> Result<Proof<E>, SynthesisError> {
+    create_proof::<E, M>(pk, circuit, E::ScalarField::zero(), E::ScalarField::zero())
+}
+
+/// `Groth16::create_proof_with_reduction` with the MSMs of `M`.
+fn create_proof<E: Pairing, M: MsmBackend<E>>(
+    pk: &ProvingKey<E>,
+    circuit: impl ConstraintSynthesizer<E::ScalarField>,
+    r: E::ScalarField,
+    s: E::ScalarField,
+) -> Result<Proof<E>, SynthesisError> {
+    let cs = ConstraintSystem::new_ref();
+    cs.set_optimization_goal(OptimizationGoal::Constraints);
+    circuit.generate_constraints(cs.clone())?;
+    cs.finalize();
+    let h = LibsnarkReduction::witness_map::<
+        E::ScalarField,
+        GeneralEvaluationDomain<E::ScalarField>,
+    >(cs.clone())?;
+    let cs = cs.borrow().ok_or(SynthesisError::MissingCS)?;
+
+    let h: Vec<_> = h.iter().map(|f| f.into_bigint()).collect();
+    let aux: Vec<_> = cs
+        .witness_assignment
+        .iter()
+        .map(|f| f.into_bigint())
+        .collect();
+    let assignment: Vec<_> = cs.instance_assignment[1..]
+        .iter()
+        .map(|f| f.into_bigint())
+        .chain(aux.iter().copied())
+        .collect();
+
+    let g_a =
+        pk.delta_g1 * r + pk.a_query[0] + M::msm_g1(&pk.a_query[1..], &assignment) + pk.vk.alpha_g1;
+    // `B` in G1 is only needed to randomize `C`
+    let g1_b = if r.is_zero() {
+        E::G1::zero()
+    } else {
+        pk.delta_g1 * s
+            + pk.b_g1_query[0]
+            + M::msm_g1(&pk.b_g1_query[1..], &assignment)
+            + pk.beta_g1
+    };
+    let g2_b = pk.vk.delta_g2 * s
+        + pk.b_g2_query[0]
+        + M::msm_g2(&pk.b_g2_query[1..], &assignment)
+        + pk.vk.beta_g2;
+    let g_c = g_a * s + g1_b * r - pk.delta_g1 * (r * s)
+        + M::msm_g1(&pk.l_query, &aux)
+        + M::msm_g1(&pk.h_query, &h);
+
+    Ok(Proof {
+        a: g_a.into_affine(),
+        b: g2_b.into_affine(),
+        c: g_c.into_affine(),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_ff::Field;
+    use ark_groth16::Groth16;
+    use ark_r1cs_std::{alloc::AllocVar, eq::EqGadget, fields::fp::FpVar};
+    use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
+    use rand::{thread_rng, Rng};
+
+    use super::{prove, prove_no_zk, CpuMsm};
+
+    /// Knowledge of a square root `x` of the public `y`.
+    #[derive(Clone, Copy)]
+    struct Square(Option<Fr>);
+
+    impl ConstraintSynthesizer<Fr> for Square {
+        fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+            let x = FpVar::new_witness(cs.clone(), || {
+                self.0.ok_or(SynthesisError::AssignmentMissing)
+            })?;
+            let y = FpVar::new_input(cs, || {
+                self.0
+                    .map(|x| x.square())
+                    .ok_or(SynthesisError::AssignmentMissing)
+            })?;
+            x.square()?.enforce_equal(&y)
+        }
+    }
+
+    #[test]
+    fn check_cpu_msm() {
+        let mut rng = thread_rng();
+        let pk =
+            Groth16::<Bls12_381>::generate_random_parameters_with_reduction(Square(None), &mut rng)
+                .unwrap();
+        let pvk = Groth16::<Bls12_381>::process_vk(&pk.vk).unwrap();
+        let x: Fr = rng.gen();
+
+        // without randomness, the proof is the one of arkworks
+        assert_eq!(
+            prove_no_zk::<_, CpuMsm>(&pk, Square(Some(x))).unwrap(),
+            Groth16::<Bls12_381>::create_proof_with_reduction_no_zk(Square(Some(x)), &pk).unwrap()
+        );
+
+        let proof = prove::<_, CpuMsm>(&pk, Square(Some(x)), &mut rng).unwrap();
+        assert!(Groth16::<Bls12_381>::verify_proof(&pvk, &proof, &[x.square()]).unwrap());
+        assert!(!Groth16::<Bls12_381>::verify_proof(&pvk, &proof, &[x]).unwrap());
+    }
+}
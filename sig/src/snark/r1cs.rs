@@ -0,0 +1,258 @@
+//! Verification of Groth16 proofs inside a circuit, whose constraint field is the base field
+//! of the pairing of the proofs, e.g. of MNT4-753 proofs in a circuit over the scalar field of
+//! MNT6-753. Proving that circuit in turn gives a proof of the first one, which recurses
+//! along the cycle instead of folding.
+//!
+//! The public inputs of the verified proof are in its scalar field, which is not the
+//! constraint field, so they are given as their little-endian bits, which must encode an
+//! element below the modulus so that each input has a single encoding.
+use std::{borrow::Borrow, marker::PhantomData};
+
+use ark_ec::pairing::Pairing;
+use ark_ff::{Field, PrimeField};
+use ark_groth16::{Proof, VerifyingKey};
+use ark_r1cs_std::{
+    alloc::{AllocVar, AllocationMode},
+    fields::FieldVar,
+    groups::CurveVar,
+    prelude::{Boolean, PairingVar},
+};
+use ark_relations::r1cs::{Namespace, SynthesisError};
+use derivative::Derivative;
+
+#[derive(Derivative)]
+#[derivative(Clone(bound = ""), Debug(bound = ""))]
+pub struct ProofVar<E: Pairing, P: PairingVar<E, CF>, CF: PrimeField> {
+    pub a: P::G1Var,
+    pub b: P::G2Var,
+    pub c: P::G1Var,
+}
+
+#[derive(Derivative)]
+#[derivative(Clone(bound = ""), Debug(bound = ""))]
+pub struct VerifyingKeyVar<E: Pairing, P: PairingVar<E, CF>, CF: PrimeField> {
+    pub alpha_g1: P::G1Var,
+    pub beta_g2: P::G2Var,
+    pub gamma_g2: P::G2Var,
+    pub delta_g2: P::G2Var,
+    /// The bases of the constant `1` and of each public input.
+    pub gamma_abc_g1: Vec<P::G1Var>,
+}
+
+impl<E: Pairing, P: PairingVar<E, CF>, CF: PrimeField> AllocVar<Proof<E>, CF>
+    for ProofVar<E, P, CF>
+{
+    fn new_variable<T: Borrow<Proof<E>>>(
+        cs: impl Into<Namespace<CF>>,
+        f: impl FnOnce() -> Result<T, SynthesisError>,
+        mode: AllocationMode,
+    ) -> Result<Self, SynthesisError> {
+        let cs = cs.into();
+
+        let proof = f();
+        let proof: Result<&Proof<E>, _> = proof
+            .as_ref()
+            .map(|proof| proof.borrow())
+            .map_err(SynthesisError::clone);
+
+        Ok(Self {
+            a: P::G1Var::new_variable(
+                cs.clone(),
+                || proof.clone().map(|p| E::G1::from(p.a)),
+                mode,
+            )?,
+            b: P::G2Var::new_variable(
+                cs.clone(),
+                || proof.clone().map(|p| E::G2::from(p.b)),
+                mode,
+            )?,
+            c: P::G1Var::new_variable(cs, || proof.map(|p| E::G1::from(p.c)), mode)?,
+        })
+    }
+}
+
+impl<E: Pairing, P: PairingVar<E, CF>, CF: PrimeField> AllocVar<VerifyingKey<E>, CF>
+    for VerifyingKeyVar<E, P, CF>
+{
+    fn new_variable<T: Borrow<VerifyingKey<E>>>(
+        cs: impl Into<Namespace<CF>>,
+        f: impl FnOnce() -> Result<T, SynthesisError>,
+        mode: AllocationMode,
+    ) -> Result<Self, SynthesisError> {
+        let cs = cs.into();
+
+        let vk = f();
+        let vk: Result<&VerifyingKey<E>, _> = vk
+            .as_ref()
+            .map(|vk| vk.borrow())
+            .map_err(SynthesisError::clone);
+
+        // the number of public inputs is not known without the key, so it must be given
+        // even at setup, as for `CommitteeVar`
+        let gamma_abc_g1 = vk
+            .clone()?
+            .gamma_abc_g1
+            .iter()
+            .map(|g| P::G1Var::new_variable(cs.clone(), || Ok(E::G1::from(*g)), mode))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            alpha_g1: P::G1Var::new_variable(
+                cs.clone(),
+                || vk.clone().map(|vk| E::G1::from(vk.alpha_g1)),
+                mode,
+            )?,
+            beta_g2: P::G2Var::new_variable(
+                cs.clone(),
+                || vk.clone().map(|vk| E::G2::from(vk.beta_g2)),
+                mode,
+            )?,
+            gamma_g2: P::G2Var::new_variable(
+                cs.clone(),
+                || vk.clone().map(|vk| E::G2::from(vk.gamma_g2)),
+                mode,
+            )?,
+            delta_g2: P::G2Var::new_variable(cs, || vk.map(|vk| E::G2::from(vk.delta_g2)), mode)?,
+            gamma_abc_g1,
+        })
+    }
+}
+
+pub struct Groth16VerifierGadget<E: Pairing, P: PairingVar<E, CF>, CF: PrimeField> {
+    _pairing: PhantomData<(E, P, CF)>,
+}
+
+impl<E: Pairing, P: PairingVar<E, CF>, CF: PrimeField> Groth16VerifierGadget<E, P, CF> {
+    /// Whether `proof` is valid under `vk` for `public_inputs`, each given as its
+    /// little-endian bits.
+    ///
+    /// Enforces that each input is less than the modulus of the scalar field, since `y` and
+    /// `y + r` would otherwise both verify. Returns `SynthesisError::Unsatisfiable` if the
+    /// number of public inputs is not the one of `vk`.
+    pub fn verify(
+        vk: &VerifyingKeyVar<E, P, CF>,
+        public_inputs: &[Vec<Boolean<CF>>],
+        proof: &ProofVar<E, P, CF>,
+    ) -> Result<Boolean<CF>, SynthesisError> {
+        let Some((g_ic, bases)) = vk.gamma_abc_g1.split_first() else {
+            return Err(SynthesisError::Unsatisfiable);
+        };
+        if bases.len() != public_inputs.len() {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+
+        // shorter inputs are below the modulus whatever their bits
+        let max = (-E::ScalarField::ONE).into_bigint();
+        for input in public_inputs {
+            if input.len() >= E::ScalarField::MODULUS_BIT_SIZE as usize {
+                Boolean::enforce_smaller_or_equal_than_le(input, max)?;
+            }
+        }
+
+        let mut g_ic = g_ic.clone();
+        for (base, input) in bases.iter().zip(public_inputs) {
+            g_ic += base.scalar_mul_le(input.iter())?;
+        }
+
+        // e(A, B) = e(alpha, beta) * e(vk_x, gamma) * e(C, delta), checked with a single
+        // final exponentiation as e(A, B) * e(-alpha, beta) * e(vk_x, -gamma) * e(C, -delta) = 1
+        P::product_of_pairings(
+            &[
+                P::prepare_g1(&proof.a)?,
+                P::prepare_g1(&vk.alpha_g1.negate()?)?,
+                P::prepare_g1(&g_ic)?,
+                P::prepare_g1(&proof.c)?,
+            ],
+            &[
+                P::prepare_g2(&proof.b)?,
+                P::prepare_g2(&vk.beta_g2)?,
+                P::prepare_g2(&vk.gamma_g2.negate()?)?,
+                P::prepare_g2(&vk.delta_g2.negate()?)?,
+            ],
+        )?
+        .is_one()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ark_ff::{BigInteger, Field, PrimeField};
+    use ark_groth16::Groth16;
+    use ark_mnt4_753::{constraints::PairingVar, Fq, Fr, MNT4_753};
+    use ark_r1cs_std::{
+        alloc::AllocVar, eq::EqGadget, fields::fp::FpVar, prelude::Boolean, R1CSVar,
+    };
+    use ark_relations::r1cs::{
+        ConstraintSynthesizer, ConstraintSystem, ConstraintSystemRef, SynthesisError,
+    };
+    use rand::{thread_rng, Rng};
+
+    use super::{Groth16VerifierGadget, ProofVar, VerifyingKeyVar};
+
+    /// Knowledge of a square root `x` of the public `y`.
+    #[derive(Clone, Copy)]
+    struct Square(Option<Fr>);
+
+    impl ConstraintSynthesizer<Fr> for Square {
+        fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+            let x = FpVar::new_witness(cs.clone(), || {
+                self.0.ok_or(SynthesisError::AssignmentMissing)
+            })?;
+            let y = FpVar::new_input(cs, || {
+                self.0
+                    .map(|x| x.square())
+                    .ok_or(SynthesisError::AssignmentMissing)
+            })?;
+            x.square()?.enforce_equal(&y)
+        }
+    }
+
+    type Verifier = Groth16VerifierGadget<MNT4_753, PairingVar, Fq>;
+
+    #[test]
+    fn check_groth16_verifier_gadget() {
+        let mut rng = thread_rng();
+        let pk =
+            Groth16::<MNT4_753>::generate_random_parameters_with_reduction(Square(None), &mut rng)
+                .unwrap();
+        let x: Fr = rng.gen();
+        let proof =
+            Groth16::<MNT4_753>::create_random_proof_with_reduction(Square(Some(x)), &pk, &mut rng)
+                .unwrap();
+
+        for (y, valid) in [(x.square(), true), (x.square() + Fr::ONE, false)] {
+            let cs = ConstraintSystem::<Fq>::new_ref();
+            let vk_var =
+                VerifyingKeyVar::<MNT4_753, PairingVar, Fq>::new_constant(cs.clone(), &pk.vk)
+                    .unwrap();
+            let proof_var =
+                ProofVar::<MNT4_753, PairingVar, Fq>::new_witness(cs.clone(), || Ok(&proof))
+                    .unwrap();
+            let y_var =
+                Vec::<Boolean<Fq>>::new_witness(cs.clone(), || Ok(y.into_bigint().to_bits_le()))
+                    .unwrap();
+
+            let verified = Verifier::verify(&vk_var, &[y_var], &proof_var).unwrap();
+            assert_eq!(verified.value().unwrap(), valid);
+            assert!(cs.is_satisfied().unwrap());
+        }
+
+        // `y + r` is the same scalar as `y`, but its bits are not canonical
+        let mut y = x.square().into_bigint();
+        assert!(!y.add_with_carry(&Fr::MODULUS));
+        let cs = ConstraintSystem::<Fq>::new_ref();
+        let vk_var =
+            VerifyingKeyVar::<MNT4_753, PairingVar, Fq>::new_constant(cs.clone(), &pk.vk).unwrap();
+        let proof_var =
+            ProofVar::<MNT4_753, PairingVar, Fq>::new_witness(cs.clone(), || Ok(&proof)).unwrap();
+        let y_var = Vec::<Boolean<Fq>>::new_witness(cs.clone(), || Ok(y.to_bits_le())).unwrap();
+        Verifier::verify(&vk_var, &[y_var], &proof_var).unwrap();
+        assert!(!cs.is_satisfied().unwrap());
+
+        let cs = ConstraintSystem::<Fq>::new_ref();
+        let vk_var =
+            VerifyingKeyVar::<MNT4_753, PairingVar, Fq>::new_constant(cs.clone(), &pk.vk).unwrap();
+        let proof_var = ProofVar::<MNT4_753, PairingVar, Fq>::new_constant(cs, &proof).unwrap();
+        assert!(Verifier::verify(&vk_var, &[], &proof_var).is_err());
+    }
+}
@@ -0,0 +1,257 @@
+//! Checks of the gadgets against their native counterparts on random inputs, allocated as
+//! witnesses: the value of each gadget must be the native one, and its constraints must be
+//! satisfied.
+//!
+//! Each check runs a few cases with `cargo test`, and many more in its `_exhaustive`
+//! variant, which is ignored: `cargo test --release -- --ignored exhaustive`.
+#[cfg(test)]
+mod test {
+    use std::{fmt::Debug, marker::PhantomData};
+
+    use ark_crypto_primitives::prf::blake2s::constraints::Blake2sGadget;
+    use ark_ec::hashing::{
+        curve_maps::wb::WBMap, map_to_curve_hasher::MapToCurveBasedHasher, HashToCurve,
+    };
+    use ark_ff::field_hashers::{
+        expander::{Expander, ExpanderXmd},
+        get_len_per_elem, DefaultFieldHasher, HashToField,
+    };
+    use ark_r1cs_std::{alloc::AllocVar, fields::fp::FpVar, uint8::UInt8, R1CSVar};
+    use ark_relations::r1cs::{ConstraintSystem, ConstraintSystemRef, SynthesisError};
+    use blake2::Blake2s256;
+    use rand::{rngs::ThreadRng, thread_rng, Rng};
+
+    use crate::{
+        bc::{params::BcConfig, testgen::ChainGenerator},
+        bls::{
+            BLSAggregateSignatureVerifyGadget, Parameters, ParametersVar, PublicKey, PublicKeyVar,
+            SecretKey, Signature, SignatureVar,
+        },
+        folding::{bc::BlockVar, serialize::SerializeGadget},
+        hash::{
+            expand::{ExpandMsgGadget, ExpanderXmdGadget},
+            hash_to_curve::{HashToCurveGadget, MapToCurveBasedHasherGadget},
+            hash_to_field::{default_hasher::DefaultFieldHasherGadget, HashToFieldGadget},
+            map_to_curve::wb::WBMapGadget,
+        },
+    };
+
+    const CASES: usize = 4;
+    const EXHAUSTIVE_CASES: usize = 256;
+
+    /// The gadgets over the base field of BLS12-381 are native, which keeps the cases cheap.
+    type Config = ark_bls12_381::Config;
+    type CF = ark_bls12_381::Fq;
+    type FV = FpVar<CF>;
+
+    /// Run `cases` random cases, each of which draws an input with `generate` and checks the
+    /// value `gadget` computes from it in a fresh constraint system against `native`.
+    fn check<T, V>(
+        cases: usize,
+        generate: impl Fn(&mut ThreadRng) -> T,
+        native: impl Fn(&T) -> V::Value,
+        gadget: impl Fn(ConstraintSystemRef<CF>, &T) -> Result<V, SynthesisError>,
+    ) where
+        V: R1CSVar<CF>,
+        V::Value: PartialEq + Debug,
+    {
+        let mut rng = thread_rng();
+        for _ in 0..cases {
+            let input = generate(&mut rng);
+            let cs = ConstraintSystem::new_ref();
+            let output = gadget(cs.clone(), &input).unwrap();
+            assert_eq!(output.value().unwrap(), native(&input));
+            assert!(cs.is_satisfied().unwrap());
+        }
+    }
+
+    fn random_bytes(rng: &mut ThreadRng, max_len: usize) -> Vec<u8> {
+        let mut bytes = vec![0u8; rng.gen_range(0..=max_len)];
+        rng.fill(&mut *bytes);
+        bytes
+    }
+
+    /// A random message and a random domain separation tag.
+    fn random_message(rng: &mut ThreadRng) -> (Vec<u8>, [u8; 16]) {
+        (random_bytes(rng, 96), rng.gen())
+    }
+
+    fn check_expander(cases: usize) {
+        let block_size = get_len_per_elem::<CF, 128>();
+        check(
+            cases,
+            |rng| (random_message(rng), rng.gen_range(1..=128)),
+            |((msg, dst), len)| {
+                ExpanderXmd::<Blake2s256> {
+                    hasher: PhantomData,
+                    dst: dst.to_vec(),
+                    block_size,
+                }
+                .expand(msg, *len)
+            },
+            |cs, ((msg, dst), len)| {
+                ExpanderXmdGadget::<Blake2sGadget<CF>, CF> {
+                    hasher: PhantomData,
+                    dst: UInt8::new_witness_vec(cs.clone(), dst)?,
+                    block_size,
+                }
+                .expand(&UInt8::new_witness_vec(cs, msg)?, *len)
+            },
+        );
+    }
+
+    fn check_hash_to_field(cases: usize) {
+        check(
+            cases,
+            random_message,
+            |(msg, dst)| {
+                <DefaultFieldHasher<Blake2s256, 128> as HashToField<CF>>::new(dst)
+                    .hash_to_field::<2>(msg)
+                    .to_vec()
+            },
+            |cs, (msg, dst)| {
+                let hasher = DefaultFieldHasherGadget::<Blake2sGadget<CF>, CF, CF, FV, 128>::new(
+                    &UInt8::new_witness_vec(cs.clone(), dst)?,
+                );
+                Ok(hasher
+                    .hash_to_field::<2>(&UInt8::new_witness_vec(cs, msg)?)?
+                    .to_vec())
+            },
+        );
+    }
+
+    fn check_hash_to_curve(cases: usize) {
+        type G1 = ark_bls12_381::G1Projective;
+        type Hasher = MapToCurveBasedHasher<
+            G1,
+            DefaultFieldHasher<Blake2s256, 128>,
+            WBMap<ark_bls12_381::g1::Config>,
+        >;
+        type HasherGadget = MapToCurveBasedHasherGadget<
+            G1,
+            DefaultFieldHasherGadget<Blake2sGadget<CF>, CF, CF, FV, 128>,
+            WBMapGadget<ark_bls12_381::g1::Config>,
+            CF,
+            FV,
+        >;
+
+        check(
+            cases,
+            random_message,
+            |(msg, dst)| Hasher::new(dst).unwrap().hash(msg).unwrap().into(),
+            |cs, (msg, dst)| {
+                HasherGadget::new(&UInt8::new_witness_vec(cs.clone(), dst)?)
+                    .hash(&UInt8::new_witness_vec(cs, msg)?)
+            },
+        );
+    }
+
+    fn check_serialize(cases: usize) {
+        let params = Parameters::<Config>::setup();
+        check(
+            cases,
+            |rng| {
+                let sk = SecretKey::new(rng);
+                let msg = random_bytes(rng, 32);
+                (
+                    PublicKey::new(&sk, &params),
+                    Signature::sign(&msg, &sk, &params),
+                )
+            },
+            |(pk, sig)| {
+                let mut bytes = bincode::serialize(pk).unwrap();
+                bytes.extend(bincode::serialize(sig).unwrap());
+                bytes
+            },
+            |cs, (pk, sig)| {
+                let mut bytes = PublicKeyVar::<Config, FV, CF>::new_witness(cs.clone(), || Ok(pk))?
+                    .serialize()?;
+                bytes.extend(
+                    SignatureVar::<Config, FV, CF>::new_witness(cs, || Ok(sig))?.serialize()?,
+                );
+                Ok(bytes)
+            },
+        );
+
+        check(
+            cases,
+            |rng| {
+                let committee_size = rng.gen_range(1..=4);
+                let config = BcConfig {
+                    max_committee_size: 4,
+                    ..Default::default()
+                };
+                let (blocks, _) = ChainGenerator::new(2, committee_size)
+                    .config(config)
+                    .generate(rng);
+                blocks[1].clone()
+            },
+            |block| block.to_canonical_bytes(),
+            |cs, block| BlockVar::<CF, Config, FV>::new_witness(cs, || Ok(block))?.serialize(),
+        );
+    }
+
+    /// Unlike the other gadgets, verification has no output: the constraints must be
+    /// satisfied exactly when the signature verifies natively.
+    fn check_verify(cases: usize) {
+        let params = Parameters::<Config>::setup();
+        let mut rng = thread_rng();
+        for _ in 0..cases {
+            let sk = SecretKey::new(&mut rng);
+            let pk = PublicKey::new(&sk, &params);
+            let msg = random_bytes(&mut rng, 32);
+            let sig = Signature::<Config>::sign(&msg, &sk, &params);
+
+            // half of the cases verify another message
+            let msg = if rng.gen() {
+                msg
+            } else {
+                random_bytes(&mut rng, 32)
+            };
+
+            let cs = ConstraintSystem::<CF>::new_ref();
+            let params_var: ParametersVar<Config, FV, CF> =
+                ParametersVar::new_constant(cs.clone(), params).unwrap();
+            BLSAggregateSignatureVerifyGadget::verify(
+                &params_var,
+                &PublicKeyVar::new_witness(cs.clone(), || Ok(pk)).unwrap(),
+                &UInt8::new_witness_vec(cs.clone(), &msg).unwrap(),
+                &SignatureVar::new_witness(cs.clone(), || Ok(sig)).unwrap(),
+            )
+            .unwrap();
+            assert_eq!(
+                cs.is_satisfied().unwrap(),
+                Signature::verify(&msg, &sig, &pk, &params)
+            );
+        }
+    }
+
+    macro_rules! generate_harness_tests {
+        ($check:ident, $test_name:ident, $exhaustive_name:ident) => {
+            #[test]
+            fn $test_name() {
+                $check(CASES);
+            }
+
+            #[test]
+            #[ignore = "runs many random cases, which takes a long time"]
+            fn $exhaustive_name() {
+                $check(EXHAUSTIVE_CASES);
+            }
+        };
+    }
+
+    generate_harness_tests!(check_expander, test_expander, test_expander_exhaustive);
+    generate_harness_tests!(
+        check_hash_to_field,
+        test_hash_to_field,
+        test_hash_to_field_exhaustive
+    );
+    generate_harness_tests!(
+        check_hash_to_curve,
+        test_hash_to_curve,
+        test_hash_to_curve_exhaustive
+    );
+    generate_harness_tests!(check_serialize, test_serialize, test_serialize_exhaustive);
+    generate_harness_tests!(check_verify, test_verify, test_verify_exhaustive);
+}
@@ -1 +1,2 @@
 mod ark_r1cs_std_test;
+mod harness;
@@ -57,11 +57,10 @@ impl DST {
     }
 }
 
-#[allow(dead_code)]
-pub(super) struct ExpanderXof<H: ExtendableOutput + Clone + Default> {
-    pub(super) xofer: PhantomData<H>,
-    pub(super) dst: Vec<u8>,
-    pub(super) k: usize,
+pub struct ExpanderXof<H: ExtendableOutput + Clone + Default> {
+    pub xofer: PhantomData<H>,
+    pub dst: Vec<u8>,
+    pub k: usize,
 }
 
 impl<H: ExtendableOutput + Clone + Default> Expander for ExpanderXof<H> {
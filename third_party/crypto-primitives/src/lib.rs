@@ -39,6 +39,7 @@ pub mod sponge;
 #[derive(Debug)]
 pub enum Error {
     IncorrectInputLength(usize),
+    IncorrectOutputLength(usize),
     NotPrimeOrder,
     GenericError(Box<dyn ark_std::error::Error + Send>),
     SerializationError(ark_serialize::SerializationError),
@@ -48,6 +49,7 @@ impl ark_std::fmt::Display for Error {
     fn fmt(&self, f: &mut ark_std::fmt::Formatter<'_>) -> ark_std::fmt::Result {
         match self {
             Self::IncorrectInputLength(len) => write!(f, "incorrect input length: {len}"),
+            Self::IncorrectOutputLength(len) => write!(f, "incorrect output length: {len}"),
             Self::NotPrimeOrder => write!(f, "element is not prime order"),
             Self::GenericError(e) => write!(f, "{e}"),
             Self::SerializationError(e) => write!(f, "{e}"),
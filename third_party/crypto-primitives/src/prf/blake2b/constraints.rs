@@ -0,0 +1,294 @@
+use crate::prf::constraints::{PRFGadget, XofGadget};
+use ark_ff::PrimeField;
+use ark_r1cs_std::{convert::ToBytesGadget, uint64::UInt64, uint8::UInt8};
+use ark_relations::r1cs::SynthesisError;
+#[cfg(not(feature = "std"))]
+use ark_std::vec::Vec;
+
+// BLAKE2b works on 64-bit words and 128-byte blocks, and runs 12 rounds of the same
+// mixing function as BLAKE2s (see `prf::blake2s::constraints` for the full description of
+// RFC 7693), with the rotation constants (R1, R2, R3, R4) = (32, 24, 16, 63).
+//
+// Note that halving the number of compressions does not halve the constraints: a BLAKE2b
+// compression works on twice as many bits over 12 rounds instead of 10, and takes ~2.7x the
+// constraints of a BLAKE2s one.
+
+const R1: usize = 32;
+const R2: usize = 24;
+const R3: usize = 16;
+const R4: usize = 63;
+
+const BLOCK_SIZE: usize = 128;
+
+const IV: [u64; 8] = [
+    0x6A09E667F3BCC908,
+    0xBB67AE8584CAA73B,
+    0x3C6EF372FE94F82B,
+    0xA54FF53A5F1D36F1,
+    0x510E527FADE682D1,
+    0x9B05688C2B3E6C1F,
+    0x1F83D9ABFB41BD6B,
+    0x5BE0CD19137E2179,
+];
+
+// SIGMA[10] and SIGMA[11] of the last two rounds of BLAKE2b are SIGMA[0] and SIGMA[1].
+const SIGMA: [[usize; 16]; 10] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+    [14, 10, 4, 8, 9, 15, 13, 6, 1, 12, 0, 2, 11, 7, 5, 3],
+    [11, 8, 12, 0, 5, 2, 15, 13, 10, 14, 3, 6, 7, 1, 9, 4],
+    [7, 9, 3, 1, 13, 12, 11, 14, 2, 6, 5, 10, 4, 0, 15, 8],
+    [9, 0, 5, 7, 2, 4, 10, 15, 14, 1, 11, 12, 6, 8, 3, 13],
+    [2, 12, 6, 10, 0, 11, 8, 3, 4, 13, 7, 5, 15, 14, 1, 9],
+    [12, 5, 1, 15, 14, 13, 4, 10, 0, 7, 6, 3, 9, 2, 8, 11],
+    [13, 11, 7, 14, 12, 1, 3, 9, 5, 0, 15, 4, 8, 6, 2, 10],
+    [6, 15, 14, 9, 11, 3, 0, 8, 12, 2, 13, 7, 1, 4, 10, 5],
+    [10, 2, 8, 4, 7, 6, 1, 5, 15, 11, 9, 14, 3, 12, 13, 0],
+];
+
+fn mixing_g<ConstraintF: PrimeField>(
+    v: &mut [UInt64<ConstraintF>],
+    a: usize,
+    b: usize,
+    c: usize,
+    d: usize,
+    x: &UInt64<ConstraintF>,
+    y: &UInt64<ConstraintF>,
+) -> Result<(), SynthesisError> {
+    v[a] = UInt64::wrapping_add_many(&[v[a].clone(), v[b].clone(), x.clone()])?;
+    v[d] = (&v[d] ^ &v[a]).rotate_right(R1);
+    v[c] = v[c].wrapping_add(&v[d]);
+    v[b] = (&v[b] ^ &v[c]).rotate_right(R2);
+    v[a] = UInt64::wrapping_add_many(&[v[a].clone(), v[b].clone(), y.clone()])?;
+    v[d] = (&v[d] ^ &v[a]).rotate_right(R3);
+    v[c] = v[c].wrapping_add(&v[d]);
+    v[b] = (&v[b] ^ &v[c]).rotate_right(R4);
+
+    Ok(())
+}
+
+fn blake2b_compression<ConstraintF: PrimeField>(
+    h: &mut [UInt64<ConstraintF>],
+    m: &[UInt64<ConstraintF>],
+    t: u128,
+    f: bool,
+) -> Result<(), SynthesisError> {
+    assert_eq!(h.len(), 8);
+    assert_eq!(m.len(), 16);
+
+    let mut v = Vec::with_capacity(16);
+    v.extend_from_slice(h);
+    v.extend(IV.map(UInt64::constant));
+
+    v[12] ^= t as u64;
+    v[13] ^= (t >> 64) as u64;
+
+    if f {
+        v[14] ^= u64::MAX;
+    }
+
+    for i in 0..12 {
+        let s = SIGMA[i % 10];
+
+        mixing_g(&mut v, 0, 4, 8, 12, &m[s[0]], &m[s[1]])?;
+        mixing_g(&mut v, 1, 5, 9, 13, &m[s[2]], &m[s[3]])?;
+        mixing_g(&mut v, 2, 6, 10, 14, &m[s[4]], &m[s[5]])?;
+        mixing_g(&mut v, 3, 7, 11, 15, &m[s[6]], &m[s[7]])?;
+        mixing_g(&mut v, 0, 5, 10, 15, &m[s[8]], &m[s[9]])?;
+        mixing_g(&mut v, 1, 6, 11, 12, &m[s[10]], &m[s[11]])?;
+        mixing_g(&mut v, 2, 7, 8, 13, &m[s[12]], &m[s[13]])?;
+        mixing_g(&mut v, 3, 4, 9, 14, &m[s[14]], &m[s[15]])?;
+    }
+
+    for i in 0..8 {
+        h[i] ^= &v[i];
+        h[i] ^= &v[i + 8];
+    }
+
+    Ok(())
+}
+
+/// BLAKE2b gadget implementing `PRFGadget`, which absorbs its input incrementally and
+/// outputs the first `OUTPUT_SIZE` (1 to 64) bytes of the state, i.e. BLAKE2b-512 by default.
+///
+/// The output length is part of the parameter block, so e.g. `Blake2bGadget<F, 32>` is
+/// BLAKE2b-256 rather than a truncation of BLAKE2b-512.
+#[derive(Clone)]
+pub struct Blake2bGadget<F: PrimeField, const OUTPUT_SIZE: usize = 64> {
+    h: Vec<UInt64<F>>,
+    pending: Vec<UInt8<F>>,
+    t: u128,
+}
+
+impl<F: PrimeField, const OUTPUT_SIZE: usize> Default for Blake2bGadget<F, OUTPUT_SIZE> {
+    fn default() -> Self {
+        assert!((1..=64).contains(&OUTPUT_SIZE));
+
+        let mut h = IV.map(UInt64::constant).to_vec();
+        // parameter block without key, salt and personalization
+        h[0] ^= 0x01010000 ^ OUTPUT_SIZE as u64;
+
+        Self {
+            h,
+            pending: Vec::with_capacity(BLOCK_SIZE),
+            t: 0,
+        }
+    }
+}
+
+impl<F: PrimeField, const OUTPUT_SIZE: usize> Blake2bGadget<F, OUTPUT_SIZE> {
+    fn compress(&mut self, block: &[UInt8<F>], f: bool) -> Result<(), SynthesisError> {
+        self.t += block.len() as u128;
+
+        let mut block = block.to_vec();
+        block.resize(BLOCK_SIZE, UInt8::constant(0));
+        let m: Vec<_> = block
+            .chunks(8)
+            .map(UInt64::from_bytes_le)
+            .collect::<Result<_, _>>()?;
+
+        blake2b_compression(&mut self.h, &m, self.t, f)
+    }
+}
+
+impl<F: PrimeField, const OUTPUT_SIZE: usize> PRFGadget<F> for Blake2bGadget<F, OUTPUT_SIZE> {
+    type OutputVar = Vec<UInt8<F>>;
+    const OUTPUT_SIZE: usize = OUTPUT_SIZE;
+
+    fn update(&mut self, input: &[UInt8<F>]) -> Result<(), SynthesisError> {
+        self.pending.extend_from_slice(input);
+
+        // keep the last (possibly full) block, which has to be compressed as the final one
+        let num_blocks = self.pending.len().saturating_sub(1) / BLOCK_SIZE;
+        let pending = core::mem::take(&mut self.pending);
+        for block in pending[..num_blocks * BLOCK_SIZE].chunks(BLOCK_SIZE) {
+            self.compress(block, false)?;
+        }
+        self.pending = pending[num_blocks * BLOCK_SIZE..].to_vec();
+
+        Ok(())
+    }
+
+    fn finalize(mut self) -> Result<Self::OutputVar, SynthesisError> {
+        // an empty input is hashed as a single block of 0 with counter 0
+        let block = core::mem::take(&mut self.pending);
+        self.compress(&block, true)?;
+
+        let mut digest = Vec::with_capacity(64);
+        for word in &self.h {
+            digest.extend(word.to_bytes_le()?);
+        }
+        digest.truncate(OUTPUT_SIZE);
+        Ok(digest)
+    }
+}
+
+impl<F: PrimeField, const OUTPUT_SIZE: usize> XofGadget<F> for Blake2bGadget<F, OUTPUT_SIZE> {
+    const MAX_OUTPUT_SIZE: usize = OUTPUT_SIZE;
+
+    fn absorb(&mut self, input: &[UInt8<F>]) -> Result<(), SynthesisError> {
+        self.update(input)
+    }
+
+    fn squeeze(self, num_bytes: usize) -> Result<Vec<UInt8<F>>, SynthesisError> {
+        if num_bytes > Self::MAX_OUTPUT_SIZE {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+        let mut result = self.finalize()?;
+        result.truncate(num_bytes);
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ark_ed_on_bls12_381::Fq as Fr;
+    use ark_r1cs_std::prelude::*;
+    use ark_relations::r1cs::{ConstraintSystem, SynthesisError};
+    use ark_std::rand::Rng;
+    use blake2::{digest::consts::U32, Blake2b, Blake2b512, Digest};
+
+    use super::Blake2bGadget;
+    use crate::prf::{blake2b::Blake2b as Blake2bPRF, PRFGadget, Xof, XofGadget, PRF};
+
+    #[test]
+    fn test_blake2b_prf() {
+        let mut rng = ark_std::test_rng();
+        let cs = ConstraintSystem::<Fr>::new_ref();
+
+        let mut input = [0u8; 32];
+        rng.fill(&mut input);
+
+        let input_var =
+            UInt8::new_witness_vec(ark_relations::ns!(cs, "declare_input"), &input).unwrap();
+        let out = Blake2bPRF::evaluate(&input).unwrap();
+
+        let mut hasher = Blake2bGadget::<Fr>::default();
+        hasher.update(&input_var).unwrap();
+        let output_var = hasher.finalize().unwrap();
+
+        assert_eq!(output_var.value().unwrap(), out);
+        assert!(cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_blake2b_incremental() {
+        let mut rng = ark_std::test_rng();
+
+        // cover the empty input, inputs around the block boundary and multi-block inputs
+        for len in [0, 1, 127, 128, 129, 256, 300] {
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let input: Vec<u8> = (0..len).map(|_| rng.gen()).collect();
+            let input_var = UInt8::new_witness_vec(cs.clone(), &input).unwrap();
+
+            // split the update to exercise the pending buffer
+            let (left, right) = input_var.split_at(len / 3);
+
+            let mut hasher = Blake2bGadget::<Fr>::default();
+            hasher.update(left).unwrap();
+            hasher.update(right).unwrap();
+            let digest = hasher.finalize().unwrap();
+            assert_eq!(digest.value().unwrap(), Blake2b512::digest(&input).to_vec());
+
+            let mut hasher = Blake2bGadget::<Fr, 32>::default();
+            hasher.update(left).unwrap();
+            hasher.update(right).unwrap();
+            let digest = hasher.finalize().unwrap();
+            assert_eq!(
+                digest.value().unwrap(),
+                Blake2b::<U32>::digest(&input).to_vec()
+            );
+
+            assert!(cs.is_satisfied().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_blake2b_xof() {
+        let mut rng = ark_std::test_rng();
+        let cs = ConstraintSystem::<Fr>::new_ref();
+
+        let input: Vec<u8> = (0..200).map(|_| rng.gen()).collect();
+        let input_var = UInt8::new_witness_vec(cs.clone(), &input).unwrap();
+
+        for num_bytes in [1, 32, 63, 64] {
+            let mut xof = Blake2b512::default();
+            xof.absorb(&input);
+
+            let mut xof_var = <Blake2bGadget<Fr> as Default>::default();
+            xof_var.absorb(&input_var).unwrap();
+
+            let output_var = xof_var.squeeze(num_bytes).unwrap();
+            assert_eq!(output_var.value().unwrap(), xof.squeeze(num_bytes).unwrap());
+        }
+        assert!(cs.is_satisfied().unwrap());
+
+        // more than a digest is an error, not a panic
+        assert!(Blake2b512::default().squeeze(65).is_err());
+        let mut xof_var = <Blake2bGadget<Fr> as Default>::default();
+        xof_var.absorb(&input_var).unwrap();
+        assert!(matches!(
+            xof_var.squeeze(65),
+            Err(SynthesisError::Unsatisfiable)
+        ));
+    }
+}
@@ -0,0 +1,47 @@
+use crate::{
+    prf::{Xof, PRF},
+    Error,
+};
+#[cfg(not(feature = "std"))]
+use ark_std::vec::Vec;
+use ark_std::{end_timer, start_timer};
+use blake2::Blake2b512 as B2b;
+use digest::Digest;
+
+#[cfg(feature = "r1cs")]
+pub mod constraints;
+
+/// Blake2b-512. The output is a `Vec` as `[u8; 64]` does not implement `Default`.
+#[derive(Clone)]
+pub struct Blake2b;
+
+impl PRF for Blake2b {
+    type Input = [u8; 32];
+    type Output = Vec<u8>;
+
+    fn evaluate(input: &Self::Input) -> Result<Self::Output, Error> {
+        let eval_time = start_timer!(|| "Blake2b::Eval");
+        let mut h = B2b::new();
+        h.update(input.as_ref());
+        let result = h.finalize().to_vec();
+        end_timer!(eval_time);
+        Ok(result)
+    }
+}
+
+impl Xof for B2b {
+    const MAX_OUTPUT_SIZE: usize = 64;
+
+    fn absorb(&mut self, input: &[u8]) {
+        Digest::update(self, input);
+    }
+
+    fn squeeze(self, num_bytes: usize) -> Result<Vec<u8>, Error> {
+        if num_bytes > Self::MAX_OUTPUT_SIZE {
+            return Err(Error::IncorrectOutputLength(num_bytes));
+        }
+        let mut result = self.finalize().to_vec();
+        result.truncate(num_bytes);
+        Ok(result)
+    }
+}
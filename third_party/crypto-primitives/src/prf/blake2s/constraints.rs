@@ -1,4 +1,4 @@
-use crate::prf::constraints::PRFGadget;
+use crate::prf::constraints::{PRFGadget, XofGadget};
 use ark_ff::PrimeField;
 use ark_r1cs_std::prelude::*;
 use ark_relations::r1cs::SynthesisError;
@@ -385,6 +385,23 @@ impl<F: PrimeField> Default for Blake2sGadget<F> {
     }
 }
 
+impl<F: PrimeField> XofGadget<F> for Blake2sGadget<F> {
+    const MAX_OUTPUT_SIZE: usize = 32;
+
+    fn absorb(&mut self, input: &[UInt8<F>]) -> Result<(), SynthesisError> {
+        self.update(input)
+    }
+
+    fn squeeze(self, num_bytes: usize) -> Result<Vec<UInt8<F>>, SynthesisError> {
+        if num_bytes > Self::MAX_OUTPUT_SIZE {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+        let mut result = self.finalize()?.0;
+        result.truncate(num_bytes);
+        Ok(result)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use ark_ed_on_bls12_381::Fq as Fr;
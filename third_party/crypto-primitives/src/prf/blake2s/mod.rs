@@ -1,4 +1,7 @@
-use crate::{prf::PRF, Error};
+use crate::{
+    prf::{Xof, PRF},
+    Error,
+};
 #[cfg(not(feature = "std"))]
 use ark_std::vec::Vec;
 use ark_std::{end_timer, start_timer};
@@ -26,6 +29,23 @@ impl PRF for Blake2s {
     }
 }
 
+impl Xof for B2s {
+    const MAX_OUTPUT_SIZE: usize = 32;
+
+    fn absorb(&mut self, input: &[u8]) {
+        Digest::update(self, input);
+    }
+
+    fn squeeze(self, num_bytes: usize) -> Result<Vec<u8>, Error> {
+        if num_bytes > Self::MAX_OUTPUT_SIZE {
+            return Err(Error::IncorrectOutputLength(num_bytes));
+        }
+        let mut result = self.finalize().to_vec();
+        result.truncate(num_bytes);
+        Ok(result)
+    }
+}
+
 #[derive(Clone)]
 pub struct Blake2sWithParameterBlock {
     pub output_size: u8,
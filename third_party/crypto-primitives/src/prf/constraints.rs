@@ -15,3 +15,18 @@ pub trait PRFGadget<F: Field> {
 
     fn finalize(self) -> Result<Self::OutputVar, SynthesisError>;
 }
+
+/// R1CS equivalent of `Xof`: a hash function absorbing bytes and squeezing as many bytes
+/// as required, up to `MAX_OUTPUT_SIZE`.
+pub trait XofGadget<F: Field>: Default {
+    // maximum number of bytes returned by `squeeze`, e.g. the digest size of a hash
+    // function with fixed output
+    const MAX_OUTPUT_SIZE: usize;
+
+    fn absorb(&mut self, input: &[UInt8<F>]) -> Result<(), SynthesisError>;
+
+    /// Squeeze the first `num_bytes` bytes of the output.
+    ///
+    /// Returns `SynthesisError::Unsatisfiable` if `num_bytes > Self::MAX_OUTPUT_SIZE`.
+    fn squeeze(self, num_bytes: usize) -> Result<Vec<UInt8<F>>, SynthesisError>;
+}
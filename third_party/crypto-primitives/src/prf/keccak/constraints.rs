@@ -0,0 +1,278 @@
+use crate::prf::constraints::{PRFGadget, XofGadget};
+use ark_ff::PrimeField;
+use ark_r1cs_std::{convert::ToBytesGadget, uint64::UInt64, uint8::UInt8};
+use ark_relations::r1cs::SynthesisError;
+#[cfg(not(feature = "std"))]
+use ark_std::vec::Vec;
+
+// Keccak-256 absorbs 1088 bits per permutation, keeping 512 bits of capacity.
+const KECCAK256_RATE: usize = 136;
+// SHAKE128 absorbs 1344 bits per permutation, keeping 256 bits of capacity.
+const SHAKE128_RATE: usize = 168;
+const OUTPUT_SIZE: usize = 32;
+const NUM_LANES: usize = 25;
+
+// Domain separation bits appended to the message before pad10*1.
+const KECCAK_DOMAIN: u8 = 0x01;
+const SHAKE_DOMAIN: u8 = 0x1f;
+
+#[rustfmt::skip]
+const ROUND_CONSTANTS: [u64; 24] = [
+    0x0000000000000001, 0x0000000000008082, 0x800000000000808a, 0x8000000080008000,
+    0x000000000000808b, 0x0000000080000001, 0x8000000080008081, 0x8000000000008009,
+    0x000000000000008a, 0x0000000000000088, 0x0000000080008009, 0x000000008000000a,
+    0x000000008000808b, 0x800000000000008b, 0x8000000000008089, 0x8000000000008003,
+    0x8000000000008002, 0x8000000000000080, 0x000000000000800a, 0x800000008000000a,
+    0x8000000080008081, 0x8000000000008080, 0x0000000080000001, 0x8000000080008008,
+];
+
+// Rotation offsets of the rho step, for the lane at (x, y) stored at index x + 5 * y.
+const RHO_OFFSETS: [usize; NUM_LANES] = [
+    0, 1, 62, 28, 27, 36, 44, 6, 55, 20, 3, 10, 43, 25, 39, 41, 45, 15, 21, 8, 18, 2, 61, 56, 14,
+];
+
+/// Keccak-f[1600] sponge absorbing `RATE` bytes per permutation, shared by the gadgets below.
+#[derive(Clone)]
+struct KeccakSponge<F: PrimeField, const RATE: usize> {
+    state: Vec<UInt64<F>>,
+    pending: Vec<UInt8<F>>,
+}
+
+impl<F: PrimeField, const RATE: usize> Default for KeccakSponge<F, RATE> {
+    fn default() -> Self {
+        Self {
+            state: vec![UInt64::constant(0); NUM_LANES],
+            pending: Vec::with_capacity(RATE),
+        }
+    }
+}
+
+impl<F: PrimeField, const RATE: usize> KeccakSponge<F, RATE> {
+    /// Keccak-f[1600]
+    fn permute(state: &mut [UInt64<F>]) {
+        for rc in ROUND_CONSTANTS {
+            // theta
+            let c: Vec<_> = (0..5)
+                .map(|x| {
+                    state[x].clone()
+                        ^ &state[x + 5]
+                        ^ &state[x + 10]
+                        ^ &state[x + 15]
+                        ^ &state[x + 20]
+                })
+                .collect();
+            for x in 0..5 {
+                let d = c[(x + 4) % 5].clone() ^ &c[(x + 1) % 5].rotate_left(1);
+                for y in 0..5 {
+                    state[x + 5 * y] ^= &d;
+                }
+            }
+
+            // rho and pi
+            let mut b = vec![UInt64::constant(0); NUM_LANES];
+            for x in 0..5 {
+                for y in 0..5 {
+                    b[y + 5 * ((2 * x + 3 * y) % 5)] =
+                        state[x + 5 * y].rotate_left(RHO_OFFSETS[x + 5 * y]);
+                }
+            }
+
+            // chi
+            for x in 0..5 {
+                for y in 0..5 {
+                    state[x + 5 * y] = b[x + 5 * y].clone()
+                        ^ &((!&b[(x + 1) % 5 + 5 * y]) & &b[(x + 2) % 5 + 5 * y]);
+                }
+            }
+
+            // iota
+            state[0] ^= UInt64::constant(rc);
+        }
+    }
+
+    fn absorb_block(state: &mut [UInt64<F>], block: &[UInt8<F>]) -> Result<(), SynthesisError> {
+        assert_eq!(block.len(), RATE);
+
+        for (lane, bytes) in state.iter_mut().zip(block.chunks(8)) {
+            *lane ^= UInt64::from_bytes_le(bytes)?;
+        }
+        Self::permute(state);
+
+        Ok(())
+    }
+
+    fn update(&mut self, input: &[UInt8<F>]) -> Result<(), SynthesisError> {
+        self.pending.extend_from_slice(input);
+
+        let num_blocks = self.pending.len() / RATE;
+        for block in self.pending[..num_blocks * RATE].chunks(RATE) {
+            Self::absorb_block(&mut self.state, block)?;
+        }
+        self.pending.drain(..num_blocks * RATE);
+
+        Ok(())
+    }
+
+    /// Absorb the pending input followed by the `domain` bits and pad10*1, and return the
+    /// state to squeeze from.
+    fn pad(self, domain: u8) -> Result<Vec<UInt64<F>>, SynthesisError> {
+        let (mut state, mut block) = (self.state, self.pending);
+        if block.len() == RATE - 1 {
+            block.push(UInt8::constant(domain | 0x80));
+        } else {
+            block.push(UInt8::constant(domain));
+            block.resize(RATE - 1, UInt8::constant(0));
+            block.push(UInt8::constant(0x80));
+        }
+        Self::absorb_block(&mut state, &block)?;
+        Ok(state)
+    }
+}
+
+/// Keccak-256 gadget implementing `PRFGadget`, which absorbs its input incrementally.
+#[derive(Clone, Default)]
+pub struct Keccak256Gadget<F: PrimeField>(KeccakSponge<F, KECCAK256_RATE>);
+
+impl<F: PrimeField> PRFGadget<F> for Keccak256Gadget<F> {
+    type OutputVar = Vec<UInt8<F>>;
+    const OUTPUT_SIZE: usize = OUTPUT_SIZE;
+
+    fn update(&mut self, input: &[UInt8<F>]) -> Result<(), SynthesisError> {
+        self.0.update(input)
+    }
+
+    fn finalize(self) -> Result<Self::OutputVar, SynthesisError> {
+        // SHA3-256 would use the domain byte 0x06 instead
+        let state = self.0.pad(KECCAK_DOMAIN)?;
+
+        let mut digest = Vec::with_capacity(OUTPUT_SIZE);
+        for lane in &state[..OUTPUT_SIZE / 8] {
+            digest.extend(lane.to_bytes_le()?);
+        }
+        Ok(digest)
+    }
+}
+
+impl<F: PrimeField> XofGadget<F> for Keccak256Gadget<F> {
+    const MAX_OUTPUT_SIZE: usize = OUTPUT_SIZE;
+
+    fn absorb(&mut self, input: &[UInt8<F>]) -> Result<(), SynthesisError> {
+        self.update(input)
+    }
+
+    fn squeeze(self, num_bytes: usize) -> Result<Vec<UInt8<F>>, SynthesisError> {
+        if num_bytes > Self::MAX_OUTPUT_SIZE {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+        let mut result = self.finalize()?;
+        result.truncate(num_bytes);
+        Ok(result)
+    }
+}
+
+/// SHAKE128 gadget implementing `XofGadget`, which squeezes outputs of any length.
+#[derive(Clone, Default)]
+pub struct Shake128Gadget<F: PrimeField>(KeccakSponge<F, SHAKE128_RATE>);
+
+impl<F: PrimeField> XofGadget<F> for Shake128Gadget<F> {
+    const MAX_OUTPUT_SIZE: usize = usize::MAX;
+
+    fn absorb(&mut self, input: &[UInt8<F>]) -> Result<(), SynthesisError> {
+        self.0.update(input)
+    }
+
+    fn squeeze(self, num_bytes: usize) -> Result<Vec<UInt8<F>>, SynthesisError> {
+        let mut state = self.0.pad(SHAKE_DOMAIN)?;
+
+        let mut output = Vec::new();
+        loop {
+            for lane in &state[..SHAKE128_RATE / 8] {
+                output.extend(lane.to_bytes_le()?);
+            }
+            if output.len() >= num_bytes {
+                break;
+            }
+            KeccakSponge::<F, SHAKE128_RATE>::permute(&mut state);
+        }
+        output.truncate(num_bytes);
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ark_ed_on_bls12_381::Fq as Fr;
+    use ark_r1cs_std::prelude::*;
+    use ark_relations::r1cs::ConstraintSystem;
+    use ark_std::rand::Rng;
+    use sha3::{Digest, Keccak256, Shake128};
+
+    use super::{Keccak256Gadget, Shake128Gadget};
+    use crate::prf::{keccak::Keccak256 as Keccak256PRF, PRFGadget, Xof, XofGadget, PRF};
+
+    #[test]
+    fn test_keccak256_prf() {
+        let mut rng = ark_std::test_rng();
+        let cs = ConstraintSystem::<Fr>::new_ref();
+
+        let mut input = [0u8; 32];
+        rng.fill(&mut input);
+
+        let input_var =
+            UInt8::new_witness_vec(ark_relations::ns!(cs, "declare_input"), &input).unwrap();
+        let out = Keccak256PRF::evaluate(&input).unwrap();
+
+        let mut hasher = Keccak256Gadget::default();
+        hasher.update(&input_var).unwrap();
+        let output_var = hasher.finalize().unwrap();
+
+        assert_eq!(output_var.value().unwrap(), out);
+        assert!(cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_keccak256_incremental() {
+        let mut rng = ark_std::test_rng();
+
+        // cover the empty input, inputs around the padding boundary and multi-block inputs
+        for len in [0, 1, 135, 136, 137, 300] {
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let input: Vec<u8> = (0..len).map(|_| rng.gen()).collect();
+            let input_var = UInt8::new_witness_vec(cs.clone(), &input).unwrap();
+
+            // split the update to exercise the pending buffer
+            let mut hasher = Keccak256Gadget::default();
+            let (left, right) = input_var.split_at(len / 3);
+            hasher.update(left).unwrap();
+            hasher.update(right).unwrap();
+            let digest = hasher.finalize().unwrap();
+
+            assert_eq!(digest.value().unwrap(), Keccak256::digest(&input).to_vec());
+            assert!(cs.is_satisfied().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_shake128_xof() {
+        let mut rng = ark_std::test_rng();
+
+        // cover inputs around the padding boundary, and outputs of several blocks
+        for (len, num_bytes) in [(0, 32), (167, 168), (168, 169), (300, 400)] {
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let input: Vec<u8> = (0..len).map(|_| rng.gen()).collect();
+            let input_var = UInt8::new_witness_vec(cs.clone(), &input).unwrap();
+
+            let mut xof = Shake128::default();
+            xof.absorb(&input);
+
+            let mut xof_var = Shake128Gadget::default();
+            let (left, right) = input_var.split_at(len / 3);
+            xof_var.absorb(left).unwrap();
+            xof_var.absorb(right).unwrap();
+
+            let output_var = xof_var.squeeze(num_bytes).unwrap();
+            assert_eq!(output_var.value().unwrap(), xof.squeeze(num_bytes).unwrap());
+            assert!(cs.is_satisfied().unwrap());
+        }
+    }
+}
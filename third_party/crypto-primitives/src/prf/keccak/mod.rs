@@ -0,0 +1,48 @@
+use crate::{
+    prf::{Xof, PRF},
+    Error,
+};
+use ark_std::{end_timer, start_timer};
+#[cfg(not(feature = "std"))]
+use ark_std::{vec, vec::Vec};
+use sha3::{
+    digest::{self, ExtendableOutput},
+    Digest, Keccak256 as K256, Shake128,
+};
+
+#[cfg(feature = "r1cs")]
+pub mod constraints;
+
+/// Keccak-256 as used by Ethereum, i.e. with the original Keccak padding rather than the
+/// one of SHA3-256.
+#[derive(Clone)]
+pub struct Keccak256;
+
+impl PRF for Keccak256 {
+    type Input = [u8; 32];
+    type Output = [u8; 32];
+
+    fn evaluate(input: &Self::Input) -> Result<Self::Output, Error> {
+        let eval_time = start_timer!(|| "Keccak256::Eval");
+        let mut h = K256::new();
+        h.update(input.as_ref());
+        let mut result = [0u8; 32];
+        result.copy_from_slice(&h.finalize());
+        end_timer!(eval_time);
+        Ok(result)
+    }
+}
+
+impl Xof for Shake128 {
+    const MAX_OUTPUT_SIZE: usize = usize::MAX;
+
+    fn absorb(&mut self, input: &[u8]) {
+        digest::Update::update(self, input);
+    }
+
+    fn squeeze(self, num_bytes: usize) -> Result<Vec<u8>, Error> {
+        let mut result = vec![0; num_bytes];
+        self.finalize_xof_into(&mut result);
+        Ok(result)
+    }
+}
@@ -1,6 +1,8 @@
 #![allow(clippy::upper_case_acronyms)]
 use crate::Error;
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+#[cfg(not(feature = "std"))]
+use ark_std::vec::Vec;
 use ark_std::{fmt::Debug, hash::Hash};
 
 #[cfg(feature = "r1cs")]
@@ -8,12 +10,35 @@ pub mod constraints;
 #[cfg(feature = "r1cs")]
 pub use self::constraints::*;
 
+pub mod blake2b;
+pub use self::blake2b::*;
+
 pub mod blake2s;
 pub use self::blake2s::*;
 
+pub mod keccak;
+pub use self::keccak::*;
+
+pub mod sha256;
+pub use self::sha256::*;
+
 pub trait PRF {
     type Input: CanonicalDeserialize + Default;
     type Output: CanonicalSerialize + Eq + Clone + Debug + Default + Hash;
 
     fn evaluate(input: &Self::Input) -> Result<Self::Output, Error>;
 }
+
+/// A hash function absorbing bytes and squeezing as many bytes as required, up to
+/// `MAX_OUTPUT_SIZE`, which avoids fixing the output to a single type as `PRF` does.
+pub trait Xof: Default {
+    // maximum number of bytes returned by `squeeze`
+    const MAX_OUTPUT_SIZE: usize;
+
+    fn absorb(&mut self, input: &[u8]);
+
+    /// Squeeze the first `num_bytes` bytes of the output.
+    ///
+    /// Returns `Error::IncorrectOutputLength` if `num_bytes > Self::MAX_OUTPUT_SIZE`.
+    fn squeeze(self, num_bytes: usize) -> Result<Vec<u8>, Error>;
+}
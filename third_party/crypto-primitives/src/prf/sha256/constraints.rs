@@ -0,0 +1,258 @@
+// The compression function is the same as in `crate::crh::sha256::constraints`, which is
+// only built with the `crh` feature. Both were adapted from
+// https://github.com/nanpuyue/sha256/blob/bf6656b7dc72e76bb617445a8865f906670e585b/src/lib.rs
+// See LICENSE-MIT in the root directory for a copy of the license
+
+use crate::prf::constraints::{PRFGadget, XofGadget};
+use ark_ff::PrimeField;
+use ark_r1cs_std::{uint32::UInt32, uint8::UInt8};
+use ark_relations::r1cs::SynthesisError;
+#[cfg(not(feature = "std"))]
+use ark_std::vec::Vec;
+
+const STATE_LEN: usize = 8;
+const BLOCK_SIZE: usize = 64;
+
+type State = [u32; STATE_LEN];
+
+const K: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+const H: State = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+/// SHA-256 gadget implementing `PRFGadget`, and `XofGadget` so it can be plugged into
+/// `ExpanderXmdGadget`.
+#[derive(Clone)]
+pub struct Sha256Gadget<F: PrimeField> {
+    state: Vec<UInt32<F>>,
+    completed_data_blocks: u64,
+    pending: Vec<UInt8<F>>,
+    num_pending: usize,
+}
+
+impl<F: PrimeField> Default for Sha256Gadget<F> {
+    fn default() -> Self {
+        Self {
+            state: H.iter().copied().map(UInt32::constant).collect(),
+            completed_data_blocks: 0,
+            pending: vec![UInt8::constant(0); BLOCK_SIZE],
+            num_pending: 0,
+        }
+    }
+}
+
+impl<F: PrimeField> Sha256Gadget<F> {
+    fn update_state(state: &mut [UInt32<F>], data: &[UInt8<F>]) -> Result<(), SynthesisError> {
+        assert_eq!(data.len(), BLOCK_SIZE);
+
+        let mut w = vec![UInt32::constant(0); 64];
+        for (word, chunk) in w.iter_mut().zip(data.chunks(4)) {
+            *word = UInt32::from_bytes_be(chunk)?;
+        }
+
+        for i in 16..64 {
+            let s0 = {
+                let x1 = w[i - 15].rotate_right(7);
+                let x2 = w[i - 15].rotate_right(18);
+                let x3 = &w[i - 15] >> 3u8;
+                x1 ^ &x2 ^ &x3
+            };
+            let s1 = {
+                let x1 = w[i - 2].rotate_right(17);
+                let x2 = w[i - 2].rotate_right(19);
+                let x3 = &w[i - 2] >> 10u8;
+                x1 ^ &x2 ^ &x3
+            };
+            w[i] = UInt32::wrapping_add_many(&[w[i - 16].clone(), s0, w[i - 7].clone(), s1])?;
+        }
+
+        let mut h = state.to_vec();
+        for i in 0..64 {
+            let ch = {
+                let x1 = &h[4] & &h[5];
+                let x2 = (!&h[4]) & &h[6];
+                x1 ^ &x2
+            };
+            let ma = {
+                let x1 = &h[0] & &h[1];
+                let x2 = &h[0] & &h[2];
+                let x3 = &h[1] & &h[2];
+                x1 ^ &x2 ^ &x3
+            };
+            let s0 = {
+                let x1 = h[0].rotate_right(2);
+                let x2 = h[0].rotate_right(13);
+                let x3 = h[0].rotate_right(22);
+                x1 ^ &x2 ^ &x3
+            };
+            let s1 = {
+                let x1 = h[4].rotate_right(6);
+                let x2 = h[4].rotate_right(11);
+                let x3 = h[4].rotate_right(25);
+                x1 ^ &x2 ^ &x3
+            };
+            let t0 = UInt32::wrapping_add_many(&[
+                h[7].clone(),
+                s1,
+                ch,
+                UInt32::constant(K[i]),
+                w[i].clone(),
+            ])?;
+            let t1 = s0.wrapping_add(&ma);
+
+            h[7] = h[6].clone();
+            h[6] = h[5].clone();
+            h[5] = h[4].clone();
+            h[4] = h[3].wrapping_add(&t0);
+            h[3] = h[2].clone();
+            h[2] = h[1].clone();
+            h[1] = h[0].clone();
+            h[0] = t0.wrapping_add(&t1);
+        }
+
+        for (s, hi) in state.iter_mut().zip(h.iter()) {
+            *s = s.wrapping_add(hi);
+        }
+
+        Ok(())
+    }
+}
+
+impl<F: PrimeField> PRFGadget<F> for Sha256Gadget<F> {
+    type OutputVar = Vec<UInt8<F>>;
+    const OUTPUT_SIZE: usize = 32;
+
+    fn update(&mut self, input: &[UInt8<F>]) -> Result<(), SynthesisError> {
+        let mut offset = 0;
+        if self.num_pending > 0 && self.num_pending + input.len() >= BLOCK_SIZE {
+            offset = BLOCK_SIZE - self.num_pending;
+            // the pending buffer is full, so process it first
+            self.pending[self.num_pending..].clone_from_slice(&input[..offset]);
+            Self::update_state(&mut self.state, &self.pending)?;
+
+            self.completed_data_blocks += 1;
+            self.num_pending = 0;
+        }
+
+        for chunk in input[offset..].chunks(BLOCK_SIZE) {
+            let chunk_size = chunk.len();
+
+            if chunk_size == BLOCK_SIZE {
+                Self::update_state(&mut self.state, chunk)?;
+                self.completed_data_blocks += 1;
+            } else {
+                self.pending[self.num_pending..self.num_pending + chunk_size]
+                    .clone_from_slice(chunk);
+                self.num_pending += chunk_size;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn finalize(mut self) -> Result<Self::OutputVar, SynthesisError> {
+        // the message length in bits, encoded as 8 big-endian bytes
+        let data_bitlen = self.completed_data_blocks * 512 + self.num_pending as u64 * 8;
+        let encoded_bitlen = data_bitlen.to_be_bytes().map(UInt8::constant);
+
+        // padding is a single 1 bit followed by zeros up to the 56 (or 120) byte boundary
+        let mut pending = vec![UInt8::constant(0); 72];
+        pending[0] = UInt8::constant(0x80);
+
+        let offset = if self.num_pending < 56 {
+            56 - self.num_pending
+        } else {
+            120 - self.num_pending
+        };
+
+        pending[offset..offset + 8].clone_from_slice(&encoded_bitlen);
+        self.update(&pending[..offset + 8])?;
+
+        let mut digest = Vec::with_capacity(Self::OUTPUT_SIZE);
+        for word in &self.state {
+            digest.extend(word.to_bytes_be()?);
+        }
+        Ok(digest)
+    }
+}
+
+impl<F: PrimeField> XofGadget<F> for Sha256Gadget<F> {
+    const MAX_OUTPUT_SIZE: usize = 32;
+
+    fn absorb(&mut self, input: &[UInt8<F>]) -> Result<(), SynthesisError> {
+        self.update(input)
+    }
+
+    fn squeeze(self, num_bytes: usize) -> Result<Vec<UInt8<F>>, SynthesisError> {
+        if num_bytes > Self::MAX_OUTPUT_SIZE {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+        let mut result = self.finalize()?;
+        result.truncate(num_bytes);
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ark_ed_on_bls12_381::Fq as Fr;
+    use ark_r1cs_std::prelude::*;
+    use ark_relations::r1cs::ConstraintSystem;
+    use ark_std::rand::Rng;
+    use sha2::{Digest, Sha256};
+
+    use super::Sha256Gadget;
+    use crate::prf::{sha256::Sha256 as Sha256PRF, PRFGadget, PRF};
+
+    #[test]
+    fn test_sha256_prf() {
+        let mut rng = ark_std::test_rng();
+        let cs = ConstraintSystem::<Fr>::new_ref();
+
+        let mut input = [0u8; 32];
+        rng.fill(&mut input);
+
+        let input_var =
+            UInt8::new_witness_vec(ark_relations::ns!(cs, "declare_input"), &input).unwrap();
+        let out = Sha256PRF::evaluate(&input).unwrap();
+
+        let mut hasher = Sha256Gadget::default();
+        hasher.update(&input_var).unwrap();
+        let output_var = hasher.finalize().unwrap();
+
+        assert_eq!(output_var.value().unwrap(), out);
+        assert!(cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_sha256_incremental() {
+        let mut rng = ark_std::test_rng();
+
+        // cover the empty input, inputs around the padding boundary and multi-block inputs
+        for len in [0, 1, 55, 56, 63, 64, 65, 200] {
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let input: Vec<u8> = (0..len).map(|_| rng.gen()).collect();
+            let input_var = UInt8::new_witness_vec(cs.clone(), &input).unwrap();
+
+            // split the update to exercise the pending buffer
+            let mut hasher = Sha256Gadget::default();
+            let (left, right) = input_var.split_at(len / 3);
+            hasher.update(left).unwrap();
+            hasher.update(right).unwrap();
+            let digest = hasher.finalize().unwrap();
+
+            assert_eq!(digest.value().unwrap(), Sha256::digest(&input).to_vec());
+            assert!(cs.is_satisfied().unwrap());
+        }
+    }
+}
@@ -0,0 +1,24 @@
+use crate::{prf::PRF, Error};
+use ark_std::{end_timer, start_timer};
+use sha2::{Digest, Sha256 as S256};
+
+#[cfg(feature = "r1cs")]
+pub mod constraints;
+
+#[derive(Clone)]
+pub struct Sha256;
+
+impl PRF for Sha256 {
+    type Input = [u8; 32];
+    type Output = [u8; 32];
+
+    fn evaluate(input: &Self::Input) -> Result<Self::Output, Error> {
+        let eval_time = start_timer!(|| "Sha256::Eval");
+        let mut h = S256::new();
+        h.update(input.as_ref());
+        let mut result = [0u8; 32];
+        result.copy_from_slice(&h.finalize());
+        end_timer!(eval_time);
+        Ok(result)
+    }
+}
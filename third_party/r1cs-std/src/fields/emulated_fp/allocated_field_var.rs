@@ -589,6 +589,17 @@ impl<TargetF: PrimeField, BaseF: PrimeField> AllocatedEmulatedFpVar<TargetF, Bas
             Err(_) => zero,
         };
         let elem_representations = Self::get_limbs_representations(&elem, optimization_type)?;
+        Self::new_variable_unchecked_from_limbs(cs, &elem_representations, mode)
+    }
+
+    /// Allocates a new variable from the limbs of its value, as returned by
+    /// `get_limbs_representations`, but does not check that the limbs are
+    /// in-range.
+    fn new_variable_unchecked_from_limbs(
+        cs: ConstraintSystemRef<BaseF>,
+        elem_representations: &[BaseF],
+        mode: AllocationMode,
+    ) -> R1CSResult<Self> {
         let mut limbs = Vec::new();
 
         for limb in elem_representations.iter() {
@@ -614,6 +625,44 @@ impl<TargetF: PrimeField, BaseF: PrimeField> AllocatedEmulatedFpVar<TargetF, Bas
         })
     }
 
+    /// Allocates a new variable from the limbs of its value, as returned by
+    /// `get_limbs_representations` for the optimization type of `cs`, which
+    /// lets the limbs of many elements be computed beforehand, e.g. in parallel.
+    /// As `new_variable`, witnesses are checked to be in-range.
+    pub fn new_variable_with_limbs<T: Borrow<[BaseF]>>(
+        cs: impl Into<Namespace<BaseF>>,
+        f: impl FnOnce() -> Result<T, SynthesisError>,
+        mode: AllocationMode,
+    ) -> R1CSResult<Self> {
+        let ns = cs.into();
+        let cs = ns.cs();
+
+        let optimization_type = match cs.optimization_goal() {
+            OptimizationGoal::None => OptimizationType::Constraints,
+            OptimizationGoal::Constraints => OptimizationType::Constraints,
+            OptimizationGoal::Weight => OptimizationType::Weight,
+        };
+        let params = get_params(
+            TargetF::MODULUS_BIT_SIZE as usize,
+            BaseF::MODULUS_BIT_SIZE as usize,
+            optimization_type,
+        );
+
+        let this = match f() {
+            Ok(limbs) => {
+                if limbs.borrow().len() != params.num_limbs {
+                    return Err(SynthesisError::Unsatisfiable);
+                }
+                Self::new_variable_unchecked_from_limbs(cs.clone(), limbs.borrow(), mode)?
+            },
+            Err(_) => Self::new_variable_unchecked(ns!(cs, "alloc"), || Ok(TargetF::zero()), mode)?,
+        };
+        if mode == AllocationMode::Witness {
+            this.enforce_in_range(ns!(cs, "bits"))?;
+        }
+        Ok(this)
+    }
+
     /// Check that this element is in-range; i.e., each limb is in-range, and
     /// the whole number is less than the modulus.
     ///
@@ -86,6 +86,59 @@ where
         Ok(Self::new(new_c0, new_c1))
     }
 
+    /// Multiplies by a sparse element of the form `(c0 = (c0, c1, 0), c1 = (0,
+    /// 1, 0))`. This is `mul_by_014` with `d1 = 1`, without the products by
+    /// `d1`.
+    #[inline]
+    pub fn mul_by_014_unit(
+        &self,
+        c0: &Fp2Var<Fp2Config<P>, F, CF>,
+        c1: &Fp2Var<Fp2Config<P>, F, CF>,
+    ) -> Result<Self, SynthesisError> {
+        let v0 = self.c0.mul_by_c0_c1_0(&c0, &c1)?;
+        let v1 = Self::mul_fp6_by_v(&self.c1)?;
+        let new_c0 = Self::mul_fp6_by_v(&v1)? + &v0;
+
+        let c1_plus_one = c1 + Fp2Var::one();
+        let new_c1 = (&self.c0 + &self.c1).mul_by_c0_c1_0(&c0, &c1_plus_one)? - &v0 - &v1;
+        Ok(Self::new(new_c0, new_c1))
+    }
+
+    /// Multiplies by a sparse element of the form `(c0 = (1, 0, 0), c1 = (d0,
+    /// d1, 0))`. This is `mul_by_034` with `c0 = 1`, without the products by
+    /// `c0`.
+    #[inline]
+    pub fn mul_by_034_unit(
+        &self,
+        d0: &Fp2Var<Fp2Config<P>, F, CF>,
+        d1: &Fp2Var<Fp2Config<P>, F, CF>,
+    ) -> Result<Self, SynthesisError> {
+        let a = &self.c0;
+        let b = self.c1.mul_by_c0_c1_0(&d0, &d1)?;
+
+        let d0_plus_one = d0 + Fp2Var::one();
+        let e = (&self.c0 + &self.c1).mul_by_c0_c1_0(&d0_plus_one, &d1)?;
+        let new_c1 = e - (a + &b);
+        let new_c0 = Self::mul_fp6_by_v(&b)? + a;
+
+        Ok(Self::new(new_c0, new_c1))
+    }
+
+    /// Multiplies an element of the base field by `v`, the quadratic nonresidue
+    /// of `Fp12` over `Fp6`, by shifting its coefficients. Unlike
+    /// `mul_base_field_by_nonresidue`, this does not multiply by the zero
+    /// coefficients of `v`, which costs constraints for emulated fields.
+    #[inline]
+    fn mul_fp6_by_v(
+        fe: &Fp6Var<P::Fp6Config, F, CF>,
+    ) -> Result<Fp6Var<P::Fp6Config, F, CF>, SynthesisError> {
+        Ok(Fp6Var::new(
+            Fp6Var::<P::Fp6Config, F, CF>::mul_base_field_by_nonresidue(&fe.c2)?,
+            fe.c0.clone(),
+            fe.c1.clone(),
+        ))
+    }
+
     /// Squares `self` when `self` is in the cyclotomic subgroup.
     pub fn cyclotomic_square(&self) -> Result<Self, SynthesisError> {
         if characteristic_square_mod_6_is_one(Fp12::<P>::characteristic()) {
@@ -8,7 +8,7 @@ use crate::{
     R1CSVar,
 };
 use ark_ec::bls12::{Bls12, Bls12Config, TwistType};
-use ark_ff::{BitIteratorBE, PrimeField};
+use ark_ff::{BitIteratorBE, Field, PrimeField};
 use ark_std::marker::PhantomData;
 
 /// Specifies the constraints for computing a pairing in a BLS12 bilinear group.
@@ -24,12 +24,35 @@ where
     // It is not removed because of the MRE example in `sig/src/lib.rs`.
     //
     // Evaluate the line function at point p.
+    //
+    // The line can be scaled by any non-zero element of `P::Fp`, which the final
+    // exponentiation maps to one. If `p` is a constant, the line is scaled by
+    // `1 / p.y` natively, so that the coefficient multiplied by `p.y` is one and
+    // its products with `f` are skipped. The result of the Miller loop then
+    // differs from the native one by such a factor.
     #[tracing::instrument(target = "r1cs", skip_all)]
     pub fn ell(
         f: &mut Fp12Var<P::Fp12Config, F, CF>,
         coeffs: &(Fp2V<P, F, CF>, Fp2V<P, F, CF>),
         p: &G1AffineVar<P, F, CF>,
     ) -> Result<(), SynthesisError> {
+        if p.x.is_constant() && p.y.is_constant() {
+            if let Some(y_inv) = p.y.value()?.inverse() {
+                let x_over_y = p.x.value()? * y_inv;
+                *f = match P::TWIST_TYPE {
+                    TwistType::M => f.mul_by_014_unit(
+                        &coeffs.0.mul_by_base_field_constant(y_inv),
+                        &coeffs.1.mul_by_base_field_constant(x_over_y),
+                    )?,
+                    TwistType::D => f.mul_by_034_unit(
+                        &coeffs.0.mul_by_base_field_constant(x_over_y),
+                        &coeffs.1.mul_by_base_field_constant(y_inv),
+                    )?,
+                };
+                return Ok(());
+            }
+        }
+
         let zero = F::zero();
 
         match P::TWIST_TYPE {